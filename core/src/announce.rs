@@ -0,0 +1,171 @@
+// Announce - Bridge macht sich per mDNS im LAN bekannt (_docflow-bridge._tcp)
+// DocFlow-Server und Begleit-Apps finden Bridges so auch ohne ausgehende Registrierung
+// Standardmäßig aus: die Ankündigung verrät Hostname, Version und Pairing-Zustand an jedes Gerät im LAN
+// Der SRV-Record nennt einen Port, den die Bridge tatsächlich bedient (Metrik-Endpunkt im LAN bzw. Push-Scan-Empfänger)
+// Ohne solchen Dienst ist die Ankündigung reine TXT-Information (SRV-Port 0)
+
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+use crate::metrics::MetricsConfig;
+use crate::push_scan::PushScanConfig;
+
+/// mDNS-Service-Typ der Bridge
+pub const SERVICE_TYPE: &str = "_docflow-bridge._tcp.local.";
+
+/// SRV-Port ohne erreichbaren Dienst (RFC 6763 Abschnitt 8: Port 0 = "kein Dienst unter diesem Port")
+/// Interessenten lesen dann nur den TXT-Record (Name, Version, Pairing-Zustand)
+pub const TXT_ONLY_PORT: u16 = 0;
+
+/// Einstellungen für die mDNS-Ankündigung
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnnounceConfig {
+    /// Ankündigung muss bewusst eingeschaltet werden
+    pub enabled: bool,
+    /// Angezeigter Name (None = "Bridge auf <Hostname>")
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Was im TXT-Record angekündigt wird
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Announcement {
+    config: AnnounceConfig,
+    paired: bool,
+    /// Im LAN erreichbarer Metrik-Endpunkt
+    metrics_port: Option<u16>,
+    /// Push-Scan-Empfänger (läuft nur mit gekoppelter Bridge)
+    push_scan_port: Option<u16>,
+}
+
+impl Announcement {
+    /// Dienst für den SRV-Record: Name (TXT "service") und Port
+    fn service(&self) -> (&'static str, u16) {
+        match (self.metrics_port, self.push_scan_port.filter(|_| self.paired)) {
+            (Some(port), _) => ("metrics", port),
+            (None, Some(port)) => ("push-scan", port),
+            (None, None) => ("none", TXT_ONLY_PORT),
+        }
+    }
+}
+
+/// Ein Daemon für alle Ankündigungen - Änderungen ersetzen nur den registrierten Service
+struct Announcer {
+    daemon: Option<ServiceDaemon>,
+    registered: Option<(String, Announcement)>,
+}
+
+static ANNOUNCER: Mutex<Announcer> = Mutex::new(Announcer {
+    daemon: None,
+    registered: None,
+});
+static CURRENT: Mutex<Option<Announcement>> = Mutex::new(None);
+
+/// Übernimmt neue Einstellungen (Metrik-Endpunkt und Push-Scan liefern den angekündigten Port)
+pub fn apply_config(config: &AnnounceConfig, metrics: &MetricsConfig, push_scan: &PushScanConfig) {
+    update(|announcement| {
+        announcement.config = config.clone();
+        announcement.metrics_port = (metrics.enabled && !is_loopback(&metrics.bind_address)).then_some(metrics.port);
+        announcement.push_scan_port = (push_scan.enabled
+            && !push_scan.listen_address.as_deref().is_some_and(is_loopback))
+        .then_some(push_scan.listen_port);
+    });
+}
+
+/// Nur lokal gebundene Dienste sind im LAN nicht erreichbar
+fn is_loopback(address: &str) -> bool {
+    address.trim() == "localhost" || address.trim().parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Pairing-Zustand im TXT-Record aktualisieren
+pub fn set_paired(paired: bool) {
+    update(|announcement| announcement.paired = paired);
+}
+
+fn update(change: impl FnOnce(&mut Announcement)) {
+    let announcement = {
+        let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+        let announcement = current.get_or_insert_with(Announcement::default);
+        change(announcement);
+        announcement.clone()
+    };
+
+    let mut announcer = ANNOUNCER.lock().unwrap_or_else(|e| e.into_inner());
+    let wanted = announcement.config.enabled;
+    match &announcer.registered {
+        Some((_, announced)) if wanted && *announced == announcement => return,
+        None if !wanted => return,
+        _ => {}
+    }
+
+    // Alte Ankündigung zurückziehen (neuer TXT-Record bzw. deaktiviert)
+    if let Some((fullname, _)) = announcer.registered.take() {
+        if let Some(daemon) = &announcer.daemon {
+            let _ = daemon.unregister(&fullname);
+        }
+    }
+
+    if !wanted {
+        return;
+    }
+
+    if announcer.daemon.is_none() {
+        match ServiceDaemon::new() {
+            Ok(daemon) => announcer.daemon = Some(daemon),
+            Err(e) => {
+                eprintln!("⚠ mDNS-Ankündigung fehlgeschlagen: {}", e);
+                return;
+            }
+        }
+    }
+    let Some(daemon) = &announcer.daemon else { return };
+
+    match register(daemon, &announcement) {
+        Ok(fullname) => {
+            println!("📣 Bridge im LAN angekündigt: {}", fullname);
+            announcer.registered = Some((fullname, announcement));
+        }
+        Err(e) => eprintln!("⚠ mDNS-Ankündigung fehlgeschlagen: {}", e),
+    }
+}
+
+fn register(
+    daemon: &ServiceDaemon,
+    announcement: &Announcement,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "docflow-bridge".to_string());
+    let name = announcement
+        .config
+        .name
+        .clone()
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| format!("Bridge auf {}", hostname));
+
+    let version = env!("CARGO_PKG_VERSION");
+    let paired = if announcement.paired { "true" } else { "false" };
+    let (service, port) = announcement.service();
+    let properties = [
+        ("name", name.as_str()),
+        ("version", version),
+        ("paired", paired),
+        ("os", std::env::consts::OS),
+        ("service", service),
+    ];
+
+    let info = ServiceInfo::new(
+        SERVICE_TYPE,
+        &name,
+        &format!("{}.local.", hostname),
+        "",
+        port,
+        &properties[..],
+    )?
+    .enable_addr_auto();
+    let fullname = info.get_fullname().to_string();
+    daemon.register(info)?;
+    Ok(fullname)
+}
@@ -0,0 +1,418 @@
+// Audit-Log - Nachvollziehbares Protokoll der Bridge-Aktionen (Pairing, Scans, Uploads, verschobene/gelöschte Dateien)
+// Nur anhängen (audit.jsonl), jeder Eintrag enthält den HMAC des vorherigen - Änderungen brechen die Kette; Upload gebündelt an DocFlow
+// Der HMAC-Schlüssel liegt im Keyring ("audit_key"), ab MAX_LOG_BYTES wird das Log rotiert (audit.1.jsonl ...)
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::rate_limit::RateLimited;
+use crate::{secrets, settings};
+
+/// Hash vor dem ersten Eintrag
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Keyring-Eintrag für den HMAC-Schlüssel der Kette
+pub const KEY_ENTRY: &str = "audit_key";
+
+/// Höchstzahl Einträge je Upload-Anfrage
+const UPLOAD_BATCH: usize = 500;
+
+/// Ab dieser Größe wird das Log rotiert
+pub const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Aufbewahrte rotierte Logs (audit.1.jsonl ist das jüngste)
+const ROTATED_LOGS: usize = 3;
+
+/// Gelesener Bereich am Dateiende für den letzten Eintrag
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// Protokollierte Aktion
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Paired,
+    Unpaired,
+    ScanCompleted,
+    ScanFailed,
+    FileUploaded,
+    FileMoved,
+    FileDeleted,
+    FileQuarantined,
+    SettingsChanged,
+}
+
+/// Ein Eintrag im Audit-Log
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Fortlaufend ab 1, ohne Lücken
+    pub seq: u64,
+    pub timestamp: String,
+    pub action: AuditAction,
+    pub details: Value,
+    pub prev_hash: String,
+    /// HMAC-SHA256 über Vorgänger-Hash und Inhalt (siehe `entry_hash`)
+    pub hash: String,
+    /// Hash mit Schlüssel gebildet (ältere Einträge: SHA256 ohne Schlüssel)
+    #[serde(default)]
+    pub keyed: bool,
+}
+
+/// Ergebnis der Kettenprüfung
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditVerification {
+    pub entries: u64,
+    pub valid: bool,
+    /// Erster Eintrag, an dem die Kette bricht
+    pub broken_at: Option<u64>,
+    pub message: String,
+}
+
+/// Ende der Kette (letzter Eintrag)
+struct Chain {
+    seq: u64,
+    hash: String,
+}
+
+static CHAIN: Mutex<Option<Chain>> = Mutex::new(None);
+
+/// HMAC-Schlüssel im Speicher (einmal aus dem Keyring geladen bzw. erzeugt)
+static KEY: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+
+/// Letztes Prüfergebnis, falls die Kette gebrochen ist (für die Status-Anzeige)
+static WARNING: Mutex<Option<String>> = Mutex::new(None);
+
+/// Upload-Stand (audit_upload.json)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UploadState {
+    uploaded_seq: u64,
+    /// Byte-Position nach dem zuletzt hochgeladenen Eintrag
+    offset: u64,
+    /// Erster Eintrag der Datei, zu der `offset` gehört (nach einer Rotation beginnt die Datei neu)
+    #[serde(default)]
+    first_seq: u64,
+}
+
+/// Setzt den HMAC-Schlüssel (z.B. nach dem Laden aus dem Keyring)
+pub fn set_key(key: &[u8]) {
+    *KEY.lock().unwrap_or_else(|e| e.into_inner()) = Some(key.to_vec());
+}
+
+/// Schlüssel aus dem Keyring - fehlt er, wird ein neuer erzeugt und gespeichert
+fn key() -> Vec<u8> {
+    let mut key = KEY.lock().unwrap_or_else(|e| e.into_inner());
+    key.get_or_insert_with(|| {
+        if let Some(stored) = secrets::get(KEY_ENTRY).and_then(|hex| decode_hex(&hex)) {
+            return stored;
+        }
+        let generated: Vec<u8> = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()]
+            .iter()
+            .flat_map(|id| id.into_bytes())
+            .collect();
+        if let Err(e) = secrets::set(KEY_ENTRY, &hex(&generated)) {
+            eprintln!("⚠ Audit-Schlüssel nicht im Keyring gespeichert - Kette nur bis zum Neustart prüfbar: {}", e);
+        }
+        generated
+    })
+    .clone()
+}
+
+/// Protokolliert eine Aktion (z.B. `audit::record(AuditAction::FileDeleted, json!({ "path": ... }))`)
+pub fn record(action: AuditAction, details: Value) {
+    let mut guard = CHAIN.lock().unwrap_or_else(|e| e.into_inner());
+    let chain = guard.get_or_insert_with(load_tail);
+
+    let mut entry = AuditEntry {
+        seq: chain.seq + 1,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        action,
+        details,
+        prev_hash: chain.hash.clone(),
+        hash: String::new(),
+        keyed: true,
+    };
+    entry.hash = entry_hash(&entry);
+
+    let Ok(line) = serde_json::to_string(&entry) else {
+        return;
+    };
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    rotate_if_full();
+    let written = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_path())
+        .and_then(|mut file| writeln!(file, "{}", line));
+    match written {
+        Ok(()) => {
+            chain.seq = entry.seq;
+            chain.hash = entry.hash;
+        }
+        Err(e) => eprintln!("⚠ Audit-Eintrag nicht geschrieben ({:?}): {}", action, e),
+    }
+}
+
+/// Hash eines Eintrags: HMAC-SHA256 über Vorgänger-Hash, Nummer, Zeit, Aktion und Details (je Zeile)
+/// Ohne Schlüssel ließe sich die ganze Kette nach einer Änderung einfach neu berechnen
+pub fn entry_hash(entry: &AuditEntry) -> String {
+    let action = serde_json::to_string(&entry.action).unwrap_or_default();
+    let content = format!(
+        "{}\n{}\n{}\n{}\n{}",
+        entry.prev_hash, entry.seq, entry.timestamp, action, entry.details
+    );
+    if !entry.keyed {
+        return format!("{:x}", Sha256::digest(content.as_bytes()));
+    }
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(&key()) else {
+        return String::new();
+    };
+    mac.update(content.as_bytes());
+    hex(&mac.finalize().into_bytes())
+}
+
+/// Prüft die Kette des aktuellen Logs (Reihenfolge, Verkettung und Hashes)
+/// Nach einer Rotation schließt der erste Eintrag an den letzten des rotierten Logs an
+/// Das Ergebnis wird für die Status-Anzeige gemerkt (siehe `current_warning`)
+pub fn verify() -> AuditVerification {
+    let verification = verify_log();
+    *WARNING.lock().unwrap_or_else(|e| e.into_inner()) =
+        (!verification.valid).then(|| format!("Audit-Log beschädigt: {}", verification.message));
+    verification
+}
+
+fn verify_log() -> AuditVerification {
+    let (mut expected_seq, mut previous) = match last_entry(&rotated_path(1)) {
+        Some(last) => (last.seq + 1, last.hash),
+        None => (1, GENESIS_HASH.to_string()),
+    };
+    let first_seq = expected_seq;
+    let broken = |seq: u64, message: String| AuditVerification {
+        entries: seq - first_seq,
+        valid: false,
+        broken_at: Some(seq),
+        message,
+    };
+
+    let file = match std::fs::File::open(audit_path()) {
+        Ok(file) => Some(file),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return broken(expected_seq, format!("Log nicht lesbar: {}", e)),
+    };
+    for line in file.into_iter().flat_map(|file| BufReader::new(file).lines()) {
+        let Ok(line) = line else {
+            return broken(expected_seq, format!("Eintrag {} nicht lesbar", expected_seq));
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) else {
+            return broken(expected_seq, format!("Eintrag {} nicht lesbar", expected_seq));
+        };
+        if entry.seq != expected_seq {
+            return broken(expected_seq, format!("Eintrag {} fehlt (gefunden: {})", expected_seq, entry.seq));
+        }
+        if entry.prev_hash != previous || entry.hash != entry_hash(&entry) {
+            return broken(expected_seq, format!("Eintrag {} wurde verändert", expected_seq));
+        }
+        previous = entry.hash;
+        expected_seq += 1;
+    }
+
+    let entries = expected_seq - first_seq;
+    AuditVerification {
+        entries,
+        valid: true,
+        broken_at: None,
+        message: format!("{} Einträge, Kette vollständig", entries),
+    }
+}
+
+/// Warnung der letzten Prüfung, falls die Kette gebrochen ist (für Status-Anzeige)
+pub fn current_warning() -> Option<String> {
+    WARNING.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Rotiert das Log, sobald es MAX_LOG_BYTES erreicht (audit.jsonl → audit.1.jsonl → ... → verworfen)
+fn rotate_if_full() {
+    let full = std::fs::metadata(audit_path()).is_ok_and(|meta| meta.len() >= MAX_LOG_BYTES);
+    if !full {
+        return;
+    }
+    for index in (1..ROTATED_LOGS).rev() {
+        let _ = std::fs::rename(rotated_path(index), rotated_path(index + 1));
+    }
+    if let Err(e) = std::fs::rename(audit_path(), rotated_path(1)) {
+        eprintln!("⚠ Audit-Log nicht rotiert: {}", e);
+    }
+}
+
+/// Lädt noch nicht übertragene Einträge zu DocFlow hoch → Anzahl
+pub async fn upload_pending(docflow_url: &str, api_key: &str) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let mut state = load_upload_state();
+    let mut uploaded = 0;
+
+    loop {
+        let (batch, offset) = read_batch(&state)?;
+        let Some(last) = batch.last() else {
+            return Ok(uploaded);
+        };
+        let last_seq = last.seq;
+
+        let response = crate::http_client::docflow()
+            .post(format!("{}/api/scanner/bridge/audit", docflow_url))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&serde_json::json!({ "entries": batch }))
+            .timeout(std::time::Duration::from_secs(30))
+            .send_limited()
+            .await?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Audit-Upload fehlgeschlagen: {}", error_text).into());
+        }
+
+        uploaded += batch.len();
+        state = UploadState { uploaded_seq: last_seq, offset, first_seq: first_entry(&audit_path()).map_or(0, |e| e.seq) };
+        save_upload_state(&state);
+    }
+}
+
+/// Liest die nächsten Einträge nach dem Upload-Stand → (Einträge, Byte-Position danach)
+fn read_batch(state: &UploadState) -> std::io::Result<(Vec<AuditEntry>, u64)> {
+    let mut file = match std::fs::File::open(audit_path()) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), state.offset)),
+        Err(e) => return Err(e),
+    };
+    let first_seq = first_entry(&audit_path()).map_or(0, |e| e.seq);
+    // Noch nicht übertragene Einträge wurden inzwischen rotiert → zuerst aus den rotierten Logs
+    if first_seq > state.uploaded_seq + 1 {
+        let batch = rotated_entries(state.uploaded_seq, first_seq);
+        if !batch.is_empty() {
+            return Ok((batch, 0));
+        }
+    }
+    // Andere Datei als beim letzten Upload (rotiert) oder kürzer als gedacht → von vorn lesen,
+    // Nummern filtern die Duplikate
+    let start = if first_seq == state.first_seq && state.offset <= file.metadata()?.len() { state.offset } else { 0 };
+    file.seek(SeekFrom::Start(start))?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)?;
+
+    let mut batch = Vec::new();
+    let mut offset = start;
+    for line in content.split_inclusive('\n') {
+        // Unvollständige letzte Zeile (wird gerade geschrieben) beim nächsten Mal
+        if !line.ends_with('\n') || batch.len() >= UPLOAD_BATCH {
+            break;
+        }
+        offset += line.len() as u64;
+        if let Ok(entry) = serde_json::from_str::<AuditEntry>(line) {
+            if entry.seq > state.uploaded_seq {
+                batch.push(entry);
+            }
+        }
+    }
+    Ok((batch, offset))
+}
+
+/// Einträge aus den rotierten Logs mit uploaded_seq < seq < before (älteste zuerst, höchstens UPLOAD_BATCH)
+fn rotated_entries(uploaded_seq: u64, before: u64) -> Vec<AuditEntry> {
+    let mut entries = Vec::new();
+    for index in (1..=ROTATED_LOGS).rev() {
+        let Ok(file) = std::fs::File::open(rotated_path(index)) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) {
+                if entry.seq > uploaded_seq && entry.seq < before {
+                    entries.push(entry);
+                    if entries.len() >= UPLOAD_BATCH {
+                        return entries;
+                    }
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Letzten Eintrag lesen (Fortsetzung der Kette nach einem Neustart) - aus dem rotierten Log,
+/// falls das aktuelle gerade neu begonnen wurde
+fn load_tail() -> Chain {
+    last_entry(&audit_path())
+        .or_else(|| last_entry(&rotated_path(1)))
+        .map(|entry| Chain { seq: entry.seq, hash: entry.hash })
+        .unwrap_or_else(|| Chain { seq: 0, hash: GENESIS_HASH.to_string() })
+}
+
+/// Letzter lesbarer Eintrag einer Log-Datei (liest nur das Dateiende, bei langen Einträgen schrittweise mehr)
+fn last_entry(path: &std::path::Path) -> Option<AuditEntry> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let mut window = TAIL_BYTES;
+    loop {
+        file.seek(SeekFrom::Start(len.saturating_sub(window))).ok()?;
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail).ok()?;
+        let entry = String::from_utf8_lossy(&tail)
+            .lines()
+            .rev()
+            .find_map(|line| serde_json::from_str::<AuditEntry>(line).ok());
+        if entry.is_some() || window >= len {
+            return entry;
+        }
+        window *= 2;
+    }
+}
+
+/// Erster Eintrag einer Log-Datei
+fn first_entry(path: &std::path::Path) -> Option<AuditEntry> {
+    let file = std::fs::File::open(path).ok()?;
+    let line = BufReader::new(file).lines().next()?.ok()?;
+    serde_json::from_str(&line).ok()
+}
+
+fn audit_path() -> PathBuf {
+    settings::data_dir().join("audit.jsonl")
+}
+
+fn rotated_path(index: usize) -> PathBuf {
+    settings::data_dir().join(format!("audit.{}.jsonl", index))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn upload_state_path() -> PathBuf {
+    settings::data_dir().join("audit_upload.json")
+}
+
+fn load_upload_state() -> UploadState {
+    std::fs::read_to_string(upload_state_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_upload_state(state: &UploadState) {
+    if let Ok(json) = serde_json::to_string_pretty(state) {
+        let _ = std::fs::create_dir_all(settings::data_dir());
+        let _ = std::fs::write(upload_state_path(), json);
+    }
+}
@@ -0,0 +1,70 @@
+// Automatische Farberkennung - Modus "auto": in Farbe scannen, einfarbige Seiten umwandeln
+// Typische Büro-Stapel sind überwiegend schwarz/weiß → 60-80% kleinere Dateien
+
+use image::{DynamicImage, GrayImage, Luma};
+use serde::{Deserialize, Serialize};
+
+/// Zielformat für als einfarbig erkannte Seiten
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum MonochromeTarget {
+    Grayscale,
+    BlackWhite,
+}
+
+/// Schwellwerte der Farberkennung
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AutoColorConfig {
+    /// Ab dieser Kanal-Differenz (max - min) gilt ein Pixel als farbig
+    pub chroma_threshold: u8,
+    /// Ab diesem Anteil farbiger Pixel (in Prozent) bleibt die Seite farbig
+    pub color_pixel_percent: f32,
+    pub target: MonochromeTarget,
+}
+
+impl Default for AutoColorConfig {
+    fn default() -> Self {
+        Self {
+            chroma_threshold: 40,
+            color_pixel_percent: 0.3,
+            target: MonochromeTarget::Grayscale,
+        }
+    }
+}
+
+/// Prüft ob eine Seite nennenswerte Farbanteile hat
+pub fn is_color_page(image: &DynamicImage, config: &AutoColorConfig) -> bool {
+    let rgb = match image {
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageLuma16(_) => return false,
+        _ => image.to_rgb8(),
+    };
+
+    let total = (rgb.width() as u64 * rgb.height() as u64).max(1);
+    let color_pixels = rgb
+        .pixels()
+        .filter(|p| {
+            let [r, g, b] = p.0;
+            r.max(g).max(b) - r.min(g).min(b) >= config.chroma_threshold
+        })
+        .count() as u64;
+
+    color_pixels as f32 * 100.0 / total as f32 >= config.color_pixel_percent
+}
+
+/// Wandelt einfarbige Seiten um, farbige bleiben unverändert
+pub fn convert_if_monochrome(image: DynamicImage, config: &AutoColorConfig) -> DynamicImage {
+    if is_color_page(&image, config) {
+        return image;
+    }
+
+    let gray = image.to_luma8();
+    match config.target {
+        MonochromeTarget::Grayscale => DynamicImage::ImageLuma8(gray),
+        MonochromeTarget::BlackWhite => {
+            let (width, height) = gray.dimensions();
+            let bw = GrayImage::from_fn(width, height, |x, y| {
+                if gray.get_pixel(x, y).0[0] >= 128 { Luma([255]) } else { Luma([0]) }
+            });
+            DynamicImage::ImageLuma8(bw)
+        }
+    }
+}
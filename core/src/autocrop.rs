@@ -0,0 +1,100 @@
+// Automatische Seitengrößen-Erkennung - Schneidet ADF-Seiten auf das tatsächliche Papier zu
+// Gemischte A4/A5/Beleg-Stapel kommen sonst als einheitliche Letter-Seiten mit breiten Rändern
+
+use image::{DynamicImage, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+
+/// Konfiguration des automatischen Zuschnitts
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AutoCropConfig {
+    pub enabled: bool,
+    /// Farbabstand zum Scanner-Hintergrund, ab dem ein Pixel zum Papier gehört
+    pub tolerance: u8,
+    /// Zusätzlicher Rand um die erkannte Papierkante (mm)
+    pub margin_mm: f32,
+}
+
+impl Default for AutoCropConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tolerance: 30,
+            margin_mm: 1.0,
+        }
+    }
+}
+
+/// Anteil abweichender Pixel, ab dem eine Zeile/Spalte zum Papier gehört
+const CONTENT_FRACTION: f32 = 0.02;
+
+/// Schneidet die Seite auf die erkannte Papierfläche zu (unverändert, wenn nichts zu erkennen ist)
+pub fn crop_to_paper(image: DynamicImage, dpi: u32, config: &AutoCropConfig) -> DynamicImage {
+    let rgb = image.to_rgb8();
+    let (width, height) = rgb.dimensions();
+    if width < 16 || height < 16 {
+        return image;
+    }
+
+    let background = estimate_background(&rgb);
+    let differs = |p: &Rgb<u8>| {
+        p.0.iter()
+            .zip(background.0.iter())
+            .any(|(a, b)| a.abs_diff(*b) > config.tolerance)
+    };
+
+    let row_has_paper = |y: u32| {
+        let count = (0..width).filter(|&x| differs(rgb.get_pixel(x, y))).count();
+        count as f32 / width as f32 > CONTENT_FRACTION
+    };
+    let column_has_paper = |x: u32| {
+        let count = (0..height).filter(|&y| differs(rgb.get_pixel(x, y))).count();
+        count as f32 / height as f32 > CONTENT_FRACTION
+    };
+
+    let top = (0..height).find(|&y| row_has_paper(y));
+    let bottom = (0..height).rev().find(|&y| row_has_paper(y));
+    let left = (0..width).find(|&x| column_has_paper(x));
+    let right = (0..width).rev().find(|&x| column_has_paper(x));
+    let (Some(top), Some(bottom), Some(left), Some(right)) = (top, bottom, left, right) else {
+        return image;
+    };
+
+    let dpi = if dpi > 0 { dpi as f32 } else { 300.0 };
+    let margin = (config.margin_mm / 25.4 * dpi) as u32;
+    let left = left.saturating_sub(margin);
+    let top = top.saturating_sub(margin);
+    let right = (right + margin).min(width - 1);
+    let bottom = (bottom + margin).min(height - 1);
+
+    let crop_width = right - left + 1;
+    let crop_height = bottom - top + 1;
+
+    // Kaum Unterschied oder unplausibel kleiner Ausschnitt → Originalseite behalten
+    let area_ratio = (crop_width * crop_height) as f32 / (width * height) as f32;
+    if area_ratio > 0.97 || crop_width < width / 10 || crop_height < height / 10 {
+        return image;
+    }
+
+    image.crop_imm(left, top, crop_width, crop_height)
+}
+
+/// Hintergrundfarbe aus den äußersten Randpixeln (Median je Kanal)
+fn estimate_background(image: &RgbImage) -> Rgb<u8> {
+    let (width, height) = image.dimensions();
+    let mut samples: Vec<Rgb<u8>> = Vec::new();
+    for x in (0..width).step_by(4) {
+        samples.push(*image.get_pixel(x, 0));
+        samples.push(*image.get_pixel(x, height - 1));
+    }
+    for y in (0..height).step_by(4) {
+        samples.push(*image.get_pixel(0, y));
+        samples.push(*image.get_pixel(width - 1, y));
+    }
+
+    let channel = |c: usize| {
+        let mut values: Vec<u8> = samples.iter().map(|p| p.0[c]).collect();
+        values.sort_unstable();
+        values[values.len() / 2]
+    };
+    Rgb([channel(0), channel(1), channel(2)])
+}
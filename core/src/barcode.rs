@@ -0,0 +1,93 @@
+// Barcode-Erkennung - Liest Barcodes/QR-Codes aus Scan-Seiten und Folder-Sync-Dateien
+// Erkannte Codes (z.B. Kundennummer) gehen als Metadaten mit dem Upload an DocFlow (Auto-Ablage)
+
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::imaging;
+
+/// Barcode-Konfiguration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BarcodeConfig {
+    pub enabled: bool,
+    /// Erlaubte Formate (rxing-Bezeichnungen, z.B. "QR_CODE", "CODE_128", "CODE_39")
+    pub formats: Vec<String>,
+}
+
+impl Default for BarcodeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            formats: vec![
+                "QR_CODE".to_string(),
+                "CODE_128".to_string(),
+                "CODE_39".to_string(),
+            ],
+        }
+    }
+}
+
+/// Erkannter Barcode
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DetectedBarcode {
+    pub page: usize,
+    pub format: String,
+    pub value: String,
+}
+
+/// Sucht Barcodes in einem einzelnen Seitenbild
+pub fn detect_in_image(image: &DynamicImage, page: usize, config: &BarcodeConfig) -> Vec<DetectedBarcode> {
+    let luma = image.to_luma8();
+    let (width, height) = luma.dimensions();
+
+    // Kein Treffer ist der Normalfall und wird als Fehler gemeldet
+    let results = match rxing::helpers::detect_multiple_in_luma(luma.into_raw(), width, height) {
+        Ok(results) => results,
+        Err(_) => return Vec::new(),
+    };
+
+    results
+        .iter()
+        .filter_map(|result| {
+            let format = format!("{:?}", result.getBarcodeFormat());
+            if !config.formats.iter().any(|f| f.eq_ignore_ascii_case(&format)) {
+                return None;
+            }
+            Some(DetectedBarcode {
+                page,
+                format,
+                value: result.getText().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Sucht Barcodes in mehreren Dokumenten (Daten, MIME-Typ) - Seiten werden fortlaufend nummeriert
+pub fn detect_in_documents(documents: &[(Vec<u8>, String)], config: &BarcodeConfig) -> Vec<DetectedBarcode> {
+    let mut barcodes: Vec<DetectedBarcode> = Vec::new();
+    let mut page = 0;
+
+    for (data, mime_type) in documents {
+        for image in imaging::decode_pages(data, mime_type) {
+            page += 1;
+            for barcode in detect_in_image(&image, page, config) {
+                if !barcodes.contains(&barcode) {
+                    barcodes.push(barcode);
+                }
+            }
+        }
+    }
+
+    barcodes
+}
+
+/// Barcode-Erkennung im Blocking-Threadpool (CPU-intensiv)
+pub async fn detect(documents: Vec<(Vec<u8>, String)>, config: BarcodeConfig) -> Vec<DetectedBarcode> {
+    if !config.enabled {
+        return Vec::new();
+    }
+
+    tokio::task::spawn_blocking(move || detect_in_documents(&documents, &config))
+        .await
+        .unwrap_or_default()
+}
@@ -0,0 +1,162 @@
+// Zertifikatsablauf - Warnt rechtzeitig, bevor das TLS-Zertifikat eines DocFlow-Servers abläuft
+// Das Serverzertifikat wird bei jeder DocFlow-Antwort mitgelesen (TLS-Info des Clients), ab 14 Tagen vor Ablauf gibt es eine Warnung
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Ab so vielen Tagen vor Ablauf wird gewarnt
+pub const WARN_DAYS: i64 = 14;
+
+/// Ablaufstatus des Zertifikats eines DocFlow-Servers
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CertificateExpiry {
+    /// "host:port"
+    pub server: String,
+    pub subject: String,
+    /// RFC3339 (UTC)
+    pub not_after: String,
+    /// Ablaufdatum in lokaler Zeit, z.B. "30.10.2026"
+    pub not_after_display: String,
+    /// Verbleibende volle Tage (negativ = abgelaufen)
+    pub days_left: i64,
+    /// Innerhalb der Warnfrist oder abgelaufen
+    pub expiring: bool,
+}
+
+impl CertificateExpiry {
+    /// Meldung für Statusanzeige und Benachrichtigung
+    pub fn message(&self) -> String {
+        if self.days_left < 0 {
+            return format!(
+                "TLS-Zertifikat von {} ist seit {} abgelaufen - DocFlow-Administrator informieren",
+                self.server, self.not_after_display
+            );
+        }
+        let when = match self.days_left {
+            0 => "heute".to_string(),
+            1 => "morgen".to_string(),
+            days => format!("in {} Tagen", days),
+        };
+        format!(
+            "TLS-Zertifikat von {} läuft {} ab ({}) - DocFlow-Administrator informieren",
+            self.server, when, self.not_after_display
+        )
+    }
+}
+
+struct Observed {
+    der: Vec<u8>,
+    subject: String,
+    not_after: DateTime<Utc>,
+    /// Tag der letzten Benachrichtigung (höchstens eine je Tag)
+    notified_on: Option<NaiveDate>,
+}
+
+static OBSERVED: Mutex<Option<HashMap<String, Observed>>> = Mutex::new(None);
+
+/// Liest das Serverzertifikat aus einer DocFlow-Antwort (nur bei HTTPS vorhanden)
+pub fn observe(server: &str, response: &reqwest::Response) {
+    let Some(der) = response
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+    else {
+        return;
+    };
+    let unchanged = OBSERVED
+        .lock()
+        .is_ok_and(|o| o.as_ref().and_then(|o| o.get(server)).is_some_and(|seen| seen.der == der));
+    if !unchanged {
+        if let Err(e) = record(server, der) {
+            eprintln!("⚠ TLS-Zertifikat von {} nicht lesbar: {}", server, e);
+        }
+    }
+}
+
+/// Übernimmt ein Serverzertifikat (DER)
+pub fn record(server: &str, der: &[u8]) -> Result<(), String> {
+    let (subject, not_after) = parse(der)?;
+    let Ok(mut observed) = OBSERVED.lock() else {
+        return Ok(());
+    };
+    let observed = observed.get_or_insert_with(HashMap::new);
+    let notified_on = observed
+        .get(server)
+        .filter(|seen| seen.not_after == not_after)
+        .and_then(|seen| seen.notified_on);
+    observed.insert(
+        server.to_string(),
+        Observed { der: der.to_vec(), subject, not_after, notified_on },
+    );
+    Ok(())
+}
+
+/// Subject und Ablaufzeitpunkt (notAfter) eines Zertifikats
+pub fn parse(der: &[u8]) -> Result<(String, DateTime<Utc>), String> {
+    let (_, certificate) = x509_parser::parse_x509_certificate(der).map_err(|e| e.to_string())?;
+    let not_after = DateTime::from_timestamp(certificate.validity().not_after.timestamp(), 0)
+        .ok_or("Ungültiges Ablaufdatum")?;
+    Ok((certificate.subject().to_string(), not_after))
+}
+
+/// Ablaufstatus zu einem festen Zeitpunkt
+pub fn expiry_at(server: &str, subject: &str, not_after: DateTime<Utc>, now: DateTime<Utc>) -> CertificateExpiry {
+    let seconds_left = (not_after - now).num_seconds();
+    CertificateExpiry {
+        server: server.to_string(),
+        subject: subject.to_string(),
+        not_after: not_after.to_rfc3339(),
+        not_after_display: not_after.with_timezone(&Local).format("%d.%m.%Y").to_string(),
+        days_left: seconds_left.div_euclid(86_400),
+        expiring: seconds_left <= WARN_DAYS * 86_400,
+    }
+}
+
+/// Ablaufstatus aller bisher gesehenen DocFlow-Server
+pub fn status() -> Vec<CertificateExpiry> {
+    let now = Utc::now();
+    let mut status: Vec<CertificateExpiry> = OBSERVED
+        .lock()
+        .ok()
+        .and_then(|o| {
+            o.as_ref().map(|o| {
+                o.iter()
+                    .map(|(server, seen)| expiry_at(server, &seen.subject, seen.not_after, now))
+                    .collect()
+            })
+        })
+        .unwrap_or_default();
+    status.sort_by_key(|s| s.days_left);
+    status
+}
+
+/// Dringendste Zertifikatswarnung (für Status-Anzeige)
+pub fn current_warning() -> Option<String> {
+    status().into_iter().find(|s| s.expiring).map(|s| s.message())
+}
+
+/// Warnungen, zu denen heute noch nicht benachrichtigt wurde (werden als benachrichtigt markiert)
+pub fn due_notifications() -> Vec<CertificateExpiry> {
+    let now = Utc::now();
+    let today = now.with_timezone(&Local).date_naive();
+    let Ok(mut observed) = OBSERVED.lock() else {
+        return Vec::new();
+    };
+    let Some(observed) = observed.as_mut() else {
+        return Vec::new();
+    };
+
+    observed
+        .iter_mut()
+        .filter_map(|(server, seen)| {
+            let expiry = expiry_at(server, &seen.subject, seen.not_after, now);
+            if !expiry.expiring || seen.notified_on == Some(today) {
+                return None;
+            }
+            seen.notified_on = Some(today);
+            Some(expiry)
+        })
+        .collect()
+}
@@ -0,0 +1,168 @@
+// Circuit-Breaker - Gemeinsames Fehlerbudget je DocFlow-Server für Poller, Folder-Sync und Statusmeldungen
+// Nach wiederholten Fehlern pausiert der gesamte DocFlow-Verkehr, eine einzelne Probe-Anfrage entscheidet über die Wiederaufnahme
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Fehlermeldung für Anfragen, die während der Pause gar nicht erst gesendet werden
+pub const PAUSED: &str = "DocFlow nicht erreichbar - Anfragen pausiert";
+
+/// Konfiguration des Circuit-Breakers
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Fehler in Folge (Verbindungsfehler, HTTP 5xx), ab denen pausiert wird (0 = deaktiviert)
+    pub failure_threshold: u32,
+    /// Pause bis zur ersten Probe-Anfrage (Sekunden)
+    pub cooldown_secs: u64,
+    /// Obergrenze der Pause, sie verdoppelt sich bei jeder fehlgeschlagenen Probe (Sekunden)
+    pub max_cooldown_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown_secs: 15,
+            max_cooldown_secs: 300,
+        }
+    }
+}
+
+enum State {
+    /// Normalbetrieb, zählt Fehler in Folge
+    Closed { failures: u32 },
+    /// Pausiert bis `until`
+    Open { until: Instant, cooldown: Duration },
+    /// Eine Probe-Anfrage ist unterwegs, alle anderen warten auf ihr Ergebnis
+    Probing { cooldown: Duration },
+}
+
+struct Breaker {
+    config: CircuitBreakerConfig,
+    servers: HashMap<String, State>,
+}
+
+static BREAKER: Mutex<Option<Breaker>> = Mutex::new(None);
+
+/// Zustand eines pausierten DocFlow-Servers
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PausedServer {
+    pub server: String,
+    /// Sekunden bis zur nächsten Probe-Anfrage (0 = Probe läuft)
+    pub retry_in_secs: u64,
+}
+
+/// Übernimmt neue Grenzwerte (laufende Pausen werden aufgehoben)
+pub fn apply_config(config: &CircuitBreakerConfig) {
+    if let Ok(mut breaker) = BREAKER.lock() {
+        *breaker = Some(Breaker {
+            config: config.clone(),
+            servers: HashMap::new(),
+        });
+    }
+}
+
+/// Darf eine Anfrage an den Server gehen? `Ok(true)` = sie ist die Probe-Anfrage
+pub fn admit(server: &str) -> Result<bool, String> {
+    with_breaker(Ok(false), |breaker| {
+        let now = Instant::now();
+        match breaker.servers.get(server) {
+            Some(State::Open { until, cooldown }) if *until <= now => {
+                let cooldown = *cooldown;
+                breaker.servers.insert(server.to_string(), State::Probing { cooldown });
+                println!("🔎 DocFlow ({}): Probe-Anfrage nach Pause", server);
+                Ok(true)
+            }
+            Some(State::Open { until, .. }) => {
+                Err(format!("{} (erneuter Versuch in {}s)", PAUSED, (*until - now).as_secs() + 1))
+            }
+            Some(State::Probing { .. }) => Err(format!("{} (Probe läuft)", PAUSED)),
+            _ => Ok(false),
+        }
+    })
+}
+
+/// Meldet das Ergebnis einer Anfrage (`success` = Server hat regulär geantwortet, auch mit 4xx)
+pub fn record(server: &str, success: bool, probe: bool) {
+    with_breaker((), |breaker| {
+        let threshold = breaker.config.failure_threshold;
+        let initial = Duration::from_secs(breaker.config.cooldown_secs.max(1));
+        let max = Duration::from_secs(breaker.config.max_cooldown_secs).max(initial);
+        let state = breaker.servers.entry(server.to_string()).or_insert(State::Closed { failures: 0 });
+
+        match state {
+            State::Probing { cooldown } if probe => {
+                if success {
+                    println!("✓ DocFlow ({}) wieder erreichbar, Anfragen laufen weiter", server);
+                    *state = State::Closed { failures: 0 };
+                } else {
+                    let cooldown = (*cooldown * 2).min(max);
+                    eprintln!("⏸ DocFlow ({}) weiterhin nicht erreichbar, nächste Probe in {}s", server, cooldown.as_secs());
+                    *state = State::Open { until: Instant::now() + cooldown, cooldown };
+                }
+            }
+            State::Closed { failures } => {
+                if success {
+                    *failures = 0;
+                    return;
+                }
+                *failures += 1;
+                if threshold > 0 && *failures >= threshold {
+                    eprintln!(
+                        "⏸ DocFlow ({}): {} Fehler in Folge, pausiere alle Anfragen für {}s",
+                        server,
+                        failures,
+                        initial.as_secs()
+                    );
+                    *state = State::Open { until: Instant::now() + initial, cooldown: initial };
+                }
+            }
+            // Nachzügler (vor der Pause gestartet) ändern am Zustand nichts
+            _ => {}
+        }
+    })
+}
+
+/// Probe-Anfrage ohne Ergebnis abgebrochen → sofort eine neue Probe zulassen
+pub fn abandon_probe(server: &str) {
+    with_breaker((), |breaker| {
+        if let Some(State::Probing { cooldown }) = breaker.servers.get(server) {
+            let cooldown = *cooldown;
+            breaker.servers.insert(server.to_string(), State::Open { until: Instant::now(), cooldown });
+        }
+    })
+}
+
+/// Aktuell pausierte DocFlow-Server (für Status-Anzeige)
+pub fn paused() -> Vec<PausedServer> {
+    with_breaker(Vec::new(), |breaker| {
+        let now = Instant::now();
+        let mut paused: Vec<PausedServer> = breaker
+            .servers
+            .iter()
+            .filter_map(|(server, state)| match state {
+                State::Closed { .. } => None,
+                State::Open { until, .. } => Some(PausedServer {
+                    server: server.clone(),
+                    retry_in_secs: until.saturating_duration_since(now).as_secs(),
+                }),
+                State::Probing { .. } => Some(PausedServer { server: server.clone(), retry_in_secs: 0 }),
+            })
+            .collect();
+        paused.sort_by(|a, b| a.server.cmp(&b.server));
+        paused
+    })
+}
+
+fn with_breaker<T>(fallback: T, f: impl FnOnce(&mut Breaker) -> T) -> T {
+    let Ok(mut guard) = BREAKER.lock() else {
+        return fallback;
+    };
+    let breaker = guard.get_or_insert_with(|| Breaker {
+        config: CircuitBreakerConfig::default(),
+        servers: HashMap::new(),
+    });
+    f(breaker)
+}
@@ -0,0 +1,73 @@
+// Zwischenablage - Bild aus der Zwischenablage (Screenshot, Snipping-Tool) als PDF oder PNG zu DocFlow hochladen
+// Das Auslesen übernimmt die Desktop-Hülle, hier nur Konvertierung und Upload über den Folder-Upload-Endpunkt
+
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+use crate::folder_watcher::{self, FolderUploadResponse};
+use crate::imaging;
+use crate::pdf;
+
+/// Angenommene Auflösung von Bildschirmfotos für die PDF-Seitengröße
+const SCREENSHOT_DPI: u32 = 150;
+
+/// Zielformat für Bilder aus der Zwischenablage
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardFormat {
+    /// Einseitiges PDF (Standard, wie ein Scan)
+    #[default]
+    Pdf,
+    /// Verlustfreies PNG
+    Png,
+}
+
+/// Wandelt RGBA-Pixel in eine Upload-Datei um → (Daten, Dateiname, MIME-Typ)
+pub fn convert(
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+    format: ClipboardFormat,
+) -> Result<(Vec<u8>, String, &'static str), Box<dyn std::error::Error + Send + Sync>> {
+    let image = RgbaImage::from_raw(width, height, rgba).ok_or("Ungültige Bilddaten in der Zwischenablage")?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+
+    match format {
+        ClipboardFormat::Png => {
+            let mut data = Vec::new();
+            DynamicImage::ImageRgba8(image).write_to(&mut Cursor::new(&mut data), ImageFormat::Png)?;
+            Ok((data, format!("zwischenablage-{}.png", stamp), "image/png"))
+        }
+        ClipboardFormat::Pdf => {
+            let jpeg = imaging::encode_jpeg_with_quality(&DynamicImage::ImageRgb8(flatten(&image)), 90)?;
+            let data = pdf::assemble_jpeg_pdf(&[jpeg], SCREENSHOT_DPI)?;
+            Ok((data, format!("zwischenablage-{}.pdf", stamp), "application/pdf"))
+        }
+    }
+}
+
+/// Lädt ein Bild aus der Zwischenablage zu DocFlow hoch
+pub async fn upload(
+    docflow_url: &str,
+    api_key: &str,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+    format: ClipboardFormat,
+) -> Result<FolderUploadResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let (data, filename, mime_type) =
+        tokio::task::spawn_blocking(move || convert(width, height, rgba, format)).await??;
+
+    println!("📋 Lade Zwischenablage hoch: {} ({} KB)", filename, data.len() / 1024);
+    folder_watcher::upload_bytes(docflow_url, api_key, data, &filename, mime_type, "Zwischenablage").await
+}
+
+/// Transparente Bereiche auf weißen Hintergrund legen (JPEG kennt keinen Alpha-Kanal)
+fn flatten(image: &RgbaImage) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let [r, g, b, a] = image.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    })
+}
@@ -0,0 +1,84 @@
+// Konfigurations-Export - Portable JSON-Datei zum Klonen einer Bridge-Einrichtung
+// Enthält Einstellungen und Folder-Sync, aber keine Secrets (API-Key, DocFlow-URL)
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::folder_watcher::FolderSyncConfig;
+use crate::migrations;
+use crate::settings::BridgeSettings;
+
+/// Aktuelles Format der Export-Datei
+const FORMAT_VERSION: u32 = 1;
+
+/// Inhalt der Export-Datei
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub format_version: u32,
+    pub exported_at: String,
+    pub bridge_version: String,
+    /// Alle Einstellungen inkl. Scanner-Bezeichnungen und Discovery-Hosts
+    /// Mit Schema-Version gespeichert, ältere Exporte werden beim Import migriert
+    #[serde(serialize_with = "store_settings", deserialize_with = "load_settings")]
+    pub settings: BridgeSettings,
+    #[serde(default, serialize_with = "store_folder_sync", deserialize_with = "load_folder_sync")]
+    pub folder_sync: Option<FolderSyncConfig>,
+}
+
+impl ConfigBundle {
+    pub fn new(settings: BridgeSettings, folder_sync: Option<FolderSyncConfig>) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            bridge_version: env!("CARGO_PKG_VERSION").to_string(),
+            settings,
+            folder_sync,
+        }
+    }
+
+    /// Liest eine Export-Datei und prüft Format und Inhalt
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let bundle: ConfigBundle =
+            serde_json::from_str(json).map_err(|e| format!("Ungültige Konfigurationsdatei: {}", e))?;
+
+        if bundle.format_version > FORMAT_VERSION {
+            return Err(format!(
+                "Konfigurationsdatei stammt von einer neueren Bridge-Version ({}), bitte Bridge aktualisieren",
+                bundle.bridge_version
+            ));
+        }
+        bundle.settings.intervals.validate()?;
+        Ok(bundle)
+    }
+}
+
+fn store_settings<S: Serializer>(settings: &BridgeSettings, serializer: S) -> Result<S::Ok, S::Error> {
+    migrations::store(settings, &migrations::SETTINGS)
+        .map_err(S::Error::custom)?
+        .serialize(serializer)
+}
+
+fn load_settings<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BridgeSettings, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    migrations::load_value(value, &migrations::SETTINGS)
+        .map(|loaded| loaded.value)
+        .map_err(D::Error::custom)
+}
+
+fn store_folder_sync<S: Serializer>(config: &Option<FolderSyncConfig>, serializer: S) -> Result<S::Ok, S::Error> {
+    config
+        .as_ref()
+        .map(|c| migrations::store(c, &migrations::FOLDER_SYNC))
+        .transpose()
+        .map_err(S::Error::custom)?
+        .serialize(serializer)
+}
+
+fn load_folder_sync<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<FolderSyncConfig>, D::Error> {
+    Option::<Value>::deserialize(deserializer)?
+        .map(|value| migrations::load_value(value, &migrations::FOLDER_SYNC).map(|loaded| loaded.value))
+        .transpose()
+        .map_err(D::Error::custom)
+}
@@ -0,0 +1,101 @@
+// Zähler - Gesamtzahlen für die Statusseite (Scan-Jobs, Folder-Uploads, Fehler), bleiben über Neustarts erhalten
+// Gespeichert in counters.json im App-Datenverzeichnis, Poller und Folder-Watcher zählen zusätzlich je Sitzung
+// Zählungen werden gesammelt und verzögert geschrieben (beim Beenden sofort per flush)
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::settings;
+
+/// Gesamtzahlen seit dem letzten Zurücksetzen
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Counters {
+    #[serde(default)]
+    pub jobs_processed: u32,
+    #[serde(default)]
+    pub files_uploaded: u32,
+    #[serde(default)]
+    pub folder_errors: u32,
+    #[serde(default)]
+    pub files_quarantined: u32,
+    #[serde(default)]
+    pub files_skipped: u32,
+    /// Bereits bekannte Dateien (nicht erneut übertragen bzw. vom Server als Duplikat erkannt)
+    #[serde(default)]
+    pub files_duplicate: u32,
+    /// Beginn der Zählung (erster Start bzw. letztes Zurücksetzen)
+    pub since: Option<String>,
+}
+
+/// Verzögerung zwischen Zählung und Schreiben - viele Uploads in kurzer Folge ergeben einen Schreibvorgang
+const SAVE_DELAY: Duration = Duration::from_secs(5);
+
+static COUNTERS: Mutex<Option<Counters>> = Mutex::new(None);
+/// Ungespeicherte Zählungen vorhanden, Schreiben ist bereits eingeplant
+static SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Zählt und plant das Speichern ein (z.B. `counters::record(|c| c.files_uploaded += 1)`)
+pub fn record(change: impl FnOnce(&mut Counters)) {
+    {
+        let mut guard = COUNTERS.lock().unwrap_or_else(|e| e.into_inner());
+        change(guard.get_or_insert_with(load));
+    }
+    if !SAVE_PENDING.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(|| {
+            std::thread::sleep(SAVE_DELAY);
+            flush();
+        });
+    }
+}
+
+/// Ungespeicherte Zählungen sofort schreiben (vor dem Beenden)
+pub fn flush() {
+    if !SAVE_PENDING.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let guard = COUNTERS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(counters) = guard.as_ref() {
+        save(counters);
+    }
+}
+
+/// Aktuelle Gesamtzahlen
+pub fn snapshot() -> Counters {
+    COUNTERS.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert_with(load).clone()
+}
+
+/// Alle Zähler auf null setzen
+pub fn reset() {
+    let mut guard = COUNTERS.lock().unwrap_or_else(|e| e.into_inner());
+    let counters = guard.insert(fresh());
+    save(counters);
+    SAVE_PENDING.store(false, Ordering::SeqCst);
+}
+
+fn fresh() -> Counters {
+    Counters {
+        since: Some(chrono::Utc::now().to_rfc3339()),
+        ..Default::default()
+    }
+}
+
+fn load() -> Counters {
+    std::fs::read_to_string(counters_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(fresh)
+}
+
+fn save(counters: &Counters) {
+    if let Ok(json) = serde_json::to_string_pretty(counters) {
+        let _ = std::fs::create_dir_all(settings::data_dir());
+        let _ = std::fs::write(counters_path(), json);
+    }
+}
+
+fn counters_path() -> PathBuf {
+    settings::data_dir().join("counters.json")
+}
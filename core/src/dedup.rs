@@ -0,0 +1,237 @@
+// Duplikat-Erkennung - Gemeinsamer Inhalts-Hash-Speicher für Folder-Sync und Scan-Uploads
+// Ein per DocFlow-Job gescanntes Dokument, das zusätzlich im Scan-Ordner landet, wird übersprungen bzw. als Duplikat markiert
+// Gleiche Hashes innerhalb eines Kanals behandelt weiterhin der jeweilige Kanal selbst (DocFlow erkennt sie serverseitig)
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::settings;
+
+/// Multipart-Feld mit dem früheren Upload (JSON), wenn ein Duplikat trotzdem hochgeladen wird
+pub const DUPLICATE_FIELD: &str = "duplicate_of";
+
+/// Upload-Weg eines Dokuments
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    /// Datei aus dem überwachten Scan-Ordner
+    Folder,
+    /// Scan über einen DocFlow-Job oder Push-Scan am Gerät
+    Scan,
+}
+
+impl Channel {
+    fn label(self) -> &'static str {
+        match self {
+            Channel::Folder => "per Folder-Sync",
+            Channel::Scan => "als Scan",
+        }
+    }
+}
+
+/// Verhalten bei einem Duplikat aus dem anderen Kanal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateAction {
+    /// Nicht erneut hochladen (Scans zu DocFlow-Jobs werden immer hochgeladen und nur markiert)
+    #[default]
+    Skip,
+    /// Hochladen und DocFlow den früheren Upload mitteilen (Feld "duplicate_of")
+    Flag,
+}
+
+/// Konfiguration der kanalübergreifenden Duplikat-Erkennung
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DedupConfig {
+    pub enabled: bool,
+    pub action: DuplicateAction,
+    /// So lange bleiben Hashes gespeichert (dedup.jsonl)
+    pub retention_days: u32,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: DuplicateAction::Skip,
+            retention_days: 30,
+        }
+    }
+}
+
+/// Ein hochgeladenes Dokument
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DedupEntry {
+    /// SHA256 (hex)
+    pub hash: String,
+    pub channel: Channel,
+    /// Dateipfad bzw. Job-ID
+    pub reference: String,
+    pub recorded_at: String,
+}
+
+impl DedupEntry {
+    /// Für Log-Meldungen, z.B. "als Scan (Job 42) am 16.10.2026 14:03:12"
+    pub fn describe(&self) -> String {
+        let when = crate::timestamps::display(&self.recorded_at)
+            .map(|t| format!(" am {}", t.local))
+            .unwrap_or_default();
+        format!("{} ({}){}", self.channel.label(), self.reference, when)
+    }
+}
+
+/// Ergebnis der Prüfung vor einem Upload
+#[derive(Clone, Debug, PartialEq)]
+pub enum Check {
+    /// Unbekannt (oder Erkennung aus)
+    New,
+    /// Schon über den anderen Kanal hochgeladen - nicht hochladen
+    Skip(DedupEntry),
+    /// Schon über den anderen Kanal hochgeladen - mit "duplicate_of" hochladen
+    Flag(DedupEntry),
+}
+
+struct Store {
+    /// Hash → je Kanal der letzte Upload (None = noch nicht geladen)
+    entries: Option<HashMap<String, Vec<DedupEntry>>>,
+    config: DedupConfig,
+}
+
+static STORE: Mutex<Store> = Mutex::new(Store {
+    entries: None,
+    config: DedupConfig {
+        enabled: true,
+        action: DuplicateAction::Skip,
+        retention_days: 30,
+    },
+});
+
+/// Übernimmt die Konfiguration
+pub fn apply_config(config: &DedupConfig) {
+    if let Ok(mut store) = STORE.lock() {
+        // Neu laden, damit eine kürzere Aufbewahrung sofort greift
+        if store.config.retention_days != config.retention_days {
+            store.entries = None;
+        }
+        store.config = config.clone();
+    }
+}
+
+/// SHA256 eines Dokuments (hex, wie im Multipart-Feld "file_hash")
+pub fn hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// SHA256 blockweise lesen (z.B. ausgelagerte Scans, ohne sie komplett zu laden)
+pub fn hash_reader(mut reader: impl std::io::Read) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut reader, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Prüft, ob der Inhalt bereits über einen anderen Kanal hochgeladen wurde
+pub fn check(hash: &str, channel: Channel) -> Check {
+    let Ok(mut store) = STORE.lock() else {
+        return Check::New;
+    };
+    if !store.config.enabled {
+        return Check::New;
+    }
+    let action = store.config.action;
+    let earlier = entries(&mut store)
+        .get(hash)
+        .and_then(|e| e.iter().find(|entry| entry.channel != channel))
+        .cloned();
+
+    match (earlier, action) {
+        (None, _) => Check::New,
+        (Some(entry), DuplicateAction::Skip) => Check::Skip(entry),
+        (Some(entry), DuplicateAction::Flag) => Check::Flag(entry),
+    }
+}
+
+/// Merkt einen erfolgreichen Upload
+pub fn record(hash: &str, channel: Channel, reference: &str) {
+    let Ok(mut store) = STORE.lock() else {
+        return;
+    };
+    if !store.config.enabled {
+        return;
+    }
+
+    let entry = DedupEntry {
+        hash: hash.to_string(),
+        channel,
+        reference: reference.to_string(),
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+    };
+    append_persisted(&entry);
+    let known = entries(&mut store).entry(hash.to_string()).or_default();
+    known.retain(|e| e.channel != channel);
+    known.push(entry);
+}
+
+/// Ergänzt eine Upload-Form um den früheren Upload
+pub fn flag(form: crate::multipart::Form, earlier: &DedupEntry) -> serde_json::Result<crate::multipart::Form> {
+    Ok(form.text(DUPLICATE_FIELD, serde_json::to_string(earlier)?))
+}
+
+fn entries(store: &mut Store) -> &mut HashMap<String, Vec<DedupEntry>> {
+    let retention_days = store.config.retention_days;
+    store.entries.get_or_insert_with(|| load_persisted(retention_days))
+}
+
+fn dedup_path() -> PathBuf {
+    settings::data_dir().join("dedup.jsonl")
+}
+
+/// Lädt die gespeicherten Hashes und kürzt die Datei um abgelaufene Einträge
+fn load_persisted(retention_days: u32) -> HashMap<String, Vec<DedupEntry>> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days as i64);
+    let mut entries: HashMap<String, Vec<DedupEntry>> = HashMap::new();
+    let mut total = 0;
+    for entry in std::fs::read_to_string(dedup_path())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<DedupEntry>(line).ok())
+    {
+        total += 1;
+        let current = chrono::DateTime::parse_from_rfc3339(&entry.recorded_at).is_ok_and(|t| t > cutoff);
+        if current {
+            let known = entries.entry(entry.hash.clone()).or_default();
+            known.retain(|e| e.channel != entry.channel);
+            known.push(entry);
+        }
+    }
+
+    let kept: usize = entries.values().map(Vec::len).sum();
+    if kept < total {
+        rewrite_persisted(&entries);
+    }
+    entries
+}
+
+fn append_persisted(entry: &DedupEntry) {
+    let Ok(line) = serde_json::to_string(entry) else {
+        return;
+    };
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(dedup_path()) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+fn rewrite_persisted(entries: &HashMap<String, Vec<DedupEntry>>) {
+    let content: String = entries
+        .values()
+        .flatten()
+        .filter_map(|e| serde_json::to_string(e).ok())
+        .map(|line| line + "\n")
+        .collect();
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    let _ = std::fs::write(dedup_path(), content);
+}
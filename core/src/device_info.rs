@@ -0,0 +1,136 @@
+// Geräteinfos - Ergänzt entdeckte Scanner um Firmware, Icon und Admin-Seite
+// Die ScannerCapabilities werden dafür einmal je Gerät geladen, Icons im App-Datenverzeichnis gecacht
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::discovery::DiscoveredScanner;
+use crate::firmware::{self, FirmwareInfo};
+use crate::push_scan::extract_tag;
+use crate::settings;
+
+/// Ergebnis der Abfrage eines Geräts
+struct DeviceInfo {
+    firmware: FirmwareInfo,
+    admin_url: Option<String>,
+    icon_url: Option<String>,
+    icon: Option<String>,
+}
+
+/// Fragt alle Scanner parallel ab und trägt die Infos ein
+pub async fn collect(scanners: &mut [DiscoveredScanner]) {
+    let tasks: Vec<_> = scanners
+        .iter()
+        .map(|scanner| {
+            let scanner = scanner.clone();
+            tokio::spawn(async move { fetch(&scanner).await })
+        })
+        .collect();
+
+    for (scanner, task) in scanners.iter_mut().zip(tasks) {
+        if let Ok(info) = task.await {
+            if let Some(version) = &info.firmware.version {
+                println!("📋 Firmware {}: {}", scanner.name, version);
+            }
+            scanner.firmware = Some(info.firmware);
+            scanner.admin_url = info.admin_url;
+            scanner.icon_url = info.icon_url;
+            scanner.icon = info.icon;
+        }
+    }
+}
+
+async fn fetch(scanner: &DiscoveredScanner) -> DeviceInfo {
+    let capabilities = fetch_capabilities(scanner).await;
+    let firmware = firmware::fetch(scanner, capabilities.as_deref()).await;
+
+    let from_capabilities = |tag: &str| {
+        capabilities
+            .as_deref()
+            .and_then(|xml| extract_tag(xml, tag))
+            .filter(|v| v.starts_with("http"))
+    };
+    let admin_url = from_capabilities("AdminURI").or_else(|| scanner.admin_url.clone());
+    let icon_url = from_capabilities("IconURI").or_else(|| scanner.icon_url.clone());
+
+    let icon = match &icon_url {
+        Some(url) => load_icon(&scanner.id, url).await,
+        None => None,
+    };
+
+    DeviceInfo {
+        firmware,
+        admin_url,
+        icon_url,
+        icon,
+    }
+}
+
+/// Lädt das ScannerCapabilities-XML
+async fn fetch_capabilities(scanner: &DiscoveredScanner) -> Option<String> {
+    let client = crate::http_client::scanner(Duration::from_secs(3)).ok()?;
+
+    let scheme = if scanner.use_tls || scanner.port == 443 { "https" } else { "http" };
+    let host = if scanner.ip.contains(':') {
+        format!("[{}]", scanner.ip)
+    } else {
+        scanner.ip.clone()
+    };
+    let url = format!("{}://{}:{}/{}/ScannerCapabilities", scheme, host, scanner.port, scanner.rs_path);
+
+    let response = client.get(&url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.text().await.ok()
+}
+
+/// Liefert das Icon als data:-URL (aus dem Cache oder frisch vom Gerät)
+async fn load_icon(scanner_id: &str, url: &str) -> Option<String> {
+    let path = icon_path(scanner_id);
+
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(_) => {
+            let client = crate::http_client::scanner(Duration::from_secs(3)).ok()?;
+            let response = client.get(url).send().await.ok()?;
+            if !response.status().is_success() {
+                return None;
+            }
+            let data = response.bytes().await.ok()?.to_vec();
+            // Keine riesigen Bilder in Payload/Cache übernehmen
+            if data.is_empty() || data.len() > 256 * 1024 {
+                return None;
+            }
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = std::fs::write(&path, &data);
+            data
+        }
+    };
+
+    let mime = match image::guess_format(&data) {
+        Ok(image::ImageFormat::Png) => "image/png",
+        Ok(image::ImageFormat::Jpeg) => "image/jpeg",
+        Ok(image::ImageFormat::Gif) => "image/gif",
+        Ok(image::ImageFormat::Bmp) => "image/bmp",
+        _ => return None,
+    };
+
+    use base64::Engine;
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(&data)
+    ))
+}
+
+/// Cache-Datei je Scanner (ID kann Doppelpunkte/Slashes enthalten)
+fn icon_path(scanner_id: &str) -> PathBuf {
+    let file_name: String = scanner_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    settings::data_dir().join("icons").join(file_name)
+}
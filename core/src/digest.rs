@@ -0,0 +1,134 @@
+// Tageszusammenfassung - Eine Meldung pro Tag statt Einzelmeldungen je Datei
+// Zählt Uploads, Duplikate, Fehler und Quarantäne des Folder-Sync seit der letzten Zusammenfassung
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::settings;
+use crate::rate_limit::RateLimited;
+
+/// Konfiguration der Tageszusammenfassung
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DigestConfig {
+    pub enabled: bool,
+    /// Uhrzeit (lokal, "HH:MM") zu der die Zusammenfassung verschickt wird
+    pub time: String,
+    /// Desktop-Benachrichtigung anzeigen
+    pub notify_desktop: bool,
+    /// Zusammenfassung an DocFlow melden
+    pub report_to_docflow: bool,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: "18:00".to_string(),
+            notify_desktop: true,
+            report_to_docflow: true,
+        }
+    }
+}
+
+/// Zähler seit der letzten Zusammenfassung
+pub struct DigestCounters {
+    pub uploaded: AtomicU64,
+    pub duplicates: AtomicU64,
+    pub failed: AtomicU64,
+    pub quarantined: AtomicU64,
+}
+
+pub static DIGEST: DigestCounters = DigestCounters {
+    uploaded: AtomicU64::new(0),
+    duplicates: AtomicU64::new(0),
+    failed: AtomicU64::new(0),
+    quarantined: AtomicU64::new(0),
+};
+
+/// Zusammenfassung eines Tages
+#[derive(Clone, Debug, Serialize)]
+pub struct DigestSummary {
+    pub date: String,
+    pub uploaded: u64,
+    pub duplicates: u64,
+    pub failed: u64,
+    pub quarantined: u64,
+}
+
+impl DigestSummary {
+    /// Text für die Desktop-Benachrichtigung
+    pub fn message(&self) -> String {
+        let mut parts = vec![
+            format!("{} hochgeladen", self.uploaded),
+            format!("{} Duplikate übersprungen", self.duplicates),
+            format!("{} fehlgeschlagen", self.failed),
+        ];
+        if self.quarantined > 0 {
+            parts.push(format!("{} in Quarantäne", self.quarantined));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Liest die Zähler und setzt sie zurück
+pub fn take_summary() -> DigestSummary {
+    DigestSummary {
+        date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        uploaded: DIGEST.uploaded.swap(0, Ordering::Relaxed),
+        duplicates: DIGEST.duplicates.swap(0, Ordering::Relaxed),
+        failed: DIGEST.failed.swap(0, Ordering::Relaxed),
+        quarantined: DIGEST.quarantined.swap(0, Ordering::Relaxed),
+    }
+}
+
+/// Ob die Zusammenfassung jetzt fällig ist (Uhrzeit erreicht, heute noch nicht verschickt)
+pub fn is_due(config: &DigestConfig) -> bool {
+    if !config.enabled {
+        return false;
+    }
+    let Ok(time) = chrono::NaiveTime::parse_from_str(&config.time, "%H:%M") else {
+        return false;
+    };
+
+    let now = chrono::Local::now();
+    let today = now.format("%Y-%m-%d").to_string();
+    now.time() >= time && last_sent_date().as_deref() != Some(today.as_str())
+}
+
+/// Merkt sich das Versanddatum (überlebt Neustarts, damit nicht doppelt gemeldet wird)
+pub fn mark_sent(summary: &DigestSummary) {
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    let _ = std::fs::write(state_path(), &summary.date);
+}
+
+/// Meldet die Zusammenfassung an DocFlow
+pub async fn report(
+    docflow_url: &str,
+    api_key: &str,
+    summary: &DigestSummary,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = crate::http_client::docflow();
+    let url = format!("{}/api/scanner/bridge/folder-sync-digest", docflow_url);
+
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .json(summary)
+        .timeout(std::time::Duration::from_secs(10))
+        .send_limited()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    Ok(())
+}
+
+fn state_path() -> PathBuf {
+    settings::data_dir().join("digest_last_sent")
+}
+
+fn last_sent_date() -> Option<String> {
+    std::fs::read_to_string(state_path()).ok().map(|s| s.trim().to_string())
+}
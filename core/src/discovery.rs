@@ -0,0 +1,1446 @@
+// Scanner Discovery - Automatische Erkennung von Scannern im Netzwerk
+// Unterstützt: mDNS/Bonjour (eSCL), WSD, SNMP, IP-Range Scan, lokal angeschlossene Scanner (WIA unter Windows, SANE unter Linux, ImageCaptureCore unter macOS)
+
+use futures::stream::{self, StreamExt};
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::timeout;
+
+use crate::device_info;
+use crate::firmware::FirmwareInfo;
+use crate::scanner_sync;
+use crate::snmp::{self, SnmpDevice};
+use crate::virtual_scanner::{self, VirtualScannerConfig};
+use crate::wol;
+use crate::wsd_discovery;
+
+/// Gefundener Scanner
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DiscoveredScanner {
+    pub id: String,
+    pub name: String,
+    pub manufacturer: String,
+    pub model: String,
+    pub ip: String,
+    pub port: u16,
+    pub use_tls: bool,
+    pub protocols: Vec<String>,
+    pub capabilities: ScannerCapabilities,
+    pub discovery_method: String,
+    /// eSCL Resource Path aus mDNS TXT-Record "rs" (z.B. "eSCL", "eSCL2")
+    #[serde(default = "default_rs_path")]
+    pub rs_path: String,
+    /// WSD-Scan-Endpunkt (für Push-Scans per ScanAvailableEvent)
+    #[serde(default)]
+    pub wsd_url: Option<String>,
+    /// Alle gemeldeten Endpoints (konkurrierende mDNS-Services/Adressen), gewählter ist ip/port/use_tls
+    #[serde(default)]
+    pub endpoints: Vec<ScannerEndpoint>,
+    /// Firmware-/Versionsinfos (eSCL oder SNMP)
+    #[serde(default)]
+    pub firmware: Option<FirmwareInfo>,
+    /// Seriennummer (Printer-MIB per SNMP)
+    #[serde(default)]
+    pub serial_number: Option<String>,
+    /// MAC-Adresse aus der ARP-Tabelle (für Wake-on-LAN)
+    #[serde(default)]
+    pub mac_address: Option<String>,
+    /// Standort (mDNS TXT "note" oder manuell gesetzt), z.B. "3. OG Kopierraum"
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Gruppe für die Auswahl in DocFlow, z.B. "Buchhaltung"
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Web-Oberfläche des Geräts (eSCL AdminURI bzw. mDNS "adminurl")
+    #[serde(default)]
+    pub admin_url: Option<String>,
+    /// Geräte-Icon (eSCL IconURI bzw. mDNS "representation")
+    #[serde(default)]
+    pub icon_url: Option<String>,
+    /// Gecachtes Icon als data:-URL (direkt im UI anzeigbar)
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// Manuell gesetzte Bezeichnungen eines Scanners (haben Vorrang vor mDNS)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScannerLabel {
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// Ein erreichbarer Endpoint eines Scanners
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScannerEndpoint {
+    pub ip: String,
+    pub port: u16,
+    pub use_tls: bool,
+    /// Bewertung nach den aktuellen Gewichten (Auswahl und Anzeige)
+    #[serde(default)]
+    pub score: i32,
+}
+
+impl ScannerEndpoint {
+    fn matches(&self, other: &ScannerEndpoint) -> bool {
+        self.ip == other.ip && self.port == other.port && self.use_tls == other.use_tls
+    }
+}
+
+/// Gewichte für die Endpoint-Auswahl (höchste Summe gewinnt)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ScoringWeights {
+    pub tls: i32,
+    pub port_443: i32,
+    pub port_80: i32,
+    pub port_8080: i32,
+    pub ipv4: i32,
+    pub ipv6_ula: i32,
+    pub ipv6_link_local: i32,
+    pub ipv6_public: i32,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self {
+            tls: 20,
+            port_443: 15,
+            port_80: 10,
+            port_8080: 5,
+            ipv4: 10,
+            ipv6_ula: 5,
+            ipv6_link_local: -5,
+            ipv6_public: -3,
+        }
+    }
+}
+
+/// Discovery-Einstellungen
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    #[serde(default)]
+    pub weights: ScoringWeights,
+    /// Vom Benutzer festgelegte Endpoints je Scanner-ID (haben Vorrang vor der Bewertung)
+    #[serde(default)]
+    pub pinned_endpoints: HashMap<String, ScannerEndpoint>,
+    /// Standort/Gruppe je Scanner-ID
+    #[serde(default)]
+    pub labels: HashMap<String, ScannerLabel>,
+    /// Importierte Hosts (Druckserver/CSV), werden bei jeder Discovery direkt geprüft
+    #[serde(default)]
+    pub static_hosts: Vec<StaticHost>,
+    /// Virtueller Demo-Scanner (ohne Hardware)
+    #[serde(default)]
+    pub virtual_scanner: VirtualScannerConfig,
+    /// Zuordnung Scanner-ID → Verbindungsprofil (DocFlow-URL), nicht zugeordnete Scanner sieht jede Instanz
+    #[serde(default)]
+    pub assignments: HashMap<String, String>,
+    /// Ausgeblendete Scanner-IDs (bleiben auch nach einer neuen Suche verborgen)
+    #[serde(default)]
+    pub hidden: BTreeSet<String>,
+    /// Bereiche und Ports für SNMP-Sweep und IP-Range-Scan
+    #[serde(default)]
+    pub ip_scan: IpScanConfig,
+}
+
+impl DiscoveryConfig {
+    /// Ob ein Scanner für die Verbindung zu dieser DocFlow-Instanz freigegeben ist
+    pub fn is_assigned_to(&self, scanner_id: &str, docflow_url: &str) -> bool {
+        self.assignments
+            .get(scanner_id)
+            .is_none_or(|profile| connection_profile(profile) == connection_profile(docflow_url))
+    }
+
+    /// Scanner, die diese DocFlow-Instanz sehen darf
+    pub fn visible_scanners(&self, scanners: &[DiscoveredScanner], docflow_url: &str) -> Vec<DiscoveredScanner> {
+        scanners
+            .iter()
+            .filter(|s| self.is_assigned_to(&s.id, docflow_url))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Bereiche für SNMP-Sweep und IP-Range-Scan (z.B. geroutete Scanner-VLANs)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IpScanConfig {
+    /// IPv4-Bereiche in CIDR-Notation, z.B. "10.20.30.0/24" (leer = /24 der lokalen IP)
+    #[serde(default)]
+    pub ranges: Vec<String>,
+    /// Ports für die eSCL-Prüfung (leer = 80, 443, 8080, 9100)
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    /// SNMP-Sweep bei der Discovery (aus = keine SNMP-Anfragen ins Netz)
+    #[serde(default = "default_snmp")]
+    pub snmp: bool,
+    /// Community für den SNMP-Sweep (None = "public")
+    #[serde(default)]
+    pub snmp_community: Option<String>,
+}
+
+fn default_snmp() -> bool {
+    true
+}
+
+impl Default for IpScanConfig {
+    fn default() -> Self {
+        Self {
+            ranges: Vec::new(),
+            ports: Vec::new(),
+            snmp: default_snmp(),
+            snmp_community: None,
+        }
+    }
+}
+
+impl IpScanConfig {
+    /// Prüft Bereiche und Ports (Meldung für das UI)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ports.contains(&0) {
+            return Err("Port 0 ist ungültig".to_string());
+        }
+        if self.ranges.is_empty() {
+            return Ok(());
+        }
+        let hosts = self.hosts().map_err(|e| e.to_string())?;
+        if hosts.len() > MAX_SCAN_HOSTS {
+            return Err(format!(
+                "IP-Bereiche umfassen {} Adressen (höchstens {} möglich)",
+                hosts.len(),
+                MAX_SCAN_HOSTS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Zu prüfende Adressen: alle konfigurierten Bereiche, sonst das /24 der lokalen IP
+    pub fn hosts(&self) -> Result<Vec<Ipv4Addr>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.ranges.is_empty() {
+            let subnet = get_subnet(&local_ip_address::local_ip()?);
+            return Ok((1..=254).filter_map(|i| format!("{}.{}", subnet, i).parse().ok()).collect());
+        }
+        let mut hosts = BTreeSet::new();
+        for range in &self.ranges {
+            hosts.extend(parse_cidr(range)?);
+        }
+        Ok(hosts.into_iter().collect())
+    }
+
+    /// Community für den SNMP-Sweep
+    pub fn snmp_community(&self) -> &str {
+        self.snmp_community
+            .as_deref()
+            .filter(|c| !c.trim().is_empty())
+            .unwrap_or(SNMP_COMMUNITY)
+    }
+
+    /// Ports für die eSCL-Prüfung
+    pub fn ports(&self) -> Vec<u16> {
+        if self.ports.is_empty() {
+            return DEFAULT_SCAN_PORTS.to_vec();
+        }
+        let mut ports = self.ports.clone();
+        ports.sort_unstable();
+        ports.dedup();
+        ports
+    }
+}
+
+/// Adressen eines IPv4-Bereichs ("10.20.30.0/24", ohne Präfix = einzelne Adresse)
+/// Netz- und Broadcast-Adresse entfallen (außer bei /31 und /32)
+pub fn parse_cidr(range: &str) -> Result<Vec<Ipv4Addr>, String> {
+    let range = range.trim();
+    let (address, prefix) = match range.split_once('/') {
+        Some((address, prefix)) => (address, prefix.trim().parse::<u32>().ok().filter(|p| *p <= 32)),
+        None => (range, Some(32)),
+    };
+    let address: Ipv4Addr = address
+        .trim()
+        .parse()
+        .map_err(|_| format!("Ungültiger IP-Bereich: {}", range))?;
+    let prefix = prefix.ok_or_else(|| format!("Ungültiges Präfix in {}", range))?;
+
+    let size = 1u64 << (32 - prefix);
+    if size > MAX_SCAN_HOSTS as u64 {
+        return Err(format!("IP-Bereich {} ist zu groß (höchstens /20)", range));
+    }
+    let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+    let network = u32::from(address) & mask;
+    let hosts = (0..size as u32).map(|offset| Ipv4Addr::from(network + offset));
+    Ok(if prefix <= 30 {
+        hosts.skip(1).take(size as usize - 2).collect()
+    } else {
+        hosts.collect()
+    })
+}
+
+/// Verbindungsprofil einer DocFlow-URL (ohne abschließenden Slash, Groß-/Kleinschreibung egal)
+pub fn connection_profile(docflow_url: &str) -> String {
+    docflow_url.trim().trim_end_matches('/').to_lowercase()
+}
+
+/// Scanner mit zuletzt bekannter Erreichbarkeit (für die Liste ohne neue Suche)
+#[derive(Clone, Debug, Serialize)]
+pub struct KnownScanner {
+    #[serde(flatten)]
+    pub scanner: DiscoveredScanner,
+    /// Bei der letzten Suche, Statusabfrage oder Wake-on-LAN-Prüfung erreichbar
+    pub online: bool,
+    pub last_seen: Option<String>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Reachability {
+    online: bool,
+    last_seen: Option<DateTime<Utc>>,
+}
+
+/// Erreichbarkeit je Scanner-ID
+static REACHABILITY: Mutex<BTreeMap<String, Reachability>> = Mutex::new(BTreeMap::new());
+
+/// Merkt sich das Ergebnis einer Verbindung zum Scanner
+/// Ein Wechsel zwischen online und offline wird an DocFlow gemeldet (scanner_sync)
+pub fn mark_reachable(scanner_id: &str, online: bool) {
+    let mut reachability = REACHABILITY.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = reachability.entry(scanner_id.to_string()).or_default();
+    let changed = entry.online != online;
+    entry.online = online;
+    if online {
+        entry.last_seen = Some(Utc::now());
+    }
+    drop(reachability);
+    if changed {
+        scanner_sync::mark_changed();
+    }
+}
+
+/// Merkt sich das Ergebnis einer Statusabfrage bzw. eines Scans
+/// Nur Verbindungsfehler gelten als offline - Fehlerantworten kommen vom erreichbaren Gerät
+pub fn mark_result<T>(scanner_id: &str, result: &Result<T, Box<dyn std::error::Error + Send + Sync>>) {
+    match result {
+        Ok(_) => mark_reachable(scanner_id, true),
+        Err(e) if is_connection_error(e.as_ref()) => mark_reachable(scanner_id, false),
+        Err(_) => {}
+    }
+}
+
+/// HTTP-Verbindungsaufbau fehlgeschlagen oder Zeitüberschreitung (auch als Ursache eines anderen Fehlers)
+pub fn is_connection_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(error) = current {
+        if let Some(e) = error.downcast_ref::<reqwest::Error>() {
+            if e.is_connect() || e.is_timeout() {
+                return true;
+            }
+        }
+        current = error.source();
+    }
+    false
+}
+
+/// Nach einer Suche: gefundene Scanner sind erreichbar, alle übrigen bekannten nicht mehr
+fn mark_found(scanners: &[DiscoveredScanner]) {
+    let mut reachability = REACHABILITY.lock().unwrap_or_else(|e| e.into_inner());
+    let mut changed = false;
+    for (id, entry) in reachability.iter_mut() {
+        if entry.online && !scanners.iter().any(|s| &s.id == id) {
+            entry.online = false;
+            changed = true;
+        }
+    }
+    for scanner in scanners {
+        let entry = reachability.entry(scanner.id.clone()).or_default();
+        changed |= !entry.online;
+        entry.online = true;
+        entry.last_seen = Some(Utc::now());
+    }
+    drop(reachability);
+    if changed {
+        scanner_sync::mark_changed();
+    }
+}
+
+/// Zuletzt bekannte Erreichbarkeit eines Scanners (virtuelle Scanner sind immer erreichbar)
+pub fn is_online(scanner: &DiscoveredScanner) -> bool {
+    let reachability = REACHABILITY.lock().unwrap_or_else(|e| e.into_inner());
+    reachability.get(&scanner.id).is_some_and(|r| r.online) || virtual_scanner::is_virtual(scanner)
+}
+
+/// Ergänzt die gespeicherte Scanner-Liste um die bekannte Erreichbarkeit (ohne Netzwerkzugriff)
+pub fn with_reachability(scanners: &[DiscoveredScanner]) -> Vec<KnownScanner> {
+    let reachability = REACHABILITY.lock().unwrap_or_else(|e| e.into_inner());
+    scanners
+        .iter()
+        .map(|scanner| {
+            let known = reachability.get(&scanner.id).copied().unwrap_or_default();
+            KnownScanner {
+                scanner: scanner.clone(),
+                online: known.online || virtual_scanner::is_virtual(scanner),
+                last_seen: known.last_seen.map(|t| t.to_rfc3339()),
+            }
+        })
+        .collect()
+}
+
+/// Bekannter Scanner-Host aus einem Import
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct StaticHost {
+    pub host: String,
+    /// None = Standard-Ports 80 und 443 prüfen
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+fn default_rs_path() -> String {
+    "eSCL".to_string()
+}
+
+/// Scanner-Fähigkeiten
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScannerCapabilities {
+    pub duplex: bool,
+    pub adf: bool,
+    pub flatbed: bool,
+    pub max_resolution: u32,
+    pub color_modes: Vec<String>,
+    pub formats: Vec<String>,
+    /// Fähigkeiten nicht abfragbar (z.B. ImageCaptureCore vor dem Öffnen einer Sitzung) - Jobs werden nicht dagegen geprüft
+    #[serde(default)]
+    pub unknown: bool,
+}
+
+/// Wartezeit auf WS-Discovery-Antworten
+const WSD_PROBE_WAIT: Duration = Duration::from_secs(3);
+
+/// Wartezeit auf SNMP-Antworten beim Sweep
+const SNMP_SWEEP_WAIT: Duration = Duration::from_secs(2);
+
+/// Standard-Community für den SNMP-Sweep (Lesezugriff, Werkseinstellung der meisten Geräte)
+const SNMP_COMMUNITY: &str = "public";
+
+/// Ports für die eSCL-Prüfung beim IP-Range-Scan (ohne eigene Konfiguration)
+const DEFAULT_SCAN_PORTS: &[u16] = &[80, 443, 8080, 9100];
+
+/// Höchstzahl an Adressen über alle IP-Bereiche (entspricht einem /20)
+pub const MAX_SCAN_HOSTS: usize = 4096;
+
+/// Gleichzeitige eSCL-Prüfungen beim IP-Range-Scan
+const IP_SCAN_CONCURRENCY: usize = 128;
+
+/// Service-Typen für mDNS Discovery (Reihenfolge = Priorität: eSCL bevorzugt über IPP)
+const MDNS_SERVICE_TYPES: &[&str] = &[
+    "_uscan._tcp.local.",   // eSCL Scanner (HTTP) — höchste Priorität
+    "_uscans._tcp.local.",  // eSCL Scanner (HTTPS)
+    "_scanner._tcp.local.", // Generic Scanner
+];
+
+/// Führt alle Discovery-Methoden aus
+/// mdns_browse: Suchdauer je mDNS-Service-Typ
+pub async fn discover_all(
+    config: &DiscoveryConfig,
+    mdns_browse: Duration,
+) -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut all_scanners = HashMap::new();
+
+    // 1. mDNS Discovery (primär)
+    if let Ok(mdns_scanners) = discover_mdns(&config.weights, mdns_browse).await {
+        for scanner in mdns_scanners {
+            all_scanners.insert(scanner.ip.clone(), scanner);
+        }
+    }
+
+    // 2. WS-Discovery (ältere Geräte ohne _uscan._tcp)
+    match wsd_discovery::discover(WSD_PROBE_WAIT).await {
+        Ok(wsd_scanners) => {
+            for scanner in wsd_scanners {
+                match all_scanners.get_mut(&scanner.ip) {
+                    // Per mDNS bekannt: nur den WSD-Endpunkt für Push-Scans übernehmen
+                    Some(existing) => {
+                        if existing.wsd_url.is_none() {
+                            existing.wsd_url = scanner.wsd_url;
+                        }
+                        if !existing.protocols.iter().any(|p| p == "wsd") {
+                            existing.protocols.push("wsd".to_string());
+                        }
+                    }
+                    // Reine WSD-Geräte (ohne eSCL) mit protocols ["wsd"] melden
+                    None => {
+                        let scanner = with_escl_endpoint(scanner).await;
+                        all_scanners.insert(scanner.ip.clone(), scanner);
+                    }
+                }
+            }
+        }
+        Err(e) => println!("⚠ WS-Discovery fehlgeschlagen: {}", e),
+    }
+
+    // 3. SNMP-Sweep (MFPs, deren mDNS nicht über VLAN-Grenzen reicht, beantworten meist SNMP)
+    let snmp_devices = match discover_snmp(&config.ip_scan).await {
+        Ok(devices) => devices,
+        Err(e) => {
+            println!("⚠ SNMP-Sweep fehlgeschlagen: {}", e);
+            Vec::new()
+        }
+    };
+    let snmp_hosts: Vec<StaticHost> = snmp_devices
+        .iter()
+        .filter(|device| !all_scanners.contains_key(&device.ip))
+        .map(|device| StaticHost { host: device.ip.clone(), port: None })
+        .collect();
+    for mut scanner in probe_hosts(&snmp_hosts).await {
+        scanner.discovery_method = "snmp".to_string();
+        all_scanners.entry(scanner.ip.clone()).or_insert(scanner);
+    }
+
+    // 4. Importierte Hosts direkt prüfen
+    for scanner in probe_hosts(&config.static_hosts).await {
+        all_scanners.entry(scanner.ip.clone()).or_insert(scanner);
+    }
+
+    // 5. IP-Range Scan (konfigurierte Bereiche immer, sonst nur als Fallback wenn nichts gefunden)
+    if !config.ip_scan.ranges.is_empty() || all_scanners.is_empty() {
+        if let Ok(ip_scanners) = discover_ip_range(&config.ip_scan).await {
+            for scanner in ip_scanners {
+                all_scanners.entry(scanner.ip.clone()).or_insert(scanner);
+            }
+        }
+    }
+
+    // Hersteller, Modell und Seriennummer aus SNMP statt "Scanner at <IP>"
+    for device in &snmp_devices {
+        if let Some(scanner) = all_scanners.get_mut(&device.ip) {
+            apply_snmp(scanner, device);
+        }
+    }
+
+    let mut scanners: Vec<DiscoveredScanner> = all_scanners.into_values().collect();
+    scanners.retain(|s| !config.hidden.contains(&s.id));
+    apply_scanner_config(&mut scanners, config);
+
+    // 6. Firmware-Versionen, Icons und Admin-Seiten abfragen (für Geräte-Kacheln in DocFlow)
+    device_info::collect(&mut scanners).await;
+
+    // 7. MAC-Adressen merken (Scanner sind jetzt in der ARP-Tabelle)
+    wol::fill_mac_addresses(&mut scanners).await;
+
+    // 8. Lokal angeschlossene USB-Scanner (ohne Netzwerk-Geräteabfragen)
+    #[cfg(target_os = "windows")]
+    match native::discover_wia().await {
+        Ok(mut local) => {
+            local.retain(|s| !config.hidden.contains(&s.id));
+            apply_scanner_config(&mut local, config);
+            scanners.append(&mut local);
+        }
+        Err(e) => println!("⚠ WIA-Discovery fehlgeschlagen: {}", e),
+    }
+    #[cfg(target_os = "linux")]
+    match native::discover_sane().await {
+        Ok(mut local) => {
+            local.retain(|s| !config.hidden.contains(&s.id));
+            apply_scanner_config(&mut local, config);
+            scanners.append(&mut local);
+        }
+        Err(e) => println!("⚠ SANE-Discovery fehlgeschlagen: {}", e),
+    }
+    #[cfg(target_os = "macos")]
+    match native::discover_image_capture().await {
+        Ok(mut local) => {
+            local.retain(|s| !config.hidden.contains(&s.id));
+            apply_scanner_config(&mut local, config);
+            scanners.append(&mut local);
+        }
+        Err(e) => println!("⚠ ImageCapture-Discovery fehlgeschlagen: {}", e),
+    }
+
+    // 9. Virtueller Demo-Scanner (falls aktiviert, ohne Geräteabfragen)
+    if config.virtual_scanner.enabled {
+        let mut demo = vec![virtual_scanner::scanner(&config.virtual_scanner)];
+        apply_scanner_config(&mut demo, config);
+        scanners.append(&mut demo);
+    }
+
+    mark_found(&scanners);
+    Ok(scanners)
+}
+
+/// Bewertet alle Endpoints neu, setzt festgelegte Endpoints und Bezeichnungen ein
+/// Ohne Festlegung wird der am besten bewertete Endpoint gewählt (z.B. nach dem Aufheben einer Festlegung)
+pub fn apply_scanner_config(scanners: &mut [DiscoveredScanner], config: &DiscoveryConfig) {
+    for scanner in scanners.iter_mut() {
+        if let Some(label) = config.labels.get(&scanner.id) {
+            if label.location.is_some() {
+                scanner.location = label.location.clone();
+            }
+            if label.group.is_some() {
+                scanner.group = label.group.clone();
+            }
+        }
+
+        if scanner.endpoints.is_empty() {
+            scanner.endpoints.push(ScannerEndpoint {
+                ip: scanner.ip.clone(),
+                port: scanner.port,
+                use_tls: scanner.use_tls,
+                score: 0,
+            });
+        }
+
+        let pinned = config.pinned_endpoints.get(&scanner.id);
+        if let Some(pinned) = pinned {
+            if !scanner.endpoints.iter().any(|e| e.matches(pinned)) {
+                scanner.endpoints.push(pinned.clone());
+            }
+        }
+
+        for endpoint in scanner.endpoints.iter_mut() {
+            endpoint.score = score_endpoint(&endpoint.ip, endpoint.port, endpoint.use_tls, &config.weights);
+        }
+
+        // Festgelegter Endpoint, sonst der am besten bewertete (bei Gleichstand bleibt der aktuelle)
+        let current = ScannerEndpoint {
+            ip: scanner.ip.clone(),
+            port: scanner.port,
+            use_tls: scanner.use_tls,
+            score: 0,
+        };
+        let current_score = scanner.endpoints.iter().find(|e| e.matches(&current)).map(|e| e.score);
+        let selected = match pinned {
+            Some(pinned) => Some(pinned),
+            None => scanner
+                .endpoints
+                .iter()
+                .max_by_key(|e| e.score)
+                .filter(|best| current_score.is_none_or(|score| best.score > score)),
+        };
+        let Some(selected) = selected.cloned() else {
+            continue;
+        };
+        if !selected.matches(&current) {
+            match pinned {
+                Some(_) => println!("📌 Endpoint für {} festgelegt: {}:{}", scanner.name, selected.ip, selected.port),
+                None => println!("🔀 Endpoint für {} automatisch gewählt: {}:{}", scanner.name, selected.ip, selected.port),
+            }
+        }
+        scanner.ip = selected.ip;
+        scanner.port = selected.port;
+        scanner.use_tls = selected.use_tls;
+    }
+}
+
+/// mDNS/Bonjour Discovery für eSCL-Scanner
+async fn discover_mdns(weights: &ScoringWeights, browse: Duration) -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+    let mdns = ServiceDaemon::new()?;
+    let mut scanners: HashMap<String, DiscoveredScanner> = HashMap::new();
+    // Merken welche Scanner via eSCL (nicht IPP) gefunden wurden
+    let mut escl_ips: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    // Receiver für alle Service-Typen
+    for service_type in MDNS_SERVICE_TYPES {
+        let is_escl = service_type.starts_with("_uscan");
+        let is_escl_tls = *service_type == "_uscans._tcp.local.";
+        let receiver = mdns.browse(service_type)?;
+
+        // Discovery-Zeit je Service-Typ (Standard 5 Sekunden)
+        let discovery_task = async {
+            while let Ok(event) = receiver.recv_async().await {
+                if let ServiceEvent::ServiceResolved(info) = event {
+                    if let Some(mut scanner) = parse_mdns_service(&info) {
+                        if is_escl_tls {
+                            scanner.use_tls = true;
+                            for endpoint in scanner.endpoints.iter_mut() {
+                                endpoint.use_tls = true;
+                            }
+                        }
+                        let ip = scanner.ip.clone();
+                        if is_escl {
+                            // eSCL-Fund: immer eintragen
+                            escl_ips.insert(ip.clone());
+                        } else if escl_ips.contains(&ip) {
+                            // Generic nur verwenden, wenn kein eSCL-Fund für diese IP existiert
+                            continue;
+                        }
+
+                        let key = scanner.id.clone();
+                        match scanners.get_mut(&key) {
+                            Some(existing) => {
+                                // Alle Endpoints merken (für die Auswahl in der UI)
+                                let mut endpoints = existing.endpoints.clone();
+                                for endpoint in &scanner.endpoints {
+                                    if !endpoints.iter().any(|e| e.matches(endpoint)) {
+                                        endpoints.push(endpoint.clone());
+                                    }
+                                }
+                                if prefer_scanner(&scanner, existing, weights) {
+                                    *existing = scanner;
+                                }
+                                existing.endpoints = endpoints;
+                            }
+                            None => {
+                                scanners.insert(key, scanner);
+                            }
+                        }
+                    }
+                }
+            }
+        };
+
+        let _ = timeout(browse, discovery_task).await;
+    }
+
+    mdns.shutdown()?;
+    Ok(scanners.into_values().collect())
+}
+
+/// Wählt die beste IP-Adresse aus einer mDNS-Adressliste:
+/// IPv4 > ULA IPv6 (fd/fc) > Global IPv6 > Link-Local IPv6
+fn pick_best_address(addresses: &[&IpAddr]) -> String {
+    // 1. Priorität: IPv4
+    if let Some(addr) = addresses.iter().find(|a| a.is_ipv4()) {
+        return addr.to_string();
+    }
+
+    // 2. Priorität: ULA IPv6 (fd00::/8, fc00::/8) — immer lokal erreichbar
+    if let Some(addr) = addresses.iter().find(|a| {
+        let s = a.to_string().to_lowercase();
+        s.starts_with("fd") || s.starts_with("fc")
+    }) {
+        return addr.to_string();
+    }
+
+    // 3. Priorität: Jede nicht-link-local IPv6
+    if let Some(addr) = addresses.iter().find(|a| {
+        let s = a.to_string().to_lowercase();
+        a.is_ipv6() && !s.starts_with("fe80")
+    }) {
+        return addr.to_string();
+    }
+
+    // 4. Fallback: Erste Adresse
+    addresses[0].to_string()
+}
+
+/// Bevorzugt stabilere/scan-fähigere Scanner-Endpoints
+fn prefer_scanner(candidate: &DiscoveredScanner, current: &DiscoveredScanner, weights: &ScoringWeights) -> bool {
+    score_scanner(candidate, weights) > score_scanner(current, weights)
+}
+
+fn score_scanner(scanner: &DiscoveredScanner, weights: &ScoringWeights) -> i32 {
+    score_endpoint(&scanner.ip, scanner.port, scanner.use_tls, weights)
+}
+
+fn score_endpoint(ip: &str, port: u16, use_tls: bool, weights: &ScoringWeights) -> i32 {
+    let mut score = 0;
+
+    // TLS bevorzugen (Standard - per Gewicht abschaltbar, z.B. bei defektem HTTPS)
+    if use_tls {
+        score += weights.tls;
+    }
+
+    // Port-Priorität: 443 > 80 > 8080 > sonst
+    score += match port {
+        443 => weights.port_443,
+        80 => weights.port_80,
+        8080 => weights.port_8080,
+        _ => 0,
+    };
+
+    // IPv4 stark bevorzugen, ULA IPv6 okay, öffentlich/link-local abwerten
+    if !ip.contains(':') {
+        score += weights.ipv4;  // IPv4 — immer lokal erreichbar
+    } else {
+        let ip_lower = ip.to_lowercase();
+        if ip_lower.starts_with("fd") || ip_lower.starts_with("fc") {
+            score += weights.ipv6_ula;  // ULA — lokal erreichbar
+        } else if ip_lower.starts_with("fe80:") {
+            score += weights.ipv6_link_local;  // Link-local — oft problematisch (Scope-ID nötig)
+        } else {
+            score += weights.ipv6_public;  // Öffentliche IPv6 — vom LAN evtl. nicht erreichbar!
+        }
+    }
+
+    score
+}
+
+/// Parst mDNS ServiceInfo zu DiscoveredScanner
+fn parse_mdns_service(info: &mdns_sd::ServiceInfo) -> Option<DiscoveredScanner> {
+    let addresses: Vec<_> = info.get_addresses().iter().collect();
+    if addresses.is_empty() {
+        return None;
+    }
+
+    // Beste IP-Adresse wählen: IPv4 > ULA IPv6 > Link-Local IPv6 > Public IPv6
+    let ip = pick_best_address(&addresses);
+    println!("📡 mDNS Adressen für {}: {:?} → gewählt: {}",
+        info.get_fullname(),
+        addresses.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+        ip);
+    let port = info.get_port();
+
+    // TXT-Records parsen
+    let properties = info.get_properties();
+    let model = properties
+        .get("ty")
+        .or_else(|| properties.get("product"))
+        .map(|v| v.val_str().to_string())
+        .unwrap_or_else(|| info.get_fullname().to_string());
+
+    let uuid = properties
+        .get("uuid")
+        .map(|v| v.val_str().to_string())
+        .unwrap_or_else(|| format!("{}:{}", ip, port));
+
+    let manufacturer = extract_manufacturer(&model);
+
+    // Web-Oberfläche und Icon (werden ggf. aus den Capabilities ergänzt)
+    let admin_url = properties
+        .get("adminurl")
+        .map(|v| v.val_str().to_string())
+        .filter(|v| v.starts_with("http"));
+    let icon_url = properties
+        .get("representation")
+        .map(|v| v.val_str().to_string())
+        .filter(|v| v.starts_with("http"));
+
+    // Standort aus TXT "note" (vom Admin am Gerät gepflegt)
+    let location = properties
+        .get("note")
+        .map(|v| v.val_str().trim().to_string())
+        .filter(|v| !v.is_empty());
+
+    // Capabilities aus TXT-Records
+    let duplex = properties
+        .get("duplex")
+        .map(|v| v.val_str().to_lowercase())
+        .map(|v| v == "t" || v == "true" || v == "1")
+        .unwrap_or(false);
+
+    let input_sources = properties
+        .get("is")
+        .map(|v| v.val_str().to_lowercase())
+        .unwrap_or_default();
+    let adf = input_sources.contains("adf") || input_sources.contains("feeder");
+    let flatbed = input_sources.contains("platen") || input_sources.is_empty();
+
+    // eSCL Resource Path (z.B. "eSCL", "eSCL2") — kritisch für korrekte URL
+    let rs_path = properties
+        .get("rs")
+        .map(|v| {
+            let val = v.val_str().to_string();
+            // Führende Slashes entfernen
+            val.trim_start_matches('/').to_string()
+        })
+        .unwrap_or_else(|| "eSCL".to_string());
+
+    println!("📡 Scanner entdeckt: {} @ {}:{} rs={}", model, ip, port, rs_path);
+
+    Some(DiscoveredScanner {
+        id: uuid,
+        name: model.clone(),
+        manufacturer,
+        model,
+        ip,
+        port,
+        use_tls: false, // Wird ggf. vom Caller auf true gesetzt (_uscans._tcp)
+        protocols: vec!["escl".to_string()],
+        capabilities: ScannerCapabilities {
+            duplex,
+            adf,
+            flatbed,
+            max_resolution: 600,
+            color_modes: vec!["RGB24".to_string(), "Grayscale8".to_string()],
+            formats: vec!["application/pdf".to_string(), "image/jpeg".to_string()],
+            unknown: false,
+        },
+        discovery_method: "mdns".to_string(),
+        rs_path,
+        wsd_url: None,
+        endpoints: addresses
+            .iter()
+            .map(|addr| ScannerEndpoint {
+                ip: addr.to_string(),
+                port,
+                use_tls: false,
+                score: 0,
+            })
+            .collect(),
+        firmware: None,
+        serial_number: None,
+        mac_address: None,
+        location,
+        group: None,
+        admin_url,
+        icon_url,
+        icon: None,
+    })
+}
+
+/// IP-Range Scan für Scanner ohne mDNS
+async fn discover_ip_range(
+    config: &IpScanConfig,
+) -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+    let ports = config.ports();
+    let candidates: Vec<(String, u16)> = config
+        .hosts()?
+        .into_iter()
+        .flat_map(|ip| ports.iter().map(move |port| (ip.to_string(), *port)))
+        .collect();
+
+    // Begrenzt parallel prüfen (größere Bereiche würden sonst zu viele Verbindungen gleichzeitig öffnen)
+    let scanners = stream::iter(candidates)
+        .map(|(ip, port)| async move { probe_escl_endpoint(&ip, port).await })
+        .buffer_unordered(IP_SCAN_CONCURRENCY)
+        .filter_map(|scanner| async move { scanner })
+        .collect()
+        .await;
+
+    Ok(scanners)
+}
+
+/// SNMP-Sweep über die Bereiche des IP-Range-Scans (abgeschaltet = keine Geräte)
+async fn discover_snmp(config: &IpScanConfig) -> Result<Vec<SnmpDevice>, Box<dyn std::error::Error + Send + Sync>> {
+    if !config.snmp {
+        return Ok(Vec::new());
+    }
+    let targets: Vec<SocketAddr> = config
+        .hosts()?
+        .into_iter()
+        .map(|ip| SocketAddr::from((ip, snmp::PORT)))
+        .collect();
+    snmp::sweep(&targets, config.snmp_community(), SNMP_SWEEP_WAIT).await
+}
+
+/// Übernimmt die SNMP-Angaben: Seriennummer immer, Hersteller/Modell nur bei unbekanntem Hersteller
+pub fn apply_snmp(scanner: &mut DiscoveredScanner, device: &SnmpDevice) {
+    if scanner.serial_number.is_none() {
+        scanner.serial_number = device.serial_number.clone();
+    }
+    if scanner.manufacturer != "Unknown" {
+        return;
+    }
+    let Some(model) = device.model_name() else {
+        return;
+    };
+    let manufacturer = extract_manufacturer(&format!("{} {}", model, device.description.as_deref().unwrap_or_default()));
+    println!("📡 SNMP: {} ist {} ({})", scanner.ip, model, manufacturer);
+    scanner.name = model.clone();
+    scanner.model = model;
+    scanner.manufacturer = manufacturer;
+}
+
+/// Liest eine Host-Liste als JSON (["host:port", {"host": .., "port": ..}]) oder CSV (host[,port] je Zeile)
+pub fn parse_host_list(content: &str) -> Result<Vec<StaticHost>, String> {
+    let content = content.trim();
+    let mut hosts = Vec::new();
+
+    if content.starts_with('[') {
+        let entries: Vec<serde_json::Value> =
+            serde_json::from_str(content).map_err(|e| format!("Ungültiges JSON: {}", e))?;
+        for entry in entries {
+            let host = match entry {
+                serde_json::Value::String(text) => parse_host_entry(&text, None),
+                serde_json::Value::Object(map) => {
+                    let host = map
+                        .get("host")
+                        .or_else(|| map.get("ip"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default();
+                    let port = map.get("port").and_then(|v| v.as_u64()).map(|p| p as u16);
+                    parse_host_entry(host, port)
+                }
+                _ => None,
+            };
+            hosts.extend(host);
+        }
+    } else {
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split([',', ';', '\t']).map(|f| f.trim().trim_matches('"')).collect();
+            let port = fields.get(1).and_then(|p| p.parse::<u16>().ok());
+            // Kopfzeile und Kommentare überspringen
+            if fields[0].is_empty() || fields[0].starts_with('#') || fields[0].eq_ignore_ascii_case("host") {
+                continue;
+            }
+            hosts.extend(parse_host_entry(fields[0], port));
+        }
+    }
+
+    if hosts.is_empty() {
+        return Err("Keine Hosts in der Liste gefunden".to_string());
+    }
+    // Doppelte Einträge auch an verschiedenen Stellen der Liste entfernen (Reihenfolge bleibt)
+    let mut seen = BTreeSet::new();
+    hosts.retain(|h| seen.insert((h.host.clone(), h.port)));
+    Ok(hosts)
+}
+
+/// "host" / "host:port" → StaticHost (expliziter Port hat Vorrang)
+fn parse_host_entry(entry: &str, port: Option<u16>) -> Option<StaticHost> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return None;
+    }
+    // IPv6 ohne Port nicht zerlegen
+    match entry.rsplit_once(':') {
+        Some((host, p)) if !host.contains(':') => Some(StaticHost {
+            host: host.to_string(),
+            port: port.or_else(|| p.parse().ok()),
+        }),
+        _ => Some(StaticHost {
+            host: entry.to_string(),
+            port,
+        }),
+    }
+}
+
+/// Prüft importierte Hosts auf eSCL (parallel)
+pub async fn probe_hosts(hosts: &[StaticHost]) -> Vec<DiscoveredScanner> {
+    let mut tasks = Vec::new();
+    for host in hosts {
+        let ports = match host.port {
+            Some(port) => vec![port],
+            None => vec![80, 443],
+        };
+        let host_name = host.host.clone();
+        tasks.push(tokio::spawn(async move {
+            for port in ports {
+                if let Some(mut scanner) = probe_escl_endpoint(&host_name, port).await {
+                    scanner.discovery_method = "import".to_string();
+                    return Some(scanner);
+                }
+            }
+            None
+        }));
+    }
+
+    let mut scanners = Vec::new();
+    for task in tasks {
+        if let Ok(Ok(Some(scanner))) = timeout(Duration::from_secs(30), task).await {
+            scanners.push(scanner);
+        }
+    }
+    scanners
+}
+
+/// Per WSD gefundener Scanner: bietet das Gerät zusätzlich eSCL an, wird darüber gescannt
+/// Antwortet kein eSCL-Endpunkt, bleibt der Scanner unverändert (nur WSD)
+async fn with_escl_endpoint(mut scanner: DiscoveredScanner) -> DiscoveredScanner {
+    for port in [80, 443] {
+        if let Some(escl) = probe_escl_endpoint(&scanner.ip, port).await {
+            scanner.port = escl.port;
+            scanner.use_tls = escl.use_tls;
+            scanner.rs_path = escl.rs_path;
+            scanner.protocols.insert(0, "escl".to_string());
+            break;
+        }
+    }
+    scanner
+}
+
+/// Prüft ob unter IP:Port ein eSCL-Endpunkt erreichbar ist
+async fn probe_escl_endpoint(ip: &str, port: u16) -> Option<DiscoveredScanner> {
+    let scheme = if port == 443 { "https" } else { "http" };
+    let url = format!("{}://{}:{}/eSCL/ScannerCapabilities", scheme, ip, port);
+
+    let client = crate::http_client::scanner(Duration::from_secs(2)).ok()?;
+
+    let response = client.get(&url).send().await.ok()?;
+
+    if response.status().is_success() {
+        let content = response.text().await.ok()?;
+
+        // Prüfen ob es eSCL XML ist
+        if content.contains("ScannerCapabilities") {
+            return Some(DiscoveredScanner {
+                id: format!("{}:{}", ip, port),
+                name: format!("Scanner at {}", ip),
+                manufacturer: "Unknown".to_string(),
+                model: format!("eSCL Scanner ({})", ip),
+                ip: ip.to_string(),
+                port,
+                use_tls: port == 443,
+                protocols: vec!["escl".to_string()],
+                capabilities: ScannerCapabilities::default(),
+                discovery_method: "ip_scan".to_string(),
+                rs_path: "eSCL".to_string(),
+                wsd_url: None,
+                endpoints: Vec::new(),
+                firmware: None,
+                serial_number: None,
+                mac_address: None,
+                location: None,
+                group: None,
+                admin_url: None,
+                icon_url: None,
+                icon: None,
+            });
+        }
+    }
+
+    None
+}
+
+/// Extrahiert Hersteller aus Modellname
+pub fn extract_manufacturer(model: &str) -> String {
+    let model_lower = model.to_lowercase();
+    let manufacturers = [
+        ("hp", "HP"),
+        ("hewlett", "HP"),
+        ("canon", "Canon"),
+        ("brother", "Brother"),
+        ("epson", "Epson"),
+        ("samsung", "Samsung"),
+        ("xerox", "Xerox"),
+        ("lexmark", "Lexmark"),
+        ("ricoh", "Ricoh"),
+        ("kyocera", "Kyocera"),
+        ("konica", "Konica Minolta"),
+    ];
+
+    for (key, name) in manufacturers {
+        if model_lower.contains(key) {
+            return name.to_string();
+        }
+    }
+
+    "Unknown".to_string()
+}
+
+/// Lokal angeschlossener Scanner (ohne Netzwerkadresse)
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn local_scanner(
+    id: String,
+    manufacturer: String,
+    model: String,
+    protocol: &str,
+    capabilities: ScannerCapabilities,
+) -> DiscoveredScanner {
+    DiscoveredScanner {
+        id,
+        name: model.clone(),
+        manufacturer,
+        model,
+        ip: String::new(),
+        port: 0,
+        use_tls: false,
+        protocols: vec![protocol.to_string()],
+        capabilities,
+        discovery_method: protocol.to_string(),
+        rs_path: String::new(),
+        wsd_url: None,
+        endpoints: Vec::new(),
+        firmware: None,
+        serial_number: None,
+        mac_address: None,
+        location: None,
+        group: None,
+        admin_url: None,
+        icon_url: None,
+        icon: None,
+    }
+}
+
+/// Ermittelt Subnet-Prefix aus IP-Adresse
+pub fn get_subnet(ip: &IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ipv4) => {
+            let octets = ipv4.octets();
+            format!("{}.{}.{}", octets[0], octets[1], octets[2])
+        }
+        IpAddr::V6(_) => "192.168.1".to_string(), // Fallback für IPv6
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub mod native {
+    //! Windows-spezifische Scanner-Erkennung via WIA
+    use super::*;
+    use windows::core::{Interface, BSTR, PROPVARIANT};
+    use windows::Win32::Devices::ImageAcquisition::{
+        IWiaDevMgr2, IWiaPropertyStorage, WiaDevMgr2, DUP, FEED, FLAT, WIA_DEVINFO_ENUM_LOCAL, WIA_DIP_DEV_ID,
+        WIA_DIP_DEV_NAME, WIA_DIP_DEV_TYPE, WIA_DIP_VEND_DESC, WIA_DPS_DOCUMENT_HANDLING_CAPABILITIES,
+        WIA_DPS_OPTICAL_XRES,
+    };
+    use windows::Win32::System::Com::StructuredStorage::{PROPSPEC, PROPSPEC_0, PRSPEC_PROPID};
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_LOCAL_SERVER, COINIT_MULTITHREADED};
+
+    /// STI-Gerätetyp "Scanner" (oberes Wort von WIA_DIP_DEV_TYPE)
+    const STI_DEVICE_TYPE_SCANNER: i32 = 1;
+
+    /// Entdeckt lokale Scanner via Windows Image Acquisition (WIA)
+    pub async fn discover_wia() -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+        // COM-Aufrufe blockieren (Gerät wird ggf. geweckt) - eigener Thread mit eigener COM-Initialisierung
+        tokio::task::spawn_blocking(|| {
+            unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.ok()?;
+            let result = enumerate();
+            unsafe { CoUninitialize() };
+            result
+        })
+        .await?
+        .map_err(Into::into)
+    }
+
+    fn enumerate() -> windows::core::Result<Vec<DiscoveredScanner>> {
+        let manager: IWiaDevMgr2 = unsafe { CoCreateInstance(&WiaDevMgr2, None, CLSCTX_LOCAL_SERVER)? };
+        // Nur lokal angeschlossene Geräte - Netzwerkscanner kommen über eSCL/WSD
+        let devices = unsafe { manager.EnumDeviceInfo(WIA_DEVINFO_ENUM_LOCAL as i32)? };
+
+        let mut scanners = Vec::new();
+        loop {
+            let mut item = [None];
+            let mut fetched = 0;
+            unsafe { devices.Next(1, item.as_mut_ptr(), &mut fetched)? };
+            let Some(info) = item[0].take().filter(|_| fetched == 1) else {
+                break;
+            };
+
+            let [id, name, vendor, device_type] =
+                read_properties(&info, [WIA_DIP_DEV_ID, WIA_DIP_DEV_NAME, WIA_DIP_VEND_DESC, WIA_DIP_DEV_TYPE])?;
+            if i32::try_from(&device_type).unwrap_or_default() >> 16 != STI_DEVICE_TYPE_SCANNER {
+                continue;
+            }
+            let device_id = BSTR::try_from(&id).map(|id| id.to_string()).unwrap_or_default();
+            let model = BSTR::try_from(&name).map(|name| name.to_string()).unwrap_or_default();
+            let vendor = BSTR::try_from(&vendor).map(|vendor| vendor.to_string()).unwrap_or_default();
+
+            // Fähigkeiten aus dem Root-Item (Einzug, Duplex, optische Auflösung)
+            let capabilities = match capabilities(&manager, &device_id) {
+                Ok(capabilities) => capabilities,
+                Err(e) => {
+                    println!("⚠ WIA-Fähigkeiten von {} nicht lesbar: {}", model, e);
+                    ScannerCapabilities { flatbed: true, ..ScannerCapabilities::default() }
+                }
+            };
+
+            println!("📡 WIA-Scanner entdeckt: {} ({})", model, device_id);
+            let manufacturer = match extract_manufacturer(&format!("{} {}", vendor, model)) {
+                unknown if unknown == "Unknown" && !vendor.is_empty() => vendor,
+                known => known,
+            };
+            scanners.push(local_scanner(format!("wia:{}", device_id), manufacturer, model, "wia", capabilities));
+        }
+        Ok(scanners)
+    }
+
+    fn capabilities(manager: &IWiaDevMgr2, device_id: &str) -> windows::core::Result<ScannerCapabilities> {
+        let root = unsafe { manager.CreateDevice(0, &BSTR::from(device_id))? };
+        let properties: IWiaPropertyStorage = root.cast()?;
+        let [handling, optical_xres] =
+            read_properties(&properties, [WIA_DPS_DOCUMENT_HANDLING_CAPABILITIES, WIA_DPS_OPTICAL_XRES])?;
+
+        // Ohne Document-Handling-Eigenschaft: reiner Flachbettscanner
+        let handling = u32::try_from(&handling)
+            .or_else(|_| i32::try_from(&handling).map(|value| value as u32))
+            .unwrap_or(FLAT);
+        let max_resolution = i32::try_from(&optical_xres).ok().filter(|dpi| *dpi > 0).unwrap_or(600) as u32;
+        Ok(ScannerCapabilities {
+            duplex: handling & DUP != 0,
+            adf: handling & FEED != 0,
+            flatbed: handling & FLAT != 0,
+            max_resolution,
+            color_modes: vec!["RGB24".to_string(), "Grayscale8".to_string()],
+            formats: vec!["image/jpeg".to_string()],
+            unknown: false,
+        })
+    }
+
+    fn read_properties<const N: usize>(
+        storage: &IWiaPropertyStorage,
+        ids: [u32; N],
+    ) -> windows::core::Result<[PROPVARIANT; N]> {
+        let specs = ids.map(|propid| PROPSPEC { ulKind: PRSPEC_PROPID, Anonymous: PROPSPEC_0 { propid } });
+        let mut values: [PROPVARIANT; N] = std::array::from_fn(|_| PROPVARIANT::default());
+        unsafe { storage.ReadMultiple(N as u32, specs.as_ptr(), values.as_mut_ptr())? };
+        Ok(values)
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub mod native {
+    //! Linux-spezifische Scanner-Erkennung via SANE
+    use super::*;
+    use crate::sane::{Sane, SaneDevice, SaneOptions};
+    use crate::scanner::sane::ID_PREFIX;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Fähigkeiten je SANE-Gerätename - sane_open nur beim ersten Auftauchen eines Geräts
+    static CAPABILITIES: Mutex<Option<HashMap<String, ScannerCapabilities>>> = Mutex::new(None);
+
+    /// Entdeckt lokal angeschlossene Scanner via SANE
+    /// Netzwerk-Backends (net, escl, airscan ...) bleiben außen vor - diese Geräte findet die Netzwerk-Discovery
+    pub async fn discover_sane() -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+        // libsane blockiert (USB-Enumeration) - eigener Thread
+        tokio::task::spawn_blocking(|| {
+            let sane = Sane::load()?;
+            let mut cache = CAPABILITIES.lock().unwrap_or_else(|e| e.into_inner());
+            let cache = cache.get_or_insert_with(HashMap::new);
+            let scanners = sane
+                .devices(true)?
+                .into_iter()
+                .map(|device| {
+                    let capabilities = match cache.get(&device.name) {
+                        Some(capabilities) => capabilities.clone(),
+                        None => {
+                            println!("📡 SANE-Scanner entdeckt: {} {} ({})", device.vendor, device.model, device.name);
+                            match sane.options(&device.name) {
+                                Ok(options) => {
+                                    let capabilities = capabilities(&options, &device);
+                                    cache.insert(device.name.clone(), capabilities.clone());
+                                    capabilities
+                                }
+                                // Nicht zwischenspeichern - beim nächsten Lauf erneut versuchen (z.B. Gerät belegt)
+                                Err(e) => {
+                                    println!("⚠ SANE-Optionen von {} nicht lesbar: {}", device.name, e);
+                                    capabilities(&Default::default(), &device)
+                                }
+                            }
+                        }
+                    };
+                    let manufacturer = match extract_manufacturer(&format!("{} {}", device.vendor, device.model)) {
+                        unknown if unknown == "Unknown" && !device.vendor.is_empty() => device.vendor.clone(),
+                        known => known,
+                    };
+                    let model = format!("{} {}", device.vendor, device.model).trim().to_string();
+                    local_scanner(format!("{}{}", ID_PREFIX, device.name), manufacturer, model, "sane", capabilities)
+                })
+                .collect();
+            Ok(scanners)
+        })
+        .await?
+    }
+
+    fn capabilities(options: &SaneOptions, device: &SaneDevice) -> ScannerCapabilities {
+        let sources: Vec<String> = options.sources.iter().map(|s| s.to_lowercase()).collect();
+        let adf = sources.iter().any(|s| s.contains("adf") || s.contains("feeder"))
+            || device.kind.contains("sheetfed");
+        let color_modes = options
+            .modes
+            .iter()
+            .filter_map(|mode| match mode.to_lowercase().as_str() {
+                "color" => Some("RGB24"),
+                "gray" => Some("Grayscale8"),
+                _ => None,
+            })
+            .map(str::to_string)
+            .collect();
+        ScannerCapabilities {
+            duplex: sources.iter().any(|s| s.contains("duplex")),
+            adf,
+            flatbed: sources.iter().any(|s| s.contains("flatbed")) || (sources.is_empty() && !adf),
+            max_resolution: options.max_resolution.unwrap_or(600),
+            color_modes,
+            formats: vec!["image/jpeg".to_string()],
+            unknown: false,
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub mod native {
+    //! macOS-spezifische Scanner-Erkennung via ImageCaptureCore
+    use super::*;
+    use objc2::rc::{autoreleasepool, Retained};
+    use objc2::runtime::{AnyClass, AnyObject, Bool, NSObject, NSObjectProtocol};
+    use objc2::{define_class, msg_send, AnyThread, DefinedClass};
+    use objc2_foundation::{NSDate, NSRunLoop, NSString};
+
+    // ICDeviceBrowser liegt im ImageCaptureCore-Framework (Klasse wird zur Laufzeit nachgeschlagen)
+    #[link(name = "ImageCaptureCore", kind = "framework")]
+    extern "C" {}
+
+    /// ICDeviceTypeMaskScanner | ICDeviceLocationTypeMaskLocal (Netzwerkscanner kommen über eSCL/WSD)
+    const BROWSED_DEVICE_TYPES: usize = 0x0000_0002 | 0x0000_0100;
+
+    /// Wartezeit auf die Geräte-Meldungen des Browsers in Sekunden
+    const BROWSE_SECONDS: f64 = 3.0;
+
+    /// Vom Browser gemeldetes Gerät
+    struct FoundDevice {
+        name: String,
+        uuid: String,
+        transport: String,
+    }
+
+    #[derive(Default)]
+    struct BrowserState {
+        devices: Mutex<Vec<FoundDevice>>,
+    }
+
+    define_class!(
+        // ICDeviceBrowserDelegate - sammelt die gemeldeten Geräte
+        #[unsafe(super(NSObject))]
+        #[ivars = BrowserState]
+        struct BrowserDelegate;
+
+        unsafe impl NSObjectProtocol for BrowserDelegate {}
+
+        impl BrowserDelegate {
+            #[unsafe(method(deviceBrowser:didAddDevice:moreComing:))]
+            fn did_add_device(&self, _browser: &AnyObject, device: &AnyObject, _more_coming: Bool) {
+                let device = unsafe { found_device(device) };
+                self.ivars().devices.lock().unwrap_or_else(|e| e.into_inner()).push(device);
+            }
+
+            #[unsafe(method(deviceBrowser:didRemoveDevice:moreGoing:))]
+            fn did_remove_device(&self, _browser: &AnyObject, _device: &AnyObject, _more_going: Bool) {}
+        }
+    );
+
+    impl BrowserDelegate {
+        fn new() -> Retained<Self> {
+            let this = Self::alloc().set_ivars(BrowserState::default());
+            unsafe { msg_send![super(this), init] }
+        }
+    }
+
+    /// Entdeckt lokale Scanner via ImageCaptureCore
+    pub async fn discover_image_capture() -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+        // Der Browser meldet Geräte über den Run Loop seines Threads - eigener Thread, der ihn kurz laufen lässt
+        tokio::task::spawn_blocking(|| autoreleasepool(|_| browse())).await?
+    }
+
+    fn browse() -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+        let class = AnyClass::get(c"ICDeviceBrowser").ok_or("ImageCaptureCore nicht verfügbar")?;
+        let delegate = BrowserDelegate::new();
+        let browser: Retained<AnyObject> = unsafe { msg_send![class, new] };
+        unsafe {
+            let _: () = msg_send![&browser, setDelegate: &*delegate];
+            let _: () = msg_send![&browser, setBrowsedDeviceTypeMask: BROWSED_DEVICE_TYPES];
+            let _: () = msg_send![&browser, start];
+        }
+
+        NSRunLoop::currentRunLoop().runUntilDate(&NSDate::dateWithTimeIntervalSinceNow(BROWSE_SECONDS));
+
+        unsafe {
+            let _: () = msg_send![&browser, stop];
+            let _: () = msg_send![&browser, setDelegate: std::ptr::null::<AnyObject>()];
+        }
+
+        let devices = std::mem::take(&mut *delegate.ivars().devices.lock().unwrap_or_else(|e| e.into_inner()));
+        Ok(devices
+            .into_iter()
+            .map(|device| {
+                println!("📡 ImageCapture-Scanner entdeckt: {} ({})", device.name, device.transport);
+                // ImageCaptureCore nennt keinen Hersteller - aus dem Gerätenamen ableiten
+                let manufacturer = extract_manufacturer(&device.name);
+                // Fähigkeiten erst nach dem Öffnen einer Sitzung bekannt - als unbekannt melden
+                let capabilities = ScannerCapabilities { unknown: true, ..ScannerCapabilities::default() };
+                local_scanner(format!("ica:{}", device.uuid), manufacturer, device.name, "imagecapture", capabilities)
+            })
+            .collect())
+    }
+
+    /// Name, UUID und Anschlussart eines ICDevice
+    unsafe fn found_device(device: &AnyObject) -> FoundDevice {
+        let text = |value: Option<Retained<NSString>>| value.map(|value| value.to_string()).unwrap_or_default();
+        let name: Option<Retained<NSString>> = msg_send![device, name];
+        let uuid: Option<Retained<NSString>> = msg_send![device, UUIDString];
+        let transport: Option<Retained<NSString>> = msg_send![device, transportType];
+        FoundDevice { name: text(name), uuid: text(uuid), transport: text(transport) }
+    }
+}
@@ -0,0 +1,206 @@
+// Bildverbesserung - Konfigurierbare Filter zwischen Scan und Upload (bessere OCR in DocFlow)
+// Jede Stufe implementiert ImageFilter; neue Stufen nur in EnhanceStage + build_filter ergänzen
+
+use image::{DynamicImage, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Verfügbare Verbesserungs-Stufen (Reihenfolge in der Konfiguration = Ausführungsreihenfolge)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum EnhanceStage {
+    Despeckle,          // Einzelne Staubpunkte entfernen
+    BackgroundCleanup,  // Grauen/vergilbten Hintergrund weiß machen
+    ContrastStretch,    // Helligkeitsbereich auf 0-255 spreizen
+    PunchHoleRemoval,   // Lochungen am Rand entfernen
+}
+
+/// Standard-Bildverbesserung (gilt, wenn Job/Profil nichts vorgibt)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EnhanceConfig {
+    pub stages: Vec<EnhanceStage>,
+}
+
+/// Ein Filter der Pipeline
+pub trait ImageFilter: Send + Sync {
+    /// dpi: Scan-Auflösung (für größenabhängige Filter)
+    fn apply(&self, image: RgbImage, dpi: u32) -> RgbImage;
+}
+
+fn build_filter(stage: &EnhanceStage) -> Box<dyn ImageFilter> {
+    match stage {
+        EnhanceStage::Despeckle => Box::new(Despeckle),
+        EnhanceStage::BackgroundCleanup => Box::new(BackgroundCleanup { threshold: 215 }),
+        EnhanceStage::ContrastStretch => Box::new(ContrastStretch { clip_percent: 1.0 }),
+        EnhanceStage::PunchHoleRemoval => Box::new(PunchHoleRemoval),
+    }
+}
+
+/// Wendet alle Stufen nacheinander auf ein Bild an
+pub fn enhance(image: &DynamicImage, stages: &[EnhanceStage], dpi: u32) -> DynamicImage {
+    let mut rgb = image.to_rgb8();
+    for filter in stages.iter().map(build_filter) {
+        rgb = filter.apply(rgb, dpi);
+    }
+    DynamicImage::ImageRgb8(rgb)
+}
+
+fn luma(pixel: &Rgb<u8>) -> u8 {
+    let [r, g, b] = pixel.0;
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+/// Entfernt isolierte dunkle Pixel (fast alle Nachbarn hell)
+struct Despeckle;
+
+impl ImageFilter for Despeckle {
+    fn apply(&self, image: RgbImage, _dpi: u32) -> RgbImage {
+        let (width, height) = image.dimensions();
+        let mut output = image.clone();
+        for y in 1..height.saturating_sub(1) {
+            for x in 1..width.saturating_sub(1) {
+                if luma(image.get_pixel(x, y)) >= 128 {
+                    continue;
+                }
+                let mut light_neighbors = 0;
+                for (dx, dy) in [(-1i32, -1i32), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                    let neighbor = image.get_pixel((x as i32 + dx) as u32, (y as i32 + dy) as u32);
+                    if luma(neighbor) >= 128 {
+                        light_neighbors += 1;
+                    }
+                }
+                if light_neighbors >= 7 {
+                    output.put_pixel(x, y, Rgb([255, 255, 255]));
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Setzt helle Hintergrundpixel auf reines Weiß
+struct BackgroundCleanup {
+    threshold: u8,
+}
+
+impl ImageFilter for BackgroundCleanup {
+    fn apply(&self, mut image: RgbImage, _dpi: u32) -> RgbImage {
+        for pixel in image.pixels_mut() {
+            if luma(pixel) >= self.threshold {
+                *pixel = Rgb([255, 255, 255]);
+            }
+        }
+        image
+    }
+}
+
+/// Lineare Kontrastspreizung zwischen unterem und oberem Perzentil
+struct ContrastStretch {
+    clip_percent: f32,
+}
+
+impl ImageFilter for ContrastStretch {
+    fn apply(&self, mut image: RgbImage, _dpi: u32) -> RgbImage {
+        let mut histogram = [0u64; 256];
+        for pixel in image.pixels() {
+            histogram[luma(pixel) as usize] += 1;
+        }
+
+        let total: u64 = histogram.iter().sum();
+        let clip = (total as f32 * self.clip_percent / 100.0) as u64;
+
+        let mut low = 0usize;
+        let mut acc = 0u64;
+        while low < 255 && acc + histogram[low] <= clip {
+            acc += histogram[low];
+            low += 1;
+        }
+        let mut high = 255usize;
+        acc = 0;
+        while high > 0 && acc + histogram[high] <= clip {
+            acc += histogram[high];
+            high -= 1;
+        }
+
+        if high <= low {
+            return image;
+        }
+
+        let range = (high - low) as f32;
+        for pixel in image.pixels_mut() {
+            for channel in pixel.0.iter_mut() {
+                let stretched = (*channel as f32 - low as f32) * 255.0 / range;
+                *channel = stretched.clamp(0.0, 255.0) as u8;
+            }
+        }
+        image
+    }
+}
+
+/// Entfernt Lochungen: dunkle, annähernd runde Flecken (4-9 mm) im Randbereich
+struct PunchHoleRemoval;
+
+impl ImageFilter for PunchHoleRemoval {
+    fn apply(&self, mut image: RgbImage, dpi: u32) -> RgbImage {
+        let (width, height) = image.dimensions();
+        let dpi = if dpi > 0 { dpi as f32 } else { 300.0 };
+        let min_size = (4.0 / 25.4 * dpi) as u32;
+        let max_size = (9.0 / 25.4 * dpi) as u32;
+        let margin_x = width / 10;
+        let margin_y = height / 10;
+
+        let in_margin = |x: u32, y: u32| {
+            x < margin_x || x >= width - margin_x || y < margin_y || y >= height - margin_y
+        };
+
+        let mut visited = vec![false; (width * height) as usize];
+        for start_y in 0..height {
+            for start_x in 0..width {
+                let index = (start_y * width + start_x) as usize;
+                if visited[index] || !in_margin(start_x, start_y) || luma(image.get_pixel(start_x, start_y)) >= 100 {
+                    continue;
+                }
+
+                // Zusammenhängende dunkle Fläche per Flood-Fill sammeln
+                let mut region = Vec::new();
+                let mut queue = VecDeque::from([(start_x, start_y)]);
+                visited[index] = true;
+                while let Some((x, y)) = queue.pop_front() {
+                    region.push((x, y));
+                    if region.len() > (max_size * max_size * 2) as usize {
+                        break;
+                    }
+                    for (nx, ny) in [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)] {
+                        if nx >= width || ny >= height {
+                            continue;
+                        }
+                        let n_index = (ny * width + nx) as usize;
+                        if !visited[n_index] && in_margin(nx, ny) && luma(image.get_pixel(nx, ny)) < 100 {
+                            visited[n_index] = true;
+                            queue.push_back((nx, ny));
+                        }
+                    }
+                }
+
+                let min_x = region.iter().map(|p| p.0).min().unwrap_or(0);
+                let max_x = region.iter().map(|p| p.0).max().unwrap_or(0);
+                let min_y = region.iter().map(|p| p.1).min().unwrap_or(0);
+                let max_y = region.iter().map(|p| p.1).max().unwrap_or(0);
+                let region_width = max_x - min_x + 1;
+                let region_height = max_y - min_y + 1;
+
+                // Rund: annähernd quadratische Bounding-Box, Füllgrad ~ π/4
+                let size_ok = (min_size..=max_size).contains(&region_width)
+                    && (min_size..=max_size).contains(&region_height);
+                let aspect = region_width as f32 / region_height as f32;
+                let fill = region.len() as f32 / (region_width * region_height) as f32;
+
+                if size_ok && (0.75..=1.33).contains(&aspect) && (0.6..=0.95).contains(&fill) {
+                    for (x, y) in region {
+                        image.put_pixel(x, y, Rgb([255, 255, 255]));
+                    }
+                }
+            }
+        }
+        image
+    }
+}
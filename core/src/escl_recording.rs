@@ -0,0 +1,470 @@
+// eSCL-Aufzeichnung - Zeichnet eSCL-Anfragen/-Antworten eines Scans bereinigt in eine Datei auf
+// Wiedergabe über einen lokalen Mini-Server, gegen den der echte Scan-Ablauf erneut läuft (Fehlersuche, CI)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::scanner::{self, ScanJob, ScanResult};
+use crate::{imaging, pdf, settings};
+
+/// Aktuelles Format der Aufzeichnungsdateien
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Inhalte dieser Tags werden entfernt (Seriennummern, Geräte-IDs, URLs mit Adressen)
+const SENSITIVE_TAGS: &[&str] = &["SerialNumber", "UUID", "AdminURI", "IconURI", "DeviceName"];
+
+/// Diese Antwort-Header werden aufgezeichnet (alle anderen verworfen)
+const RECORDED_HEADERS: &[&str] = &["content-type", "location", "retry-after", "content-range"];
+
+/// Einstellungen der Aufzeichnung
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordingConfig {
+    pub enabled: bool,
+    /// Gescannte Seiten unverändert speichern (Standard: durch leere Seiten gleicher Größe ersetzt)
+    #[serde(default)]
+    pub include_documents: bool,
+    /// Zielordner (None = "recordings" im App-Datenverzeichnis)
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+/// Aufgezeichnete Scan-Sitzung
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Recording {
+    pub format_version: u32,
+    pub recorded_at: String,
+    pub bridge_version: String,
+    /// Scan-Parameter (Scanner-ID entfernt)
+    pub job: ScanJob,
+    pub exchanges: Vec<Exchange>,
+}
+
+/// Eine Anfrage mit Antwort
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Exchange {
+    pub method: String,
+    /// Pfad ohne Host, z.B. "/eSCL/ScanJobs/12/NextDocument"
+    pub path: String,
+    #[serde(default)]
+    pub request_body: Option<String>,
+    /// None = Verbindungsfehler (siehe error)
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    /// Text-Antworten im Klartext, Binärdaten (Seiten) als Base64
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub binary: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CONFIG: Mutex<Option<RecordingConfig>> = Mutex::new(None);
+/// Laufende Aufzeichnungen je Scanner-Host
+static SESSIONS: Mutex<Option<HashMap<String, Recording>>> = Mutex::new(None);
+
+/// Übernimmt die Einstellungen (wirkt ab dem nächsten Scan)
+pub fn apply_config(config: &RecordingConfig) {
+    ENABLED.store(config.enabled, Ordering::Relaxed);
+    if let Ok(mut current) = CONFIG.lock() {
+        *current = Some(config.clone());
+    }
+}
+
+fn config() -> RecordingConfig {
+    CONFIG.lock().ok().and_then(|c| c.clone()).unwrap_or_default()
+}
+
+/// Laufende Aufzeichnung - wird beim Drop in die Datei geschrieben
+pub struct RecordingSession {
+    host: String,
+}
+
+/// Startet die Aufzeichnung für einen Scan (None wenn deaktiviert)
+pub fn start(host: &str, job: &ScanJob) -> Option<RecordingSession> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let mut job = job.clone();
+    job.scanner_id = "recorded".to_string();
+    let recording = Recording {
+        format_version: FORMAT_VERSION,
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+        bridge_version: env!("CARGO_PKG_VERSION").to_string(),
+        job,
+        exchanges: Vec::new(),
+    };
+
+    let mut sessions = SESSIONS.lock().ok()?;
+    sessions.get_or_insert_with(HashMap::new).insert(host.to_string(), recording);
+    Some(RecordingSession { host: host.to_string() })
+}
+
+impl Drop for RecordingSession {
+    fn drop(&mut self) {
+        let recording = SESSIONS
+            .lock()
+            .ok()
+            .and_then(|mut sessions| sessions.as_mut()?.remove(&self.host));
+        let Some(recording) = recording else {
+            return;
+        };
+
+        match save(&recording) {
+            Ok(path) => println!("🎙 eSCL-Aufzeichnung gespeichert: {}", path.display()),
+            Err(e) => eprintln!("⚠ eSCL-Aufzeichnung nicht gespeichert: {}", e),
+        }
+    }
+}
+
+fn save(recording: &Recording) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    let directory = config()
+        .directory
+        .map(PathBuf::from)
+        .unwrap_or_else(|| settings::data_dir().join("recordings"));
+    std::fs::create_dir_all(&directory)?;
+
+    let path = directory.join(format!("escl-{}.json", chrono::Utc::now().format("%Y%m%d-%H%M%S-%3f")));
+    std::fs::write(&path, serde_json::to_string_pretty(recording)?)?;
+    Ok(path)
+}
+
+/// Lädt eine Aufzeichnung
+pub fn load(path: &Path) -> Result<Recording, Box<dyn std::error::Error + Send + Sync>> {
+    let recording: Recording = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if recording.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "Aufzeichnung hat Format {} (unterstützt bis {})",
+            recording.format_version, FORMAT_VERSION
+        )
+        .into());
+    }
+    Ok(recording)
+}
+
+/// `.send_recorded()` statt `.send()` für eSCL-Anfragen
+pub trait Recorded {
+    fn send_recorded(self) -> impl Future<Output = reqwest::Result<reqwest::Response>> + Send;
+}
+
+impl Recorded for reqwest::RequestBuilder {
+    fn send_recorded(self) -> impl Future<Output = reqwest::Result<reqwest::Response>> + Send {
+        send(self)
+    }
+}
+
+/// Sendet die Anfrage und zeichnet sie auf, falls für den Host eine Aufzeichnung läuft
+/// Die Antwort wird dafür vollständig gepuffert (Streaming nur ohne Aufzeichnung)
+async fn send(request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    let host = request.url().host_str().unwrap_or_default().to_string();
+
+    if !ENABLED.load(Ordering::Relaxed) || !is_recording(&host) {
+        return client.execute(request).await;
+    }
+
+    let method = request.method().to_string();
+    let path = path_of(request.url());
+    let request_body = request
+        .body()
+        .and_then(|b| b.as_bytes())
+        .map(|b| sanitize_text(&String::from_utf8_lossy(b), &host));
+    let started = Instant::now();
+
+    let mut exchange = Exchange {
+        method,
+        path,
+        request_body,
+        status: None,
+        headers: Vec::new(),
+        body: String::new(),
+        binary: false,
+        error: None,
+        elapsed_ms: 0,
+    };
+
+    let response = match client.execute(request).await {
+        Ok(response) => response,
+        Err(e) => {
+            exchange.error = Some(e.to_string());
+            exchange.elapsed_ms = started.elapsed().as_millis() as u64;
+            push(&host, exchange);
+            return Err(e);
+        }
+    };
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = match response.bytes().await {
+        Ok(body) => body.to_vec(),
+        Err(e) => {
+            exchange.status = Some(status.as_u16());
+            exchange.error = Some(e.to_string());
+            exchange.elapsed_ms = started.elapsed().as_millis() as u64;
+            push(&host, exchange);
+            return Err(e);
+        }
+    };
+
+    exchange.status = Some(status.as_u16());
+    exchange.elapsed_ms = started.elapsed().as_millis() as u64;
+    exchange.headers = headers
+        .iter()
+        .filter(|(name, _)| RECORDED_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            let value = if name == "location" { location_path(value) } else { value.to_string() };
+            Some((name.to_string(), value))
+        })
+        .collect();
+
+    let content_type = headers
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if content_type.contains("xml") || content_type.starts_with("text/") || (content_type.is_empty() && std::str::from_utf8(&body).is_ok()) {
+        exchange.body = sanitize_text(&String::from_utf8_lossy(&body), &host);
+    } else {
+        let data = if config().include_documents {
+            body.clone()
+        } else {
+            blank_document(&body, &content_type, resolution_of(&host))
+        };
+        use base64::Engine;
+        exchange.body = base64::engine::general_purpose::STANDARD.encode(data);
+        exchange.binary = true;
+    }
+    push(&host, exchange);
+
+    // Gepufferte Antwort an den Aufrufer weitergeben
+    let mut rebuilt = http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.headers_mut() = headers;
+    Ok(reqwest::Response::from(rebuilt))
+}
+
+fn is_recording(host: &str) -> bool {
+    SESSIONS
+        .lock()
+        .map(|sessions| sessions.as_ref().is_some_and(|s| s.contains_key(host)))
+        .unwrap_or(false)
+}
+
+fn push(host: &str, exchange: Exchange) {
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        if let Some(recording) = sessions.as_mut().and_then(|s| s.get_mut(host)) {
+            recording.exchanges.push(exchange);
+        }
+    }
+}
+
+fn resolution_of(host: &str) -> u32 {
+    SESSIONS
+        .lock()
+        .ok()
+        .and_then(|sessions| Some(sessions.as_ref()?.get(host)?.job.resolution))
+        .unwrap_or(300)
+}
+
+fn path_of(url: &reqwest::Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// Location-Header ohne Schema/Host (relativ und absolut möglich)
+fn location_path(location: &str) -> String {
+    reqwest::Url::parse(location)
+        .map(|url| path_of(&url))
+        .unwrap_or_else(|_| location.to_string())
+}
+
+/// Entfernt Host-Adresse und sensible Tag-Inhalte aus XML/Text
+fn sanitize_text(text: &str, host: &str) -> String {
+    let mut text = if host.is_empty() { text.to_string() } else { text.replace(host, "scanner.local") };
+    for tag in SENSITIVE_TAGS {
+        text = redact_tag(&text, tag);
+    }
+    text
+}
+
+/// Ersetzt den Inhalt von <tag>…</tag> bzw. <ns:tag>…</ns:tag> durch "REDACTED"
+fn redact_tag(text: &str, tag: &str) -> String {
+    let needle = format!("{}>", tag);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(pos) = rest.find(&needle) {
+        let end_of_open = pos + needle.len();
+        let opening = rest[..pos]
+            .rfind('<')
+            .map(|lt| !rest[lt..].starts_with("</") && !rest[lt..pos].contains('>'))
+            .unwrap_or(false);
+        out.push_str(&rest[..end_of_open]);
+        rest = &rest[end_of_open..];
+
+        if opening {
+            let content_end = rest.find('<').unwrap_or(rest.len());
+            if content_end > 0 {
+                out.push_str("REDACTED");
+            }
+            rest = &rest[content_end..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Ersetzt gescannte Seiten durch leere Seiten gleicher Pixelgröße (Struktur bleibt reproduzierbar)
+fn blank_document(data: &[u8], content_type: &str, resolution: u32) -> Vec<u8> {
+    let blank = |jpeg: &[u8]| -> Option<Vec<u8>> {
+        let img = image::load_from_memory(jpeg).ok()?;
+        let white = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            img.width(),
+            img.height(),
+            image::Rgb([255, 255, 255]),
+        ));
+        imaging::encode_jpeg(&white).ok()
+    };
+
+    if content_type.contains("pdf") || data.starts_with(b"%PDF") {
+        let pages: Vec<Vec<u8>> = imaging::extract_pdf_jpegs(data).iter().filter_map(|p| blank(p)).collect();
+        if !pages.is_empty() {
+            if let Ok(pdf) = pdf::assemble_jpeg_pdf(&pages, resolution) {
+                return pdf;
+            }
+        }
+        return Vec::new();
+    }
+
+    blank(data).unwrap_or_default()
+}
+
+/// Spielt eine Aufzeichnung ab: lokaler Server liefert die aufgezeichneten Antworten,
+/// der echte eSCL-Ablauf (scan_escl_with_tls) läuft dagegen
+pub async fn replay(recording: &Recording) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let port = listener.local_addr()?.port();
+    let exchanges = Arc::new(tokio::sync::Mutex::new(recording.exchanges.clone()));
+
+    let server = tokio::spawn(async move {
+        loop {
+            let Ok((socket, _)) = listener.accept().await else {
+                break;
+            };
+            let exchanges = exchanges.clone();
+            tokio::spawn(async move {
+                let _ = answer(socket, exchanges, port).await;
+            });
+        }
+    });
+
+    // Resource-Path aus der ersten Anfrage (z.B. "/eSCL/ScannerStatus" → "eSCL")
+    let rs_path = recording
+        .exchanges
+        .first()
+        .and_then(|e| e.path.trim_start_matches('/').split('/').next())
+        .unwrap_or("eSCL")
+        .to_string();
+
+    let result = scanner::scan_escl_with_tls("127.0.0.1", port, false, &rs_path, &recording.job).await;
+    server.abort();
+    result
+}
+
+/// Beantwortet eine Anfrage mit dem nächsten passenden Austausch (Methode + Pfad)
+async fn answer(
+    mut socket: tokio::net::TcpStream,
+    exchanges: Arc<tokio::sync::Mutex<Vec<Exchange>>>,
+    port: u16,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Header lesen, danach den Body laut Content-Length
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let content_length = head
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length").then(|| value.trim().parse::<usize>().ok())?
+        })
+        .unwrap_or(0);
+    while buffer.len() < header_end + content_length {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+
+    let exchange = {
+        let mut exchanges = exchanges.lock().await;
+        exchanges
+            .iter()
+            .position(|e| e.method == method && e.path == path)
+            .map(|index| exchanges.remove(index))
+    };
+
+    let Some(exchange) = exchange else {
+        socket
+            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .await?;
+        return Ok(());
+    };
+
+    // Aufgezeichneter Verbindungsfehler: Verbindung ohne Antwort schließen
+    let Some(status) = exchange.status.filter(|_| exchange.error.is_none()) else {
+        return Ok(());
+    };
+
+    let body = if exchange.binary {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.decode(&exchange.body)?
+    } else {
+        exchange.body.into_bytes()
+    };
+
+    let mut response = format!("HTTP/1.1 {} Replay\r\n", status);
+    for (name, value) in &exchange.headers {
+        let value = if name == "location" && value.starts_with('/') {
+            format!("http://127.0.0.1:{}{}", port, value)
+        } else {
+            value.clone()
+        };
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", body.len()));
+
+    socket.write_all(response.as_bytes()).await?;
+    socket.write_all(&body).await?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+// Ereignis-Verlauf - Begrenzter Ringpuffer wichtiger Ereignisse (Scans, Uploads, Fehler, Discovery)
+// Die UI lädt beim Öffnen den Verlauf, damit nichts verloren geht, während das Fenster versteckt ist
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::settings;
+
+/// Konfiguration des Ereignis-Verlaufs
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventLogConfig {
+    /// Maximale Anzahl gespeicherter Ereignisse
+    pub capacity: usize,
+    /// Verlauf zusätzlich in events.jsonl sichern (übersteht Neustarts)
+    pub persist: bool,
+}
+
+impl Default for EventLogConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 500,
+            persist: false,
+        }
+    }
+}
+
+/// Art eines Ereignisses
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Scan,
+    Upload,
+    Error,
+    Discovery,
+}
+
+/// Ein Ereignis im Verlauf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BridgeEvent {
+    /// Fortlaufende ID (für get_events(since))
+    pub id: u64,
+    pub timestamp: String,
+    pub kind: EventKind,
+    pub message: String,
+}
+
+struct EventLog {
+    events: VecDeque<BridgeEvent>,
+    next_id: u64,
+    config: EventLogConfig,
+}
+
+static EVENTS: Mutex<EventLog> = Mutex::new(EventLog {
+    events: VecDeque::new(),
+    next_id: 1,
+    config: EventLogConfig {
+        capacity: 500,
+        persist: false,
+    },
+});
+
+/// Übernimmt die Konfiguration (lädt beim Aktivieren der Persistenz den gespeicherten Verlauf)
+pub fn apply_config(config: &EventLogConfig) {
+    let Ok(mut log) = EVENTS.lock() else {
+        return;
+    };
+
+    if config.persist && !log.config.persist {
+        let mut stored = load_persisted();
+        stored.extend(log.events.drain(..));
+        log.events = stored;
+    }
+
+    log.config = config.clone();
+    let capacity = config.capacity.max(1);
+    while log.events.len() > capacity {
+        log.events.pop_front();
+    }
+    log.next_id = log.events.back().map(|e| e.id + 1).unwrap_or(1).max(log.next_id);
+
+    if config.persist {
+        rewrite_persisted(&log.events);
+    }
+}
+
+/// Zeichnet ein Ereignis auf
+pub fn record(kind: EventKind, message: impl Into<String>) {
+    let Ok(mut log) = EVENTS.lock() else {
+        return;
+    };
+
+    let event = BridgeEvent {
+        id: log.next_id,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        kind,
+        message: message.into(),
+    };
+    log.next_id += 1;
+
+    if log.config.persist {
+        append_persisted(&event);
+    }
+
+    log.events.push_back(event);
+    let capacity = log.config.capacity.max(1);
+    if log.events.len() > capacity {
+        log.events.pop_front();
+        // Datei gelegentlich kürzen, damit sie nicht unbegrenzt wächst
+        if log.config.persist && log.next_id % capacity as u64 == 0 {
+            rewrite_persisted(&log.events);
+        }
+    }
+}
+
+/// Ereignisse mit ID größer als `since` (None = gesamter Verlauf)
+pub fn since(since: Option<u64>) -> Vec<BridgeEvent> {
+    let Ok(log) = EVENTS.lock() else {
+        return Vec::new();
+    };
+    log.events
+        .iter()
+        .filter(|e| since.is_none_or(|id| e.id > id))
+        .cloned()
+        .collect()
+}
+
+fn events_path() -> PathBuf {
+    settings::data_dir().join("events.jsonl")
+}
+
+fn load_persisted() -> VecDeque<BridgeEvent> {
+    std::fs::read_to_string(events_path())
+        .map(|content| content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+fn append_persisted(event: &BridgeEvent) {
+    let Ok(line) = serde_json::to_string(event) else {
+        return;
+    };
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(events_path()) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+fn rewrite_persisted(events: &VecDeque<BridgeEvent>) {
+    let content: String = events
+        .iter()
+        .filter_map(|e| serde_json::to_string(e).ok())
+        .map(|line| line + "\n")
+        .collect();
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    let _ = std::fs::write(events_path(), content);
+}
@@ -0,0 +1,140 @@
+// Datei-Gruppen im Folder-Sync - Teil-PDFs einer Scan-Software ("vertrag_p1.pdf", "vertrag_p2.pdf")
+// werden nach Ablauf eines Zeitfensters zu einem Dokument zusammengeführt und als ein Job hochgeladen
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::folder_watcher::glob_match;
+use crate::imaging;
+
+/// Platzhalter für die Teilnummer im Muster
+const PART_PLACEHOLDER: &str = "{n}";
+
+/// Gruppierungsregeln für den überwachten Ordner
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GroupingConfig {
+    #[serde(default)]
+    pub rules: Vec<GroupRule>,
+}
+
+/// Eine Regel: Dateinamen-Muster mit Teilnummer und Wartezeit auf weitere Teile
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupRule {
+    /// Muster mit genau einem "{n}" für die Teilnummer, * und ? als Platzhalter (z.B. "*_p{n}.pdf")
+    pub pattern: String,
+    /// Die Gruppe gilt als vollständig, wenn so lange kein neuer Teil hinzugekommen ist
+    #[serde(default = "default_window")]
+    pub window_secs: u64,
+}
+
+fn default_window() -> u64 {
+    60
+}
+
+impl GroupRule {
+    /// Gruppenname und Teilnummer, falls der Dateiname passt
+    /// Der Gruppenname ist der Dateiname ohne Teilnummer und den festen Text davor ("vertrag_p2.pdf" → "vertrag.pdf")
+    pub fn matches(&self, name: &str) -> Option<(String, u64)> {
+        let pattern = self.pattern.to_lowercase();
+        let (prefix, suffix) = pattern.split_once(PART_PLACEHOLDER)?;
+        let literal_before = prefix.rsplit(['*', '?']).next().unwrap_or_default();
+
+        // Jede Ziffernfolge im Namen als Teilnummer probieren (die letzte passende gewinnt)
+        let mut found = None;
+        let mut chars = name.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            if !c.is_ascii_digit() {
+                continue;
+            }
+            let mut end = start + 1;
+            while let Some((offset, _)) = chars.next_if(|(_, next)| next.is_ascii_digit()) {
+                end = offset + 1;
+            }
+            if !glob_match(prefix, &name[..start].to_lowercase()) || !glob_match(suffix, &name[end..].to_lowercase()) {
+                continue;
+            }
+            let Ok(part) = name[start..end].parse() else {
+                continue;
+            };
+            let cut = start.saturating_sub(literal_before.len());
+            let stem = if cut > 0 && name.is_char_boundary(cut) { &name[..cut] } else { &name[..start] };
+            found = Some((format!("{}{}", stem, &name[end..]), part));
+        }
+        found
+    }
+}
+
+/// Dateien, die gemeinsam als ein Dokument hochgeladen werden
+#[derive(Clone, Debug)]
+pub struct FileBatch {
+    /// Dateien mit Änderungszeit, bei Gruppen nach Teilnummer sortiert
+    pub files: Vec<(PathBuf, Option<SystemTime>)>,
+    /// Upload-Name der Gruppe (None = Einzeldatei)
+    pub group_name: Option<String>,
+}
+
+impl FileBatch {
+    pub fn single(path: PathBuf, modified: Option<SystemTime>) -> Self {
+        Self {
+            files: vec![(path, modified)],
+            group_name: None,
+        }
+    }
+
+    /// Erste Datei - steht für die Gruppe in Warteschlange, Prioritäten und Hook
+    pub fn path(&self) -> &Path {
+        &self.files[0].0
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+}
+
+/// Teil einer Gruppe: (Teilnummer, Datei, Änderungszeit)
+type Part = (u64, PathBuf, Option<SystemTime>);
+
+/// Fasst passende PDFs zu Gruppen zusammen
+/// Gruppen, deren Zeitfenster noch läuft, fehlen im Ergebnis und werden im nächsten Durchlauf erneut geprüft
+pub fn batch(candidates: Vec<(PathBuf, Option<SystemTime>)>, config: &GroupingConfig, now: SystemTime) -> Vec<FileBatch> {
+    if config.rules.is_empty() {
+        return candidates.into_iter().map(|(path, modified)| FileBatch::single(path, modified)).collect();
+    }
+
+    let mut batches = Vec::new();
+    // (Ordner, Regel, Gruppenname) → Teile
+    let mut groups: BTreeMap<(PathBuf, usize, String), Vec<Part>> = BTreeMap::new();
+
+    for (path, modified) in candidates {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let is_pdf = imaging::mime_type_for(&path) == "application/pdf";
+        let matched = is_pdf
+            .then(|| config.rules.iter().enumerate().find_map(|(index, rule)| rule.matches(&name).map(|m| (index, m))))
+            .flatten();
+        match matched {
+            Some((rule, (group_name, part))) => {
+                let folder = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                groups.entry((folder, rule, group_name)).or_default().push((part, path, modified));
+            }
+            None => batches.push(FileBatch::single(path, modified)),
+        }
+    }
+
+    for ((_, rule, group_name), mut parts) in groups {
+        let window = Duration::from_secs(config.rules[rule].window_secs);
+        let newest = parts.iter().filter_map(|(_, _, modified)| *modified).max();
+        if newest.is_some_and(|newest| now.duration_since(newest).unwrap_or_default() < window) {
+            continue;
+        }
+
+        parts.sort_by_key(|(part, _, _)| *part);
+        let files: Vec<_> = parts.into_iter().map(|(_, path, modified)| (path, modified)).collect();
+        // Nur ein Teil eingetroffen: wie eine normale Datei behandeln
+        let group_name = (files.len() > 1).then_some(group_name);
+        batches.push(FileBatch { files, group_name });
+    }
+
+    batches
+}
@@ -0,0 +1,44 @@
+// Firmware - Ermittelt Firmware-/Versionsinfos der Scanner während der Discovery
+// Quellen: eSCL ScannerCapabilities (falls vom Gerät geliefert), sonst SNMP v1 sysDescr
+
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::DiscoveredScanner;
+use crate::push_scan::extract_tag;
+use crate::snmp;
+
+/// Versionsinfos eines Scanners
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FirmwareInfo {
+    /// Firmware-Version bzw. Gerätebeschreibung mit Firmware
+    pub version: Option<String>,
+    /// Vom Gerät unterstützte eSCL-Version
+    pub escl_version: Option<String>,
+    /// Quelle der Firmware-Angabe ("escl" oder "snmp")
+    pub source: Option<String>,
+}
+
+/// Wertet die ScannerCapabilities aus und fragt (falls dort keine Firmware steht) SNMP ab
+pub async fn fetch(scanner: &DiscoveredScanner, capabilities: Option<&str>) -> FirmwareInfo {
+    let mut info = FirmwareInfo::default();
+
+    if let Some(capabilities) = capabilities {
+        info.escl_version = extract_tag(capabilities, "Version");
+        info.version = ["FirmwareVersion", "FirmwareRevision", "Firmware"]
+            .iter()
+            .find_map(|tag| extract_tag(capabilities, tag))
+            .filter(|v| !v.is_empty());
+        if info.version.is_some() {
+            info.source = Some("escl".to_string());
+        }
+    }
+
+    if info.version.is_none() {
+        if let Some(descr) = snmp::get_string(&scanner.ip, "public", snmp::SYS_DESCR).await {
+            info.version = Some(descr);
+            info.source = Some("snmp".to_string());
+        }
+    }
+
+    info
+}
@@ -0,0 +1,1763 @@
+// Folder Watcher - Überwacht einen lokalen Ordner und lädt neue Dateien zu DocFlow hoch
+// Nutzt notify-Crate für Filesystem-Events (inotify/FSEvents/ReadDirectoryChanges)
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+use tokio::sync::{Notify, RwLock};
+
+use crate::audit::{self, AuditAction};
+use crate::barcode::{self, BarcodeConfig, DetectedBarcode};
+use crate::counters;
+use crate::dedup::{self, Channel, Check, DedupEntry};
+use crate::multipart;
+use crate::digest::DIGEST;
+use crate::events::{self, EventKind};
+use crate::file_grouping::{self, FileBatch};
+use crate::heic;
+use crate::hooks::{self, HookContext};
+use crate::imaging;
+use crate::job_queue::{ActiveJob, JobState};
+use crate::metrics::{self, METRICS};
+use crate::paths;
+use crate::migrations;
+use crate::orientation::{self, OrientationConfig};
+use crate::pdf;
+use crate::resources;
+use crate::secrets;
+use crate::settings::BridgeSettings;
+use crate::status_report::DeltaReport;
+use crate::supervisor;
+use crate::timestamps::{self, DisplayTime};
+use crate::tray::{self, Activity};
+use crate::upload_progress::UploadKind;
+use crate::rate_limit::RateLimited;
+
+/// Konfiguration für den Folder-Sync
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FolderSyncConfig {
+    pub enabled: bool,
+    pub watch_path: String,
+    pub post_upload_action: PostUploadAction,
+    /// Bilder vor dem Upload aufrecht drehen (PDFs bleiben unverändert)
+    #[serde(default)]
+    pub auto_orient: bool,
+}
+
+impl FolderSyncConfig {
+    /// Gespeicherte Konfiguration aus dem Keyring (ältere Stände werden migriert und zurückgeschrieben)
+    pub fn load() -> Option<Self> {
+        let json = secrets::get("folder_sync_config")?;
+        match migrations::load::<Self>(&json, &migrations::FOLDER_SYNC) {
+            Ok(loaded) => {
+                if loaded.changed(&migrations::FOLDER_SYNC) {
+                    loaded.value.save();
+                }
+                Some(loaded.value)
+            }
+            Err(e) => {
+                // Eintrag bleibt unverändert, bis der Folder-Sync neu eingerichtet wird
+                eprintln!("⚠ {}", e);
+                None
+            }
+        }
+    }
+
+    /// Speichert die Konfiguration im Keyring (mit aktueller Schema-Version)
+    pub fn save(&self) {
+        if let Ok(json) = migrations::store(self, &migrations::FOLDER_SYNC) {
+            let _ = secrets::set("folder_sync_config", &json.to_string());
+        }
+    }
+}
+
+/// Aktion nach erfolgreichem Upload
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum PostUploadAction {
+    MoveToSubfolder,  // In "uploaded" Unterordner verschieben
+    Delete,           // Löschen
+    Keep,             // Nichts tun (für Tests)
+}
+
+/// Ignorier-Regeln für temporäre und versteckte Dateien
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IgnoreConfig {
+    /// Zusätzliche Muster zu den eingebauten (z.B. "*.bak", "scan_*.lock"), * und ? als Platzhalter
+    #[serde(default)]
+    pub extra_patterns: Vec<String>,
+    /// Versteckte Dateien (".name" bzw. Windows-Attribut "versteckt") ignorieren
+    #[serde(default = "default_ignore_hidden")]
+    pub ignore_hidden: bool,
+}
+
+fn default_ignore_hidden() -> bool {
+    true
+}
+
+impl Default for IgnoreConfig {
+    fn default() -> Self {
+        Self {
+            extra_patterns: Vec::new(),
+            ignore_hidden: default_ignore_hidden(),
+        }
+    }
+}
+
+/// Eingebaute Muster für Temp-/Teildateien von Scannern, Browsern und Office
+const BUILTIN_IGNORE_PATTERNS: &[&str] = &[
+    "*.tmp", "*.temp", "*.part", "*.partial", "*.crdownload", "*.download", "*.filepart",
+    "~$*", ".~lock.*", "*.swp", "Thumbs.db", "desktop.ini",
+];
+
+impl IgnoreConfig {
+    /// Ob eine Datei vor der Verarbeitung übersprungen wird
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            return true;
+        };
+
+        if self.ignore_hidden && is_hidden(path, name) {
+            return true;
+        }
+
+        BUILTIN_IGNORE_PATTERNS
+            .iter()
+            .copied()
+            .chain(self.extra_patterns.iter().map(String::as_str))
+            .any(|pattern| glob_match(&pattern.to_lowercase(), &name.to_lowercase()))
+    }
+}
+
+fn is_hidden(path: &Path, name: &str) -> bool {
+    if name.starts_with('.') {
+        return true;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            return metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0;
+        }
+    }
+    let _ = path;
+    false
+}
+
+/// Einfacher Glob-Vergleich (* = beliebig viele Zeichen, ? = genau ein Zeichen)
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            p = star_p + 1;
+            n = star_n + 1;
+            backtrack = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Status des Folder-Watchers
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FolderSyncStatus {
+    pub running: bool,
+    pub watch_path: Option<String>,
+    pub files_uploaded: u32,
+    pub files_pending: u32,
+    pub errors: u32,
+    /// In den quarantine/-Unterordner verschobene Dateien
+    pub files_quarantined: u32,
+    /// Vom Benutzer abgebrochene Uploads (Datei bleibt liegen)
+    pub files_skipped: u32,
+    /// Bereits bekannte Dateien (lokal, per Hash-Prüfung oder vom Server als Duplikat erkannt) - nicht in files_uploaded
+    pub files_duplicate: u32,
+    pub last_upload: Option<String>,
+    pub last_error: Option<String>,
+    /// last_upload in lokaler Zeit und als "vor X" (wird bei get_status berechnet)
+    pub last_upload_display: Option<DisplayTime>,
+    /// Laufzeit seit dem Start des Watchers in Sekunden
+    pub running_secs: Option<u64>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+/// Backend-Response nach Upload
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FolderUploadResponse {
+    pub success: bool,
+    pub job_id: i64,
+    pub filename: String,
+    pub file_size_mb: f64,
+    pub duplicate: bool,
+    pub message: String,
+}
+
+/// Ergebnis einer einzelnen Datei beim Ad-hoc-Upload (Drag & Drop)
+#[derive(Clone, Debug, Serialize)]
+pub struct FileUploadResult {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<FolderUploadResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Upload-fertige Datei (ggf. konvertiert)
+struct UploadData {
+    source: UploadSource,
+    filename: String,
+    mime_type: String,
+}
+
+/// Inhalt im Speicher oder (große Dateien) erst beim Senden blockweise von der Festplatte
+enum UploadSource {
+    Memory(Vec<u8>),
+    File { path: PathBuf, len: u64 },
+}
+
+impl UploadData {
+    /// Daten im Speicher (None bei gestreamten großen Dateien)
+    fn bytes(&self) -> Option<&[u8]> {
+        match &self.source {
+            UploadSource::Memory(data) => Some(data),
+            UploadSource::File { .. } => None,
+        }
+    }
+
+    fn len(&self) -> u64 {
+        match &self.source {
+            UploadSource::Memory(data) => data.len() as u64,
+            UploadSource::File { len, .. } => *len,
+        }
+    }
+
+    /// Multipart-Teil für einen Upload-Versuch (Dateien werden je Versuch neu geöffnet)
+    async fn part(&self, id: &str) -> Result<multipart::Part, Box<dyn std::error::Error + Send + Sync>> {
+        let part = match &self.source {
+            UploadSource::Memory(data) => multipart::Part::bytes(data.clone()),
+            UploadSource::File { path, .. } => multipart::Part::file(path).await?,
+        };
+        Ok(part
+            .file_name(self.filename.clone())
+            .progress(UploadKind::Folder, id)
+            .mime_str(&self.mime_type)?)
+    }
+
+    /// Quer oder kopfüber fotografierte/gescannte Bilder aufrecht drehen
+    /// PDFs (Textebene, Signaturen) und gestreamte große Dateien bleiben unverändert
+    async fn orient(self, config: OrientationConfig) -> Self {
+        let UploadSource::Memory(data) = &self.source else {
+            return self;
+        };
+        if !self.mime_type.starts_with("image/") {
+            return self;
+        }
+        let (data, mime_type) = (data.clone(), self.mime_type.clone());
+        match tokio::task::spawn_blocking(move || orientation::correct_file(&data, &mime_type, &config)).await {
+            Ok(Some((rotated, rotation))) => {
+                println!("↻ {} um {}° gedreht", self.filename, rotation.degrees());
+                Self { source: UploadSource::Memory(rotated), ..self }
+            }
+            _ => self,
+        }
+    }
+
+    /// Barcodes suchen - gestreamte Dateien (höchstens MAX_FILE_SIZE) nur für die Erkennung einlesen
+    async fn detect_barcodes(&self, config: &BarcodeConfig) -> Vec<DetectedBarcode> {
+        if !config.enabled {
+            return Vec::new();
+        }
+        let data = match &self.source {
+            UploadSource::Memory(data) => data.clone(),
+            UploadSource::File { path, .. } => match tokio::fs::read(path).await {
+                Ok(data) => data,
+                Err(e) => {
+                    println!("⚠ Barcode-Erkennung für {} nicht möglich: {}", self.filename, e);
+                    return Vec::new();
+                }
+            },
+        };
+        barcode::detect(vec![(data, self.mime_type.clone())], config.clone()).await
+    }
+}
+
+/// Erlaubte Datei-Endungen
+const ALLOWED_EXTENSIONS: &[&str] = &["pdf", "jpg", "jpeg", "png", "tiff", "tif"];
+
+/// Fehler eines abgebrochenen Uploads (per `is::<UploadCancelled>()` von anderen Fehlern unterscheidbar)
+#[derive(Debug)]
+pub struct UploadCancelled;
+
+impl std::fmt::Display for UploadCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Upload abgebrochen")
+    }
+}
+
+impl std::error::Error for UploadCancelled {}
+
+/// Hash-Prüfung vor dem Upload (GET ?file_hash=… → {"exists": bool, "job_id": …})
+const HASH_CHECK_PATH: &str = "/api/scanner/bridge/folder-upload/exists";
+
+/// DocFlow-Server ohne Hash-Prüfung (404/405/501) - bis zum Neustart nicht erneut fragen
+static HASH_CHECK_UNSUPPORTED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Laufende Uploads: Upload-ID (aus upload-progress) und Herkunftspfad → Abbruch-Signal
+static RUNNING_UPLOADS: Mutex<BTreeMap<String, Arc<Notify>>> = Mutex::new(BTreeMap::new());
+
+/// Zeit für das Einlesen des Ordners bis zum nächsten Heartbeat
+const SCAN_BUDGET: std::time::Duration = std::time::Duration::from_secs(120);
+/// Zeit für eine Datei (Stabilitätsprüfung, Barcodes, Upload mit 3 Versuchen)
+const FILE_BUDGET: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Max. Dateigröße in Bytes (50 MB)
+const MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Blockgröße beim Hashen großer Dateien
+const HASH_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Ab dieser Größe wird die Datei beim Upload von der Festplatte gestreamt statt eingelesen
+const STREAM_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+/// Folder Watcher
+pub struct FolderWatcher {
+    pub config: RwLock<FolderSyncConfig>,
+    api_key: String,
+    docflow_url: String,
+    settings: Arc<RwLock<BridgeSettings>>,
+    status: Arc<RwLock<FolderSyncStatus>>,
+    known_hashes: RwLock<HashSet<String>>,
+    /// Fehlgeschlagene Dateien mit nächstem Versuchszeitpunkt
+    retries: RwLock<HashMap<PathBuf, FileRetry>>,
+    /// Zuletzt an DocFlow gemeldeter Status (nur Änderungen werden gesendet)
+    report: RwLock<DeltaReport>,
+    /// Dateien des laufenden Durchlaufs, die noch hochgeladen werden
+    queue: RwLock<Vec<PathBuf>>,
+    /// Datei, die gerade verarbeitet wird
+    current_file: RwLock<Option<PathBuf>>,
+    /// Lokal gesetzte Prioritäten (höher = früher)
+    priorities: RwLock<HashMap<PathBuf, i32>>,
+    /// Abgebrochene Dateien mit Änderungszeit - werden erst nach Ersetzen wieder verarbeitet
+    cancelled: RwLock<HashMap<PathBuf, Option<SystemTime>>>,
+}
+
+/// Wiederholungs-Stand einer fehlgeschlagenen Datei
+struct FileRetry {
+    attempts: u32,
+    next_try: Instant,
+    /// Änderungszeit beim Fehlschlag - wird die Datei ersetzt, startet sie neu
+    modified: Option<SystemTime>,
+}
+
+/// Wartezeiten nach dem 1., 2., 3., ... Fehlschlag (danach bleibt es beim letzten Wert)
+const RETRY_SCHEDULE_SECS: &[u64] = &[60, 300, 1800, 7200, 21600];
+
+impl FolderWatcher {
+    pub fn new(
+        config: FolderSyncConfig,
+        api_key: String,
+        docflow_url: String,
+        settings: Arc<RwLock<BridgeSettings>>,
+    ) -> Self {
+        Self {
+            config: RwLock::new(config),
+            api_key,
+            docflow_url,
+            settings,
+            status: Arc::new(RwLock::new(FolderSyncStatus::default())),
+            known_hashes: RwLock::new(HashSet::new()),
+            retries: RwLock::new(HashMap::new()),
+            report: RwLock::new(DeltaReport::new()),
+            queue: RwLock::new(Vec::new()),
+            current_file: RwLock::new(None),
+            priorities: RwLock::new(HashMap::new()),
+            cancelled: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Prüft ob eine Datei eine erlaubte Endung hat (HEIC nur mit Feature "heic")
+    fn is_allowed_extension(path: &Path) -> bool {
+        let allowed = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ALLOWED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+            .unwrap_or(false);
+        allowed || (heic::is_supported() && heic::is_heic(path))
+    }
+
+    /// Liest eine Datei für den Upload (HEIC-Fotos werden dabei nach JPEG konvertiert)
+    async fn read_upload_data(path: &Path) -> Result<UploadData, Box<dyn std::error::Error + Send + Sync>> {
+        let len = tokio::fs::metadata(path).await?.len();
+        if len > STREAM_THRESHOLD && !heic::is_heic(path) {
+            return Ok(Self::streamed_upload_data(path, len));
+        }
+        Self::upload_data(path, tokio::fs::read(path).await?).await
+    }
+
+    /// Wie read_upload_data, dazu der SHA256 der Originaldatei aus demselben Lesevorgang
+    /// Große Dateien werden erst beim Senden gestreamt - hier nur blockweise gehasht
+    async fn read_upload_data_with_hash(
+        path: &Path,
+    ) -> Result<(UploadData, String), Box<dyn std::error::Error + Send + Sync>> {
+        let len = tokio::fs::metadata(path).await?.len();
+        if len > STREAM_THRESHOLD && !heic::is_heic(path) {
+            return Ok((Self::streamed_upload_data(path, len), Self::compute_file_hash(path).await?));
+        }
+        let data = tokio::fs::read(path).await?;
+        let file_hash = dedup::hash(&data);
+        Ok((Self::upload_data(path, data).await?, file_hash))
+    }
+
+    fn streamed_upload_data(path: &Path, len: u64) -> UploadData {
+        UploadData {
+            source: UploadSource::File { path: path.to_path_buf(), len },
+            filename: paths::upload_name(&path.file_name().unwrap_or_default().to_string_lossy()),
+            mime_type: imaging::mime_type_for(path).to_string(),
+        }
+    }
+
+    /// Upload aus dem gelesenen Dateiinhalt
+    async fn upload_data(path: &Path, data: Vec<u8>) -> Result<UploadData, Box<dyn std::error::Error + Send + Sync>> {
+        let filename = paths::upload_name(&path.file_name().unwrap_or_default().to_string_lossy());
+        if heic::is_heic(path) {
+            let jpeg = tokio::task::spawn_blocking(move || heic::convert_to_jpeg(&data)).await??;
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            println!("🖼 HEIC nach JPEG konvertiert: {}", path.display());
+            return Ok(UploadData {
+                source: UploadSource::Memory(jpeg),
+                filename: paths::upload_name(&format!("{}.jpg", stem)),
+                mime_type: "image/jpeg".to_string(),
+            });
+        }
+
+        Ok(UploadData {
+            source: UploadSource::Memory(data),
+            filename,
+            mime_type: imaging::mime_type_for(path).to_string(),
+        })
+    }
+
+    /// Berechnet SHA256-Hash einer Datei
+    /// Liest blockweise in einem Blocking-Thread, damit große Dateien den Watcher nicht ausbremsen
+    async fn compute_file_hash(path: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || -> std::io::Result<String> {
+            let mut file = std::fs::File::open(&path)?;
+            let mut hasher = Sha256::new();
+            let mut buffer = vec![0u8; HASH_CHUNK_SIZE];
+            loop {
+                let read = file.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+            }
+            Ok(format!("{:x}", hasher.finalize()))
+        })
+        .await?
+        .map_err(Into::into)
+    }
+
+    /// Wartet bis eine Datei stabil ist (nicht mehr geschrieben wird)
+    async fn wait_for_file_stable(path: &Path) -> bool {
+        let mut sizes = Vec::new();
+        for _ in 0..3 {
+            match tokio::fs::metadata(path).await {
+                Ok(meta) => sizes.push(meta.len()),
+                Err(_) => return false,
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+        }
+        sizes.len() == 3 && sizes[0] == sizes[1] && sizes[1] == sizes[2] && sizes[0] > 0
+    }
+
+    /// Lädt eine Datei zum DocFlow-Server hoch
+    async fn upload_file(
+        &self,
+        path: &Path,
+        upload: &UploadData,
+        file_hash: &str,
+        barcodes: &[DetectedBarcode],
+        duplicate_of: Option<&DedupEntry>,
+    ) -> Result<FolderUploadResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let _uploading = tray::activity(Activity::Uploading);
+        let original_path = path.to_string_lossy();
+        upload_to_docflow(&self.docflow_url, &self.api_key, upload, file_hash, &original_path, barcodes, duplicate_of).await
+    }
+
+    /// Liest die Teile einer Gruppe und führt sie zu einem PDF zusammen
+    async fn read_group_data(
+        batch: &FileBatch,
+        group_name: &str,
+    ) -> Result<UploadData, Box<dyn std::error::Error + Send + Sync>> {
+        let mut documents = Vec::with_capacity(batch.files.len());
+        for path in batch.paths() {
+            documents.push(tokio::fs::read(path).await?);
+        }
+        let merged = tokio::task::spawn_blocking(move || pdf::merge(&documents)).await??;
+        println!("📎 {} Teile zu {} zusammengeführt", batch.files.len(), group_name);
+        Ok(UploadData {
+            source: UploadSource::Memory(merged),
+            filename: paths::upload_name(group_name),
+            mime_type: "application/pdf".to_string(),
+        })
+    }
+
+    /// Verarbeitet eine einzelne Datei bzw. eine Datei-Gruppe (ein Dokument, ein Job in DocFlow)
+    async fn process_batch(&self, batch: &FileBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let path = batch.path();
+        for member in batch.paths() {
+            // Extension prüfen
+            if !Self::is_allowed_extension(member) {
+                return Ok(()); // Ignorieren, kein Fehler
+            }
+
+            // Dateigröße prüfen
+            let metadata = tokio::fs::metadata(member).await?;
+            if metadata.len() > MAX_FILE_SIZE {
+                return Err(format!(
+                    "Datei zu groß: {} MB (max {} MB)",
+                    metadata.len() / 1024 / 1024,
+                    MAX_FILE_SIZE / 1024 / 1024
+                ).into());
+            }
+
+            // Warten bis Datei stabil ist
+            if !Self::wait_for_file_stable(member).await {
+                return Err("Datei nicht stabil (wird noch geschrieben?)".into());
+            }
+
+            // Verschlüsselte/beschädigte PDFs nicht hochladen, sondern in Quarantäne
+            // (die übrigen Teile einer Gruppe folgen im nächsten Durchlauf ohne sie)
+            if imaging::mime_type_for(member) == "application/pdf" {
+                let pdf_path = member.to_path_buf();
+                if let Err(reason) = tokio::task::spawn_blocking(move || pdf::validate_file(&pdf_path)).await? {
+                    self.quarantine(member, &reason).await?;
+                    return Ok(());
+                }
+            }
+        }
+
+        // SHA256 berechnen - bei Gruppen über das zusammengeführte Dokument
+        let (file_hash, group_upload) = match &batch.group_name {
+            Some(group_name) => {
+                let upload = Self::read_group_data(batch, group_name).await?;
+                (dedup::hash(upload.bytes().unwrap_or_default()), Some(upload))
+            }
+            None => (Self::compute_file_hash(path).await?, None),
+        };
+
+        // Lokal auf Duplikate prüfen
+        {
+            let hashes = self.known_hashes.read().await;
+            if hashes.contains(&file_hash) {
+                println!("⏭ Datei bereits hochgeladen (Hash bekannt): {}", path.display());
+                drop(hashes);
+                self.count_duplicate().await;
+                // Trotzdem verschieben/löschen
+                self.post_upload_actions(batch).await?;
+                return Ok(());
+            }
+        }
+
+        // Schon als Scan hochgeladen (z.B. per DocFlow-Job gescannt und zusätzlich im Ordner abgelegt)?
+        let duplicate_of = match dedup::check(&file_hash, Channel::Folder) {
+            Check::New => None,
+            Check::Skip(earlier) => {
+                println!("⏭ Bereits {} hochgeladen: {}", earlier.describe(), paths::display(path));
+                self.count_duplicate().await;
+                self.post_upload_actions(batch).await?;
+                return Ok(());
+            }
+            Check::Flag(earlier) => {
+                println!("⚠ Bereits {} hochgeladen, wird als Duplikat markiert: {}", earlier.describe(), paths::display(path));
+                Some(earlier)
+            }
+        };
+
+        // Barcodes suchen (falls aktiviert)
+        let (barcode_config, orientation_config) = {
+            let settings = self.settings.read().await;
+            (settings.barcode.clone(), settings.orientation.clone())
+        };
+        let mut upload = match group_upload {
+            Some(upload) => upload,
+            None => Self::read_upload_data(path).await?,
+        };
+        if self.config.read().await.auto_orient {
+            upload = upload.orient(orientation_config).await;
+        }
+        let barcodes = upload.detect_barcodes(&barcode_config).await;
+
+        if !barcodes.is_empty() {
+            println!("🏷 {} Barcode(s) erkannt in {}", barcodes.len(), path.display());
+        }
+
+        // Während der Vorbereitung abgebrochen?
+        if self.cancelled.read().await.contains_key(path) {
+            return Err(UploadCancelled.into());
+        }
+
+        // Hochladen
+        match &batch.group_name {
+            Some(group_name) => println!("📤 Lade hoch: {} ({} Teile)", group_name, batch.files.len()),
+            None => println!("📤 Lade hoch: {}", path.display()),
+        }
+        let result = self.upload_file(path, &upload, &file_hash, &barcodes, duplicate_of.as_ref()).await?;
+
+        let mut details = serde_json::json!({
+            "path": paths::display(path),
+            "file_hash": file_hash,
+            "job_id": result.job_id,
+            "duplicate": result.duplicate,
+        });
+        if batch.group_name.is_some() {
+            details["parts"] = batch.paths().map(paths::display).collect::<Vec<_>>().into();
+        }
+        audit::record(AuditAction::FileUploaded, details);
+
+        // Hash merken (auch kanalübergreifend für Scan-Uploads)
+        dedup::record(&file_hash, Channel::Folder, &paths::display(path));
+        {
+            let mut hashes = self.known_hashes.write().await;
+            hashes.insert(file_hash);
+        }
+
+        // Status aktualisieren - Duplikate zählen nicht als Upload
+        if result.duplicate {
+            println!("⏭ Server: Duplikat (Job #{}, {})", result.job_id, result.message);
+            metrics::inc(&METRICS.folder_duplicates);
+            self.count_duplicate().await;
+        } else {
+            println!("✓ Hochgeladen: {} → Job #{} ({})", result.filename, result.job_id, result.message);
+            metrics::inc(&METRICS.folder_uploads);
+            metrics::inc(&DIGEST.uploaded);
+            events::record(EventKind::Upload, format!("{} hochgeladen (Job #{})", result.filename, result.job_id));
+            counters::record(|c| c.files_uploaded += 1);
+            let mut status = self.status.write().await;
+            status.files_uploaded += 1;
+            status.last_upload = Some(chrono::Utc::now().to_rfc3339());
+        }
+
+        // Post-Upload-Hook (vor der Post-Upload-Aktion, Datei liegt noch am Originalpfad - bei Gruppen der erste Teil)
+        let hook_config = self.settings.read().await.hooks.clone();
+        hooks::run_post_upload(
+            &hook_config,
+            &HookContext {
+                source: "folder",
+                file_path: path,
+                job_id: &result.job_id.to_string(),
+                scanner: None,
+                result: if result.duplicate { "duplicate" } else { "uploaded" },
+            },
+        )
+        .await;
+
+        // Post-Upload-Aktion
+        self.post_upload_actions(batch).await
+    }
+
+    /// Zählt eine bereits bekannte Datei (Status, Gesamtzahlen, Tageszusammenfassung)
+    async fn count_duplicate(&self) {
+        metrics::inc(&DIGEST.duplicates);
+        counters::record(|c| c.files_duplicate += 1);
+        self.status.write().await.files_duplicate += 1;
+    }
+
+    /// Post-Upload-Aktion für alle Dateien einer Gruppe
+    async fn post_upload_actions(&self, batch: &FileBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for path in batch.paths() {
+            self.post_upload_action(path).await?;
+        }
+        Ok(())
+    }
+
+    /// Führt die konfigurierte Post-Upload-Aktion aus
+    async fn post_upload_action(&self, path: &Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let config = self.config.read().await;
+        match config.post_upload_action {
+            PostUploadAction::MoveToSubfolder => {
+                let parent = path.parent().unwrap_or(Path::new("."));
+                let uploaded_dir = parent.join("uploaded");
+                tokio::fs::create_dir_all(&uploaded_dir).await?;
+                let dest = uploaded_dir.join(path.file_name().unwrap_or_default());
+                tokio::fs::rename(path, &dest).await?;
+                println!("  → Verschoben nach: {}", dest.display());
+                audit::record(
+                    AuditAction::FileMoved,
+                    serde_json::json!({ "from": paths::display(path), "to": paths::display(&dest) }),
+                );
+            }
+            PostUploadAction::Delete => {
+                tokio::fs::remove_file(path).await?;
+                println!("  → Gelöscht");
+                audit::record(AuditAction::FileDeleted, serde_json::json!({ "path": paths::display(path) }));
+            }
+            PostUploadAction::Keep => {
+                // Nichts tun
+            }
+        }
+        Ok(())
+    }
+
+    /// Ob eine Datei (erneut) verarbeitet werden soll - ersetzte Dateien starten ohne Wartezeit
+    async fn retry_due(&self, path: &Path, modified: Option<SystemTime>) -> bool {
+        let mut retries = self.retries.write().await;
+        match retries.get(path) {
+            None => true,
+            Some(retry) if retry.modified != modified => {
+                retries.remove(path);
+                true
+            }
+            Some(retry) => Instant::now() >= retry.next_try,
+        }
+    }
+
+    /// Plant den nächsten Versuch (1 min, 5 min, 30 min, 2 h, 6 h) → Anzahl Fehlversuche
+    async fn schedule_retry(&self, path: &Path, modified: Option<SystemTime>) -> u32 {
+        let mut retries = self.retries.write().await;
+        let retry = retries.entry(path.to_path_buf()).or_insert(FileRetry {
+            attempts: 0,
+            next_try: Instant::now(),
+            modified,
+        });
+        retry.attempts += 1;
+        let index = (retry.attempts as usize - 1).min(RETRY_SCHEDULE_SECS.len() - 1);
+        retry.next_try = Instant::now() + std::time::Duration::from_secs(RETRY_SCHEDULE_SECS[index]);
+        retry.attempts
+    }
+
+    /// Ob eine abgebrochene Datei wieder verarbeitet werden darf (nur wenn sie ersetzt wurde)
+    async fn cancel_expired(&self, path: &Path, modified: Option<SystemTime>) -> bool {
+        let mut cancelled = self.cancelled.write().await;
+        match cancelled.get(path) {
+            None => true,
+            Some(at) if *at != modified => {
+                cancelled.remove(path);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    /// Laufender Upload, wartende und zurückgestellte Dateien
+    pub async fn active_jobs(&self) -> Vec<ActiveJob> {
+        let priorities = self.priorities.read().await;
+        let entry = |path: &Path, state: JobState, message: Option<String>| ActiveJob {
+            kind: UploadKind::Folder,
+            id: path.to_string_lossy().to_string(),
+            name: paths::nfc(&path.file_name().unwrap_or_default().to_string_lossy()),
+            state,
+            priority: priorities.get(path).copied().unwrap_or(0),
+            message,
+        };
+
+        let current = self.current_file.read().await.clone();
+        let queue = self.queue.read().await;
+        let mut result = Vec::new();
+        if let Some(path) = current.as_ref() {
+            result.push(entry(path, JobState::Running, None));
+        }
+        for path in queue.iter() {
+            result.push(entry(path, JobState::Queued, None));
+        }
+        // Fällige Wiederholungen stehen schon in der Warteschlange
+        let scheduled = self.retries.read().await;
+        for (path, retry) in scheduled.iter().filter(|(p, _)| current.as_ref() != Some(*p) && !queue.contains(*p)) {
+            let wait_secs = retry.next_try.saturating_duration_since(Instant::now()).as_secs();
+            let message = format!("{}. Versuch fehlgeschlagen, nächster in {} s", retry.attempts, wait_secs);
+            result.push(entry(path, JobState::Retrying, Some(message)));
+        }
+        result
+    }
+
+    /// Bricht eine wartende oder laufende Datei ab - sie bleibt im Ordner liegen, bis sie ersetzt wird
+    pub async fn cancel_file(&self, path: &Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let path = &paths::extended(path);
+        let modified = tokio::fs::metadata(path).await.ok().and_then(|m| m.modified().ok());
+
+        // Laufende Datei: vor dem Upload greift die Markierung, während des Uploads das Abbruch-Signal
+        if self.current_file.read().await.as_deref() == Some(path) {
+            self.cancelled.write().await.insert(path.to_path_buf(), modified);
+            cancel_upload(&path.to_string_lossy());
+            return Ok(());
+        }
+
+        let queued = {
+            let mut queue = self.queue.write().await;
+            let before = queue.len();
+            queue.retain(|p| p != path);
+            queue.len() != before
+        };
+        let retrying = self.retries.write().await.remove(path).is_some();
+        if !queued && !retrying {
+            return Err(format!("{} wartet nicht auf den Upload", path.display()).into());
+        }
+
+        self.cancelled.write().await.insert(path.to_path_buf(), modified);
+        println!("🚫 Upload abgebrochen: {}", path.display());
+        Ok(())
+    }
+
+    /// Setzt die Priorität einer wartenden Datei (wirkt im laufenden und in späteren Durchläufen)
+    pub async fn set_file_priority(
+        &self,
+        path: &Path,
+        priority: i32,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let path = &paths::extended(path);
+        let watch_path = paths::extended(Path::new(&self.config.read().await.watch_path));
+        if !path.starts_with(&watch_path) || !path.is_file() {
+            return Err(format!("{} liegt nicht im überwachten Ordner", path.display()).into());
+        }
+        self.priorities.write().await.insert(path.to_path_buf(), priority);
+
+        // Restliche Warteschlange des laufenden Durchlaufs neu ordnen
+        let priorities = self.priorities.read().await;
+        self.queue
+            .write()
+            .await
+            .sort_by_key(|p| -priorities.get(p).copied().unwrap_or(0));
+        Ok(())
+    }
+
+    /// Verschiebt eine nicht verarbeitbare Datei nach quarantine/ und legt den Grund daneben ab
+    async fn quarantine(&self, path: &Path, reason: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let parent = path.parent().unwrap_or(Path::new("."));
+        let quarantine_dir = parent.join("quarantine");
+        tokio::fs::create_dir_all(&quarantine_dir).await?;
+
+        let file_name = path.file_name().unwrap_or_default();
+        let dest = quarantine_dir.join(file_name);
+        tokio::fs::rename(path, &dest).await?;
+        tokio::fs::write(
+            quarantine_dir.join(format!("{}.reason.txt", file_name.to_string_lossy())),
+            format!("{}\n{}\n", chrono::Utc::now().to_rfc3339(), reason),
+        )
+        .await?;
+
+        println!("🚫 Quarantäne: {} ({})", path.display(), reason);
+        audit::record(
+            AuditAction::FileQuarantined,
+            serde_json::json!({ "path": paths::display(path), "reason": reason }),
+        );
+        events::record(EventKind::Error, format!("{} in Quarantäne: {}", file_name.to_string_lossy(), reason));
+        counters::record(|c| c.files_quarantined += 1);
+        let mut status = self.status.write().await;
+        status.files_quarantined += 1;
+        metrics::inc(&DIGEST.quarantined);
+        status.last_error = Some(format!("{}: {}", file_name.to_string_lossy(), reason));
+        Ok(())
+    }
+
+    /// Meldet den Status an DocFlow (nur Änderungen seit der letzten Meldung, sonst Heartbeat)
+    async fn report_status_to_server(&self) {
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/folder-sync-status", self.docflow_url);
+
+        let current = {
+            let status = self.status.read().await;
+            let config = self.config.read().await;
+            let serde_json::Value::Object(current) = serde_json::json!({
+                "folder_sync_enabled": config.enabled,
+                "watched_folder": config.watch_path,
+                "files_uploaded": status.files_uploaded,
+                "files_duplicate": status.files_duplicate,
+                "errors": status.errors,
+                "files_quarantined": status.files_quarantined,
+                "last_sync_at": status.last_upload,
+            }) else {
+                return;
+            };
+            current
+        };
+
+        let heartbeat = std::time::Duration::from_secs(self.settings.read().await.intervals.status_heartbeat_secs);
+        let Some(body) = self.report.read().await.next(&current, heartbeat) else {
+            return;
+        };
+
+        let result = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(10))
+            .send_limited()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => self.report.write().await.confirm(current),
+            _ => self.report.write().await.reset(),
+        }
+    }
+
+    /// Startet den Folder-Watcher (Polling-basiert für maximale Kompatibilität)
+    /// Nutzt Polling statt notify-Events, da SMB-Shares keine Events generieren
+    pub async fn start_watching(self: Arc<Self>) {
+        // Extended-Length-Pfad: Dateien unter Windows auch jenseits von 260 Zeichen erreichbar
+        let config = self.config.read().await;
+        let watch_path = paths::extended(Path::new(&config.watch_path));
+        drop(config);
+
+        if !watch_path.exists() {
+            eprintln!("❌ Ordner existiert nicht: {}", paths::display(&watch_path));
+            let mut status = self.status.write().await;
+            status.last_error = Some(format!("Ordner nicht gefunden: {}", paths::display(&watch_path)));
+            return;
+        }
+
+        {
+            let mut status = self.status.write().await;
+            status.running = true;
+            status.started = Some(Instant::now());
+            status.watch_path = Some(paths::display(&watch_path));
+        }
+
+        println!("📁 Folder-Sync gestartet: {}", paths::display(&watch_path));
+
+        // Hauptschleife: Polling im konfigurierten Intervall
+        let mut last_report: Option<Instant> = None;
+        loop {
+            // Stop-Flag prüfen
+            {
+                let status = self.status.read().await;
+                if !status.running {
+                    break;
+                }
+            }
+
+            let poll_secs = self.settings.read().await.intervals.folder_poll_secs;
+            supervisor::beat(supervisor::FOLDER_WATCHER, std::time::Duration::from_secs(poll_secs) + SCAN_BUDGET);
+
+            // Bei Speicherknappheit keine Dateien puffern/hochladen (nächster Zyklus prüft erneut)
+            let resource_config = self.settings.read().await.resources.clone();
+            if let Err(reason) = resources::check(&resource_config) {
+                self.status.write().await.last_error = Some(reason);
+                let poll_secs = self.settings.read().await.intervals.folder_poll_secs;
+                tokio::time::sleep(tokio::time::Duration::from_secs(poll_secs)).await;
+                continue;
+            }
+
+            // Ordner scannen
+            match tokio::fs::read_dir(&watch_path).await {
+                Ok(mut entries) => {
+                    let mut pending_count = 0u32;
+                    let ignore = self.settings.read().await.folder_ignore.clone();
+                    let mut candidates: Vec<(PathBuf, Option<SystemTime>)> = Vec::new();
+
+                    while let Ok(Some(entry)) = entries.next_entry().await {
+                        let path = entry.path();
+
+                        // Nur Dateien, keine Unterordner (uploaded/ ignorieren)
+                        if !path.is_file() {
+                            continue;
+                        }
+
+                        // uploaded/ Ordner überspringen
+                        if path.parent()
+                            .and_then(|p| p.file_name())
+                            .and_then(|n| n.to_str())
+                            == Some("uploaded")
+                        {
+                            continue;
+                        }
+
+                        // Temp-, Teil- und versteckte Dateien gar nicht erst prüfen
+                        if ignore.is_ignored(&path) {
+                            continue;
+                        }
+
+                        if !Self::is_allowed_extension(&path) {
+                            continue;
+                        }
+
+                        // Abgebrochene Dateien bleiben liegen, bis sie ersetzt werden
+                        let modified = entry.metadata().await.ok().and_then(|m| m.modified().ok());
+                        if !self.cancel_expired(&path, modified).await {
+                            continue;
+                        }
+
+                        pending_count += 1;
+
+                        // Fehlgeschlagene Dateien erst zum geplanten Zeitpunkt erneut versuchen
+                        if !self.retry_due(&path, modified).await {
+                            continue;
+                        }
+
+                        candidates.push((path, modified));
+                    }
+
+                    // Teil-PDFs zu Gruppen zusammenfassen (Gruppen im Zeitfenster warten auf weitere Teile)
+                    let grouping = self.settings.read().await.folder_grouping.clone();
+                    let mut batches = file_grouping::batch(candidates, &grouping, SystemTime::now());
+
+                    // Höhere Priorität zuerst, sonst Reihenfolge im Ordner
+                    {
+                        let priorities = self.priorities.read().await;
+                        batches.sort_by_key(|batch| -priorities.get(batch.path()).copied().unwrap_or(0));
+                    }
+                    *self.queue.write().await = batches.iter().map(|batch| batch.path().to_path_buf()).collect();
+
+                    for batch in batches {
+                        let path = batch.path().to_path_buf();
+                        // Inzwischen abgebrochen?
+                        {
+                            let mut queue = self.queue.write().await;
+                            let Some(index) = queue.iter().position(|p| p == &path) else {
+                                continue;
+                            };
+                            queue.remove(index);
+                        }
+
+                        // Datei verarbeiten (Upload inkl. Wiederholungen darf dauern)
+                        supervisor::beat(supervisor::FOLDER_WATCHER, FILE_BUDGET);
+                        *self.current_file.write().await = Some(path.clone());
+                        let result = self.process_batch(&batch).await;
+                        *self.current_file.write().await = None;
+                        match result {
+                            Ok(()) => {
+                                self.retries.write().await.retain(|retry, _| !batch.paths().any(|p| p == retry));
+                                tray::clear_error();
+                            }
+                            Err(e) if e.is::<UploadCancelled>() => {
+                                // Übersprungen, kein neuer Versuch bis die Datei ersetzt wird
+                                println!("⏭ Upload abgebrochen, Datei bleibt liegen: {}", path.display());
+                                self.retries.write().await.retain(|retry, _| !batch.paths().any(|p| p == retry));
+                                self.cancelled.write().await.extend(batch.files.iter().cloned());
+                                self.status.write().await.files_skipped += 1;
+                                counters::record(|c| c.files_skipped += 1);
+                                events::record(
+                                    EventKind::Upload,
+                                    format!("{}: Upload abgebrochen", path.file_name().unwrap_or_default().to_string_lossy()),
+                                );
+                            }
+                            Err(e) => {
+                                let mut attempts = 0;
+                                for (member, modified) in &batch.files {
+                                    attempts = attempts.max(self.schedule_retry(member, *modified).await);
+                                }
+                                eprintln!("❌ Fehler bei {} (Versuch {}): {}", path.display(), attempts, e);
+                                tray::report_error(&e.to_string());
+                                if attempts == 1 {
+                                    events::record(
+                                        EventKind::Error,
+                                        format!("{}: {}", path.file_name().unwrap_or_default().to_string_lossy(), e),
+                                    );
+                                }
+                                metrics::inc(&METRICS.folder_upload_errors);
+                                let mut status = self.status.write().await;
+                                // Dauerhaft fehlschlagende Dateien nur einmal zählen
+                                if attempts == 1 {
+                                    counters::record(|c| c.folder_errors += 1);
+                                    status.errors += 1;
+                                    metrics::inc(&DIGEST.failed);
+                                }
+                                status.last_error = Some(format!(
+                                    "{}: {}", path.file_name().unwrap_or_default().to_string_lossy(), e
+                                ));
+                            }
+                        }
+                    }
+
+                    // Einträge verschwundener Dateien verwerfen
+                    self.retries.write().await.retain(|path, _| path.exists());
+                    self.priorities.write().await.retain(|path, _| path.exists());
+                    self.cancelled.write().await.retain(|path, _| path.exists());
+
+                    {
+                        let mut status = self.status.write().await;
+                        status.files_pending = pending_count;
+                    }
+                    tray::PENDING_FILES.store(pending_count as usize, std::sync::atomic::Ordering::Relaxed);
+                }
+                Err(e) => {
+                    eprintln!("❌ Ordner nicht lesbar: {}", e);
+                    let mut status = self.status.write().await;
+                    status.last_error = Some(format!("Ordner nicht lesbar: {}", e));
+                    status.errors += 1;
+                    counters::record(|c| c.folder_errors += 1);
+                }
+            }
+
+            // Status an Server melden (Standard alle 30 Sekunden)
+            let intervals = self.settings.read().await.intervals.clone();
+            let report_due = last_report
+                .is_none_or(|t| t.elapsed() >= std::time::Duration::from_secs(intervals.status_report_secs));
+            if report_due {
+                self.report_status_to_server().await;
+                last_report = Some(Instant::now());
+            }
+
+            // Warten bis zur nächsten Ordner-Prüfung (Standard 5 Sekunden)
+            tokio::time::sleep(tokio::time::Duration::from_secs(intervals.folder_poll_secs)).await;
+        }
+
+        println!("🛑 Folder-Sync gestoppt");
+
+        // Letzten Status melden
+        self.report_status_to_server().await;
+    }
+
+    /// Stoppt den Watcher
+    pub async fn stop(&self) {
+        let mut status = self.status.write().await;
+        status.running = false;
+        status.started = None;
+        tray::PENDING_FILES.store(0, std::sync::atomic::Ordering::Relaxed);
+
+        // Disabled-Status an Server melden
+        let config = self.config.read().await;
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/folder-sync-status", self.docflow_url);
+        let body = serde_json::json!({
+            "folder_sync_enabled": false,
+            "watched_folder": config.watch_path,
+            "files_uploaded": status.files_uploaded,
+            "errors": status.errors,
+        });
+        drop(config);
+        drop(status);
+
+        let _ = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(5))
+            .send_limited()
+            .await;
+    }
+
+    /// Gibt aktuellen Status zurück (mit Anzeige-Zeiten)
+    pub async fn get_status(&self) -> FolderSyncStatus {
+        let mut status = self.status.read().await.clone();
+        status.last_upload_display = status.last_upload.as_deref().and_then(timestamps::display);
+        status.running_secs = status.started.map(|s| s.elapsed().as_secs());
+        status
+    }
+}
+
+/// Lädt Daten ohne Datei im überwachten Ordner hoch (Zwischenablage, Drag & Drop)
+pub async fn upload_bytes(
+    docflow_url: &str,
+    api_key: &str,
+    data: Vec<u8>,
+    filename: &str,
+    mime_type: &str,
+    original_path: &str,
+) -> Result<FolderUploadResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let (data, file_hash) = tokio::task::spawn_blocking(move || {
+        let hash = format!("{:x}", Sha256::digest(&data));
+        (data, hash)
+    })
+    .await?;
+    let upload = UploadData {
+        source: UploadSource::Memory(data),
+        filename: paths::upload_name(filename),
+        mime_type: mime_type.to_string(),
+    };
+
+    let _uploading = tray::activity(Activity::Uploading);
+    let result = upload_to_docflow(docflow_url, api_key, &upload, &file_hash, original_path, &[], None).await?;
+    if !result.duplicate {
+        metrics::inc(&METRICS.folder_uploads);
+        events::record(EventKind::Upload, format!("{} hochgeladen (Job #{})", result.filename, result.job_id));
+    }
+    Ok(result)
+}
+
+/// Lädt beliebige Dateien hoch (Drag & Drop ins Bridge-Fenster)
+/// Gleiche Prüfungen wie der Folder-Sync, Dateien bleiben aber unverändert liegen
+pub async fn upload_files(
+    docflow_url: &str,
+    api_key: &str,
+    paths: &[PathBuf],
+    barcode_config: &BarcodeConfig,
+) -> Vec<FileUploadResult> {
+    let mut seen_hashes = HashSet::new();
+    let mut results = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let outcome =
+            upload_dropped_file(docflow_url, api_key, &paths::extended(path), barcode_config, &mut seen_hashes).await;
+        match &outcome {
+            Err(e) if e.is::<UploadCancelled>() => println!("⏭ Upload abgebrochen: {}", path.display()),
+            Err(e) => {
+                eprintln!("✗ Upload fehlgeschlagen: {}: {}", path.display(), e);
+                metrics::inc(&METRICS.folder_upload_errors);
+            }
+            Ok(_) => {}
+        }
+        results.push(FileUploadResult {
+            path: path.to_string_lossy().to_string(),
+            error: outcome.as_ref().err().map(|e| e.to_string()),
+            response: outcome.ok(),
+        });
+    }
+    results
+}
+
+async fn upload_dropped_file(
+    docflow_url: &str,
+    api_key: &str,
+    path: &Path,
+    barcode_config: &BarcodeConfig,
+    seen_hashes: &mut HashSet<String>,
+) -> Result<FolderUploadResponse, Box<dyn std::error::Error + Send + Sync>> {
+    if !FolderWatcher::is_allowed_extension(path) {
+        return Err(format!("Dateityp nicht unterstützt (erlaubt: {})", ALLOWED_EXTENSIONS.join(", ")).into());
+    }
+
+    let metadata = tokio::fs::metadata(path).await?;
+    if !metadata.is_file() {
+        return Err("Keine Datei".into());
+    }
+    if metadata.len() > MAX_FILE_SIZE {
+        return Err(format!(
+            "Datei zu groß: {} MB (max {} MB)",
+            metadata.len() / 1024 / 1024,
+            MAX_FILE_SIZE / 1024 / 1024
+        )
+        .into());
+    }
+
+    // Einmal lesen: Inhalt und Hash (nur gestreamte große Dateien werden beim Senden erneut gelesen)
+    let (upload, file_hash) = FolderWatcher::read_upload_data_with_hash(path).await?;
+    if upload.mime_type == "application/pdf" {
+        match upload.bytes() {
+            Some(data) => pdf::validate(data)?,
+            None => {
+                let pdf_path = path.to_path_buf();
+                tokio::task::spawn_blocking(move || pdf::validate_file(&pdf_path)).await??;
+            }
+        }
+    }
+
+    // Dieselbe Datei mehrfach abgelegt → nur einmal hochladen (DocFlow erkennt Duplikate über den Hash)
+    if !seen_hashes.insert(file_hash.clone()) {
+        return Err("Datei mehrfach ausgewählt".into());
+    }
+
+    // Abgelegte Dateien zählen wie Folder-Sync: schon als Scan hochgeladen?
+    let duplicate_of = match dedup::check(&file_hash, Channel::Folder) {
+        Check::New => None,
+        Check::Skip(earlier) => {
+            metrics::inc(&METRICS.folder_duplicates);
+            return Err(format!("Bereits {} hochgeladen", earlier.describe()).into());
+        }
+        Check::Flag(earlier) => Some(earlier),
+    };
+
+    let barcodes = upload.detect_barcodes(barcode_config).await;
+
+    println!("📤 Lade hoch (Drag & Drop): {}", path.display());
+    let _uploading = tray::activity(Activity::Uploading);
+    let result = upload_to_docflow(
+        docflow_url,
+        api_key,
+        &upload,
+        &file_hash,
+        &path.to_string_lossy(),
+        &barcodes,
+        duplicate_of.as_ref(),
+    )
+    .await?;
+    dedup::record(&file_hash, Channel::Folder, &paths::display(path));
+    audit::record(
+        AuditAction::FileUploaded,
+        serde_json::json!({
+            "path": paths::display(path),
+            "file_hash": file_hash,
+            "job_id": result.job_id,
+            "duplicate": result.duplicate,
+            "source": "drop",
+        }),
+    );
+
+    if result.duplicate {
+        println!("⏭ Server: Duplikat (Job #{})", result.job_id);
+        metrics::inc(&METRICS.folder_duplicates);
+        metrics::inc(&DIGEST.duplicates);
+    } else {
+        println!("✓ Hochgeladen: {} → Job #{} ({})", result.filename, result.job_id, result.message);
+        metrics::inc(&METRICS.folder_uploads);
+        metrics::inc(&DIGEST.uploaded);
+        events::record(EventKind::Upload, format!("{} hochgeladen (Job #{})", result.filename, result.job_id));
+    }
+    Ok(result)
+}
+
+/// Bricht einen laufenden Upload ab (ID aus upload-progress bzw. Warteschlange) → ob er lief
+pub fn cancel_upload(id: &str) -> bool {
+    let running = RUNNING_UPLOADS.lock().unwrap_or_else(|e| e.into_inner());
+    match running.get(id) {
+        Some(signal) => {
+            signal.notify_one();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Meldet einen Upload unter seinen IDs an, beim Drop wieder ab
+/// Eigene Upload-ID statt Datei-Hash, damit gleiche Inhalte aus verschiedenen Dateien getrennt abbrechbar sind
+struct RunningUpload {
+    /// Upload-ID und Herkunftspfad
+    ids: [String; 2],
+    signal: Arc<Notify>,
+}
+
+impl RunningUpload {
+    fn register(original_path: &str) -> Self {
+        let upload = Self {
+            ids: [uuid::Uuid::new_v4().to_string(), original_path.to_string()],
+            signal: Arc::new(Notify::new()),
+        };
+        let mut running = RUNNING_UPLOADS.lock().unwrap_or_else(|e| e.into_inner());
+        for id in &upload.ids {
+            running.insert(id.clone(), upload.signal.clone());
+        }
+        upload
+    }
+
+    /// Upload-ID der Fortschrittsmeldungen
+    fn id(&self) -> &str {
+        &self.ids[0]
+    }
+
+    fn original_path(&self) -> &str {
+        &self.ids[1]
+    }
+}
+
+impl Drop for RunningUpload {
+    fn drop(&mut self) {
+        let mut running = RUNNING_UPLOADS.lock().unwrap_or_else(|e| e.into_inner());
+        for id in &self.ids {
+            if running.get(id).is_some_and(|signal| Arc::ptr_eq(signal, &self.signal)) {
+                running.remove(id);
+            }
+        }
+    }
+}
+
+/// Lädt Daten über den Folder-Upload-Endpunkt hoch (abbrechbar über cancel_upload)
+/// original_path: Herkunft für DocFlow (Dateipfad, "Zwischenablage", ...)
+async fn upload_to_docflow(
+    docflow_url: &str,
+    api_key: &str,
+    upload: &UploadData,
+    file_hash: &str,
+    original_path: &str,
+    barcodes: &[DetectedBarcode],
+    duplicate_of: Option<&DedupEntry>,
+) -> Result<FolderUploadResponse, Box<dyn std::error::Error + Send + Sync>> {
+    // Bekannte Dateien gar nicht erst übertragen
+    if let Some(job_id) = known_on_server(docflow_url, api_key, file_hash).await {
+        return Ok(FolderUploadResponse {
+            success: true,
+            job_id,
+            filename: upload.filename.clone(),
+            file_size_mb: upload.len() as f64 / 1024.0 / 1024.0,
+            duplicate: true,
+            message: "Bereits in DocFlow vorhanden (Hash-Prüfung)".to_string(),
+        });
+    }
+
+    let running = RunningUpload::register(original_path);
+    // Abbruch verwirft die laufende Anfrage samt Stream
+    tokio::select! {
+        result = upload_with_retries(docflow_url, api_key, upload, &running, file_hash, barcodes, duplicate_of) => result,
+        _ = running.signal.notified() => Err(UploadCancelled.into()),
+    }
+}
+
+/// Antwort der Hash-Prüfung
+#[derive(Deserialize)]
+struct HashCheckResponse {
+    exists: bool,
+    #[serde(default)]
+    job_id: Option<i64>,
+}
+
+/// Fragt DocFlow, ob eine Datei mit diesem Hash schon vorhanden ist → Job-ID (0 wenn unbekannt)
+/// Fehler oder ältere Server ohne Endpunkt gelten als "nicht vorhanden", dann wird normal hochgeladen
+async fn known_on_server(docflow_url: &str, api_key: &str, file_hash: &str) -> Option<i64> {
+    if HASH_CHECK_UNSUPPORTED.lock().is_ok_and(|s| s.contains(docflow_url)) {
+        return None;
+    }
+
+    let response = crate::http_client::docflow()
+        .get(format!("{}{}", docflow_url, HASH_CHECK_PATH))
+        .query(&[("file_hash", file_hash)])
+        .header("Authorization", format!("Bearer {}", api_key))
+        .timeout(std::time::Duration::from_secs(10))
+        .send_limited()
+        .await
+        .ok()?;
+    match response.status().as_u16() {
+        200 => {
+            let check: HashCheckResponse = response.json().await.ok()?;
+            check.exists.then(|| check.job_id.unwrap_or_default())
+        }
+        404 | 405 | 501 => {
+            println!("ℹ DocFlow bietet keine Hash-Prüfung vor dem Upload - Dateien werden direkt hochgeladen");
+            if let Ok(mut unsupported) = HASH_CHECK_UNSUPPORTED.lock() {
+                unsupported.insert(docflow_url.to_string());
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Folder-Upload mit 3 Versuchen und exponentiellem Backoff
+async fn upload_with_retries(
+    docflow_url: &str,
+    api_key: &str,
+    upload: &UploadData,
+    running: &RunningUpload,
+    file_hash: &str,
+    barcodes: &[DetectedBarcode],
+    duplicate_of: Option<&DedupEntry>,
+) -> Result<FolderUploadResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let client = crate::http_client::docflow();
+    let url = format!("{}/api/scanner/bridge/folder-upload", docflow_url);
+
+    use multipart::Form;
+
+    // Retry-Logik: 3 Versuche mit exponentiellem Backoff
+    // 60 s plus 1 s je MB, damit große Dateien auch über langsame Leitungen durchgehen
+    let timeout = std::time::Duration::from_secs(60 + upload.len() / (1024 * 1024));
+    let mut last_error = String::new();
+    for attempt in 0..3u32 {
+        if attempt > 0 {
+            let delay = 2u64.pow(attempt);
+            tokio::time::sleep(tokio::time::Duration::from_secs(delay)).await;
+        }
+
+        // Form muss für jeden Versuch neu gebaut werden
+        let retry_file_part = upload.part(running.id()).await?;
+        let mut retry_form = Form::new()
+            .part("file", retry_file_part)
+            .text("file_hash", file_hash)
+            // Herkunft wie im Dateinamen in NFC und ohne \\?\-Präfix, die Datei auf der Platte bleibt unverändert
+            .text("original_path", paths::nfc(&paths::display(Path::new(running.original_path()))));
+
+        // Erkannte Barcodes als Metadaten (DocFlow nutzt sie für die Auto-Ablage)
+        if !barcodes.is_empty() {
+            retry_form = retry_form.text("barcodes", serde_json::to_string(barcodes)?);
+        }
+        // Schon über den anderen Kanal hochgeladen (DocFlow kann verknüpfen statt doppelt abzulegen)
+        if let Some(earlier) = duplicate_of {
+            retry_form = dedup::flag(retry_form, earlier)?;
+        }
+
+        let request = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .timeout(timeout);
+        let request = retry_form.attach(request).await?;
+        let started = std::time::Instant::now();
+        match request.send_limited().await {
+            Ok(response) => {
+                if response.status().is_success() {
+                    let result: FolderUploadResponse = response.json().await?;
+                    METRICS.folder_upload_duration.observe(started.elapsed());
+                    return Ok(result);
+                } else if response.status().as_u16() == 429 {
+                    // Rate-Limit: Länger warten
+                    last_error = "Rate-Limit erreicht".to_string();
+                    tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+                    continue;
+                } else {
+                    last_error = response.text().await.unwrap_or_default();
+                    continue;
+                }
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        }
+    }
+
+    Err(format!("Upload fehlgeschlagen nach 3 Versuchen: {}", last_error).into())
+}
+
+/// Vorschlag für einen Scan-Ordner (Setup-Assistent)
+#[derive(Clone, Debug, Serialize)]
+pub struct FolderSuggestion {
+    pub path: String,
+    /// Herkunft des Ordners ("Windows Fax und Scan", "Epson", ...)
+    pub source: String,
+    /// Anzahl unterstützter Dateien im Ordner
+    pub file_count: usize,
+}
+
+/// Bekannte Standard-Ablageorte von Scan-Software (relativ zum Home-Verzeichnis)
+const KNOWN_SCAN_FOLDERS: &[(&str, &str)] = &[
+    ("Documents/Scanned Documents", "Windows Fax und Scan"),
+    ("Documents/Gescannte Dokumente", "Windows Fax und Scan"),
+    ("OneDrive/Documents/Scanned Documents", "Windows Fax und Scan (OneDrive)"),
+    ("OneDrive/Dokumente/Gescannte Dokumente", "Windows Fax und Scan (OneDrive)"),
+    ("Documents/HP Scans", "HP"),
+    ("Pictures/HP Scans", "HP"),
+    ("Documents/Epson", "Epson"),
+    ("Pictures/Epson Scan", "Epson"),
+    ("Documents/Epson Scan", "Epson"),
+    ("Pictures/ControlCenter4/Scan", "Brother"),
+    ("Documents/ControlCenter4/Scan", "Brother"),
+    ("Pictures/Brother", "Brother"),
+    ("Documents/Canon", "Canon"),
+    ("Pictures/Canon", "Canon"),
+    ("Scans", "Scans"),
+    ("Documents/Scans", "Scans"),
+    ("Dokumente/Scans", "Scans"),
+    ("Pictures/Scans", "Scans"),
+    ("Bilder/Scans", "Scans"),
+    ("Desktop/Scans", "Scans"),
+];
+
+/// Sucht bekannte Scan-Ordner und liefert die vorhandenen (meiste Dateien zuerst)
+pub fn suggest_watch_folders() -> Vec<FolderSuggestion> {
+    let home = std::env::var_os("USERPROFILE")
+        .or_else(|| std::env::var_os("HOME"))
+        .map(PathBuf::from);
+    let Some(home) = home else {
+        return Vec::new();
+    };
+
+    let mut suggestions: Vec<FolderSuggestion> = Vec::new();
+    for (relative, source) in KNOWN_SCAN_FOLDERS {
+        let path = home.join(relative);
+        if !path.is_dir() {
+            continue;
+        }
+        // Ordnernamen sind unter Windows/macOS case-insensitiv → Duplikate über den kanonischen Pfad erkennen
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        let path_str = canonical.to_string_lossy().to_string();
+        if suggestions.iter().any(|s| s.path == path_str) {
+            continue;
+        }
+
+        let file_count = std::fs::read_dir(&canonical)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| e.path().is_file() && FolderWatcher::is_allowed_extension(&e.path()))
+                    .count()
+            })
+            .unwrap_or(0);
+
+        suggestions.push(FolderSuggestion {
+            path: path_str,
+            source: source.to_string(),
+            file_count,
+        });
+    }
+
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.file_count));
+    suggestions
+}
+
+/// Ergebnis der Ordnerprüfung vor dem Aktivieren des Folder-Syncs
+#[derive(Clone, Debug, Serialize)]
+pub struct FolderValidation {
+    pub path: String,
+    pub readable: bool,
+    pub writable: bool,
+    /// Unterordner "uploaded/" anlegbar bzw. beschreibbar (Post-Upload-Aktion "verschieben")
+    pub can_create_uploaded: bool,
+    /// Netzlaufwerk (SMB/NFS/UNC-Pfad)
+    pub network_share: bool,
+    pub free_bytes: Option<u64>,
+    /// Bereits vorhandene Dateien, die nach dem Aktivieren hochgeladen würden
+    pub eligible_files: usize,
+    pub eligible_bytes: u64,
+    /// Hinweise, die eine Aktivierung nicht verhindern
+    pub warnings: Vec<String>,
+    /// Probleme, mit denen der Folder-Sync nicht funktioniert
+    pub errors: Vec<String>,
+}
+
+/// Prüft einen Scan-Ordner vor dem Aktivieren (blockierend - Netzlaufwerke können langsam sein)
+pub fn validate_folder(path: &Path, ignore: &IgnoreConfig, min_free_disk_mb: u64) -> FolderValidation {
+    let path = &paths::extended(path);
+    let mut validation = FolderValidation {
+        path: paths::display(path),
+        readable: false,
+        writable: false,
+        can_create_uploaded: false,
+        network_share: false,
+        free_bytes: None,
+        eligible_files: 0,
+        eligible_bytes: 0,
+        warnings: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if !path.is_dir() {
+        validation.errors.push(format!("{} existiert nicht oder ist kein Ordner", paths::display(path)));
+        return validation;
+    }
+
+    validation.network_share = is_network_share(path);
+    if validation.network_share {
+        validation
+            .warnings
+            .push("Netzlaufwerk: Uploads pausieren, solange die Freigabe nicht erreichbar ist".to_string());
+    }
+
+    match std::fs::read_dir(path) {
+        Ok(entries) => {
+            validation.readable = true;
+            for entry in entries.flatten() {
+                let file = entry.path();
+                if !file.is_file() || ignore.is_ignored(&file) || !FolderWatcher::is_allowed_extension(&file) {
+                    continue;
+                }
+                validation.eligible_files += 1;
+                validation.eligible_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+        }
+        Err(e) => validation.errors.push(format!("Ordner nicht lesbar: {}", e)),
+    }
+
+    match probe_write(path) {
+        Ok(()) => validation.writable = true,
+        Err(e) => validation.errors.push(format!("Ordner nicht beschreibbar: {}", e)),
+    }
+
+    let uploaded = path.join("uploaded");
+    let uploaded_result = if uploaded.is_dir() {
+        probe_write(&uploaded)
+    } else if uploaded.exists() {
+        Err(std::io::Error::new(std::io::ErrorKind::AlreadyExists, "Datei mit diesem Namen vorhanden"))
+    } else {
+        std::fs::create_dir(&uploaded).and_then(|_| std::fs::remove_dir(&uploaded))
+    };
+    match uploaded_result {
+        Ok(()) => validation.can_create_uploaded = true,
+        Err(e) => validation
+            .errors
+            .push(format!("Unterordner \"uploaded\" nicht anlegbar: {}", e)),
+    }
+
+    match fs4::available_space(path) {
+        Ok(free) => {
+            validation.free_bytes = Some(free);
+            if free / 1024 / 1024 < min_free_disk_mb {
+                validation.warnings.push(format!(
+                    "Wenig Speicherplatz: {} MB frei (mindestens {} MB empfohlen)",
+                    free / 1024 / 1024,
+                    min_free_disk_mb
+                ));
+            }
+        }
+        Err(e) => validation.warnings.push(format!("Freier Speicherplatz nicht ermittelbar: {}", e)),
+    }
+
+    if validation.eligible_files > 0 {
+        validation.warnings.push(format!(
+            "{} vorhandene Datei(en) ({} MB) werden nach dem Aktivieren hochgeladen",
+            validation.eligible_files,
+            validation.eligible_bytes.div_ceil(1024 * 1024)
+        ));
+    }
+
+    validation
+}
+
+/// Legt eine Testdatei an und entfernt sie wieder
+fn probe_write(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".docflow-validate-{}", uuid::Uuid::new_v4()));
+    std::fs::write(&probe, b"test")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Liegt der Pfad auf einem Netzlaufwerk?
+fn is_network_share(path: &Path) -> bool {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    #[cfg(target_os = "windows")]
+    {
+        let text = path.to_string_lossy();
+        // canonicalize liefert \\?\UNC\server\share bzw. \\?\C:\...
+        let text = text.strip_prefix(r"\\?\").unwrap_or(&text);
+        if text.starts_with(r"\\") || text.starts_with("UNC\\") {
+            return true;
+        }
+        if let Some(letter) = text.chars().next().filter(|_| text.get(1..2) == Some(":")) {
+            const DRIVE_REMOTE: u32 = 4;
+            let root: Vec<u16> = format!("{}:\\", letter).encode_utf16().chain(Some(0)).collect();
+            let root = windows::core::PCWSTR(root.as_ptr());
+            return unsafe { windows::Win32::Storage::FileSystem::GetDriveTypeW(root) } == DRIVE_REMOTE;
+        }
+        false
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        const NETWORK_FILESYSTEMS: &[&str] = &[
+            "nfs", "nfs4", "cifs", "smb3", "smbfs", "afpfs", "webdav", "fuse.sshfs", "9p", "afs",
+        ];
+        let mounts = mount_table();
+        // Längster passender Einhängepunkt bestimmt das Dateisystem
+        mounts
+            .iter()
+            .filter(|(mount_point, _)| path.starts_with(mount_point))
+            .max_by_key(|(mount_point, _)| mount_point.as_os_str().len())
+            .is_some_and(|(_, fs_type)| NETWORK_FILESYSTEMS.contains(&fs_type.as_str()))
+    }
+}
+
+/// Einhängepunkte mit Dateisystemtyp (Linux: /proc/mounts, macOS: Ausgabe von `mount`)
+#[cfg(not(target_os = "windows"))]
+fn mount_table() -> Vec<(PathBuf, String)> {
+    #[cfg(target_os = "macos")]
+    {
+        // "//user@server/share on /Volumes/share (smbfs, nodev, nosuid, mounted by user)"
+        let output = std::process::Command::new("mount").output().map(|o| o.stdout).unwrap_or_default();
+        String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(|line| {
+                let (_, rest) = line.split_once(" on ")?;
+                let (mount_point, options) = rest.rsplit_once(" (")?;
+                let fs_type = options.split([',', ')']).next()?.trim();
+                Some((PathBuf::from(mount_point), fs_type.to_string()))
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        // "server:/export /mnt/scans nfs4 rw,relatime 0 0" - Leerzeichen sind als \040 kodiert
+        std::fs::read_to_string("/proc/mounts")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let mount_point = fields.nth(1)?.replace("\\040", " ");
+                let fs_type = fields.next()?;
+                Some((PathBuf::from(mount_point), fs_type.to_string()))
+            })
+            .collect()
+    }
+}
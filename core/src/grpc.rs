@@ -0,0 +1,705 @@
+// gRPC-Transport - Alternative zu REST+Multipart für Standorte mit hohem Scan-Aufkommen
+// Anmeldung, Job-Stream und Upload-Stream nach proto/docflow_bridge.proto (Nachrichten hier von Hand nachgebildet)
+// Nur mit Feature "grpc" enthalten; ist gRPC nicht erreichbar, läuft alles unverändert über HTTP (Polling, Multipart)
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+use crate::dedup::DedupEntry;
+use crate::scan_poller::{self, ScanDocument, ScanPoller};
+use crate::settings::BridgeSettings;
+
+/// Standard-Port des gRPC-Dienstes von DocFlow
+pub const DEFAULT_PORT: u16 = 50051;
+
+/// Konfiguration des gRPC-Transports
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    /// z.B. "https://docflow.example:50051" (leer = DocFlow-Host mit Port 50051)
+    #[serde(default)]
+    pub endpoint: String,
+    /// Nach einem Verbindungsfehler so lange über HTTP arbeiten, dann gRPC erneut versuchen
+    pub retry_after_secs: u64,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            retry_after_secs: 300,
+        }
+    }
+}
+
+impl GrpcConfig {
+    /// gRPC-Endpunkt für eine DocFlow-Instanz
+    pub fn endpoint(&self, docflow_url: &str) -> Result<String, String> {
+        let endpoint = self.endpoint.trim();
+        if !endpoint.is_empty() {
+            return Ok(endpoint.trim_end_matches('/').to_string());
+        }
+        let mut url = reqwest::Url::parse(docflow_url.trim())
+            .map_err(|e| format!("Ungültige DocFlow-URL '{}': {}", docflow_url, e))?;
+        url.set_port(Some(DEFAULT_PORT))
+            .map_err(|_| format!("DocFlow-URL '{}' hat keinen Host", docflow_url))?;
+        url.set_path("");
+        url.set_query(None);
+        Ok(url.as_str().trim_end_matches('/').to_string())
+    }
+}
+
+/// gRPC-Status für die Anzeige
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GrpcStatus {
+    pub endpoint: String,
+    /// Job-Stream steht - der Poller fragt dann keine pending-scans ab
+    pub streaming: bool,
+    /// Per gRPC hochgeladene Dokumente
+    pub uploads: u64,
+    /// Uploads, die auf HTTP ausweichen mussten
+    pub fallbacks: u64,
+    pub last_error: Option<String>,
+}
+
+/// None = gRPC nicht aktiv
+static STATUS: Mutex<Option<GrpcStatus>> = Mutex::new(None);
+
+/// Ob der gRPC-Transport in diesem Build verfügbar ist
+pub fn is_supported() -> bool {
+    cfg!(feature = "grpc")
+}
+
+/// Aktueller gRPC-Status (None = nicht aktiviert bzw. Bridge nicht gekoppelt)
+pub fn status() -> Option<GrpcStatus> {
+    STATUS.lock().ok().and_then(|s| s.clone())
+}
+
+/// Job-Stream steht
+pub fn is_streaming() -> bool {
+    STATUS.lock().is_ok_and(|s| s.as_ref().is_some_and(|s| s.streaming))
+}
+
+#[cfg_attr(not(feature = "grpc"), allow(dead_code))]
+fn update_status(update: impl FnOnce(&mut GrpcStatus)) {
+    if let Ok(mut status) = STATUS.lock() {
+        if let Some(status) = status.as_mut() {
+            update(status);
+        }
+    }
+}
+
+/// gRPC-Dienst (Anmeldung und Job-Stream halten, Kanal für Uploads bereitstellen)
+pub struct GrpcService {
+    task: tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl GrpcService {
+    /// Startet den Dienst, falls in den Einstellungen aktiviert und im Build enthalten
+    pub async fn start(
+        poller: Arc<ScanPoller>,
+        docflow_url: String,
+        api_key: String,
+        settings: Arc<RwLock<BridgeSettings>>,
+    ) -> Option<Arc<Self>> {
+        let config = settings.read().await.grpc.clone();
+        if !config.enabled {
+            return None;
+        }
+        if !is_supported() {
+            eprintln!("⚠ gRPC ist aktiviert, aber nicht in diesem Build enthalten (Feature \"grpc\") - DocFlow-Verkehr läuft über HTTP");
+            return None;
+        }
+        let endpoint = match config.endpoint(&docflow_url) {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                eprintln!("❌ gRPC: {}", e);
+                return None;
+            }
+        };
+
+        if let Ok(mut status) = STATUS.lock() {
+            *status = Some(GrpcStatus { endpoint: endpoint.clone(), ..GrpcStatus::default() });
+        }
+        let handle = tokio::spawn(transport::run(poller, endpoint, api_key, config, settings));
+        Some(Arc::new(Self {
+            task: tokio::sync::Mutex::new(Some(handle)),
+        }))
+    }
+
+    /// Beendet Job-Stream und Uploads per gRPC - alles läuft wieder über HTTP
+    pub async fn stop(&self) {
+        if let Some(task) = self.task.lock().await.take() {
+            task.abort();
+        }
+        transport::disconnect();
+        if let Ok(mut status) = STATUS.lock() {
+            *status = None;
+        }
+        scan_poller::wake();
+        println!("🛑 gRPC-Transport beendet");
+    }
+}
+
+/// Lädt ein Scan-Dokument per gRPC hoch
+/// None: gRPC nicht verbunden bzw. nicht erreichbar - der Aufrufer lädt über HTTP hoch
+pub async fn upload_scan(
+    api_key: &str,
+    job_id: &str,
+    document: &ScanDocument,
+    part: Option<(usize, usize)>,
+    file_hash: &str,
+    duplicate_of: Option<&DedupEntry>,
+) -> Option<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
+    transport::upload_scan(api_key, job_id, document, part, file_hash, duplicate_of).await
+}
+
+/// Nachrichten aus proto/docflow_bridge.proto
+#[cfg(feature = "grpc")]
+pub mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RegisterRequest {
+        #[prost(string, tag = "1")]
+        pub bridge_version: String,
+        #[prost(string, tag = "2")]
+        pub os: String,
+        #[prost(string, tag = "3")]
+        pub hostname: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RegisterResponse {
+        #[prost(string, tag = "1")]
+        pub bridge_id: String,
+        #[prost(bool, tag = "2")]
+        pub accepted: bool,
+        #[prost(string, tag = "3")]
+        pub message: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct JobStreamRequest {
+        #[prost(string, tag = "1")]
+        pub bridge_id: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ScanJob {
+        #[prost(string, tag = "1")]
+        pub job_id: String,
+        #[prost(string, tag = "2")]
+        pub scanner_id: String,
+        #[prost(string, optional, tag = "3")]
+        pub profile_id: Option<String>,
+        #[prost(uint32, tag = "4")]
+        pub resolution: u32,
+        #[prost(string, tag = "5")]
+        pub color_mode: String,
+        #[prost(string, tag = "6")]
+        pub source: String,
+        #[prost(bool, tag = "7")]
+        pub duplex: bool,
+        #[prost(string, tag = "8")]
+        pub format: String,
+        #[prost(message, optional, tag = "9")]
+        pub enhancements: Option<Enhancements>,
+        #[prost(uint32, optional, tag = "10")]
+        pub paper_length_mm: Option<u32>,
+        #[prost(message, repeated, tag = "11")]
+        pub regions: Vec<Region>,
+        #[prost(uint32, optional, tag = "12")]
+        pub max_document_kb: Option<u32>,
+        #[prost(int32, tag = "13")]
+        pub priority: i32,
+        #[prost(string, tag = "14")]
+        pub created_at: String,
+        #[prost(string, tag = "15")]
+        pub expires_at: String,
+        #[prost(bool, optional, tag = "16")]
+        pub auto_orient: Option<bool>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Enhancements {
+        #[prost(string, repeated, tag = "1")]
+        pub stages: Vec<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Region {
+        #[prost(uint32, tag = "1")]
+        pub x_offset: u32,
+        #[prost(uint32, tag = "2")]
+        pub y_offset: u32,
+        #[prost(uint32, tag = "3")]
+        pub width: u32,
+        #[prost(uint32, tag = "4")]
+        pub height: u32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct UploadChunk {
+        #[prost(oneof = "upload_chunk::Content", tags = "1, 2")]
+        pub content: Option<upload_chunk::Content>,
+    }
+
+    pub mod upload_chunk {
+        #[derive(Clone, PartialEq, prost::Oneof)]
+        pub enum Content {
+            #[prost(message, tag = "1")]
+            Header(super::UploadHeader),
+            #[prost(bytes = "vec", tag = "2")]
+            Data(Vec<u8>),
+        }
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct UploadHeader {
+        #[prost(string, tag = "1")]
+        pub job_id: String,
+        #[prost(string, tag = "2")]
+        pub file_name: String,
+        #[prost(string, tag = "3")]
+        pub file_hash: String,
+        #[prost(uint64, tag = "4")]
+        pub size: u64,
+        #[prost(uint32, optional, tag = "5")]
+        pub split_index: Option<u32>,
+        #[prost(uint32, optional, tag = "6")]
+        pub split_count: Option<u32>,
+        #[prost(bool, tag = "7")]
+        pub incomplete: bool,
+        #[prost(message, repeated, tag = "8")]
+        pub barcodes: Vec<Barcode>,
+        #[prost(message, repeated, tag = "9")]
+        pub renditions: Vec<Rendition>,
+        #[prost(string, optional, tag = "10")]
+        pub duplicate_of: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Barcode {
+        #[prost(uint32, tag = "1")]
+        pub page: u32,
+        #[prost(string, tag = "2")]
+        pub format: String,
+        #[prost(string, tag = "3")]
+        pub value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Rendition {
+        #[prost(string, tag = "1")]
+        pub file_name: String,
+        #[prost(string, tag = "2")]
+        pub mime_type: String,
+        #[prost(bytes = "vec", tag = "3")]
+        pub data: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct UploadResult {
+        #[prost(bool, tag = "1")]
+        pub success: bool,
+        #[prost(string, tag = "2")]
+        pub message: String,
+    }
+
+    impl From<ScanJob> for crate::scan_poller::PendingScanJob {
+        fn from(job: ScanJob) -> Self {
+            Self {
+                job_id: job.job_id,
+                scanner_id: job.scanner_id,
+                profile_id: job.profile_id,
+                resolution: job.resolution,
+                color_mode: job.color_mode,
+                source: job.source,
+                duplex: job.duplex,
+                format: job.format,
+                // Unbekannte Stufen (neuere DocFlow-Version) entfallen
+                enhancements: job.enhancements.map(|e| {
+                    e.stages
+                        .into_iter()
+                        .filter_map(|stage| serde_json::from_value(serde_json::Value::String(stage)).ok())
+                        .collect()
+                }),
+                paper_length_mm: job.paper_length_mm,
+                regions: job
+                    .regions
+                    .into_iter()
+                    .map(|r| crate::scanner::ScanRegion {
+                        x_offset: r.x_offset,
+                        y_offset: r.y_offset,
+                        width: r.width,
+                        height: r.height,
+                    })
+                    .collect(),
+                max_document_kb: job.max_document_kb,
+                auto_orient: job.auto_orient,
+                priority: job.priority,
+                created_at: job.created_at,
+                expires_at: job.expires_at,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "grpc")]
+mod transport {
+    use http::uri::PathAndQuery;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::RwLock;
+    use tonic::codec::ProstCodec;
+    use tonic::metadata::{MetadataKey, MetadataValue};
+    use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
+    use tonic::{Code, Status};
+
+    use super::proto::{self, upload_chunk::Content};
+    use super::{update_status, GrpcConfig};
+    use crate::dedup::DedupEntry;
+    use crate::http_client::HttpConfig;
+    use crate::rate_limit::Slot;
+    use crate::request_signing;
+    use crate::scan_poller::{self, ScanDocument, ScanPoller};
+    use crate::settings::BridgeSettings;
+
+    const REGISTER: &str = "/docflow.bridge.v1.BridgeService/Register";
+    const STREAM_JOBS: &str = "/docflow.bridge.v1.BridgeService/StreamJobs";
+    const UPLOAD_SCAN: &str = "/docflow.bridge.v1.BridgeService/UploadScan";
+
+    /// Blockgröße der PDF-Daten im Upload-Stream
+    const CHUNK_SIZE: usize = 256 * 1024;
+
+    /// Angemeldeter Kanal (None = Uploads über HTTP)
+    static CHANNEL: Mutex<Option<Channel>> = Mutex::new(None);
+
+    pub fn disconnect() {
+        set_channel(None);
+    }
+
+    fn set_channel(channel: Option<Channel>) {
+        if let Ok(mut current) = CHANNEL.lock() {
+            *current = channel;
+        }
+    }
+
+    /// Sitzungs-Loop: nach einem Fehler arbeitet die Bridge bis zum nächsten Versuch über HTTP
+    pub async fn run(
+        poller: Arc<ScanPoller>,
+        endpoint: String,
+        api_key: String,
+        config: GrpcConfig,
+        settings: Arc<RwLock<BridgeSettings>>,
+    ) {
+        let retry_after = Duration::from_secs(config.retry_after_secs.max(1));
+        let mut backoff = Duration::from_secs(1);
+
+        loop {
+            // Netzwerk-Einstellungen je Sitzung neu lesen (CA kann sich geändert haben)
+            let http = settings.read().await.http.clone();
+            let mut opened = false;
+            let result = session(&poller, &endpoint, &api_key, &http, &mut opened).await;
+            set_channel(None);
+            update_status(|s| s.streaming = false);
+
+            let wait = match result {
+                // DocFlow hat den Stream regulär beendet (z.B. Neustart) - gleich neu anmelden
+                Ok(()) => {
+                    backoff = Duration::from_secs(1);
+                    backoff
+                }
+                Err(status) => {
+                    let wait = if falls_back(&status, opened) { retry_after } else { backoff.min(retry_after) };
+                    eprintln!(
+                        "⚠ gRPC zu {} nicht nutzbar: {} - HTTP bis zum nächsten Versuch in {}s",
+                        endpoint,
+                        describe(&status),
+                        wait.as_secs()
+                    );
+                    update_status(|s| s.last_error = Some(describe(&status)));
+                    backoff = (backoff * 2).min(retry_after);
+                    wait
+                }
+            };
+            // Bis der Job-Stream wieder steht, übernimmt das Polling
+            scan_poller::wake();
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Verbinden, anmelden und Jobs empfangen, bis der Stream endet (`opened`: Job-Stream stand)
+    async fn session(
+        poller: &Arc<ScanPoller>,
+        endpoint: &str,
+        api_key: &str,
+        http: &HttpConfig,
+        opened: &mut bool,
+    ) -> Result<(), Status> {
+        let channel = connect(endpoint, http).await?;
+
+        let hostname = hostname::get()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_else(|_| "Unknown".to_string());
+        let register = proto::RegisterRequest {
+            bridge_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            hostname,
+        };
+        let register = request(register, api_key, REGISTER).map_err(Status::unauthenticated)?;
+        let registered: proto::RegisterResponse = client(channel.clone())
+            .await?
+            .unary(register, PathAndQuery::from_static(REGISTER), ProstCodec::default())
+            .await?
+            .into_inner();
+        if !registered.accepted {
+            return Err(Status::permission_denied(format!("Anmeldung abgelehnt: {}", registered.message)));
+        }
+
+        let stream_request = proto::JobStreamRequest { bridge_id: registered.bridge_id };
+        let stream_request = request(stream_request, api_key, STREAM_JOBS).map_err(Status::unauthenticated)?;
+        let mut jobs = client(channel.clone())
+            .await?
+            .server_streaming::<_, proto::ScanJob, _>(
+                stream_request,
+                PathAndQuery::from_static(STREAM_JOBS),
+                ProstCodec::default(),
+            )
+            .await?
+            .into_inner();
+
+        *opened = true;
+        set_channel(Some(channel));
+        update_status(|s| {
+            s.streaming = true;
+            s.last_error = None;
+        });
+        println!("🔌 gRPC-Job-Stream zu {} verbunden", endpoint);
+
+        while let Some(job) = jobs.message().await? {
+            let job: crate::scan_poller::PendingScanJob = job.into();
+            println!("⚡ Scan-Job {} über gRPC erhalten", job.job_id);
+            if let Err(e) = poller.enqueue_job(job).await {
+                eprintln!("⚠ {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn upload_scan(
+        api_key: &str,
+        job_id: &str,
+        document: &ScanDocument,
+        part: Option<(usize, usize)>,
+        file_hash: &str,
+        duplicate_of: Option<&DedupEntry>,
+    ) -> Option<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
+        let channel = CHANNEL.lock().ok()?.clone()?;
+        let endpoint = super::status()?.endpoint;
+
+        let header = proto::UploadHeader {
+            job_id: job_id.to_string(),
+            file_name: match part {
+                Some((index, _)) => format!("scan_{}.pdf", index + 1),
+                None => "scan.pdf".to_string(),
+            },
+            file_hash: file_hash.to_string(),
+            size: document.data.len() as u64,
+            split_index: part.map(|(index, _)| index as u32),
+            split_count: part.map(|(_, count)| count as u32),
+            incomplete: document.incomplete,
+            barcodes: document
+                .barcodes
+                .iter()
+                .map(|b| proto::Barcode { page: b.page as u32, format: b.format.clone(), value: b.value.clone() })
+                .collect(),
+            renditions: document
+                .renditions
+                .iter()
+                .map(|r| proto::Rendition {
+                    file_name: r.file_name.clone(),
+                    mime_type: r.mime_type.clone(),
+                    data: r.data.clone(),
+                })
+                .collect(),
+            duplicate_of: duplicate_of.and_then(|earlier| serde_json::to_string(earlier).ok()),
+        };
+        // Ausgelagerte Dokumente werden erst beim Senden blockweise aus dem Spool gelesen
+        let data = match document.data.chunks(CHUNK_SIZE) {
+            Ok(data) => data,
+            Err(e) => return Some(Err(e.into())),
+        };
+        let job = job_id.to_string();
+        let chunks = std::iter::once(Content::Header(header))
+            .chain(data.map_while(move |chunk| match chunk {
+                Ok(chunk) => Some(Content::Data(chunk)),
+                // Stream endet vorzeitig - weniger Daten als im Header angekündigt
+                Err(e) => {
+                    eprintln!("⚠ Scan zu Job {} nicht lesbar: {}", job, e);
+                    None
+                }
+            }))
+            .map(|content| proto::UploadChunk { content: Some(content) });
+        // Sobald DocFlow den ersten Block abgeholt hat, ist der Upload begonnen (kein Ausweichen auf HTTP mehr)
+        let opened = Arc::new(AtomicBool::new(false));
+        let started = opened.clone();
+        let chunks = chunks.inspect(move |_| started.store(true, Ordering::Relaxed));
+
+        // Gleiche Grenzen wie beim HTTP-Upload: Circuit-Breaker, Limiter, Streams, Zeitlimit
+        let slot = match Slot::acquire(&endpoint).await {
+            Ok(slot) => slot,
+            Err(e) => return Some(Err(e)),
+        };
+        let result = async {
+            let chunks = futures::stream::iter(chunks);
+            let mut request = request(chunks, api_key, UPLOAD_SCAN).map_err(Status::unauthenticated)?;
+            request.set_timeout(scan_poller::UPLOAD_TIMEOUT);
+            let upload = async {
+                client(channel)
+                    .await?
+                    .client_streaming::<_, _, proto::UploadResult, _>(
+                        request,
+                        PathAndQuery::from_static(UPLOAD_SCAN),
+                        ProstCodec::default(),
+                    )
+                    .await
+                    .map(tonic::Response::into_inner)
+            };
+            tokio::time::timeout(scan_poller::UPLOAD_TIMEOUT, upload)
+                .await
+                .map_err(|_| Status::deadline_exceeded("Zeitüberschreitung beim Upload"))?
+        }
+        .await;
+        slot.finish(!result.as_ref().is_err_and(is_server_failure));
+        let opened = opened.load(Ordering::Relaxed);
+
+        match result {
+            Ok(result) if result.success => {
+                update_status(|s| s.uploads += 1);
+                Some(Ok(()))
+            }
+            Ok(result) => Some(Err(format!("Upload fehlgeschlagen: {}", result.message).into())),
+            Err(status) if falls_back(&status, opened) => {
+                eprintln!("⚠ gRPC-Upload zu Job {} nicht möglich ({}) - Upload über HTTP", job_id, describe(&status));
+                set_channel(None);
+                update_status(|s| s.fallbacks += 1);
+                None
+            }
+            Err(status) => Some(Err(format!("Upload fehlgeschlagen: {}", describe(&status)).into())),
+        }
+    }
+
+    /// TLS wie beim DocFlow-HTTP-Client: System-Zertifikate plus die CA aus den Netzwerk-Einstellungen
+    async fn connect(endpoint: &str, http: &HttpConfig) -> Result<Channel, Status> {
+        let mut builder = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| Status::invalid_argument(format!("Ungültiger gRPC-Endpunkt: {}", e)))?
+            .connect_timeout(Duration::from_secs(10))
+            .http2_keep_alive_interval(Duration::from_secs(30))
+            .keep_alive_while_idle(true);
+        if endpoint.starts_with("https://") {
+            let mut tls = ClientTlsConfig::new().with_native_roots();
+            if let Some(pem) = crate::http_client::ca_certificate(http).map_err(Status::failed_precondition)? {
+                tls = tls.ca_certificate(Certificate::from_pem(pem));
+            }
+            builder = builder
+                .tls_config(tls)
+                .map_err(|e| Status::unavailable(error_chain(&e)))?;
+        }
+        builder.connect().await.map_err(|e| Status::unavailable(error_chain(&e)))
+    }
+
+    async fn client(channel: Channel) -> Result<tonic::client::Grpc<Channel>, Status> {
+        let mut grpc = tonic::client::Grpc::new(channel);
+        grpc.ready().await.map_err(|e| Status::unavailable(error_chain(&e)))?;
+        Ok(grpc)
+    }
+
+    /// Anfrage mit API-Key und ggf. Signatur (Inhalt wird gestreamt, daher UNSIGNED-PAYLOAD)
+    fn request<T>(message: T, api_key: &str, path: &str) -> Result<tonic::Request<T>, String> {
+        let mut request = tonic::Request::new(message);
+        let metadata = request.metadata_mut();
+        let authorization = MetadataValue::try_from(format!("Bearer {}", api_key))
+            .map_err(|_| "Ungültiger API-Key".to_string())?;
+        metadata.insert("authorization", authorization);
+
+        if crate::http_client::request_signing() {
+            let headers = request_signing::signature_headers("POST", path, request_signing::UNSIGNED_PAYLOAD.to_string())?;
+            for (name, value) in headers {
+                let key = MetadataKey::from_bytes(name.to_lowercase().as_bytes()).map_err(|e| e.to_string())?;
+                let value = MetadataValue::try_from(value).map_err(|e| e.to_string())?;
+                metadata.insert(key, value);
+            }
+        }
+        Ok(request)
+    }
+
+    /// Fehler, bei denen DocFlow kein gRPC anbietet bzw. nicht erreichbar ist (`opened`: Stream stand bereits)
+    /// Nach Beginn des Streams kann DocFlow schon Daten verarbeitet haben - dann kein Ausweichen auf HTTP
+    fn falls_back(status: &Status, opened: bool) -> bool {
+        match status.code() {
+            Code::Unimplemented => true,
+            Code::Unavailable => !opened,
+            _ => false,
+        }
+    }
+
+    /// Fehler, die für den Circuit-Breaker als Ausfall des Servers zählen
+    fn is_server_failure(status: &Status) -> bool {
+        matches!(
+            status.code(),
+            Code::Unavailable | Code::Internal | Code::Unknown | Code::DeadlineExceeded
+        )
+    }
+
+    fn describe(status: &Status) -> String {
+        format!("{:?}: {}", status.code(), status.message())
+    }
+
+    /// Fehlermeldung mit Ursachen (tonic meldet sonst nur "transport error")
+    fn error_chain(error: &dyn std::error::Error) -> String {
+        let mut message = error.to_string();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        message
+    }
+}
+
+#[cfg(not(feature = "grpc"))]
+mod transport {
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    use super::GrpcConfig;
+    use crate::dedup::DedupEntry;
+    use crate::scan_poller::{ScanDocument, ScanPoller};
+    use crate::settings::BridgeSettings;
+
+    pub fn disconnect() {}
+
+    pub async fn run(
+        _poller: Arc<ScanPoller>,
+        _endpoint: String,
+        _api_key: String,
+        _config: GrpcConfig,
+        _settings: Arc<RwLock<BridgeSettings>>,
+    ) {
+    }
+
+    pub async fn upload_scan(
+        _api_key: &str,
+        _job_id: &str,
+        _document: &ScanDocument,
+        _part: Option<(usize, usize)>,
+        _file_hash: &str,
+        _duplicate_of: Option<&DedupEntry>,
+    ) -> Option<Result<(), Box<dyn std::error::Error + Send + Sync>>> {
+        None
+    }
+}
@@ -0,0 +1,49 @@
+// HEIC - Konvertiert Handy-Fotos (.heic/.heif) vor dem Upload nach JPEG
+// Nur mit Feature "heic" aktiv (benötigt libheif auf dem Build-System)
+
+use std::path::Path;
+
+/// Endungen von HEIC/HEIF-Dateien
+pub const HEIC_EXTENSIONS: &[&str] = &["heic", "heif"];
+
+/// Ob die Datei ein HEIC/HEIF-Bild ist (anhand der Endung)
+pub fn is_heic(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| HEIC_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Ob die HEIC-Konvertierung in diesem Build verfügbar ist
+pub fn is_supported() -> bool {
+    cfg!(feature = "heic")
+}
+
+/// Dekodiert das Hauptbild einer HEIC-Datei und kodiert es als JPEG
+#[cfg(feature = "heic")]
+pub fn convert_to_jpeg(data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let lib_heif = LibHeif::new();
+    let context = HeifContext::read_from_bytes(data)?;
+    let handle = context.primary_image_handle()?;
+    let decoded = lib_heif.decode(&handle, ColorSpace::Rgb(RgbChroma::Rgb), None)?;
+
+    let planes = decoded.planes();
+    let plane = planes.interleaved.ok_or("HEIC: keine RGB-Bilddaten")?;
+    let (width, height) = (plane.width, plane.height);
+
+    // Zeilen können aufgefüllt sein (stride > width * 3)
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 3);
+    for row in plane.data.chunks(plane.stride).take(height as usize) {
+        pixels.extend_from_slice(&row[..width as usize * 3]);
+    }
+
+    let image = image::RgbImage::from_raw(width, height, pixels).ok_or("HEIC: ungültige Bildgröße")?;
+    Ok(crate::imaging::encode_jpeg(&image::DynamicImage::ImageRgb8(image))?)
+}
+
+#[cfg(not(feature = "heic"))]
+pub fn convert_to_jpeg(_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    Err("HEIC-Unterstützung ist in diesem Build nicht aktiviert (Feature \"heic\")".into())
+}
@@ -0,0 +1,108 @@
+// Post-Upload-Hooks - Führt nach jedem erfolgreichen Upload ein konfiguriertes Skript aus
+// Ermöglicht standortspezifische Integrationen (Stempeln, ERP-Logging, Kopien verschieben)
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::spool;
+
+/// Konfiguration des Post-Upload-Hooks
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HookConfig {
+    pub enabled: bool,
+    /// Programm oder Skript (z.B. "C:\\scripts\\erp.bat" oder "/usr/local/bin/stamp.sh")
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Max. Laufzeit, danach wird der Prozess beendet
+    pub timeout_secs: u64,
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: String::new(),
+            args: Vec::new(),
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// Kontext eines Uploads (wird als Umgebungsvariablen an das Skript übergeben)
+pub struct HookContext<'a> {
+    /// "scan" oder "folder"
+    pub source: &'a str,
+    pub file_path: &'a Path,
+    pub job_id: &'a str,
+    pub scanner: Option<&'a str>,
+    /// "uploaded" oder "duplicate"
+    pub result: &'a str,
+}
+
+/// Führt den Hook aus - Fehler werden nur geloggt, der Upload gilt trotzdem als erfolgreich
+pub async fn run_post_upload(config: &HookConfig, context: &HookContext<'_>) {
+    if !config.enabled || config.command.trim().is_empty() {
+        return;
+    }
+
+    let mut command = tokio::process::Command::new(&config.command);
+    command
+        .args(&config.args)
+        .env("DOCFLOW_SOURCE", context.source)
+        .env("DOCFLOW_FILE_PATH", context.file_path)
+        .env("DOCFLOW_JOB_ID", context.job_id)
+        .env("DOCFLOW_SCANNER", context.scanner.unwrap_or(""))
+        .env("DOCFLOW_RESULT", context.result)
+        .env("DOCFLOW_BRIDGE_VERSION", env!("CARGO_PKG_VERSION"))
+        .kill_on_drop(true);
+
+    let output = tokio::time::timeout(Duration::from_secs(config.timeout_secs), command.output()).await;
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => {
+            println!("🪝 Post-Upload-Hook ausgeführt (Job {})", context.job_id);
+        }
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            eprintln!("⚠ Post-Upload-Hook fehlgeschlagen ({}): {}", output.status, stderr.trim());
+        }
+        Ok(Err(e)) => {
+            eprintln!("⚠ Post-Upload-Hook konnte nicht gestartet werden: {}", e);
+        }
+        Err(_) => {
+            eprintln!("⚠ Post-Upload-Hook nach {}s abgebrochen", config.timeout_secs);
+        }
+    }
+}
+
+/// Führt den Hook für ein Scan-Dokument aus (wird dafür temporär auf Disk geschrieben)
+/// Der Hook braucht Klartext - die Datei ist nur für den Benutzer lesbar und wird danach sicher gelöscht
+pub async fn run_for_scan(config: &HookConfig, data: &spool::Buffer, job_id: &str, scanner: &str) {
+    if !config.enabled || config.command.trim().is_empty() {
+        return;
+    }
+
+    let temp_path = spool::spool_dir().join(format!("docflow-scan-{}.pdf", uuid::Uuid::new_v4()));
+    if let Err(e) = data.reader().and_then(|reader| spool::write_private(&temp_path, reader)) {
+        eprintln!("⚠ Temp-Datei für Post-Upload-Hook nicht schreibbar: {}", e);
+        return;
+    }
+
+    run_post_upload(
+        config,
+        &HookContext {
+            source: "scan",
+            file_path: &temp_path,
+            job_id,
+            scanner: Some(scanner),
+            result: "uploaded",
+        },
+    )
+    .await;
+
+    if let Err(e) = spool::secure_delete(&temp_path) {
+        eprintln!("⚠ Temp-Datei des Post-Upload-Hooks nicht gelöscht: {}", e);
+    }
+}
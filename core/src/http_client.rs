@@ -0,0 +1,332 @@
+// HTTP-Client - Zentrale reqwest-Clients mit Connection-Pooling statt eines neuen Clients je Anfrage
+// DocFlow: ein gemeinsamer Client (Proxy aus Einstellungen oder System, CA aus den Einstellungen), eSCL: je Timeout ein Client ohne Proxy
+// DocFlow-Anfragen tragen Bridge-ID und Version im User-Agent sowie eine X-Request-Id für den Log-Abgleich
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::system_proxy::DetectedProxy;
+
+/// Proxy-Verhalten für DocFlow-Anfragen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// Eingetragener Proxy, sonst System-Proxy (inkl. PAC) - wird bei Netzwechsel neu erkannt
+    #[default]
+    Auto,
+    /// Immer direkt verbinden (System-Proxy ignorieren)
+    Direct,
+}
+
+/// Netzwerk-Einstellungen für ausgehende Verbindungen
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HttpConfig {
+    /// Proxy für DocFlow-Anfragen (z.B. "http://proxy.firma.local:3128"), überschreibt den System-Proxy
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    #[serde(default)]
+    pub proxy_mode: ProxyMode,
+    /// Zusätzliche Root-CA (PEM-Datei), z.B. bei TLS-Inspection im Firmennetz
+    #[serde(default)]
+    pub ca_certificate_path: Option<String>,
+    /// Scanner-Zertifikate prüfen (Standard aus: Geräte nutzen fast immer selbstsignierte Zertifikate)
+    #[serde(default)]
+    pub verify_scanner_certs: bool,
+    /// Eigener Produktname im User-Agent (z.B. für Proxy-Regeln), Version und Bridge-ID werden immer angehängt
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// HTTP/2 nutzen, wenn der DocFlow-Server es anbietet (aus = immer HTTP/1.1, z.B. bei fehlerhaften Proxys)
+    #[serde(default = "default_http2")]
+    pub http2: bool,
+    /// Ungenutzte Verbindungen so lange offen halten (Sekunden)
+    #[serde(default = "default_pool_idle_timeout")]
+    pub pool_idle_timeout_secs: u64,
+    /// Keep-Alive-Intervall für TCP und HTTP/2-Pings (Sekunden, 0 = aus)
+    #[serde(default = "default_keep_alive")]
+    pub keep_alive_secs: u64,
+    /// Höchstzahl gleichzeitiger DocFlow-Anfragen (HTTP/2-Streams bzw. Verbindungen, 0 = unbegrenzt)
+    #[serde(default = "default_max_concurrent_streams")]
+    pub max_concurrent_streams: u32,
+    /// Authentifizierte DocFlow-Anfragen per HMAC signieren (Schlüssel aus dem Pairing, siehe request_signing)
+    #[serde(default)]
+    pub request_signing: bool,
+}
+
+fn default_http2() -> bool {
+    true
+}
+
+fn default_pool_idle_timeout() -> u64 {
+    90
+}
+
+fn default_keep_alive() -> u64 {
+    30
+}
+
+fn default_max_concurrent_streams() -> u32 {
+    8
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            proxy_url: None,
+            proxy_mode: ProxyMode::default(),
+            ca_certificate_path: None,
+            verify_scanner_certs: false,
+            user_agent: None,
+            http2: default_http2(),
+            pool_idle_timeout_secs: default_pool_idle_timeout(),
+            keep_alive_secs: default_keep_alive(),
+            max_concurrent_streams: default_max_concurrent_streams(),
+            request_signing: false,
+        }
+    }
+}
+
+struct Clients {
+    config: HttpConfig,
+    docflow: reqwest::Client,
+    /// Begrenzung gleichzeitiger DocFlow-Anfragen (None = unbegrenzt)
+    streams: Option<Arc<Semaphore>>,
+    /// eSCL-Clients je Timeout (Sekunden)
+    scanner: HashMap<u64, reqwest::Client>,
+}
+
+static CLIENTS: Mutex<Option<Clients>> = Mutex::new(None);
+/// Bridge-ID aus dem Pairing (für den User-Agent)
+static BRIDGE_ID: Mutex<Option<String>> = Mutex::new(None);
+
+/// Header für die Korrelations-ID jeder DocFlow-Anfrage
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Übernimmt neue Netzwerk-Einstellungen (Fehler bei ungültigem Proxy oder CA-Datei)
+pub fn apply_config(config: &HttpConfig) -> Result<(), String> {
+    let docflow = build_docflow(config)?;
+    if let Ok(mut clients) = CLIENTS.lock() {
+        *clients = Some(Clients {
+            config: config.clone(),
+            docflow,
+            streams: streams(config),
+            scanner: HashMap::new(),
+        });
+    }
+    Ok(())
+}
+
+/// Setzt die Bridge-ID nach Pairing bzw. Trennen (baut den DocFlow-Client mit neuem User-Agent neu)
+pub fn set_bridge_id(bridge_id: Option<&str>) {
+    if let Ok(mut id) = BRIDGE_ID.lock() {
+        *id = bridge_id.map(str::to_string);
+    }
+    reload();
+}
+
+/// Baut den DocFlow-Client mit den aktuellen Einstellungen neu auf (z.B. nach Wechsel des System-Proxys)
+pub fn reload() {
+    let mut guard = CLIENTS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(clients) = guard.as_mut() {
+        if let Ok(docflow) = build_docflow(&clients.config) {
+            clients.docflow = docflow;
+        }
+    }
+}
+
+/// User-Agent für DocFlow-Anfragen, z.B. "DocFlow-Scanner-Bridge/1.4.0 (bridge 3f2a...; windows)"
+pub fn user_agent(config: &HttpConfig) -> String {
+    let product = config
+        .user_agent
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or("DocFlow-Scanner-Bridge");
+    let bridge_id = BRIDGE_ID
+        .lock()
+        .ok()
+        .and_then(|id| id.clone())
+        .unwrap_or_else(|| "unpaired".to_string());
+    format!(
+        "{}/{} (bridge {}; {})",
+        product,
+        env!("CARGO_PKG_VERSION"),
+        bridge_id,
+        std::env::consts::OS
+    )
+}
+
+/// User-Agent ohne Bridge-ID und Betriebssystem für Anfragen an Dritte (z.B. Telemetrie)
+pub fn generic_user_agent() -> String {
+    format!("DocFlow-Scanner-Bridge/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Neue Korrelations-ID für eine Anfrage (kurz genug für Logzeilen)
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// Gemeinsamer Client für alle DocFlow-Anfragen (Klonen ist günstig, der Pool wird geteilt)
+pub fn docflow() -> reqwest::Client {
+    let mut guard = CLIENTS.lock().unwrap_or_else(|e| e.into_inner());
+    match guard.as_ref() {
+        Some(clients) => clients.docflow.clone(),
+        None => {
+            let config = HttpConfig::default();
+            let docflow = build_docflow(&config).unwrap_or_default();
+            *guard = Some(Clients {
+                streams: streams(&config),
+                config,
+                docflow: docflow.clone(),
+                scanner: HashMap::new(),
+            });
+            docflow
+        }
+    }
+}
+
+/// Anfragesignatur aktiviert
+pub fn request_signing() -> bool {
+    CLIENTS.lock().is_ok_and(|c| c.as_ref().is_some_and(|c| c.config.request_signing))
+}
+
+/// Wartet auf einen freien Platz für eine DocFlow-Anfrage (Freigabe beim Drop)
+pub async fn stream_permit() -> Option<OwnedSemaphorePermit> {
+    let streams = CLIENTS.lock().ok()?.as_ref()?.streams.clone()?;
+    streams.acquire_owned().await.ok()
+}
+
+fn streams(config: &HttpConfig) -> Option<Arc<Semaphore>> {
+    (config.max_concurrent_streams > 0).then(|| Arc::new(Semaphore::new(config.max_concurrent_streams as usize)))
+}
+
+/// Client für eSCL-/WSD-Anfragen an Scanner (kein Proxy, Timeout je Anfrageart)
+pub fn scanner(timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    let mut guard = CLIENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let verify_certs = guard.as_ref().is_some_and(|c| c.config.verify_scanner_certs);
+
+    if let Some(client) = guard.as_ref().and_then(|c| c.scanner.get(&timeout.as_secs())) {
+        return Ok(client.clone());
+    }
+
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(!verify_certs)
+        .no_proxy()
+        .timeout(timeout)
+        .build()?;
+
+    if let Some(clients) = guard.as_mut() {
+        clients.scanner.insert(timeout.as_secs(), client.clone());
+    }
+    Ok(client)
+}
+
+/// Client für Anfragen an Dritte (z.B. eigener Telemetrie-Endpunkt): Proxy und CA wie bei DocFlow,
+/// aber generischer User-Agent - Anfragen laufen per `.send()` ohne API-Key, Signatur, Limiter und Circuit-Breaker
+pub fn plain(timeout: Duration) -> Result<reqwest::Client, String> {
+    let config = CLIENTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|c| c.config.clone())
+        .unwrap_or_default();
+    let builder = reqwest::Client::builder()
+        .user_agent(generic_user_agent())
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(timeout);
+    with_network(builder, &config)?.build().map_err(|e| e.to_string())
+}
+
+fn build_docflow(config: &HttpConfig) -> Result<reqwest::Client, String> {
+    // Eine (HTTP/2-)Verbindung je Host bleibt offen, Folder-Uploads in Serie sparen so den TLS-Handshake
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent(config))
+        .connect_timeout(Duration::from_secs(10))
+        // Serverzertifikat an der Antwort (Ablaufwarnung, siehe cert_expiry)
+        .tls_info(true)
+        .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs));
+
+    if config.keep_alive_secs > 0 {
+        let interval = Duration::from_secs(config.keep_alive_secs);
+        builder = builder
+            .tcp_keepalive(interval)
+            .http2_keep_alive_interval(interval)
+            .http2_keep_alive_timeout(Duration::from_secs(10));
+    }
+    builder = if config.http2 {
+        // Große Uploads: Flusskontroll-Fenster an die Leitung anpassen
+        builder.http2_adaptive_window(true)
+    } else {
+        builder.http1_only()
+    };
+
+    with_network(builder, config)?.build().map_err(|e| e.to_string())
+}
+
+/// Proxy und zusätzliche Root-CA aus den Einstellungen
+fn with_network(mut builder: reqwest::ClientBuilder, config: &HttpConfig) -> Result<reqwest::ClientBuilder, String> {
+    // Vor der ersten System-Erkennung bleibt es beim reqwest-Standard (Umgebungsvariablen)
+    match crate::system_proxy::effective(config) {
+        Some(DetectedProxy { proxy_url: Some(proxy_url), no_proxy, bypass_local, .. }) => {
+            let url = reqwest::Url::parse(&proxy_url).map_err(|e| format!("Ungültiger Proxy '{}': {}", proxy_url, e))?;
+            let proxy = if bypass_local {
+                reqwest::Proxy::custom(move |target| {
+                    (!target.host_str().is_some_and(crate::system_proxy::is_plain_host_name)).then(|| url.clone())
+                })
+            } else {
+                reqwest::Proxy::all(url).map_err(|e| format!("Ungültiger Proxy '{}': {}", proxy_url, e))?
+            }
+            .no_proxy(no_proxy.as_deref().and_then(reqwest::NoProxy::from_string));
+            builder = builder.proxy(proxy);
+        }
+        Some(_) => builder = builder.no_proxy(),
+        None => {}
+    }
+
+    if let Some(pem) = ca_certificate(config)? {
+        let certificate = reqwest::Certificate::from_pem(&pem)
+            .map_err(|e| format!("CA-Zertifikat '{}' ungültig: {}", config.ca_certificate_path.as_deref().unwrap_or_default(), e))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    Ok(builder)
+}
+
+/// Zusätzliche Root-CA aus den Einstellungen (PEM, None = nicht eingetragen)
+pub fn ca_certificate(config: &HttpConfig) -> Result<Option<Vec<u8>>, String> {
+    let Some(path) = config.ca_certificate_path.as_deref().filter(|p| !p.trim().is_empty()) else {
+        return Ok(None);
+    };
+    std::fs::read(path)
+        .map(Some)
+        .map_err(|e| format!("CA-Zertifikat '{}' nicht lesbar: {}", path, e))
+}
+
+/// TLS-Konfiguration für DocFlow-Verbindungen außerhalb von reqwest (z.B. Reverse-Tunnel):
+/// System-Zertifikate plus die CA aus den Einstellungen
+pub fn rustls_config(config: &HttpConfig) -> Result<Arc<rustls::ClientConfig>, String> {
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::CertificateDer;
+
+    let mut roots = rustls::RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    roots.add_parsable_certificates(native.certs);
+    if let Some(pem) = ca_certificate(config)? {
+        let path = config.ca_certificate_path.as_deref().unwrap_or_default();
+        for certificate in CertificateDer::pem_slice_iter(&pem) {
+            let certificate = certificate.map_err(|e| format!("CA-Zertifikat '{}' ungültig: {}", path, e))?;
+            roots
+                .add(certificate)
+                .map_err(|e| format!("CA-Zertifikat '{}' ungültig: {}", path, e))?;
+        }
+    }
+    if roots.is_empty() {
+        return Err("Keine Root-Zertifikate für TLS gefunden".to_string());
+    }
+    Ok(Arc::new(
+        rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ))
+}
@@ -0,0 +1,261 @@
+// Bildverarbeitung - Dekodiert Scan-Seiten und Dateien zu Rasterbildern
+// Grundlage für Seitenanalysen (Barcodes etc.)
+
+use image::{DynamicImage, GenericImage, Rgb, RgbImage};
+use lopdf::{Object, ObjectId};
+use std::collections::HashSet;
+
+use crate::scanner::ScanRegion;
+
+/// Dekodiert ein Dokument zu Einzelseiten-Bildern
+/// Unterstützt JPEG/PNG/TIFF direkt sowie PDFs mit eingebetteten JPEG-Seiten
+pub fn decode_pages(data: &[u8], mime_type: &str) -> Vec<DynamicImage> {
+    if mime_type == "application/pdf" {
+        extract_pdf_jpegs(data)
+            .iter()
+            .filter_map(|jpeg| image::load_from_memory(jpeg).ok())
+            .collect()
+    } else {
+        image::load_from_memory(data).ok().into_iter().collect()
+    }
+}
+
+/// Liefert die Seiten eines Dokuments als JPEG-Daten
+/// JPEG und DCTDecode-Bilder aus PDFs werden ohne Neukomprimierung übernommen, andere Formate konvertiert
+/// Nicht dekodierbare Seiten sind ein Fehler (sonst fehlten sie unbemerkt im Dokument)
+pub fn pages_as_jpeg(data: &[u8], mime_type: &str) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+    match mime_type {
+        "image/jpeg" => Ok(vec![data.to_vec()]),
+        "application/pdf" => pdf_pages_as_jpeg(data),
+        _ => Ok(vec![encode_jpeg(&image::load_from_memory(data)?)?]),
+    }
+}
+
+/// Seitenbilder eines PDFs als JPEG - DCTDecode unverändert, Flate/LZW-Bilder (Grau, RGB, CMYK) neu kodiert
+fn pdf_pages_as_jpeg(data: &[u8]) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+    let doc = lopdf::Document::load_mem(data)?;
+
+    // Transparenz-Masken sind eigene Bild-Objekte, aber keine Seiten
+    let masks: HashSet<ObjectId> = doc
+        .objects
+        .values()
+        .filter_map(|obj| obj.as_stream().ok())
+        .flat_map(|stream| [b"SMask".as_slice(), b"Mask"].map(|key| stream.dict.get(key).and_then(Object::as_reference).ok()))
+        .flatten()
+        .collect();
+
+    let mut pages = Vec::new();
+    for (id, obj) in &doc.objects {
+        let Ok(stream) = obj.as_stream() else {
+            continue;
+        };
+        let is_image = stream.dict.get(b"Subtype").and_then(|s| s.as_name()).is_ok_and(|n| n == b"Image");
+        let is_mask = masks.contains(id) || stream.dict.get(b"ImageMask").and_then(Object::as_bool).unwrap_or(false);
+        if !is_image || is_mask {
+            continue;
+        }
+        if stream.filters().is_ok_and(|filters| filters == ["DCTDecode"]) {
+            pages.push(stream.content.clone());
+        } else {
+            pages.push(encode_jpeg(&pdf_image(&doc, stream)?)?);
+        }
+    }
+
+    if pages.is_empty() {
+        return Err("PDF enthält keine Seitenbilder".into());
+    }
+    Ok(pages)
+}
+
+/// Dekodiert ein unkomprimiertes bzw. Flate/LZW-komprimiertes Bild-XObject (1 oder 8 Bit je Komponente)
+fn pdf_image(doc: &lopdf::Document, stream: &lopdf::Stream) -> Result<DynamicImage, Box<dyn std::error::Error + Send + Sync>> {
+    let filters = stream.filters().unwrap_or_default();
+    if let Some(filter) = filters
+        .iter()
+        .find(|f| !matches!(f.as_str(), "FlateDecode" | "LZWDecode" | "ASCII85Decode"))
+    {
+        return Err(format!("PDF-Seitenbild mit {} wird nicht unterstützt", filter).into());
+    }
+
+    let number = |key: &[u8]| stream.dict.get(key).and_then(Object::as_i64).ok();
+    let (Some(width), Some(height)) = (number(b"Width"), number(b"Height")) else {
+        return Err("PDF-Seitenbild ohne Größenangabe".into());
+    };
+    let (width, height) = (u32::try_from(width)?, u32::try_from(height)?);
+    let bits = number(b"BitsPerComponent").unwrap_or(8);
+    let components = color_components(doc, stream.dict.get(b"ColorSpace").ok())?;
+
+    let samples = if filters.is_empty() {
+        stream.content.clone()
+    } else {
+        // lopdf dekomprimiert keine Bild-Streams - Subtype zum Dekodieren entfernen
+        let mut plain = stream.clone();
+        plain.dict.remove(b"Subtype");
+        plain.decompressed_content()?
+    };
+
+    let incomplete = || -> Box<dyn std::error::Error + Send + Sync> { "PDF-Seitenbild unvollständig".into() };
+    let pixels = width as usize * height as usize;
+    match (bits, components) {
+        (8, 1) => image::GrayImage::from_raw(width, height, samples.get(..pixels).ok_or_else(incomplete)?.to_vec())
+            .map(DynamicImage::ImageLuma8)
+            .ok_or_else(incomplete),
+        (8, 3) => image::RgbImage::from_raw(width, height, samples.get(..pixels * 3).ok_or_else(incomplete)?.to_vec())
+            .map(DynamicImage::ImageRgb8)
+            .ok_or_else(incomplete),
+        (8, 4) => {
+            let cmyk = samples.get(..pixels * 4).ok_or_else(incomplete)?;
+            let rgb = cmyk
+                .chunks_exact(4)
+                .flat_map(|p| {
+                    let white = 255 - p[3] as u32;
+                    [0, 1, 2].map(|i| ((255 - p[i] as u32) * white / 255) as u8)
+                })
+                .collect();
+            image::RgbImage::from_raw(width, height, rgb)
+                .map(DynamicImage::ImageRgb8)
+                .ok_or_else(incomplete)
+        }
+        // Schwarzweiß: Zeilen auf ganze Bytes aufgefüllt, gesetztes Bit = weiß
+        (1, 1) => {
+            let row_bytes = (width as usize).div_ceil(8);
+            if samples.len() < row_bytes * height as usize {
+                return Err(incomplete());
+            }
+            let gray = image::GrayImage::from_fn(width, height, |x, y| {
+                let byte = samples[y as usize * row_bytes + x as usize / 8];
+                image::Luma([if byte & (0x80 >> (x % 8)) != 0 { 255 } else { 0 }])
+            });
+            Ok(DynamicImage::ImageLuma8(gray))
+        }
+        _ => Err(format!("PDF-Seitenbild mit {} Bit und {} Farbkomponenten wird nicht unterstützt", bits, components).into()),
+    }
+}
+
+/// Anzahl der Farbkomponenten eines PDF-Farbraums (Geräte-, Kalibrier- und ICC-Farbräume)
+fn color_components(doc: &lopdf::Document, color_space: Option<&Object>) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(color_space) = color_space else {
+        return Err("PDF-Seitenbild ohne Farbraum".into());
+    };
+    let (_, color_space) = doc.dereference(color_space)?;
+    let (name, argument) = match color_space {
+        Object::Name(name) => (name.as_slice(), None),
+        Object::Array(items) => (items.first().and_then(|n| n.as_name().ok()).unwrap_or_default(), items.get(1)),
+        _ => (&b""[..], None),
+    };
+    match name {
+        b"DeviceGray" | b"CalGray" | b"G" => Ok(1),
+        b"DeviceRGB" | b"CalRGB" | b"RGB" => Ok(3),
+        b"DeviceCMYK" | b"CMYK" => Ok(4),
+        b"ICCBased" => {
+            let profile = argument.map(|a| doc.dereference(a)).transpose()?.and_then(|(_, p)| p.as_stream().ok());
+            match profile.and_then(|p| p.dict.get(b"N").and_then(Object::as_i64).ok()) {
+                Some(n @ (1 | 3 | 4)) => Ok(n as usize),
+                _ => Err("PDF-Seitenbild mit unbekanntem ICC-Profil".into()),
+            }
+        }
+        _ => Err(format!("PDF-Farbraum {} wird nicht unterstützt", String::from_utf8_lossy(name)).into()),
+    }
+}
+
+/// Schneidet die Bereiche aus einem Scan des umschließenden Bereichs aus
+/// und setzt sie untereinander auf eine Seite (Abstand 5 mm, weißer Hintergrund)
+pub fn compose_regions(image: &DynamicImage, regions: &[ScanRegion], dpi: u32) -> DynamicImage {
+    let Some(bounds) = ScanRegion::bounding(regions) else {
+        return image.clone();
+    };
+    let dpi = if dpi > 0 { dpi } else { 300 };
+    // 1/300 Zoll → Pixel
+    let to_px = |units: u32| (units as u64 * dpi as u64 / 300) as u32;
+    let gap = to_px(59);
+
+    let crops: Vec<DynamicImage> = regions
+        .iter()
+        .map(|region| {
+            let x = to_px(region.x_offset - bounds.x_offset).min(image.width().saturating_sub(1));
+            let y = to_px(region.y_offset - bounds.y_offset).min(image.height().saturating_sub(1));
+            let width = to_px(region.width).min(image.width() - x).max(1);
+            let height = to_px(region.height).min(image.height() - y).max(1);
+            image.crop_imm(x, y, width, height)
+        })
+        .collect();
+
+    let width = crops.iter().map(|c| c.width()).max().unwrap_or(1);
+    let height = crops.iter().map(|c| c.height()).sum::<u32>() + gap * (crops.len() as u32 - 1);
+    let mut page = RgbImage::from_pixel(width, height, Rgb([255, 255, 255]));
+
+    let mut y = 0;
+    for crop in crops {
+        let _ = page.copy_from(&crop.to_rgb8(), 0, y);
+        y += crop.height() + gap;
+    }
+    DynamicImage::ImageRgb8(page)
+}
+
+/// Kodiert ein Bild als JPEG (Alpha-Kanal wird verworfen, Graustufen bleiben einkanalig)
+pub fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>, image::ImageError> {
+    encode_jpeg_with_quality(image, 75)
+}
+
+/// Wie encode_jpeg, mit wählbarer Qualität (1-100)
+pub fn encode_jpeg_with_quality(image: &DynamicImage, quality: u8) -> Result<Vec<u8>, image::ImageError> {
+    let mut buffer = Vec::new();
+    let converted = match image {
+        DynamicImage::ImageLuma8(_) => image.clone(),
+        _ => DynamicImage::ImageRgb8(image.to_rgb8()),
+    };
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality);
+    converted.write_with_encoder(encoder)?;
+    Ok(buffer)
+}
+
+/// Extrahiert eingebettete JPEG-Bilder (DCTDecode) aus einem PDF
+/// Scanner-PDFs enthalten typischerweise genau ein JPEG pro Seite (Reihenfolge = Objekt-ID)
+pub fn extract_pdf_jpegs(data: &[u8]) -> Vec<Vec<u8>> {
+    let doc = match lopdf::Document::load_mem(data) {
+        Ok(doc) => doc,
+        Err(_) => return Vec::new(),
+    };
+
+    doc.objects
+        .values()
+        .filter_map(|obj| {
+            let stream = obj.as_stream().ok()?;
+            let is_image = stream
+                .dict
+                .get(b"Subtype")
+                .and_then(|s| s.as_name())
+                .map(|n| n == b"Image")
+                .unwrap_or(false);
+            let is_jpeg = match stream.dict.get(b"Filter") {
+                Ok(Object::Name(name)) => name == b"DCTDecode",
+                Ok(Object::Array(filters)) => filters
+                    .iter()
+                    .any(|f| f.as_name().map(|n| n == b"DCTDecode").unwrap_or(false)),
+                _ => false,
+            };
+
+            if is_image && is_jpeg {
+                Some(stream.content.clone())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// MIME-Typ anhand der Dateiendung
+pub fn mime_type_for(path: &std::path::Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .as_deref()
+    {
+        Some("pdf") => "application/pdf",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("tiff") | Some("tif") => "image/tiff",
+        _ => "application/octet-stream",
+    }
+}
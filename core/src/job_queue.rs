@@ -0,0 +1,42 @@
+// Job-Warteschlange - Gemeinsame Sicht auf laufende und wartende Scan-Jobs und Ordner-Uploads
+// Poller und Folder-Watcher liefern ihre Einträge, main.rs führt sie für die Warteschlangen-Ansicht zusammen
+
+use serde::Serialize;
+
+use crate::upload_progress::UploadKind;
+
+/// Zustand eines Eintrags
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// Wird gerade gescannt bzw. hochgeladen
+    Running,
+    /// Wartet auf den Worker bzw. den nächsten Durchlauf
+    Queued,
+    /// Nach einem Fehler zurückgestellt (nächster Versuch geplant)
+    Retrying,
+}
+
+/// Eintrag der Warteschlangen-Ansicht
+#[derive(Clone, Debug, Serialize)]
+pub struct ActiveJob {
+    pub kind: UploadKind,
+    /// Job-ID bzw. Dateipfad (für Abbrechen/Priorität)
+    pub id: String,
+    /// Scanner-ID bzw. Dateiname
+    pub name: String,
+    pub state: JobState,
+    /// Höher = früher
+    pub priority: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Sortierung für die Anzeige: laufende zuerst, dann nach Priorität
+pub fn sort_for_display(jobs: &mut [ActiveJob]) {
+    jobs.sort_by(|a, b| {
+        (b.state == JobState::Running)
+            .cmp(&(a.state == JobState::Running))
+            .then_with(|| b.priority.cmp(&a.priority))
+    });
+}
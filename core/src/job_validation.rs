@@ -0,0 +1,103 @@
+// Job-Prüfung - Gleicht Scan-Parameter vor dem Scan mit den Scanner-Fähigkeiten ab
+// Statt eines undurchsichtigen eSCL-Fehlers gibt es eine genaue Meldung (oder eine Herabstufung)
+
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::DiscoveredScanner;
+use crate::scan_poller::PendingScanJob;
+
+/// Verhalten bei nicht unterstützten Parametern
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValidationConfig {
+    /// true (Standard): auf unterstützte Werte herabstufen (mit Warnung), false: Job ablehnen
+    pub auto_downgrade: bool,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self { auto_downgrade: true }
+    }
+}
+
+/// Ergebnis der Prüfung: angepasster Job + Warnungen zu Herabstufungen
+pub struct ValidatedJob {
+    pub job: PendingScanJob,
+    pub warnings: Vec<String>,
+}
+
+/// Prüft Auflösung, Farbmodus, Quelle und Duplex gegen die bekannten Fähigkeiten
+/// Unbekannte Fähigkeiten (z.B. IP-Scan ohne Details oder ImageCaptureCore) werden nicht geprüft
+pub fn validate_job(
+    job: &PendingScanJob,
+    scanner: &DiscoveredScanner,
+    config: &ValidationConfig,
+) -> Result<ValidatedJob, String> {
+    let caps = &scanner.capabilities;
+    let mut job = job.clone();
+    if caps.unknown {
+        return Ok(ValidatedJob { job, warnings: Vec::new() });
+    }
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+
+    let mut check = |unsupported: String, downgrade: Option<String>| match downgrade {
+        Some(downgrade) if config.auto_downgrade => warnings.push(format!("{} → {}", unsupported, downgrade)),
+        _ => problems.push(unsupported),
+    };
+
+    // Auflösung
+    if caps.max_resolution > 0 && job.resolution > caps.max_resolution {
+        check(
+            format!("Auflösung {} dpi (max. {} dpi)", job.resolution, caps.max_resolution),
+            Some(format!("{} dpi", caps.max_resolution)),
+        );
+        job.resolution = caps.max_resolution;
+    }
+
+    // Quelle (nur wenn mindestens eine Quelle bekannt ist)
+    if caps.adf || caps.flatbed {
+        if job.source == "adf" && !caps.adf {
+            check("Einzug (ADF) nicht vorhanden".to_string(), Some("Flachbett".to_string()));
+            job.source = "flatbed".to_string();
+        } else if job.source != "adf" && !caps.flatbed {
+            check("Flachbett nicht vorhanden".to_string(), Some("Einzug (ADF)".to_string()));
+            job.source = "adf".to_string();
+        }
+    }
+
+    // Duplex
+    if job.duplex && !caps.duplex && (caps.adf || caps.flatbed) {
+        check("Duplex nicht unterstützt".to_string(), Some("Simplex".to_string()));
+        job.duplex = false;
+    } else if job.duplex && job.source != "adf" {
+        // Duplex gibt es nur im Einzug
+        check("Duplex nur mit Einzug (ADF) möglich".to_string(), Some("Simplex".to_string()));
+        job.duplex = false;
+    }
+
+    // Farbmodus ("auto" scannt in Farbe)
+    if !caps.color_modes.is_empty() {
+        let requested = match job.color_mode.to_lowercase().as_str() {
+            "grayscale" | "grayscale8" | "gray" | "bw" => "Grayscale8",
+            _ => "RGB24",
+        };
+        if !caps.color_modes.iter().any(|m| m == requested) {
+            if requested == "RGB24" && caps.color_modes.iter().any(|m| m == "Grayscale8") {
+                check("Farbscan nicht unterstützt".to_string(), Some("Graustufen".to_string()));
+                job.color_mode = "grayscale".to_string();
+            } else {
+                check(format!("Farbmodus '{}' nicht unterstützt", job.color_mode), None);
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(format!(
+            "Nicht unterstützte Parameter für '{}': {}",
+            scanner.name,
+            problems.join(", ")
+        ));
+    }
+
+    Ok(ValidatedJob { job, warnings })
+}
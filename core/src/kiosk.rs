@@ -0,0 +1,169 @@
+// Kiosk-Modus - Sperrt ändernde Befehle (Trennen, Folder-Sync, Einstellungen) hinter einer Admin-PIN
+// Die PIN liegt gesalzen und gehasht im Keyring, die Prüfung erfolgt ausschließlich im Backend
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::secrets;
+
+const KEYRING_ENTRY: &str = "kiosk_pin";
+
+/// Wie lange die Einstellungen nach korrekter PIN entsperrt bleiben
+const UNLOCK_DURATION: Duration = Duration::from_secs(300);
+/// Nach so vielen Fehlversuchen wird die PIN-Eingabe kurz gesperrt
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const LOCKOUT_DURATION: Duration = Duration::from_secs(60);
+/// Hash-Runden (erschwert Durchprobieren kurzer PINs)
+const HASH_ROUNDS: u32 = 100_000;
+
+/// Gespeicherte PIN
+#[derive(Serialize, Deserialize)]
+struct StoredPin {
+    salt: String,
+    hash: String,
+}
+
+/// Sperrstatus für die UI
+#[derive(Clone, Debug, Serialize)]
+pub struct LockStatus {
+    /// Kiosk-Modus aktiv (Admin-PIN gesetzt)
+    pub enabled: bool,
+    /// Aktuell entsperrt (bzw. Kiosk-Modus aus)
+    pub unlocked: bool,
+    /// Sekunden bis zur automatischen Sperre
+    pub unlocked_for_secs: Option<u64>,
+}
+
+/// Sperrzustand der laufenden Bridge
+pub struct KioskLock {
+    enabled: AtomicBool,
+    unlocked_until: Mutex<Option<Instant>>,
+    /// (Fehlversuche, gesperrt bis)
+    failures: Mutex<(u32, Option<Instant>)>,
+}
+
+impl Default for KioskLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KioskLock {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(load_pin().is_some()),
+            unlocked_until: Mutex::new(None),
+            failures: Mutex::new((0, None)),
+        }
+    }
+
+    pub async fn status(&self) -> LockStatus {
+        let enabled = self.enabled.load(Ordering::Relaxed);
+        let remaining = self
+            .unlocked_until
+            .lock()
+            .await
+            .and_then(|until| until.checked_duration_since(Instant::now()));
+        LockStatus {
+            enabled,
+            unlocked: !enabled || remaining.is_some(),
+            unlocked_for_secs: remaining.filter(|_| enabled).map(|d| d.as_secs()),
+        }
+    }
+
+    /// Für ändernde Befehle: Fehler, solange der Kiosk-Modus aktiv und nicht entsperrt ist
+    pub async fn ensure_unlocked(&self) -> Result<(), String> {
+        if self.status().await.unlocked {
+            Ok(())
+        } else {
+            Err("Einstellungen sind gesperrt - Admin-PIN erforderlich".to_string())
+        }
+    }
+
+    /// Entsperrt nach Prüfung der PIN (mit Sperre nach zu vielen Fehlversuchen)
+    pub async fn unlock(&self, pin: &str) -> Result<(), String> {
+        let mut failures = self.failures.lock().await;
+        if let Some(until) = failures.1 {
+            if Instant::now() < until {
+                return Err(format!(
+                    "Zu viele Fehlversuche - bitte {}s warten",
+                    until.duration_since(Instant::now()).as_secs() + 1
+                ));
+            }
+        }
+
+        let stored = load_pin().ok_or("Kiosk-Modus ist nicht aktiv")?;
+        let pin = pin.to_string();
+        let valid = tokio::task::spawn_blocking(move || hash_pin(&stored.salt, &pin) == stored.hash)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !valid {
+            failures.0 += 1;
+            if failures.0 >= MAX_FAILED_ATTEMPTS {
+                failures.0 = 0;
+                failures.1 = Some(Instant::now() + LOCKOUT_DURATION);
+            }
+            eprintln!("⚠ Falsche Admin-PIN");
+            return Err("Falsche PIN".to_string());
+        }
+
+        *failures = (0, None);
+        *self.unlocked_until.lock().await = Some(Instant::now() + UNLOCK_DURATION);
+        println!("🔓 Einstellungen entsperrt");
+        Ok(())
+    }
+
+    /// Sperrt sofort wieder
+    pub async fn lock(&self) {
+        *self.unlocked_until.lock().await = None;
+    }
+
+    /// Setzt eine neue Admin-PIN (None = Kiosk-Modus aus) - nur im entsperrten Zustand
+    pub async fn set_pin(&self, pin: Option<String>) -> Result<(), String> {
+        self.ensure_unlocked().await?;
+        let entry = secrets::entry(KEYRING_ENTRY).map_err(|e| e.to_string())?;
+
+        match pin {
+            Some(pin) => {
+                if pin.chars().count() < 4 {
+                    return Err("Die PIN muss mindestens 4 Zeichen haben".to_string());
+                }
+                let salt = uuid::Uuid::new_v4().to_string();
+                let hash = {
+                    let salt = salt.clone();
+                    tokio::task::spawn_blocking(move || hash_pin(&salt, &pin))
+                        .await
+                        .map_err(|e| e.to_string())?
+                };
+                let json = serde_json::to_string(&StoredPin { salt, hash }).map_err(|e| e.to_string())?;
+                entry.set_password(&json).map_err(|e| e.to_string())?;
+                self.enabled.store(true, Ordering::Relaxed);
+                println!("🔒 Kiosk-Modus aktiviert");
+            }
+            None => {
+                let _ = entry.delete_password();
+                self.enabled.store(false, Ordering::Relaxed);
+                println!("🔓 Kiosk-Modus deaktiviert");
+            }
+        }
+
+        self.lock().await;
+        Ok(())
+    }
+}
+
+fn load_pin() -> Option<StoredPin> {
+    secrets::get(KEYRING_ENTRY).and_then(|json| serde_json::from_str(&json).ok())
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut digest = Sha256::digest(format!("{}:{}", salt, pin).as_bytes());
+    for _ in 1..HASH_ROUNDS {
+        digest = Sha256::digest(digest);
+    }
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,73 @@
+// DocFlow Bridge Core - Kernlogik der Scanner Bridge ohne Tauri-Abhängigkeit
+// Discovery, eSCL, Pairing, Scan-Poller und Folder-Sync für Desktop-App, Headless-Betrieb und Tests
+
+pub mod announce;
+pub mod audit;
+pub mod autocolor;
+pub mod autocrop;
+pub mod barcode;
+pub mod cert_expiry;
+pub mod circuit_breaker;
+pub mod clipboard;
+pub mod config_bundle;
+pub mod counters;
+pub mod dedup;
+pub mod device_info;
+pub mod digest;
+pub mod discovery;
+pub mod enhance;
+pub mod escl_recording;
+pub mod events;
+pub mod file_grouping;
+pub mod firmware;
+pub mod folder_watcher;
+pub mod grpc;
+pub mod heic;
+pub mod hooks;
+pub mod http_client;
+pub mod imaging;
+pub mod job_queue;
+pub mod job_validation;
+pub mod kiosk;
+pub mod metrics;
+pub mod migrations;
+pub mod multipart;
+pub mod network_diagnostics;
+pub mod onboarding;
+pub mod orientation;
+pub mod pairing;
+pub mod paths;
+pub mod pdf;
+pub mod pipeline;
+pub mod profiles;
+pub mod push_scan;
+pub mod quirks;
+pub mod rate_limit;
+pub mod request_signing;
+pub mod resources;
+#[cfg(target_os = "linux")]
+pub mod sane;
+pub mod scan_poller;
+pub mod scanner;
+pub mod scanner_events;
+pub mod scanner_stats;
+pub mod scanner_sync;
+pub mod secrets;
+pub mod self_test;
+pub mod server_discovery;
+pub mod settings;
+pub mod snmp;
+pub mod splitter;
+pub mod spool;
+pub mod status_report;
+pub mod supervisor;
+pub mod system_proxy;
+pub mod telemetry;
+pub mod test_scan;
+pub mod timestamps;
+pub mod tray;
+pub mod tunnel;
+pub mod upload_progress;
+pub mod virtual_scanner;
+pub mod wol;
+pub mod wsd_discovery;
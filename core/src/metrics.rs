@@ -0,0 +1,254 @@
+// Metriken - Prometheus-Exporter (/metrics) für zentrales Monitoring der Bridges
+// Zähler sind globale Atomics, der HTTP-Endpunkt ist optional und standardmäßig nur lokal
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Konfiguration des Metrik-Endpunkts
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    /// Bind-Adresse ("127.0.0.1" = nur lokal, "0.0.0.0" = für zentralen Prometheus erreichbar)
+    pub bind_address: String,
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9464,
+        }
+    }
+}
+
+/// Bucket-Grenzen für Upload-Dauern (Sekunden)
+const LATENCY_BUCKETS: [f64; 8] = [0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Einfaches Histogramm (kumulative Buckets wie bei Prometheus)
+pub struct Histogram {
+    buckets: [AtomicU64; 8],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        Self {
+            buckets: [
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+            ],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, limit) in self.buckets.iter().zip(LATENCY_BUCKETS.iter()) {
+            if seconds <= *limit {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        for (bucket, limit) in self.buckets.iter().zip(LATENCY_BUCKETS.iter()) {
+            out.push_str(&format!(
+                "{}_bucket{{{},le=\"{}\"}} {}\n",
+                name,
+                labels,
+                limit,
+                bucket.load(Ordering::Relaxed)
+            ));
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        out.push_str(&format!("{}_bucket{{{},le=\"+Inf\"}} {}\n", name, labels, count));
+        out.push_str(&format!(
+            "{}_sum{{{}}} {}\n",
+            name,
+            labels,
+            self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+        ));
+        out.push_str(&format!("{}_count{{{}}} {}\n", name, labels, count));
+    }
+}
+
+/// Alle Bridge-Metriken
+pub struct Metrics {
+    pub polls: AtomicU64,
+    pub poll_errors: AtomicU64,
+    pub scan_jobs_succeeded: AtomicU64,
+    pub scan_jobs_failed: AtomicU64,
+    pub scan_pages: AtomicU64,
+    pub folder_uploads: AtomicU64,
+    pub folder_upload_errors: AtomicU64,
+    pub folder_duplicates: AtomicU64,
+    pub discovery_runs: AtomicU64,
+    pub discovered_scanners: AtomicU64,
+    pub scan_upload_duration: Histogram,
+    pub folder_upload_duration: Histogram,
+}
+
+/// Globale Metrik-Instanz
+pub static METRICS: Metrics = Metrics {
+    polls: AtomicU64::new(0),
+    poll_errors: AtomicU64::new(0),
+    scan_jobs_succeeded: AtomicU64::new(0),
+    scan_jobs_failed: AtomicU64::new(0),
+    scan_pages: AtomicU64::new(0),
+    folder_uploads: AtomicU64::new(0),
+    folder_upload_errors: AtomicU64::new(0),
+    folder_duplicates: AtomicU64::new(0),
+    discovery_runs: AtomicU64::new(0),
+    discovered_scanners: AtomicU64::new(0),
+    scan_upload_duration: Histogram::new(),
+    folder_upload_duration: Histogram::new(),
+};
+
+/// Erhöht einen Zähler um 1
+pub fn inc(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Rendert alle Metriken im Prometheus-Textformat
+pub fn render() -> String {
+    let m = &METRICS;
+    let mut out = String::new();
+
+    let counters: [(&str, &str, &AtomicU64); 9] = [
+        ("docflow_bridge_polls_total", "Anzahl Polls auf pending-scans", &m.polls),
+        ("docflow_bridge_poll_errors_total", "Fehlgeschlagene Polls", &m.poll_errors),
+        ("docflow_bridge_scan_jobs_succeeded_total", "Erfolgreich ausgeführte Scan-Jobs", &m.scan_jobs_succeeded),
+        ("docflow_bridge_scan_jobs_failed_total", "Fehlgeschlagene Scan-Jobs", &m.scan_jobs_failed),
+        ("docflow_bridge_scan_pages_total", "Gescannte Seiten", &m.scan_pages),
+        ("docflow_bridge_folder_uploads_total", "Hochgeladene Dateien (Folder-Sync)", &m.folder_uploads),
+        ("docflow_bridge_folder_upload_errors_total", "Fehler im Folder-Sync", &m.folder_upload_errors),
+        ("docflow_bridge_folder_duplicates_total", "Als Duplikat erkannte Dateien", &m.folder_duplicates),
+        ("docflow_bridge_discovery_runs_total", "Durchgeführte Scanner-Suchen", &m.discovery_runs),
+    ];
+
+    for (name, help, counter) in counters {
+        out.push_str(&format!("# HELP {} {}\n# TYPE {} counter\n", name, help, name));
+        out.push_str(&format!("{} {}\n", name, counter.load(Ordering::Relaxed)));
+    }
+
+    out.push_str("# HELP docflow_bridge_discovered_scanners Anzahl bekannter Scanner\n");
+    out.push_str("# TYPE docflow_bridge_discovered_scanners gauge\n");
+    out.push_str(&format!(
+        "docflow_bridge_discovered_scanners {}\n",
+        m.discovered_scanners.load(Ordering::Relaxed)
+    ));
+
+    out.push_str("# HELP docflow_bridge_upload_duration_seconds Dauer der Uploads zu DocFlow\n");
+    out.push_str("# TYPE docflow_bridge_upload_duration_seconds histogram\n");
+    m.scan_upload_duration
+        .render(&mut out, "docflow_bridge_upload_duration_seconds", "channel=\"scan\"");
+    m.folder_upload_duration
+        .render(&mut out, "docflow_bridge_upload_duration_seconds", "channel=\"folder\"");
+
+    out.push_str("# HELP docflow_bridge_info Bridge-Version\n# TYPE docflow_bridge_info gauge\n");
+    out.push_str(&format!(
+        "docflow_bridge_info{{version=\"{}\",os=\"{}\"}} 1\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS
+    ));
+
+    out
+}
+
+/// Laufender Exporter-Task (wird bei Konfigurationsänderung ersetzt)
+static SERVER_TASK: Mutex<Option<tokio::task::JoinHandle<()>>> = Mutex::new(None);
+
+/// Startet/stoppt den Exporter gemäß Konfiguration
+pub fn apply_config(config: &MetricsConfig) {
+    let mut task = SERVER_TASK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(handle) = task.take() {
+        handle.abort();
+    }
+
+    if !config.enabled {
+        return;
+    }
+
+    let address = format!("{}:{}", config.bind_address, config.port);
+    *task = Some(tokio::spawn(async move {
+        if let Err(e) = serve(&address).await {
+            eprintln!("❌ Metrik-Endpunkt {} fehlgeschlagen: {}", address, e);
+        }
+    }));
+}
+
+/// Minimaler HTTP-Server für GET /metrics
+async fn serve(address: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listener = TcpListener::bind(address).await?;
+    println!("📊 Metrik-Endpunkt: http://{}/metrics", address);
+
+    loop {
+        let mut socket = match listener.accept().await {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                eprintln!("⚠ Metrik-Endpunkt: Verbindung nicht angenommen: {}", e);
+                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                continue;
+            }
+        };
+        tokio::spawn(async move {
+            let Some(request) = read_request_head(&mut socket).await else {
+                return;
+            };
+
+            let response = if request.starts_with("GET /metrics") {
+                let body = render();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+/// Pause nach einem Fehler beim Annehmen einer Verbindung (z.B. keine freien Datei-Handles)
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+/// Größe des Anfrage-Kopfs, ab der die Verbindung verworfen wird
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+/// Wartezeit auf den vollständigen Anfrage-Kopf
+const REQUEST_HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Liest bis zum Ende der Header (Leerzeile) - Anfragen können in mehreren TCP-Segmenten ankommen
+async fn read_request_head(socket: &mut tokio::net::TcpStream) -> Option<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    let read = async {
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = socket.read(&mut buffer).await.ok()?;
+            if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+                return None;
+            }
+            head.extend_from_slice(&buffer[..n]);
+        }
+        Some(())
+    };
+    tokio::time::timeout(REQUEST_HEAD_TIMEOUT, read).await.ok()??;
+    Some(String::from_utf8_lossy(&head).into_owned())
+}
@@ -0,0 +1,154 @@
+// Konfigurations-Migrationen - Schema-Version für Einstellungen und Folder-Sync-Konfiguration
+// Ältere JSON-Stände werden beim Laden Schritt für Schritt angehoben statt verworfen zu werden
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Feld mit der Schema-Version im gespeicherten JSON (fehlt bei Ständen vor der Versionierung = 0)
+pub const VERSION_FIELD: &str = "schema_version";
+
+/// Ein Migrationsschritt hebt das JSON um genau eine Version an
+type Migration = fn(&mut Map<String, Value>);
+
+/// Versioniertes Konfigurationsformat
+pub struct Schema {
+    /// Bezeichnung für Log-Meldungen
+    pub name: &'static str,
+    /// Schritt i hebt Version i auf i + 1 - die aktuelle Version ist die Anzahl der Schritte
+    steps: &'static [Migration],
+    /// Unlesbare Bereiche einzeln auf Standardwerte setzen statt alles zu verwerfen
+    /// (nur für Strukturen, deren Felder alle `#[serde(default)]` haben)
+    salvage: bool,
+}
+
+impl Schema {
+    /// Aktuelle Schema-Version
+    pub fn version(&self) -> u32 {
+        self.steps.len() as u32
+    }
+}
+
+/// settings.json (BridgeSettings)
+pub const SETTINGS: Schema = Schema {
+    name: "Einstellungen",
+    steps: &[settings_v1],
+    salvage: true,
+};
+
+/// Folder-Sync-Konfiguration im Keyring (FolderSyncConfig)
+pub const FOLDER_SYNC: Schema = Schema {
+    name: "Folder-Sync-Konfiguration",
+    steps: &[folder_sync_v1],
+    salvage: false,
+};
+
+/// Gelesene Konfiguration mit Herkunft
+pub struct Loaded<T> {
+    pub value: T,
+    /// Schema-Version der gelesenen Daten
+    pub from_version: u32,
+    /// Bereiche, die nicht lesbar waren und jetzt Standardwerte haben
+    pub dropped: Vec<String>,
+}
+
+impl<T> Loaded<T> {
+    /// Migriert oder teilweise zurückgesetzt → sollte neu gespeichert werden
+    pub fn changed(&self, schema: &Schema) -> bool {
+        self.from_version < schema.version() || !self.dropped.is_empty()
+    }
+
+    /// Stammt von einer neueren Bridge-Version (unbekannte Felder gehen beim Speichern verloren)
+    pub fn newer(&self, schema: &Schema) -> bool {
+        self.from_version > schema.version()
+    }
+}
+
+/// Liest gespeichertes JSON und hebt es auf die aktuelle Schema-Version
+pub fn load<T: DeserializeOwned>(json: &str, schema: &Schema) -> Result<Loaded<T>, String> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("{}: kein gültiges JSON ({})", schema.name, e))?;
+    load_value(value, schema)
+}
+
+/// Wie `load`, für bereits geparste Werte (z.B. eingebettet in eine Export-Datei)
+pub fn load_value<T: DeserializeOwned>(value: Value, schema: &Schema) -> Result<Loaded<T>, String> {
+    let Value::Object(mut map) = value else {
+        return Err(format!("{}: JSON-Objekt erwartet", schema.name));
+    };
+
+    let from_version = map
+        .remove(VERSION_FIELD)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if from_version > schema.version() {
+        eprintln!(
+            "⚠ {} stammen von einer neueren Bridge-Version (Schema {}, erwartet {}), unbekannte Felder werden ignoriert",
+            schema.name,
+            from_version,
+            schema.version()
+        );
+    }
+
+    for (version, step) in schema.steps.iter().enumerate().skip(from_version as usize) {
+        step(&mut map);
+        println!("🔄 {}: Schema {} → {}", schema.name, version, version + 1);
+    }
+
+    match serde_json::from_value(Value::Object(map.clone())) {
+        Ok(value) => Ok(Loaded { value, from_version, dropped: Vec::new() }),
+        Err(e) if !schema.salvage => Err(format!("{}: {}", schema.name, e)),
+        Err(_) => salvage(map, schema, from_version),
+    }
+}
+
+/// Serialisiert mit aktueller Schema-Version
+pub fn store<T: Serialize>(value: &T, schema: &Schema) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(value)?;
+    if let Value::Object(map) = &mut value {
+        map.insert(VERSION_FIELD.to_string(), schema.version().into());
+    }
+    Ok(value)
+}
+
+/// Übernimmt alle einzeln lesbaren Bereiche, der Rest fällt auf Standardwerte zurück
+fn salvage<T: DeserializeOwned>(map: Map<String, Value>, schema: &Schema, from_version: u32) -> Result<Loaded<T>, String> {
+    let mut kept = Map::new();
+    let mut dropped = Vec::new();
+    for (key, section) in map {
+        let single = Map::from_iter([(key.clone(), section.clone())]);
+        if serde_json::from_value::<T>(Value::Object(single)).is_ok() {
+            kept.insert(key, section);
+        } else {
+            dropped.push(key);
+        }
+    }
+
+    let value = serde_json::from_value(Value::Object(kept)).map_err(|e| format!("{}: {}", schema.name, e))?;
+    eprintln!(
+        "⚠ {}: nicht lesbare Bereiche auf Standardwerte zurückgesetzt: {}",
+        schema.name,
+        dropped.join(", ")
+    );
+    Ok(Loaded { value, from_version, dropped })
+}
+
+/// Einstellungen 0 → 1: Bereiche, die ältere Builds als `null` geschrieben haben, erhalten Standardwerte
+fn settings_v1(map: &mut Map<String, Value>) {
+    map.retain(|_, section| !section.is_null());
+}
+
+/// Folder-Sync 0 → 1: Aktion in der Schreibweise der Oberfläche ("move", "delete", "keep") übernehmen,
+/// fehlende Felder ergänzen (ältere Builds speicherten nur beim Aktivieren)
+fn folder_sync_v1(map: &mut Map<String, Value>) {
+    let action = match map.get("post_upload_action").and_then(Value::as_str) {
+        Some(action) => match action.to_lowercase().replace(['_', '-'], "").as_str() {
+            "delete" => "Delete",
+            "keep" => "Keep",
+            _ => "MoveToSubfolder",
+        },
+        None => "MoveToSubfolder",
+    };
+    map.insert("post_upload_action".to_string(), action.into());
+    map.entry("enabled").or_insert(Value::Bool(true));
+}
@@ -0,0 +1,285 @@
+// Multipart-Uploads - eigene Kodierung statt reqwest::multipart, damit Länge und Inhalts-Hash vor dem Senden feststehen
+// Bei aktiver Anfragesignatur wird der Body einmal blockweise gehasht und danach erneut gestreamt (nie komplett im Speicher)
+
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use sha2::{Digest, Sha256};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+
+use crate::request_signing;
+use crate::spool;
+use crate::upload_progress::{Reporter, UploadKind};
+
+/// Größe der gestreamten Blöcke
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Inhalt eines Teils - jede Quelle lässt sich zum Hashen und zum Senden getrennt lesen
+enum Source {
+    Bytes(Vec<u8>),
+    /// Scan-Puffer (ausgelagerte Dokumente werden blockweise aus dem Spool entschlüsselt)
+    Buffer(spool::Buffer),
+    /// Datei auf der Festplatte (Länge beim Anlegen des Teils)
+    File { path: PathBuf, len: u64 },
+}
+
+/// Ein Feld der Form
+pub struct Part {
+    source: Source,
+    file_name: Option<String>,
+    mime: Option<String>,
+    /// Fortschrittsmeldung beim Senden (Herkunft, Job-ID bzw. Datei-Hash)
+    progress: Option<(UploadKind, String)>,
+}
+
+impl Part {
+    pub fn bytes(data: Vec<u8>) -> Self {
+        Self::new(Source::Bytes(data))
+    }
+
+    pub fn buffer(buffer: spool::Buffer) -> Self {
+        Self::new(Source::Buffer(buffer))
+    }
+
+    /// Teil, der die Datei blockweise von der Festplatte liest
+    pub async fn file(path: &Path) -> io::Result<Self> {
+        let len = tokio::fs::metadata(path).await?.len();
+        Ok(Self::new(Source::File { path: path.to_path_buf(), len }))
+    }
+
+    fn new(source: Source) -> Self {
+        Self {
+            source,
+            file_name: None,
+            mime: None,
+            progress: None,
+        }
+    }
+
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Content-Type des Teils (ungültige Werte würden den Body-Aufbau brechen)
+    pub fn mime_str(mut self, mime: &str) -> Result<Self, reqwest::header::InvalidHeaderValue> {
+        reqwest::header::HeaderValue::from_str(mime)?;
+        self.mime = Some(mime.to_string());
+        Ok(self)
+    }
+
+    /// Meldet beim Senden den Fortschritt (siehe upload_progress)
+    pub fn progress(mut self, kind: UploadKind, id: &str) -> Self {
+        self.progress = Some((kind, id.to_string()));
+        self
+    }
+
+    fn len(&self) -> u64 {
+        match &self.source {
+            Source::Bytes(data) => data.len() as u64,
+            Source::Buffer(buffer) => buffer.len() as u64,
+            Source::File { len, .. } => *len,
+        }
+    }
+
+    /// Liest den Inhalt blockweise, ohne den Teil aufzugeben
+    async fn hash_into(&self, hasher: &mut Sha256) -> io::Result<()> {
+        match &self.source {
+            Source::Bytes(data) => hasher.update(data),
+            Source::Buffer(buffer) => {
+                io::copy(&mut buffer.reader()?, hasher)?;
+            }
+            Source::File { path, .. } => {
+                let mut file = tokio::fs::File::open(path).await?;
+                let mut chunk = vec![0u8; CHUNK_SIZE];
+                loop {
+                    let read = file.read(&mut chunk).await?;
+                    if read == 0 {
+                        break;
+                    }
+                    hasher.update(&chunk[..read]);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Inhalt als Stream (Dateien und Spool werden erst hier geöffnet)
+    async fn into_stream(self) -> io::Result<BoxStream<'static, io::Result<Vec<u8>>>> {
+        let total_bytes = self.len();
+        let chunks = match self.source {
+            Source::Bytes(data) | Source::Buffer(spool::Buffer::Memory(data)) => memory_stream(data),
+            Source::Buffer(spool::Buffer::Spooled(file)) => spool_stream(file)?,
+            Source::File { path, .. } => file_stream(tokio::fs::File::open(path).await?),
+        };
+        Ok(match self.progress {
+            Some((kind, id)) => {
+                let mut reporter = Reporter::new(kind, &id, self.file_name.as_deref().unwrap_or_default(), total_bytes);
+                chunks.inspect_ok(move |chunk| reporter.advance(chunk.len())).boxed()
+            }
+            None => chunks,
+        })
+    }
+}
+
+/// Multipart-Form mit fester Boundary
+pub struct Form {
+    boundary: String,
+    parts: Vec<(String, Part)>,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("docflow-{}", uuid::Uuid::new_v4().simple()),
+            parts: Vec::new(),
+        }
+    }
+
+    pub fn text(self, name: &str, value: impl Into<String>) -> Self {
+        self.part(name, Part::bytes(value.into().into_bytes()))
+    }
+
+    pub fn part(mut self, name: &str, part: Part) -> Self {
+        self.parts.push((name.to_string(), part));
+        self
+    }
+
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// Länge des gesamten Bodys
+    pub fn len(&self) -> u64 {
+        let parts: u64 = self
+            .parts
+            .iter()
+            .map(|(name, part)| Self::header(&self.boundary, name, part).len() as u64 + part.len() + 2)
+            .sum();
+        parts + self.closing().len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// SHA256 des gesamten Bodys als Hex (Inhalts-Hash der Anfragesignatur)
+    pub async fn content_hash(&self) -> io::Result<String> {
+        let mut hasher = Sha256::new();
+        for (name, part) in &self.parts {
+            hasher.update(Self::header(&self.boundary, name, part));
+            part.hash_into(&mut hasher).await?;
+            hasher.update(b"\r\n");
+        }
+        hasher.update(self.closing());
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Setzt Content-Type, Content-Length und Body der Anfrage
+    /// Mit Anfragesignatur zusätzlich den vorab berechneten Inhalts-Hash, den request_signing::sign übernimmt
+    pub async fn attach(self, request: reqwest::RequestBuilder) -> io::Result<reqwest::RequestBuilder> {
+        let mut request = request
+            .header(reqwest::header::CONTENT_TYPE, self.content_type())
+            .header(reqwest::header::CONTENT_LENGTH, self.len());
+        if crate::http_client::request_signing() {
+            request = request.header(request_signing::CONTENT_HASH_HEADER, self.content_hash().await?);
+        }
+        Ok(request.body(reqwest::Body::wrap_stream(self.into_stream().await?)))
+    }
+
+    /// Gesamter Body als Stream: je Teil Boundary und Header, Inhalt, CRLF - danach die schließende Boundary
+    pub async fn into_stream(self) -> io::Result<BoxStream<'static, io::Result<Vec<u8>>>> {
+        let closing = self.closing();
+        let mut body = Vec::with_capacity(self.parts.len() * 3 + 1);
+        for (name, part) in self.parts {
+            let header = Self::header(&self.boundary, &name, &part);
+            body.push(stream::iter([Ok(header)]).boxed());
+            body.push(part.into_stream().await?);
+            body.push(stream::iter([Ok(b"\r\n".to_vec())]).boxed());
+        }
+        body.push(stream::iter([Ok(closing)]).boxed());
+        Ok(stream::iter(body).flatten().boxed())
+    }
+
+    /// Boundary und Header eines Teils
+    fn header(boundary: &str, name: &str, part: &Part) -> Vec<u8> {
+        let mut header = format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"", boundary, quote(name));
+        if let Some(file_name) = &part.file_name {
+            header.push_str(&format!("; filename=\"{}\"", quote(file_name)));
+        }
+        if let Some(mime) = &part.mime {
+            header.push_str(&format!("\r\nContent-Type: {}", mime));
+        }
+        header.push_str("\r\n\r\n");
+        header.into_bytes()
+    }
+
+    fn closing(&self) -> Vec<u8> {
+        format!("--{}--\r\n", self.boundary).into_bytes()
+    }
+}
+
+/// Anführungszeichen und Zeilenumbrüche in Feld- und Dateinamen maskieren (wie reqwest, RFC 7578)
+fn quote(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\r', "\\\r")
+        .replace('\n', "\\\n")
+}
+
+/// Blöcke erst beim Abruf kopieren, damit große Dateien nicht doppelt im Speicher liegen
+fn memory_stream(data: Vec<u8>) -> BoxStream<'static, io::Result<Vec<u8>>> {
+    let chunks = (0..data.len())
+        .step_by(CHUNK_SIZE)
+        .map(move |start| Ok(data[start..(start + CHUNK_SIZE).min(data.len())].to_vec()));
+    stream::iter(chunks).boxed()
+}
+
+/// Die Spool-Datei lebt bis zum Ende des Streams und wird danach gelöscht
+fn spool_stream(file: spool::SpoolFile) -> io::Result<BoxStream<'static, io::Result<Vec<u8>>>> {
+    let reader = file.reader()?;
+    Ok(stream::unfold(Some((file, reader)), |state| async move {
+        let (file, mut reader) = state?;
+        let read = tokio::task::spawn_blocking(move || {
+            let mut chunk = vec![0u8; CHUNK_SIZE];
+            let read = reader.read(&mut chunk).map(|read| {
+                chunk.truncate(read);
+                chunk
+            });
+            (reader, read)
+        })
+        .await;
+        match read {
+            Ok((_, Ok(chunk))) if chunk.is_empty() => None,
+            Ok((reader, Ok(chunk))) => Some((Ok(chunk), Some((file, reader)))),
+            Ok((_, Err(e))) => Some((Err(e), None)),
+            Err(e) => Some((Err(io::Error::other(e)), None)),
+        }
+    })
+    .boxed())
+}
+
+/// Nie mehr als ein Block der Datei im Speicher
+fn file_stream(file: tokio::fs::File) -> BoxStream<'static, io::Result<Vec<u8>>> {
+    stream::unfold(Some(file), |state| async move {
+        let mut file = state?;
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        match file.read(&mut chunk).await {
+            Ok(0) => None,
+            Ok(read) => {
+                chunk.truncate(read);
+                Some((Ok(chunk), Some(file)))
+            }
+            // Nach einem Lesefehler endet der Stream, die Anfrage schlägt fehl
+            Err(e) => Some((Err(e), None)),
+        }
+    })
+    .boxed()
+}
@@ -0,0 +1,274 @@
+// Netzwerk-Diagnose - Misst die Strecke zu DocFlow, um langsame Uploads dem Scanner oder dem WAN zuzuordnen
+// DNS-Auflösung, Round-Trip-Latenz zum Status-Endpoint und Upload-Durchsatz mit Testdaten (DocFlow verwirft sie)
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::self_test::{CheckStatus, SelfTestCheck};
+
+/// Testdaten für die Durchsatzmessung
+pub const UPLOAD_TEST_BYTES: usize = 1024 * 1024;
+/// Anzahl der Latenz-Messungen (die erste enthält den Verbindungsaufbau und zählt separat)
+const LATENCY_SAMPLES: usize = 5;
+/// Ab hier wird vor hoher Latenz bzw. geringem Durchsatz gewarnt
+const LATENCY_WARN_MS: u64 = 300;
+const UPLOAD_WARN_KBIT: u64 = 1000;
+
+/// Latenz-Statistik der Messreihe (ohne Verbindungsaufbau)
+#[derive(Clone, Debug, Serialize)]
+pub struct LatencyStats {
+    pub samples_ms: Vec<u64>,
+    pub min_ms: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Bericht der Netzwerk-Diagnose
+#[derive(Clone, Debug, Serialize)]
+pub struct NetworkDiagnosticsReport {
+    pub docflow_url: String,
+    pub passed: bool,
+    pub started_at: String,
+    /// Dauer der Namensauflösung (None = IP-Adresse in der URL bzw. fehlgeschlagen)
+    pub dns_ms: Option<u64>,
+    pub addresses: Vec<String>,
+    /// Erste Anfrage inkl. TCP- und TLS-Verbindungsaufbau
+    pub connect_ms: Option<u64>,
+    pub latency: Option<LatencyStats>,
+    pub upload_bytes: usize,
+    pub upload_ms: Option<u64>,
+    /// Effektiver Upload-Durchsatz in kbit/s
+    pub upload_kbit_per_sec: Option<u64>,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// Führt die Messungen nacheinander aus
+pub async fn run(api_key: &str, docflow_url: &str) -> NetworkDiagnosticsReport {
+    let mut report = NetworkDiagnosticsReport {
+        docflow_url: docflow_url.to_string(),
+        passed: false,
+        started_at: chrono::Utc::now().to_rfc3339(),
+        dns_ms: None,
+        addresses: Vec::new(),
+        connect_ms: None,
+        latency: None,
+        upload_bytes: 0,
+        upload_ms: None,
+        upload_kbit_per_sec: None,
+        checks: Vec::new(),
+    };
+
+    let url = match reqwest::Url::parse(docflow_url) {
+        Ok(url) => url,
+        Err(e) => {
+            report.checks.push(check("DNS", CheckStatus::Failed, format!("Ungültige DocFlow-URL: {}", e)));
+            return report;
+        }
+    };
+
+    // 1. Namensauflösung
+    let resolved = resolve(&url, &mut report).await;
+    if !resolved {
+        report.checks.push(check("Latenz", CheckStatus::Skipped, "DocFlow-Host nicht aufgelöst"));
+        report.checks.push(check("Upload", CheckStatus::Skipped, "DocFlow-Host nicht aufgelöst"));
+        return report;
+    }
+
+    // 2. Round-Trip-Latenz
+    let client = crate::http_client::docflow();
+    let reachable = measure_latency(&client, api_key, docflow_url, &mut report).await;
+
+    // 3. Upload-Durchsatz
+    if reachable {
+        measure_upload(&client, api_key, docflow_url, &mut report).await;
+    } else {
+        report.checks.push(check("Upload", CheckStatus::Skipped, "DocFlow nicht erreichbar"));
+    }
+
+    report.passed = report.checks.iter().all(|c| c.status != CheckStatus::Failed);
+    report
+}
+
+fn check(name: &str, status: CheckStatus, message: impl Into<String>) -> SelfTestCheck {
+    SelfTestCheck {
+        category: "network".to_string(),
+        name: name.to_string(),
+        status,
+        message: message.into(),
+    }
+}
+
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+async fn resolve(url: &reqwest::Url, report: &mut NetworkDiagnosticsReport) -> bool {
+    let Some(host) = url.host_str() else {
+        report.checks.push(check("DNS", CheckStatus::Failed, "DocFlow-URL ohne Host"));
+        return false;
+    };
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<std::net::IpAddr>() {
+        report.addresses.push(ip.to_string());
+        report.checks.push(check("DNS", CheckStatus::Skipped, format!("IP-Adresse {} (keine Auflösung nötig)", ip)));
+        return true;
+    }
+
+    let start = Instant::now();
+    let lookup = tokio::time::timeout(Duration::from_secs(10), tokio::net::lookup_host((host, port))).await;
+    let ms = elapsed_ms(start);
+    match lookup {
+        Ok(Ok(addresses)) => {
+            report.dns_ms = Some(ms);
+            report.addresses = addresses.map(|a| a.ip().to_string()).collect();
+            report.addresses.dedup();
+            report.checks.push(check(
+                "DNS",
+                CheckStatus::Ok,
+                format!("{} → {} in {} ms", host, report.addresses.join(", "), ms),
+            ));
+            true
+        }
+        Ok(Err(e)) => {
+            report.checks.push(check("DNS", CheckStatus::Failed, format!("{} nicht auflösbar: {}", host, e)));
+            false
+        }
+        Err(_) => {
+            report.checks.push(check("DNS", CheckStatus::Failed, format!("{}: keine Antwort des DNS-Servers nach 10s", host)));
+            false
+        }
+    }
+}
+
+/// Mehrere Anfragen an den Status-Endpoint über dieselbe Verbindung
+async fn measure_latency(
+    client: &reqwest::Client,
+    api_key: &str,
+    docflow_url: &str,
+    report: &mut NetworkDiagnosticsReport,
+) -> bool {
+    let url = format!("{}/api/scanner/bridge/status", docflow_url);
+    let mut samples = Vec::with_capacity(LATENCY_SAMPLES);
+
+    for _ in 0..LATENCY_SAMPLES {
+        let request = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .timeout(Duration::from_secs(10));
+        let response = match signed(request) {
+            Ok(request) => {
+                let start = Instant::now();
+                client.execute(request).await.map(|r| (r, start))
+            }
+            Err(e) => {
+                report.checks.push(check("Latenz", CheckStatus::Failed, e.to_string()));
+                return false;
+            }
+        };
+        match response {
+            // Auch 401/403 ist eine vollständige Antwort - für die Latenz genügt das
+            Ok((response, start)) => {
+                let _ = response.bytes().await;
+                samples.push(elapsed_ms(start));
+            }
+            Err(e) => {
+                report.checks.push(check("Latenz", CheckStatus::Failed, format!("DocFlow nicht erreichbar: {}", e)));
+                return false;
+            }
+        }
+    }
+
+    report.connect_ms = Some(samples.remove(0));
+    let stats = LatencyStats {
+        min_ms: samples.iter().copied().min().unwrap_or_default(),
+        max_ms: samples.iter().copied().max().unwrap_or_default(),
+        avg_ms: samples.iter().sum::<u64>() / samples.len().max(1) as u64,
+        samples_ms: samples,
+    };
+    let message = format!(
+        "Ø {} ms (min {} / max {} ms), Verbindungsaufbau {} ms",
+        stats.avg_ms,
+        stats.min_ms,
+        stats.max_ms,
+        report.connect_ms.unwrap_or_default()
+    );
+    let status = if stats.avg_ms > LATENCY_WARN_MS { CheckStatus::Warning } else { CheckStatus::Ok };
+    report.checks.push(check("Latenz", status, message));
+    report.latency = Some(stats);
+    true
+}
+
+/// Lädt zufällige Testdaten hoch (nicht komprimierbar, damit Proxys das Ergebnis nicht schönen)
+async fn measure_upload(
+    client: &reqwest::Client,
+    api_key: &str,
+    docflow_url: &str,
+    report: &mut NetworkDiagnosticsReport,
+) {
+    let payload: Vec<u8> = std::iter::repeat_with(|| *uuid::Uuid::new_v4().as_bytes())
+        .take(UPLOAD_TEST_BYTES / 16)
+        .flatten()
+        .collect();
+    let size = payload.len();
+
+    let request = client
+        .post(format!("{}/api/scanner/bridge/diagnostics/upload", docflow_url))
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/octet-stream")
+        .body(payload)
+        .timeout(Duration::from_secs(120));
+    let request = match signed(request) {
+        Ok(request) => request,
+        Err(e) => {
+            report.checks.push(check("Upload", CheckStatus::Failed, e.to_string()));
+            return;
+        }
+    };
+    let start = Instant::now();
+    let response = client.execute(request).await;
+    let ms = elapsed_ms(start).max(1);
+
+    match response {
+        Ok(r) if r.status().is_success() => {
+            // Bit je Millisekunde = kbit/s
+            let kbit_per_sec = size as u64 * 8 / ms;
+            report.upload_bytes = size;
+            report.upload_ms = Some(ms);
+            report.upload_kbit_per_sec = Some(kbit_per_sec);
+            let status = if kbit_per_sec < UPLOAD_WARN_KBIT { CheckStatus::Warning } else { CheckStatus::Ok };
+            report.checks.push(check(
+                "Upload",
+                status,
+                format!("{} KB in {} ms ({})", size / 1024, ms, format_rate(kbit_per_sec)),
+            ));
+        }
+        Ok(r) if r.status().as_u16() == 404 => report.checks.push(check(
+            "Upload",
+            CheckStatus::Skipped,
+            "DocFlow-Version ohne Upload-Test (Endpoint fehlt)",
+        )),
+        Ok(r) if r.status().as_u16() == 401 || r.status().as_u16() == 403 => report.checks.push(check(
+            "Upload",
+            CheckStatus::Failed,
+            "API-Key abgelehnt - Bridge neu verbinden",
+        )),
+        Ok(r) => report.checks.push(check("Upload", CheckStatus::Failed, format!("Unerwartete Antwort: HTTP {}", r.status()))),
+        Err(e) => report.checks.push(check("Upload", CheckStatus::Failed, format!("Upload abgebrochen nach {} ms: {}", ms, e))),
+    }
+}
+
+/// Signiert wie `send_limited`, aber ohne Limiter und Circuit-Breaker (deren Wartezeiten würden mitgemessen)
+fn signed(request: reqwest::RequestBuilder) -> Result<reqwest::Request, Box<dyn std::error::Error + Send + Sync>> {
+    let mut request = request.build()?;
+    crate::request_signing::sign(&mut request)?;
+    Ok(request)
+}
+
+fn format_rate(kbit_per_sec: u64) -> String {
+    if kbit_per_sec >= 1000 {
+        format!("{:.1} Mbit/s", kbit_per_sec as f64 / 1000.0)
+    } else {
+        format!("{} kbit/s", kbit_per_sec)
+    }
+}
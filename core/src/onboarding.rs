@@ -0,0 +1,84 @@
+// Onboarding - Persistenter Fortschritt der Ersteinrichtung
+// Der Setup-Assistent setzt nach einem Neustart dort fort, wo der Benutzer aufgehört hat
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::settings;
+
+/// Schritte der Ersteinrichtung (Reihenfolge = Fortschritt)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    NotPaired,
+    Paired,
+    ScannerFound,
+    FolderConfigured,
+    Done,
+}
+
+impl OnboardingStep {
+    /// Hinweis auf den fehlenden Schritt (für Tray-Tooltip), None wenn abgeschlossen
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            OnboardingStep::NotPaired => Some("Einrichtung: Mit DocFlow verbinden"),
+            OnboardingStep::Paired => Some("Einrichtung: Scanner suchen"),
+            OnboardingStep::ScannerFound => Some("Einrichtung: Scan-Ordner festlegen (optional)"),
+            OnboardingStep::FolderConfigured => Some("Einrichtung: Abschließen"),
+            OnboardingStep::Done => None,
+        }
+    }
+}
+
+/// Gespeicherter Onboarding-Stand
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OnboardingState {
+    pub step: OnboardingStep,
+    pub updated_at: Option<String>,
+}
+
+impl OnboardingState {
+    /// Lädt den Stand (NotPaired, falls noch nicht vorhanden)
+    pub fn load() -> Self {
+        std::fs::read_to_string(state_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or(Self {
+                step: OnboardingStep::NotPaired,
+                updated_at: None,
+            })
+    }
+
+    fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::create_dir_all(settings::data_dir());
+            let _ = std::fs::write(state_path(), json);
+        }
+    }
+}
+
+/// Setzt den Fortschritt auf mindestens `step` (nie zurück) → neuer Stand
+pub fn advance(step: OnboardingStep) -> OnboardingState {
+    let mut state = OnboardingState::load();
+    if step > state.step {
+        state.step = step;
+        state.updated_at = Some(chrono::Utc::now().to_rfc3339());
+        state.save();
+        println!("🧭 Einrichtung: {:?}", step);
+    }
+    state
+}
+
+/// Startet die Einrichtung von vorn
+pub fn reset() -> OnboardingState {
+    let state = OnboardingState {
+        step: OnboardingStep::NotPaired,
+        updated_at: Some(chrono::Utc::now().to_rfc3339()),
+    };
+    state.save();
+    state
+}
+
+fn state_path() -> PathBuf {
+    settings::data_dir().join("onboarding.json")
+}
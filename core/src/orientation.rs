@@ -0,0 +1,181 @@
+// Seitenausrichtung - Erkennt quer oder kopfüber eingelegte Seiten und dreht sie vor der PDF-Erzeugung aufrecht
+// Heuristik ohne OCR: Zeilenstruktur im Projektionsprofil (quer?) und Ober-/Unterlängen lateinischer Schrift (kopfüber?)
+
+use image::{DynamicImage, GrayImage};
+use serde::{Deserialize, Serialize};
+
+/// Auflösung der Analyse (längere Kante in Pixeln) - genug für Ober-/Unterlängen bei 10pt-Schrift
+const ANALYSIS_SIZE: u32 = 1600;
+/// Bis zu diesem Grauwert gilt ein Pixel als Schrift
+const INK_THRESHOLD: u8 = 140;
+/// Abschnitte mit weniger Schrift (Anteil am Maximum) gelten als Zwischenraum
+const GAP_LEVEL: f32 = 0.02;
+/// Mindestanteil an Zwischenräumen für erkennbare Textzeilen
+const MIN_GAP_SHARE: f32 = 0.05;
+
+/// Automatische Ausrichtung (global; Profile und Folder-Sync können sie einzeln schalten)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrientationConfig {
+    pub enabled: bool,
+    /// Mindestverhältnis zwischen erkannter und verworfener Lage - darunter bleibt die Seite unverändert
+    pub min_confidence: f32,
+}
+
+impl Default for OrientationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_confidence: 1.3,
+        }
+    }
+}
+
+/// Nötige Drehung im Uhrzeigersinn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    None,
+    Clockwise90,
+    Rotate180,
+    Clockwise270,
+}
+
+impl Rotation {
+    pub fn degrees(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Clockwise90 => 90,
+            Self::Rotate180 => 180,
+            Self::Clockwise270 => 270,
+        }
+    }
+
+    pub fn apply(self, image: DynamicImage) -> DynamicImage {
+        match self {
+            Self::None => image,
+            Self::Clockwise90 => image.rotate90(),
+            Self::Rotate180 => image.rotate180(),
+            Self::Clockwise270 => image.rotate270(),
+        }
+    }
+}
+
+/// Ermittelt die Drehung, mit der die Seite aufrecht steht
+/// Fotos, leere Seiten und unklare Fälle ergeben `Rotation::None`
+pub fn detect(image: &DynamicImage, config: &OrientationConfig) -> Rotation {
+    let gray = image.thumbnail(ANALYSIS_SIZE, ANALYSIS_SIZE).to_luma8();
+    let (width, height) = gray.dimensions();
+    let ink = gray.pixels().filter(|p| p.0[0] <= INK_THRESHOLD).count() as f32;
+    let coverage = ink / (width as f32 * height as f32).max(1.0);
+    if !(0.002..=0.4).contains(&coverage) {
+        return Rotation::None;
+    }
+
+    // Zwischen Textzeilen liegen leere Pixelzeilen, quer dazu kaum (Buchstabenabstände versetzen sich)
+    let rows = gap_share(&profile(&gray, true));
+    let columns = gap_share(&profile(&gray, false));
+    let sideways = if rows >= MIN_GAP_SHARE && rows >= columns * config.min_confidence {
+        false
+    } else if columns >= MIN_GAP_SHARE && columns >= rows * config.min_confidence {
+        true
+    } else {
+        return Rotation::None;
+    };
+
+    let upright = if sideways { image::imageops::rotate90(&gray) } else { gray };
+    let (above, below) = ascenders_and_descenders(&upright);
+    let flipped = if above >= below * config.min_confidence {
+        false
+    } else if below >= above * config.min_confidence {
+        true
+    } else if sideways {
+        // Quer ist sicher erkannt - ohne klare Richtung ist 90° die häufigere Einzugslage
+        false
+    } else {
+        return Rotation::None;
+    };
+
+    match (sideways, flipped) {
+        (false, false) => Rotation::None,
+        (false, true) => Rotation::Rotate180,
+        (true, false) => Rotation::Clockwise90,
+        (true, true) => Rotation::Clockwise270,
+    }
+}
+
+/// Dreht die Seite bei Bedarf aufrecht
+pub fn correct(image: DynamicImage, config: &OrientationConfig) -> (DynamicImage, Rotation) {
+    let rotation = detect(&image, config);
+    (rotation.apply(image), rotation)
+}
+
+/// Dreht eine Bilddatei aufrecht und speichert sie im selben Format (None = unverändert bzw. nicht lesbar)
+pub fn correct_file(data: &[u8], mime_type: &str, config: &OrientationConfig) -> Option<(Vec<u8>, Rotation)> {
+    let format = image::ImageFormat::from_mime_type(mime_type)?;
+    let image = image::load_from_memory_with_format(data, format).ok()?;
+    let (image, rotation) = correct(image, config);
+    if rotation == Rotation::None {
+        return None;
+    }
+    let data = if format == image::ImageFormat::Jpeg {
+        crate::imaging::encode_jpeg(&image).ok()?
+    } else {
+        let mut out = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut out, format).ok()?;
+        out.into_inner()
+    };
+    Some((data, rotation))
+}
+
+/// Schriftpixel je Zeile (rows = true) bzw. je Spalte
+fn profile(gray: &GrayImage, rows: bool) -> Vec<f32> {
+    let (width, height) = gray.dimensions();
+    let mut profile = vec![0.0; if rows { height } else { width } as usize];
+    for (x, y, pixel) in gray.enumerate_pixels() {
+        if pixel.0[0] <= INK_THRESHOLD {
+            profile[if rows { y } else { x } as usize] += 1.0;
+        }
+    }
+    profile
+}
+
+/// Anteil leerer Abschnitte innerhalb des beschrifteten Bereichs (Ränder zählen nicht)
+fn gap_share(profile: &[f32]) -> f32 {
+    let peak = profile.iter().copied().fold(0.0, f32::max);
+    let inked = |v: &f32| *v > peak * GAP_LEVEL;
+    let (Some(first), Some(last)) = (profile.iter().position(inked), profile.iter().rposition(inked)) else {
+        return 0.0;
+    };
+    let span = &profile[first..=last];
+    span.iter().filter(|v| !inked(v)).count() as f32 / span.len() as f32
+}
+
+/// Schrift über bzw. unter dem Mittelband (x-Höhe) aller Textzeilen
+/// Lateinische Schrift hat deutlich mehr Ober- als Unterlängen (Großbuchstaben, b d f h k l t)
+fn ascenders_and_descenders(gray: &GrayImage) -> (f32, f32) {
+    let rows = profile(gray, true);
+    let peak = rows.iter().copied().fold(0.0, f32::max);
+    let gap = peak * GAP_LEVEL;
+
+    let (mut above, mut below) = (0.0, 0.0);
+    let mut start = None;
+    for (index, value) in rows.iter().copied().chain(std::iter::once(0.0)).enumerate() {
+        match (start, value > gap) {
+            (None, true) => start = Some(index),
+            (Some(top), false) => {
+                let line = &rows[top..index];
+                start = None;
+                // Zu flache Zeilen (Linien, Störungen) haben kein Mittelband
+                if line.len() < 4 {
+                    continue;
+                }
+                let line_peak = line.iter().copied().fold(0.0, f32::max);
+                let core_top = line.iter().position(|v| *v >= line_peak * 0.5).unwrap_or(0);
+                let core_bottom = line.iter().rposition(|v| *v >= line_peak * 0.5).unwrap_or(line.len() - 1);
+                above += line[..core_top].iter().sum::<f32>();
+                below += line[core_bottom + 1..].iter().sum::<f32>();
+            }
+            _ => {}
+        }
+    }
+    (above, below)
+}
@@ -2,6 +2,9 @@
 // Unterstützt: QR-Code, manueller Token
 
 use serde::{Deserialize, Serialize};
+use crate::rate_limit::RateLimited;
+use crate::request_signing;
+use crate::secrets;
 
 /// Pairing-Code Struktur (aus QR-Code oder manuelle Eingabe)
 #[derive(Debug, Deserialize)]
@@ -22,6 +25,9 @@ pub struct PairingResult {
     pub refresh_token: String,
     pub docflow_url: String,
     pub tenant_name: String,
+    /// Schlüssel für die HMAC-Anfragesignatur (nur wenn DocFlow Signaturen verlangt)
+    #[serde(default)]
+    pub signing_secret: Option<String>,
 }
 
 /// Registrierungsanfrage an DocFlow
@@ -56,7 +62,7 @@ pub async fn pair(pairing_code: &str, docflow_url: Option<&str>) -> Result<Pairi
     };
 
     // Bridge bei DocFlow registrieren (mit effektiver URL inkl. korrektem Port)
-    let client = reqwest::Client::new();
+    let client = crate::http_client::docflow();
     let register_url = format!("{}/api/scanner/bridge/register", effective_url);
 
     let hostname = hostname::get()
@@ -74,7 +80,7 @@ pub async fn pair(pairing_code: &str, docflow_url: Option<&str>) -> Result<Pairi
     let response = client
         .post(&register_url)
         .json(&request)
-        .send()
+        .send_limited()
         .await?;
 
     if !response.status().is_success() {
@@ -87,14 +93,24 @@ pub async fn pair(pairing_code: &str, docflow_url: Option<&str>) -> Result<Pairi
     result.docflow_url = effective_url.clone();
 
     // API-Key sicher speichern (Keyring)
-    if let Ok(entry) = keyring::Entry::new("docflow-scanner-bridge", "api_key") {
-        let _ = entry.set_password(&result.api_key);
-    }
+    let _ = secrets::set("api_key", &result.api_key);
 
     // DocFlow-URL speichern (mit korrektem Port)
-    if let Ok(entry) = keyring::Entry::new("docflow-scanner-bridge", "docflow_url") {
-        let _ = entry.set_password(&effective_url);
+    let _ = secrets::set("docflow_url", &effective_url);
+
+    // Bridge-ID für den User-Agent nach einem Neustart
+    let _ = secrets::set("bridge_id", &result.bridge_id);
+
+    // Signaturschlüssel ersetzen (ein alter Schlüssel gehört zu einer früheren Kopplung)
+    match result.signing_secret.as_deref() {
+        Some(secret) => {
+            let _ = secrets::set(request_signing::SECRET_ENTRY, secret);
+        }
+        None => {
+            let _ = secrets::delete(request_signing::SECRET_ENTRY);
+        }
     }
+    request_signing::set_secret(result.signing_secret.as_deref());
 
     Ok(result)
 }
@@ -104,11 +120,11 @@ async fn resolve_manual_code(code: &str, docflow_url: &str) -> Result<PairingCod
     // DocFlow URL vom Parameter verwenden (z.B. "http://localhost:4000")
     let resolve_url = format!("{}/api/scanner/bridge/resolve-code", docflow_url.trim_end_matches('/'));
 
-    let client = reqwest::Client::new();
+    let client = crate::http_client::docflow();
     let response = client
         .post(&resolve_url)
         .json(&serde_json::json!({ "code": code }))
-        .send()
+        .send_limited()
         .await
         .map_err(|e| format!("Verbindung zu {} fehlgeschlagen: {}", resolve_url, e))?;
 
@@ -122,28 +138,21 @@ async fn resolve_manual_code(code: &str, docflow_url: &str) -> Result<PairingCod
 
 /// Lädt gespeicherte Verbindungsdaten
 pub async fn load_saved_connection() -> Option<(String, String)> {
-    let api_key = keyring::Entry::new("docflow-scanner-bridge", "api_key")
-        .ok()?
-        .get_password()
-        .ok()?;
-
-    let docflow_url = keyring::Entry::new("docflow-scanner-bridge", "docflow_url")
-        .ok()?
-        .get_password()
-        .ok()?;
+    let api_key = secrets::get("api_key")?;
+    let docflow_url = secrets::get("docflow_url")?;
 
     Some((api_key, docflow_url))
 }
 
 /// Validiert bestehende Verbindung
 pub async fn validate_connection(api_key: &str, docflow_url: &str) -> bool {
-    let client = reqwest::Client::new();
+    let client = crate::http_client::docflow();
     let status_url = format!("{}/api/scanner/bridge/status", docflow_url);
 
     let response = client
         .get(&status_url)
         .header("Authorization", format!("Bearer {}", api_key))
-        .send()
+        .send_limited()
         .await;
 
     response.map(|r| r.status().is_success()).unwrap_or(false)
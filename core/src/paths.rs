@@ -0,0 +1,100 @@
+// Pfade und Dateinamen - Lange Windows-Pfade und robuste Dateinamen für den Upload
+// Scan-Ordner auf Netzlaufwerken überschreiten schnell 260 Zeichen, Dateinamen kommen in NFD (macOS) oder mit Sonderzeichen
+
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+/// Längster Dateiname im Upload in Bytes (Grenze gängiger Dateisysteme)
+const MAX_UPLOAD_NAME_BYTES: usize = 255;
+
+/// Zeichen, die Windows bzw. SMB-Server in Dateinamen ablehnen
+const RESERVED_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Pfad für Dateizugriffe - unter Windows als Extended-Length-Pfad (ohne 260-Zeichen-Grenze), sonst unverändert
+pub fn extended(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        PathBuf::from(windows_extended(&absolute.to_string_lossy()))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        path.to_path_buf()
+    }
+}
+
+/// Wandelt einen absoluten Windows-Pfad in die Form `\\?\C:\...` bzw. `\\?\UNC\server\share\...`
+/// `.` und `..` werden aufgelöst, da Extended-Length-Pfade sie nicht interpretieren
+pub fn windows_extended(path: &str) -> String {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return path.to_string();
+    }
+    let path = path.replace('/', "\\");
+
+    let (prefix, rest) = if let Some(unc) = path.strip_prefix(r"\\") {
+        (r"\\?\UNC\", unc)
+    } else if path.as_bytes().get(1) == Some(&b':') && path.as_bytes().get(2) == Some(&b'\\') {
+        (r"\\?\", path.as_str())
+    } else {
+        // Relative Pfade lassen sich nicht erweitern
+        return path;
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => {}
+            // Laufwerk bzw. Server/Freigabe nicht verlassen
+            ".." if parts.len() > 1 => {
+                parts.pop();
+            }
+            ".." => {}
+            _ => parts.push(part),
+        }
+    }
+    format!("{}{}", prefix, parts.join("\\"))
+}
+
+/// Pfad für Anzeige und Einstellungen (ohne `\\?\`-Präfix)
+pub fn display(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", unc)
+    } else {
+        text.strip_prefix(r"\\?\").unwrap_or(&text).to_string()
+    }
+}
+
+/// Text in Unicode-NFC - macOS liefert Dateinamen zerlegt (NFD, "a" + Trema statt "ä"),
+/// DocFlow zeigt diese verstümmelt an und erkennt Duplikate über den Namen nicht
+pub fn nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// Dateiname für das Multipart-Feld: in NFC, ohne Steuer- und reservierte Zeichen,
+/// höchstens 255 Bytes (Endung bleibt erhalten)
+pub fn upload_name(name: &str) -> String {
+    let cleaned: String = nfc(name)
+        .chars()
+        .map(|c| if c.is_control() || RESERVED_CHARS.contains(&c) { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.').trim_end();
+    if cleaned.is_empty() {
+        return "scan".to_string();
+    }
+    if cleaned.len() <= MAX_UPLOAD_NAME_BYTES {
+        return cleaned.to_string();
+    }
+
+    // Stamm kürzen, Endung behalten
+    let (stem, extension) = match cleaned.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && ext.len() <= 16 => (stem, format!(".{}", ext)),
+        _ => (cleaned, String::new()),
+    };
+    let mut end = MAX_UPLOAD_NAME_BYTES - extension.len();
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", stem[..end].trim_end(), extension)
+}
@@ -0,0 +1,473 @@
+// PDF-Erzeugung - Setzt gescannte JPEG-Seiten zu einem PDF zusammen
+// Die JPEGs werden unverändert eingebettet (DCTDecode), keine Neukomprimierung
+// Neue PDFs werden Seite für Seite geschrieben, damit große Stapel aus dem Spool nicht komplett in den Speicher müssen
+
+use image::ImageDecoder;
+use lopdf::{dictionary, Document, Object};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::imaging;
+
+/// Maximale Seitenkante in Punkt (PDF-Implementierungslimit)
+const MAX_PAGE_POINTS: f32 = 14400.0;
+
+/// Erzeugt ein PDF mit einer Seite pro JPEG
+/// resolution: Scan-Auflösung in DPI (bestimmt die Seitengröße)
+pub fn assemble_jpeg_pdf(pages: &[Vec<u8>], resolution: u32) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    if pages.is_empty() {
+        return Err("Keine Seiten für PDF".into());
+    }
+
+    let mut writer = JpegPdfWriter::new(Vec::new(), resolution)?;
+    for jpeg in pages {
+        writer.add_page(jpeg)?;
+    }
+    writer.finish()
+}
+
+/// Schreibt ein JPEG-PDF Seite für Seite (z.B. direkt in eine Spool-Datei) - nur die aktuelle Seite liegt im Speicher
+/// Objekt 1 ist der Katalog, Objekt 2 der Seitenbaum (wird mit der Seitenliste am Ende geschrieben)
+pub struct JpegPdfWriter<W: Write> {
+    out: W,
+    dpi: f32,
+    /// Geschriebene Bytes (für die Offsets der Querverweistabelle)
+    position: usize,
+    /// Offset je Objekt (Index = Objektnummer - 1)
+    offsets: Vec<usize>,
+    kids: Vec<usize>,
+}
+
+impl<W: Write> JpegPdfWriter<W> {
+    pub fn new(out: W, resolution: u32) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut writer = Self {
+            out,
+            dpi: if resolution > 0 { resolution as f32 } else { 300.0 },
+            position: 0,
+            offsets: Vec::new(),
+            kids: Vec::new(),
+        };
+        writer.emit(b"%PDF-1.6\n%\xe2\xe3\xcf\xd3\n")?;
+        writer.object(1, b"<< /Type /Catalog /Pages 2 0 R >>")?;
+        // Platz für den Seitenbaum (Objekt 2), Offset folgt in finish()
+        writer.offsets.push(0);
+        Ok(writer)
+    }
+
+    /// Hängt eine Seite an (Bild unverändert als DCTDecode)
+    pub fn add_page(&mut self, jpeg: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Farbraum aus dem JPEG-Header - der Decoder liefert CMYK bereits nach RGB gewandelt
+        let header = match jpeg_header(jpeg) {
+            Some(header) => header,
+            None => {
+                let decoder = image::ImageReader::new(Cursor::new(jpeg))
+                    .with_guessed_format()?
+                    .into_decoder()?;
+                let (width, height) = decoder.dimensions();
+                JpegHeader {
+                    width,
+                    height,
+                    components: decoder.color_type().channel_count(),
+                    adobe: false,
+                }
+            }
+        };
+        let (width, height) = (header.width, header.height);
+        // Adobe-CMYK-JPEGs speichern die Farbwerte invertiert
+        let color_space = match header.components {
+            1 => "/DeviceGray",
+            4 if header.adobe => "/DeviceCMYK /Decode [1 0 1 0 1 0 1 0]",
+            4 => "/DeviceCMYK",
+            _ => "/DeviceRGB",
+        };
+
+        // Seitengröße in Punkt (1/72 Zoll)
+        let mut page_width = width as f32 * 72.0 / self.dpi;
+        let mut page_height = height as f32 * 72.0 / self.dpi;
+
+        // PDF erlaubt max. 14400 pt (200") je Kante - Langpapier per UserUnit skalieren
+        let user_unit = (page_width.max(page_height) / MAX_PAGE_POINTS).max(1.0);
+        page_width /= user_unit;
+        page_height /= user_unit;
+
+        let image_id = self.offsets.len() + 1;
+        let image = format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {} /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+            width,
+            height,
+            color_space,
+            jpeg.len()
+        );
+        self.begin_object(image_id)?;
+        self.emit(image.as_bytes())?;
+        self.emit(jpeg)?;
+        self.emit(b"\nendstream\nendobj\n")?;
+
+        let content = format!("q\n{} 0 0 {} 0 0 cm\n/Im0 Do\nQ\n", real(page_width), real(page_height));
+        let content_id = image_id + 1;
+        self.object(
+            content_id,
+            format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content).as_bytes(),
+        )?;
+
+        let user_unit = if user_unit > 1.0 { format!(" /UserUnit {}", real(user_unit)) } else { String::new() };
+        let page_id = content_id + 1;
+        self.object(
+            page_id,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R /Resources << /XObject << /Im0 {} 0 R >> >>{} >>",
+                real(page_width),
+                real(page_height),
+                content_id,
+                image_id,
+                user_unit
+            )
+            .as_bytes(),
+        )?;
+        self.kids.push(page_id);
+        Ok(())
+    }
+
+    /// Schreibt Seitenbaum, Querverweistabelle und Trailer
+    pub fn finish(mut self) -> Result<W, Box<dyn std::error::Error + Send + Sync>> {
+        if self.kids.is_empty() {
+            return Err("Keine Seiten für PDF".into());
+        }
+
+        let kids: Vec<String> = self.kids.iter().map(|id| format!("{} 0 R", id)).collect();
+        self.offsets[1] = self.position;
+        self.emit(
+            format!("2 0 obj\n<< /Type /Pages /Kids [{}] /Count {} >>\nendobj\n", kids.join(" "), self.kids.len()).as_bytes(),
+        )?;
+
+        let xref_position = self.position;
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            xref.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        xref.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            self.offsets.len() + 1,
+            xref_position
+        ));
+        self.emit(xref.as_bytes())?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn object(&mut self, id: usize, body: &[u8]) -> std::io::Result<()> {
+        self.begin_object(id)?;
+        self.emit(body)?;
+        self.emit(b"\nendobj\n")
+    }
+
+    fn begin_object(&mut self, id: usize) -> std::io::Result<()> {
+        debug_assert_eq!(id, self.offsets.len() + 1);
+        self.offsets.push(self.position);
+        self.emit(format!("{} 0 obj\n", id).as_bytes())
+    }
+
+    fn emit(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.out.write_all(bytes)?;
+        self.position += bytes.len();
+        Ok(())
+    }
+}
+
+/// Eckdaten aus dem Header eines JPEGs
+struct JpegHeader {
+    width: u32,
+    height: u32,
+    /// Farbkomponenten (1 = Grau, 3 = YCbCr/RGB, 4 = CMYK/YCCK)
+    components: u8,
+    /// APP14-Marker "Adobe" vorhanden
+    adobe: bool,
+}
+
+/// Liest die Marker bis zum ersten SOF (None = kein lesbarer JPEG-Header)
+fn jpeg_header(jpeg: &[u8]) -> Option<JpegHeader> {
+    if !jpeg.starts_with(&[0xff, 0xd8]) {
+        return None;
+    }
+    let mut adobe = false;
+    let mut pos = 2;
+    loop {
+        // Füllbytes vor dem Marker überspringen
+        while *jpeg.get(pos)? == 0xff && *jpeg.get(pos + 1)? == 0xff {
+            pos += 1;
+        }
+        if *jpeg.get(pos)? != 0xff {
+            return None;
+        }
+        let marker = *jpeg.get(pos + 1)?;
+        // Marker ohne Länge (RST, TEM)
+        if matches!(marker, 0x01 | 0xd0..=0xd7) {
+            pos += 2;
+            continue;
+        }
+        let length = u16::from_be_bytes([*jpeg.get(pos + 2)?, *jpeg.get(pos + 3)?]) as usize;
+        let segment = jpeg.get(pos + 4..pos + 2 + length)?;
+        match marker {
+            0xee => adobe |= segment.starts_with(b"Adobe"),
+            // SOF0-SOF15 außer DHT (C4), JPG (C8) und DAC (CC)
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                let header = JpegHeader {
+                    height: u16::from_be_bytes([*segment.get(1)?, *segment.get(2)?]) as u32,
+                    width: u16::from_be_bytes([*segment.get(3)?, *segment.get(4)?]) as u32,
+                    components: *segment.get(5)?,
+                    adobe,
+                };
+                // Höhe 0 = erst im DNL-Marker hinter den Bilddaten (dann liest der Decoder)
+                return (header.width > 0 && header.height > 0).then_some(header);
+            }
+            // Bilddaten ohne vorheriges SOF
+            0xda | 0xd9 => return None,
+            _ => {}
+        }
+        pos += 2 + length;
+    }
+}
+
+/// Zahl im PDF-Format (ohne Exponent, max. drei Nachkommastellen)
+fn real(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Komprimiert die Seiten eines Scan-PDFs neu, bis es max_bytes unterschreitet
+/// Erst Qualität senken, dann Auflösung - liefert das kleinste Ergebnis, falls das Ziel unerreichbar ist
+pub fn optimize_to_size(
+    data: Vec<u8>,
+    max_bytes: usize,
+    resolution: u32,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    if data.len() <= max_bytes {
+        return Ok(data);
+    }
+
+    let pages: Vec<image::DynamicImage> = imaging::decode_pages(&data, "application/pdf");
+    if pages.is_empty() {
+        return Ok(data);
+    }
+
+    let dpi = if resolution > 0 { resolution } else { 300 };
+    let original_size = data.len();
+    let mut best = data;
+
+    // (Skalierung, JPEG-Qualität) in aufsteigender Stärke
+    const STEPS: &[(f32, u8)] = &[(1.0, 70), (1.0, 55), (1.0, 40), (0.75, 45), (0.5, 45), (0.5, 30)];
+    for &(scale, quality) in STEPS {
+        let jpegs = pages
+            .iter()
+            .map(|page| {
+                let scaled = if scale < 1.0 {
+                    page.resize(
+                        (page.width() as f32 * scale) as u32,
+                        (page.height() as f32 * scale) as u32,
+                        image::imageops::FilterType::Triangle,
+                    )
+                } else {
+                    page.clone()
+                };
+                imaging::encode_jpeg_with_quality(&scaled, quality)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Seitengröße bleibt gleich: DPI mitskalieren
+        let candidate = assemble_jpeg_pdf(&jpegs, (dpi as f32 * scale) as u32)?;
+        if candidate.len() < best.len() {
+            best = candidate;
+        }
+        if best.len() <= max_bytes {
+            break;
+        }
+    }
+
+    println!(
+        "🗜 PDF optimiert: {} KB → {} KB (Ziel {} KB)",
+        original_size / 1024,
+        best.len() / 1024,
+        max_bytes / 1024
+    );
+    Ok(best)
+}
+
+/// Seiten-Attribute, die ein Seitenbaum-Knoten an seine Seiten vererbt
+const INHERITED_PAGE_KEYS: &[&[u8]] = &[b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Hängt mehrere PDFs in der angegebenen Reihenfolge zu einem Dokument aneinander
+/// Seiten werden unverändert übernommen (Textebene, Vektorgrafik) - Lesezeichen und Formulare entfallen
+pub fn merge(documents: &[Vec<u8>]) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    if documents.is_empty() {
+        return Err("Keine Dokumente zum Zusammenführen".into());
+    }
+
+    let mut merged = Document::with_version("1.6");
+    let pages_id = merged.new_object_id();
+    let mut kids: Vec<Object> = Vec::new();
+
+    for data in documents {
+        let mut doc = Document::load_mem(data)?;
+        doc.renumber_objects_with(merged.max_id + 1);
+        merged.max_id = doc.max_id;
+
+        for page_id in doc.get_pages().into_values() {
+            // Vererbte Attribute vor dem Umhängen direkt an die Seite schreiben
+            let mut inherited = Vec::new();
+            let mut parent = doc.get_dictionary(page_id)?.get(b"Parent").and_then(Object::as_reference).ok();
+            while let Some(node_id) = parent {
+                let node = doc.get_dictionary(node_id)?;
+                for key in INHERITED_PAGE_KEYS {
+                    if let Ok(value) = node.get(key) {
+                        inherited.push((key.to_vec(), value.clone()));
+                    }
+                }
+                parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+            }
+
+            let page = doc.get_object_mut(page_id)?.as_dict_mut()?;
+            for (key, value) in inherited {
+                if !page.has(&key) {
+                    page.set(key, value);
+                }
+            }
+            page.set("Parent", pages_id);
+            kids.push(page_id.into());
+        }
+        merged.objects.extend(doc.objects);
+    }
+
+    let page_count = kids.len() as i64;
+    merged.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => page_count,
+        }),
+    );
+    let catalog_id = merged.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    merged.trailer.set("Root", catalog_id);
+    // Kataloge und Seitenbäume der Einzeldokumente sind nicht mehr erreichbar
+    merged.prune_objects();
+
+    let mut buffer = Vec::new();
+    merged.save_to(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Schnelle Plausibilitätsprüfung vor dem Upload (Header, Dateiende, xref, Verschlüsselung)
+/// Liefert den Grund, falls das PDF nicht verarbeitet werden kann
+pub fn validate(data: &[u8]) -> Result<(), String> {
+    let head = &data[..data.len().min(1024)];
+    let tail = &data[data.len().saturating_sub(2048)..];
+    let offset = check_structure(head, tail, |offset| {
+        data.get(offset..).map(|target| target[..target.len().min(64)].to_vec())
+    })?;
+
+    let trailer_tail = &data[data.len().saturating_sub(TRAILER_WINDOW)..];
+    let xref = &data[offset..data.len().min(offset + TRAILER_WINDOW)];
+    if declares_encryption(trailer_tail, xref) {
+        return Err("PDF ist passwortgeschützt/verschlüsselt".to_string());
+    }
+
+    Ok(())
+}
+
+/// Wie `validate`, liest die Datei aber blockweise statt komplett in den Speicher (große Dateien)
+pub fn validate_file(path: &Path) -> Result<(), String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut read_at = |offset: u64, max: u64| -> Option<Vec<u8>> {
+        let mut buffer = Vec::new();
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        (&mut file).take(max).read_to_end(&mut buffer).ok()?;
+        Some(buffer)
+    };
+
+    let head = read_at(0, 1024).unwrap_or_default();
+    let tail = read_at(len.saturating_sub(2048), 2048).unwrap_or_default();
+    let offset = check_structure(&head, &tail, |offset| {
+        ((offset as u64) <= len).then(|| read_at(offset as u64, 64)).flatten()
+    })?;
+
+    // Nur das Trailer- bzw. xref-Stream-Dictionary lesen statt die ganze Datei zu durchsuchen
+    let trailer_tail = read_at(len.saturating_sub(TRAILER_WINDOW as u64), TRAILER_WINDOW as u64).unwrap_or_default();
+    let xref = read_at(offset as u64, TRAILER_WINDOW as u64).unwrap_or_default();
+    if declares_encryption(&trailer_tail, &xref) {
+        return Err("PDF ist passwortgeschützt/verschlüsselt".to_string());
+    }
+
+    Ok(())
+}
+
+/// Bereich für das Trailer-Dictionary (Dateiende bzw. ab dem xref-Stream)
+const TRAILER_WINDOW: usize = 16 * 1024;
+
+/// Verschlüsselung laut Trailer: Schlüssel /Encrypt im letzten Trailer-Dictionary oder im Dictionary
+/// des xref-Streams, auf den startxref zeigt (Inhaltsströme und Metadaten zählen nicht)
+/// tail: Dateiende, xref: Daten ab dem startxref-Offset
+fn declares_encryption(tail: &[u8], xref: &[u8]) -> bool {
+    let dictionary = if xref.starts_with(b"xref") {
+        // Klassische xref-Tabelle: Trailer steht zwischen "trailer" und "startxref" am Dateiende
+        let end = rfind(tail, b"startxref").unwrap_or(tail.len());
+        match rfind(&tail[..end], b"trailer") {
+            Some(start) => &tail[start..end],
+            None => return false,
+        }
+    } else {
+        // xref-Stream: Dictionary des Objekts bis zum Beginn der Stream-Daten
+        &xref[..find(xref, b"stream").unwrap_or(xref.len())]
+    };
+    has_key(dictionary, b"/Encrypt")
+}
+
+/// Name als vollständiger Schlüssel (nicht nur Präfix wie bei /EncryptMetadata)
+fn has_key(dictionary: &[u8], key: &[u8]) -> bool {
+    dictionary
+        .windows(key.len())
+        .enumerate()
+        .any(|(i, w)| w == key && !dictionary.get(i + key.len()).is_some_and(|b| b.is_ascii_alphanumeric()))
+}
+
+/// Header, Dateiende und startxref prüfen (xref_target: bis zu 64 Bytes ab einem Offset) → startxref-Offset
+fn check_structure(
+    head: &[u8],
+    tail: &[u8],
+    mut xref_target: impl FnMut(usize) -> Option<Vec<u8>>,
+) -> Result<usize, String> {
+    if find(head, b"%PDF-").is_none() {
+        return Err("Kein gültiger PDF-Header".to_string());
+    }
+
+    if find(tail, b"%%EOF").is_none() {
+        return Err("PDF unvollständig (kein %%EOF - abgeschnitten?)".to_string());
+    }
+
+    // startxref muss auf eine xref-Tabelle oder einen xref-Stream ("N 0 obj") zeigen
+    let startxref = rfind(tail, b"startxref").ok_or("PDF beschädigt (startxref fehlt)")?;
+    let offset: usize = std::str::from_utf8(&tail[startxref + 9..])
+        .ok()
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|n| n.parse().ok())
+        .ok_or("PDF beschädigt (ungültiger startxref-Wert)")?;
+    let target = xref_target(offset).ok_or("PDF beschädigt (xref-Offset außerhalb der Datei)")?;
+    let points_to_xref = target.starts_with(b"xref")
+        || (target.first().is_some_and(|b| b.is_ascii_digit()) && find(&target, b"obj").is_some());
+    if !points_to_xref {
+        return Err("PDF beschädigt (xref-Tabelle nicht gefunden)".to_string());
+    }
+
+    Ok(offset)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|w| w == needle)
+}
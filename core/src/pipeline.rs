@@ -0,0 +1,249 @@
+// Scan-Pipeline - Verarbeitet gescannte Seiten zwischen Scan und Upload
+// Ablauf: Seiten → JPEG → Bildverbesserung → Trennung → PDF je Dokument
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::autocolor::{self, AutoColorConfig};
+use crate::autocrop::{self, AutoCropConfig};
+use crate::barcode::BarcodeConfig;
+use crate::enhance::{self, EnhanceStage};
+use crate::orientation::{self, OrientationConfig, Rotation};
+use crate::scanner::ScanRegion;
+use crate::splitter::{self, SplitConfig};
+use crate::{imaging, pdf, spool};
+
+/// Seite bzw. Dokument: (Daten, MIME-Typ)
+pub type Page = (Vec<u8>, String);
+
+/// Gescannte Seite vor der Verarbeitung: (Puffer, MIME-Typ)
+pub type ScanPage = (spool::Buffer, String);
+
+/// Zusätzliche Ausgabeformate neben dem Hauptdokument
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum RenditionKind {
+    Thumbnail,     // Seite 1 verkleinert (Sofort-Vorschau in DocFlow)
+    FirstPageJpeg, // Seite 1 in voller Auflösung
+    PageJpegs,     // Jede Seite als eigenes JPEG
+}
+
+/// Konfiguration der zusätzlichen Ausgabeformate
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RenditionConfig {
+    pub kinds: Vec<RenditionKind>,
+    /// Maximale Kantenlänge des Thumbnails in Pixeln
+    pub thumbnail_size: u32,
+}
+
+impl Default for RenditionConfig {
+    fn default() -> Self {
+        Self {
+            kinds: Vec::new(),
+            thumbnail_size: 512,
+        }
+    }
+}
+
+/// Eine zusätzliche Ausgabe (wird als eigener Multipart-Teil hochgeladen)
+#[derive(Clone, Debug)]
+pub struct Rendition {
+    pub file_name: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Optionen für einen Pipeline-Durchlauf
+#[derive(Clone, Debug)]
+pub struct PipelineOptions {
+    pub resolution: u32,
+    /// Duplex-Scan: Seiten kommen paarweise (Vorder- und Rückseite), getrennt wird nur an ganzen Blättern
+    pub duplex: bool,
+    pub split: SplitConfig,
+    pub barcode: BarcodeConfig,
+    pub enhance: Vec<EnhanceStage>,
+    pub renditions: RenditionConfig,
+    /// Farbmodus "auto": einfarbige Seiten nach dem Scan umwandeln
+    pub auto_color: Option<AutoColorConfig>,
+    pub crop: AutoCropConfig,
+    /// Quer oder kopfüber eingelegte Seiten aufrecht drehen (None = aus)
+    pub orientation: Option<OrientationConfig>,
+    /// Mehrere Scan-Bereiche je Seite zu einer Seite kombinieren
+    pub regions: Vec<ScanRegion>,
+    /// Größenziel je Dokument in Bytes (None = keine Optimierung)
+    pub max_document_bytes: Option<usize>,
+}
+
+impl PipelineOptions {
+    /// Ob die Seiten überhaupt verarbeitet werden müssen
+    pub fn needs_processing(&self) -> bool {
+        self.split.enabled || !self.enhance.is_empty() || self.auto_color.is_some() || self.crop.enabled
+            || self.orientation.is_some()
+            || !self.regions.is_empty()
+            || self.max_document_bytes.is_some()
+    }
+}
+
+/// Verarbeitet die gescannten Seiten zu upload-fertigen Dokumenten
+/// Seiten werden einzeln geladen; aus ausgelagerten Seiten entsteht das PDF direkt im Spool
+/// CPU-intensiv - im Blocking-Threadpool aufrufen
+pub fn process(
+    pages: Vec<ScanPage>,
+    options: &PipelineOptions,
+) -> Result<Vec<ScanPage>, Box<dyn std::error::Error + Send + Sync>> {
+    // Ein fertiges PDF ohne Verarbeitung unverändert übernehmen, sonst alle Seiten zu einem PDF zusammenfügen
+    if !options.needs_processing() && pages.len() == 1 && pages[0].1 == "application/pdf" {
+        return Ok(pages);
+    }
+
+    let mut jpeg_pages: Vec<spool::Buffer> = Vec::new();
+    for (buffer, format) in pages {
+        let spooled = buffer.is_spooled();
+        let data = buffer.into_data()?;
+        for jpeg in imaging::pages_as_jpeg(&data, &format)? {
+            let jpeg = process_page(jpeg, options);
+            jpeg_pages.push(if spooled { spool::Buffer::spill(jpeg) } else { spool::Buffer::Memory(jpeg) });
+        }
+    }
+
+    // Trennung (ohne Trennung: ein Dokument mit allen Seiten)
+    let documents = if options.split.enabled {
+        let load = |page: &spool::Buffer| page.read().ok().and_then(|data| image::load_from_memory(&data).ok());
+        splitter::split_pages_by(jpeg_pages, options.duplex, load, &options.split, &options.barcode)
+    } else {
+        vec![jpeg_pages]
+    };
+
+    documents
+        .into_iter()
+        .filter(|pages| !pages.is_empty())
+        .map(|pages| {
+            let spooled = pages.iter().any(spool::Buffer::is_spooled);
+            let data = match options.max_document_bytes {
+                // Größenoptimierung komprimiert das fertige Dokument neu und braucht es dafür im Speicher
+                Some(max_bytes) => {
+                    let jpegs = pages
+                        .into_iter()
+                        .map(spool::Buffer::into_data)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let data = pdf::assemble_jpeg_pdf(&jpegs, options.resolution)?;
+                    spool::Buffer::Memory(pdf::optimize_to_size(data, max_bytes, options.resolution)?)
+                }
+                None if spooled => assemble_spooled(pages, options.resolution)?,
+                None => {
+                    let mut writer = pdf::JpegPdfWriter::new(Vec::new(), options.resolution)?;
+                    for page in pages {
+                        writer.add_page(&page.into_data()?)?;
+                    }
+                    spool::Buffer::Memory(writer.finish()?)
+                }
+            };
+            Ok((data, "application/pdf".to_string()))
+        })
+        .collect()
+}
+
+/// Schreibt das PDF Seite für Seite in eine neue Spool-Datei (je Seite liegt nur ein Bild im Speicher)
+fn assemble_spooled(
+    pages: Vec<spool::Buffer>,
+    resolution: u32,
+) -> Result<spool::Buffer, Box<dyn std::error::Error + Send + Sync>> {
+    let mut writer = pdf::JpegPdfWriter::new(spool::SpoolWriter::create()?, resolution)?;
+    for page in pages {
+        writer.add_page(&page.into_data()?)?;
+    }
+    Ok(spool::Buffer::Spooled(writer.finish()?.finish()?))
+}
+
+/// Bereiche kombinieren, Zuschnitt, Ausrichtung, Bildverbesserung, danach Farberkennung (auf dem bereinigten Bild)
+fn process_page(jpeg: Vec<u8>, options: &PipelineOptions) -> Vec<u8> {
+    if options.regions.is_empty()
+        && !options.crop.enabled
+        && options.orientation.is_none()
+        && options.enhance.is_empty()
+        && options.auto_color.is_none()
+    {
+        return jpeg;
+    }
+    match image::load_from_memory(&jpeg) {
+        Ok(mut img) => {
+            if options.regions.len() > 1 {
+                img = imaging::compose_regions(&img, &options.regions, options.resolution);
+            }
+            if options.crop.enabled {
+                img = autocrop::crop_to_paper(img, options.resolution, &options.crop);
+            }
+            if let Some(orientation) = &options.orientation {
+                let (rotated, rotation) = orientation::correct(img, orientation);
+                if rotation != Rotation::None {
+                    println!("↻ Seite um {}° gedreht", rotation.degrees());
+                }
+                img = rotated;
+            }
+            if !options.enhance.is_empty() {
+                img = enhance::enhance(&img, &options.enhance, options.resolution);
+            }
+            if let Some(auto_color) = &options.auto_color {
+                img = autocolor::convert_if_monochrome(img, auto_color);
+            }
+            imaging::encode_jpeg(&img).unwrap_or(jpeg)
+        }
+        Err(_) => jpeg,
+    }
+}
+
+/// Erzeugt die konfigurierten Zusatz-Ausgaben für ein Dokument
+/// CPU-intensiv - im Blocking-Threadpool aufrufen
+pub fn renditions(document: &Page, config: &RenditionConfig) -> Vec<Rendition> {
+    if config.kinds.is_empty() {
+        return Vec::new();
+    }
+
+    let (data, mime_type) = document;
+    let pages = match imaging::pages_as_jpeg(data, mime_type) {
+        Ok(pages) => pages,
+        Err(e) => {
+            eprintln!("⚠ Zusatz-Ausgaben nicht erzeugt: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut renditions = Vec::new();
+
+    for kind in &config.kinds {
+        match kind {
+            RenditionKind::Thumbnail => {
+                let thumbnail = pages
+                    .first()
+                    .and_then(|jpeg| image::load_from_memory(jpeg).ok())
+                    .and_then(|img| {
+                        imaging::encode_jpeg(&img.thumbnail(config.thumbnail_size, config.thumbnail_size)).ok()
+                    });
+                if let Some(data) = thumbnail {
+                    renditions.push(jpeg_rendition("thumbnail.jpg".to_string(), data));
+                }
+            }
+            RenditionKind::FirstPageJpeg => {
+                if let Some(jpeg) = pages.first() {
+                    renditions.push(jpeg_rendition("page_1.jpg".to_string(), jpeg.clone()));
+                }
+            }
+            RenditionKind::PageJpegs => {
+                for (index, jpeg) in pages.iter().enumerate() {
+                    renditions.push(jpeg_rendition(format!("page_{}.jpg", index + 1), jpeg.clone()));
+                }
+            }
+        }
+    }
+
+    // FirstPageJpeg + PageJpegs nicht doppelt hochladen (unabhängig von der Reihenfolge in der Konfiguration)
+    let mut seen = HashSet::new();
+    renditions.retain(|r| seen.insert(r.file_name.clone()));
+    renditions
+}
+
+fn jpeg_rendition(file_name: String, data: Vec<u8>) -> Rendition {
+    Rendition {
+        file_name,
+        mime_type: "image/jpeg".to_string(),
+        data,
+    }
+}
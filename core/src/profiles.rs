@@ -0,0 +1,189 @@
+// Scan-Profile - Zentral in DocFlow definierte Profile ("Rechnung 300dpi Duplex S/W")
+// Werden vom Server geladen, lokal gecacht und in Pending-Jobs mit profile_id eingesetzt
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::enhance::EnhanceStage;
+use crate::scan_poller::PendingScanJob;
+use crate::scanner::ScanRegion;
+use crate::settings;
+use crate::rate_limit::RateLimited;
+
+/// Wie lange die Profile gültig sind, bevor neu geladen wird
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Scan-Profil aus DocFlow
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScanProfile {
+    pub id: String,
+    pub name: String,
+    pub resolution: u32,
+    pub color_mode: String,
+    pub source: String,
+    pub duplex: bool,
+    pub format: String,
+    /// Bildverbesserung für dieses Profil (None = globale Einstellung)
+    #[serde(default)]
+    pub enhancements: Option<Vec<EnhanceStage>>,
+    /// Langpapier-Länge in mm (None = Letter)
+    #[serde(default)]
+    pub paper_length_mm: Option<u32>,
+    /// Scan-Bereiche, die zu einer Seite kombiniert werden
+    #[serde(default)]
+    pub regions: Vec<ScanRegion>,
+    /// Größenziel je Dokument in KB (z.B. 2048 = max. 2 MB)
+    #[serde(default)]
+    pub max_document_kb: Option<u32>,
+    /// Seiten automatisch aufrecht drehen (None = globale Einstellung)
+    #[serde(default)]
+    pub auto_orient: Option<bool>,
+}
+
+/// Response des scan-profiles Endpoints
+#[derive(Debug, Deserialize)]
+struct ScanProfilesResponse {
+    profiles: Vec<ScanProfile>,
+}
+
+/// Profil-Cache (Speicher + Datei für Offline-Start)
+pub struct ProfileCache {
+    profiles: RwLock<Vec<ScanProfile>>,
+    last_refresh: RwLock<Option<Instant>>,
+}
+
+impl Default for ProfileCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProfileCache {
+    pub fn new() -> Self {
+        Self {
+            profiles: RwLock::new(load_cached_profiles()),
+            last_refresh: RwLock::new(None),
+        }
+    }
+
+    /// Lädt die Profile von DocFlow und aktualisiert den Cache
+    pub async fn refresh(&self, docflow_url: &str, api_key: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/scan-profiles", docflow_url);
+
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .timeout(Duration::from_secs(10))
+            .send_limited()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Scan-Profile laden fehlgeschlagen: {}", error_text).into());
+        }
+
+        let result: ScanProfilesResponse = response.json().await?;
+        println!("✓ {} Scan-Profile geladen", result.profiles.len());
+
+        if let Ok(json) = serde_json::to_string(&result.profiles) {
+            let _ = std::fs::create_dir_all(settings::data_dir());
+            let _ = std::fs::write(cache_path(), json);
+        }
+
+        *self.profiles.write().await = result.profiles;
+        *self.last_refresh.write().await = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Lädt neu, falls der Cache veraltet ist
+    pub async fn refresh_if_stale(&self, docflow_url: &str, api_key: &str) {
+        let stale = self
+            .last_refresh
+            .read()
+            .await
+            .map(|t| t.elapsed() > REFRESH_INTERVAL)
+            .unwrap_or(true);
+
+        if stale {
+            if let Err(e) = self.refresh(docflow_url, api_key).await {
+                eprintln!("⚠ {}", e);
+                // Nicht bei jedem Poll erneut versuchen
+                *self.last_refresh.write().await = Some(Instant::now());
+            }
+        }
+    }
+
+    /// Sucht ein Profil (lädt bei unbekannter ID einmal neu)
+    pub async fn get(&self, profile_id: &str, docflow_url: &str, api_key: &str) -> Option<ScanProfile> {
+        if let Some(profile) = self.find(profile_id).await {
+            return Some(profile);
+        }
+
+        if let Err(e) = self.refresh(docflow_url, api_key).await {
+            eprintln!("⚠ {}", e);
+        }
+        self.find(profile_id).await
+    }
+
+    async fn find(&self, profile_id: &str) -> Option<ScanProfile> {
+        self.profiles
+            .read()
+            .await
+            .iter()
+            .find(|p| p.id == profile_id)
+            .cloned()
+    }
+
+    /// Alle gecachten Profile
+    pub async fn list(&self) -> Vec<ScanProfile> {
+        self.profiles.read().await.clone()
+    }
+}
+
+/// Setzt die Profil-Werte in einen Job ein - explizit gesetzte Job-Werte haben Vorrang
+pub fn apply_profile(job: &PendingScanJob, profile: &ScanProfile) -> PendingScanJob {
+    let mut resolved = job.clone();
+    if resolved.resolution == 0 {
+        resolved.resolution = profile.resolution;
+    }
+    if resolved.color_mode.is_empty() {
+        resolved.color_mode = profile.color_mode.clone();
+    }
+    if resolved.source.is_empty() {
+        resolved.source = profile.source.clone();
+    }
+    if resolved.format.is_empty() {
+        resolved.format = profile.format.clone();
+    }
+    resolved.duplex = resolved.duplex || profile.duplex;
+    if resolved.enhancements.is_none() {
+        resolved.enhancements = profile.enhancements.clone();
+    }
+    if resolved.paper_length_mm.is_none() {
+        resolved.paper_length_mm = profile.paper_length_mm;
+    }
+    if resolved.regions.is_empty() {
+        resolved.regions = profile.regions.clone();
+    }
+    if resolved.max_document_kb.is_none() {
+        resolved.max_document_kb = profile.max_document_kb;
+    }
+    if resolved.auto_orient.is_none() {
+        resolved.auto_orient = profile.auto_orient;
+    }
+    resolved
+}
+
+fn cache_path() -> PathBuf {
+    settings::data_dir().join("scan_profiles.json")
+}
+
+fn load_cached_profiles() -> Vec<ScanProfile> {
+    std::fs::read_to_string(cache_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,410 @@
+// Push-Scan - Scan-Taste am Gerät löst Scan nach DocFlow aus (ohne Web-UI)
+// WSD: Bridge abonniert ScanAvailableEvent (WS-Eventing), Gerät zeigt "DocFlow" als Ziel an
+// Das Dokument selbst wird über eSCL abgeholt (einheitlicher Scan-Pfad wie bei Poller-Jobs)
+// Zusätzlich werden Zustandsänderungen abonniert (siehe scanner_events), statt ScannerStatus zu pollen
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+use crate::discovery::DiscoveredScanner;
+use crate::events::{self, EventKind};
+use crate::scan_poller::ScanPoller;
+use crate::scanner_events;
+use crate::settings::BridgeSettings;
+
+/// Laufzeit eines WS-Eventing-Abos (wird vor Ablauf erneuert)
+pub const SUBSCRIPTION_SECS: u64 = 3600;
+
+/// Pause nach einem Fehler beim Annehmen einer Verbindung (z.B. keine freien Datei-Handles)
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Konfiguration für Push-Scans
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PushScanConfig {
+    pub enabled: bool,
+    /// Name, den das Gerät im Zielmenü anzeigt
+    pub display_name: String,
+    /// Port für eingehende WSD-Events (muss vom Scanner erreichbar sein)
+    pub listen_port: u16,
+    /// Lokale Adresse für eingehende Events (None = LAN-Adresse der Bridge)
+    #[serde(default)]
+    pub listen_address: Option<String>,
+    /// Weitere Absender-IPs, deren Events angenommen werden (z.B. Scanner hinter NAT)
+    /// Ohne Eintrag nur die Adressen der abonnierten Scanner
+    #[serde(default)]
+    pub allowed_sources: Vec<String>,
+    /// Scan-Profil aus DocFlow für Push-Scans (None = Standardwerte)
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Vorlagenquelle ("adf", "flatbed"), None = aus dem Profil bzw. je nach belegtem Einzug
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Manuelle WSD-Scan-Endpunkte je Scanner-ID (falls nicht per Discovery bekannt)
+    #[serde(default)]
+    pub wsd_urls: HashMap<String, String>,
+    /// Zustandsänderungen (Einzug belegt, Papierstau, ...) per WSD-Event statt Polling
+    #[serde(default = "default_status_events")]
+    pub status_events: bool,
+}
+
+fn default_status_events() -> bool {
+    true
+}
+
+impl Default for PushScanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            display_name: "DocFlow".to_string(),
+            listen_port: 5359,
+            listen_address: None,
+            allowed_sources: Vec::new(),
+            profile_id: None,
+            source: None,
+            wsd_urls: HashMap::new(),
+            status_events: default_status_events(),
+        }
+    }
+}
+
+/// Push-Scan-Dienst (Event-Listener + Abo-Erneuerung + Scan-Ausführung)
+pub struct PushScanService {
+    tasks: Mutex<Vec<tokio::task::JoinHandle<()>>>,
+}
+
+impl PushScanService {
+    /// Startet den Dienst, falls in den Einstellungen aktiviert
+    pub async fn start(
+        poller: Arc<ScanPoller>,
+        scanners: Arc<RwLock<Vec<DiscoveredScanner>>>,
+        settings: Arc<RwLock<BridgeSettings>>,
+    ) -> Option<Arc<Self>> {
+        let config = settings.read().await.push_scan.clone();
+        if !config.enabled {
+            return None;
+        }
+
+        let listen_ip = match listen_ip(&config) {
+            Ok(ip) => ip,
+            Err(e) => {
+                eprintln!("❌ Push-Scan: {}", e);
+                return None;
+            }
+        };
+        let listener = match TcpListener::bind((listen_ip, config.listen_port)).await {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("❌ Push-Scan: {}:{} nicht verfügbar: {}", listen_ip, config.listen_port, e);
+                return None;
+            }
+        };
+
+        // Geheimnis für dieses Abo - das Gerät sendet es als ReferenceParameter in jedem Event mit
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let (tx, mut rx) = mpsc::channel::<String>(16);
+        let mut tasks = Vec::new();
+
+        // 1. Eingehende WSD-Events annehmen (nur mit passendem Token vom abonnierten Scanner)
+        let event_scanners = scanners.clone();
+        let event_settings = settings.clone();
+        let event_token = token.clone();
+        tasks.push(tokio::spawn(async move {
+            loop {
+                let (socket, peer) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        eprintln!("⚠ Push-Scan: Verbindung nicht angenommen: {}", e);
+                        tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                        continue;
+                    }
+                };
+                let tx = tx.clone();
+                let scanners = event_scanners.clone();
+                let settings = event_settings.clone();
+                let token = event_token.clone();
+                tokio::spawn(async move {
+                    let Some(xml) = read_event(socket).await else {
+                        return;
+                    };
+                    let config = settings.read().await.push_scan.clone();
+                    let scanner_list = scanners.read().await.clone();
+                    let Some(scanner_id) = verify_event(&xml, peer.ip(), &token, &config, &scanner_list) else {
+                        eprintln!("⚠ Push-Scan: Event von {} abgewiesen", peer.ip());
+                        return;
+                    };
+                    if let Some(scanner_id) = handle_event(&xml, &scanner_id) {
+                        let _ = tx.send(scanner_id).await;
+                    }
+                });
+            }
+        }));
+
+        // 2. Abos anlegen und regelmäßig erneuern
+        let subscribe_scanners = scanners.clone();
+        let subscribe_settings = settings.clone();
+        tasks.push(tokio::spawn(async move {
+            loop {
+                let config = subscribe_settings.read().await.push_scan.clone();
+                let scanner_list = subscribe_scanners.read().await.clone();
+                subscribe_all(&config, listen_ip, &token, &scanner_list).await;
+                tokio::time::sleep(Duration::from_secs(SUBSCRIPTION_SECS - 300)).await;
+            }
+        }));
+
+        // 3. Tastendrücke nacheinander abarbeiten
+        tasks.push(tokio::spawn(async move {
+            while let Some(scanner_id) = rx.recv().await {
+                let config = settings.read().await.push_scan.clone();
+                println!("🔘 Scan-Taste gedrückt: {}", scanner_id);
+                match poller.handle_push_scan(&scanner_id, config.profile_id, config.source).await {
+                    Ok(()) => events::record(EventKind::Scan, format!("Push-Scan von {} hochgeladen", scanner_id)),
+                    Err(e) => {
+                        eprintln!("❌ Push-Scan fehlgeschlagen: {}", e);
+                        events::record(EventKind::Error, format!("Push-Scan von {} fehlgeschlagen: {}", scanner_id, e));
+                    }
+                }
+            }
+        }));
+
+        println!("🔘 Push-Scan aktiv ({}:{})", listen_ip, config.listen_port);
+        Some(Arc::new(Self {
+            tasks: Mutex::new(tasks),
+        }))
+    }
+
+    /// Stoppt alle Tasks des Dienstes und wartet, bis sie beendet sind (Port ist danach wieder frei)
+    pub async fn stop(&self) {
+        let tasks: Vec<_> = self.tasks.lock().await.drain(..).collect();
+        for task in &tasks {
+            task.abort();
+        }
+        for task in tasks {
+            let _ = task.await;
+        }
+    }
+}
+
+/// Adresse für den Event-Listener (auch Ziel in den Abos)
+fn listen_ip(config: &PushScanConfig) -> Result<IpAddr, String> {
+    match config.listen_address.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(address) => address
+            .parse()
+            .map_err(|_| format!("Ungültige Listen-Adresse: {}", address)),
+        None => local_ip_address::local_ip().map_err(|e| format!("Lokale IP nicht ermittelbar: {}", e)),
+    }
+}
+
+/// Prüft ein eingehendes Event: Token aus dem Abo, bekannter Scanner und Absender-IP dieses Scanners
+/// Liefert die Scanner-ID des Events
+pub fn verify_event(
+    xml: &str,
+    peer: IpAddr,
+    token: &str,
+    config: &PushScanConfig,
+    scanners: &[DiscoveredScanner],
+) -> Option<String> {
+    if extract_tag(xml, "Token").as_deref() != Some(token) {
+        return None;
+    }
+    let scanner_id = extract_tag(xml, "ScannerId")?;
+    let scanner = scanners.iter().find(|s| s.id == scanner_id)?;
+
+    let wsd_host = wsd_url_for(config, scanner)
+        .and_then(|url| reqwest::Url::parse(&url).ok())
+        .and_then(|url| url.host_str().map(|host| host.trim_matches(['[', ']']).to_string()));
+    let allowed = std::iter::once(scanner.ip.as_str())
+        .chain(wsd_host.as_deref())
+        .chain(config.allowed_sources.iter().map(String::as_str))
+        .filter_map(|address| address.trim().parse::<IpAddr>().ok())
+        .any(|address| address == peer);
+    allowed.then_some(scanner_id)
+}
+
+/// WSD-Scan-Endpunkt eines Scanners (manuelle Konfiguration hat Vorrang)
+fn wsd_url_for(config: &PushScanConfig, scanner: &DiscoveredScanner) -> Option<String> {
+    config
+        .wsd_urls
+        .get(&scanner.id)
+        .cloned()
+        .or_else(|| scanner.wsd_url.clone())
+}
+
+/// Abonniert ScanAvailableEvent (und ggf. Zustandsänderungen) bei allen Scannern mit bekanntem WSD-Endpunkt
+async fn subscribe_all(config: &PushScanConfig, listen_ip: IpAddr, token: &str, scanners: &[DiscoveredScanner]) {
+    let notify_url = format!(
+        "http://{}/wsd/events",
+        std::net::SocketAddr::from((listen_ip, config.listen_port))
+    );
+
+    for scanner in scanners {
+        let Some(wsd_url) = wsd_url_for(config, scanner) else {
+            continue;
+        };
+
+        let destination = format!(
+            r#"<sca:ScanDestinations>
+                <sca:ScanDestination>
+                    <sca:ClientDisplayName>{}</sca:ClientDisplayName>
+                    <sca:ClientContext>docflow:{}</sca:ClientContext>
+                </sca:ScanDestination>
+            </sca:ScanDestinations>"#,
+            config.display_name, scanner.id
+        );
+        match subscribe(&wsd_url, &notify_url, SCAN_AVAILABLE_FILTER, &scanner.id, token, &destination).await {
+            Ok(()) => println!("🔘 Push-Scan abonniert: {}", scanner.name),
+            Err(e) => eprintln!("⚠ Push-Scan-Abo für {} fehlgeschlagen: {}", scanner.name, e),
+        }
+
+        if config.status_events {
+            match subscribe(&wsd_url, &notify_url, scanner_events::STATUS_EVENT_FILTER, &scanner.id, token, "").await {
+                Ok(()) => scanner_events::subscribed(&scanner.id),
+                Err(e) => eprintln!("⚠ Status-Abo für {} fehlgeschlagen: {}", scanner.name, e),
+            }
+        }
+    }
+}
+
+/// WS-Eventing-Filter für Tastendrücke am Gerät
+const SCAN_AVAILABLE_FILTER: &str = "http://schemas.microsoft.com/windows/2006/08/wdp/scan/ScanAvailableEvent";
+
+/// Sendet ein WS-Eventing Subscribe (Scanner-ID und Token kommen als ReferenceParameter in jedem Event zurück)
+async fn subscribe(
+    wsd_url: &str,
+    notify_url: &str,
+    filter: &str,
+    scanner_id: &str,
+    token: &str,
+    extra_body: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let body = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"
+               xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing"
+               xmlns:wse="http://schemas.xmlsoap.org/ws/2004/08/eventing"
+               xmlns:sca="http://schemas.microsoft.com/windows/2006/08/wdp/scan"
+               xmlns:docflow="urn:docflow:scanner-bridge">
+    <soap:Header>
+        <wsa:To>{wsd_url}</wsa:To>
+        <wsa:Action>http://schemas.xmlsoap.org/ws/2004/08/eventing/Subscribe</wsa:Action>
+        <wsa:MessageID>urn:uuid:{message_id}</wsa:MessageID>
+        <wsa:ReplyTo>
+            <wsa:Address>http://schemas.xmlsoap.org/ws/2004/08/addressing/role/anonymous</wsa:Address>
+        </wsa:ReplyTo>
+    </soap:Header>
+    <soap:Body>
+        <wse:Subscribe>
+            <wse:Delivery Mode="http://schemas.xmlsoap.org/ws/2004/08/eventing/DeliveryModes/Push">
+                <wse:NotifyTo>
+                    <wsa:Address>{notify_url}</wsa:Address>
+                    <wsa:ReferenceParameters>
+                        <docflow:ScannerId>{scanner_id}</docflow:ScannerId>
+                        <docflow:Token>{token}</docflow:Token>
+                    </wsa:ReferenceParameters>
+                </wse:NotifyTo>
+            </wse:Delivery>
+            <wse:Expires>PT{expires}S</wse:Expires>
+            <wse:Filter Dialect="http://schemas.xmlsoap.org/ws/2006/02/devprof/Action">{filter}</wse:Filter>
+            {extra_body}
+        </wse:Subscribe>
+    </soap:Body>
+</soap:Envelope>"#,
+        wsd_url = wsd_url,
+        message_id = uuid::Uuid::new_v4(),
+        notify_url = notify_url,
+        expires = SUBSCRIPTION_SECS,
+        filter = filter,
+        scanner_id = scanner_id,
+        token = token,
+        extra_body = extra_body,
+    );
+
+    let client = crate::http_client::scanner(Duration::from_secs(10))?;
+    let response = client
+        .post(wsd_url)
+        .header("Content-Type", "application/soap+xml; charset=utf-8")
+        .body(body)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    Ok(())
+}
+
+/// Liest ein eingehendes Event (Header + Body) und bestätigt es
+async fn read_event(mut socket: tokio::net::TcpStream) -> Option<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    // Header + Body lesen (Content-Length beachten)
+    loop {
+        let read = tokio::time::timeout(Duration::from_secs(5), socket.read(&mut chunk))
+            .await
+            .ok()?
+            .ok()?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+
+        let text = String::from_utf8_lossy(&buffer);
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let content_length = text[..header_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    if name.eq_ignore_ascii_case("content-length") {
+                        value.trim().parse::<usize>().ok()
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(0);
+            if buffer.len() >= header_end + 4 + content_length {
+                break;
+            }
+        }
+        if buffer.len() > 1024 * 1024 {
+            break;
+        }
+    }
+
+    let _ = socket
+        .write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+        .await;
+
+    Some(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// Verarbeitet ein geprüftes Event und liefert die Scanner-ID bei ScanAvailableEvent
+fn handle_event(xml: &str, scanner_id: &str) -> Option<String> {
+    if scanner_events::handle_event(xml) {
+        return None;
+    }
+    if !xml.contains("ScanAvailableEvent") {
+        return None;
+    }
+
+    // Ziel im Gerätemenü muss zum abonnierten Scanner gehören
+    extract_tag(xml, "ClientContext")
+        .and_then(|context| context.strip_prefix("docflow:").map(|id| id.to_string()))
+        .filter(|id| id == scanner_id)
+}
+
+/// Extrahiert den Inhalt des ersten Elements mit diesem Namen (Namespace-Prefix egal)
+pub fn extract_tag(xml: &str, tag: &str) -> Option<String> {
+    let open_pattern = format!(":{}>", tag);
+    let start = xml
+        .find(&open_pattern)
+        .map(|i| i + open_pattern.len())
+        .or_else(|| xml.find(&format!("<{}>", tag)).map(|i| i + tag.len() + 2))?;
+    let end = xml[start..].find("</")? + start;
+    Some(xml[start..end].trim().to_string())
+}
@@ -0,0 +1,155 @@
+// eSCL-Herstellerbesonderheiten - HP, Brother, Canon u.a. weichen an einzelnen Stellen von der Spezifikation ab
+// Profile je Hersteller/Modell kommen aus der mitgelieferten JSON, eine escl_quirks.json im Datenverzeichnis
+// ergänzt bzw. ersetzt sie (Korrekturen ohne neues Release)
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::folder_watcher::glob_match;
+use crate::settings;
+
+/// Mitgelieferte Profile
+const BUNDLED: &str = include_str!("escl_quirks.json");
+
+/// Dateiname der lokalen Profile im Datenverzeichnis
+const OVERRIDE_FILE: &str = "escl_quirks.json";
+
+/// Wie der Location-Header von POST /ScanJobs zu lesen ist
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationFormat {
+    /// Absolute URL unverändert, Pfad ("/eSCL/ScanJobs/7") bzw. Job-ID ("7") relativ zum Scanner
+    #[default]
+    Auto,
+    /// Wie Auto, Host und Port einer absoluten URL werden aber durch die Scanner-Adresse ersetzt
+    /// (Geräte melden interne Hostnamen oder Port 80 trotz TLS)
+    ScannerHost,
+}
+
+/// Besonderheiten eines Herstellers bzw. Modells
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuirkProfile {
+    /// Hersteller wie in der Discovery ermittelt ("HP", "Canon"), "*" = alle
+    pub manufacturer: String,
+    /// Modell-Muster mit * und ? (None = alle Modelle des Herstellers)
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub location: LocationFormat,
+    /// HTTP-Status beim Anlegen eines Jobs, die "Scanner belegt" bedeuten (erneut versuchen)
+    #[serde(default = "default_busy_status")]
+    pub busy_status: Vec<u16>,
+    /// Herstellereigene JobState-Werte → eSCL-Werte (Processing, Pending, Completed, Aborted, Canceled)
+    #[serde(default)]
+    pub job_states: BTreeMap<String, String>,
+    /// pwg:Version in den ScanSettings (None = "2.0")
+    #[serde(default)]
+    pub escl_version: Option<String>,
+}
+
+fn default_busy_status() -> Vec<u16> {
+    vec![409]
+}
+
+impl Default for QuirkProfile {
+    /// Verhalten nach Spezifikation
+    fn default() -> Self {
+        Self {
+            manufacturer: "*".to_string(),
+            model: None,
+            description: String::new(),
+            location: LocationFormat::Auto,
+            busy_status: default_busy_status(),
+            job_states: BTreeMap::new(),
+            escl_version: None,
+        }
+    }
+}
+
+impl QuirkProfile {
+    fn matches(&self, manufacturer: &str, model: &str) -> bool {
+        let vendor = self.manufacturer == "*" || self.manufacturer.eq_ignore_ascii_case(manufacturer);
+        vendor
+            && self
+                .model
+                .as_ref()
+                .is_none_or(|pattern| glob_match(&pattern.to_lowercase(), &model.to_lowercase()))
+    }
+
+    /// Statuscode beim Anlegen eines Jobs bedeutet "Scanner belegt"
+    pub fn is_busy(&self, status: u16) -> bool {
+        self.busy_status.contains(&status)
+    }
+
+    /// JobState des Geräts als eSCL-Wert
+    pub fn job_state(&self, raw: &str) -> String {
+        self.job_states
+            .iter()
+            .find(|(vendor, _)| vendor.eq_ignore_ascii_case(raw))
+            .map(|(_, state)| state.clone())
+            .unwrap_or_else(|| raw.to_string())
+    }
+
+    /// Job-URL aus dem Location-Header
+    /// `origin` = "scheme://host:port" des Scanners, `base_url` = origin mit Resource-Path
+    pub fn job_url(&self, location: &str, origin: &str, base_url: &str) -> String {
+        let location = location.trim();
+        if let Some(rest) = location.strip_prefix("http://").or_else(|| location.strip_prefix("https://")) {
+            if self.location == LocationFormat::ScannerHost {
+                let path = rest.find('/').map(|i| &rest[i..]).unwrap_or("");
+                return format!("{}{}", origin, path);
+            }
+            return location.to_string();
+        }
+        if location.starts_with('/') {
+            return format!("{}{}", origin, location);
+        }
+        // Relativer Pfad ("ScanJobs/7", "eSCL/ScanJobs/7") oder nur die Job-ID
+        let job_id = location.rsplit("ScanJobs/").next().unwrap_or(location);
+        format!("{}/ScanJobs/{}", base_url, job_id)
+    }
+}
+
+/// Profil für einen Scanner: lokale Profile vor mitgelieferten, modellspezifische vor Hersteller-Profilen
+pub fn for_scanner(manufacturer: &str, model: &str) -> QuirkProfile {
+    let mut profiles = load_local();
+    profiles.extend(bundled());
+    // Stabile Sortierung: Reihenfolge innerhalb der Gruppen bleibt erhalten
+    profiles.sort_by_key(|profile| profile.model.is_none());
+
+    match profiles.into_iter().find(|profile| profile.matches(manufacturer, model)) {
+        Some(profile) => {
+            println!("🔧 eSCL-Besonderheiten für {} {}: {}", manufacturer, model, profile.description);
+            profile
+        }
+        None => QuirkProfile::default(),
+    }
+}
+
+/// Mitgelieferte Profile
+pub fn bundled() -> Vec<QuirkProfile> {
+    serde_json::from_str(BUNDLED).unwrap_or_else(|e| {
+        eprintln!("⚠ Mitgelieferte eSCL-Profile ungültig: {}", e);
+        Vec::new()
+    })
+}
+
+/// Pfad der lokalen Profile
+pub fn local_path() -> PathBuf {
+    settings::data_dir().join(OVERRIDE_FILE)
+}
+
+/// Lokale Profile (bei jedem Scan neu gelesen, Änderungen wirken ohne Neustart)
+fn load_local() -> Vec<QuirkProfile> {
+    let path = local_path();
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        eprintln!("⚠ {} ungültig, wird ignoriert: {}", path.display(), e);
+        Vec::new()
+    })
+}
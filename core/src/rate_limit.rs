@@ -0,0 +1,245 @@
+// Rate-Limit - Gemeinsamer Token-Bucket je DocFlow-Host für Poller, Folder-Sync und Statusmeldungen
+// HTTP 429 halbiert die Rate und pausiert den Bucket (Retry-After), Erfolge erhöhen sie langsam wieder
+// Vor dem Limiter sitzt der Circuit-Breaker, der bei einem Server-Ausfall den gesamten Verkehr pausiert
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::circuit_breaker;
+
+/// Untergrenze der Rate nach wiederholten 429-Antworten (Anfragen/Sekunde)
+const MIN_RATE: f64 = 0.2;
+/// Pause nach 429 ohne Retry-After
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Konfiguration des Limiters
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Dauerhafte Rate je DocFlow-Host (Anfragen/Sekunde)
+    pub requests_per_second: f64,
+    /// Kurzzeitig erlaubte Spitze
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 10.0,
+            burst: 20,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    /// Aktuelle (durch 429 ggf. reduzierte) Rate
+    rate: f64,
+    last_refill: Instant,
+    paused_until: Option<Instant>,
+}
+
+struct Limiter {
+    config: RateLimitConfig,
+    buckets: HashMap<String, Bucket>,
+}
+
+static LIMITER: Mutex<Option<Limiter>> = Mutex::new(None);
+
+/// Übernimmt neue Grenzwerte (bestehende Buckets starten neu)
+pub fn apply_config(config: &RateLimitConfig) {
+    if let Ok(mut limiter) = LIMITER.lock() {
+        *limiter = Some(Limiter {
+            config: config.clone(),
+            buckets: HashMap::new(),
+        });
+    }
+}
+
+/// Sendet eine DocFlow-Anfrage über Circuit-Breaker und gemeinsamen Limiter
+pub async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+    let (client, request) = request.build_split();
+    let mut request = request?;
+    let host = request.url().host_str().unwrap_or_default().to_string();
+    let server = format!("{}:{}", host, request.url().port_or_known_default().unwrap_or_default());
+
+    // Korrelations-ID (taucht auch in den DocFlow-Server-Logs auf)
+    let request_id = crate::http_client::new_request_id();
+    if let Ok(value) = reqwest::header::HeaderValue::from_str(&request_id) {
+        request
+            .headers_mut()
+            .entry(crate::http_client::REQUEST_ID_HEADER)
+            .or_insert(value);
+    }
+    let label = format!("{} {}", request.method(), request.url().path());
+    crate::request_signing::sign(&mut request)?;
+
+    let probe = Probe {
+        server: &server,
+        pending: circuit_breaker::admit(&server)?,
+    };
+    acquire(&host).await;
+    let stream = crate::http_client::stream_permit().await;
+    let response = match client.execute(request).await {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("⚠ DocFlow {} fehlgeschlagen [{}]: {}", label, request_id, e);
+            probe.finish(false);
+            return Err(e.into());
+        }
+    };
+    if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_MODIFIED {
+        eprintln!("⚠ DocFlow {} → HTTP {} [{}]", label, response.status().as_u16(), request_id);
+    }
+    drop(stream);
+    crate::cert_expiry::observe(&server, &response);
+    probe.finish(!response.status().is_server_error());
+    feedback(&host, &response);
+    Ok(response)
+}
+
+/// Meldet das Ergebnis an den Circuit-Breaker - eine abgebrochene Probe gibt den Weg für die nächste frei
+struct Probe<'a> {
+    server: &'a str,
+    pending: bool,
+}
+
+impl Probe<'_> {
+    fn finish(mut self, success: bool) {
+        circuit_breaker::record(self.server, success, self.pending);
+        self.pending = false;
+    }
+}
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        if self.pending {
+            circuit_breaker::abandon_probe(self.server);
+        }
+    }
+}
+
+/// Platz für eine DocFlow-Anfrage außerhalb von reqwest (z.B. gRPC-Upload): Circuit-Breaker, Limiter und
+/// Stream-Begrenzung wie bei [`send`] - das Ergebnis meldet der Aufrufer per [`Slot::finish`]
+pub struct Slot {
+    server: String,
+    pending: bool,
+    _stream: Option<tokio::sync::OwnedSemaphorePermit>,
+}
+
+impl Slot {
+    /// Wartet, bis eine Anfrage an den Endpunkt (URL) erlaubt ist
+    pub async fn acquire(url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let url = reqwest::Url::parse(url)?;
+        let host = url.host_str().unwrap_or_default().to_string();
+        let mut slot = Self {
+            server: format!("{}:{}", host, url.port_or_known_default().unwrap_or_default()),
+            pending: false,
+            _stream: None,
+        };
+        slot.pending = circuit_breaker::admit(&slot.server)?;
+        acquire(&host).await;
+        slot._stream = crate::http_client::stream_permit().await;
+        Ok(slot)
+    }
+
+    /// Meldet das Ergebnis an den Circuit-Breaker (false = Server nicht erreichbar bzw. Serverfehler)
+    pub fn finish(mut self, success: bool) {
+        circuit_breaker::record(&self.server, success, self.pending);
+        self.pending = false;
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if self.pending {
+            circuit_breaker::abandon_probe(&self.server);
+        }
+    }
+}
+
+/// `.send_limited()` statt `.send()` für alle DocFlow-Anfragen
+pub trait RateLimited {
+    fn send_limited(self) -> impl Future<Output = Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>>> + Send;
+}
+
+impl RateLimited for reqwest::RequestBuilder {
+    fn send_limited(self) -> impl Future<Output = Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>>> + Send {
+        send(self)
+    }
+}
+
+/// Wartet, bis ein Token für den Host verfügbar ist
+async fn acquire(host: &str) {
+    loop {
+        let wait = {
+            let Ok(mut guard) = LIMITER.lock() else {
+                return;
+            };
+            let limiter = guard.get_or_insert_with(|| Limiter {
+                config: RateLimitConfig::default(),
+                buckets: HashMap::new(),
+            });
+            let config = limiter.config.clone();
+            let bucket = limiter.buckets.entry(host.to_string()).or_insert_with(|| Bucket {
+                tokens: config.burst as f64,
+                rate: config.requests_per_second,
+                last_refill: Instant::now(),
+                paused_until: None,
+            });
+
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(config.burst.max(1) as f64);
+            bucket.last_refill = now;
+
+            match bucket.paused_until {
+                Some(until) if until > now => until - now,
+                _ if bucket.tokens >= 1.0 => {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                _ => Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.rate.max(MIN_RATE)),
+            }
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Passt den Bucket an die Antwort an (429 → bremsen, Erfolg → langsam erholen)
+fn feedback(host: &str, response: &reqwest::Response) {
+    let Ok(mut guard) = LIMITER.lock() else {
+        return;
+    };
+    let Some(limiter) = guard.as_mut() else {
+        return;
+    };
+    let max_rate = limiter.config.requests_per_second;
+    let Some(bucket) = limiter.buckets.get_mut(host) else {
+        return;
+    };
+
+    if response.status().as_u16() == 429 {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_BACKOFF);
+
+        bucket.rate = (bucket.rate / 2.0).max(MIN_RATE);
+        bucket.tokens = 0.0;
+        bucket.paused_until = Some(Instant::now() + retry_after);
+        eprintln!(
+            "⏳ DocFlow-Rate-Limit ({}): Pause {}s, neue Rate {:.1}/s",
+            host,
+            retry_after.as_secs(),
+            bucket.rate
+        );
+    } else if response.status().is_success() && bucket.rate < max_rate {
+        bucket.rate = (bucket.rate * 1.05).min(max_rate);
+    }
+}
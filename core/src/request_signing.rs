@@ -0,0 +1,124 @@
+// Anfragesignatur - Optionale HMAC-SHA256-Signatur jeder authentifizierten DocFlow-Anfrage
+// Ein abgegriffener Bearer-Token allein genügt dann nicht mehr, Zeitstempel und Nonce verhindern Wiederholungen
+// Der Schlüssel wird beim Pairing von DocFlow vergeben und liegt im Keyring ("signing_secret")
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+
+use crate::secrets;
+
+/// Keyring-Eintrag für den Signaturschlüssel
+pub const SECRET_ENTRY: &str = "signing_secret";
+
+/// Version des Signaturverfahrens (Präfix im Signatur-Header)
+pub const VERSION: &str = "v1";
+
+pub const TIMESTAMP_HEADER: &str = "X-DocFlow-Timestamp";
+pub const NONCE_HEADER: &str = "X-DocFlow-Nonce";
+pub const CONTENT_HASH_HEADER: &str = "X-DocFlow-Content-SHA256";
+pub const SIGNATURE_HEADER: &str = "X-DocFlow-Signature";
+
+/// Inhalts-Hash für gestreamte Bodys ohne vorab berechneten Hash (gRPC-Upload-Streams)
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Signaturschlüssel im Speicher (Keyring-Zugriffe sind für jede Anfrage zu langsam)
+static SECRET: Mutex<Option<String>> = Mutex::new(None);
+
+/// Setzt den Signaturschlüssel nach Pairing bzw. Trennen
+pub fn set_secret(secret: Option<&str>) {
+    if let Ok(mut current) = SECRET.lock() {
+        *current = secret.map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+    }
+}
+
+/// Lädt den gespeicherten Signaturschlüssel (beim Start), Ergebnis: Schlüssel vorhanden
+pub fn load_secret() -> bool {
+    let secret = secrets::get(SECRET_ENTRY);
+    set_secret(secret.as_deref());
+    has_secret()
+}
+
+/// Signaturschlüssel vorhanden
+pub fn has_secret() -> bool {
+    SECRET.lock().is_ok_and(|s| s.is_some())
+}
+
+/// Signiert eine DocFlow-Anfrage, sofern aktiviert
+/// Nur Anfragen mit Authorization-Header (Pairing selbst läuft ohne Schlüssel)
+pub fn sign(request: &mut reqwest::Request) -> Result<(), String> {
+    if !crate::http_client::request_signing()
+        || !request.headers().contains_key(reqwest::header::AUTHORIZATION)
+    {
+        return Ok(());
+    }
+    let content_hash = match request.body().map(|b| b.as_bytes()) {
+        None => hex(&Sha256::digest(b"")),
+        Some(Some(bytes)) => hex(&Sha256::digest(bytes)),
+        // Gestreamte Multipart-Bodys bringen ihren vorab berechneten Hash mit (multipart::Form::attach)
+        Some(None) => request
+            .headers()
+            .get(CONTENT_HASH_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or(UNSIGNED_PAYLOAD)
+            .to_string(),
+    };
+    let path = path_and_query(request.url());
+    let signed = signature_headers(request.method().as_str(), &path, content_hash)?;
+
+    let headers = request.headers_mut();
+    for (name, value) in signed {
+        let value = reqwest::header::HeaderValue::from_str(&value).map_err(|e| e.to_string())?;
+        headers.insert(name, value);
+    }
+    Ok(())
+}
+
+/// Signatur-Header (Name, Wert) für eine Anfrage - auch für Transporte ohne reqwest (gRPC)
+pub fn signature_headers(method: &str, path: &str, content_hash: String) -> Result<Vec<(&'static str, String)>, String> {
+    let Some(secret) = SECRET.lock().ok().and_then(|s| s.clone()) else {
+        return Err("Anfragesignatur ist aktiviert, aber kein Signaturschlüssel vorhanden - Bridge bitte neu koppeln".to_string());
+    };
+
+    let timestamp = chrono::Utc::now().timestamp().to_string();
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let signature = signature(&secret, method, path, &timestamp, &nonce, &content_hash);
+    Ok(vec![
+        (TIMESTAMP_HEADER, timestamp),
+        (NONCE_HEADER, nonce),
+        (CONTENT_HASH_HEADER, content_hash),
+        (SIGNATURE_HEADER, format!("{}={}", VERSION, signature)),
+    ])
+}
+
+/// Zu signierender Text: Verfahren, Methode, Pfad mit Query, Zeitstempel, Nonce und Inhalts-Hash je Zeile
+pub fn canonical(method: &str, path: &str, timestamp: &str, nonce: &str, content_hash: &str) -> String {
+    format!("{}\n{}\n{}\n{}\n{}\n{}", VERSION, method.to_uppercase(), path, timestamp, nonce, content_hash)
+}
+
+/// Signatur als Hex (HMAC-SHA256 über `canonical`)
+pub fn signature(secret: &str, method: &str, path: &str, timestamp: &str, nonce: &str, content_hash: &str) -> String {
+    hex(&hmac_sha256(
+        secret.as_bytes(),
+        canonical(method, path, timestamp, nonce, content_hash).as_bytes(),
+    ))
+}
+
+/// HMAC-SHA256 nach RFC 2104
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    // HMAC akzeptiert Schlüssel jeder Länge
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC-Schlüssel beliebiger Länge");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
+
+fn path_and_query(url: &reqwest::Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
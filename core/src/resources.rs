@@ -0,0 +1,80 @@
+// Ressourcen-Prüfung - Freier Speicherplatz und Arbeitsspeicher vor Scans und Uploads
+// Bei Knappheit werden Jobs zurückgestellt statt mitten im Scan fehlzuschlagen
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::settings;
+
+/// Mindestanforderungen
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResourceConfig {
+    /// Freier Speicherplatz (Temp- und Datenverzeichnis) in MB
+    pub min_free_disk_mb: u64,
+    /// Verfügbarer Arbeitsspeicher in MB (die ersten Seiten eines Stapels werden im Speicher gepuffert)
+    pub min_free_memory_mb: u64,
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        Self {
+            min_free_disk_mb: 500,
+            min_free_memory_mb: 256,
+        }
+    }
+}
+
+/// Letzte Warnung (None = genug Ressourcen)
+static WARNING: Mutex<Option<String>> = Mutex::new(None);
+
+/// Prüft Speicherplatz und Arbeitsspeicher - Err mit verständlichem Grund bei Knappheit
+pub fn check(config: &ResourceConfig) -> Result<(), String> {
+    let result = evaluate(config);
+
+    if let Ok(mut warning) = WARNING.lock() {
+        let message = result.as_ref().err().cloned();
+        if *warning != message {
+            match &message {
+                Some(m) => eprintln!("⚠ {}", m),
+                None => println!("✓ Ressourcen wieder ausreichend"),
+            }
+            *warning = message;
+        }
+    }
+    result
+}
+
+/// Aktuelle Ressourcen-Warnung (für Status-Anzeige)
+pub fn current_warning() -> Option<String> {
+    WARNING.lock().ok().and_then(|w| w.clone())
+}
+
+fn evaluate(config: &ResourceConfig) -> Result<(), String> {
+    for dir in [std::env::temp_dir(), settings::data_dir()] {
+        // Datenverzeichnis existiert evtl. noch nicht - dann das Elternverzeichnis prüfen
+        let existing = dir.ancestors().find(|p| p.exists()).unwrap_or(&dir).to_path_buf();
+        if let Ok(free) = fs4::available_space(&existing) {
+            let free_mb = free / 1024 / 1024;
+            if free_mb < config.min_free_disk_mb {
+                return Err(format!(
+                    "Wenig Speicherplatz: {} MB frei in {} (mindestens {} MB nötig)",
+                    free_mb,
+                    existing.display(),
+                    config.min_free_disk_mb
+                ));
+            }
+        }
+    }
+
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let available_mb = system.available_memory() / 1024 / 1024;
+    if available_mb > 0 && available_mb < config.min_free_memory_mb {
+        return Err(format!(
+            "Wenig Arbeitsspeicher: {} MB verfügbar (mindestens {} MB nötig)",
+            available_mb, config.min_free_memory_mb
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,478 @@
+// SANE - Anbindung an libsane unter Linux (USB-Scanner und Scanner an einem saned-Server)
+// libsane wird zur Laufzeit geladen: ohne installiertes SANE startet die Bridge trotzdem, findet nur keine lokalen Scanner
+
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::ptr;
+
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgb, RgbImage};
+use libloading::Library;
+
+/// Bibliotheksnamen in Suchreihenfolge
+const LIBRARY_NAMES: &[&str] = &["libsane.so.1", "libsane.so"];
+
+const SANE_STATUS_GOOD: c_int = 0;
+const SANE_STATUS_EOF: c_int = 5;
+const SANE_STATUS_NO_DOCS: c_int = 7;
+const SANE_ACTION_SET_VALUE: c_int = 1;
+const SANE_FRAME_GRAY: c_int = 0;
+const SANE_FRAME_RGB: c_int = 1;
+const SANE_TYPE_INT: c_int = 1;
+const SANE_TYPE_FIXED: c_int = 2;
+const SANE_TYPE_STRING: c_int = 3;
+const SANE_CONSTRAINT_RANGE: c_int = 1;
+const SANE_CONSTRAINT_WORD_LIST: c_int = 2;
+const SANE_CONSTRAINT_STRING_LIST: c_int = 3;
+
+#[repr(C)]
+struct RawDevice {
+    name: *const c_char,
+    vendor: *const c_char,
+    model: *const c_char,
+    kind: *const c_char,
+}
+
+// Felder mit _ werden nicht gelesen, sind aber für das Speicherlayout nötig
+#[repr(C)]
+struct RawRange {
+    _min: i32,
+    max: i32,
+    _quant: i32,
+}
+
+#[repr(C)]
+struct RawOptionDescriptor {
+    name: *const c_char,
+    _title: *const c_char,
+    _desc: *const c_char,
+    value_type: c_int,
+    _unit: c_int,
+    size: c_int,
+    _cap: c_int,
+    constraint_type: c_int,
+    constraint: *const c_void,
+}
+
+type InitFn = unsafe extern "C" fn(*mut c_int, *const c_void) -> c_int;
+type ExitFn = unsafe extern "C" fn();
+type GetDevicesFn = unsafe extern "C" fn(*mut *const *const RawDevice, c_int) -> c_int;
+type OpenFn = unsafe extern "C" fn(*const c_char, *mut *mut c_void) -> c_int;
+type CloseFn = unsafe extern "C" fn(*mut c_void);
+type GetOptionDescriptorFn = unsafe extern "C" fn(*mut c_void, c_int) -> *const RawOptionDescriptor;
+type ControlOptionFn = unsafe extern "C" fn(*mut c_void, c_int, c_int, *mut c_void, *mut c_int) -> c_int;
+type StartFn = unsafe extern "C" fn(*mut c_void) -> c_int;
+type GetParametersFn = unsafe extern "C" fn(*mut c_void, *mut FrameParameters) -> c_int;
+type ReadFn = unsafe extern "C" fn(*mut c_void, *mut u8, c_int, *mut c_int) -> c_int;
+type CancelFn = unsafe extern "C" fn(*mut c_void);
+
+/// SANE_Parameters eines Bildes (nach sane_start)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameParameters {
+    /// SANE_Frame: 0 = Graustufen, 1 = RGB (weitere nur bei Drei-Durchgang-Scannern)
+    pub format: c_int,
+    pub last_frame: c_int,
+    pub bytes_per_line: c_int,
+    pub pixels_per_line: c_int,
+    /// -1, wenn die Höhe vorab nicht bekannt ist (z.B. Einzug mit Längenerkennung)
+    pub lines: c_int,
+    /// Bits je Farbkanal: 1 (Strich), 8 oder 16
+    pub depth: c_int,
+}
+
+/// Von SANE gemeldetes Gerät
+#[derive(Clone, Debug)]
+pub struct SaneDevice {
+    /// Gerätename für sane_open, z.B. "genesys:libusb:001:004" oder "net:192.168.1.5:pixma:..."
+    pub name: String,
+    pub vendor: String,
+    pub model: String,
+    /// z.B. "flatbed scanner", "sheetfed scanner"
+    pub kind: String,
+}
+
+/// Fähigkeiten aus den Optionen des Geräts
+#[derive(Clone, Debug, Default)]
+pub struct SaneOptions {
+    /// Werte der Option "source", z.B. ["Flatbed", "ADF", "ADF Duplex"]
+    pub sources: Vec<String>,
+    /// Werte der Option "mode", z.B. ["Color", "Gray", "Lineart"]
+    pub modes: Vec<String>,
+    /// Höchste Auflösung der Option "resolution" in dpi
+    pub max_resolution: Option<u32>,
+}
+
+/// Einstellungen für einen Scan (Werte wie vom Gerät angeboten, None = Vorgabe des Geräts)
+#[derive(Clone, Debug, Default)]
+pub struct SaneScanSettings {
+    pub source: Option<String>,
+    pub mode: Option<String>,
+    pub resolution: u32,
+    /// Weitere Blätter aus dem Einzug holen, bis er leer ist
+    pub batch: bool,
+}
+
+/// Geladene und initialisierte libsane (sane_exit beim Drop)
+pub struct Sane {
+    library: Library,
+}
+
+impl Sane {
+    /// Lädt libsane und ruft sane_init auf
+    pub fn load() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let library = LIBRARY_NAMES
+            .iter()
+            .find_map(|name| unsafe { Library::new(name) }.ok())
+            .ok_or("libsane nicht gefunden (Paket libsane1 bzw. sane-backends installieren)")?;
+
+        let mut version: c_int = 0;
+        let status = unsafe {
+            let init = library.get::<InitFn>(b"sane_init\0")?;
+            init(&mut version, ptr::null())
+        };
+        if status != SANE_STATUS_GOOD {
+            return Err(format!("sane_init fehlgeschlagen (Status {})", status).into());
+        }
+        Ok(Self { library })
+    }
+
+    /// Alle Geräte - lokal angeschlossene und (falls local_only = false) per saned im Netz
+    pub fn devices(&self, local_only: bool) -> Result<Vec<SaneDevice>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut list: *const *const RawDevice = ptr::null();
+        let status = unsafe {
+            let get_devices = self.library.get::<GetDevicesFn>(b"sane_get_devices\0")?;
+            get_devices(&mut list, c_int::from(local_only))
+        };
+        if status != SANE_STATUS_GOOD {
+            return Err(format!("sane_get_devices fehlgeschlagen (Status {})", status).into());
+        }
+
+        let mut devices = Vec::new();
+        if list.is_null() {
+            return Ok(devices);
+        }
+        // NULL-terminierte Liste, gültig bis zum nächsten sane_get_devices/sane_exit
+        let mut index = 0;
+        loop {
+            let device = unsafe { *list.add(index) };
+            if device.is_null() {
+                break;
+            }
+            let device = unsafe { &*device };
+            devices.push(SaneDevice {
+                name: string(device.name),
+                vendor: string(device.vendor),
+                model: string(device.model),
+                kind: string(device.kind),
+            });
+            index += 1;
+        }
+        Ok(devices)
+    }
+
+    /// Öffnet das Gerät kurz und liest Quellen, Farbmodi und Auflösung aus den Optionsbeschreibungen
+    pub fn options(&self, device_name: &str) -> Result<SaneOptions, Box<dyn std::error::Error + Send + Sync>> {
+        let handle = self.open(device_name)?;
+        let mut options = SaneOptions::default();
+        for (_, descriptor) in handle.descriptors()? {
+            match string(descriptor.name).as_str() {
+                "source" => options.sources = unsafe { string_list(descriptor) },
+                "mode" => options.modes = unsafe { string_list(descriptor) },
+                "resolution" => options.max_resolution = unsafe { max_value(descriptor) },
+                _ => {}
+            }
+        }
+        Ok(options)
+    }
+
+    /// Scannt mit den Einstellungen - eine Seite vom Vorlagenglas bzw. alle Blätter aus dem Einzug
+    pub fn scan(
+        &self,
+        device_name: &str,
+        settings: &SaneScanSettings,
+    ) -> Result<Vec<DynamicImage>, Box<dyn std::error::Error + Send + Sync>> {
+        let handle = self.open(device_name)?;
+        if let Some(source) = &settings.source {
+            handle.set_option("source", OptionValue::Text(source))?;
+        }
+        if let Some(mode) = &settings.mode {
+            handle.set_option("mode", OptionValue::Text(mode))?;
+        }
+        if settings.resolution > 0 {
+            handle.set_option("resolution", OptionValue::Number(settings.resolution))?;
+        }
+
+        let mut pages = Vec::new();
+        loop {
+            match handle.read_image()? {
+                Some(page) => pages.push(page),
+                None if pages.is_empty() => return Err("Kein Papier im Einzug".into()),
+                None => break,
+            }
+            if !settings.batch {
+                break;
+            }
+        }
+        handle.cancel();
+        Ok(pages)
+    }
+
+    fn open(&self, device_name: &str) -> Result<Handle<'_>, Box<dyn std::error::Error + Send + Sync>> {
+        let name = CString::new(device_name)?;
+        let open = unsafe { self.library.get::<OpenFn>(b"sane_open\0")? };
+        let mut raw: *mut c_void = ptr::null_mut();
+        check(unsafe { open(name.as_ptr(), &mut raw) }, "sane_open")?;
+        Ok(Handle { sane: self, raw })
+    }
+}
+
+/// Wert zum Setzen einer Option
+enum OptionValue<'a> {
+    Text(&'a str),
+    Number(u32),
+}
+
+/// Geöffnetes Gerät (sane_close beim Drop)
+struct Handle<'a> {
+    sane: &'a Sane,
+    raw: *mut c_void,
+}
+
+impl Handle<'_> {
+    /// Alle Optionsbeschreibungen mit Index (Option 0 ist die Anzahl, die Beschreibungen folgen ab 1)
+    fn descriptors(&self) -> Result<Vec<(c_int, &RawOptionDescriptor)>, Box<dyn std::error::Error + Send + Sync>> {
+        let describe = unsafe { self.sane.library.get::<GetOptionDescriptorFn>(b"sane_get_option_descriptor\0")? };
+        let mut descriptors = Vec::new();
+        let mut index = 1;
+        loop {
+            let descriptor = unsafe { describe(self.raw, index) };
+            if descriptor.is_null() {
+                break;
+            }
+            descriptors.push((index, unsafe { &*descriptor }));
+            index += 1;
+        }
+        Ok(descriptors)
+    }
+
+    /// Setzt eine Option (fehlt sie beim Gerät, bleibt dessen Vorgabe)
+    fn set_option(&self, name: &str, value: OptionValue) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let control = unsafe { self.sane.library.get::<ControlOptionFn>(b"sane_control_option\0")? };
+        let Some((index, descriptor)) = self.descriptors()?.into_iter().find(|(_, d)| string(d.name) == name) else {
+            return Ok(());
+        };
+        let mut info: c_int = 0;
+        let status = match (value, descriptor.value_type) {
+            (OptionValue::Text(text), SANE_TYPE_STRING) => {
+                // Puffer in der vom Gerät angegebenen Größe (inkl. abschließender Null)
+                let mut buffer = vec![0u8; (descriptor.size.max(0) as usize).max(text.len() + 1)];
+                buffer[..text.len()].copy_from_slice(text.as_bytes());
+                unsafe { control(self.raw, index, SANE_ACTION_SET_VALUE, buffer.as_mut_ptr().cast(), &mut info) }
+            }
+            (OptionValue::Number(number), SANE_TYPE_INT) => {
+                let mut word = number as i32;
+                unsafe { control(self.raw, index, SANE_ACTION_SET_VALUE, (&mut word as *mut i32).cast(), &mut info) }
+            }
+            (OptionValue::Number(number), SANE_TYPE_FIXED) => {
+                let mut word = (number as i32) << 16;
+                unsafe { control(self.raw, index, SANE_ACTION_SET_VALUE, (&mut word as *mut i32).cast(), &mut info) }
+            }
+            // Typ passt nicht zum Wert (z.B. Zahl für eine Text-Option) - Option übergehen
+            _ => return Ok(()),
+        };
+        check(status, &format!("Option '{}'", name))
+    }
+
+    /// Startet einen Scan und liest das Bild → None, wenn der Einzug leer ist
+    fn read_image(&self) -> Result<Option<DynamicImage>, Box<dyn std::error::Error + Send + Sync>> {
+        let (start, get_parameters, read) = unsafe {
+            (
+                self.sane.library.get::<StartFn>(b"sane_start\0")?,
+                self.sane.library.get::<GetParametersFn>(b"sane_get_parameters\0")?,
+                self.sane.library.get::<ReadFn>(b"sane_read\0")?,
+            )
+        };
+
+        match unsafe { start(self.raw) } {
+            SANE_STATUS_NO_DOCS => return Ok(None),
+            status => check(status, "sane_start")?,
+        }
+        let mut parameters = FrameParameters::default();
+        check(unsafe { get_parameters(self.raw, &mut parameters) }, "sane_get_parameters")?;
+
+        let mut data = Vec::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let mut length: c_int = 0;
+            match unsafe { read(self.raw, buffer.as_mut_ptr(), buffer.len() as c_int, &mut length) } {
+                SANE_STATUS_EOF => break,
+                status => check(status, "sane_read")?,
+            }
+            data.extend_from_slice(&buffer[..length.max(0) as usize]);
+        }
+        frame_image(&parameters, data).map(Some)
+    }
+
+    fn cancel(&self) {
+        if let Ok(cancel) = unsafe { self.sane.library.get::<CancelFn>(b"sane_cancel\0") } {
+            unsafe { cancel(self.raw) };
+        }
+    }
+}
+
+impl Drop for Handle<'_> {
+    fn drop(&mut self) {
+        if let Ok(close) = unsafe { self.sane.library.get::<CloseFn>(b"sane_close\0") } {
+            unsafe { close(self.raw) };
+        }
+    }
+}
+
+/// Wandelt die gelesenen Bilddaten in ein 8-Bit-Bild (Graustufen oder RGB, 1/8/16 Bit)
+pub fn frame_image(
+    parameters: &FrameParameters,
+    data: Vec<u8>,
+) -> Result<DynamicImage, Box<dyn std::error::Error + Send + Sync>> {
+    let channels = match parameters.format {
+        SANE_FRAME_GRAY => 1,
+        SANE_FRAME_RGB => 3,
+        format => return Err(format!("SANE-Bildformat {} wird nicht unterstützt (Drei-Durchgang-Scanner)", format).into()),
+    };
+    let width = u32::try_from(parameters.pixels_per_line)?;
+    let bytes_per_line = usize::try_from(parameters.bytes_per_line)?;
+    if width == 0 || bytes_per_line == 0 {
+        return Err("SANE lieferte ein leeres Bild".into());
+    }
+    // Unbekannte Höhe: aus der gelesenen Datenmenge
+    let height = match parameters.lines {
+        lines if lines > 0 => lines as usize,
+        _ => data.len() / bytes_per_line,
+    };
+    if data.len() < height * bytes_per_line || height == 0 {
+        return Err(format!("SANE-Bild unvollständig ({} von {} Bytes)", data.len(), height * bytes_per_line).into());
+    }
+    let rows = data.chunks_exact(bytes_per_line).take(height);
+
+    let image = match (channels, parameters.depth) {
+        // Strich: gesetztes Bit = schwarz
+        (1, 1) => {
+            let pixels = rows
+                .flat_map(|row| {
+                    (0..width as usize).map(move |x| if row[x / 8] & (0x80 >> (x % 8)) != 0 { 0 } else { 255 })
+                })
+                .collect();
+            DynamicImage::ImageLuma8(GrayImage::from_raw(width, height as u32, pixels).ok_or("Bildgröße passt nicht")?)
+        }
+        (1, 8) => {
+            let pixels = rows.flat_map(|row| row[..width as usize].iter().copied()).collect();
+            DynamicImage::ImageLuma8(GrayImage::from_raw(width, height as u32, pixels).ok_or("Bildgröße passt nicht")?)
+        }
+        (3, 8) => {
+            let pixels = rows.flat_map(|row| row[..width as usize * 3].iter().copied()).collect();
+            DynamicImage::ImageRgb8(RgbImage::from_raw(width, height as u32, pixels).ok_or("Bildgröße passt nicht")?)
+        }
+        // 16 Bit (Host-Byte-Reihenfolge) auf 8 Bit reduzieren - die Seiten werden als JPEG weitergegeben
+        (1, 16) => {
+            let pixels = rows.flat_map(|row| words(&row[..width as usize * 2])).collect();
+            let image: ImageBuffer<Luma<u16>, Vec<u16>> =
+                ImageBuffer::from_raw(width, height as u32, pixels).ok_or("Bildgröße passt nicht")?;
+            DynamicImage::ImageLuma8(DynamicImage::ImageLuma16(image).to_luma8())
+        }
+        (3, 16) => {
+            let pixels = rows.flat_map(|row| words(&row[..width as usize * 6])).collect();
+            let image: ImageBuffer<Rgb<u16>, Vec<u16>> =
+                ImageBuffer::from_raw(width, height as u32, pixels).ok_or("Bildgröße passt nicht")?;
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgb16(image).to_rgb8())
+        }
+        (_, depth) => return Err(format!("SANE-Farbtiefe {} Bit wird nicht unterstützt", depth).into()),
+    };
+    Ok(image)
+}
+
+fn words(bytes: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    bytes.chunks_exact(2).map(|pair| u16::from_ne_bytes([pair[0], pair[1]]))
+}
+
+/// Wählt aus den angebotenen Werten den ersten, der einen der Begriffe enthält (Groß-/Kleinschreibung egal)
+pub fn pick(values: &[String], terms: &[&str]) -> Option<String> {
+    terms.iter().find_map(|term| {
+        values
+            .iter()
+            .find(|value| value.to_lowercase().contains(term))
+            .cloned()
+    })
+}
+
+fn check(status: c_int, call: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if status == SANE_STATUS_GOOD {
+        return Ok(());
+    }
+    let reason = match status {
+        2 => "abgebrochen",
+        3 => "Gerät belegt",
+        6 => "Papierstau",
+        7 => "kein Papier",
+        8 => "Abdeckung offen",
+        9 => "Ein-/Ausgabefehler",
+        11 => "Zugriff verweigert",
+        _ => "Fehler",
+    };
+    Err(format!("{} fehlgeschlagen: {} (Status {})", call, reason, status).into())
+}
+
+impl Drop for Sane {
+    fn drop(&mut self) {
+        unsafe {
+            if let Ok(exit) = self.library.get::<ExitFn>(b"sane_exit\0") {
+                exit();
+            }
+        }
+    }
+}
+
+fn string(value: *const c_char) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(value) }.to_string_lossy().trim().to_string()
+}
+
+/// Werte einer String-Option mit Listen-Constraint
+unsafe fn string_list(descriptor: &RawOptionDescriptor) -> Vec<String> {
+    if descriptor.value_type != SANE_TYPE_STRING
+        || descriptor.constraint_type != SANE_CONSTRAINT_STRING_LIST
+        || descriptor.constraint.is_null()
+    {
+        return Vec::new();
+    }
+    let list = descriptor.constraint as *const *const c_char;
+    let mut values = Vec::new();
+    let mut index = 0;
+    while !(*list.add(index)).is_null() {
+        values.push(string(*list.add(index)));
+        index += 1;
+    }
+    values
+}
+
+/// Höchster erlaubter Wert einer Zahlen-Option (Bereich oder Werteliste)
+unsafe fn max_value(descriptor: &RawOptionDescriptor) -> Option<u32> {
+    if descriptor.constraint.is_null() {
+        return None;
+    }
+    let max = match descriptor.constraint_type {
+        SANE_CONSTRAINT_RANGE => (*(descriptor.constraint as *const RawRange)).max,
+        // Erstes Element ist die Anzahl der folgenden Werte
+        SANE_CONSTRAINT_WORD_LIST => {
+            let words = descriptor.constraint as *const i32;
+            let count = usize::try_from(*words).ok()?;
+            (1..=count).map(|i| *words.add(i)).max()?
+        }
+        _ => return None,
+    };
+    match descriptor.value_type {
+        SANE_TYPE_INT => u32::try_from(max).ok(),
+        // SANE_Fixed: 16 Nachkommabits
+        SANE_TYPE_FIXED => u32::try_from(max >> 16).ok(),
+        _ => None,
+    }
+}
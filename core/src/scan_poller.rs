@@ -0,0 +1,1755 @@
+// Scan-Job-Poller - Holt Scan-Aufträge von DocFlow und führt sie aus
+// Polling-Modell: Bridge fragt DocFlow regelmäßig nach neuen Jobs, je Scanner arbeitet ein eigener Worker
+// Mit Reverse-Tunnel (siehe tunnel) bzw. gRPC-Job-Stream (siehe grpc) stellt DocFlow Jobs direkt zu, das Polling ruht solange
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{Mutex, Notify, RwLock};
+
+use crate::audit::{self, AuditAction};
+use crate::barcode::{self, DetectedBarcode};
+use crate::counters;
+use crate::dedup::{self, Channel, Check, DedupEntry};
+use crate::discovery::{self, DiscoveredScanner};
+use crate::scanner::{self, scan_escl_with_quirks, scanner_state, ScanJob, ScanRegion, ScanResult};
+use crate::scanner_events;
+use crate::settings::BridgeSettings;
+use crate::status_report::DeltaReport;
+use crate::hooks;
+use crate::metrics::{self, METRICS};
+use crate::multipart;
+use crate::profiles::{self, ProfileCache, ScanProfile};
+use crate::resources;
+use crate::enhance::EnhanceStage;
+use crate::events::{self, EventKind};
+use crate::grpc;
+use crate::pipeline::{self, PipelineOptions, Rendition};
+use crate::quirks;
+use crate::job_validation::validate_job;
+use crate::tray::{self, Activity};
+use crate::job_queue::{ActiveJob, JobState};
+use crate::scanner_stats;
+use crate::spool::{self, SpoolConfig};
+use crate::supervisor;
+use crate::timestamps::{self, DisplayTime};
+use crate::tunnel;
+use crate::upload_progress::UploadKind;
+use crate::virtual_scanner;
+use crate::wol;
+use crate::rate_limit::RateLimited;
+
+/// Pending Scan-Job von DocFlow
+#[derive(Debug, Deserialize, Clone)]
+pub struct PendingScanJob {
+    pub job_id: String,
+    pub scanner_id: String,
+    /// Verweis auf ein Scan-Profil aus DocFlow (Einstellungen werden lokal eingesetzt)
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    #[serde(default)]
+    pub resolution: u32,
+    #[serde(default)]
+    pub color_mode: String,
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub duplex: bool,
+    #[serde(default)]
+    pub format: String,
+    /// Bildverbesserung für diesen Job (None = Profil bzw. globale Einstellung)
+    #[serde(default)]
+    pub enhancements: Option<Vec<EnhanceStage>>,
+    /// Langpapier (Belege): gewünschte Papierlänge in mm (None = Letter)
+    #[serde(default)]
+    pub paper_length_mm: Option<u32>,
+    /// Mehrere Scan-Bereiche in 1/300 Zoll (z.B. Ausweis Vorder-/Rückseite auf einer Seite)
+    #[serde(default)]
+    pub regions: Vec<ScanRegion>,
+    /// Größenziel je Dokument in KB (PDF wird bei Bedarf neu komprimiert)
+    #[serde(default)]
+    pub max_document_kb: Option<u32>,
+    /// Seiten automatisch aufrecht drehen (None = Profil bzw. globale Einstellung)
+    #[serde(default)]
+    pub auto_orient: Option<bool>,
+    /// Priorität (höher = früher), DocFlow kann sie für wartende Jobs jederzeit anheben
+    #[serde(default)]
+    pub priority: i32,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+impl PendingScanJob {
+    /// In DocFlow abgelaufen (ohne gültiges expires_at, z.B. Push-Scans, läuft ein Job nicht ab)
+    pub fn is_expired(&self) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.expires_at).is_ok_and(|expires| expires < chrono::Utc::now())
+    }
+}
+
+/// Response von pending-scans Endpoint
+#[derive(Debug, Deserialize)]
+struct PendingScansResponse {
+    jobs: Vec<PendingScanJob>,
+}
+
+/// Upload-fertiges Dokument aus einem Scan-Job
+pub struct ScanDocument {
+    /// Große Dokumente bleiben bis zum Upload verschlüsselt im Spool
+    pub data: spool::Buffer,
+    pub barcodes: Vec<DetectedBarcode>,
+    /// Scan wurde abgebrochen, Dokument enthält nur die übertragenen Seiten
+    pub incomplete: bool,
+    /// Zusatz-Ausgaben (Thumbnail, Einzelseiten-JPEGs)
+    pub renditions: Vec<Rendition>,
+}
+
+/// Poller-Status
+#[derive(Clone, Debug, Serialize)]
+pub struct PollerStatus {
+    pub running: bool,
+    pub last_poll: Option<String>,
+    pub jobs_processed: u32,
+    pub last_error: Option<String>,
+    /// last_poll in lokaler Zeit und als "vor X" (wird bei get_status berechnet)
+    pub last_poll_display: Option<DisplayTime>,
+    /// Laufzeit seit dem Start des Pollers in Sekunden
+    pub running_secs: Option<u64>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+/// Scan-Job-Poller
+pub struct ScanPoller {
+    api_key: String,
+    docflow_url: String,
+    scanners: Arc<RwLock<Vec<DiscoveredScanner>>>,
+    settings: Arc<RwLock<BridgeSettings>>,
+    profiles: ProfileCache,
+    status: Arc<RwLock<PollerStatus>>,
+    /// Jobs, die auf einen belegten Scanner warten (Job-ID → Wartebeginn)
+    waiting: RwLock<HashMap<String, Instant>>,
+    /// Worker je Scanner-ID
+    workers: Mutex<HashMap<String, Arc<ScannerWorker>>>,
+    /// Exklusiver Gerätezugriff je Scanner-ID (Worker und Push-Scans)
+    device_locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// Zuletzt abgeschlossene bzw. fehlgeschlagene Jobs → bis wann sie bei der Verteilung ignoriert werden
+    finished: Mutex<HashMap<String, Instant>>,
+    /// Live-Zustand je Scanner-ID
+    activity: RwLock<HashMap<String, ScannerActivity>>,
+    activity_changed: std::sync::atomic::AtomicBool,
+    /// Fehlversuche je Job-ID
+    failures: Mutex<HashMap<String, u32>>,
+    /// Zuletzt an DocFlow gemeldeter Fehler je Job-ID (Wiederholungen mit gleichem Fehler werden nicht erneut gemeldet)
+    reported_errors: Mutex<HashMap<String, String>>,
+    /// Endgültig fehlgeschlagene Jobs (persistiert, werden nicht erneut ausgeführt)
+    dead_letters: Mutex<HashMap<String, DeadLetter>>,
+    /// Letzte pending-scans-Antwort mit ETag (bei 304 unverändert weiterverwenden)
+    pending_cache: Mutex<Option<(String, Vec<PendingScanJob>)>>,
+    /// Zuletzt gemeldete Scanner-Zustände (nur Änderungen werden gesendet)
+    activity_report: Mutex<DeltaReport>,
+    /// Lokal geänderte Prioritäten (Job-ID → Priorität), gelten vor der Priorität aus DocFlow
+    priority_overrides: Mutex<HashMap<String, i32>>,
+    /// Letzter Job bzw. letzte Interaktion (bestimmt das Abfrage-Intervall)
+    last_activity: Mutex<Instant>,
+    /// Hochgeladene Teile getrennter Scans (Job-ID → Anzahl Teile, Teil-Indizes) - bei erneutem Versuch übersprungen
+    uploaded_parts: Mutex<HashMap<String, (usize, BTreeSet<usize>)>>,
+}
+
+/// Weckt den Poller aus dem Leerlauf (Tray-Interaktion, Push-Scan)
+static WAKE: Notify = Notify::const_new();
+
+/// Sofort nach neuen Jobs fragen und wieder im schnellen Intervall abfragen
+pub fn wake() {
+    WAKE.notify_one();
+}
+
+/// Job, der nach zu vielen Fehlversuchen aufgegeben wurde
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub job_id: String,
+    pub scanner_id: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub dead_lettered_at: String,
+}
+
+/// Dead-Letter werden nach dieser Zeit aus der lokalen Liste entfernt
+const DEAD_LETTER_RETENTION_DAYS: i64 = 7;
+
+/// Live-Zustand eines Scanners für DocFlow (Kachel ausgrauen, Warteposition anzeigen)
+#[derive(Clone, Debug, Serialize)]
+pub struct ScannerActivity {
+    pub scanner_id: String,
+    /// "idle", "scanning", "busy" (Gerät belegt), "low_resources" oder "error"
+    pub state: String,
+    pub job_id: Option<String>,
+    pub queue_length: usize,
+    pub message: Option<String>,
+    pub updated_at: String,
+}
+
+/// Wie lange abgeschlossene Jobs bei der Verteilung ignoriert werden
+const FINISHED_JOB_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Wartezeit bis zum erneuten Versuch eines fehlgeschlagenen Jobs (bis er im Dead-Letter landet)
+const FAILED_JOB_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Zeit für einen Poll-Durchlauf (Abfrage, Profile, Statusmeldungen) bis zum nächsten Heartbeat
+const POLL_BUDGET: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Zeitlimit je Dokument-Upload (HTTP und gRPC)
+pub const UPLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Maximaler Backoff eines Scanner-Workers
+const MAX_BACKOFF_SECS: u64 = 60;
+
+/// Ergebnis einer Job-Verarbeitung
+enum JobOutcome {
+    Done,
+    Failed(String),
+    Busy,
+    /// Zurückgestellt (z.B. zu wenig Speicherplatz) - bleibt in DocFlow wartend
+    Deferred(String),
+}
+
+/// Worker eines Scanners (eigene Warteschlange, eigener Backoff)
+#[derive(Default)]
+struct ScannerWorker {
+    queue: Mutex<Vec<PendingScanJob>>,
+    notify: Notify,
+    running_job: Mutex<Option<PendingScanJob>>,
+    /// Fehlgeschlagene Jobs bis zum nächsten Versuch (nur ohne Polling, sonst liefert DocFlow sie erneut)
+    retrying: Mutex<Vec<(Instant, PendingScanJob)>>,
+    task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl ScanPoller {
+    pub fn new(
+        api_key: String,
+        docflow_url: String,
+        scanners: Arc<RwLock<Vec<DiscoveredScanner>>>,
+        settings: Arc<RwLock<BridgeSettings>>,
+    ) -> Self {
+        Self {
+            api_key,
+            docflow_url,
+            scanners,
+            settings,
+            profiles: ProfileCache::new(),
+            status: Arc::new(RwLock::new(PollerStatus {
+                running: false,
+                last_poll: None,
+                jobs_processed: 0,
+                last_error: None,
+                last_poll_display: None,
+                running_secs: None,
+                started: None,
+            })),
+            waiting: RwLock::new(HashMap::new()),
+            workers: Mutex::new(HashMap::new()),
+            device_locks: Mutex::new(HashMap::new()),
+            finished: Mutex::new(HashMap::new()),
+            activity: RwLock::new(HashMap::new()),
+            activity_changed: std::sync::atomic::AtomicBool::new(true),
+            failures: Mutex::new(HashMap::new()),
+            reported_errors: Mutex::new(HashMap::new()),
+            dead_letters: Mutex::new(load_dead_letters()),
+            pending_cache: Mutex::new(None),
+            activity_report: Mutex::new(DeltaReport::new()),
+            priority_overrides: Mutex::new(HashMap::new()),
+            last_activity: Mutex::new(Instant::now()),
+            uploaded_parts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Holt ausstehende Scan-Jobs von DocFlow
+    pub async fn poll_pending_jobs(&self) -> Result<Vec<PendingScanJob>, Box<dyn std::error::Error + Send + Sync>> {
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/pending-scans", self.docflow_url);
+
+        let mut request = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .timeout(std::time::Duration::from_secs(10));
+        // Bedingte Anfrage: unveränderte Job-Liste kommt als 304 ohne Body
+        if let Some((etag, _)) = self.pending_cache.lock().await.as_ref() {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag.as_str());
+        }
+
+        let response = request.send_limited().await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some((_, jobs)) = self.pending_cache.lock().await.as_ref() {
+                return Ok(jobs.clone());
+            }
+        }
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Polling fehlgeschlagen: {}", error_text).into());
+        }
+
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let result: PendingScansResponse = response.json().await?;
+        *self.pending_cache.lock().await = etag.map(|etag| (etag, result.jobs.clone()));
+        Ok(result.jobs)
+    }
+
+    /// Setzt das referenzierte Scan-Profil ein und füllt fehlende Werte mit Defaults
+    pub async fn resolve_job(&self, job: &PendingScanJob) -> Result<PendingScanJob, Box<dyn std::error::Error + Send + Sync>> {
+        let mut resolved = match &job.profile_id {
+            Some(profile_id) => {
+                let profile = self
+                    .profiles
+                    .get(profile_id, &self.docflow_url, &self.api_key)
+                    .await
+                    .ok_or_else(|| format!("Scan-Profil '{}' nicht gefunden", profile_id))?;
+                println!("📋 Scan-Profil: {}", profile.name);
+                profiles::apply_profile(job, &profile)
+            }
+            None => job.clone(),
+        };
+
+        if resolved.resolution == 0 {
+            resolved.resolution = 300;
+        }
+        if resolved.color_mode.is_empty() {
+            resolved.color_mode = "color".to_string();
+        }
+        if resolved.source.is_empty() {
+            resolved.source = "flatbed".to_string();
+        }
+        if resolved.format.is_empty() {
+            resolved.format = "pdf".to_string();
+        }
+
+        Ok(resolved)
+    }
+
+    /// Gecachte Scan-Profile
+    pub async fn get_profiles(&self) -> Vec<ScanProfile> {
+        self.profiles.list().await
+    }
+
+    /// Führt einen Scan-Job aus (liefert ein oder mehrere Dokumente bei aktivierter Trennung)
+    /// Dauer, Seiten und Fehler fließen in die Geräte-Statistik
+    pub async fn execute_scan_job(
+        &self,
+        job: &PendingScanJob,
+    ) -> Result<Vec<ScanDocument>, Box<dyn std::error::Error + Send + Sync>> {
+        let started = Instant::now();
+        match self.scan_documents(job).await {
+            Ok((documents, pages)) => {
+                scanner_stats::record_job(&job.scanner_id, pages, started.elapsed());
+                Ok(documents)
+            }
+            Err(e) => {
+                scanner_stats::record_failure(&job.scanner_id, &e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Scan, Bildverarbeitung und Barcode-Erkennung → (Dokumente, gescannte Seiten)
+    async fn scan_documents(
+        &self,
+        job: &PendingScanJob,
+    ) -> Result<(Vec<ScanDocument>, usize), Box<dyn std::error::Error + Send + Sync>> {
+        // Scanner finden
+        let scanner = self
+            .scanners
+            .read()
+            .await
+            .iter()
+            .find(|s| s.id == job.scanner_id)
+            .cloned()
+            .ok_or_else(|| format!("Scanner '{}' nicht gefunden", job.scanner_id))?;
+
+        // Gescannt wird per eSCL bzw. unter Linux per SANE - reine WSD-Geräte haben keinen eSCL-Endpunkt
+        let supported = virtual_scanner::is_virtual(&scanner)
+            || scanner.protocols.iter().any(|p| p == "escl" || (cfg!(target_os = "linux") && p == "sane"));
+        if !supported {
+            return Err(format!(
+                "Scan über {} wird noch nicht unterstützt ({})",
+                scanner.protocols.join("/"),
+                scanner.name
+            )
+            .into());
+        }
+
+        let (wol_config, transfer, validation, virtual_config, spool_config) = {
+            let settings = self.settings.read().await;
+            (
+                settings.wol.clone(),
+                settings.transfer.clone(),
+                settings.validation.clone(),
+                settings.discovery.virtual_scanner.clone(),
+                settings.spool.clone(),
+            )
+        };
+
+        // Parameter gegen die Fähigkeiten prüfen (ggf. herabstufen)
+        let validated = validate_job(job, &scanner, &validation)?;
+        if !validated.warnings.is_empty() {
+            let message = format!("Parameter angepasst: {}", validated.warnings.join(", "));
+            println!("⚠ {}", message);
+            if let Err(e) = self.report_status(&job.job_id, "adjusted", &message).await {
+                eprintln!("⚠ Status-Meldung fehlgeschlagen: {}", e);
+            }
+        }
+        let job = &validated.job;
+
+        // Schlafende Scanner per Wake-on-LAN wecken (nicht bei lokal angeschlossenen)
+        let is_virtual = virtual_scanner::is_virtual(&scanner);
+        if !is_virtual && !scanner.ip.is_empty() {
+            wol::ensure_awake(&scanner, &wol_config).await?;
+        }
+
+        println!("📄 Starte Scan auf {} ({})...", scanner.name, scanner.ip);
+
+        // Scan durchführen
+        let scan_job = ScanJob {
+            scanner_id: job.scanner_id.clone(),
+            resolution: job.resolution,
+            color_mode: job.color_mode.clone(),
+            format: if job.format == "pdf" { "application/pdf".to_string() } else { "image/jpeg".to_string() },
+            source: job.source.clone(),
+            duplex: job.duplex,
+            page_retries: transfer.page_retries,
+            allow_partial: transfer.partial_results,
+            // mm → 1/300 Zoll
+            height: job.paper_length_mm.map(|mm| (mm as f32 / 25.4 * 300.0).round() as u32),
+            regions: job.regions.clone(),
+            timeout_secs: transfer.job_timeout_secs,
+            spool_after_kb: spool_config.spill_over_kb,
+        };
+
+        let result = if is_virtual {
+            virtual_scanner::scan(&virtual_config, &scan_job)?
+        } else if scanner.protocols.iter().any(|p| p == "sane") {
+            scan_sane(&scanner, &scan_job).await?
+        } else {
+            let quirks = quirks::for_scanner(&scanner.manufacturer, &scanner.model);
+            let result =
+                scan_escl_with_quirks(&scanner.ip, scanner.port, scanner.use_tls, &scanner.rs_path, &scan_job, &quirks).await;
+            discovery::mark_result(&scanner.id, &result);
+            result?
+        };
+
+        if result.pages.is_empty() {
+            return Err("Keine Seiten gescannt".into());
+        }
+
+        METRICS
+            .scan_pages
+            .fetch_add(result.total_pages as u64, std::sync::atomic::Ordering::Relaxed);
+
+        let pages: Vec<pipeline::ScanPage> = result.pages.into_iter().map(|page| (page.data, page.format)).collect();
+
+        let (options, barcode_config) = {
+            let settings = self.settings.read().await;
+            let options = PipelineOptions {
+                resolution: job.resolution,
+                duplex: job.duplex,
+                split: settings.split.clone(),
+                barcode: settings.barcode.clone(),
+                // Job/Profil-Vorgabe hat Vorrang vor der globalen Einstellung
+                enhance: job
+                    .enhancements
+                    .clone()
+                    .unwrap_or_else(|| settings.enhance.stages.clone()),
+                renditions: settings.renditions.clone(),
+                auto_color: (job.color_mode == "auto").then(|| settings.auto_color.clone()),
+                crop: settings.auto_crop.clone(),
+                orientation: job
+                    .auto_orient
+                    .unwrap_or(settings.orientation.enabled)
+                    .then(|| settings.orientation.clone()),
+                regions: job.regions.clone(),
+                max_document_bytes: job.max_document_kb.map(|kb| kb as usize * 1024),
+            };
+            (options, settings.barcode.clone())
+        };
+
+        // Bildverbesserung, Trennung, PDF-Erzeugung, Zusatz-Ausgaben
+        let documents = tokio::task::spawn_blocking(move || {
+            let mut documents = Vec::new();
+            for (data, format) in pipeline::process(pages, &options)? {
+                // Zusatz-Ausgaben brauchen das ganze Dokument - nur laden, wenn welche konfiguriert sind
+                let renditions = if options.renditions.kinds.is_empty() {
+                    Vec::new()
+                } else {
+                    pipeline::renditions(&(data.read()?, format.clone()), &options.renditions)
+                };
+                documents.push(((data, format), renditions));
+            }
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(documents)
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        if documents.is_empty() {
+            return Err("Nur Trennblätter gescannt - kein Dokument erzeugt".into());
+        }
+
+        println!(
+            "✓ Scan abgeschlossen: {} Seiten → {} Dokument(e)",
+            result.total_pages,
+            documents.len()
+        );
+
+        // Barcodes je Dokument suchen (falls aktiviert)
+        let mut scan_documents = Vec::new();
+        for ((data, format), renditions) in documents {
+            // Ausgelagerte Dokumente nur für die Erkennung laden, wenn sie aktiviert ist
+            let barcodes = if barcode_config.enabled {
+                barcode::detect(vec![(data.read()?, format)], barcode_config.clone()).await
+            } else {
+                Vec::new()
+            };
+            if !barcodes.is_empty() {
+                println!("🏷 {} Barcode(s) erkannt", barcodes.len());
+            }
+            scan_documents.push(ScanDocument {
+                data,
+                barcodes,
+                incomplete: result.incomplete,
+                renditions,
+            });
+        }
+
+        Ok((scan_documents, result.total_pages))
+    }
+
+    /// Lädt ein Scan-Dokument zu DocFlow hoch
+    /// part: (Index, Anzahl) wenn der Scan in mehrere Dokumente getrennt wurde
+    pub async fn upload_scan_result(
+        &self,
+        job_id: &str,
+        document: ScanDocument,
+        part: Option<(usize, usize)>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/scan-upload/{}", self.docflow_url, job_id);
+
+        // Der DocFlow-Job wartet auf das Ergebnis - Duplikate aus dem Scan-Ordner werden nur markiert
+        let file_hash = dedup::hash_reader(document.data.reader()?)?;
+        let duplicate_of = match dedup::check(&file_hash, Channel::Scan) {
+            Check::New => None,
+            Check::Skip(earlier) | Check::Flag(earlier) => {
+                println!("⚠ Scan zu Job {} wurde bereits {} hochgeladen, wird als Duplikat markiert", job_id, earlier.describe());
+                Some(earlier)
+            }
+        };
+
+        let started = std::time::Instant::now();
+        // gRPC-Upload-Stream, falls verbunden - sonst bzw. bei Verbindungsfehlern Multipart über HTTP
+        match grpc::upload_scan(&self.api_key, job_id, &document, part, &file_hash, duplicate_of.as_ref()).await {
+            Some(result) => result?,
+            None => {
+                let form = Self::document_form(document, part, job_id, &file_hash, duplicate_of.as_ref())?;
+                let request = client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_key))
+                    .timeout(UPLOAD_TIMEOUT);
+                let response = form.attach(request).await?.send_limited().await?;
+
+                if !response.status().is_success() {
+                    let error_text = response.text().await.unwrap_or_default();
+                    return Err(format!("Upload fehlgeschlagen: {}", error_text).into());
+                }
+            }
+        }
+
+        METRICS.scan_upload_duration.observe(started.elapsed());
+        dedup::record(&file_hash, Channel::Scan, &format!("Job {}", job_id));
+        println!("✓ Scan hochgeladen: Job {}", job_id);
+        Ok(())
+    }
+
+    /// Baut die Multipart-Form für ein Scan-Dokument (Datei meldet Upload-Fortschritt)
+    fn document_form(
+        document: ScanDocument,
+        part: Option<(usize, usize)>,
+        job_id: &str,
+        file_hash: &str,
+        duplicate_of: Option<&DedupEntry>,
+    ) -> Result<multipart::Form, Box<dyn std::error::Error + Send + Sync>> {
+        use multipart::{Form, Part};
+
+        let file_name = match part {
+            Some((index, _)) => format!("scan_{}.pdf", index + 1),
+            None => "scan.pdf".to_string(),
+        };
+
+        let file_part = Part::buffer(document.data)
+            .file_name(file_name)
+            .progress(UploadKind::Scan, job_id)
+            .mime_str("application/pdf")?;
+
+        let mut form = Form::new()
+            .part("file", file_part)
+            .text("success", "true")
+            .text("file_hash", file_hash);
+
+        // Schon per Folder-Sync hochgeladen (DocFlow kann verknüpfen statt doppelt abzulegen)
+        if let Some(earlier) = duplicate_of {
+            form = dedup::flag(form, earlier)?;
+        }
+
+        // Erkannte Barcodes als Metadaten (DocFlow nutzt sie für die Auto-Ablage)
+        if !document.barcodes.is_empty() {
+            form = form.text("barcodes", serde_json::to_string(&document.barcodes)?);
+        }
+
+        // Zusatz-Ausgaben (z.B. Thumbnail für die Sofort-Vorschau)
+        for rendition in document.renditions {
+            let part = Part::bytes(rendition.data)
+                .file_name(rendition.file_name)
+                .mime_str(&rendition.mime_type)?;
+            form = form.part("renditions", part);
+        }
+
+        // Teilergebnis kennzeichnen (DocFlow zeigt Hinweis "Seiten fehlen")
+        if document.incomplete {
+            form = form.text("incomplete", "true");
+        }
+
+        // Getrennte Dokumente: DocFlow legt je Teil ein eigenes Dokument an und schließt den Job mit dem letzten Teil ab
+        if let Some((index, count)) = part {
+            form = form
+                .text("split_index", index.to_string())
+                .text("split_count", count.to_string());
+        }
+
+        Ok(form)
+    }
+
+    /// Führt einen am Gerät ausgelösten Push-Scan aus und lädt ihn zu DocFlow hoch
+    pub async fn handle_push_scan(
+        &self,
+        scanner_id: &str,
+        profile_id: Option<String>,
+        source: Option<String>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let resource_config = self.settings.read().await.resources.clone();
+        resources::check(&resource_config)?;
+        *self.last_activity.lock().await = Instant::now();
+
+        // Quelle aus der Einstellung bzw. dem Profil, sonst Einzug - außer er ist (per Event gemeldet) leer
+        let source = match source.filter(|s| !s.is_empty()) {
+            Some(source) => source,
+            None if profile_id.is_some() => String::new(),
+            None if scanner_events::current(scanner_id).is_some_and(|s| s.adf_empty()) => "flatbed".to_string(),
+            None => "adf".to_string(),
+        };
+
+        let job = PendingScanJob {
+            job_id: format!("push-{}", uuid::Uuid::new_v4()),
+            scanner_id: scanner_id.to_string(),
+            profile_id,
+            resolution: 0,
+            color_mode: String::new(),
+            source,
+            duplex: false,
+            format: String::new(),
+            enhancements: None,
+            paper_length_mm: None,
+            regions: Vec::new(),
+            max_document_kb: None,
+            auto_orient: None,
+            priority: 0,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            expires_at: String::new(),
+        };
+        let job = self.resolve_job(&job).await?;
+        let documents = {
+            let device_lock = self.device_lock(&job.scanner_id).await;
+            let _device = device_lock.lock().await;
+            self.execute_scan_job(&job).await?
+        };
+
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/push-scan", self.docflow_url);
+        let count = documents.len();
+        let spool_config = self.settings.read().await.spool.clone();
+
+        for (index, document) in spool_documents(documents, &spool_config).into_iter().enumerate() {
+            let part = if count > 1 { Some((index, count)) } else { None };
+            // Push-Scans erwartet kein Job - Duplikate aus dem Scan-Ordner dürfen entfallen
+            let file_hash = dedup::hash_reader(document.data.reader()?)?;
+            let duplicate_of = match dedup::check(&file_hash, Channel::Scan) {
+                Check::New => None,
+                Check::Skip(earlier) => {
+                    println!("⏭ Push-Scan bereits {} hochgeladen", earlier.describe());
+                    continue;
+                }
+                Check::Flag(earlier) => Some(earlier),
+            };
+            let mut form = Self::document_form(document, part, &job.job_id, &file_hash, duplicate_of.as_ref())?
+                .text("scanner_id", job.scanner_id.clone());
+            if let Some(profile_id) = &job.profile_id {
+                form = form.text("profile_id", profile_id.clone());
+            }
+
+            let request = client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .timeout(UPLOAD_TIMEOUT);
+            let response = form.attach(request).await?.send_limited().await?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("Push-Scan-Upload fehlgeschlagen: {}", error_text).into());
+            }
+            dedup::record(&file_hash, Channel::Scan, &format!("Job {}", job.job_id));
+        }
+
+        audit::record(
+            AuditAction::ScanCompleted,
+            serde_json::json!({ "job_id": job.job_id, "scanner_id": job.scanner_id, "documents": count, "push": true }),
+        );
+        metrics::inc(&METRICS.scan_jobs_succeeded);
+        counters::record(|c| c.jobs_processed += 1);
+        let mut status = self.status.write().await;
+        status.jobs_processed += 1;
+        println!("✓ Push-Scan hochgeladen ({} Dokument(e))", count);
+        Ok(())
+    }
+
+    /// Lädt alle Dokumente eines Scan-Jobs hoch und führt ggf. den Post-Upload-Hook aus
+    async fn upload_scan_documents(
+        &self,
+        job: &PendingScanJob,
+        documents: Vec<ScanDocument>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (hook_config, spool_config) = {
+            let settings = self.settings.read().await;
+            (settings.hooks.clone(), settings.spool.clone())
+        };
+        let count = documents.len();
+        // Bei gleicher Aufteilung wie im vorigen Versuch liegen diese Teile in DocFlow schon vor
+        let uploaded = match self.uploaded_parts.lock().await.get(&job.job_id) {
+            Some((parts, uploaded)) if *parts == count => uploaded.clone(),
+            _ => BTreeSet::new(),
+        };
+        for (index, document) in spool_documents(documents, &spool_config).into_iter().enumerate() {
+            if uploaded.contains(&index) {
+                println!("↷ Teil {}/{} von Job {} bereits hochgeladen", index + 1, count, job.job_id);
+                continue;
+            }
+            let part = if count > 1 { Some((index, count)) } else { None };
+            // Kopie für den Hook während des Uploads ggf. ebenfalls nur verschlüsselt
+            let hook_data = if hook_config.enabled {
+                match document.data.try_clone() {
+                    Ok(spool::Buffer::Memory(data)) => Some(spool::Buffer::new(data, &spool_config)),
+                    Ok(spooled) => Some(spooled),
+                    Err(e) => {
+                        eprintln!("⚠ Post-Upload-Hook übersprungen: {}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            self.upload_scan_result(&job.job_id, document, part).await?;
+            if let Some((index, count)) = part {
+                let mut parts = self.uploaded_parts.lock().await;
+                let (parts, uploaded) = parts.entry(job.job_id.clone()).or_insert_with(|| (count, BTreeSet::new()));
+                // Andere Aufteilung als zuvor: DocFlow verwirft die früheren Teile
+                if *parts != count {
+                    *parts = count;
+                    uploaded.clear();
+                }
+                uploaded.insert(index);
+            }
+
+            if let Some(data) = hook_data {
+                hooks::run_for_scan(&hook_config, &data, &job.job_id, &job.scanner_id).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prüft vor dem Scan, ob der Scanner frei ist (z.B. kein Kopierjob am Gerät)
+    /// Ok(false): Job bleibt in DocFlow wartend und wird beim nächsten Poll erneut geprüft
+    async fn check_scanner_ready(&self, job: &PendingScanJob) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let scanner = self
+            .scanners
+            .read()
+            .await
+            .iter()
+            .find(|s| s.id == job.scanner_id)
+            .cloned();
+
+        // Unbekannt oder nicht erreichbar: execute_scan_job meldet den Fehler (inkl. Wake-on-LAN)
+        let Some(scanner) = scanner.filter(|s| !virtual_scanner::is_virtual(s)) else {
+            return Ok(true);
+        };
+        // Per WSD-Event gemeldeter Zustand spart die ScannerStatus-Abfrage
+        let state = match scanner_events::current(&scanner.id).and_then(|s| s.state) {
+            Some(state) => state,
+            None => {
+                let result = scanner_state(&scanner.ip, scanner.port, scanner.use_tls, &scanner.rs_path).await;
+                discovery::mark_result(&scanner.id, &result);
+                match result {
+                    Ok(state) => state,
+                    Err(_) => return Ok(true),
+                }
+            }
+        };
+
+        if state == "Idle" {
+            self.waiting.write().await.remove(&job.job_id);
+            return Ok(true);
+        }
+
+        let busy_wait_secs = self.settings.read().await.transfer.busy_wait_secs;
+        let mut waiting = self.waiting.write().await;
+        let first_check = !waiting.contains_key(&job.job_id);
+        let since = *waiting.entry(job.job_id.clone()).or_insert_with(Instant::now);
+
+        if since.elapsed().as_secs() > busy_wait_secs {
+            waiting.remove(&job.job_id);
+            return Err(format!(
+                "Scanner '{}' seit {}s belegt ({}) - Job abgebrochen",
+                scanner.name, busy_wait_secs, state
+            )
+            .into());
+        }
+        drop(waiting);
+
+        if first_check {
+            println!("⏳ {} ist belegt ({}) - Job {} wartet", scanner.name, state, job.job_id);
+            let message = format!("Warte auf Scanner ({})", state);
+            if let Err(e) = self.report_status(&job.job_id, "waiting_for_scanner", &message).await {
+                eprintln!("⚠ Status-Meldung fehlgeschlagen: {}", e);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Meldet einen Zwischenstatus eines Jobs an DocFlow
+    pub async fn report_status(
+        &self,
+        job_id: &str,
+        status: &str,
+        message: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/scan-status/{}", self.docflow_url, job_id);
+
+        let response = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&serde_json::json!({ "status": status, "message": message }))
+            .timeout(std::time::Duration::from_secs(10))
+            .send_limited()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()).into());
+        }
+        Ok(())
+    }
+
+    /// Meldet einen Fehler an DocFlow - nur wenn er sich vom zuletzt gemeldeten Fehler des Jobs unterscheidet
+    pub async fn report_error(
+        &self,
+        job_id: &str,
+        error_message: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        {
+            let mut reported = self.reported_errors.lock().await;
+            if reported.get(job_id).is_some_and(|last| last == error_message) {
+                return Ok(());
+            }
+            reported.insert(job_id.to_string(), error_message.to_string());
+        }
+
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/scan-upload/{}", self.docflow_url, job_id);
+
+        use multipart::{Form, Part};
+
+        // Leere Datei mit Fehler
+        let empty_part = Part::bytes(vec![])
+            .file_name("error.txt")
+            .mime_str("text/plain")?;
+
+        let form = Form::new()
+            .part("file", empty_part)
+            .text("success", "false")
+            .text("error_message", error_message);
+
+        let request = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .timeout(std::time::Duration::from_secs(10));
+        let _ = form.attach(request).await?.send_limited().await;
+
+        Ok(())
+    }
+
+    /// Verarbeitet einen Job komplett (Profil, Bereitschaft, Scan, Upload, Fehlermeldung)
+    /// Busy: Scanner belegt - Job bleibt in DocFlow wartend und kommt beim nächsten Poll erneut
+    async fn process_job(&self, job: PendingScanJob) -> JobOutcome {
+        println!("📥 Neuer Scan-Job: {} (Scanner: {})", job.job_id, job.scanner_id);
+
+        // Scanner einer anderen DocFlow-Instanz zugeordnet?
+        if !self.settings.read().await.discovery.is_assigned_to(&job.scanner_id, &self.docflow_url) {
+            let message = format!("Scanner {} ist dieser DocFlow-Instanz nicht zugeordnet", job.scanner_id);
+            eprintln!("❌ {}", message);
+            metrics::inc(&METRICS.scan_jobs_failed);
+            let _ = self.report_error(&job.job_id, &message).await;
+            return JobOutcome::Failed(message);
+        }
+
+        // Scan-Profil auflösen
+        let job = match self.resolve_job(&job).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                eprintln!("❌ Scan-Job ungültig: {}", e);
+                metrics::inc(&METRICS.scan_jobs_failed);
+                let _ = self.report_error(&job.job_id, &e.to_string()).await;
+                return JobOutcome::Failed(e.to_string());
+            }
+        };
+
+        // Genug Speicherplatz/Arbeitsspeicher für Spool und Seitenpuffer?
+        let resource_config = self.settings.read().await.resources.clone();
+        if let Err(reason) = resources::check(&resource_config) {
+            let _ = self.report_status(&job.job_id, "waiting_for_resources", &reason).await;
+            return JobOutcome::Deferred(reason);
+        }
+
+        // Scanner belegt? Dann Job in DocFlow wartend lassen
+        match self.check_scanner_ready(&job).await {
+            Ok(true) => {}
+            Ok(false) => return JobOutcome::Busy,
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                metrics::inc(&METRICS.scan_jobs_failed);
+                let _ = self.report_error(&job.job_id, &e.to_string()).await;
+                return JobOutcome::Failed(e.to_string());
+            }
+        }
+
+        // Scan ausführen (exklusiv je Gerät - auch gegenüber Push-Scans)
+        let device_lock = self.device_lock(&job.scanner_id).await;
+        let _device = device_lock.lock().await;
+        let scanning = tray::activity(Activity::Scanning);
+        let scan_result = self.execute_scan_job(&job).await;
+        drop(scanning);
+        match scan_result {
+            Ok(documents) => {
+                // Upload
+                let _uploading = tray::activity(Activity::Uploading);
+                let count = documents.len();
+                if let Err(e) = self.upload_scan_documents(&job, documents).await {
+                    eprintln!("❌ Upload fehlgeschlagen: {}", e);
+                    metrics::inc(&METRICS.scan_jobs_failed);
+                    audit_scan_failed(&job, &e.to_string());
+                    let _ = self.report_error(&job.job_id, &e.to_string()).await;
+                    JobOutcome::Failed(e.to_string())
+                } else {
+                    audit::record(
+                        AuditAction::ScanCompleted,
+                        serde_json::json!({ "job_id": job.job_id, "scanner_id": job.scanner_id, "documents": count }),
+                    );
+                    metrics::inc(&METRICS.scan_jobs_succeeded);
+                    counters::record(|c| c.jobs_processed += 1);
+                    let mut status = self.status.write().await;
+                    status.jobs_processed += 1;
+                    JobOutcome::Done
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ Scan fehlgeschlagen: {}", e);
+                metrics::inc(&METRICS.scan_jobs_failed);
+                audit_scan_failed(&job, &e.to_string());
+                let _ = self.report_error(&job.job_id, &e.to_string()).await;
+                JobOutcome::Failed(e.to_string())
+            }
+        }
+    }
+
+    /// Setzt den Live-Zustand eines Scanners (wird beim nächsten Poll an DocFlow gemeldet)
+    async fn set_activity(&self, scanner_id: &str, state: &str, job_id: Option<String>, message: Option<String>) {
+        self.activity.write().await.insert(
+            scanner_id.to_string(),
+            ScannerActivity {
+                scanner_id: scanner_id.to_string(),
+                state: state.to_string(),
+                job_id,
+                queue_length: 0,
+                message,
+                updated_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        self.activity_changed.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Live-Zustand aller bekannten Scanner inkl. Warteschlangenlänge
+    pub async fn scanner_activity(&self) -> Vec<ScannerActivity> {
+        let activity = self.activity.read().await;
+        let workers = self.workers.lock().await;
+        let mut result = Vec::new();
+
+        for scanner in self.scanners.read().await.iter() {
+            let mut entry = activity.get(&scanner.id).cloned().unwrap_or_else(|| ScannerActivity {
+                scanner_id: scanner.id.clone(),
+                state: "idle".to_string(),
+                job_id: None,
+                queue_length: 0,
+                message: None,
+                updated_at: chrono::Utc::now().to_rfc3339(),
+            });
+            if let Some(worker) = workers.get(&scanner.id) {
+                entry.queue_length = worker.queue.lock().await.len();
+            }
+            // Vom Gerät gemeldete Störungen (Papierstau, Klappe offen) auch ohne laufenden Job anzeigen
+            if entry.state == "idle" {
+                if let Some(pushed) = scanner_events::current(&scanner.id).filter(|s| !s.faults().is_empty()) {
+                    entry.state = "error".to_string();
+                    entry.message = Some(pushed.faults().join(", "));
+                }
+            }
+            result.push(entry);
+        }
+        result
+    }
+
+    /// Meldet geänderte Scanner-Zustände an DocFlow (ohne Änderungen nur den Heartbeat)
+    async fn push_scanner_activity(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let states = self.scanner_activity().await;
+
+        // Vergleich ohne Zeitstempel (unbenutzte Scanner erhalten bei jeder Abfrage einen neuen)
+        let current: serde_json::Map<String, serde_json::Value> = states
+            .iter()
+            .filter_map(|state| {
+                let mut value = serde_json::to_value(state).ok()?;
+                value.as_object_mut()?.remove("updated_at");
+                Some((state.scanner_id.clone(), value))
+            })
+            .collect();
+
+        let heartbeat = std::time::Duration::from_secs(self.settings.read().await.intervals.status_heartbeat_secs);
+        let Some(report) = self.activity_report.lock().await.next(&current, heartbeat) else {
+            return Ok(());
+        };
+
+        let report_type = report["report_type"].clone();
+        let changed: Vec<&ScannerActivity> = states
+            .iter()
+            .filter(|state| report.get(&state.scanner_id).is_some_and(|v| !v.is_null()))
+            .collect();
+        let removed: Vec<&String> = report
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(_, value)| value.is_null())
+            .map(|(scanner_id, _)| scanner_id)
+            .collect();
+        let body = match report_type.as_str() {
+            Some("heartbeat") => report.clone(),
+            _ => serde_json::json!({ "report_type": report_type, "states": changed, "removed": removed }),
+        };
+
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/scanner-states", self.docflow_url);
+
+        let response = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&body)
+            .timeout(std::time::Duration::from_secs(10))
+            .send_limited()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                self.activity_report.lock().await.confirm(current);
+                Ok(())
+            }
+            Ok(response) => {
+                self.activity_report.lock().await.reset();
+                Err(format!("HTTP {}", response.status()).into())
+            }
+            Err(e) => {
+                self.activity_report.lock().await.reset();
+                Err(e)
+            }
+        }
+    }
+
+    /// Zählt einen Fehlversuch - nach max_job_attempts wird der Job zum Dead-Letter
+    async fn record_failure(&self, job_id: &str, scanner_id: &str, message: &str) {
+        let max_attempts = self.settings.read().await.transfer.max_job_attempts.max(1);
+        let attempts = {
+            let mut failures = self.failures.lock().await;
+            let count = failures.entry(job_id.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+
+        if attempts < max_attempts {
+            println!("⚠ Job {} fehlgeschlagen (Versuch {}/{})", job_id, attempts, max_attempts);
+            return;
+        }
+
+        println!("🪦 Job {} nach {} Versuchen aufgegeben (Dead-Letter)", job_id, attempts);
+        self.failures.lock().await.remove(job_id);
+        self.reported_errors.lock().await.remove(job_id);
+        self.uploaded_parts.lock().await.remove(job_id);
+        {
+            let mut dead_letters = self.dead_letters.lock().await;
+            dead_letters.insert(
+                job_id.to_string(),
+                DeadLetter {
+                    job_id: job_id.to_string(),
+                    scanner_id: scanner_id.to_string(),
+                    attempts,
+                    last_error: message.to_string(),
+                    dead_lettered_at: chrono::Utc::now().to_rfc3339(),
+                },
+            );
+            save_dead_letters(&dead_letters);
+        }
+
+        if let Err(e) = self.report_terminal_failure(job_id, attempts, message).await {
+            eprintln!("⚠ Dead-Letter konnte nicht an DocFlow gemeldet werden: {}", e);
+        }
+    }
+
+    /// Meldet einen endgültigen Fehlschlag - DocFlow entfernt den Job aus pending-scans
+    async fn report_terminal_failure(
+        &self,
+        job_id: &str,
+        attempts: u32,
+        error_message: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let client = crate::http_client::docflow();
+        let url = format!("{}/api/scanner/bridge/scan-upload/{}", self.docflow_url, job_id);
+
+        use multipart::{Form, Part};
+
+        let empty_part = Part::bytes(vec![])
+            .file_name("error.txt")
+            .mime_str("text/plain")?;
+
+        let form = Form::new()
+            .part("file", empty_part)
+            .text("success", "false")
+            .text("terminal", "true")
+            .text("attempts", attempts.to_string())
+            .text(
+                "error_message",
+                format!("Nach {} Versuchen aufgegeben: {}", attempts, error_message),
+            );
+
+        let request = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .timeout(std::time::Duration::from_secs(10));
+        let response = form.attach(request).await?.send_limited().await?;
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()).into());
+        }
+        Ok(())
+    }
+
+    /// Lokal aufgegebene Jobs
+    pub async fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.lock().await.values().cloned().collect()
+    }
+
+    /// Laufende und wartende Jobs aller Scanner-Worker
+    pub async fn active_jobs(&self) -> Vec<ActiveJob> {
+        let activity = self.activity.read().await;
+        let mut result = Vec::new();
+
+        for (scanner_id, worker) in self.workers.lock().await.iter() {
+            if let Some(job) = worker.running_job.lock().await.as_ref() {
+                // Belegtes Gerät oder Ressourcenmangel: Job läuft, wartet aber eigentlich
+                let message = activity
+                    .get(scanner_id)
+                    .filter(|a| a.job_id.as_ref() == Some(&job.job_id))
+                    .and_then(|a| a.message.clone());
+                result.push(active_job(job, JobState::Running, message));
+            }
+            for job in worker.queue.lock().await.iter() {
+                result.push(active_job(job, JobState::Queued, None));
+            }
+            for (_, job) in worker.retrying.lock().await.iter() {
+                result.push(active_job(job, JobState::Retrying, None));
+            }
+        }
+        result
+    }
+
+    /// Bricht einen wartenden oder laufenden Job ab und meldet ihn in DocFlow als fehlgeschlagen
+    pub async fn cancel_job(self: &Arc<Self>, job_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut found = false;
+        let mut aborted_scanner = None;
+        for worker in self.workers.lock().await.values() {
+            let mut queue = worker.queue.lock().await;
+            let before = queue.len();
+            queue.retain(|job| job.job_id != job_id);
+            if queue.len() != before {
+                found = true;
+                break;
+            }
+            drop(queue);
+
+            let mut retrying = worker.retrying.lock().await;
+            let before = retrying.len();
+            retrying.retain(|(_, job)| job.job_id != job_id);
+            if retrying.len() != before {
+                found = true;
+                break;
+            }
+            drop(retrying);
+
+            let mut running = worker.running_job.lock().await;
+            let Some(job) = running.take_if(|job| job.job_id == job_id) else {
+                continue;
+            };
+            // Job-URL am Gerät vor dem Beenden des Tasks merken (der Scan trägt sie beim Beenden aus)
+            let job_url = scanner::running_job_url(&job.scanner_id);
+            // Laufenden Scan abbrechen: Worker-Task beenden und neu starten (Gerätesperre wird dabei frei)
+            if let Some(task) = worker.task.lock().await.take() {
+                task.abort();
+            }
+            drop(running);
+            *worker.task.lock().await = Some(tokio::spawn(self.clone().run_worker(worker.clone())));
+            aborted_scanner = Some((job.scanner_id, job_url));
+            found = true;
+            break;
+        }
+
+        if !found {
+            return Err(format!("Job {} ist nicht (mehr) in der Warteschlange", job_id).into());
+        }
+        if let Some((scanner_id, job_url)) = aborted_scanner {
+            // Sonst scannt das Gerät weiter und bleibt bis zum eigenen Timeout belegt
+            if let Some(job_url) = job_url {
+                if let Err(e) = scanner::cancel_running_job(&job_url).await {
+                    eprintln!("⚠ {}", e);
+                }
+            }
+            self.set_activity(&scanner_id, "idle", None, None).await;
+        }
+
+        println!("🚫 Scan-Job {} abgebrochen", job_id);
+        events::record(EventKind::Scan, format!("Scan-Job {} in der Bridge abgebrochen", job_id));
+        self.ignore_job(job_id, FINISHED_JOB_TTL).await;
+        self.priority_overrides.lock().await.remove(job_id);
+        self.uploaded_parts.lock().await.remove(job_id);
+        self.report_error(job_id, "In der Bridge abgebrochen").await
+    }
+
+    /// Ändert die Priorität eines wartenden Jobs (gilt bis DocFlow den Job nicht mehr liefert)
+    pub async fn set_job_priority(
+        &self,
+        job_id: &str,
+        priority: i32,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for worker in self.workers.lock().await.values() {
+            let mut queue = worker.queue.lock().await;
+            let Some(job) = queue.iter_mut().find(|job| job.job_id == job_id) else {
+                continue;
+            };
+            job.priority = priority;
+            sort_by_priority(&mut queue);
+            self.priority_overrides.lock().await.insert(job_id.to_string(), priority);
+            return Ok(());
+        }
+        Err(format!("Job {} wartet nicht (mehr) in der Warteschlange", job_id).into())
+    }
+
+    /// Ignoriert einen Job bei der Verteilung für die angegebene Zeit
+    async fn ignore_job(&self, job_id: &str, duration: std::time::Duration) {
+        self.finished.lock().await.insert(job_id.to_string(), Instant::now() + duration);
+    }
+
+    /// Sperre für exklusiven Gerätezugriff
+    async fn device_lock(&self, scanner_id: &str) -> Arc<Mutex<()>> {
+        self.device_locks
+            .lock()
+            .await
+            .entry(scanner_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Worker-Loop eines Scanners: arbeitet seine Warteschlange nach Priorität ab
+    /// Belegte/gestörte Geräte warten mit eigenem Backoff, ohne andere Scanner aufzuhalten
+    /// Ohne Polling (Tunnel/gRPC-Stream) kommen offene Jobs nicht erneut - sie werden selbst wieder eingereiht
+    async fn run_worker(self: Arc<Self>, worker: Arc<ScannerWorker>) {
+        let mut backoff_secs = 0u64;
+
+        loop {
+            let next_retry = self.requeue_due_retries(&worker).await;
+            let job = {
+                let mut queue = worker.queue.lock().await;
+                if queue.is_empty() {
+                    None
+                } else {
+                    Some(queue.remove(0))
+                }
+            };
+            let Some(job) = job else {
+                match next_retry {
+                    Some(at) => {
+                        tokio::select! {
+                            _ = worker.notify.notified() => {}
+                            _ = tokio::time::sleep_until(at.into()) => {}
+                        }
+                    }
+                    None => worker.notify.notified().await,
+                }
+                continue;
+            };
+
+            let job_id = job.job_id.clone();
+            let scanner_id = job.scanner_id.clone();
+            *worker.running_job.lock().await = Some(job.clone());
+            self.set_activity(&scanner_id, "scanning", Some(job_id.clone()), None).await;
+            let outcome = self.process_job(job.clone()).await;
+            *worker.running_job.lock().await = None;
+            let mut retry = true;
+            let mut retry_at = None;
+
+            match outcome {
+                JobOutcome::Done => {
+                    events::record(EventKind::Scan, format!("Scan-Job {} abgeschlossen ({})", job_id, scanner_id));
+                    tray::clear_error();
+                    self.failures.lock().await.remove(&job_id);
+                    self.reported_errors.lock().await.remove(&job_id);
+                    self.uploaded_parts.lock().await.remove(&job_id);
+                    self.ignore_job(&job_id, FINISHED_JOB_TTL).await;
+                    self.set_activity(&scanner_id, "idle", None, None).await;
+                    backoff_secs = 0;
+                    retry = false;
+                }
+                JobOutcome::Failed(message) => {
+                    events::record(EventKind::Error, format!("Scan-Job {} fehlgeschlagen: {}", job_id, message));
+                    tray::report_error(&message);
+                    self.ignore_job(&job_id, FAILED_JOB_RETRY_DELAY).await;
+                    self.record_failure(&job_id, &scanner_id, &message).await;
+                    retry = !self.dead_letters.lock().await.contains_key(&job_id);
+                    // Wie beim Polling erst nach FAILED_JOB_RETRY_DELAY erneut versuchen
+                    retry_at = self.finished.lock().await.get(&job_id).copied();
+                    self.set_activity(&scanner_id, "error", Some(job_id), Some(message)).await;
+                    backoff_secs = next_backoff(backoff_secs);
+                }
+                JobOutcome::Busy => {
+                    self.set_activity(&scanner_id, "busy", Some(job_id), Some("Gerät belegt".to_string())).await;
+                    backoff_secs = next_backoff(backoff_secs);
+                }
+                JobOutcome::Deferred(reason) => {
+                    tray::report_error(&reason);
+                    self.set_activity(&scanner_id, "low_resources", Some(job_id), Some(reason)).await;
+                    backoff_secs = next_backoff(backoff_secs);
+                }
+            }
+
+            if retry && (tunnel::is_connected() || grpc::is_streaming()) {
+                match retry_at {
+                    Some(at) if !job.is_expired() => worker.retrying.lock().await.push((at, job)),
+                    Some(_) => println!("⌛ Job {} abgelaufen - kein weiterer Versuch", job.job_id),
+                    None => self.requeue(&worker, job).await,
+                }
+            }
+
+            if backoff_secs > 0 {
+                tokio::time::sleep(std::time::Duration::from_secs(backoff_secs)).await;
+            }
+        }
+    }
+
+    /// Verteilt die aktuellen Jobs auf die Scanner-Worker (startet neue Worker bei Bedarf)
+    async fn dispatch_jobs(self: &Arc<Self>, jobs: Vec<PendingScanJob>) {
+        // Gerade erledigte Jobs ignorieren, bis DocFlow sie nicht mehr liefert
+        let finished: Vec<String> = {
+            let mut finished = self.finished.lock().await;
+            let now = Instant::now();
+            finished.retain(|_, until| *until > now);
+            finished.keys().cloned().collect()
+        };
+
+        // Lokale Prioritäten nur für Jobs behalten, die DocFlow noch liefert
+        let mut overrides = self.priority_overrides.lock().await;
+        overrides.retain(|job_id, _| jobs.iter().any(|j| &j.job_id == job_id));
+
+        let dead_letters = self.dead_letters.lock().await;
+        let mut by_scanner: HashMap<String, Vec<PendingScanJob>> = HashMap::new();
+        for mut job in jobs {
+            if !finished.contains(&job.job_id) && !dead_letters.contains_key(&job.job_id) {
+                if let Some(priority) = overrides.get(&job.job_id) {
+                    job.priority = *priority;
+                }
+                by_scanner.entry(job.scanner_id.clone()).or_default().push(job);
+            }
+        }
+        drop(dead_letters);
+        drop(overrides);
+
+        let mut workers = self.workers.lock().await;
+        for scanner_id in by_scanner.keys() {
+            if !workers.contains_key(scanner_id) {
+                workers.insert(scanner_id.clone(), self.spawn_worker().await);
+            }
+        }
+
+        // Warteschlangen ersetzen - so greifen Prioritätsänderungen und Stornierungen sofort
+        let mut queued = 0;
+        for (scanner_id, worker) in workers.iter() {
+            let mut jobs = by_scanner.remove(scanner_id).unwrap_or_default();
+            let running = worker.running_job.lock().await.as_ref().map(|job| job.job_id.clone());
+            jobs.retain(|job| Some(&job.job_id) != running.as_ref());
+            sort_by_priority(&mut jobs);
+
+            let has_jobs = !jobs.is_empty();
+            queued += jobs.len();
+            *worker.queue.lock().await = jobs;
+            // Beim Polling liefert DocFlow fehlgeschlagene Jobs selbst erneut
+            worker.retrying.lock().await.clear();
+            if has_jobs {
+                worker.notify.notify_one();
+            }
+        }
+        tray::QUEUED_JOBS.store(queued, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Reiht einen direkt zugestellten Job ein (Reverse-Tunnel) → Position in der Warteschlange des Scanners
+    /// Anders als dispatch_jobs bleiben die übrigen wartenden Jobs unverändert
+    pub async fn enqueue_job(self: &Arc<Self>, job: PendingScanJob) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let finished = self
+            .finished
+            .lock()
+            .await
+            .get(&job.job_id)
+            .is_some_and(|until| *until > Instant::now());
+        if finished || self.dead_letters.lock().await.contains_key(&job.job_id) {
+            return Err(format!("Job {} wurde bereits bearbeitet", job.job_id).into());
+        }
+        if job.is_expired() {
+            return Err(format!("Job {} ist abgelaufen", job.job_id).into());
+        }
+        *self.last_activity.lock().await = Instant::now();
+
+        let worker = {
+            let mut workers = self.workers.lock().await;
+            match workers.get(&job.scanner_id) {
+                Some(worker) => worker.clone(),
+                None => {
+                    let worker = self.spawn_worker().await;
+                    workers.insert(job.scanner_id.clone(), worker.clone());
+                    worker
+                }
+            }
+        };
+        // Erneut zugestellt, während er schon läuft
+        if worker.running_job.lock().await.as_ref().is_some_and(|running| running.job_id == job.job_id) {
+            return Ok(0);
+        }
+
+        let job_id = job.job_id.clone();
+        let position = {
+            let mut queue = worker.queue.lock().await;
+            queue.retain(|queued| queued.job_id != job_id);
+            queue.push(job);
+            sort_by_priority(&mut queue);
+            queue.iter().position(|queued| queued.job_id == job_id).unwrap_or_default()
+        };
+        worker.notify.notify_one();
+        tray::QUEUED_JOBS.store(self.queued_jobs().await, std::sync::atomic::Ordering::Relaxed);
+        Ok(position)
+    }
+
+    /// Stellt einen nicht erledigten Job zurück in die Warteschlange (nach dem Backoff des Workers)
+    async fn requeue(&self, worker: &ScannerWorker, job: PendingScanJob) {
+        if job.is_expired() {
+            println!("⌛ Job {} abgelaufen - kein weiterer Versuch", job.job_id);
+            return;
+        }
+        {
+            let mut queue = worker.queue.lock().await;
+            if queue.iter().any(|queued| queued.job_id == job.job_id) {
+                return;
+            }
+            queue.push(job);
+            sort_by_priority(&mut queue);
+        }
+        tray::QUEUED_JOBS.store(self.queued_jobs().await, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Reiht fällige Wiederholungen ein → Zeitpunkt der nächsten noch wartenden
+    async fn requeue_due_retries(&self, worker: &ScannerWorker) -> Option<Instant> {
+        let now = Instant::now();
+        let due: Vec<PendingScanJob> = {
+            let mut retrying = worker.retrying.lock().await;
+            let (due, waiting): (Vec<_>, Vec<_>) = retrying.drain(..).partition(|(at, _)| *at <= now);
+            *retrying = waiting;
+            due.into_iter().map(|(_, job)| job).collect()
+        };
+        for job in due {
+            self.requeue(worker, job).await;
+        }
+        worker.retrying.lock().await.iter().map(|(at, _)| *at).min()
+    }
+
+    /// Startet einen neuen Scanner-Worker
+    async fn spawn_worker(self: &Arc<Self>) -> Arc<ScannerWorker> {
+        let worker = Arc::new(ScannerWorker::default());
+        let handle = tokio::spawn(self.clone().run_worker(worker.clone()));
+        *worker.task.lock().await = Some(handle);
+        worker
+    }
+
+    /// Wartende Jobs aller Scanner-Worker
+    async fn queued_jobs(&self) -> usize {
+        let mut queued = 0;
+        for worker in self.workers.lock().await.values() {
+            queued += worker.queue.lock().await.len();
+        }
+        queued
+    }
+
+    /// Meldet Scanner-Zustände bei Änderung bzw. im Meldeintervall (sendet nur Deltas oder Heartbeat)
+    async fn report_activity(&self, last_push: &mut Option<Instant>) {
+        let changed = self.activity_changed.swap(false, std::sync::atomic::Ordering::Relaxed);
+        let heartbeat = std::time::Duration::from_secs(self.settings.read().await.intervals.status_report_secs);
+        if changed || last_push.is_none_or(|t| t.elapsed() > heartbeat) {
+            if let Err(e) = self.push_scanner_activity().await {
+                eprintln!("⚠ Scanner-Zustand melden fehlgeschlagen: {}", e);
+            }
+            if scanner_stats::take_changed() {
+                if let Err(e) = scanner_stats::report(&self.docflow_url, &self.api_key).await {
+                    eprintln!("⚠ Geräte-Statistik melden fehlgeschlagen: {}", e);
+                }
+            }
+            *last_push = Some(Instant::now());
+        }
+    }
+
+    /// Startet den Polling-Loop
+    pub async fn start_polling(self: Arc<Self>) {
+        {
+            let mut status = self.status.write().await;
+            status.running = true;
+            status.started = Some(Instant::now());
+        }
+
+        println!("🔄 Scan-Job-Poller gestartet");
+        let mut last_activity_push: Option<Instant> = None;
+
+        loop {
+            // Status prüfen
+            {
+                let status = self.status.read().await;
+                if !status.running {
+                    break;
+                }
+            }
+
+            // Reverse-Tunnel bzw. gRPC-Job-Stream verbunden: Jobs kommen direkt (enqueue_job), nur Zustände melden
+            if tunnel::is_connected() || grpc::is_streaming() {
+                tray::QUEUED_JOBS.store(self.queued_jobs().await, std::sync::atomic::Ordering::Relaxed);
+                self.report_activity(&mut last_activity_push).await;
+                // Profile kommen nicht über den Tunnel - weiterhin periodisch abrufen
+                self.profiles.refresh_if_stale(&self.docflow_url, &self.api_key).await;
+            } else {
+                // Polling durchführen
+                metrics::inc(&METRICS.polls);
+                match self.poll_pending_jobs().await {
+                    Ok(jobs) => {
+                        {
+                            let mut status = self.status.write().await;
+                            status.last_poll = Some(chrono::Utc::now().to_rfc3339());
+                            status.last_error = None;
+                        }
+
+                        // Profile periodisch aktualisieren
+                        self.profiles.refresh_if_stale(&self.docflow_url, &self.api_key).await;
+
+                        if !jobs.is_empty() {
+                            *self.last_activity.lock().await = Instant::now();
+                        }
+
+                        // Wartende Jobs vergessen, die DocFlow nicht mehr liefert (storniert/abgelaufen)
+                        self.waiting
+                            .write()
+                            .await
+                            .retain(|job_id, _| jobs.iter().any(|j| &j.job_id == job_id));
+
+                        // Jeder Scanner arbeitet seine Jobs unabhängig ab
+                        self.dispatch_jobs(jobs).await;
+
+                        self.report_activity(&mut last_activity_push).await;
+                    }
+                    Err(e) => {
+                        metrics::inc(&METRICS.poll_errors);
+                        let mut status = self.status.write().await;
+                        status.last_error = Some(e.to_string());
+                        // Bei Fehler nicht sofort aufgeben, nur loggen
+                        if !e.to_string().contains("401") {
+                            eprintln!("⚠ Polling-Fehler: {}", e);
+                        }
+                    }
+                }
+            }
+
+            // Warten vor nächstem Poll (Standard 2 Sekunden, im Leerlauf außerhalb der Bürozeiten seltener)
+            let idle_for = self.last_activity.lock().await.elapsed();
+            let delay = self
+                .settings
+                .read()
+                .await
+                .intervals
+                .job_poll_delay(idle_for, chrono::Local::now().naive_local());
+            // Nächster Heartbeat nach der Wartezeit plus Zeit für die Abfrage selbst
+            supervisor::beat(supervisor::POLLER, delay + POLL_BUDGET);
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = WAKE.notified() => *self.last_activity.lock().await = Instant::now(),
+            }
+        }
+
+        self.abort_workers().await;
+        println!("🛑 Scan-Job-Poller gestoppt");
+    }
+
+    /// Beendet alle Scanner-Worker (auch nach einem abgebrochenen Polling-Loop)
+    pub async fn abort_workers(&self) {
+        for (_, worker) in self.workers.lock().await.drain() {
+            if let Some(task) = worker.task.lock().await.take() {
+                task.abort();
+            }
+        }
+    }
+
+    /// Stoppt den Poller und beendet die Scanner-Worker
+    pub async fn stop(&self) {
+        {
+            let mut status = self.status.write().await;
+            status.running = false;
+            status.started = None;
+        }
+        self.abort_workers().await;
+    }
+
+    /// Gibt aktuellen Status zurück (mit Anzeige-Zeiten)
+    pub async fn get_status(&self) -> PollerStatus {
+        let mut status = self.status.read().await.clone();
+        status.last_poll_display = status.last_poll.as_deref().and_then(timestamps::display);
+        status.running_secs = status.started.map(|s| s.elapsed().as_secs());
+        status
+    }
+}
+
+/// Scan auf einem per SANE angebundenen Gerät (Scanner-ID "sane:<Gerätename>")
+#[cfg(target_os = "linux")]
+async fn scan_sane(
+    scanner: &DiscoveredScanner,
+    job: &ScanJob,
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+    let device = scanner
+        .id
+        .strip_prefix(crate::scanner::sane::ID_PREFIX)
+        .ok_or_else(|| format!("Kein SANE-Gerät: {}", scanner.id))?;
+    crate::scanner::sane::scan(device, job).await
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn scan_sane(
+    scanner: &DiscoveredScanner,
+    _job: &ScanJob,
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+    Err(format!("SANE ist nur unter Linux verfügbar ({})", scanner.name).into())
+}
+
+/// Wartende Teile großer Scans liegen bis zum Upload verschlüsselt im Spool
+/// (das erste Dokument wird sofort hochgeladen und bleibt, wo es ist)
+fn spool_documents(documents: Vec<ScanDocument>, config: &SpoolConfig) -> Vec<ScanDocument> {
+    documents
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut document)| {
+            document.data = match document.data {
+                spool::Buffer::Memory(data) if index > 0 => spool::Buffer::new(data, config),
+                data => data,
+            };
+            document
+        })
+        .collect()
+}
+
+fn dead_letters_path() -> std::path::PathBuf {
+    crate::settings::data_dir().join("dead_letters.json")
+}
+
+/// Lädt die Dead-Letter (ältere Einträge als DEAD_LETTER_RETENTION_DAYS entfallen)
+fn load_dead_letters() -> HashMap<String, DeadLetter> {
+    let entries: Vec<DeadLetter> = std::fs::read_to_string(dead_letters_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(DEAD_LETTER_RETENTION_DAYS);
+    entries
+        .into_iter()
+        .filter(|d| {
+            chrono::DateTime::parse_from_rfc3339(&d.dead_lettered_at)
+                .map(|t| t > cutoff)
+                .unwrap_or(false)
+        })
+        .map(|d| (d.job_id.clone(), d))
+        .collect()
+}
+
+fn save_dead_letters(dead_letters: &HashMap<String, DeadLetter>) {
+    let entries: Vec<&DeadLetter> = dead_letters.values().collect();
+    if let Ok(json) = serde_json::to_string_pretty(&entries) {
+        let _ = std::fs::create_dir_all(crate::settings::data_dir());
+        let _ = std::fs::write(dead_letters_path(), json);
+    }
+}
+
+/// Fehlgeschlagener Scan bzw. Upload im Audit-Log
+fn audit_scan_failed(job: &PendingScanJob, error: &str) {
+    audit::record(
+        AuditAction::ScanFailed,
+        serde_json::json!({ "job_id": job.job_id, "scanner_id": job.scanner_id, "error": error }),
+    );
+}
+
+/// Sortiert Jobs nach Priorität (absteigend), bei Gleichstand nach Erstellungszeit
+fn sort_by_priority(jobs: &mut [PendingScanJob]) {
+    jobs.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.created_at.cmp(&b.created_at)));
+}
+
+fn active_job(job: &PendingScanJob, state: JobState, message: Option<String>) -> ActiveJob {
+    ActiveJob {
+        kind: UploadKind::Scan,
+        id: job.job_id.clone(),
+        name: job.scanner_id.clone(),
+        state,
+        priority: job.priority,
+        message,
+    }
+}
+
+/// Backoff verdoppeln (5s, 10s, 20s, ... max. MAX_BACKOFF_SECS)
+fn next_backoff(current: u64) -> u64 {
+    if current == 0 { 5 } else { (current * 2).min(MAX_BACKOFF_SECS) }
+}
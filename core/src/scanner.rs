@@ -0,0 +1,747 @@
+// Scanner-Modul - Scan-Operationen ausführen
+// Platzhalter für zukünftige Implementierung
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::escl_recording::{self, Recorded};
+use crate::quirks::QuirkProfile;
+use crate::spool;
+
+/// Standard-Scanhöhe in 1/300 Zoll (Letter, 11")
+pub const DEFAULT_HEIGHT: u32 = 3300;
+
+/// pwg:Version der ScanSettings, sofern das Gerät keine andere verlangt
+const ESCL_VERSION: &str = "2.0";
+
+/// Am Scanner angelegte, noch laufende eSCL-Jobs (Scanner-ID → Job-URL)
+static RUNNING_JOBS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Trägt den Job für die Dauer des Scans ein - auch ein abgebrochener Scan-Task trägt ihn wieder aus
+struct RunningJob(String);
+
+impl RunningJob {
+    fn start(scanner_id: &str, job_url: &str) -> Self {
+        let mut jobs = RUNNING_JOBS.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.insert(scanner_id.to_string(), job_url.to_string());
+        Self(scanner_id.to_string())
+    }
+}
+
+impl Drop for RunningJob {
+    fn drop(&mut self) {
+        RUNNING_JOBS.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.0);
+    }
+}
+
+/// Job-URL des laufenden eSCL-Scans (vor dem Abbruch des Scan-Tasks abfragen)
+pub fn running_job_url(scanner_id: &str) -> Option<String> {
+    RUNNING_JOBS.lock().unwrap_or_else(|e| e.into_inner()).get(scanner_id).cloned()
+}
+
+/// Bricht einen laufenden eSCL-Job am Scanner ab (DELETE auf die Job-URL aus ScanJobs)
+pub async fn cancel_running_job(job_url: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = crate::http_client::scanner(std::time::Duration::from_secs(10))?;
+    let status = client.delete(job_url).send_recorded().await?.status();
+    // 404: Job ist bereits beendet
+    if status.is_success() || status.as_u16() == 404 {
+        println!("🗑 Job am Scanner abgebrochen: {} (HTTP {})", job_url, status.as_u16());
+        Ok(())
+    } else {
+        Err(format!("Job am Scanner nicht abgebrochen: HTTP {}", status).into())
+    }
+}
+
+/// Übertragungs-Einstellungen für eSCL-Scans
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransferConfig {
+    /// Wiederholungen je Seite bei Verbindungsabbruch
+    pub page_retries: u32,
+    /// Teilergebnis hochladen statt den ganzen Scan zu verwerfen
+    pub partial_results: bool,
+    /// Watchdog: maximale Dauer eines Scan-Jobs in Sekunden (0 = unbegrenzt)
+    #[serde(default = "default_job_timeout")]
+    pub job_timeout_secs: u64,
+    /// Wie lange ein Job auf einen belegten Scanner wartet, bevor er fehlschlägt
+    #[serde(default = "default_busy_wait")]
+    pub busy_wait_secs: u64,
+    /// Fehlversuche je Job, bevor er als Dead-Letter endgültig fehlschlägt
+    #[serde(default = "default_max_job_attempts")]
+    pub max_job_attempts: u32,
+}
+
+fn default_max_job_attempts() -> u32 {
+    5
+}
+
+fn default_busy_wait() -> u64 {
+    900
+}
+
+fn default_job_timeout() -> u64 {
+    600
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            page_retries: 3,
+            partial_results: true,
+            job_timeout_secs: default_job_timeout(),
+            busy_wait_secs: default_busy_wait(),
+            max_job_attempts: default_max_job_attempts(),
+        }
+    }
+}
+
+/// Scan-Auftrag
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScanJob {
+    pub scanner_id: String,
+    pub resolution: u32,
+    pub color_mode: String,
+    pub format: String,
+    pub source: String, // flatbed, adf
+    pub duplex: bool,
+    /// Wiederholungen je Seite bei Verbindungsabbruch (mit Range-Request fortsetzen)
+    #[serde(default)]
+    pub page_retries: u32,
+    /// Bei Abbruch bereits übertragene Seiten als unvollständiges Ergebnis liefern
+    #[serde(default)]
+    pub allow_partial: bool,
+    /// Scan-Höhe in 1/300 Zoll für Langpapier (None = Letter, 3300)
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// Mehrere Bereiche (z.B. Ausweis Vorder-/Rückseite) - werden zu einer Seite kombiniert
+    #[serde(default)]
+    pub regions: Vec<ScanRegion>,
+    /// Maximale Dauer des Seitenabrufs in Sekunden (0 = unbegrenzt)
+    #[serde(default)]
+    pub timeout_secs: u64,
+    /// Seiten ab dieser Stapelgröße in KB verschlüsselt auf Disk statt im Speicher (0 = alle im Speicher)
+    #[serde(default)]
+    pub spool_after_kb: u64,
+}
+
+/// Scan-Bereich in 1/300 Zoll (eSCL ThreeHundredthsOfInches)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScanRegion {
+    pub x_offset: u32,
+    pub y_offset: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScanRegion {
+    /// Umschließender Bereich aller Regionen (wird am Gerät gescannt, zugeschnitten wird lokal)
+    pub fn bounding(regions: &[ScanRegion]) -> Option<ScanRegion> {
+        let x = regions.iter().map(|r| r.x_offset).min()?;
+        let y = regions.iter().map(|r| r.y_offset).min()?;
+        let right = regions.iter().map(|r| r.x_offset + r.width).max()?;
+        let bottom = regions.iter().map(|r| r.y_offset + r.height).max()?;
+        Some(ScanRegion {
+            x_offset: x,
+            y_offset: y,
+            width: right - x,
+            height: bottom - y,
+        })
+    }
+}
+
+/// Scan-Ergebnis
+#[derive(Debug, Serialize)]
+pub struct ScanResult {
+    pub job_id: String,
+    pub pages: Vec<ScannedPage>,
+    pub total_pages: usize,
+    /// Scan wurde abgebrochen - nicht alle Seiten übertragen
+    pub incomplete: bool,
+}
+
+/// Gescannte Seite
+#[derive(Debug, Serialize)]
+pub struct ScannedPage {
+    pub page_number: usize,
+    pub format: String,
+    pub size_bytes: usize,
+    /// Seitendaten - bei großen Stapeln im Spool, damit der Speicherbedarf nicht mit der Seitenzahl wächst
+    #[serde(skip)]
+    pub data: spool::Buffer,
+}
+
+/// Führt Scan auf Netzwerk-Scanner via eSCL aus
+pub async fn scan_escl(
+    scanner_ip: &str,
+    scanner_port: u16,
+    job: &ScanJob,
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+    scan_escl_with_tls(scanner_ip, scanner_port, false, "eSCL", job).await
+}
+
+/// Führt Scan auf Netzwerk-Scanner via eSCL aus (mit optionalem TLS)
+pub async fn scan_escl_with_tls(
+    scanner_ip: &str,
+    scanner_port: u16,
+    use_tls: bool,
+    rs_path: &str,
+    job: &ScanJob,
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+    scan_escl_with_quirks(scanner_ip, scanner_port, use_tls, rs_path, job, &QuirkProfile::default()).await
+}
+
+/// Führt Scan auf Netzwerk-Scanner via eSCL aus - mit den Besonderheiten des Geräts (siehe quirks)
+pub async fn scan_escl_with_quirks(
+    scanner_ip: &str,
+    scanner_port: u16,
+    use_tls: bool,
+    rs_path: &str,
+    job: &ScanJob,
+    quirks: &QuirkProfile,
+) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+    // HTTPS für TLS oder Port 443 (selbstsignierte Zertifikate erlaubt der gemeinsame Scanner-Client)
+    let client = crate::http_client::scanner(std::time::Duration::from_secs(120))?;
+
+    let scheme = if use_tls || scanner_port == 443 { "https" } else { "http" };
+
+    // IPv6-Adressen brauchen Brackets in URLs
+    let host = if scanner_ip.contains(':') {
+        format!("[{}]", scanner_ip)
+    } else {
+        scanner_ip.to_string()
+    };
+
+    // Resource Path aus mDNS TXT "rs" Record (z.B. "eSCL", "eSCL2")
+    let rs = if rs_path.is_empty() { "eSCL" } else { rs_path };
+    let origin = format!("{}://{}:{}", scheme, host, scanner_port);
+    let base_url = format!("{}/{}", origin, rs);
+    println!("🔗 eSCL Base-URL: {}", base_url);
+
+    // Aufzeichnung (falls aktiviert) endet mit dem Scan
+    let _recording = escl_recording::start(&host, job);
+
+    // Langpapier: angeforderte Höhe auf das Gerätemaximum begrenzen
+    let height = match job.height {
+        Some(requested) if requested > DEFAULT_HEIGHT => {
+            match max_scan_height(&client, &base_url, &job.source).await {
+                Some(max) if max < requested => {
+                    println!("📏 Langpapier: {} angefordert, Gerät erlaubt max. {} (1/300\")", requested, max);
+                    max.max(DEFAULT_HEIGHT)
+                }
+                _ => requested,
+            }
+        }
+        Some(requested) => requested,
+        None => DEFAULT_HEIGHT,
+    };
+
+    // Mehrere Bereiche: nur den umschließenden Bereich scannen (Geräte unterstützen selten mehrere)
+    let region = ScanRegion::bounding(&job.regions).unwrap_or(ScanRegion {
+        x_offset: 0,
+        y_offset: 0,
+        width: 2550,
+        height,
+    });
+
+    // 1. Scan-Job erstellen
+    let scan_settings = scan_settings_xml_for(job, &region, quirks);
+
+    // Vor dem Scan: Scanner-Status prüfen und ggf. alte Jobs aufräumen
+    println!("🔍 Prüfe Scanner-Status bei {}...", base_url);
+    match client.get(format!("{}/ScannerStatus", base_url)).send_recorded().await {
+        Ok(status_resp) => {
+            let status_code = status_resp.status();
+            println!("📋 ScannerStatus HTTP {}", status_code);
+            if let Ok(status_xml) = status_resp.text().await {
+                // Erste 500 Zeichen loggen für Debugging
+                let preview: String = status_xml.chars().take(500).collect();
+                println!("📋 ScannerStatus Response:\n{}", preview);
+
+                let state = if status_xml.contains("Idle") { "Idle" }
+                    else if status_xml.contains("Processing") { "Processing" }
+                    else if status_xml.contains("Testing") { "Testing" }
+                    else { "Unbekannt" };
+                println!("📋 Scanner-State: {}", state);
+
+                // Abgeschlossene/hängende Jobs aus ScannerStatus extrahieren und löschen
+                // Laufende Fremd-Jobs (z.B. Kopierjob am Gerät) bleiben unangetastet
+                for job_info in parse_job_infos(&status_xml, quirks) {
+                    if job_info.is_running() {
+                        println!("⏭ Laufender Job am Scanner wird nicht gelöscht");
+                        continue;
+                    }
+                    let delete_url = format!("{}{}", origin, job_info.job_uri);
+                    println!("🗑 Lösche hängenden Job: {}", delete_url);
+                    let del_resp = client.delete(&delete_url).send_recorded().await;
+                    println!("🗑 DELETE Response: {:?}", del_resp.map(|r| r.status()));
+                }
+            }
+        }
+        Err(e) => {
+            println!("⚠ ScannerStatus fehlgeschlagen: {}", e);
+        }
+    }
+
+    // Scan-Job erstellen mit Retry bei 409 Conflict (Scanner busy)
+    let mut job_url = String::new();
+    let max_retries = 4;
+
+    for attempt in 0..max_retries {
+        if attempt > 0 {
+            println!("⏳ Scanner busy, Versuch {}/{}...", attempt + 1, max_retries);
+            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        }
+
+        let response = client
+            .post(format!("{}/ScanJobs", base_url))
+            .header("Content-Type", "application/xml")
+            .body(scan_settings.clone())
+            .send_recorded()
+            .await?;
+
+        let status = response.status();
+
+        if status.is_success() {
+            let location = response
+                .headers()
+                .get("Location")
+                .and_then(|v| v.to_str().ok())
+                .ok_or("Keine Job-URL erhalten")?;
+            job_url = quirks.job_url(location, &origin, &base_url);
+            println!("✓ Scan-Job erstellt: {}", job_url);
+            break;
+        } else if quirks.is_busy(status.as_u16()) && attempt < max_retries - 1 {
+            continue;
+        } else {
+            return Err(format!("Scan-Job erstellen fehlgeschlagen: {}", status).into());
+        }
+    }
+
+    if job_url.is_empty() {
+        return Err(
+            "Scanner dauerhaft busy — bitte Display prüfen, hängende Jobs unter \"Jobs am Scanner\" löschen oder Scanner neu starten"
+                .into(),
+        );
+    }
+
+    // Abbruch in der Bridge löscht den Job am Gerät (siehe running_job_url)
+    let _running = RunningJob::start(&job.scanner_id, &job_url);
+
+    // 2. Auf Scan-Ergebnis warten
+    let mut pages = Vec::new();
+    let mut page_number = 1;
+    let mut incomplete = false;
+    let mut buffered_bytes: u64 = 0;
+
+    // Watchdog: hängende Scanner liefern endlos "nicht bereit" - Job nach Ablauf abbrechen
+    let deadline = (job.timeout_secs > 0)
+        .then(|| tokio::time::Instant::now() + tokio::time::Duration::from_secs(job.timeout_secs));
+
+    loop {
+        // NextDocument abrufen
+        let doc_url = format!("{}/NextDocument", job_url);
+        let fetch = fetch_next_page(&client, &doc_url, job.page_retries);
+        let fetched = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, fetch).await {
+                Ok(result) => result,
+                Err(_) => {
+                    eprintln!("⏱ Scan-Watchdog: Job nach {}s abgebrochen ({})", job.timeout_secs, job_url);
+                    let _ = client.delete(&job_url).send_recorded().await;
+                    return Err(format!(
+                        "Scan-Timeout: Scanner hat nach {}s nicht fertig gescannt, Job abgebrochen",
+                        job.timeout_secs
+                    )
+                    .into());
+                }
+            },
+            None => fetch.await,
+        };
+        let data = match fetched {
+            // Keine weiteren Seiten
+            Ok(PageFetch::Done) => break,
+            Ok(PageFetch::NotReady) => {
+                // Scan noch nicht fertig, warten
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                continue;
+            }
+            Ok(PageFetch::Page(data)) => data,
+            Err(e) => {
+                // Papier ist schon durch den Einzug - vorhandene Seiten nicht verwerfen
+                if job.allow_partial && !pages.is_empty() {
+                    eprintln!("⚠ Scan nach {} Seite(n) abgebrochen, liefere Teilergebnis: {}", pages.len(), e);
+                    incomplete = true;
+                    break;
+                }
+                return Err(e);
+            }
+        };
+
+        // Großer Stapel: weitere Seiten direkt beim Eintreffen in den Spool
+        let size_bytes = data.len();
+        let data = if job.spool_after_kb > 0 && buffered_bytes >= job.spool_after_kb * 1024 {
+            spool::Buffer::spill(data)
+        } else {
+            buffered_bytes += size_bytes as u64;
+            spool::Buffer::Memory(data)
+        };
+
+        pages.push(ScannedPage {
+            page_number,
+            format: job.format.clone(),
+            size_bytes,
+            data,
+        });
+
+        page_number += 1;
+    }
+
+    let spooled = pages.iter().filter(|page| page.data.is_spooled()).count();
+    if spooled > 0 {
+        println!("💾 {} von {} Seiten bis zur Verarbeitung im Spool", spooled, pages.len());
+    }
+
+    Ok(ScanResult {
+        job_id: uuid::Uuid::new_v4().to_string(),
+        total_pages: pages.len(),
+        pages,
+        incomplete,
+    })
+}
+
+/// ScanSettings-XML für einen Job (Region bereits auf das Gerät angepasst)
+pub fn scan_settings_xml(job: &ScanJob, region: &ScanRegion) -> String {
+    scan_settings_xml_for(job, region, &QuirkProfile::default())
+}
+
+/// ScanSettings-XML mit den Besonderheiten des Geräts (z.B. abweichende pwg:Version)
+pub fn scan_settings_xml_for(job: &ScanJob, region: &ScanRegion, quirks: &QuirkProfile) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<scan:ScanSettings xmlns:scan="http://schemas.hp.com/imaging/escl/2011/05/03"
+                   xmlns:pwg="http://www.pwg.org/schemas/2010/12/sm">
+    <pwg:Version>{}</pwg:Version>
+    <scan:Intent>Document</scan:Intent>
+    <pwg:ScanRegions>
+        <pwg:ScanRegion>
+            <pwg:ContentRegionUnits>escl:ThreeHundredthsOfInches</pwg:ContentRegionUnits>
+            <pwg:XOffset>{}</pwg:XOffset>
+            <pwg:YOffset>{}</pwg:YOffset>
+            <pwg:Width>{}</pwg:Width>
+            <pwg:Height>{}</pwg:Height>
+        </pwg:ScanRegion>
+    </pwg:ScanRegions>
+    <pwg:InputSource>{}</pwg:InputSource>
+    <scan:ColorMode>{}</scan:ColorMode>
+    <scan:XResolution>{}</scan:XResolution>
+    <scan:YResolution>{}</scan:YResolution>
+    <pwg:DocumentFormat>{}</pwg:DocumentFormat>
+</scan:ScanSettings>"#,
+        quirks.escl_version.as_deref().unwrap_or(ESCL_VERSION),
+        region.x_offset,
+        region.y_offset,
+        region.width,
+        region.height,
+        if job.source == "adf" { "Feeder" } else { "Platen" },
+        // Frontend sendet "color"/"grayscale", eSCL erwartet "RGB24"/"Grayscale8"
+        match job.color_mode.to_lowercase().as_str() {
+            // "auto": in Farbe scannen, Umwandlung erfolgt in der Pipeline
+            "color" | "rgb24" | "rgb" | "auto" => "RGB24",
+            "grayscale" | "grayscale8" | "gray" | "bw" => "Grayscale8",
+            _ => "RGB24",  // Fallback
+        },
+        job.resolution,
+        job.resolution,
+        job.format
+    )
+}
+
+/// Fragt den aktuellen Gerätezustand ab (pwg:State: Idle, Processing, Testing, Stopped, Down)
+pub async fn scanner_state(
+    scanner_ip: &str,
+    scanner_port: u16,
+    use_tls: bool,
+    rs_path: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let status_xml = scanner_status(scanner_ip, scanner_port, use_tls, rs_path).await?;
+    crate::push_scan::extract_tag(&status_xml, "State").ok_or_else(|| "Kein State in ScannerStatus".into())
+}
+
+/// Job aus der Job-Liste des Scanners (eSCL JobInfo in ScannerStatus)
+#[derive(Clone, Debug, Serialize)]
+pub struct ScannerJobInfo {
+    /// Pfad des Jobs am Scanner, z.B. "/eSCL/ScanJobs/12"
+    pub job_uri: String,
+    /// JobState als eSCL-Wert (Processing, Pending, Completed, Aborted, Canceled)
+    pub state: String,
+    /// Alter in Sekunden laut Scanner
+    pub age_secs: Option<u64>,
+    pub images_completed: Option<u32>,
+    pub images_to_transfer: Option<u32>,
+    /// Erster JobStateReason, z.B. "JobCompletedSuccessfully"
+    pub reason: Option<String>,
+}
+
+impl ScannerJobInfo {
+    /// Job läuft noch (eigener Scan oder Fremd-Job wie ein Kopierauftrag am Gerät)
+    pub fn is_running(&self) -> bool {
+        matches!(self.state.as_str(), "Processing" | "Pending")
+    }
+}
+
+/// Liest die JobInfo-Einträge aus einer ScannerStatus-Antwort
+pub fn parse_job_infos(status_xml: &str, quirks: &QuirkProfile) -> Vec<ScannerJobInfo> {
+    use crate::push_scan::extract_tag;
+
+    status_xml
+        .split("JobInfo>")
+        .filter_map(|block| {
+            let uri = extract_tag(block, "JobUri")?;
+            // Manche Geräte melden eine absolute URL - nur der Pfad zählt
+            let job_uri = match uri.strip_prefix("http://").or_else(|| uri.strip_prefix("https://")) {
+                Some(rest) => rest.find('/').map(|i| rest[i..].to_string())?,
+                None => uri,
+            };
+            Some(ScannerJobInfo {
+                job_uri,
+                state: extract_tag(block, "JobState").map(|raw| quirks.job_state(&raw)).unwrap_or_default(),
+                age_secs: extract_tag(block, "Age").and_then(|age| age.parse().ok()),
+                images_completed: extract_tag(block, "ImagesCompleted").and_then(|n| n.parse().ok()),
+                images_to_transfer: extract_tag(block, "ImagesToTransfer").and_then(|n| n.parse().ok()),
+                reason: extract_tag(block, "JobStateReason"),
+            })
+        })
+        .collect()
+}
+
+/// Job-Liste des Scanners (zur Fehlersuche bei hängenden Geräten)
+pub async fn scanner_jobs(
+    scanner_ip: &str,
+    scanner_port: u16,
+    use_tls: bool,
+    rs_path: &str,
+    quirks: &QuirkProfile,
+) -> Result<Vec<ScannerJobInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let status_xml = scanner_status(scanner_ip, scanner_port, use_tls, rs_path).await?;
+    Ok(parse_job_infos(&status_xml, quirks))
+}
+
+/// Löscht einen Job am Scanner - nur Job-Pfade des Geräts, keine beliebigen URLs
+/// Laufende Jobs (Processing) werden nicht gelöscht - das DELETE würde einen aktiven Scan abbrechen
+pub async fn clear_job(
+    scanner_ip: &str,
+    scanner_port: u16,
+    use_tls: bool,
+    rs_path: &str,
+    quirks: &QuirkProfile,
+    job_uri: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !job_uri.starts_with('/') || !job_uri.contains("/ScanJobs/") || job_uri.contains("..") {
+        return Err(format!("Ungültiger Job-Pfad: {}", job_uri).into());
+    }
+
+    let jobs = scanner_jobs(scanner_ip, scanner_port, use_tls, rs_path, quirks).await?;
+    if jobs.iter().any(|job| job.job_uri == job_uri && job.state == "Processing") {
+        return Err(format!("Job {} läuft gerade und wird nicht gelöscht", job_uri).into());
+    }
+
+    let client = crate::http_client::scanner(std::time::Duration::from_secs(10))?;
+    let url = format!("{}{}", scanner_origin(scanner_ip, scanner_port, use_tls), job_uri);
+    let status = client.delete(&url).send_recorded().await?.status();
+    // 404: Job ist bereits weg
+    if status.is_success() || status.as_u16() == 404 {
+        println!("🗑 Job am Scanner gelöscht: {} (HTTP {})", url, status.as_u16());
+        Ok(())
+    } else {
+        Err(format!("Job löschen fehlgeschlagen: HTTP {}", status).into())
+    }
+}
+
+/// "scheme://host:port" des Scanners (IPv6 in Brackets, HTTPS für TLS oder Port 443)
+fn scanner_origin(scanner_ip: &str, scanner_port: u16, use_tls: bool) -> String {
+    let scheme = if use_tls || scanner_port == 443 { "https" } else { "http" };
+    if scanner_ip.contains(':') {
+        format!("{}://[{}]:{}", scheme, scanner_ip, scanner_port)
+    } else {
+        format!("{}://{}:{}", scheme, scanner_ip, scanner_port)
+    }
+}
+
+/// ScannerStatus-XML abrufen
+async fn scanner_status(
+    scanner_ip: &str,
+    scanner_port: u16,
+    use_tls: bool,
+    rs_path: &str,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let client = crate::http_client::scanner(std::time::Duration::from_secs(5))?;
+    let rs = if rs_path.is_empty() { "eSCL" } else { rs_path };
+    let url = format!("{}/{}/ScannerStatus", scanner_origin(scanner_ip, scanner_port, use_tls), rs);
+    Ok(client.get(&url).send_recorded().await?.text().await?)
+}
+
+/// Liest MaxHeight (1/300 Zoll) der Eingabequelle aus den ScannerCapabilities
+async fn max_scan_height(client: &reqwest::Client, base_url: &str, source: &str) -> Option<u32> {
+    let xml = client
+        .get(format!("{}/ScannerCapabilities", base_url))
+        .send_recorded()
+        .await
+        .ok()?
+        .text()
+        .await
+        .ok()?;
+
+    let section = if source == "adf" { ":Adf>" } else { ":Platen>" };
+    let start = xml.find(section).unwrap_or(0);
+    crate::push_scan::extract_tag(&xml[start..], "MaxHeight")?.parse().ok()
+}
+
+/// Ergebnis eines NextDocument-Abrufs
+enum PageFetch {
+    Page(Vec<u8>),
+    NotReady,
+    Done,
+}
+
+/// Lädt die nächste Seite - bricht die Verbindung mitten in der Seite ab,
+/// wird per Range-Request ab dem letzten Byte fortgesetzt (falls der Scanner das unterstützt)
+async fn fetch_next_page(
+    client: &reqwest::Client,
+    doc_url: &str,
+    retries: u32,
+) -> Result<PageFetch, Box<dyn std::error::Error + Send + Sync>> {
+    let mut data: Vec<u8> = Vec::new();
+    let mut attempt = 0;
+
+    loop {
+        let mut request = client.get(doc_url);
+        if !data.is_empty() {
+            request = request.header("Range", format!("bytes={}-", data.len()));
+        }
+
+        let error = match request.send_recorded().await {
+            Ok(mut response) => {
+                let status = response.status().as_u16();
+                if data.is_empty() {
+                    if status == 404 {
+                        return Ok(PageFetch::Done);
+                    }
+                    if !response.status().is_success() {
+                        return Ok(PageFetch::NotReady);
+                    }
+                } else {
+                    match status {
+                        206 => {}
+                        // Keine Range-Unterstützung: Seite kommt komplett neu
+                        200 => data.clear(),
+                        _ => return Err(format!("Seite nach Abbruch nicht mehr abrufbar (HTTP {})", status).into()),
+                    }
+                }
+
+                loop {
+                    match response.chunk().await {
+                        Ok(Some(chunk)) => data.extend_from_slice(&chunk),
+                        Ok(None) => return Ok(PageFetch::Page(data)),
+                        Err(e) => break e,
+                    }
+                }
+            }
+            Err(e) => e,
+        };
+
+        attempt += 1;
+        if attempt > retries {
+            return Err(error.into());
+        }
+        println!(
+            "⚠ Seitenübertragung abgebrochen ({} Bytes), Versuch {}/{}: {}",
+            data.len(),
+            attempt,
+            retries,
+            error
+        );
+        tokio::time::sleep(tokio::time::Duration::from_secs(attempt as u64)).await;
+    }
+}
+
+// Native Scanner-Zugriffe
+#[cfg(target_os = "linux")]
+pub mod sane {
+    //! SANE Scanner Access
+    use super::{ScanJob, ScanResult, ScannedPage};
+    use crate::sane::{pick, Sane, SaneScanSettings};
+    use crate::{imaging, pdf, spool};
+
+    /// Präfix der Scanner-ID von SANE-Geräten ("sane:<Gerätename>")
+    pub const ID_PREFIX: &str = "sane:";
+
+    /// Scannt auf einem SANE-Gerät (USB oder saned) - Quelle und Farbmodus werden auf die Werte des Geräts abgebildet
+    pub async fn scan(device_name: &str, job: &ScanJob) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+        let device_name = device_name.to_string();
+        let job = job.clone();
+        // libsane blockiert während des ganzen Scans - eigener Thread
+        tokio::task::spawn_blocking(move || {
+            let sane = Sane::load()?;
+            let options = sane.options(&device_name)?;
+            let source = match job.source.as_str() {
+                "adf" if job.duplex => pick(&options.sources, &["duplex"]),
+                "adf" => pick(&options.sources, &["adf", "feeder", "automatic document"]),
+                _ => pick(&options.sources, &["flatbed", "platen"]),
+            };
+            if job.source == "adf" && source.is_none() && !options.sources.is_empty() {
+                return Err(format!("Gerät bietet keinen Einzug ({})", options.sources.join(", ")).into());
+            }
+            let mode = match job.color_mode.to_lowercase().as_str() {
+                "grayscale8" | "grayscale" | "gray" => pick(&options.modes, &["gray"]),
+                "blackandwhite1" | "bw" | "lineart" => pick(&options.modes, &["lineart", "binary", "black"]),
+                _ => pick(&options.modes, &["color"]),
+            };
+            let settings = SaneScanSettings {
+                source,
+                mode,
+                resolution: job.resolution,
+                batch: job.source == "adf",
+            };
+
+            let jpegs = sane
+                .scan(&device_name, &settings)?
+                .iter()
+                .map(imaging::encode_jpeg)
+                .collect::<Result<Vec<_>, _>>()?;
+            let total_pages = jpegs.len();
+            println!("✓ SANE-Scan: {} Seite(n) von {}", total_pages, device_name);
+
+            let documents = if job.format == "application/pdf" {
+                vec![pdf::assemble_jpeg_pdf(&jpegs, job.resolution)?]
+            } else {
+                jpegs
+            };
+
+            // Großer Stapel: wie beim eSCL-Scan ab der Schwelle in den Spool
+            let mut buffered_bytes = 0u64;
+            let mut pages = Vec::new();
+            for (index, data) in documents.into_iter().enumerate() {
+                let size_bytes = data.len();
+                let data = if job.spool_after_kb > 0 && buffered_bytes >= job.spool_after_kb * 1024 {
+                    spool::Buffer::spill(data)
+                } else {
+                    buffered_bytes += size_bytes as u64;
+                    spool::Buffer::Memory(data)
+                };
+                pages.push(ScannedPage {
+                    page_number: index + 1,
+                    format: job.format.clone(),
+                    size_bytes,
+                    data,
+                });
+            }
+
+            Ok(ScanResult {
+                job_id: uuid::Uuid::new_v4().to_string(),
+                pages,
+                total_pages,
+                incomplete: false,
+            })
+        })
+        .await?
+    }
+}
@@ -0,0 +1,146 @@
+// Scanner-Events - Zustandsänderungen per WS-Eventing (WSD) statt ScannerStatus-Polling
+// eSCL kennt keinen standardisierten Push-Kanal: reine eSCL-Geräte werden weiterhin per ScannerStatus abgefragt
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::discovery;
+use crate::events::{self, EventKind};
+use crate::push_scan::extract_tag;
+
+/// WS-Eventing-Filter für Zustandsänderungen (Leerzeichen-getrennte Actions)
+pub const STATUS_EVENT_FILTER: &str = "http://schemas.microsoft.com/windows/2006/08/wdp/scan/ScannerStatusSummaryEvent \
+http://schemas.microsoft.com/windows/2006/08/wdp/scan/ScannerStatusConditionEvent \
+http://schemas.microsoft.com/windows/2006/08/wdp/scan/ScannerStatusConditionClearedEvent";
+
+/// Gepushte Zustände gelten nur, solange das Abo läuft
+const SUBSCRIPTION_VALIDITY: Duration = Duration::from_secs(crate::push_scan::SUBSCRIPTION_SECS);
+
+/// Ein gepushter Zustand gilt nur so lange als aktuell, danach wird wieder ScannerStatus abgefragt
+/// (ein verlorenes Idle-Event blockiert den Scanner so höchstens kurz)
+pub const PUSHED_STATE_TTL: Duration = Duration::from_secs(120);
+
+/// Zuletzt gemeldeter Zustand eines Scanners
+#[derive(Clone, Debug, Serialize)]
+pub struct PushedState {
+    /// "Idle", "Processing", "Stopped" (wie ScannerState bei eSCL)
+    pub state: Option<String>,
+    /// Aktive Gerätezustände je ConditionId ("InputTrayEmpty", "MediaJam", "CoverOpen", ...)
+    pub conditions: BTreeMap<String, String>,
+    #[serde(skip)]
+    subscribed_at: Instant,
+    /// Zeitpunkt des letzten Events
+    #[serde(skip)]
+    updated_at: Instant,
+}
+
+impl PushedState {
+    /// Einzug leer (nur bekannt, wenn das Gerät die Condition meldet)
+    pub fn adf_empty(&self) -> bool {
+        self.conditions.values().any(|name| name == "InputTrayEmpty")
+    }
+
+    /// Störungen, die einen Eingriff am Gerät erfordern
+    pub fn faults(&self) -> Vec<&str> {
+        self.conditions.values().map(String::as_str).filter(|name| is_fault(name)).collect()
+    }
+}
+
+static STATES: Mutex<Option<HashMap<String, PushedState>>> = Mutex::new(None);
+
+/// Merkt sich ein erfolgreiches Abo (bis zum Ablauf gelten gepushte Zustände als aktuell)
+pub fn subscribed(scanner_id: &str) {
+    with_states(|states| {
+        let entry = states.entry(scanner_id.to_string()).or_insert_with(|| PushedState {
+            state: None,
+            conditions: BTreeMap::new(),
+            subscribed_at: Instant::now(),
+            updated_at: Instant::now(),
+        });
+        entry.subscribed_at = Instant::now();
+    });
+}
+
+/// Aktueller Zustand aus Events (None = kein Abo, noch kein Event oder letztes Event zu alt)
+pub fn current(scanner_id: &str) -> Option<PushedState> {
+    current_as_of(scanner_id, Instant::now())
+}
+
+/// Zustand aus Events zum Zeitpunkt `now`
+pub fn current_as_of(scanner_id: &str, now: Instant) -> Option<PushedState> {
+    with_states(|states| {
+        states
+            .get(scanner_id)
+            .filter(|s| now.saturating_duration_since(s.subscribed_at) < SUBSCRIPTION_VALIDITY)
+            .filter(|s| now.saturating_duration_since(s.updated_at) < PUSHED_STATE_TTL)
+            .filter(|s| s.state.is_some() || !s.conditions.is_empty())
+            .cloned()
+    })
+}
+
+/// Verarbeitet ein eingehendes Status-Event (Scanner-ID aus den ReferenceParameters des Abos)
+/// Token und Absender prüft vorher push_scan::verify_event
+pub fn handle_event(xml: &str) -> bool {
+    let Some(scanner_id) = extract_tag(xml, "ScannerId") else {
+        return false;
+    };
+
+    if xml.contains("ScannerStatusSummaryEvent") {
+        let state = extract_tag(xml, "ScannerState");
+        with_states(|states| {
+            if let Some(entry) = states.get_mut(&scanner_id) {
+                entry.state = state;
+                entry.updated_at = Instant::now();
+            }
+        });
+    } else if xml.contains("ScannerStatusConditionClearedEvent") {
+        let Some(condition_id) = extract_tag(xml, "ConditionId") else {
+            return false;
+        };
+        let cleared = with_states(|states| {
+            states
+                .get_mut(&scanner_id)
+                .and_then(|entry| {
+                    entry.updated_at = Instant::now();
+                    entry.conditions.remove(&condition_id)
+                })
+        });
+        if let Some(name) = cleared {
+            println!("✓ {}: {} behoben", scanner_id, name);
+        }
+    } else if xml.contains("ScannerStatusConditionEvent") {
+        let (Some(condition_id), Some(name)) = (extract_tag(xml, "ConditionId"), extract_tag(xml, "Name")) else {
+            return false;
+        };
+        let added = with_states(|states| {
+            states
+                .get_mut(&scanner_id)
+                .map(|entry| {
+                    entry.updated_at = Instant::now();
+                    entry.conditions.insert(condition_id, name.clone()).is_none()
+                })
+                .unwrap_or(false)
+        });
+        if added && is_fault(&name) {
+            eprintln!("⚠ {}: {}", scanner_id, name);
+            events::record(EventKind::Error, format!("Scanner {}: {}", scanner_id, name));
+        }
+    } else {
+        return false;
+    }
+    // Ein Event kommt nur vom erreichbaren Gerät
+    discovery::mark_reachable(&scanner_id, true);
+    true
+}
+
+/// Zustände, die einen Eingriff am Gerät erfordern (leerer Einzug ist normal)
+fn is_fault(condition: &str) -> bool {
+    !matches!(condition, "InputTrayEmpty" | "LampWarming" | "Calibrating")
+}
+
+fn with_states<T>(f: impl FnOnce(&mut HashMap<String, PushedState>) -> T) -> T {
+    let mut guard = STATES.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
@@ -0,0 +1,135 @@
+// Geräte-Statistik - Jobs, Seiten, Fehler und Scan-Dauer je Scanner
+// Lokal in scanner_stats.json gespeichert und an DocFlow gemeldet (welche MFPs sind ausgelastet, welche fallen ständig aus)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::settings;
+use crate::rate_limit::RateLimited;
+
+/// Zähler eines Scanners
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScannerStats {
+    pub scanner_id: String,
+    /// Erfolgreiche Scan-Jobs
+    pub jobs: u64,
+    pub pages: u64,
+    pub failures: u64,
+    /// Summe der Scan-Dauern erfolgreicher Jobs
+    pub total_duration_ms: u64,
+    #[serde(default)]
+    pub average_duration_secs: f64,
+    pub last_job_at: Option<String>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+}
+
+static STATS: Mutex<Option<HashMap<String, ScannerStats>>> = Mutex::new(None);
+
+/// Seit der letzten Meldung an DocFlow geändert
+static CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// Erfolgreichen Scan zählen
+pub fn record_job(scanner_id: &str, pages: usize, duration: Duration) {
+    update(scanner_id, |stats| {
+        stats.jobs += 1;
+        stats.pages += pages as u64;
+        stats.total_duration_ms += duration.as_millis() as u64;
+        stats.last_job_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+}
+
+/// Fehlgeschlagenen Scan zählen
+pub fn record_failure(scanner_id: &str, message: &str) {
+    update(scanner_id, |stats| {
+        stats.failures += 1;
+        stats.last_error = Some(message.to_string());
+        stats.last_error_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+}
+
+/// Aktuelle Zähler aller Scanner (nach Scanner-ID sortiert)
+pub fn snapshot() -> Vec<ScannerStats> {
+    let mut guard = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut stats: Vec<ScannerStats> = guard.get_or_insert_with(load).values().cloned().collect();
+    stats.sort_by(|a, b| a.scanner_id.cmp(&b.scanner_id));
+    stats
+}
+
+/// Alle Zähler zurücksetzen
+pub fn reset() {
+    let mut guard = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let stats = guard.insert(HashMap::new());
+    save(stats);
+    CHANGED.store(true, Ordering::Relaxed);
+}
+
+/// Ob seit der letzten Meldung etwas gezählt wurde (setzt das Flag zurück)
+pub fn take_changed() -> bool {
+    CHANGED.swap(false, Ordering::Relaxed)
+}
+
+/// Meldet die Zähler an DocFlow (bei Fehler wird beim nächsten Mal erneut gemeldet)
+pub async fn report(docflow_url: &str, api_key: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = crate::http_client::docflow();
+    let url = format!("{}/api/scanner/bridge/scanner-stats", docflow_url);
+
+    let result = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .json(&serde_json::json!({ "scanners": snapshot() }))
+        .timeout(Duration::from_secs(10))
+        .send_limited()
+        .await;
+
+    match result {
+        Ok(response) if response.status().is_success() => Ok(()),
+        Ok(response) => {
+            CHANGED.store(true, Ordering::Relaxed);
+            Err(format!("HTTP {}", response.status()).into())
+        }
+        Err(e) => {
+            CHANGED.store(true, Ordering::Relaxed);
+            Err(e)
+        }
+    }
+}
+
+fn update(scanner_id: &str, change: impl FnOnce(&mut ScannerStats)) {
+    let mut guard = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let stats = guard.get_or_insert_with(load);
+    let entry = stats.entry(scanner_id.to_string()).or_insert_with(|| ScannerStats {
+        scanner_id: scanner_id.to_string(),
+        ..Default::default()
+    });
+    change(entry);
+    if entry.jobs > 0 {
+        entry.average_duration_secs = entry.total_duration_ms as f64 / entry.jobs as f64 / 1000.0;
+    }
+    save(stats);
+    CHANGED.store(true, Ordering::Relaxed);
+}
+
+fn stats_path() -> PathBuf {
+    settings::data_dir().join("scanner_stats.json")
+}
+
+fn load() -> HashMap<String, ScannerStats> {
+    let entries: Vec<ScannerStats> = std::fs::read_to_string(stats_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    entries.into_iter().map(|s| (s.scanner_id.clone(), s)).collect()
+}
+
+fn save(stats: &HashMap<String, ScannerStats>) {
+    let entries: Vec<&ScannerStats> = stats.values().collect();
+    if let Ok(json) = serde_json::to_string_pretty(&entries) {
+        let _ = std::fs::create_dir_all(settings::data_dir());
+        let _ = std::fs::write(stats_path(), json);
+    }
+}
@@ -0,0 +1,105 @@
+// Scanner-Sync - Meldet die Scanner-Liste an DocFlow, sobald sie sich ändert
+// Änderungen (Discovery, Erreichbarkeit, Import, Bezeichnung, Endpoint, virtueller Scanner) werden gebündelt und nur bei Unterschied gesendet
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::discovery::{self, DiscoveredScanner};
+use crate::rate_limit::RateLimited;
+
+/// Ruhezeit nach der letzten Änderung, bevor gesendet wird
+pub const DEBOUNCE: Duration = Duration::from_secs(2);
+
+static CHANGED: Notify = Notify::const_new();
+/// In dieser Sitzung entfernte Scanner-IDs (DocFlow soll sie aus seiner Liste löschen)
+static REMOVED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Scanner-Liste hat sich geändert (wird gebündelt gesendet)
+pub fn mark_changed() {
+    CHANGED.notify_one();
+}
+
+/// Scanner wurde vom Benutzer entfernt (wird mit der nächsten Liste als gelöscht gemeldet)
+pub fn mark_removed(scanner_id: &str) {
+    REMOVED.lock().unwrap_or_else(|e| e.into_inner()).insert(scanner_id.to_string());
+    mark_changed();
+}
+
+/// Entfernte bzw. ausgeblendete Scanner, die nicht (wieder) in der Liste stehen
+pub fn removed_ids(scanners: &[DiscoveredScanner], hidden: &BTreeSet<String>) -> Vec<String> {
+    let removed = REMOVED.lock().unwrap_or_else(|e| e.into_inner());
+    removed
+        .union(hidden)
+        .filter(|id| !scanners.iter().any(|s| &s.id == *id))
+        .cloned()
+        .collect()
+}
+
+/// Wartet auf eine Änderung und danach, bis DEBOUNCE lang keine weitere kam
+pub async fn changed() {
+    CHANGED.notified().await;
+    while tokio::time::timeout(DEBOUNCE, CHANGED.notified()).await.is_ok() {}
+}
+
+/// Scanner-Daten im Format der DocFlow-API (removed: von DocFlow zu löschende Scanner-IDs)
+pub fn payload(scanners: &[DiscoveredScanner], removed: &[String]) -> serde_json::Value {
+    let scanner_data: Vec<serde_json::Value> = scanners
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "id": s.id,
+                "name": s.name,
+                "manufacturer": s.manufacturer,
+                "model": s.model,
+                "ip": s.ip,
+                "port": s.port,
+                "protocols": s.protocols,
+                "discovery_method": s.discovery_method,
+                "firmware": s.firmware,
+                "location": s.location,
+                "group": s.group,
+                "admin_url": s.admin_url,
+                "icon_url": s.icon_url,
+                "icon": s.icon,
+                "online": discovery::is_online(s),
+                "capabilities": {
+                    "duplex": s.capabilities.duplex,
+                    "adf": s.capabilities.adf,
+                    "flatbed": s.capabilities.flatbed,
+                    "max_resolution": s.capabilities.max_resolution,
+                    "color_modes": s.capabilities.color_modes,
+                    "formats": s.capabilities.formats
+                }
+            })
+        })
+        .collect();
+    serde_json::json!({ "scanners": scanner_data, "removed": removed })
+}
+
+/// Sendet die Scanner-Liste an DocFlow
+pub async fn send(
+    docflow_url: &str,
+    api_key: &str,
+    payload: &serde_json::Value,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = crate::http_client::docflow();
+    let url = format!("{}/api/scanner/bridge/scanners", docflow_url.trim_end_matches('/'));
+
+    let response = client
+        .post(&url)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .json(payload)
+        .send_limited()
+        .await?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("DocFlow-Fehler: {}", error_text).into());
+    }
+
+    let count = payload["scanners"].as_array().map_or(0, Vec::len);
+    println!("✓ {} Scanner an DocFlow gesendet", count);
+    Ok(())
+}
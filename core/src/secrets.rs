@@ -0,0 +1,89 @@
+// Keyring - Secrets je Benutzer bzw. Profil getrennt ablegen ("docflow-scanner-bridge.<profil>")
+// Einträge unter dem früheren gemeinsamen Dienstnamen werden beim ersten Start übernommen
+
+use std::sync::OnceLock;
+
+/// Früherer, für alle Benutzer gleicher Dienstname
+const LEGACY_SERVICE: &str = "docflow-scanner-bridge";
+
+/// Umgebungsvariable für eine eigene Instanz (z.B. zweite Bridge unter demselben Benutzer)
+pub const PROFILE_ENV: &str = "DOCFLOW_BRIDGE_PROFILE";
+
+/// Alle Einträge der Bridge (werden bei der Übernahme berücksichtigt)
+const ENTRIES: &[&str] = &["api_key", "docflow_url", "bridge_id", "folder_sync_config", "kiosk_pin"];
+
+/// Profil dieser Instanz: DOCFLOW_BRIDGE_PROFILE, sonst der angemeldete Benutzer
+pub fn profile() -> &'static str {
+    static PROFILE: OnceLock<String> = OnceLock::new();
+    PROFILE.get_or_init(|| {
+        let raw = std::env::var(PROFILE_ENV)
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .or_else(|| ["USERNAME", "USER", "LOGNAME"].iter().find_map(|name| std::env::var(name).ok()))
+            .unwrap_or_default();
+
+        let profile: String = raw
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        if profile.is_empty() {
+            "default".to_string()
+        } else {
+            profile
+        }
+    })
+}
+
+/// Dienstname im Keyring für dieses Profil
+pub fn service() -> String {
+    format!("{}.{}", LEGACY_SERVICE, profile())
+}
+
+/// Keyring-Eintrag dieses Profils
+pub fn entry(name: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(&service(), name)
+}
+
+/// Liest einen Eintrag (None = nicht vorhanden oder Keyring nicht verfügbar)
+pub fn get(name: &str) -> Option<String> {
+    entry(name).ok()?.get_password().ok()
+}
+
+/// Schreibt einen Eintrag
+pub fn set(name: &str, value: &str) -> keyring::Result<()> {
+    entry(name)?.set_password(value)
+}
+
+/// Löscht einen Eintrag
+pub fn delete(name: &str) -> keyring::Result<()> {
+    entry(name)?.delete_password()
+}
+
+/// Übernimmt Einträge vom gemeinsamen Dienstnamen in dieses Profil (einmalig beim ersten Start)
+/// Bereits vorhandene Einträge des Profils werden nicht überschrieben
+pub fn migrate_legacy() -> usize {
+    let mut migrated = 0;
+    for name in ENTRIES {
+        let Ok(legacy) = keyring::Entry::new(LEGACY_SERVICE, name) else {
+            continue;
+        };
+        let Ok(value) = legacy.get_password() else {
+            continue;
+        };
+        if get(name).is_none() {
+            if let Err(e) = set(name, &value) {
+                eprintln!("⚠ Keyring-Eintrag {} nicht übernommen: {}", name, e);
+                continue;
+            }
+            migrated += 1;
+        }
+        let _ = legacy.delete_password();
+    }
+
+    if migrated > 0 {
+        println!("🔑 {} Keyring-Einträge in Profil '{}' übernommen", migrated, profile());
+    }
+    migrated
+}
@@ -0,0 +1,230 @@
+// Selbsttest - Prüft alle Voraussetzungen der Bridge und liefert eine Checkliste für die Fehlersuche
+// Keyring, DocFlow (Erreichbarkeit + Auth + Zertifikatsablauf), Scanner, Scan-Ordner und freier Speicherplatz
+
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::discovery::DiscoveredScanner;
+use crate::secrets;
+use crate::settings;
+use crate::virtual_scanner;
+use crate::rate_limit::RateLimited;
+
+/// Unter dieser Grenze wird gewarnt bzw. der Test als fehlgeschlagen gewertet
+const DISK_WARN_BYTES: u64 = 500 * 1024 * 1024;
+const DISK_FAIL_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Ergebnis einer einzelnen Prüfung
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+    Skipped,
+}
+
+/// Eine Zeile der Checkliste
+#[derive(Clone, Debug, Serialize)]
+pub struct SelfTestCheck {
+    /// Bereich ("keyring", "docflow", "scanner", "folder", "disk")
+    pub category: String,
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+/// Gesamtergebnis des Selbsttests
+#[derive(Clone, Debug, Serialize)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub checked_at: String,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// Was geprüft werden soll
+pub struct SelfTestInput<'a> {
+    pub api_key: Option<&'a str>,
+    pub docflow_url: Option<&'a str>,
+    pub scanners: &'a [DiscoveredScanner],
+    pub watch_path: Option<&'a str>,
+}
+
+/// Führt alle Prüfungen aus
+pub async fn run(input: SelfTestInput<'_>) -> SelfTestReport {
+    let mut checks = vec![check_keyring()];
+    checks.push(check_docflow(input.api_key, input.docflow_url).await);
+    checks.push(check_certificate(input.docflow_url));
+
+    for scanner in input.scanners {
+        checks.push(check_scanner(scanner).await);
+    }
+
+    match input.watch_path {
+        Some(path) => {
+            checks.push(check_folder(Path::new(path)));
+            checks.push(check_disk("Scan-Ordner", Path::new(path)));
+        }
+        None => checks.push(check(
+            "folder",
+            "Scan-Ordner",
+            CheckStatus::Skipped,
+            "Folder-Sync nicht eingerichtet",
+        )),
+    }
+    checks.push(check_disk("App-Daten", &settings::data_dir()));
+    checks.push(check_disk("Temp-Verzeichnis", &std::env::temp_dir()));
+
+    SelfTestReport {
+        passed: checks.iter().all(|c| c.status != CheckStatus::Failed),
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        checks,
+    }
+}
+
+fn check(category: &str, name: &str, status: CheckStatus, message: impl Into<String>) -> SelfTestCheck {
+    SelfTestCheck {
+        category: category.to_string(),
+        name: name.to_string(),
+        status,
+        message: message.into(),
+    }
+}
+
+/// Schreibt, liest und löscht einen Test-Eintrag im Keyring
+fn check_keyring() -> SelfTestCheck {
+    let result = (|| -> Result<(), keyring::Error> {
+        let entry = secrets::entry("self_test")?;
+        let value = uuid::Uuid::new_v4().to_string();
+        entry.set_password(&value)?;
+        let read = entry.get_password()?;
+        let _ = entry.delete_password();
+        if read == value {
+            Ok(())
+        } else {
+            Err(keyring::Error::NoEntry)
+        }
+    })();
+
+    match result {
+        Ok(()) => check("keyring", "Keyring", CheckStatus::Ok, "Schreiben und Lesen möglich"),
+        Err(e) => check("keyring", "Keyring", CheckStatus::Failed, format!("Kein Zugriff: {}", e)),
+    }
+}
+
+/// Prüft Erreichbarkeit und API-Key über den Status-Endpoint
+async fn check_docflow(api_key: Option<&str>, docflow_url: Option<&str>) -> SelfTestCheck {
+    let (Some(api_key), Some(docflow_url)) = (api_key, docflow_url) else {
+        return check("docflow", "DocFlow", CheckStatus::Failed, "Nicht mit DocFlow verbunden (Pairing fehlt)");
+    };
+
+    let client = crate::http_client::docflow();
+    let response = client
+        .get(format!("{}/api/scanner/bridge/status", docflow_url))
+        .header("Authorization", format!("Bearer {}", api_key))
+        .timeout(Duration::from_secs(10))
+        .send_limited()
+        .await;
+
+    match response {
+        Ok(r) if r.status().is_success() => {
+            check("docflow", "DocFlow", CheckStatus::Ok, format!("{} erreichbar, API-Key gültig", docflow_url))
+        }
+        Ok(r) if r.status().as_u16() == 401 || r.status().as_u16() == 403 => check(
+            "docflow",
+            "DocFlow",
+            CheckStatus::Failed,
+            "Erreichbar, aber API-Key abgelehnt - Bridge neu verbinden",
+        ),
+        Ok(r) => check("docflow", "DocFlow", CheckStatus::Failed, format!("Unerwartete Antwort: HTTP {}", r.status())),
+        Err(e) => check("docflow", "DocFlow", CheckStatus::Failed, format!("{} nicht erreichbar: {}", docflow_url, e)),
+    }
+}
+
+/// Ablaufdatum des DocFlow-Serverzertifikats (bei der DocFlow-Prüfung mitgelesen)
+fn check_certificate(docflow_url: Option<&str>) -> SelfTestCheck {
+    const NAME: &str = "TLS-Zertifikat";
+    let Some(url) = docflow_url.and_then(|u| reqwest::Url::parse(u).ok()) else {
+        return check("docflow", NAME, CheckStatus::Skipped, "Nicht mit DocFlow verbunden");
+    };
+    if url.scheme() != "https" {
+        return check("docflow", NAME, CheckStatus::Skipped, "Verbindung ohne TLS (http)");
+    }
+
+    let server = format!("{}:{}", url.host_str().unwrap_or_default(), url.port_or_known_default().unwrap_or_default());
+    match crate::cert_expiry::status().into_iter().find(|s| s.server == server) {
+        Some(expiry) if expiry.expiring => check("docflow", NAME, CheckStatus::Warning, expiry.message()),
+        Some(expiry) => check(
+            "docflow",
+            NAME,
+            CheckStatus::Ok,
+            format!("Gültig bis {} (noch {} Tage)", expiry.not_after_display, expiry.days_left),
+        ),
+        None => check("docflow", NAME, CheckStatus::Skipped, "Zertifikat nicht gelesen (Server nicht erreichbar?)"),
+    }
+}
+
+/// TCP-Verbindung zum eSCL-Port des Scanners
+async fn check_scanner(scanner: &DiscoveredScanner) -> SelfTestCheck {
+    if virtual_scanner::is_virtual(scanner) {
+        return check("scanner", &scanner.name, CheckStatus::Ok, "Virtueller Scanner (keine Hardware)");
+    }
+
+    let addr = if scanner.ip.contains(':') {
+        format!("[{}]:{}", scanner.ip, scanner.port)
+    } else {
+        format!("{}:{}", scanner.ip, scanner.port)
+    };
+
+    match timeout(Duration::from_secs(3), TcpStream::connect(&addr)).await {
+        Ok(Ok(_)) => check("scanner", &scanner.name, CheckStatus::Ok, format!("{} erreichbar", addr)),
+        Ok(Err(e)) => check("scanner", &scanner.name, CheckStatus::Failed, format!("{} nicht erreichbar: {}", addr, e)),
+        Err(_) => check(
+            "scanner",
+            &scanner.name,
+            CheckStatus::Warning,
+            format!("{} antwortet nicht (Energiesparmodus?)", addr),
+        ),
+    }
+}
+
+/// Ordner lesbar und beschreibbar (Post-Upload-Aktion verschiebt/löscht Dateien)
+fn check_folder(path: &Path) -> SelfTestCheck {
+    if let Err(e) = std::fs::read_dir(path) {
+        return check("folder", "Scan-Ordner", CheckStatus::Failed, format!("{} nicht lesbar: {}", path.display(), e));
+    }
+
+    let probe = path.join(format!(".docflow-self-test-{}", uuid::Uuid::new_v4()));
+    match std::fs::write(&probe, b"test") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            check("folder", "Scan-Ordner", CheckStatus::Ok, format!("{} lesbar und beschreibbar", path.display()))
+        }
+        Err(e) => check(
+            "folder",
+            "Scan-Ordner",
+            CheckStatus::Failed,
+            format!("{} nicht beschreibbar: {}", path.display(), e),
+        ),
+    }
+}
+
+fn check_disk(name: &str, path: &Path) -> SelfTestCheck {
+    let name = format!("Speicherplatz ({})", name);
+    match fs4::available_space(path) {
+        Ok(free) => {
+            let status = if free < DISK_FAIL_BYTES {
+                CheckStatus::Failed
+            } else if free < DISK_WARN_BYTES {
+                CheckStatus::Warning
+            } else {
+                CheckStatus::Ok
+            };
+            check("disk", &name, status, format!("{} MB frei", free / 1024 / 1024))
+        }
+        Err(e) => check("disk", &name, CheckStatus::Warning, format!("Nicht ermittelbar: {}", e)),
+    }
+}
@@ -0,0 +1,160 @@
+// Server-Discovery - Findet DocFlow-Instanzen im LAN für den Pairing-Dialog
+// Zuerst mDNS (_docflow._tcp, vom Server angekündigt), sonst Port-Probe im lokalen /24-Netz und auf localhost
+
+use futures::stream::{self, StreamExt};
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::discovery::get_subnet;
+
+/// mDNS-Service-Typ des DocFlow-Servers
+const SERVICE_TYPE: &str = "_docflow._tcp.local.";
+
+/// Typische Ports von DocFlow-Installationen (Docker-Standard zuerst)
+const PROBE_PORTS: &[u16] = &[4000, 443, 80, 8080, 3000];
+
+/// Gleichzeitige Verbindungsversuche beim Port-Probe
+const PROBE_CONCURRENCY: usize = 64;
+
+/// Gefundener DocFlow-Server
+#[derive(Clone, Debug, Serialize)]
+pub struct DetectedServer {
+    /// Basis-URL für das Pairing (z.B. "http://192.168.1.10:4000")
+    pub url: String,
+    pub name: String,
+    /// "mdns" oder "probe"
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Sucht DocFlow-Server (mDNS-Funde haben Vorrang, Port-Probe nur ohne mDNS-Treffer)
+pub async fn discover(browse: Duration) -> Vec<DetectedServer> {
+    let mut servers = match discover_mdns(browse).await {
+        Ok(servers) => servers,
+        Err(e) => {
+            eprintln!("⚠ DocFlow-Suche per mDNS fehlgeschlagen: {}", e);
+            Vec::new()
+        }
+    };
+
+    if servers.is_empty() {
+        servers = probe_lan().await;
+    }
+
+    println!("🔎 {} DocFlow-Server im LAN gefunden", servers.len());
+    servers
+}
+
+async fn discover_mdns(browse: Duration) -> Result<Vec<DetectedServer>, Box<dyn std::error::Error + Send + Sync>> {
+    let mdns = ServiceDaemon::new()?;
+    let receiver = mdns.browse(SERVICE_TYPE)?;
+    let mut servers: BTreeMap<String, DetectedServer> = BTreeMap::new();
+
+    let collect = async {
+        while let Ok(event) = receiver.recv_async().await {
+            let ServiceEvent::ServiceResolved(info) = event else {
+                continue;
+            };
+            let addresses = info.get_addresses();
+            let Some(ip) = addresses.iter().find(|a| a.is_ipv4()).or_else(|| addresses.iter().next()) else {
+                continue;
+            };
+
+            let tls = info.get_property_val_str("tls") == Some("true") || info.get_port() == 443;
+            let path = info.get_property_val_str("path").unwrap_or("").trim_end_matches('/');
+            let url = format!("{}://{}{}", if tls { "https" } else { "http" }, host_port(ip, info.get_port(), tls), path);
+            let name = info
+                .get_property_val_str("name")
+                .map(str::to_string)
+                .unwrap_or_else(|| info.get_fullname().trim_end_matches(SERVICE_TYPE).trim_end_matches('.').to_string());
+
+            servers.insert(
+                url.clone(),
+                DetectedServer {
+                    url,
+                    name,
+                    source: "mdns".to_string(),
+                    version: info.get_property_val_str("version").map(str::to_string),
+                },
+            );
+        }
+    };
+    let _ = tokio::time::timeout(browse, collect).await;
+
+    mdns.shutdown()?;
+    Ok(servers.into_values().collect())
+}
+
+/// Probt localhost und das lokale /24-Netz auf typischen Ports
+async fn probe_lan() -> Vec<DetectedServer> {
+    let mut hosts = vec!["127.0.0.1".to_string()];
+    if let Ok(local_ip @ IpAddr::V4(_)) = local_ip_address::local_ip() {
+        let subnet = get_subnet(&local_ip);
+        hosts.extend((1..=254).map(|i| format!("{}.{}", subnet, i)));
+    }
+
+    let candidates: Vec<(String, u16)> = hosts
+        .into_iter()
+        .flat_map(|host| PROBE_PORTS.iter().map(move |port| (host.clone(), *port)))
+        .collect();
+
+    stream::iter(candidates)
+        .map(|(host, port)| async move { probe(&host, port).await })
+        .buffer_unordered(PROBE_CONCURRENCY)
+        .filter_map(|server| async move { server })
+        .collect()
+        .await
+}
+
+/// DocFlow erkennt man am Bridge-Status-Endpunkt: ohne API-Key 401 statt 404
+/// Geräte, die jede Anfrage gleich beantworten (z.B. Router mit Login), fallen über einen Gegentest heraus
+async fn probe(host: &str, port: u16) -> Option<DetectedServer> {
+    let tls = port == 443;
+    let ip: IpAddr = host.parse().ok()?;
+    let url = format!("{}://{}", if tls { "https" } else { "http" }, host_port(&ip, port, tls));
+
+    let client = crate::http_client::scanner(Duration::from_secs(2)).ok()?;
+    let response = client
+        .get(format!("{}/api/scanner/bridge/status", url))
+        .send()
+        .await
+        .ok()?;
+
+    if !matches!(response.status().as_u16(), 200 | 401 | 403) {
+        return None;
+    }
+
+    let unknown = client
+        .get(format!("{}/{}", url, uuid::Uuid::new_v4().simple()))
+        .send()
+        .await
+        .ok()?;
+    if unknown.status() == response.status() {
+        return None;
+    }
+
+    let name = if ip.is_loopback() { "DocFlow (lokal)".to_string() } else { format!("DocFlow auf {}", host) };
+    Some(DetectedServer {
+        url,
+        name,
+        source: "probe".to_string(),
+        version: None,
+    })
+}
+
+/// "host:port" bzw. nur "host" bei Standard-Port (IPv6 in eckigen Klammern)
+fn host_port(ip: &IpAddr, port: u16, tls: bool) -> String {
+    let host = match ip {
+        IpAddr::V6(v6) => format!("[{}]", v6),
+        IpAddr::V4(v4) => v4.to_string(),
+    };
+    if (tls && port == 443) || (!tls && port == 80) {
+        host
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
@@ -0,0 +1,335 @@
+// Einstellungen - Persistente Bridge-Einstellungen (JSON-Datei im App-Datenverzeichnis)
+// Secrets (API-Key, URL) bleiben im Keyring - Windows begrenzt Credential-Blobs auf 2,5 KB
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::announce::AnnounceConfig;
+use crate::autocolor::AutoColorConfig;
+use crate::autocrop::AutoCropConfig;
+use crate::barcode::BarcodeConfig;
+use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::dedup::DedupConfig;
+use crate::digest::DigestConfig;
+use crate::discovery::DiscoveryConfig;
+use crate::enhance::EnhanceConfig;
+use crate::escl_recording::RecordingConfig;
+use crate::events::EventLogConfig;
+use crate::file_grouping::GroupingConfig;
+use crate::folder_watcher::IgnoreConfig;
+use crate::grpc::GrpcConfig;
+use crate::hooks::HookConfig;
+use crate::http_client::HttpConfig;
+use crate::job_validation::ValidationConfig;
+use crate::metrics::MetricsConfig;
+use crate::migrations;
+use crate::orientation::OrientationConfig;
+use crate::pipeline::RenditionConfig;
+use crate::push_scan::PushScanConfig;
+use crate::rate_limit::RateLimitConfig;
+use crate::resources::ResourceConfig;
+use crate::scanner::TransferConfig;
+use crate::splitter::SplitConfig;
+use crate::spool::SpoolConfig;
+use crate::telemetry::TelemetryConfig;
+use crate::tunnel::TunnelConfig;
+use crate::wol::WolConfig;
+
+/// Persistente Bridge-Einstellungen
+/// Neue Bereiche immer mit #[serde(default)] ergänzen, damit alte Dateien lesbar bleiben
+/// Umbenennungen und Formatänderungen brauchen einen Migrationsschritt in `migrations::SETTINGS`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BridgeSettings {
+    #[serde(default)]
+    pub barcode: BarcodeConfig,
+    #[serde(default)]
+    pub split: SplitConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub hooks: HookConfig,
+    #[serde(default)]
+    pub push_scan: PushScanConfig,
+    #[serde(default)]
+    pub enhance: EnhanceConfig,
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+    #[serde(default)]
+    pub wol: WolConfig,
+    #[serde(default)]
+    pub transfer: TransferConfig,
+    #[serde(default)]
+    pub renditions: RenditionConfig,
+    #[serde(default)]
+    pub auto_color: AutoColorConfig,
+    #[serde(default)]
+    pub auto_crop: AutoCropConfig,
+    #[serde(default)]
+    pub validation: ValidationConfig,
+    #[serde(default)]
+    pub folder_ignore: IgnoreConfig,
+    #[serde(default)]
+    pub folder_grouping: GroupingConfig,
+    #[serde(default)]
+    pub digest: DigestConfig,
+    #[serde(default)]
+    pub window: WindowConfig,
+    #[serde(default)]
+    pub intervals: IntervalConfig,
+    #[serde(default)]
+    pub events: EventLogConfig,
+    #[serde(default)]
+    pub resources: ResourceConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub recording: RecordingConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub announce: AnnounceConfig,
+    #[serde(default)]
+    pub dedup: DedupConfig,
+    #[serde(default)]
+    pub tunnel: TunnelConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub spool: SpoolConfig,
+    #[serde(default)]
+    pub orientation: OrientationConfig,
+}
+
+/// Polling-Intervalle (wirken ohne Neustart ab dem nächsten Zyklus)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IntervalConfig {
+    /// Abfrage neuer Scan-Jobs bei DocFlow
+    #[serde(default = "default_job_poll")]
+    pub job_poll_secs: u64,
+    /// Langsamere Abfrage im Leerlauf (kein Job seit idle_after_secs, außerhalb der Bürozeiten)
+    #[serde(default = "default_job_poll_idle")]
+    pub job_poll_idle_secs: u64,
+    /// Ohne neuen Job gilt die Bridge nach dieser Zeit als im Leerlauf
+    #[serde(default = "default_idle_after")]
+    pub idle_after_secs: u64,
+    /// Bürozeiten Mo-Fr als "HH:MM-HH:MM" (lokal), leer = Leerlauf-Intervall gilt rund um die Uhr
+    #[serde(default = "default_office_hours")]
+    pub office_hours: String,
+    /// Prüfung des überwachten Ordners
+    #[serde(default = "default_folder_poll")]
+    pub folder_poll_secs: u64,
+    /// Statusmeldungen an DocFlow (Folder-Sync, Scanner-Zustand) - nur bei Änderungen
+    #[serde(default = "default_status_report")]
+    pub status_report_secs: u64,
+    /// Heartbeat an DocFlow, wenn sich länger nichts geändert hat
+    #[serde(default = "default_status_heartbeat")]
+    pub status_heartbeat_secs: u64,
+    /// Suchdauer je mDNS-Service-Typ bei der Discovery
+    #[serde(default = "default_mdns_browse")]
+    pub mdns_browse_secs: u64,
+}
+
+fn default_job_poll() -> u64 {
+    2
+}
+
+fn default_job_poll_idle() -> u64 {
+    30
+}
+
+fn default_idle_after() -> u64 {
+    600
+}
+
+fn default_office_hours() -> String {
+    "07:00-19:00".to_string()
+}
+
+fn default_folder_poll() -> u64 {
+    5
+}
+
+fn default_status_report() -> u64 {
+    30
+}
+
+fn default_status_heartbeat() -> u64 {
+    300
+}
+
+fn default_mdns_browse() -> u64 {
+    5
+}
+
+impl Default for IntervalConfig {
+    fn default() -> Self {
+        Self {
+            job_poll_secs: default_job_poll(),
+            job_poll_idle_secs: default_job_poll_idle(),
+            idle_after_secs: default_idle_after(),
+            office_hours: default_office_hours(),
+            folder_poll_secs: default_folder_poll(),
+            status_report_secs: default_status_report(),
+            status_heartbeat_secs: default_status_heartbeat(),
+            mdns_browse_secs: default_mdns_browse(),
+        }
+    }
+}
+
+impl IntervalConfig {
+    /// Prüft die Intervalle auf sinnvolle Grenzen
+    pub fn validate(&self) -> Result<(), String> {
+        let checks = [
+            ("Job-Abfrage", self.job_poll_secs, 1, 300),
+            ("Job-Abfrage im Leerlauf", self.job_poll_idle_secs, self.job_poll_secs, 600),
+            ("Leerlauf ab", self.idle_after_secs, 60, 86400),
+            ("Ordner-Prüfung", self.folder_poll_secs, 1, 600),
+            ("Statusmeldung", self.status_report_secs, 5, 3600),
+            ("Heartbeat", self.status_heartbeat_secs, 30, 3600),
+            ("mDNS-Suche", self.mdns_browse_secs, 1, 60),
+        ];
+        for (name, value, min, max) in checks {
+            if !(min..=max).contains(&value) {
+                return Err(format!("{}: {}s liegt außerhalb von {}-{}s", name, value, min, max));
+            }
+        }
+        if !self.office_hours.trim().is_empty() && parse_office_hours(&self.office_hours).is_none() {
+            return Err(format!("Bürozeiten \"{}\" ungültig (Format HH:MM-HH:MM)", self.office_hours));
+        }
+        Ok(())
+    }
+
+    /// Wartezeit bis zur nächsten Job-Abfrage: schnell nach Aktivität und in Bürozeiten, sonst Leerlauf-Intervall
+    pub fn job_poll_delay(&self, idle_for: Duration, now: chrono::NaiveDateTime) -> Duration {
+        let idle = idle_for >= Duration::from_secs(self.idle_after_secs);
+        if idle && !self.is_office_hours(now) {
+            Duration::from_secs(self.job_poll_idle_secs.max(self.job_poll_secs))
+        } else {
+            Duration::from_secs(self.job_poll_secs)
+        }
+    }
+
+    fn is_office_hours(&self, now: chrono::NaiveDateTime) -> bool {
+        use chrono::Datelike;
+        let Some((start, end)) = parse_office_hours(&self.office_hours) else {
+            return false;
+        };
+        let weekday = now.weekday().number_from_monday() <= 5;
+        weekday && (start..end).contains(&now.time())
+    }
+}
+
+/// "HH:MM-HH:MM" → (Beginn, Ende)
+fn parse_office_hours(value: &str) -> Option<(chrono::NaiveTime, chrono::NaiveTime)> {
+    let (start, end) = value.trim().split_once('-')?;
+    let start = chrono::NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = chrono::NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+    (start < end).then_some((start, end))
+}
+
+/// Verhalten des Hauptfensters
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowConfig {
+    /// Fenster beim Start nicht anzeigen (nur Tray) - Autostart übergibt zusätzlich --minimized
+    #[serde(default)]
+    pub start_minimized: bool,
+    /// Was beim Schließen des Fensters passiert
+    #[serde(default)]
+    pub close_action: CloseAction,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            start_minimized: false,
+            close_action: CloseAction::MinimizeToTray,
+        }
+    }
+}
+
+/// Aktion beim Schließen des Fensters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum CloseAction {
+    /// In den Tray minimieren, Bridge läuft weiter
+    #[default]
+    MinimizeToTray,
+    /// Anwendung beenden
+    Exit,
+}
+
+impl BridgeSettings {
+    /// Lädt die Einstellungen (Defaults falls nicht vorhanden)
+    /// Ältere Stände werden migriert, vorher wird eine Kopie der Datei angelegt
+    pub fn load() -> Self {
+        let path = settings_path();
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        match migrations::load::<Self>(&json, &migrations::SETTINGS) {
+            Ok(loaded) => {
+                if loaded.changed(&migrations::SETTINGS) || loaded.newer(&migrations::SETTINGS) {
+                    backup(&path, &format!("v{}", loaded.from_version));
+                }
+                if loaded.changed(&migrations::SETTINGS) {
+                    if let Err(e) = loaded.value.save() {
+                        eprintln!("⚠ Migrierte Einstellungen nicht gespeichert: {}", e);
+                    }
+                }
+                loaded.value
+            }
+            Err(e) => {
+                eprintln!("⚠ {} - verwende Standardwerte", e);
+                backup(&path, &format!("unlesbar-{}", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+                Self::default()
+            }
+        }
+    }
+
+    /// Speichert die Einstellungen (mit aktueller Schema-Version)
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        std::fs::create_dir_all(data_dir())?;
+        let json = migrations::store(self, &migrations::SETTINGS)?;
+        std::fs::write(settings_path(), serde_json::to_string_pretty(&json)?)?;
+        Ok(())
+    }
+}
+
+/// Kopie neben der Originaldatei (z.B. settings.json.v0.bak), bestehende Kopien bleiben erhalten
+fn backup(path: &Path, suffix: &str) {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.bak", suffix));
+    let target = path.with_file_name(name);
+    if target.exists() {
+        return;
+    }
+    match std::fs::copy(path, &target) {
+        Ok(_) => println!("💾 Sicherung der alten Einstellungen: {}", target.display()),
+        Err(e) => eprintln!("⚠ Sicherung von {} fehlgeschlagen: {}", path.display(), e),
+    }
+}
+
+/// App-Datenverzeichnis (plattformabhängig)
+/// Windows: %APPDATA%, macOS: ~/Library/Application Support, Linux: $XDG_CONFIG_HOME bzw. ~/.config
+pub fn data_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|h| PathBuf::from(h).join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+    };
+
+    base.unwrap_or_else(std::env::temp_dir).join("docflow-scanner-bridge")
+}
+
+fn settings_path() -> PathBuf {
+    data_dir().join("settings.json")
+}
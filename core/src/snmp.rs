@@ -0,0 +1,265 @@
+// SNMP - Minimaler SNMP-Client (GET, v1/v2c) ohne externe Crate
+// Für Firmware-Abfragen und den SNMP-Sweep der Discovery (sysDescr, Host-Resources- und Printer-MIB)
+// Antworten zählen nur mit passender Request-ID; Geräte ohne SNMPv2c werden per SNMPv1 nachgefragt
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// Standard-Port für SNMP-Anfragen
+pub const PORT: u16 = 161;
+
+/// SNMPv1 (Fehler bei einer OID verwirft die ganze Antwort)
+pub const VERSION_1: u32 = 0;
+/// SNMPv2c (fehlende OIDs kommen einzeln als noSuchObject/noSuchInstance zurück)
+pub const VERSION_2C: u32 = 1;
+
+/// SNMPv2-MIB sysDescr.0 (bei den meisten Druckern/MFPs Hersteller, Modell und Firmware)
+pub const SYS_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+/// HOST-RESOURCES-MIB hrDeviceDescr.1 (bei Druckern das Modell, z.B. "HP LaserJet MFP M428fdw")
+pub const HR_DEVICE_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1, 3, 1];
+/// Printer-MIB prtGeneralSerialNumber.1
+pub const PRT_SERIAL_NUMBER: &[u32] = &[1, 3, 6, 1, 2, 1, 43, 5, 1, 1, 17, 1];
+
+/// Per SNMP gefundenes Gerät
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnmpDevice {
+    pub ip: String,
+    /// sysDescr
+    pub description: Option<String>,
+    /// hrDeviceDescr (nur Geräte mit Host-Resources-MIB)
+    pub model: Option<String>,
+    /// prtGeneralSerialNumber (nur Geräte mit Printer-MIB)
+    pub serial_number: Option<String>,
+}
+
+impl SnmpDevice {
+    /// Baut das Gerät aus den Werten einer Antwort auf [`device_request`] (SNMPv1: nur sysDescr)
+    pub fn from_values(ip: &str, values: &[Option<String>]) -> Self {
+        let value = |index: usize| values.get(index).cloned().flatten();
+        Self {
+            ip: ip.to_string(),
+            description: value(0),
+            model: value(1),
+            serial_number: value(2),
+        }
+    }
+
+    /// Modellbezeichnung: hrDeviceDescr, sonst der erste Abschnitt von sysDescr
+    /// "Brother NC-8300h, Firmware Ver.1.04" → "Brother NC-8300h"
+    pub fn model_name(&self) -> Option<String> {
+        self.model.clone().or_else(|| {
+            self.description
+                .as_deref()
+                .and_then(|descr| descr.split([';', ',']).next())
+                .map(|model| model.trim().to_string())
+                .filter(|model| !model.is_empty())
+        })
+    }
+}
+
+/// GET-Anfrage nach Beschreibung, Modell und Seriennummer
+/// SNMPv1 fragt nur sysDescr ab - dort würde eine fehlende OID die ganze Antwort verwerfen
+pub fn device_request(version: u32, community: &str, request_id: u32) -> Vec<u8> {
+    let oids: &[&[u32]] = if version == VERSION_1 {
+        &[SYS_DESCR]
+    } else {
+        &[SYS_DESCR, HR_DEVICE_DESCR, PRT_SERIAL_NUMBER]
+    };
+    encode_get_request(version, community, oids, request_id)
+}
+
+/// Fragt alle Ziele parallel über einen Socket ab und sammelt die Antworten bis `wait` abgelaufen ist
+/// Ziele ohne Antwort auf SNMPv2c werden danach noch einmal per SNMPv1 gefragt
+pub async fn sweep(
+    targets: &[SocketAddr],
+    community: &str,
+    wait: Duration,
+) -> Result<Vec<SnmpDevice>, Box<dyn std::error::Error + Send + Sync>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    let mut devices: HashMap<IpAddr, SnmpDevice> = HashMap::new();
+    let mut buffer = vec![0u8; 65535];
+
+    for version in [VERSION_2C, VERSION_1] {
+        let pending: Vec<SocketAddr> = targets
+            .iter()
+            .filter(|target| !devices.contains_key(&target.ip()))
+            .copied()
+            .collect();
+        if pending.is_empty() {
+            break;
+        }
+
+        // Neue Request-ID je Runde: verspätete Antworten der vorigen Runde werden verworfen
+        let request_id = rand_request_id();
+        let request = device_request(version, community, request_id);
+        for target in &pending {
+            // Einzelne nicht erreichbare Ziele (z.B. Broadcast-Adresse) nicht als Fehler werten
+            let _ = socket.send_to(&request, target).await;
+        }
+
+        let deadline = tokio::time::Instant::now() + wait;
+        while let Ok(Ok((len, from))) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await {
+            if !pending.contains(&from) {
+                continue;
+            }
+            if let Some(values) = decode_get_response(&buffer[..len], request_id) {
+                let ip = from.ip().to_string();
+                devices.entry(from.ip()).or_insert_with(|| SnmpDevice::from_values(&ip, &values));
+            }
+        }
+    }
+
+    let mut devices: Vec<SnmpDevice> = devices.into_values().collect();
+    devices.sort_by(|a, b| a.ip.cmp(&b.ip));
+    Ok(devices)
+}
+
+/// SNMP v1 GET für einen String-Wert
+pub async fn get_string(ip: &str, community: &str, oid: &[u32]) -> Option<String> {
+    let bind_addr = if ip.contains(':') { "[::]:0" } else { "0.0.0.0:0" };
+    let socket = UdpSocket::bind(bind_addr).await.ok()?;
+    let target = if ip.contains(':') { format!("[{}]:{}", ip, PORT) } else { format!("{}:{}", ip, PORT) };
+
+    let request_id = rand_request_id();
+    let request = encode_get_request(VERSION_1, community, &[oid], request_id);
+    socket.send_to(&request, &target).await.ok()?;
+
+    // Fremde bzw. verspätete Antworten überspringen, bis die passende kommt
+    let mut buffer = [0u8; 2048];
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+    loop {
+        let (len, _) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await.ok()?.ok()?;
+        if let Some(values) = decode_get_response(&buffer[..len], request_id) {
+            return values.into_iter().next().flatten();
+        }
+    }
+}
+
+fn rand_request_id() -> u32 {
+    uuid::Uuid::new_v4().as_u128() as u32 & 0x7fff_ffff
+}
+
+/// BER: Tag + Länge + Inhalt
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    if content.len() < 0x80 {
+        out.push(content.len() as u8);
+    } else {
+        let len_bytes: Vec<u8> = (content.len() as u32)
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | len_bytes.len() as u8);
+        out.extend(len_bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+fn encode_integer(value: u32) -> Vec<u8> {
+    let mut bytes: Vec<u8> = value.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+    if bytes.is_empty() || bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+    tlv(0x02, &bytes)
+}
+
+fn encode_oid(oid: &[u32]) -> Vec<u8> {
+    let mut content = vec![(oid[0] * 40 + oid[1]) as u8];
+    for &sub in &oid[2..] {
+        let mut chunk = vec![(sub & 0x7f) as u8];
+        let mut rest = sub >> 7;
+        while rest > 0 {
+            chunk.insert(0, (rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        content.extend(chunk);
+    }
+    tlv(0x06, &content)
+}
+
+/// GetRequest-PDU mit einem Varbind (Wert NULL) je OID
+pub fn encode_get_request(version: u32, community: &str, oids: &[&[u32]], request_id: u32) -> Vec<u8> {
+    let varbinds: Vec<u8> = oids
+        .iter()
+        .flat_map(|oid| tlv(0x30, &[encode_oid(oid), vec![0x05, 0x00]].concat()))
+        .collect();
+    let varbind_list = tlv(0x30, &varbinds);
+    let pdu = tlv(
+        0xa0,
+        &[encode_integer(request_id), encode_integer(0), encode_integer(0), varbind_list].concat(),
+    );
+    tlv(
+        0x30,
+        &[encode_integer(version), tlv(0x04, community.as_bytes()), pdu].concat(),
+    )
+}
+
+/// Liest ein TLV ab Position pos → (Tag, Inhalt, Position nach dem TLV)
+fn read_tlv(data: &[u8], pos: usize) -> Option<(u8, &[u8], usize)> {
+    let tag = *data.get(pos)?;
+    let first_len = *data.get(pos + 1)? as usize;
+    let (len, header) = if first_len & 0x80 == 0 {
+        (first_len, 2)
+    } else {
+        let count = first_len & 0x7f;
+        let len = data
+            .get(pos + 2..pos + 2 + count)?
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, 2 + count)
+    };
+    let start = pos + header;
+    let content = data.get(start..start + len)?;
+    Some((tag, content, start + len))
+}
+
+/// PDU-Typ und Inhalt einer SNMP-Nachricht
+fn read_pdu(data: &[u8]) -> Option<(u8, &[u8])> {
+    let (_, message, _) = read_tlv(data, 0)?;
+    let (_, _, pos) = read_tlv(message, 0)?; // Version
+    let (_, _, pos) = read_tlv(message, pos)?; // Community
+    let (pdu_tag, pdu, _) = read_tlv(message, pos)?;
+    Some((pdu_tag, pdu))
+}
+
+/// Request-ID einer Anfrage oder Antwort
+pub fn request_id(data: &[u8]) -> Option<u32> {
+    let (_, pdu) = read_pdu(data)?;
+    let (tag, id, _) = read_tlv(pdu, 0)?;
+    (tag == 0x02 && id.len() <= 5).then(|| id.iter().fold(0u32, |acc, b| (acc << 8) | *b as u32))
+}
+
+/// Liest die GetResponse auf die Anfrage `request_id` → ein Wert je angefragter OID (None = kein Text bzw. nicht vorhanden)
+pub fn decode_get_response(data: &[u8], request_id: u32) -> Option<Vec<Option<String>>> {
+    let (pdu_tag, pdu) = read_pdu(data)?;
+    if pdu_tag != 0xa2 || self::request_id(data)? != request_id {
+        return None;
+    }
+
+    let (_, _, pos) = read_tlv(pdu, 0)?; // Request-ID
+    let (_, error_status, pos) = read_tlv(pdu, pos)?;
+    if error_status.iter().any(|b| *b != 0) {
+        return None;
+    }
+    let (_, _, pos) = read_tlv(pdu, pos)?; // Error-Index
+    let (_, varbinds, _) = read_tlv(pdu, pos)?;
+
+    let mut values = Vec::new();
+    let mut next = 0;
+    while next < varbinds.len() {
+        let (_, varbind, after) = read_tlv(varbinds, next)?;
+        let (_, _, pos) = read_tlv(varbind, 0)?; // OID
+        let (value_tag, value, _) = read_tlv(varbind, pos)?;
+        // Nur OCTET STRING auswerten, noSuchObject/noSuchInstance (0x80/0x81) ergeben None
+        let text = (value_tag == 0x04)
+            .then(|| String::from_utf8_lossy(value).trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string())
+            .filter(|text| !text.is_empty());
+        values.push(text);
+        next = after;
+    }
+    Some(values)
+}
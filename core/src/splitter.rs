@@ -0,0 +1,140 @@
+// Dokument-Trennung - Teilt große ADF-Stapel an Trennblättern in Einzeldokumente
+// Trennblätter: leere Seiten und/oder Seiten mit Trenn-Barcode (Patch-Code-Blatt)
+
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::barcode::{self, BarcodeConfig};
+
+/// Wonach getrennt wird
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum SplitMode {
+    BlankPage,  // Leere Seiten
+    Barcode,    // Seiten mit Trenn-Barcode
+    Both,       // Beides
+}
+
+/// Konfiguration der Dokument-Trennung
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SplitConfig {
+    pub enabled: bool,
+    pub mode: SplitMode,
+    /// Max. Anteil dunkler Pixel (Prozent), bis zu dem eine Seite als leer gilt
+    pub blank_threshold_percent: f32,
+    /// Barcode-Werte, die ein Trennblatt markieren (leer = jeder Barcode)
+    pub separator_values: Vec<String>,
+    /// Trennblätter verwerfen statt sie als erste Seite des Folgedokuments zu behalten
+    pub drop_separators: bool,
+}
+
+impl Default for SplitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: SplitMode::Both,
+            blank_threshold_percent: 0.5,
+            separator_values: Vec::new(),
+            drop_separators: true,
+        }
+    }
+}
+
+/// Prüft ob eine Seite (nahezu) leer ist
+/// Der Rand (5%) wird ignoriert, da Scanner dort oft Schatten/Kanten abbilden
+pub fn is_blank_page(image: &DynamicImage, threshold_percent: f32) -> bool {
+    // Für die Analyse reicht eine verkleinerte Version
+    let gray = image.thumbnail(400, 400).to_luma8();
+    let (width, height) = gray.dimensions();
+    let margin_x = width / 20;
+    let margin_y = height / 20;
+
+    let mut total = 0u64;
+    let mut dark = 0u64;
+    for y in margin_y..height.saturating_sub(margin_y) {
+        for x in margin_x..width.saturating_sub(margin_x) {
+            total += 1;
+            if gray.get_pixel(x, y).0[0] < 128 {
+                dark += 1;
+            }
+        }
+    }
+
+    if total == 0 {
+        return true;
+    }
+
+    (dark as f32 / total as f32) * 100.0 <= threshold_percent
+}
+
+/// Prüft ob ein Blatt ein Trennblatt ist
+/// Leer nur, wenn alle Seiten des Blatts leer sind (bei Duplex also Vorder- und Rückseite); Trenn-Barcode auf einer Seite genügt
+fn is_separator(sheet: &[DynamicImage], first_page: usize, config: &SplitConfig, barcode_config: &BarcodeConfig) -> bool {
+    let check_blank = matches!(config.mode, SplitMode::BlankPage | SplitMode::Both);
+    let check_barcode = matches!(config.mode, SplitMode::Barcode | SplitMode::Both);
+
+    if sheet.is_empty() {
+        return false;
+    }
+
+    if check_blank && sheet.iter().all(|image| is_blank_page(image, config.blank_threshold_percent)) {
+        return true;
+    }
+
+    check_barcode
+        && sheet.iter().enumerate().any(|(offset, image)| {
+            barcode::detect_in_image(image, first_page + offset, barcode_config)
+                .iter()
+                .any(|b| config.separator_values.is_empty() || config.separator_values.iter().any(|v| v == &b.value))
+        })
+}
+
+/// Teilt JPEG-Seiten an Trennblättern in Dokumente auf (leere Dokumente entfallen)
+/// duplex: Seiten kommen paarweise (Vorder- und Rückseite eines Blatts)
+pub fn split_pages(
+    pages: Vec<Vec<u8>>,
+    duplex: bool,
+    config: &SplitConfig,
+    barcode_config: &BarcodeConfig,
+) -> Vec<Vec<Vec<u8>>> {
+    split_pages_by(pages, duplex, |page| image::load_from_memory(page).ok(), config, barcode_config)
+}
+
+/// Wie `split_pages`, die Seiten werden aber erst zur Prüfung geladen (z.B. aus dem Spool)
+pub fn split_pages_by<T>(
+    pages: Vec<T>,
+    duplex: bool,
+    load: impl Fn(&T) -> Option<DynamicImage>,
+    config: &SplitConfig,
+    barcode_config: &BarcodeConfig,
+) -> Vec<Vec<T>> {
+    let sheet_size = if duplex { 2 } else { 1 };
+    let mut documents: Vec<Vec<T>> = Vec::new();
+    let mut current: Vec<T> = Vec::new();
+    let mut pages = pages.into_iter().peekable();
+    let mut page_number = 1;
+
+    while pages.peek().is_some() {
+        let sheet: Vec<T> = pages.by_ref().take(sheet_size).collect();
+        // Nicht lesbare Seiten machen kein Trennblatt
+        let images: Option<Vec<DynamicImage>> = sheet.iter().map(&load).collect();
+        let separator = images.is_some_and(|images| is_separator(&images, page_number, config, barcode_config));
+        page_number += sheet.len();
+
+        if separator {
+            if !current.is_empty() {
+                documents.push(std::mem::take(&mut current));
+            }
+            if !config.drop_separators {
+                current.extend(sheet);
+            }
+        } else {
+            current.extend(sheet);
+        }
+    }
+
+    if !current.is_empty() {
+        documents.push(current);
+    }
+
+    documents
+}
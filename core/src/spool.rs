@@ -0,0 +1,397 @@
+// Verschlüsselter Spool - Scan-Daten, die bis zum Upload auf Disk liegen, nur verschlüsselt ablegen
+// Der Schlüssel entsteht beim Start und existiert nur im Arbeitsspeicher; Dateien werden nach Gebrauch überschrieben und gelöscht
+// Dateien bestehen aus einzeln verschlüsselten Blöcken, damit sie sich ohne Komplettkopie im Speicher schreiben und lesen lassen
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::settings;
+
+/// Länge des zufälligen Nonce-Präfixes am Anfang jeder Spool-Datei
+const PREFIX_LEN: usize = 7;
+/// Klartext je verschlüsseltem Block
+const BLOCK_SIZE: usize = 64 * 1024;
+/// Authentifizierungs-Tag je Block
+const TAG_LEN: usize = 16;
+
+/// Auslagerung großer Scans
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpoolConfig {
+    /// Wartende Dokumente ab dieser Größe bis zum Upload verschlüsselt auf Disk statt im Speicher (0 = nie)
+    /// Gilt auch für Scan-Stapel: Seiten darüber hinaus gehen direkt beim Eintreffen in den Spool
+    pub spill_over_kb: u64,
+}
+
+impl Default for SpoolConfig {
+    fn default() -> Self {
+        Self { spill_over_kb: 16 * 1024 }
+    }
+}
+
+/// Prozessweiter Schlüssel - nach einem Neustart sind alte Spool-Dateien nicht mehr lesbar
+static CIPHER: OnceLock<ChaCha20Poly1305> = OnceLock::new();
+
+fn cipher() -> &'static ChaCha20Poly1305 {
+    CIPHER.get_or_init(|| ChaCha20Poly1305::new(&ChaCha20Poly1305::generate_key(&mut OsRng)))
+}
+
+/// Nonce je Block: Präfix + Blocknummer + Kennung für den letzten Block (erkennt vertauschte und abgeschnittene Blöcke)
+fn block_nonce(prefix: &[u8; PREFIX_LEN], counter: u32, last: bool) -> Nonce {
+    let mut nonce = [0u8; 12];
+    nonce[..PREFIX_LEN].copy_from_slice(prefix);
+    nonce[PREFIX_LEN..PREFIX_LEN + 4].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    *Nonce::from_slice(&nonce)
+}
+
+/// Spool-Verzeichnis (im App-Datenverzeichnis, nicht im gemeinsamen Temp-Ordner)
+pub fn spool_dir() -> PathBuf {
+    settings::data_dir().join("spool")
+}
+
+/// Verschlüsselte Datei im Spool (wird beim Drop sicher gelöscht)
+#[derive(Debug)]
+pub struct SpoolFile {
+    path: PathBuf,
+    len: usize,
+}
+
+impl SpoolFile {
+    pub fn write(data: &[u8]) -> io::Result<Self> {
+        let mut writer = SpoolWriter::create()?;
+        writer.write_all(data)?;
+        writer.finish()
+    }
+
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(self.len);
+        self.reader()?.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Liest die Datei blockweise (nie mehr als ein Block entschlüsselt im Speicher)
+    pub fn reader(&self) -> io::Result<SpoolReader> {
+        let mut file = BufReader::new(File::open(&self.path)?);
+        let mut prefix = [0u8; PREFIX_LEN];
+        file.read_exact(&mut prefix)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Spool-Datei unvollständig"))?;
+        let next_len = read_block_len(&mut file)?;
+        Ok(SpoolReader {
+            file,
+            prefix,
+            counter: 0,
+            next_len,
+            block: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Größe der unverschlüsselten Daten
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Drop for SpoolFile {
+    fn drop(&mut self) {
+        if let Err(e) = secure_delete(&self.path) {
+            eprintln!("⚠ Spool-Datei {} nicht gelöscht: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Schreibt eine Spool-Datei blockweise - unvollendet (ohne [`SpoolWriter::finish`]) wird sie beim Drop gelöscht
+pub struct SpoolWriter {
+    file: SpoolFile,
+    out: BufWriter<File>,
+    prefix: [u8; PREFIX_LEN],
+    counter: u32,
+    block: Vec<u8>,
+}
+
+impl SpoolWriter {
+    pub fn create() -> io::Result<Self> {
+        let path = spool_dir().join(format!("{}.spool", uuid::Uuid::new_v4()));
+        let out = create_private(&path)?;
+        let file = SpoolFile { path, len: 0 };
+        let mut prefix = [0u8; PREFIX_LEN];
+        OsRng.fill_bytes(&mut prefix);
+        let mut out = BufWriter::new(out);
+        out.write_all(&prefix)?;
+        Ok(Self {
+            file,
+            out,
+            prefix,
+            counter: 0,
+            block: Vec::with_capacity(BLOCK_SIZE),
+        })
+    }
+
+    /// Bisher geschriebene Klartext-Bytes
+    pub fn len(&self) -> usize {
+        self.file.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.file.len == 0
+    }
+
+    /// Schreibt den letzten Block und gibt die fertige Datei zurück
+    pub fn finish(mut self) -> io::Result<SpoolFile> {
+        self.seal(true)?;
+        self.out.flush()?;
+        let Self { file, .. } = self;
+        Ok(file)
+    }
+
+    fn seal(&mut self, last: bool) -> io::Result<()> {
+        let nonce = block_nonce(&self.prefix, self.counter, last);
+        let ciphertext = cipher()
+            .encrypt(&nonce, self.block.as_slice())
+            .map_err(|_| io::Error::other("Verschlüsselung fehlgeschlagen"))?;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("Spool-Datei zu groß"))?;
+        self.out.write_all(&(ciphertext.len() as u32).to_le_bytes())?;
+        self.out.write_all(&ciphertext)?;
+        self.block.clear();
+        Ok(())
+    }
+}
+
+impl Write for SpoolWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let count = buf.len().min(BLOCK_SIZE - self.block.len());
+        self.block.extend_from_slice(&buf[..count]);
+        self.file.len += count;
+        if self.block.len() == BLOCK_SIZE {
+            self.seal(false)?;
+        }
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Angefangene Blöcke erst mit dem nächsten vollen Block bzw. in finish() schreiben
+        self.out.flush()
+    }
+}
+
+/// Liest eine Spool-Datei blockweise und prüft jeden Block
+pub struct SpoolReader {
+    file: BufReader<File>,
+    prefix: [u8; PREFIX_LEN],
+    counter: u32,
+    /// Länge des nächsten Blocks (None = Dateiende)
+    next_len: Option<usize>,
+    block: Vec<u8>,
+    pos: usize,
+}
+
+impl Read for SpoolReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.block.len() {
+            let Some(len) = self.next_len.take() else {
+                return Ok(0);
+            };
+            let mut ciphertext = vec![0u8; len];
+            self.file.read_exact(&mut ciphertext).map_err(|_| corrupt())?;
+            self.next_len = read_block_len(&mut self.file)?;
+            let nonce = block_nonce(&self.prefix, self.counter, self.next_len.is_none());
+            self.block = cipher().decrypt(&nonce, ciphertext.as_slice()).map_err(|_| corrupt())?;
+            self.counter = self.counter.wrapping_add(1);
+            self.pos = 0;
+        }
+        let count = buf.len().min(self.block.len() - self.pos);
+        buf[..count].copy_from_slice(&self.block[self.pos..self.pos + count]);
+        self.pos += count;
+        Ok(count)
+    }
+}
+
+/// Längenfeld vor jedem Block (None = Dateiende)
+fn read_block_len(file: &mut impl Read) -> io::Result<Option<usize>> {
+    let mut len = [0u8; 4];
+    match file.read(&mut len[..1])? {
+        0 => return Ok(None),
+        _ => file.read_exact(&mut len[1..]).map_err(|_| corrupt())?,
+    }
+    let len = u32::from_le_bytes(len) as usize;
+    if !(TAG_LEN..=BLOCK_SIZE + TAG_LEN).contains(&len) {
+        return Err(corrupt());
+    }
+    Ok(Some(len))
+}
+
+fn corrupt() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "Spool-Datei beschädigt")
+}
+
+/// Scan-Daten bis zum Upload - kleine Dokumente im Speicher, große verschlüsselt im Spool
+#[derive(Debug)]
+pub enum Buffer {
+    Memory(Vec<u8>),
+    Spooled(SpoolFile),
+}
+
+impl Buffer {
+    /// Lagert ab `spill_over_kb` aus; ist der Spool nicht beschreibbar, bleiben die Daten im Speicher
+    pub fn new(data: Vec<u8>, config: &SpoolConfig) -> Self {
+        if config.spill_over_kb == 0 || (data.len() as u64) < config.spill_over_kb * 1024 {
+            return Self::Memory(data);
+        }
+        Self::spill(data)
+    }
+
+    /// Lagert unabhängig von der Größe aus (z.B. Seiten eines großen Einzug-Stapels)
+    pub fn spill(data: Vec<u8>) -> Self {
+        match SpoolFile::write(&data) {
+            Ok(file) => Self::Spooled(file),
+            Err(e) => {
+                eprintln!("⚠ Spool nicht beschreibbar, Dokument bleibt im Speicher: {}", e);
+                Self::Memory(data)
+            }
+        }
+    }
+
+    /// Liest die Daten, ohne den Puffer aufzugeben
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Memory(data) => Ok(data.clone()),
+            Self::Spooled(file) => file.read(),
+        }
+    }
+
+    /// Liest die Daten blockweise (ausgelagerte Daten werden nie komplett geladen)
+    pub fn reader(&self) -> io::Result<Box<dyn Read + Send + '_>> {
+        Ok(match self {
+            Self::Memory(data) => Box::new(data.as_slice()),
+            Self::Spooled(file) => Box::new(file.reader()?),
+        })
+    }
+
+    /// Blöcke der Daten als eigenständiger Iterator (z.B. für Upload-Streams, die den Puffer nicht ausleihen dürfen)
+    /// Ein Lesefehler beendet den Iterator nach der Fehlermeldung
+    pub fn chunks(&self, size: usize) -> io::Result<impl Iterator<Item = io::Result<Vec<u8>>> + Send + 'static> {
+        let mut reader: Option<Box<dyn Read + Send>> = Some(match self {
+            Self::Memory(data) => Box::new(io::Cursor::new(data.clone())),
+            Self::Spooled(file) => Box::new(file.reader()?),
+        });
+        Ok(std::iter::from_fn(move || {
+            let mut chunk = vec![0u8; size];
+            match reader.as_mut()?.read(&mut chunk) {
+                Ok(0) => None,
+                Ok(read) => {
+                    chunk.truncate(read);
+                    Some(Ok(chunk))
+                }
+                Err(e) => {
+                    reader = None;
+                    Some(Err(e))
+                }
+            }
+        }))
+    }
+
+    /// Kopie, die unabhängig vom Original gelöscht wird (ausgelagerte Daten bleiben dabei im Spool)
+    pub fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Memory(data) => Ok(Self::Memory(data.clone())),
+            Self::Spooled(file) => {
+                let mut writer = SpoolWriter::create()?;
+                io::copy(&mut file.reader()?, &mut writer)?;
+                Ok(Self::Spooled(writer.finish()?))
+            }
+        }
+    }
+
+    /// Größe der unverschlüsselten Daten
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Memory(data) => data.len(),
+            Self::Spooled(file) => file.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_spooled(&self) -> bool {
+        matches!(self, Self::Spooled(_))
+    }
+
+    /// Liefert die Daten zurück (Spool-Datei wird danach gelöscht)
+    pub fn into_data(self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Memory(data) => Ok(data),
+            Self::Spooled(file) => file.read(),
+        }
+    }
+}
+
+/// Schreibt eine Klartext-Datei nur für den aktuellen Benutzer lesbar (z.B. für den Post-Upload-Hook)
+pub fn write_private(path: &Path, mut data: impl Read) -> io::Result<()> {
+    io::copy(&mut data, &mut create_private(path)?)?;
+    Ok(())
+}
+
+/// Überschreibt eine Datei mit Nullen und löscht sie
+/// Auf SSDs und Copy-on-Write-Dateisystemen ohne Garantie - dort schützt die Verschlüsselung
+pub fn secure_delete(path: &Path) -> io::Result<()> {
+    if let Ok(mut file) = OpenOptions::new().write(true).open(path) {
+        let mut remaining = file.metadata()?.len();
+        let zeros = vec![0u8; 64 * 1024];
+        while remaining > 0 {
+            let chunk = remaining.min(zeros.len() as u64) as usize;
+            file.write_all(&zeros[..chunk])?;
+            remaining -= chunk as u64;
+        }
+        file.sync_all()?;
+    }
+    std::fs::remove_file(path)
+}
+
+/// Entfernt Spool-Dateien früherer Starts (ohne deren Schlüssel ohnehin nicht mehr lesbar)
+pub fn cleanup() -> usize {
+    let Ok(entries) = std::fs::read_dir(spool_dir()) else {
+        return 0;
+    };
+    let removed = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| secure_delete(&entry.path()).is_ok())
+        .count();
+    if removed > 0 {
+        println!("🧹 {} Spool-Datei(en) eines früheren Starts gelöscht", removed);
+    }
+    removed
+}
+
+fn create_private(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
@@ -0,0 +1,69 @@
+// Status-Meldungen - Sendet nur Änderungen gegenüber der letzten bestätigten Meldung an DocFlow
+// Ohne Änderungen genügt ein gemeinsamer Heartbeat für Poller und Folder-Sync, nach Fehlern wird wieder vollständig gemeldet
+
+use serde_json::{Map, Value};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Letzte erfolgreiche Statusmeldung (egal von welchem Dienst) - ersetzt separate Heartbeats
+static LAST_REPORT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Merkt sich den zuletzt bestätigten Stand eines Statusberichts
+#[derive(Default)]
+pub struct DeltaReport {
+    last_sent: Option<Map<String, Value>>,
+}
+
+impl DeltaReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Body der nächsten Meldung: vollständig, nur geänderte Felder oder Heartbeat (None = nichts zu senden)
+    pub fn next(&self, current: &Map<String, Value>, heartbeat: Duration) -> Option<Value> {
+        let Some(last_sent) = &self.last_sent else {
+            let mut body = current.clone();
+            body.insert("report_type".to_string(), "full".into());
+            return Some(Value::Object(body));
+        };
+
+        let mut delta: Map<String, Value> = current
+            .iter()
+            .filter(|(key, value)| last_sent.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        // Entfallene Einträge explizit löschen
+        for key in last_sent.keys().filter(|key| !current.contains_key(*key)) {
+            delta.insert(key.clone(), Value::Null);
+        }
+
+        if !delta.is_empty() {
+            delta.insert("report_type".to_string(), "delta".into());
+            return Some(Value::Object(delta));
+        }
+
+        heartbeat_due(heartbeat).then(|| serde_json::json!({ "report_type": "heartbeat" }))
+    }
+
+    /// Nach erfolgreichem Senden: Stand als bestätigt merken
+    pub fn confirm(&mut self, current: Map<String, Value>) {
+        self.last_sent = Some(current);
+        if let Ok(mut last) = LAST_REPORT.lock() {
+            *last = Some(Instant::now());
+        }
+    }
+
+    /// Nach Fehlern: nächste Meldung wieder vollständig senden
+    pub fn reset(&mut self) {
+        self.last_sent = None;
+    }
+}
+
+/// Ob seit der letzten Meldung eines beliebigen Dienstes das Heartbeat-Intervall abgelaufen ist
+fn heartbeat_due(heartbeat: Duration) -> bool {
+    LAST_REPORT
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .is_none_or(|last| last.elapsed() >= heartbeat)
+}
@@ -0,0 +1,153 @@
+// Supervisor - Überwacht Poller und Folder-Watcher über Heartbeats
+// Beendete (Panic) oder hängende Tasks werden mit Backoff neu gestartet, dauerhaft fehlschlagende als Fehler gemeldet
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Scan-Job-Poller
+pub const POLLER: &str = "poller";
+/// Folder-Watcher
+pub const FOLDER_WATCHER: &str = "folder_watcher";
+
+/// Zeit bis zum ersten Heartbeat nach dem Start
+const START_GRACE: Duration = Duration::from_secs(120);
+/// Zuschlag auf die angekündigte Zeit bis zum nächsten Heartbeat
+const BEAT_GRACE: Duration = Duration::from_secs(60);
+/// Mindestabstand nach dem 1., 2., 3., ... Neustart in Folge (danach bleibt es beim letzten Wert)
+const RESTART_BACKOFF_SECS: &[u64] = &[30, 120, 600];
+/// Ab so vielen Neustarts in Folge gilt die Bridge als gestört
+const MAX_RESTARTS: u32 = 3;
+/// So lange muss ein Task nach einem Neustart laufen, bis er wieder als gesund gilt
+const HEALTHY_AFTER: Duration = Duration::from_secs(600);
+
+struct Task {
+    handle: Option<JoinHandle<()>>,
+    /// Spätester Zeitpunkt für den nächsten Heartbeat
+    deadline: Instant,
+    restarts: u32,
+    /// Neustarts ohne zwischenzeitlich gesunden Lauf
+    consecutive_restarts: u32,
+    last_restart: Option<Instant>,
+    next_restart: Instant,
+    last_error: Option<String>,
+}
+
+static TASKS: Mutex<BTreeMap<&'static str, Task>> = Mutex::new(BTreeMap::new());
+
+/// Zustand eines überwachten Tasks
+#[derive(Clone, Debug, Serialize)]
+pub struct TaskHealth {
+    pub name: String,
+    pub healthy: bool,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+/// Übernimmt einen (neu) gestarteten Task in die Überwachung (Neustart-Zähler bleiben erhalten)
+pub fn watch(task: &'static str, handle: JoinHandle<()>) {
+    let mut tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let entry = tasks.entry(task).or_insert_with(|| Task {
+        handle: None,
+        deadline: now,
+        restarts: 0,
+        consecutive_restarts: 0,
+        last_restart: None,
+        next_restart: now,
+        last_error: None,
+    });
+    entry.handle = Some(handle);
+    entry.deadline = now + START_GRACE;
+}
+
+/// Beendet die Überwachung (gewollter Stopp, z.B. Verbindung getrennt)
+pub fn unwatch(task: &'static str) {
+    TASKS.lock().unwrap_or_else(|e| e.into_inner()).remove(task);
+}
+
+/// Heartbeat: Task lebt, nächster Heartbeat spätestens nach `within`
+pub fn beat(task: &'static str, within: Duration) {
+    let mut tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(entry) = tasks.get_mut(task) else {
+        return;
+    };
+    entry.deadline = Instant::now() + within + BEAT_GRACE;
+    if entry.consecutive_restarts > 0 && entry.last_restart.is_some_and(|t| t.elapsed() >= HEALTHY_AFTER) {
+        println!("✓ {} läuft wieder stabil", task);
+        entry.consecutive_restarts = 0;
+        entry.last_error = None;
+    }
+}
+
+/// Tasks, die beendet sind oder keinen Heartbeat mehr senden und jetzt neu gestartet werden sollen
+/// Der alte Task wird abgebrochen, der Aufrufer startet ihn neu und meldet ihn per watch() an
+pub fn due_for_restart() -> Vec<&'static str> {
+    let mut tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let mut due = Vec::new();
+
+    for (name, task) in tasks.iter_mut() {
+        let finished = task.handle.as_ref().is_none_or(|h| h.is_finished());
+        let stalled = now > task.deadline;
+        if !(finished || stalled) || now < task.next_restart {
+            continue;
+        }
+
+        let reason = if finished { "Task beendet" } else { "kein Heartbeat (hängt)" };
+        eprintln!("⚠ {}: {} - starte neu", name, reason);
+        if let Some(handle) = task.handle.take() {
+            handle.abort();
+        }
+        task.restarts += 1;
+        task.consecutive_restarts += 1;
+        task.last_restart = Some(now);
+        task.last_error = Some(reason.to_string());
+        let index = (task.consecutive_restarts as usize - 1).min(RESTART_BACKOFF_SECS.len() - 1);
+        task.next_restart = now + Duration::from_secs(RESTART_BACKOFF_SECS[index]);
+        // Bis zum Neustart durch den Aufrufer nicht erneut melden
+        task.deadline = task.next_restart.max(now + START_GRACE);
+        due.push(*name);
+    }
+    due
+}
+
+/// Fehlermeldung, wenn Neustarts wiederholt nicht helfen
+pub fn failing() -> Option<String> {
+    let tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    tasks
+        .iter()
+        .find(|(_, task)| task.consecutive_restarts >= MAX_RESTARTS)
+        .map(|(name, task)| {
+            format!(
+                "{} startet wiederholt neu ({}x): {}",
+                label(name),
+                task.consecutive_restarts,
+                task.last_error.as_deref().unwrap_or("unbekannt")
+            )
+        })
+}
+
+/// Zustand aller überwachten Tasks
+pub fn status() -> Vec<TaskHealth> {
+    let tasks = TASKS.lock().unwrap_or_else(|e| e.into_inner());
+    tasks
+        .iter()
+        .map(|(name, task)| TaskHealth {
+            name: name.to_string(),
+            healthy: task.consecutive_restarts == 0,
+            restarts: task.restarts,
+            last_error: task.last_error.clone(),
+        })
+        .collect()
+}
+
+fn label(task: &str) -> &str {
+    match task {
+        POLLER => "Scan-Poller",
+        FOLDER_WATCHER => "Folder-Sync",
+        other => other,
+    }
+}
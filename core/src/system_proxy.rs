@@ -0,0 +1,488 @@
+// System-Proxy - Erkennt die Proxy-Einstellungen des Betriebssystems (inkl. PAC) für den DocFlow-Client
+// Windows: Internet-Optionen (Registry), macOS: scutil, Linux: Umgebungsvariablen bzw. GNOME-Einstellungen
+// PAC-Skripte mit Bedingungen wertet unter Windows WinHTTP für die DocFlow-URL aus, sonst wird direkt verbunden
+
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::http_client::{HttpConfig, ProxyMode};
+
+/// Herkunft des verwendeten Proxys
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxySource {
+    /// Fest in den Einstellungen eingetragen
+    Manual,
+    /// Proxy aus den Systemeinstellungen
+    System,
+    /// Aus einer PAC-Datei (automatisches Konfigurationsskript)
+    Pac,
+    /// Kein Proxy
+    Direct,
+}
+
+/// Ermittelter Proxy für DocFlow-Anfragen
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DetectedProxy {
+    pub source: ProxySource,
+    /// z.B. "http://proxy.firma.local:3128" (None = direkte Verbindung)
+    pub proxy_url: Option<String>,
+    /// Ausnahmen im NO_PROXY-Format (kommagetrennt)
+    pub no_proxy: Option<String>,
+    /// Ausnahme "<local>": Hostnamen ohne Punkt (z.B. "docflow") direkt ansprechen
+    #[serde(default)]
+    pub bypass_local: bool,
+    pub pac_url: Option<String>,
+}
+
+/// Rohwerte aus den Systemeinstellungen
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SystemSettings {
+    proxy: Option<String>,
+    exceptions: Vec<String>,
+    pac_url: Option<String>,
+}
+
+/// Ergebnis eines PAC-Skripts ohne JavaScript-Auswertung
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PacDecision {
+    /// Immer derselbe Proxy
+    Proxy(String),
+    /// Immer direkt
+    Direct,
+    /// Ergebnis hängt von URL/Host ab (if, switch, ?:) - muss ausgewertet werden
+    Conditional,
+}
+
+struct Detection {
+    /// Systemeinstellungen, DocFlow-URL und lokale IP der letzten Erkennung (Änderung = Netzwechsel)
+    fingerprint: (SystemSettings, Option<String>, Option<IpAddr>),
+    proxy: DetectedProxy,
+}
+
+static DETECTED: Mutex<Option<Detection>> = Mutex::new(None);
+
+/// Zuletzt erkannter System-Proxy (None = noch keine Erkennung gelaufen)
+pub fn current() -> Option<DetectedProxy> {
+    DETECTED.lock().ok()?.as_ref().map(|d| d.proxy.clone())
+}
+
+/// Proxy, den der DocFlow-Client nach den Einstellungen verwenden soll (None = reqwest-Standard bis zur ersten Erkennung)
+pub fn effective(config: &HttpConfig) -> Option<DetectedProxy> {
+    if config.proxy_mode == ProxyMode::Direct {
+        return Some(direct(None));
+    }
+    if let Some(proxy_url) = config.proxy_url.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        return Some(DetectedProxy {
+            source: ProxySource::Manual,
+            proxy_url: Some(proxy_url.to_string()),
+            no_proxy: None,
+            bypass_local: false,
+            pac_url: None,
+        });
+    }
+    current()
+}
+
+/// Erkennt den System-Proxy neu, wenn sich Einstellungen oder Netz geändert haben (`force` = immer)
+/// `target_url` ist die DocFlow-URL, für die ein PAC-Skript mit Bedingungen ausgewertet wird
+/// Bei Änderung wird der DocFlow-Client neu aufgebaut, Rückgabe: hat sich etwas geändert?
+pub async fn refresh(target_url: Option<&str>, force: bool) -> bool {
+    let settings = tokio::task::spawn_blocking(read_system).await.unwrap_or_default();
+    let fingerprint = (settings, target_url.map(str::to_string), local_ip_address::local_ip().ok());
+
+    let previous = DETECTED.lock().ok().and_then(|d| d.as_ref().map(|d| (d.fingerprint.clone(), d.proxy.clone())));
+    if !force && previous.as_ref().is_some_and(|(f, _)| *f == fingerprint) {
+        return false;
+    }
+
+    let settings = &fingerprint.0;
+    let proxy = match (&settings.pac_url, &settings.proxy) {
+        (Some(pac_url), _) => match fetch_pac(pac_url).await {
+            Ok(script) => match parse_pac(&script) {
+                PacDecision::Proxy(proxy_url) => pac(pac_url, proxy_url, &settings.exceptions),
+                PacDecision::Direct => direct(Some(pac_url.clone())),
+                PacDecision::Conditional => match evaluate_pac(pac_url, target_url).await {
+                    Some(Some(proxy_url)) => pac(pac_url, proxy_url, &settings.exceptions),
+                    Some(None) => direct(Some(pac_url.clone())),
+                    None => {
+                        eprintln!(
+                            "⚠️ PAC-Datei {} enthält Bedingungen und kann nicht ausgewertet werden - DocFlow wird direkt angesprochen (Proxy ggf. manuell eintragen)",
+                            pac_url
+                        );
+                        direct(Some(pac_url.clone()))
+                    }
+                },
+            },
+            Err(e) => {
+                eprintln!("⚠️ PAC-Datei {} nicht ladbar: {}", pac_url, e);
+                // Bisherigen Proxy behalten, beim nächsten Durchlauf erneut versuchen
+                if previous.is_some() {
+                    return false;
+                }
+                match &settings.proxy {
+                    Some(proxy_url) => system(proxy_url, &settings.exceptions),
+                    None => direct(Some(pac_url.clone())),
+                }
+            }
+        },
+        (None, Some(proxy_url)) => system(proxy_url, &settings.exceptions),
+        (None, None) => direct(None),
+    };
+
+    let changed = previous.as_ref().map(|(_, p)| p) != Some(&proxy);
+    if changed {
+        match &proxy.proxy_url {
+            Some(url) => println!("🌐 System-Proxy erkannt ({:?}): {}", proxy.source, url),
+            None => println!("🌐 Kein System-Proxy, DocFlow wird direkt angesprochen"),
+        }
+    }
+    if let Ok(mut detected) = DETECTED.lock() {
+        *detected = Some(Detection { fingerprint, proxy });
+    }
+    if changed {
+        crate::http_client::reload();
+    }
+    changed
+}
+
+/// Ermittelt den Proxy aus einem PAC-Skript, ohne es auszuführen
+/// Nur Skripte ohne Bedingungen liefern direkt ein Ergebnis (erster PROXY-/HTTPS-Eintrag bzw. DIRECT)
+pub fn parse_pac(script: &str) -> PacDecision {
+    let code = strip_comments(script);
+    let conditional = code.contains('?')
+        || code
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .any(|word| matches!(word, "if" | "else" | "switch" | "case"));
+    if conditional {
+        return PacDecision::Conditional;
+    }
+
+    let tokens: Vec<&str> = code
+        .split(|c: char| c == '"' || c == '\'' || c == ';' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tokens
+        .windows(2)
+        .find_map(|pair| match pair[0] {
+            "PROXY" | "HTTP" if pair[1].contains(':') => Some(format!("http://{}", pair[1])),
+            "HTTPS" if pair[1].contains(':') => Some(format!("https://{}", pair[1])),
+            _ => None,
+        })
+        .map_or(PacDecision::Direct, PacDecision::Proxy)
+}
+
+/// Entfernt Kommentare, damit "if" oder "?" darin nicht als Bedingung zählen
+fn strip_comments(script: &str) -> String {
+    let mut code = String::with_capacity(script.len());
+    let mut rest = script;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("//") {
+            rest = after.find('\n').map_or("", |end| &after[end..]);
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.find("*/").map_or("", |end| &after[end + 2..]);
+        } else {
+            let mut chars = rest.chars();
+            code.extend(chars.next());
+            rest = chars.as_str();
+        }
+    }
+    code
+}
+
+/// Wandelt einen Windows-Proxy-Eintrag in eine URL um ("proxy:8080" oder "http=a:80;https=b:443")
+pub fn parse_proxy_server(value: &str) -> Option<String> {
+    let value = value.trim();
+    let server = if value.contains('=') {
+        let entries: Vec<(&str, &str)> = value
+            .split(';')
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(scheme, server)| (scheme.trim(), server.trim()))
+            .collect();
+        ["https", "http"]
+            .iter()
+            .find_map(|wanted| entries.iter().find(|(scheme, _)| scheme.eq_ignore_ascii_case(wanted)))
+            .map(|(_, server)| *server)?
+    } else {
+        value
+    };
+    if server.is_empty() {
+        None
+    } else if server.contains("://") {
+        Some(server.to_string())
+    } else {
+        Some(format!("http://{}", server))
+    }
+}
+
+fn system(proxy_url: &str, exceptions: &[String]) -> DetectedProxy {
+    DetectedProxy {
+        source: ProxySource::System,
+        proxy_url: Some(proxy_url.to_string()),
+        no_proxy: no_proxy(exceptions),
+        bypass_local: bypass_local(exceptions),
+        pac_url: None,
+    }
+}
+
+fn pac(pac_url: &str, proxy_url: String, exceptions: &[String]) -> DetectedProxy {
+    DetectedProxy {
+        source: ProxySource::Pac,
+        proxy_url: Some(proxy_url),
+        no_proxy: no_proxy(exceptions),
+        bypass_local: bypass_local(exceptions),
+        pac_url: Some(pac_url.to_string()),
+    }
+}
+
+fn direct(pac_url: Option<String>) -> DetectedProxy {
+    DetectedProxy {
+        source: ProxySource::Direct,
+        proxy_url: None,
+        no_proxy: None,
+        bypass_local: false,
+        pac_url,
+    }
+}
+
+/// Ausnahmen ins NO_PROXY-Format ("*.firma.local" → ".firma.local", "<local>" siehe [`bypass_local`])
+fn no_proxy(exceptions: &[String]) -> Option<String> {
+    let entries: Vec<String> = exceptions
+        .iter()
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty() && *e != "<local>")
+        .map(|e| e.strip_prefix('*').filter(|rest| rest.starts_with('.')).unwrap_or(e).to_string())
+        .collect();
+    (!entries.is_empty()).then(|| entries.join(","))
+}
+
+/// "<local>" in den Windows-Ausnahmen: Hostnamen ohne Punkt am Proxy vorbei
+fn bypass_local(exceptions: &[String]) -> bool {
+    exceptions.iter().any(|e| e.trim().eq_ignore_ascii_case("<local>"))
+}
+
+/// Ob ein Host unter "<local>" fällt (Name ohne Punkt, keine IP-Adresse)
+pub fn is_plain_host_name(host: &str) -> bool {
+    !host.is_empty() && !host.contains('.') && !host.contains(':')
+}
+
+/// Ob ein Host laut Ausnahmen am Proxy vorbei verbunden wird (für Verbindungen außerhalb von reqwest)
+pub fn bypasses(proxy: &DetectedProxy, host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase();
+    if proxy.bypass_local && is_plain_host_name(&host) {
+        return true;
+    }
+    proxy
+        .no_proxy
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(|entry| entry.trim().to_ascii_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| match entry.strip_prefix('.') {
+            Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+            None => entry == "*" || host == entry,
+        })
+}
+
+/// Wertet ein PAC-Skript mit Bedingungen für die DocFlow-URL aus
+/// Some(Some(url)) = Proxy, Some(None) = direkt, None = keine Auswertung möglich
+async fn evaluate_pac(pac_url: &str, target_url: Option<&str>) -> Option<Option<String>> {
+    let (pac_url, target_url) = (pac_url.to_string(), target_url?.to_string());
+    tokio::task::spawn_blocking(move || evaluate_pac_blocking(&pac_url, &target_url))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// WinHTTP lädt und führt das Skript aus (wie Edge/Internet-Optionen)
+#[cfg(target_os = "windows")]
+fn evaluate_pac_blocking(pac_url: &str, target_url: &str) -> Option<Option<String>> {
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::{GlobalFree, HGLOBAL};
+    use windows::Win32::Networking::WinHttp::*;
+
+    let agent = HSTRING::from(crate::http_client::generic_user_agent());
+    let pac_url = HSTRING::from(pac_url);
+    let target_url = HSTRING::from(target_url);
+    unsafe {
+        let session = WinHttpOpen(&agent, WINHTTP_ACCESS_TYPE_NO_PROXY, PCWSTR::null(), PCWSTR::null(), 0);
+        if session.is_null() {
+            return None;
+        }
+        let mut options = WINHTTP_AUTOPROXY_OPTIONS {
+            dwFlags: WINHTTP_AUTOPROXY_CONFIG_URL,
+            lpszAutoConfigUrl: PCWSTR(pac_url.as_ptr()),
+            fAutoLogonIfChallenged: true.into(),
+            ..Default::default()
+        };
+        let mut info = WINHTTP_PROXY_INFO::default();
+        let result = WinHttpGetProxyForUrl(session, &target_url, &mut options, &mut info);
+        let _ = WinHttpCloseHandle(session);
+        if let Err(e) = result {
+            eprintln!("⚠️ WinHTTP konnte die PAC-Datei nicht auswerten: {}", e);
+            return None;
+        }
+
+        // "proxy:3128" oder "proxy1:3128;proxy2:8080" - der erste Eintrag zählt
+        let proxy = (!info.lpszProxy.is_null())
+            .then(|| info.lpszProxy.to_string().ok())
+            .flatten()
+            .filter(|_| info.dwAccessType == WINHTTP_ACCESS_TYPE_NAMED_PROXY)
+            .and_then(|value| {
+                let value = if value.contains('=') { value } else { value.split([';', ' ']).next().unwrap_or_default().to_string() };
+                parse_proxy_server(&value)
+            });
+        for text in [info.lpszProxy, info.lpszProxyBypass] {
+            if !text.is_null() {
+                let _ = GlobalFree(HGLOBAL(text.0 as _));
+            }
+        }
+        Some(proxy)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn evaluate_pac_blocking(_pac_url: &str, _target_url: &str) -> Option<Option<String>> {
+    None
+}
+
+/// PAC-Datei immer direkt laden (sie entscheidet ja erst über den Proxy)
+async fn fetch_pac(url: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let response = reqwest::Client::builder()
+        .no_proxy()
+        .timeout(Duration::from_secs(10))
+        .build()?
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.text().await?)
+}
+
+#[cfg(target_os = "windows")]
+fn read_system() -> SystemSettings {
+    use std::os::windows::process::CommandExt;
+
+    // "    ProxyServer    REG_SZ    proxy.firma.local:3128"
+    let output = std::process::Command::new("reg")
+        .args(["query", r"HKCU\Software\Microsoft\Windows\CurrentVersion\Internet Settings"])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output();
+    let Ok(output) = output else {
+        return SystemSettings::default();
+    };
+
+    let mut enabled = false;
+    let mut settings = SystemSettings::default();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(_kind)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let value = fields.collect::<Vec<_>>().join(" ");
+        match name {
+            "ProxyEnable" => enabled = value == "0x1",
+            "ProxyServer" => settings.proxy = parse_proxy_server(&value),
+            "ProxyOverride" => settings.exceptions = value.split(';').map(str::to_string).collect(),
+            "AutoConfigURL" if !value.is_empty() => settings.pac_url = Some(value),
+            _ => {}
+        }
+    }
+    if !enabled {
+        settings.proxy = None;
+    }
+    settings
+}
+
+#[cfg(target_os = "macos")]
+fn read_system() -> SystemSettings {
+    // "  HTTPSProxy : proxy.firma.local", Ausnahmen als "    0 : *.local" im ExceptionsList-Block
+    let Ok(output) = std::process::Command::new("scutil").arg("--proxy").output() else {
+        return SystemSettings::default();
+    };
+
+    let mut values = std::collections::HashMap::new();
+    let mut settings = SystemSettings::default();
+    let mut in_exceptions = false;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let line = line.trim();
+        if line.starts_with("ExceptionsList") {
+            in_exceptions = true;
+            continue;
+        }
+        if line == "}" {
+            in_exceptions = false;
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(" : ") {
+            if in_exceptions {
+                settings.exceptions.push(value.trim().to_string());
+            } else {
+                values.insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+
+    let get = |key: &str| values.get(key).map(String::as_str);
+    if get("ProxyAutoConfigEnable") == Some("1") {
+        settings.pac_url = get("ProxyAutoConfigURLString").map(str::to_string);
+    }
+    settings.proxy = ["HTTPS", "HTTP"].iter().find_map(|scheme| {
+        (get(&format!("{}Enable", scheme)) == Some("1")).then_some(())?;
+        let host = get(&format!("{}Proxy", scheme))?;
+        let port = get(&format!("{}Port", scheme)).unwrap_or("80");
+        Some(format!("http://{}:{}", host, port))
+    });
+    settings
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn read_system() -> SystemSettings {
+    let env = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| std::env::var(name).ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let mut settings = SystemSettings {
+        proxy: env(&["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"])
+            .and_then(|v| parse_proxy_server(&v)),
+        exceptions: env(&["NO_PROXY", "no_proxy"])
+            .map(|v| v.split(',').map(str::to_string).collect())
+            .unwrap_or_default(),
+        pac_url: None,
+    };
+    if settings.proxy.is_some() {
+        return settings;
+    }
+
+    // GNOME: mode 'manual' (Host/Port) oder 'auto' (PAC-URL)
+    let gsettings = |key: &[&str]| {
+        std::process::Command::new("gsettings")
+            .arg("get")
+            .args(key)
+            .output()
+            .ok()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().trim_matches('\'').to_string())
+            .filter(|v| !v.is_empty())
+    };
+    match gsettings(&["org.gnome.system.proxy", "mode"]).as_deref() {
+        Some("auto") => settings.pac_url = gsettings(&["org.gnome.system.proxy", "autoconfig-url"]),
+        Some("manual") => {
+            let host = gsettings(&["org.gnome.system.proxy.https", "host"])
+                .or_else(|| gsettings(&["org.gnome.system.proxy.http", "host"]));
+            let port = gsettings(&["org.gnome.system.proxy.https", "port"])
+                .filter(|p| p != "0")
+                .or_else(|| gsettings(&["org.gnome.system.proxy.http", "port"]));
+            if let (Some(host), Some(port)) = (host, port) {
+                settings.proxy = Some(format!("http://{}:{}", host, port));
+            }
+        }
+        _ => {}
+    }
+    settings
+}
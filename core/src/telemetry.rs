@@ -0,0 +1,159 @@
+// Telemetrie - Anonyme Nutzungsstatistik (nur mit ausdrücklicher Zustimmung, standardmäßig aus)
+// Enthält nur Aggregate: Version, OS, Herstellerverteilung der Scanner, Erfolgsquoten - keine IPs, Namen, Dokumente
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use crate::discovery::DiscoveredScanner;
+use crate::metrics::METRICS;
+use crate::rate_limit::RateLimited;
+use crate::settings;
+
+/// Abstand zwischen zwei Meldungen
+const REPORT_INTERVAL: chrono::Duration = chrono::Duration::hours(24);
+/// Timeout je Meldung
+const SEND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Telemetrie-Einstellungen
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Opt-in: ohne Zustimmung wird nichts gesendet
+    pub enabled: bool,
+    /// Abweichender Empfänger (None = verbundener DocFlow-Server, leitet gesammelt weiter)
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// Inhalt einer Meldung (wird vor dem Senden auch im UI angezeigt)
+#[derive(Clone, Debug, Serialize)]
+pub struct TelemetryReport {
+    /// Zufällige Installations-ID (nicht die Bridge-ID, nicht mit DocFlow verknüpft)
+    pub install_id: String,
+    pub bridge_version: String,
+    pub os: String,
+    pub arch: String,
+    /// Anzahl Scanner je Hersteller
+    pub scanner_vendors: BTreeMap<String, u32>,
+    /// Anzahl Scanner je Protokoll (eSCL, WSD, ...)
+    pub scanner_protocols: BTreeMap<String, u32>,
+    /// Zähler seit dem Start der Bridge
+    pub scan_jobs_succeeded: u64,
+    pub scan_jobs_failed: u64,
+    pub scan_success_rate: Option<f64>,
+    pub folder_uploads: u64,
+    pub folder_upload_errors: u64,
+}
+
+/// Stellt die Meldung aus Scanner-Liste und Metriken zusammen
+pub fn build(scanners: &[DiscoveredScanner]) -> TelemetryReport {
+    let mut scanner_vendors = BTreeMap::new();
+    let mut scanner_protocols = BTreeMap::new();
+    for scanner in scanners {
+        *scanner_vendors.entry(normalize_vendor(&scanner.manufacturer)).or_insert(0) += 1;
+        for protocol in &scanner.protocols {
+            *scanner_protocols.entry(protocol.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    let succeeded = METRICS.scan_jobs_succeeded.load(Ordering::Relaxed);
+    let failed = METRICS.scan_jobs_failed.load(Ordering::Relaxed);
+
+    TelemetryReport {
+        install_id: install_id(),
+        bridge_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        scanner_vendors,
+        scanner_protocols,
+        scan_jobs_succeeded: succeeded,
+        scan_jobs_failed: failed,
+        scan_success_rate: (succeeded + failed > 0).then(|| succeeded as f64 / (succeeded + failed) as f64),
+        folder_uploads: METRICS.folder_uploads.load(Ordering::Relaxed),
+        folder_upload_errors: METRICS.folder_upload_errors.load(Ordering::Relaxed),
+    }
+}
+
+/// Nur bekannte Herstellernamen übernehmen (Freitext könnte Kundennamen enthalten)
+fn normalize_vendor(manufacturer: &str) -> String {
+    const KNOWN_VENDORS: &[&str] = &[
+        "brother", "canon", "epson", "fujitsu", "hp", "kodak", "konica", "kyocera", "lexmark", "panasonic",
+        "ricoh", "samsung", "sharp", "toshiba", "xerox",
+    ];
+    let lower = manufacturer.to_lowercase();
+    let lower = if lower.contains("hewlett") { "hp".to_string() } else { lower };
+    KNOWN_VENDORS
+        .iter()
+        .find(|vendor| lower.contains(*vendor))
+        .map(|vendor| vendor.to_string())
+        .unwrap_or_else(|| "other".to_string())
+}
+
+/// Ob eine Meldung fällig ist (einmal je 24 Stunden, auch über Neustarts hinweg)
+pub fn is_due(config: &TelemetryConfig) -> bool {
+    if !config.enabled {
+        return false;
+    }
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s.trim()).ok())
+        .is_none_or(|last| chrono::Utc::now().signed_duration_since(last) >= REPORT_INTERVAL)
+}
+
+/// Merkt sich den Versandzeitpunkt
+pub fn mark_sent() {
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    let _ = std::fs::write(state_path(), chrono::Utc::now().to_rfc3339());
+}
+
+/// Sendet die Meldung (eigener Endpunkt ohne API-Key, sonst an DocFlow)
+pub async fn send(
+    config: &TelemetryConfig,
+    report: &TelemetryReport,
+    docflow_url: &str,
+    api_key: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let response = match config.endpoint.as_deref().filter(|e| !e.trim().is_empty()) {
+        // Fremder Empfänger: eigener Client ohne API-Key und Signatur, zählt nicht gegen Limiter und Circuit-Breaker von DocFlow
+        Some(endpoint) => {
+            crate::http_client::plain(SEND_TIMEOUT)?
+                .post(endpoint.trim())
+                .json(report)
+                .send()
+                .await?
+        }
+        // Die Bridge-ID aus dem Standard-User-Agent würde die Meldung mit der Installation verknüpfen
+        None => {
+            crate::http_client::docflow()
+                .post(format!("{}/api/scanner/bridge/telemetry", docflow_url))
+                .header("Authorization", format!("Bearer {}", api_key))
+                .header(reqwest::header::USER_AGENT, crate::http_client::generic_user_agent())
+                .json(report)
+                .timeout(SEND_TIMEOUT)
+                .send_limited()
+                .await?
+        }
+    };
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    Ok(())
+}
+
+/// Zufällige Installations-ID (wird beim ersten Aufruf erzeugt)
+fn install_id() -> String {
+    let path = settings::data_dir().join("telemetry_id");
+    if let Some(id) = std::fs::read_to_string(&path).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) {
+        return id;
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let _ = std::fs::create_dir_all(settings::data_dir());
+    let _ = std::fs::write(&path, &id);
+    id
+}
+
+fn state_path() -> PathBuf {
+    settings::data_dir().join("telemetry_last_sent")
+}
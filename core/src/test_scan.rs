@@ -0,0 +1,327 @@
+// Test-Scan - Einseitiger Probescan in niedriger Auflösung für die Fehlersuche am Telefon
+// Misst Status-Abfrage, Job-Erstellung und Übertragung einzeln, lädt nichts zu DocFlow hoch
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::discovery::DiscoveredScanner;
+use crate::escl_recording::Recorded;
+use crate::quirks::{self, QuirkProfile};
+use crate::scanner::{self, ScanJob, ScanRegion};
+use crate::self_test::CheckStatus;
+
+/// Auflösung des Probescans (niedrigste gängige eSCL-Auflösung)
+const TEST_RESOLUTION: u32 = 75;
+/// Maximale Wartezeit auf die gescannte Seite
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(90);
+/// Rohantworten werden für den Bericht gekürzt
+const RAW_RESPONSE_LIMIT: usize = 4000;
+
+/// Eine gemessene Phase des Probescans
+#[derive(Clone, Debug, Serialize)]
+pub struct TestScanPhase {
+    /// "status", "job" oder "transfer"
+    pub name: String,
+    pub status: CheckStatus,
+    pub duration_ms: u64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    /// Unveränderte eSCL-Antwort (nur bei Fehlern)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_response: Option<String>,
+}
+
+/// Diagnosebericht eines Probescans
+#[derive(Clone, Debug, Serialize)]
+pub struct TestScanReport {
+    pub scanner_id: String,
+    pub scanner_name: String,
+    pub base_url: String,
+    pub passed: bool,
+    pub started_at: String,
+    pub total_ms: u64,
+    /// Größe der gescannten Seite (0 = keine Seite erhalten)
+    pub page_bytes: usize,
+    pub page_format: Option<String>,
+    pub phases: Vec<TestScanPhase>,
+}
+
+/// Ergebnis einer Phase vor der Zeitmessung
+struct Outcome {
+    ok: bool,
+    message: String,
+    http_status: Option<u16>,
+    raw_response: Option<String>,
+}
+
+impl Outcome {
+    fn ok(message: impl Into<String>, http_status: Option<u16>) -> Self {
+        Self { ok: true, message: message.into(), http_status, raw_response: None }
+    }
+
+    fn failed(message: impl Into<String>, http_status: Option<u16>, raw: Option<String>) -> Self {
+        Self {
+            ok: false,
+            message: message.into(),
+            http_status,
+            raw_response: raw.map(truncate),
+        }
+    }
+}
+
+/// Führt den Probescan aus (Flachbett bevorzugt, sonst ein Blatt aus dem Einzug)
+pub async fn run(scanner: &DiscoveredScanner) -> TestScanReport {
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let start = Instant::now();
+
+    let scheme = if scanner.use_tls || scanner.port == 443 { "https" } else { "http" };
+    let host = if scanner.ip.contains(':') {
+        format!("[{}]", scanner.ip)
+    } else {
+        scanner.ip.to_string()
+    };
+    let rs = if scanner.rs_path.is_empty() { "eSCL" } else { &scanner.rs_path };
+    let origin = format!("{}://{}:{}", scheme, host, scanner.port);
+    let base_url = format!("{}/{}", origin, rs);
+    let quirks = quirks::for_scanner(&scanner.manufacturer, &scanner.model);
+
+    let mut report = TestScanReport {
+        scanner_id: scanner.id.clone(),
+        scanner_name: scanner.name.clone(),
+        base_url: base_url.clone(),
+        passed: false,
+        started_at,
+        total_ms: 0,
+        page_bytes: 0,
+        page_format: None,
+        phases: Vec::new(),
+    };
+
+    let client = match crate::http_client::scanner(Duration::from_secs(30)) {
+        Ok(client) => client,
+        Err(e) => {
+            report.phases.push(phase("status", Instant::now(), Outcome::failed(e.to_string(), None, None)));
+            skip_rest(&mut report, &["job", "transfer"]);
+            report.total_ms = elapsed_ms(start);
+            return report;
+        }
+    };
+
+    // 1. Scanner-Status
+    let phase_start = Instant::now();
+    let outcome = check_status(&client, &base_url).await;
+    let ok = outcome.ok;
+    report.phases.push(phase("status", phase_start, outcome));
+    if !ok {
+        skip_rest(&mut report, &["job", "transfer"]);
+        report.total_ms = elapsed_ms(start);
+        return report;
+    }
+
+    // 2. Scan-Job anlegen
+    let job = test_job(scanner);
+    let phase_start = Instant::now();
+    let (outcome, job_url) = create_job(&client, &origin, &base_url, &job, &quirks).await;
+    report.phases.push(phase("job", phase_start, outcome));
+    let Some(job_url) = job_url else {
+        skip_rest(&mut report, &["transfer"]);
+        report.total_ms = elapsed_ms(start);
+        return report;
+    };
+
+    // 3. Erste Seite abholen, danach Job sofort beenden (kein weiteres Blatt aus dem Einzug)
+    let phase_start = Instant::now();
+    let (outcome, page_bytes) = match tokio::time::timeout(TRANSFER_TIMEOUT, fetch_page(&client, &job_url)).await {
+        Ok(result) => result,
+        Err(_) => (
+            Outcome::failed(
+                format!("Keine Seite nach {}s erhalten", TRANSFER_TIMEOUT.as_secs()),
+                None,
+                None,
+            ),
+            0,
+        ),
+    };
+    report.phases.push(phase("transfer", phase_start, outcome));
+    let _ = client.delete(&job_url).send_recorded().await;
+
+    report.page_bytes = page_bytes;
+    report.page_format = (page_bytes > 0).then(|| job.format.clone());
+    report.passed = report.phases.iter().all(|p| p.status == CheckStatus::Ok);
+    report.total_ms = elapsed_ms(start);
+    report
+}
+
+/// Einseitiger Graustufen-Scan in niedriger Auflösung (A6-Ausschnitt genügt zum Test der Übertragung)
+fn test_job(scanner: &DiscoveredScanner) -> ScanJob {
+    let source = if scanner.capabilities.flatbed || !scanner.capabilities.adf { "flatbed" } else { "adf" };
+    let format = if scanner.capabilities.formats.iter().any(|f| f == "image/jpeg") {
+        "image/jpeg"
+    } else {
+        "application/pdf"
+    };
+    ScanJob {
+        scanner_id: scanner.id.clone(),
+        resolution: TEST_RESOLUTION,
+        color_mode: "grayscale".to_string(),
+        format: format.to_string(),
+        source: source.to_string(),
+        duplex: false,
+        page_retries: 0,
+        allow_partial: false,
+        height: None,
+        regions: Vec::new(),
+        timeout_secs: TRANSFER_TIMEOUT.as_secs(),
+        spool_after_kb: 0,
+    }
+}
+
+async fn check_status(client: &reqwest::Client, base_url: &str) -> Outcome {
+    let response = match client.get(format!("{}/ScannerStatus", base_url)).send_recorded().await {
+        Ok(response) => response,
+        Err(e) => return Outcome::failed(format!("Scanner nicht erreichbar: {}", e), None, None),
+    };
+    let code = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !code.is_success() {
+        return Outcome::failed(format!("ScannerStatus: HTTP {}", code), Some(code.as_u16()), Some(body));
+    }
+
+    match crate::push_scan::extract_tag(&body, "State") {
+        Some(state) if state == "Idle" => Outcome::ok("Scanner bereit (Idle)", Some(code.as_u16())),
+        Some(state) => Outcome::failed(
+            format!("Scanner nicht bereit ({})", state),
+            Some(code.as_u16()),
+            Some(body),
+        ),
+        None => Outcome::failed("Kein State in ScannerStatus", Some(code.as_u16()), Some(body)),
+    }
+}
+
+async fn create_job(
+    client: &reqwest::Client,
+    origin: &str,
+    base_url: &str,
+    job: &ScanJob,
+    quirks: &QuirkProfile,
+) -> (Outcome, Option<String>) {
+    let region = ScanRegion {
+        x_offset: 0,
+        y_offset: 0,
+        width: 1240,
+        height: 1748,
+    };
+    let response = match client
+        .post(format!("{}/ScanJobs", base_url))
+        .header("Content-Type", "application/xml")
+        .body(scanner::scan_settings_xml_for(job, &region, quirks))
+        .send_recorded()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return (Outcome::failed(format!("ScanJobs fehlgeschlagen: {}", e), None, None), None),
+    };
+
+    let code = response.status();
+    let location = response
+        .headers()
+        .get("Location")
+        .and_then(|v| v.to_str().ok())
+        .map(|location| quirks.job_url(location, origin, base_url));
+    match location {
+        Some(job_url) if code.is_success() => (
+            Outcome::ok(
+                format!("Job erstellt ({} dpi, {}, {})", job.resolution, job.source, job.format),
+                Some(code.as_u16()),
+            ),
+            Some(job_url),
+        ),
+        _ => {
+            let message = if code.is_success() {
+                "Keine Job-URL erhalten".to_string()
+            } else if quirks.is_busy(code.as_u16()) {
+                format!("Scanner belegt (HTTP {})", code.as_u16())
+            } else {
+                format!("Scan-Job abgelehnt: HTTP {}", code)
+            };
+            let body = response.text().await.unwrap_or_default();
+            (Outcome::failed(message, Some(code.as_u16()), Some(body)), None)
+        }
+    }
+}
+
+async fn fetch_page(client: &reqwest::Client, job_url: &str) -> (Outcome, usize) {
+    let doc_url = format!("{}/NextDocument", job_url);
+    loop {
+        let response = match client.get(&doc_url).send_recorded().await {
+            Ok(response) => response,
+            Err(e) => return (Outcome::failed(format!("Übertragung abgebrochen: {}", e), None, None), 0),
+        };
+        let code = response.status();
+
+        // 503/409 = Scan läuft noch
+        if matches!(code.as_u16(), 409 | 503) {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            continue;
+        }
+        if !code.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return (
+                Outcome::failed(format!("NextDocument: HTTP {}", code), Some(code.as_u16()), Some(body)),
+                0,
+            );
+        }
+
+        return match response.bytes().await {
+            Ok(data) if !data.is_empty() => (
+                Outcome::ok(format!("Seite erhalten ({} KB)", data.len().div_ceil(1024)), Some(code.as_u16())),
+                data.len(),
+            ),
+            Ok(_) => (Outcome::failed("Leere Seite erhalten", Some(code.as_u16()), None), 0),
+            Err(e) => (
+                Outcome::failed(format!("Übertragung abgebrochen: {}", e), Some(code.as_u16()), None),
+                0,
+            ),
+        };
+    }
+}
+
+fn phase(name: &str, started: Instant, outcome: Outcome) -> TestScanPhase {
+    TestScanPhase {
+        name: name.to_string(),
+        status: if outcome.ok { CheckStatus::Ok } else { CheckStatus::Failed },
+        duration_ms: elapsed_ms(started),
+        message: outcome.message,
+        http_status: outcome.http_status,
+        raw_response: outcome.raw_response,
+    }
+}
+
+fn skip_rest(report: &mut TestScanReport, names: &[&str]) {
+    for name in names {
+        report.phases.push(TestScanPhase {
+            name: name.to_string(),
+            status: CheckStatus::Skipped,
+            duration_ms: 0,
+            message: "Übersprungen".to_string(),
+            http_status: None,
+            raw_response: None,
+        });
+    }
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+fn truncate(raw: String) -> String {
+    if raw.len() <= RAW_RESPONSE_LIMIT {
+        return raw;
+    }
+    let mut end = RAW_RESPONSE_LIMIT;
+    while !raw.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}… ({} Bytes gekürzt)", &raw[..end], raw.len() - end)
+}
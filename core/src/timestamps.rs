@@ -0,0 +1,70 @@
+// Zeitangaben - Zeitstempel in lokaler Zeit und als "vor X" für die Statusanzeige
+// Gespeichert bleibt UTC (RFC3339), Laufzeiten werden monoton gemessen und überstehen Uhrumstellungen
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Zeitpunkt für die Anzeige (im Backend berechnet)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DisplayTime {
+    /// Lokale Zeit, z.B. "16.10.2026 14:03:12"
+    pub local: String,
+    /// Sekunden seit dem Zeitpunkt (0 bei Zeitpunkten in der Zukunft)
+    pub seconds_ago: u64,
+    /// Lesbar, z.B. "vor 3 Min."
+    pub ago: String,
+}
+
+/// Bereitet einen gespeicherten RFC3339-Zeitstempel für die Anzeige auf
+pub fn display(rfc3339: &str) -> Option<DisplayTime> {
+    display_at(rfc3339, Utc::now())
+}
+
+/// Wie `display`, mit festem Bezugszeitpunkt
+pub fn display_at(rfc3339: &str, now: DateTime<Utc>) -> Option<DisplayTime> {
+    let time = DateTime::parse_from_rfc3339(rfc3339).ok()?;
+    let seconds_ago = (now - time.with_timezone(&Utc)).num_seconds().max(0) as u64;
+    Some(DisplayTime {
+        local: time.with_timezone(&Local).format("%d.%m.%Y %H:%M:%S").to_string(),
+        seconds_ago,
+        ago: if seconds_ago < 10 {
+            "gerade eben".to_string()
+        } else {
+            format!("vor {}", duration(Duration::from_secs(seconds_ago)))
+        },
+    })
+}
+
+/// Dauer lesbar, z.B. "45 s", "3 Min.", "2 Std. 5 Min.", "3 Tg. 4 Std."
+pub fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{} s", secs),
+        60..=3599 => format!("{} Min.", secs / 60),
+        3600..=86_399 => match (secs % 3600) / 60 {
+            0 => format!("{} Std.", secs / 3600),
+            minutes => format!("{} Std. {} Min.", secs / 3600, minutes),
+        },
+        _ => match (secs % 86_400) / 3600 {
+            0 => format!("{} Tg.", secs / 86_400),
+            hours => format!("{} Tg. {} Std.", secs / 86_400, hours),
+        },
+    }
+}
+
+/// Merkt den Programmstart (früh in main aufrufen)
+pub fn mark_start() {
+    started();
+}
+
+/// Laufzeit seit Programmstart (monoton)
+pub fn uptime() -> Duration {
+    started().elapsed()
+}
+
+fn started() -> &'static Instant {
+    static STARTED: OnceLock<Instant> = OnceLock::new();
+    STARTED.get_or_init(Instant::now)
+}
@@ -0,0 +1,204 @@
+// Tray-Status - Dynamisches Tray-Icon (Verbindung, Fehler, Scan/Upload aktiv) mit Warteschlangen-Badge
+// Poller und Folder-Watcher melden ihre Aktivität über globale Zähler, main.rs zeichnet das Icon neu
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Wie lange ein Fehler im Icon angezeigt wird (sofern kein Erfolg folgt)
+const ERROR_DISPLAY: Duration = Duration::from_secs(300);
+
+static SCANNING: AtomicUsize = AtomicUsize::new(0);
+static UPLOADING: AtomicUsize = AtomicUsize::new(0);
+/// Wartende Scan-Jobs (Poller)
+pub static QUEUED_JOBS: AtomicUsize = AtomicUsize::new(0);
+/// Wartende Dateien (Folder-Sync)
+pub static PENDING_FILES: AtomicUsize = AtomicUsize::new(0);
+static LAST_ERROR: Mutex<Option<(Instant, String)>> = Mutex::new(None);
+
+/// Laufende Tätigkeit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activity {
+    Scanning,
+    Uploading,
+}
+
+/// Zustand, den das Tray-Icon anzeigt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayState {
+    Disconnected,
+    Connected,
+    Error,
+    Scanning,
+    Uploading,
+}
+
+impl TrayState {
+    fn color(self) -> [u8; 3] {
+        match self {
+            TrayState::Disconnected => [150, 150, 150],
+            TrayState::Connected => [46, 160, 67],
+            TrayState::Error => [210, 40, 40],
+            TrayState::Scanning => [30, 110, 230],
+            TrayState::Uploading => [240, 150, 20],
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TrayState::Disconnected => "Nicht verbunden",
+            TrayState::Connected => "Verbunden",
+            TrayState::Error => "Fehler",
+            TrayState::Scanning => "Scannt",
+            TrayState::Uploading => "Lädt hoch",
+        }
+    }
+}
+
+/// Markiert eine laufende Tätigkeit, bis der Guard fallen gelassen wird
+pub struct ActivityGuard(Activity);
+
+pub fn activity(activity: Activity) -> ActivityGuard {
+    counter(activity).fetch_add(1, Ordering::Relaxed);
+    ActivityGuard(activity)
+}
+
+impl Drop for ActivityGuard {
+    fn drop(&mut self) {
+        counter(self.0).fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn counter(activity: Activity) -> &'static AtomicUsize {
+    match activity {
+        Activity::Scanning => &SCANNING,
+        Activity::Uploading => &UPLOADING,
+    }
+}
+
+/// Meldet einen Fehler (Icon wird rot)
+pub fn report_error(message: &str) {
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = Some((Instant::now(), message.to_string()));
+    }
+}
+
+/// Erfolgreiche Verarbeitung - Fehleranzeige zurücksetzen
+pub fn clear_error() {
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = None;
+    }
+}
+
+/// Aktueller Zustand → (Zustand, Warteschlangenlänge, Tooltip)
+pub fn current(connected: bool) -> (TrayState, usize, String) {
+    let error = LAST_ERROR
+        .lock()
+        .ok()
+        .and_then(|last| last.clone())
+        .filter(|(at, _)| at.elapsed() < ERROR_DISPLAY);
+
+    let state = if !connected {
+        TrayState::Disconnected
+    } else if error.is_some() {
+        TrayState::Error
+    } else if SCANNING.load(Ordering::Relaxed) > 0 {
+        TrayState::Scanning
+    } else if UPLOADING.load(Ordering::Relaxed) > 0 {
+        TrayState::Uploading
+    } else {
+        TrayState::Connected
+    };
+
+    let queued = QUEUED_JOBS.load(Ordering::Relaxed) + PENDING_FILES.load(Ordering::Relaxed);
+    let mut tooltip = format!("DocFlow Scanner Bridge - {}", state.label());
+    if queued > 0 {
+        tooltip.push_str(&format!(" ({} wartend)", queued));
+    }
+    if let Some((_, message)) = error {
+        tooltip.push_str(&format!("\n{}", message));
+    }
+
+    (state, queued, tooltip)
+}
+
+/// Zeichnet Status-Punkt (unten rechts) und Badge mit Anzahl (oben rechts) auf das Basis-Icon
+pub fn render(base: &[u8], width: u32, height: u32, state: TrayState, queued: usize) -> Vec<u8> {
+    let mut rgba = base.to_vec();
+    let size = width.min(height) as i32;
+
+    // Status-Punkt
+    let radius = size / 5;
+    let (cx, cy) = (width as i32 - radius - 1, height as i32 - radius - 1);
+    fill_circle(&mut rgba, width, height, cx, cy, radius + size / 24, [255, 255, 255]);
+    fill_circle(&mut rgba, width, height, cx, cy, radius, state.color());
+
+    // Badge mit Warteschlangenlänge
+    if queued > 0 {
+        let text = if queued > 9 { "9+".to_string() } else { queued.to_string() };
+        let radius = size / 4;
+        let (cx, cy) = (width as i32 - radius - 1, radius + 1);
+        fill_circle(&mut rgba, width, height, cx, cy, radius, [210, 40, 40]);
+
+        let scale = (radius / 4).max(1);
+        let text_width = text.len() as i32 * 4 * scale - scale;
+        let mut x = cx - text_width / 2;
+        let y = cy - 5 * scale / 2;
+        for c in text.chars() {
+            draw_glyph(&mut rgba, width, height, x, y, scale, c);
+            x += 4 * scale;
+        }
+    }
+
+    rgba
+}
+
+fn set_pixel(rgba: &mut [u8], width: u32, height: u32, x: i32, y: i32, color: [u8; 3]) {
+    if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+        return;
+    }
+    let index = (y as usize * width as usize + x as usize) * 4;
+    rgba[index..index + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+}
+
+fn fill_circle(rgba: &mut [u8], width: u32, height: u32, cx: i32, cy: i32, radius: i32, color: [u8; 3]) {
+    for y in -radius..=radius {
+        for x in -radius..=radius {
+            if x * x + y * y <= radius * radius {
+                set_pixel(rgba, width, height, cx + x, cy + y, color);
+            }
+        }
+    }
+}
+
+/// 3x5-Pixelfont für Ziffern und "+" (Bit 2 = linke Spalte)
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => [0; 5],
+    }
+}
+
+fn draw_glyph(rgba: &mut [u8], width: u32, height: u32, x: i32, y: i32, scale: i32, c: char) {
+    for (row, bits) in glyph(c).iter().enumerate() {
+        for col in 0..3 {
+            if bits & (0b100 >> col) != 0 {
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        set_pixel(rgba, width, height, x + col * scale + dx, y + row as i32 * scale + dy, [255, 255, 255]);
+                    }
+                }
+            }
+        }
+    }
+}
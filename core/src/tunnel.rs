@@ -0,0 +1,428 @@
+// Reverse-Tunnel - Ausgehende WebSocket-Verbindung zu DocFlow, über die Scan-Befehle ohne Verzögerung eintreffen
+// Für Cloud-DocFlow, das die Scanner im LAN nicht erreicht: solange der Tunnel steht, entfällt das Polling der pending-scans
+// Jede Anfrage trägt eine ID, Antworten gehen mit derselben ID zurück (parallel bearbeitet, Reihenfolge beliebig)
+
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, RwLock};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::Connector;
+
+use crate::events::{self, EventKind};
+use crate::http_client::HttpConfig;
+use crate::scan_poller::{self, PendingScanJob, ScanPoller};
+use crate::settings::BridgeSettings;
+
+/// WebSocket-Endpunkt relativ zur DocFlow-URL
+pub const TUNNEL_PATH: &str = "/api/scanner/bridge/tunnel";
+
+/// Verbindungen, die kürzer standen, zählen für den Backoff als fehlgeschlagen
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// Konfiguration des Reverse-Tunnels
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TunnelConfig {
+    pub enabled: bool,
+    /// WebSocket-URL (leer = aus der DocFlow-URL, z.B. wss://docflow.example/api/scanner/bridge/tunnel)
+    #[serde(default)]
+    pub url: String,
+    /// Ping an DocFlow - bleiben drei Intervalle ohne Lebenszeichen, wird neu verbunden
+    pub ping_secs: u64,
+    /// Längste Wartezeit zwischen zwei Verbindungsversuchen
+    pub max_reconnect_secs: u64,
+}
+
+impl Default for TunnelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            ping_secs: 30,
+            max_reconnect_secs: 60,
+        }
+    }
+}
+
+impl TunnelConfig {
+    /// WebSocket-URL für eine DocFlow-Instanz (https → wss, http → ws)
+    pub fn endpoint(&self, docflow_url: &str) -> String {
+        let url = self.url.trim();
+        if !url.is_empty() {
+            return url.to_string();
+        }
+        let base = docflow_url.trim().trim_end_matches('/');
+        let base = if let Some(host) = base.strip_prefix("https://") {
+            format!("wss://{}", host)
+        } else if let Some(host) = base.strip_prefix("http://") {
+            format!("ws://{}", host)
+        } else {
+            base.to_string()
+        };
+        format!("{}{}", base, TUNNEL_PATH)
+    }
+}
+
+/// Anfrage von DocFlow
+#[derive(Debug, Deserialize)]
+pub struct TunnelRequest {
+    pub id: String,
+    #[serde(flatten)]
+    pub command: Command,
+}
+
+/// Befehle über den Tunnel (Feld "type")
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Command {
+    /// Scan-Job sofort einreihen → Position in der Warteschlange des Scanners
+    Scan { job: Box<PendingScanJob> },
+    /// Wartenden oder laufenden Job abbrechen
+    Cancel { job_id: String },
+    /// Priorität eines wartenden Jobs ändern
+    SetPriority { job_id: String, priority: i32 },
+    /// Live-Zustand aller Scanner
+    ScannerActivity,
+    /// Laufende und wartende Jobs
+    ActiveJobs,
+    Ping,
+}
+
+/// Antwort der Bridge (gleiche ID wie die Anfrage)
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TunnelResponse {
+    pub id: String,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Tunnel-Status für die Anzeige
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TunnelStatus {
+    pub connected: bool,
+    pub url: String,
+    /// RFC3339, seit wann die aktuelle Verbindung steht
+    pub connected_since: Option<String>,
+    /// Über den Tunnel beantwortete Anfragen
+    pub requests: u64,
+    pub last_error: Option<String>,
+}
+
+/// None = Tunnel nicht aktiv
+static STATUS: Mutex<Option<TunnelStatus>> = Mutex::new(None);
+
+/// Aktueller Tunnel-Status (None = nicht aktiviert bzw. Bridge nicht gekoppelt)
+pub fn status() -> Option<TunnelStatus> {
+    STATUS.lock().ok().and_then(|s| s.clone())
+}
+
+/// Tunnel steht - der Poller fragt dann keine pending-scans ab
+pub fn is_connected() -> bool {
+    STATUS.lock().is_ok_and(|s| s.as_ref().is_some_and(|s| s.connected))
+}
+
+fn update_status(update: impl FnOnce(&mut TunnelStatus)) {
+    if let Ok(mut status) = STATUS.lock() {
+        if let Some(status) = status.as_mut() {
+            update(status);
+        }
+    }
+}
+
+/// Reverse-Tunnel-Dienst (Verbindung halten, Anfragen an den Poller weiterreichen)
+pub struct TunnelService {
+    task: tokio::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+}
+
+impl TunnelService {
+    /// Startet den Dienst, falls in den Einstellungen aktiviert
+    pub async fn start(
+        poller: Arc<ScanPoller>,
+        docflow_url: String,
+        api_key: String,
+        settings: Arc<RwLock<BridgeSettings>>,
+    ) -> Option<Arc<Self>> {
+        let config = settings.read().await.tunnel.clone();
+        if !config.enabled {
+            return None;
+        }
+
+        let url = config.endpoint(&docflow_url);
+        if let Ok(mut status) = STATUS.lock() {
+            *status = Some(TunnelStatus { url: url.clone(), ..TunnelStatus::default() });
+        }
+        let handle = tokio::spawn(run(poller, url, api_key, config, settings));
+        Some(Arc::new(Self {
+            task: tokio::sync::Mutex::new(Some(handle)),
+        }))
+    }
+
+    /// Beendet die Verbindung - der Poller fragt wieder selbst ab
+    pub async fn stop(&self) {
+        if let Some(task) = self.task.lock().await.take() {
+            task.abort();
+        }
+        if let Ok(mut status) = STATUS.lock() {
+            *status = None;
+        }
+        scan_poller::wake();
+        println!("🛑 Reverse-Tunnel beendet");
+    }
+}
+
+/// Verbindungs-Loop mit exponentiellem Backoff
+async fn run(
+    poller: Arc<ScanPoller>,
+    url: String,
+    api_key: String,
+    config: TunnelConfig,
+    settings: Arc<RwLock<BridgeSettings>>,
+) {
+    let max_backoff = config.max_reconnect_secs.max(1);
+    let mut backoff_secs = 1;
+
+    loop {
+        // Netzwerk-Einstellungen je Versuch neu lesen (CA oder Proxy können sich geändert haben)
+        let http = settings.read().await.http.clone();
+        let result = match connect(&url, &api_key, &http).await {
+            Ok(socket) => {
+                let connected = Instant::now();
+                println!("🔌 Reverse-Tunnel zu DocFlow verbunden ({})", url);
+                events::record(EventKind::Discovery, "Reverse-Tunnel zu DocFlow verbunden");
+                update_status(|s| {
+                    s.connected = true;
+                    s.connected_since = Some(chrono::Utc::now().to_rfc3339());
+                    s.last_error = None;
+                });
+
+                let result = serve(socket, &poller, &config).await;
+                update_status(|s| {
+                    s.connected = false;
+                    s.connected_since = None;
+                });
+                if connected.elapsed() >= STABLE_AFTER {
+                    backoff_secs = 1;
+                }
+                result
+            }
+            Err(e) => Err(e.to_string()),
+        };
+
+        if let Err(e) = result {
+            eprintln!("⚠ Reverse-Tunnel: {} - neuer Versuch in {}s", e, backoff_secs);
+            update_status(|s| s.last_error = Some(e));
+        }
+        // Bis der Tunnel wieder steht, übernimmt das Polling
+        scan_poller::wake();
+        tokio::time::sleep(Duration::from_secs(backoff_secs)).await;
+        backoff_secs = (backoff_secs * 2).min(max_backoff);
+    }
+}
+
+type Socket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Baut die WebSocket-Verbindung auf (Authorization und ggf. Signatur wie bei jeder DocFlow-Anfrage)
+/// TLS mit der CA aus den Netzwerk-Einstellungen, über den DocFlow-Proxy per CONNECT
+async fn connect(url: &str, api_key: &str, http: &HttpConfig) -> Result<Socket, Box<dyn std::error::Error + Send + Sync>> {
+    // wss:// → https:// bzw. ws:// → http:// (Signatur über Methode und Pfad)
+    let http_url = url.replacen("ws", "http", 1);
+    let mut signed = crate::http_client::docflow()
+        .get(&http_url)
+        .header(reqwest::header::AUTHORIZATION, format!("Bearer {}", api_key))
+        .build()?;
+    crate::request_signing::sign(&mut signed)?;
+
+    let mut request = url.into_client_request()?;
+    for (name, value) in signed.headers() {
+        request.headers_mut().insert(name, value.clone());
+    }
+
+    let target = reqwest::Url::parse(&http_url)?;
+    let host = target.host_str().ok_or("Tunnel-URL ohne Host")?.to_string();
+    let port = target.port_or_known_default().unwrap_or(443);
+    let connector = Connector::Rustls(crate::http_client::rustls_config(http)?);
+
+    let connecting = async {
+        let stream = match crate::system_proxy::effective(http) {
+            Some(proxy) if !crate::system_proxy::bypasses(&proxy, &host) => match proxy.proxy_url.as_deref() {
+                Some(proxy_url) => connect_via_proxy(proxy_url, &host, port).await?,
+                None => TcpStream::connect((host.as_str(), port)).await?,
+            },
+            _ => TcpStream::connect((host.as_str(), port)).await?,
+        };
+        let (socket, _) = tokio_tungstenite::client_async_tls_with_config(request, stream, None, Some(connector)).await?;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(socket)
+    };
+    tokio::time::timeout(Duration::from_secs(15), connecting)
+        .await
+        .map_err(|_| "Zeitüberschreitung beim Verbindungsaufbau")?
+}
+
+/// Öffnet per HTTP CONNECT einen Tunnel durch den Proxy (Zugangsdaten aus der Proxy-URL)
+async fn connect_via_proxy(
+    proxy_url: &str,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream, Box<dyn std::error::Error + Send + Sync>> {
+    use base64::Engine;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let proxy = reqwest::Url::parse(proxy_url)?;
+    if proxy.scheme() != "http" {
+        return Err(format!("Proxy-Schema '{}' wird für den Tunnel nicht unterstützt", proxy.scheme()).into());
+    }
+    let proxy_host = proxy.host_str().ok_or("Proxy-URL ohne Host")?;
+    let mut stream = TcpStream::connect((proxy_host, proxy.port_or_known_default().unwrap_or(80))).await?;
+
+    let mut connect = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !proxy.username().is_empty() {
+        let user = urlencoding_decode(proxy.username());
+        let password = urlencoding_decode(proxy.password().unwrap_or_default());
+        let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password));
+        connect.push_str(&format!("Proxy-Authorization: Basic {}\r\n", credentials));
+    }
+    connect.push_str("\r\n");
+    stream.write_all(connect.as_bytes()).await?;
+
+    // Antwort bis zum Ende der Header lesen, danach gehört die Verbindung dem TLS-Handshake
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > 8192 || stream.read(&mut byte).await? == 0 {
+            return Err("Ungültige Antwort des Proxys auf CONNECT".into());
+        }
+        response.push(byte[0]);
+    }
+    let status_line = String::from_utf8_lossy(&response);
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(format!("Proxy lehnt den Tunnel ab: {}", status_line.lines().next().unwrap_or_default()).into());
+    }
+    Ok(stream)
+}
+
+/// Prozent-kodierte Zugangsdaten aus der Proxy-URL ("p%40ss" → "p@ss")
+fn urlencoding_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = (bytes[index] == b'%')
+            .then(|| value.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Bearbeitet Anfragen, bis die Verbindung abbricht
+async fn serve(socket: Socket, poller: &Arc<ScanPoller>, config: &TunnelConfig) -> Result<(), String> {
+    let (mut sink, mut stream) = socket.split();
+    let (responses, mut outgoing) = mpsc::unbounded_channel::<String>();
+    let ping_interval = Duration::from_secs(config.ping_secs.max(5));
+    let mut ping = tokio::time::interval(ping_interval);
+    let mut last_seen = Instant::now();
+
+    loop {
+        tokio::select! {
+            message = stream.next() => {
+                let Some(message) = message else {
+                    return Err("Verbindung von DocFlow geschlossen".to_string());
+                };
+                last_seen = Instant::now();
+                match message.map_err(|e| e.to_string())? {
+                    Message::Text(text) => {
+                        let poller = poller.clone();
+                        let responses = responses.clone();
+                        tokio::spawn(async move {
+                            let response = respond(&poller, &text).await;
+                            if let Ok(json) = serde_json::to_string(&response) {
+                                let _ = responses.send(json);
+                            }
+                        });
+                    }
+                    Message::Close(frame) => {
+                        let reason = frame.map(|f| f.reason.to_string()).filter(|r| !r.is_empty());
+                        return Err(format!("Verbindung von DocFlow beendet ({})", reason.as_deref().unwrap_or("ohne Grund")));
+                    }
+                    // Pings beantwortet tungstenite selbst, Pongs zählen nur als Lebenszeichen
+                    _ => {}
+                }
+            }
+            Some(json) = outgoing.recv() => {
+                sink.send(Message::Text(json)).await.map_err(|e| e.to_string())?;
+                update_status(|s| s.requests += 1);
+            }
+            _ = ping.tick() => {
+                if last_seen.elapsed() > ping_interval * 3 {
+                    return Err(format!("Kein Lebenszeichen seit {}s", last_seen.elapsed().as_secs()));
+                }
+                sink.send(Message::Ping(Vec::new())).await.map_err(|e| e.to_string())?;
+            }
+        }
+    }
+}
+
+/// Beantwortet eine Anfrage (ungültige Anfragen mit Fehler, sofern eine ID lesbar ist)
+pub async fn respond(poller: &Arc<ScanPoller>, text: &str) -> TunnelResponse {
+    let request = match serde_json::from_str::<TunnelRequest>(text) {
+        Ok(request) => request,
+        Err(e) => {
+            let id = serde_json::from_str::<Value>(text)
+                .ok()
+                .and_then(|v| v.get("id").and_then(Value::as_str).map(str::to_string))
+                .unwrap_or_default();
+            return TunnelResponse {
+                id,
+                ok: false,
+                result: None,
+                error: Some(format!("Ungültige Anfrage: {}", e)),
+            };
+        }
+    };
+
+    match handle(poller, request.command).await {
+        Ok(result) => TunnelResponse { id: request.id, ok: true, result: Some(result), error: None },
+        Err(e) => TunnelResponse { id: request.id, ok: false, result: None, error: Some(e) },
+    }
+}
+
+/// Führt einen Befehl aus
+async fn handle(poller: &Arc<ScanPoller>, command: Command) -> Result<Value, String> {
+    let to_json = |value: Result<Value, serde_json::Error>| value.map_err(|e| e.to_string());
+    match command {
+        Command::Scan { job } => {
+            println!("⚡ Scan-Job {} über den Tunnel erhalten", job.job_id);
+            let position = poller.enqueue_job(*job).await.map_err(|e| e.to_string())?;
+            Ok(serde_json::json!({ "queue_position": position }))
+        }
+        Command::Cancel { job_id } => {
+            poller.cancel_job(&job_id).await.map_err(|e| e.to_string())?;
+            Ok(Value::Null)
+        }
+        Command::SetPriority { job_id, priority } => {
+            poller.set_job_priority(&job_id, priority).await.map_err(|e| e.to_string())?;
+            Ok(Value::Null)
+        }
+        Command::ScannerActivity => to_json(serde_json::to_value(poller.scanner_activity().await)),
+        Command::ActiveJobs => to_json(serde_json::to_value(poller.active_jobs().await)),
+        Command::Ping => Ok(Value::String("pong".to_string())),
+    }
+}
@@ -0,0 +1,72 @@
+// Upload-Fortschritt - Multipart-Dateien melden beim Senden die übertragenen Bytes (siehe multipart::Part::progress)
+// Poller und Folder-Sync senden in einen Broadcast-Kanal, main.rs leitet als "upload-progress" ans Frontend weiter
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Höchstens so oft wird Fortschritt gemeldet (Ende wird immer gemeldet)
+const REPORT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Herkunft des Uploads
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadKind {
+    /// Scan-Job (Poller oder Push-Scan)
+    Scan,
+    /// Datei aus dem überwachten Ordner
+    Folder,
+}
+
+/// Fortschritt eines laufenden Uploads
+#[derive(Clone, Debug, Serialize)]
+pub struct UploadProgress {
+    pub kind: UploadKind,
+    /// Job-ID bzw. Upload-ID (für cancel_upload)
+    pub id: String,
+    pub file_name: String,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+}
+
+fn channel() -> &'static broadcast::Sender<UploadProgress> {
+    static CHANNEL: OnceLock<broadcast::Sender<UploadProgress>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(64).0)
+}
+
+/// Empfänger für Fortschrittsmeldungen (verpasste Meldungen werden übersprungen)
+pub fn subscribe() -> broadcast::Receiver<UploadProgress> {
+    channel().subscribe()
+}
+
+/// Zählt gesendete Blöcke und meldet höchstens alle REPORT_INTERVAL (Ende immer)
+pub struct Reporter {
+    progress: UploadProgress,
+    last_report: Option<Instant>,
+}
+
+impl Reporter {
+    pub fn new(kind: UploadKind, id: &str, file_name: &str, total_bytes: u64) -> Self {
+        Self {
+            progress: UploadProgress {
+                kind,
+                id: id.to_string(),
+                file_name: file_name.to_string(),
+                bytes_sent: 0,
+                total_bytes,
+            },
+            last_report: None,
+        }
+    }
+
+    pub fn advance(&mut self, bytes: usize) {
+        self.progress.bytes_sent += bytes as u64;
+        let finished = self.progress.bytes_sent >= self.progress.total_bytes;
+        if finished || self.last_report.is_none_or(|t| t.elapsed() >= REPORT_INTERVAL) {
+            // Ohne Empfänger (kein Fenster) schlägt send fehl - das ist unkritisch
+            let _ = channel().send(self.progress.clone());
+            self.last_report = Some(Instant::now());
+        }
+    }
+}
@@ -0,0 +1,172 @@
+// Virtueller Scanner - Demo-/Entwicklungsgerät ohne Hardware
+// Erscheint in der Discovery und liefert generierte Beispielseiten statt eines eSCL-Scans
+
+use image::{DynamicImage, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::{DiscoveredScanner, ScannerCapabilities};
+use crate::scanner::{ScanJob, ScanResult, ScannedPage};
+use crate::{imaging, pdf, spool};
+
+/// Scanner-ID des virtuellen Geräts
+pub const VIRTUAL_SCANNER_ID: &str = "virtual-scanner";
+
+/// Einstellungen des virtuellen Scanners
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VirtualScannerConfig {
+    pub enabled: bool,
+    /// Anzeigename in Bridge und DocFlow
+    #[serde(default = "default_name")]
+    pub name: String,
+    /// Seiten je ADF-Scan (Flachbett liefert immer eine Seite)
+    #[serde(default = "default_pages")]
+    pub adf_pages: u32,
+}
+
+fn default_name() -> String {
+    "DocFlow Demo-Scanner".to_string()
+}
+
+fn default_pages() -> u32 {
+    3
+}
+
+impl Default for VirtualScannerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            name: default_name(),
+            adf_pages: default_pages(),
+        }
+    }
+}
+
+/// Ob ein Scanner der virtuelle ist
+pub fn is_virtual(scanner: &DiscoveredScanner) -> bool {
+    scanner.discovery_method == "virtual"
+}
+
+/// Eintrag für die Scanner-Liste
+pub fn scanner(config: &VirtualScannerConfig) -> DiscoveredScanner {
+    DiscoveredScanner {
+        id: VIRTUAL_SCANNER_ID.to_string(),
+        name: config.name.clone(),
+        manufacturer: "DocFlow".to_string(),
+        model: "Virtueller Scanner".to_string(),
+        ip: "127.0.0.1".to_string(),
+        port: 0,
+        use_tls: false,
+        protocols: vec!["virtual".to_string()],
+        capabilities: ScannerCapabilities {
+            duplex: true,
+            adf: true,
+            flatbed: true,
+            max_resolution: 300,
+            color_modes: vec!["RGB24".to_string(), "Grayscale8".to_string()],
+            formats: vec!["application/pdf".to_string(), "image/jpeg".to_string()],
+            unknown: false,
+        },
+        discovery_method: "virtual".to_string(),
+        rs_path: String::new(),
+        wsd_url: None,
+        endpoints: Vec::new(),
+        firmware: None,
+        serial_number: None,
+        mac_address: None,
+        location: None,
+        group: Some("Demo".to_string()),
+        admin_url: None,
+        icon_url: None,
+        icon: None,
+    }
+}
+
+/// Fügt den virtuellen Scanner hinzu bzw. entfernt ihn (Einstellung wirkt ohne neue Discovery)
+pub fn sync(scanners: &mut Vec<DiscoveredScanner>, config: &VirtualScannerConfig) {
+    scanners.retain(|s| !is_virtual(s));
+    if config.enabled {
+        scanners.push(scanner(config));
+    }
+}
+
+/// "Scannt" generierte Beispielseiten - PDF als ein Dokument, JPEG je Seite (wie eSCL-Geräte)
+pub fn scan(config: &VirtualScannerConfig, job: &ScanJob) -> Result<ScanResult, Box<dyn std::error::Error + Send + Sync>> {
+    let resolution = job.resolution.clamp(75, 300);
+    let sheets = if job.source == "adf" { config.adf_pages.max(1) } else { 1 };
+    let page_count = if job.duplex && job.source == "adf" { sheets * 2 } else { sheets };
+    let grayscale = matches!(job.color_mode.to_lowercase().as_str(), "grayscale" | "grayscale8" | "gray" | "bw");
+
+    let jpegs = (1..=page_count)
+        .map(|number| imaging::encode_jpeg(&sample_page(number, resolution, grayscale)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let documents = if job.format == "application/pdf" {
+        vec![pdf::assemble_jpeg_pdf(&jpegs, resolution)?]
+    } else {
+        jpegs
+    };
+
+    let pages = documents
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| ScannedPage {
+            page_number: index + 1,
+            format: job.format.clone(),
+            size_bytes: data.len(),
+            data: spool::Buffer::Memory(data),
+        })
+        .collect::<Vec<_>>();
+
+    println!("🧪 Virtueller Scan: {} Seite(n) mit {} dpi", page_count, resolution);
+
+    Ok(ScanResult {
+        job_id: uuid::Uuid::new_v4().to_string(),
+        total_pages: pages.len(),
+        pages,
+        incomplete: false,
+    })
+}
+
+/// Zeichnet eine A4-Beispielseite: Briefkopf, Textzeilen, Tabelle, Seitenmarkierung unten
+fn sample_page(number: u32, dpi: u32, grayscale: bool) -> DynamicImage {
+    let mm = |value: u32| value * dpi * 10 / 254;
+    let (width, height) = (mm(210), mm(297));
+    let mut page = RgbImage::from_pixel(width, height, Rgb([255, 255, 255]));
+
+    let mut fill = |x: u32, y: u32, w: u32, h: u32, color: [u8; 3]| {
+        for py in y..(y + h).min(height) {
+            for px in x..(x + w).min(width) {
+                page.put_pixel(px, py, Rgb(color));
+            }
+        }
+    };
+
+    // Briefkopf mit Logo-Fläche
+    fill(mm(20), mm(15), mm(25), mm(25), [30, 90, 170]);
+    fill(mm(50), mm(20), mm(80), mm(6), [60, 60, 60]);
+    fill(mm(50), mm(30), mm(50), mm(4), [140, 140, 140]);
+
+    // Textzeilen mit unterschiedlicher Länge (je Seite leicht versetzt)
+    for line in 0..18u32 {
+        let length = 120 + (line * 37 + number * 13) % 50;
+        fill(mm(20), mm(60 + line * 7), mm(length), mm(3), [90, 90, 90]);
+    }
+
+    // Tabelle
+    for row in 0..6u32 {
+        fill(mm(20), mm(195 + row * 8), mm(170), 1.max(dpi / 150), [120, 120, 120]);
+    }
+    fill(mm(140), mm(195), 1.max(dpi / 150), mm(40), [120, 120, 120]);
+
+    // Seitenmarkierung: ein Block je Seitennummer
+    for block in 0..number.min(20) {
+        fill(mm(20 + block * 8), mm(270), mm(5), mm(5), [200, 40, 40]);
+    }
+
+    let image = DynamicImage::ImageRgb8(page);
+    if grayscale {
+        DynamicImage::ImageLuma8(image.to_luma8())
+    } else {
+        image
+    }
+}
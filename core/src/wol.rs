@@ -0,0 +1,168 @@
+// Wake-on-LAN - Weckt Scanner aus dem Tiefschlaf, bevor sie als offline gelten
+// MAC-Adressen stammen aus der ARP-Tabelle (nach der Discovery ist der Scanner dort eingetragen)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::time::timeout;
+
+use crate::discovery::{self, DiscoveredScanner};
+
+/// Wake-on-LAN Konfiguration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WolConfig {
+    pub enabled: bool,
+    /// Ziel für das Magic Packet (Broadcast im Scanner-Subnetz)
+    pub broadcast_address: String,
+    /// Maximale Wartezeit nach dem Wecken, bis der Scanner als offline gilt
+    pub max_wait_secs: u64,
+}
+
+impl Default for WolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            broadcast_address: "255.255.255.255".to_string(),
+            max_wait_secs: 60,
+        }
+    }
+}
+
+/// Trägt die MAC-Adressen aus der ARP-Tabelle bei den Scannern ein
+pub async fn fill_mac_addresses(scanners: &mut [DiscoveredScanner]) {
+    let table = tokio::task::spawn_blocking(read_arp_table).await.unwrap_or_default();
+    for scanner in scanners.iter_mut() {
+        if let Some(mac) = table.get(&scanner.ip) {
+            scanner.mac_address = Some(mac.clone());
+        }
+    }
+}
+
+/// Prüft ob der Scanner erreichbar ist - weckt ihn sonst per WoL und wartet mit Backoff
+pub async fn ensure_awake(
+    scanner: &DiscoveredScanner,
+    config: &WolConfig,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if is_reachable(scanner).await {
+        discovery::mark_reachable(&scanner.id, true);
+        return Ok(());
+    }
+
+    let mac = match (&scanner.mac_address, config.enabled) {
+        (Some(mac), true) => mac,
+        _ => {
+            discovery::mark_reachable(&scanner.id, false);
+            return Err(format!("Scanner '{}' ist offline", scanner.name).into());
+        }
+    };
+
+    println!("💤 {} antwortet nicht - sende Wake-on-LAN an {}", scanner.name, mac);
+    send_magic_packet(mac, &config.broadcast_address).await?;
+
+    // Backoff: 2s, 4s, 8s, ... bis max_wait_secs
+    let mut waited = 0;
+    let mut delay = 2;
+    while waited < config.max_wait_secs {
+        tokio::time::sleep(Duration::from_secs(delay)).await;
+        waited += delay;
+        if is_reachable(scanner).await {
+            println!("✓ {} ist aufgewacht ({}s)", scanner.name, waited);
+            discovery::mark_reachable(&scanner.id, true);
+            return Ok(());
+        }
+        delay = (delay * 2).min(config.max_wait_secs.saturating_sub(waited).max(1));
+    }
+
+    discovery::mark_reachable(&scanner.id, false);
+    Err(format!(
+        "Scanner '{}' ist offline (kein Aufwachen nach Wake-on-LAN, {}s gewartet)",
+        scanner.name, waited
+    )
+    .into())
+}
+
+async fn is_reachable(scanner: &DiscoveredScanner) -> bool {
+    let addr = if scanner.ip.contains(':') {
+        format!("[{}]:{}", scanner.ip, scanner.port)
+    } else {
+        format!("{}:{}", scanner.ip, scanner.port)
+    };
+    matches!(timeout(Duration::from_secs(2), TcpStream::connect(&addr)).await, Ok(Ok(_)))
+}
+
+/// Sendet ein Magic Packet (6x 0xFF + 16x MAC) an Port 9
+pub async fn send_magic_packet(
+    mac: &str,
+    broadcast_address: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = parse_mac(mac).ok_or_else(|| format!("Ungültige MAC-Adresse: {}", mac))?;
+
+    let mut packet = vec![0xffu8; 6];
+    for _ in 0..16 {
+        packet.extend_from_slice(&bytes);
+    }
+
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.set_broadcast(true)?;
+    socket.send_to(&packet, (broadcast_address, 9)).await?;
+    Ok(())
+}
+
+fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+    let parts: Vec<u8> = mac
+        .split([':', '-'])
+        .map(|p| u8::from_str_radix(p, 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    parts.try_into().ok()
+}
+
+/// Liest die ARP-Tabelle (IP → MAC im Format aa:bb:cc:dd:ee:ff)
+fn read_arp_table() -> HashMap<String, String> {
+    let mut table = HashMap::new();
+
+    // Linux: /proc/net/arp (IP HW-Typ Flags MAC Maske Device)
+    if let Ok(content) = std::fs::read_to_string("/proc/net/arp") {
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() >= 4 {
+                insert_mac(&mut table, fields[0], fields[3]);
+            }
+        }
+        return table;
+    }
+
+    // Windows: "  192.168.1.20   aa-bb-cc-dd-ee-ff   dynamisch"
+    // macOS:   "? (192.168.1.20) at aa:bb:cc:dd:ee:ff on en0 ..."
+    let mut command = std::process::Command::new("arp");
+    command.arg("-a");
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    if let Ok(output) = command.output() {
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() >= 4 && fields[2] == "at" {
+                insert_mac(&mut table, fields[1].trim_matches(|c| c == '(' || c == ')'), fields[3]);
+            } else if fields.len() >= 2 {
+                insert_mac(&mut table, fields[0], fields[1]);
+            }
+        }
+    }
+
+    table
+}
+
+fn insert_mac(table: &mut HashMap<String, String>, ip: &str, mac: &str) {
+    // macOS kürzt führende Nullen ("a:b:c:..."), daher erst parsen
+    if let Some(bytes) = parse_mac(mac) {
+        if bytes != [0; 6] && bytes != [0xff; 6] {
+            let formatted = bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":");
+            table.insert(ip.to_string(), formatted);
+        }
+    }
+}
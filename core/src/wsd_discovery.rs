@@ -0,0 +1,231 @@
+// WSD-Discovery - Scanner per WS-Discovery finden (UDP-Multicast 3702, SOAP Probe/Resolve)
+// Für ältere Geräte (z.B. Brother, Kyocera), die kein _uscan._tcp per mDNS anbieten
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+use crate::discovery::{self, DiscoveredScanner, ScannerCapabilities};
+use crate::push_scan::extract_tag;
+
+/// WS-Discovery-Multicastgruppe (IPv4)
+const MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(239, 255, 255, 250);
+const MULTICAST_PORT: u16 = 3702;
+
+/// Gesuchter Gerätetyp: WSD-Scanner
+const SCAN_DEVICE_TYPE: &str = "ScanDeviceType";
+
+/// Antwort auf Probe bzw. Resolve
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbeMatch {
+    /// Endpoint-Referenz des Geräts, z.B. "urn:uuid:e3248000-80ce-11db-8000-30055c773bcf"
+    pub endpoint: String,
+    pub types: String,
+    /// Transport-Adressen (leer = per Resolve nachfragen)
+    pub xaddrs: Vec<String>,
+}
+
+/// SOAP-Probe nach WSD-Scannern
+pub fn probe_message(message_id: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"
+               xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing"
+               xmlns:wsd="http://schemas.xmlsoap.org/ws/2005/04/discovery"
+               xmlns:wscn="http://schemas.microsoft.com/windows/2006/08/wdp/scan">
+    <soap:Header>
+        <wsa:To>urn:schemas-xmlsoap-org:ws:2005:04:discovery</wsa:To>
+        <wsa:Action>http://schemas.xmlsoap.org/ws/2005/04/discovery/Probe</wsa:Action>
+        <wsa:MessageID>urn:uuid:{}</wsa:MessageID>
+    </soap:Header>
+    <soap:Body>
+        <wsd:Probe>
+            <wsd:Types>wscn:{}</wsd:Types>
+        </wsd:Probe>
+    </soap:Body>
+</soap:Envelope>"#,
+        message_id, SCAN_DEVICE_TYPE
+    )
+}
+
+/// SOAP-Resolve für ein Gerät, das im ProbeMatch keine Adressen genannt hat
+pub fn resolve_message(message_id: &str, endpoint: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"
+               xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing"
+               xmlns:wsd="http://schemas.xmlsoap.org/ws/2005/04/discovery">
+    <soap:Header>
+        <wsa:To>urn:schemas-xmlsoap-org:ws:2005:04:discovery</wsa:To>
+        <wsa:Action>http://schemas.xmlsoap.org/ws/2005/04/discovery/Resolve</wsa:Action>
+        <wsa:MessageID>urn:uuid:{}</wsa:MessageID>
+    </soap:Header>
+    <soap:Body>
+        <wsd:Resolve>
+            <wsa:EndpointReference>
+                <wsa:Address>{}</wsa:Address>
+            </wsa:EndpointReference>
+        </wsd:Resolve>
+    </soap:Body>
+</soap:Envelope>"#,
+        message_id, endpoint
+    )
+}
+
+/// Liest ProbeMatch- bzw. ResolveMatch-Einträge aus einer Antwort
+pub fn parse_matches(xml: &str) -> Vec<ProbeMatch> {
+    if !xml.contains("ProbeMatches") && !xml.contains("ResolveMatches") {
+        return Vec::new();
+    }
+    xml.split("Match>")
+        .filter_map(|block| {
+            let endpoint = extract_tag(block, "Address")?;
+            Some(ProbeMatch {
+                endpoint,
+                types: extract_tag(block, "Types").unwrap_or_default(),
+                xaddrs: extract_tag(block, "XAddrs")
+                    .map(|xaddrs| xaddrs.split_whitespace().map(str::to_string).collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Sucht WSD-Scanner im lokalen Netz (Probe per Multicast, Antworten kommen per Unicast)
+pub async fn discover(wait: Duration) -> Result<Vec<DiscoveredScanner>, Box<dyn std::error::Error + Send + Sync>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.set_multicast_ttl_v4(4)?;
+    let target = SocketAddr::from((MULTICAST_ADDR, MULTICAST_PORT));
+
+    // UDP ist unzuverlässig: Probe wie in der Spezifikation empfohlen wiederholen
+    let probe = probe_message(&uuid::Uuid::new_v4().to_string());
+    for _ in 0..2 {
+        socket.send_to(probe.as_bytes(), target).await?;
+    }
+
+    let mut matches: HashMap<String, ProbeMatch> = HashMap::new();
+    let mut buffer = vec![0u8; 65535];
+    let deadline = tokio::time::Instant::now() + wait;
+    while let Ok(Ok((len, _))) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await {
+        let xml = String::from_utf8_lossy(&buffer[..len]);
+        for found in parse_matches(&xml) {
+            if !found.types.is_empty() && !found.types.contains(SCAN_DEVICE_TYPE) {
+                continue;
+            }
+            if found.xaddrs.is_empty() {
+                let resolve = resolve_message(&uuid::Uuid::new_v4().to_string(), &found.endpoint);
+                let _ = socket.send_to(resolve.as_bytes(), target).await;
+            }
+            // ResolveMatch ergänzt die Adressen eines bereits bekannten Geräts
+            let entry = matches.entry(found.endpoint.clone()).or_insert_with(|| found.clone());
+            if entry.xaddrs.is_empty() {
+                entry.xaddrs = found.xaddrs;
+            }
+        }
+    }
+
+    let mut scanners = Vec::new();
+    for found in matches.into_values() {
+        match device(&found).await {
+            Some(scanner) => scanners.push(scanner),
+            None => println!("⚠ WSD-Gerät {} ohne erreichbare Metadaten", found.endpoint),
+        }
+    }
+    Ok(scanners)
+}
+
+/// Fragt die Gerätemetadaten ab (WS-Transfer Get) und baut daraus den Scanner
+pub async fn device(found: &ProbeMatch) -> Option<DiscoveredScanner> {
+    let xaddr = pick_xaddr(&found.xaddrs)?;
+    let url = reqwest::Url::parse(&xaddr).ok()?;
+    let ip = url.host_str()?.trim_start_matches('[').trim_end_matches(']').to_string();
+    let port = url.port_or_known_default()?;
+
+    let client = crate::http_client::scanner(Duration::from_secs(5)).ok()?;
+    let metadata = client
+        .post(&xaddr)
+        .header("Content-Type", "application/soap+xml; charset=utf-8")
+        .body(get_message(&uuid::Uuid::new_v4().to_string(), &found.endpoint))
+        .send()
+        .await
+        .ok()?
+        .text()
+        .await
+        .ok()?;
+
+    let model = extract_tag(&metadata, "ModelName").unwrap_or_else(|| format!("WSD Scanner ({})", ip));
+    let name = extract_tag(&metadata, "FriendlyName").unwrap_or_else(|| model.clone());
+    let vendor = extract_tag(&metadata, "Manufacturer").unwrap_or_default();
+    // "Brother Industries, Ltd." → "Brother" (wie bei mDNS, damit Herstellerprofile greifen)
+    let manufacturer = match discovery::extract_manufacturer(&format!("{} {}", vendor, model)) {
+        unknown if unknown == "Unknown" && !vendor.is_empty() => vendor,
+        known => known,
+    };
+    let wsd_url = scan_service_address(&metadata).unwrap_or_else(|| xaddr.clone());
+    let id = found.endpoint.trim_start_matches("urn:uuid:").to_string();
+
+    println!("📡 WSD-Scanner entdeckt: {} @ {}:{}", model, ip, port);
+
+    Some(DiscoveredScanner {
+        id,
+        name,
+        manufacturer,
+        model,
+        ip,
+        port,
+        use_tls: url.scheme() == "https",
+        protocols: vec!["wsd".to_string()],
+        capabilities: ScannerCapabilities::default(),
+        discovery_method: "wsd".to_string(),
+        // eSCL-Pfad erst, wenn die Discovery am Gerät einen eSCL-Endpunkt gefunden hat
+        rs_path: String::new(),
+        wsd_url: Some(wsd_url),
+        endpoints: Vec::new(),
+        firmware: None,
+        serial_number: None,
+        mac_address: None,
+        location: None,
+        group: None,
+        admin_url: extract_tag(&metadata, "PresentationUrl").filter(|url| url.starts_with("http")),
+        icon_url: None,
+        icon: None,
+    })
+}
+
+/// Adresse des gehosteten Scan-Dienstes (Ziel für Push-Scan-Abos)
+pub fn scan_service_address(metadata: &str) -> Option<String> {
+    metadata
+        .split("Hosted>")
+        .find(|block| extract_tag(block, "Types").is_some_and(|types| types.contains("ScannerServiceType")))
+        .and_then(|block| extract_tag(block, "Address"))
+}
+
+/// IPv4-HTTP-Adresse bevorzugen (Link-Local-IPv6 braucht eine Scope-ID)
+fn pick_xaddr(xaddrs: &[String]) -> Option<String> {
+    xaddrs
+        .iter()
+        .find(|xaddr| xaddr.starts_with("http") && !xaddr.contains('['))
+        .or_else(|| xaddrs.iter().find(|xaddr| xaddr.starts_with("http")))
+        .cloned()
+}
+
+/// WS-Transfer Get für die Gerätemetadaten
+fn get_message(message_id: &str, endpoint: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"
+               xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing">
+    <soap:Header>
+        <wsa:To>{}</wsa:To>
+        <wsa:Action>http://schemas.xmlsoap.org/ws/2004/09/transfer/Get</wsa:Action>
+        <wsa:MessageID>urn:uuid:{}</wsa:MessageID>
+        <wsa:ReplyTo>
+            <wsa:Address>http://schemas.xmlsoap.org/ws/2004/08/addressing/role/anonymous</wsa:Address>
+        </wsa:ReplyTo>
+    </soap:Header>
+    <soap:Body/>
+</soap:Envelope>"#,
+        endpoint, message_id
+    )
+}
@@ -0,0 +1,58 @@
+// Integrationstests Audit-Log - HMAC-Kette, Manipulationserkennung und gebündelter Upload
+// Eigene Test-Binary mit eigenem Datenverzeichnis (XDG_CONFIG_HOME), da das Log global ist
+
+use docflow_bridge_core::audit::{self, AuditAction, AuditEntry};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn chain_detects_tampering_and_uploads_once() {
+    let data = tempfile::tempdir().expect("Temp-Ordner");
+    std::env::set_var("XDG_CONFIG_HOME", data.path());
+    let log = data.path().join("docflow-scanner-bridge").join("audit.jsonl");
+
+    audit::record(AuditAction::Paired, serde_json::json!({ "bridge_id": "b-1" }));
+    audit::record(AuditAction::ScanCompleted, serde_json::json!({ "job_id": "job-1", "scanner_id": "s-1" }));
+    audit::record(AuditAction::FileDeleted, serde_json::json!({ "path": "/scans/beleg.pdf" }));
+
+    let verification = audit::verify();
+    assert!(verification.valid, "{}", verification.message);
+    assert_eq!(verification.entries, 3);
+
+    // Alle Einträge genau einmal übertragen
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/audit"))
+        .and(body_partial_json(serde_json::json!({ "entries": [{ "seq": 1, "action": "paired" }] })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    assert_eq!(audit::upload_pending(&server.uri(), "test-api-key").await.unwrap(), 3);
+    assert_eq!(audit::upload_pending(&server.uri(), "test-api-key").await.unwrap(), 0);
+
+    // Nachträglich geänderter Eintrag bricht die Kette
+    let content = std::fs::read_to_string(&log).unwrap();
+    let mut entries: Vec<AuditEntry> = content.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(entries[1].prev_hash, entries[0].hash);
+    entries[1].details = serde_json::json!({ "job_id": "job-2", "scanner_id": "s-1" });
+    let tampered: String = entries.iter().map(|e| serde_json::to_string(e).unwrap() + "\n").collect();
+    std::fs::write(&log, tampered).unwrap();
+
+    let verification = audit::verify();
+    assert!(!verification.valid);
+    assert_eq!(verification.broken_at, Some(2));
+    assert!(audit::current_warning().is_some_and(|w| w.contains("Eintrag 2")));
+
+    // Ohne Schlüssel neu berechnete Kette (SHA256) wird ebenfalls erkannt
+    for index in 1..entries.len() {
+        entries[index].prev_hash = entries[index - 1].hash.clone();
+        entries[index].keyed = false;
+        entries[index].hash = audit::entry_hash(&entries[index]);
+    }
+    let recomputed: String = entries.iter().map(|e| serde_json::to_string(e).unwrap() + "\n").collect();
+    std::fs::write(&log, recomputed).unwrap();
+    let mut forged = entries[1].clone();
+    forged.keyed = true;
+    assert_ne!(audit::entry_hash(&forged), entries[1].hash);
+}
@@ -0,0 +1,54 @@
+// Integrationstests Audit-Log-Rotation - Kette und Upload laufen über die Dateigrenze hinweg weiter
+// Eigene Test-Binary mit eigenem Datenverzeichnis (XDG_CONFIG_HOME), da das Log global ist
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use docflow_bridge_core::audit::{self, AuditAction, AuditEntry};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+#[tokio::test]
+async fn rotated_log_continues_chain_and_upload() {
+    let data = tempfile::tempdir().expect("Temp-Ordner");
+    std::env::set_var("XDG_CONFIG_HOME", data.path());
+    let dir = data.path().join("docflow-scanner-bridge");
+    audit::set_key(b"test-audit-key");
+
+    // Große Details, damit die Rotationsgrenze mit wenigen Einträgen erreicht wird
+    let padding = "x".repeat(64 * 1024);
+    let mut recorded = 0;
+    while !dir.join("audit.1.jsonl").exists() {
+        audit::record(AuditAction::FileUploaded, serde_json::json!({ "path": padding }));
+        recorded += 1;
+    }
+    audit::record(AuditAction::FileDeleted, serde_json::json!({ "path": "/scans/beleg.pdf" }));
+    recorded += 1;
+
+    let rotated = std::fs::metadata(dir.join("audit.1.jsonl")).unwrap().len();
+    assert!(rotated >= audit::MAX_LOG_BYTES);
+    let current = std::fs::read_to_string(dir.join("audit.jsonl")).unwrap();
+    let first: AuditEntry = serde_json::from_str(current.lines().next().unwrap()).unwrap();
+    assert!(first.seq > 1, "aktuelles Log beginnt nach der Rotation nicht bei 1");
+
+    let verification = audit::verify();
+    assert!(verification.valid, "{}", verification.message);
+    assert!(audit::current_warning().is_none());
+
+    // Alle Einträge (rotiert und aktuell) genau einmal übertragen
+    let server = MockServer::start().await;
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/audit"))
+        .respond_with(move |request: &Request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            counter.fetch_add(body["entries"].as_array().unwrap().len(), Ordering::SeqCst);
+            ResponseTemplate::new(200)
+        })
+        .mount(&server)
+        .await;
+    assert_eq!(audit::upload_pending(&server.uri(), "test-api-key").await.unwrap(), recorded);
+    assert_eq!(audit::upload_pending(&server.uri(), "test-api-key").await.unwrap(), 0);
+    assert_eq!(received.load(Ordering::SeqCst), recorded);
+}
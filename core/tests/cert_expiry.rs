@@ -0,0 +1,51 @@
+// Integrationstests Zertifikatsablauf - notAfter lesen und Warnfrist von 14 Tagen
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use docflow_bridge_core::cert_expiry;
+
+/// Selbstsigniertes Zertifikat für "docflow.test", gültig bis 30.10.2026 12:00 UTC
+const CERTIFICATE: &str = "\
+MIIBgzCCASmgAwIBAgIUEkclVn6b4U7hvuZtdcNzPW13bPEwCgYIKoZIzj0EAwIwFzEVMBMGA1UE\
+AwwMZG9jZmxvdy50ZXN0MB4XDTI2MDEwMTAwMDAwMFoXDTI2MTAzMDEyMDAwMFowFzEVMBMGA1UE\
+AwwMZG9jZmxvdy50ZXN0MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE4owRZmQiLzu9g+vOq5+T\
+6RKxmFmrqtUpw1DWMCt5oEm+tVv0fbbGcN0VUptiLFvIy3m+bNw7LGWYvezY5beY5KNTMFEwHQYD\
+VR0OBBYEFO8AeJ/XCRj3uuV/LGZTL05cKTuaMB8GA1UdIwQYMBaAFO8AeJ/XCRj3uuV/LGZTL05c\
+KTuaMA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDSAAwRQIhALaehWCA8mFItX0GnYqeUOU3\
+LqvOZaCrwRQ8AVR8/IRHAiBXqIJ52otAWQej9IpfkHXCRavEx/UN4CHwIQlLwU/9fA==";
+
+fn at(rfc3339: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+}
+
+#[test]
+fn reads_not_after_from_certificate() {
+    let der = base64::engine::general_purpose::STANDARD.decode(CERTIFICATE).unwrap();
+    let (subject, not_after) = cert_expiry::parse(&der).unwrap();
+    assert_eq!(subject, "CN=docflow.test");
+    assert_eq!(not_after, at("2026-10-30T12:00:00Z"));
+
+    assert!(cert_expiry::parse(b"kein Zertifikat").is_err());
+}
+
+#[test]
+fn warns_within_fourteen_days() {
+    let not_after = at("2026-10-30T12:00:00Z");
+    let expiry = |now: &str| cert_expiry::expiry_at("docflow.test:443", "CN=docflow.test", not_after, at(now));
+
+    let early = expiry("2026-10-15T11:59:59Z");
+    assert_eq!(early.days_left, 15);
+    assert!(!early.expiring);
+
+    let due = expiry("2026-10-16T12:00:00Z");
+    assert_eq!(due.days_left, 14);
+    assert!(due.expiring);
+    assert!(due.message().contains("docflow.test:443 läuft in 14 Tagen ab"), "{}", due.message());
+
+    assert!(expiry("2026-10-30T08:00:00Z").message().contains("läuft heute ab"));
+
+    let expired = expiry("2026-10-31T00:00:00Z");
+    assert_eq!(expired.days_left, -1);
+    assert!(expired.expiring);
+    assert!(expired.message().contains("abgelaufen"), "{}", expired.message());
+}
@@ -0,0 +1,61 @@
+// Integrationstests Circuit-Breaker - Pause des DocFlow-Verkehrs nach wiederholten Serverfehlern
+// Eigene Test-Binary, da die Breaker-Konfiguration global ist
+
+use std::time::Duration;
+
+use docflow_bridge_core::circuit_breaker::{self, CircuitBreakerConfig};
+use docflow_bridge_core::rate_limit::RateLimited;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn status(server: &MockServer) -> Result<u16, String> {
+    docflow_bridge_core::http_client::docflow()
+        .get(format!("{}/api/scanner/bridge/status", server.uri()))
+        .send_limited()
+        .await
+        .map(|r| r.status().as_u16())
+        .map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+async fn outage_pauses_traffic_until_probe_succeeds() {
+    circuit_breaker::apply_config(&CircuitBreakerConfig {
+        failure_threshold: 3,
+        cooldown_secs: 1,
+        max_cooldown_secs: 1,
+    });
+    let server = MockServer::start().await;
+
+    let outage = Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/status"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(4)
+        .mount_as_scoped(&server)
+        .await;
+
+    for _ in 0..3 {
+        assert_eq!(status(&server).await, Ok(503));
+    }
+    // Pausiert: Anfrage erreicht den Server nicht
+    let error = status(&server).await.expect_err("Verkehr pausiert");
+    assert!(error.contains(circuit_breaker::PAUSED), "{}", error);
+    assert_eq!(circuit_breaker::paused().len(), 1);
+
+    // Fehlgeschlagene Probe → erneute Pause
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(status(&server).await, Ok(503));
+    assert!(status(&server).await.is_err());
+    drop(outage);
+
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/status"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    // Erfolgreiche Probe → Verkehr läuft wieder
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(status(&server).await, Ok(200));
+    assert_eq!(status(&server).await, Ok(200));
+    assert!(circuit_breaker::paused().is_empty());
+}
@@ -0,0 +1,75 @@
+// Integrationstests Zwischenablage - Umwandlung der Bildpixel in PNG bzw. PDF und Upload über den Folder-Upload-Endpunkt
+
+use docflow_bridge_core::clipboard::{self, ClipboardFormat};
+use docflow_bridge_core::imaging;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// 2 x 1 Pixel: links deckend rot, rechts vollständig transparent (schwarz)
+fn pixels() -> Vec<u8> {
+    vec![255, 0, 0, 255, 0, 0, 0, 0]
+}
+
+#[test]
+fn png_keeps_pixels_and_transparency() {
+    let (data, filename, mime_type) = clipboard::convert(2, 1, pixels(), ClipboardFormat::Png).expect("PNG");
+
+    assert_eq!(mime_type, "image/png");
+    assert!(filename.starts_with("zwischenablage-") && filename.ends_with(".png"), "{}", filename);
+    let image = image::load_from_memory(&data).expect("PNG lesbar").to_rgba8();
+    assert_eq!(image.dimensions(), (2, 1));
+    assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(1, 0).0[3], 0);
+}
+
+#[test]
+fn pdf_puts_transparent_areas_on_white() {
+    let width = 16;
+    let rgba: Vec<u8> = (0..width * width).flat_map(|_| [0, 0, 0, 0]).collect();
+    let (data, filename, mime_type) = clipboard::convert(width, width, rgba, ClipboardFormat::Pdf).expect("PDF");
+
+    assert_eq!(mime_type, "application/pdf");
+    assert!(filename.ends_with(".pdf"), "{}", filename);
+    let pages = imaging::extract_pdf_jpegs(&data);
+    assert_eq!(pages.len(), 1);
+    let page = image::load_from_memory(&pages[0]).expect("JPEG").to_rgb8();
+    assert_eq!(page.dimensions(), (width, width));
+    assert!(page.get_pixel(8, 8).0.iter().all(|c| *c > 245), "{:?}", page.get_pixel(8, 8));
+}
+
+#[test]
+fn pixel_data_not_matching_the_size_is_rejected() {
+    let error = clipboard::convert(3, 3, pixels(), ClipboardFormat::Pdf).unwrap_err();
+    assert!(error.to_string().contains("Ungültige Bilddaten"), "{}", error);
+}
+
+#[tokio::test]
+async fn clipboard_image_is_uploaded_as_folder_upload() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "success": true,
+            "job_id": 31,
+            "filename": "zwischenablage.png",
+            "file_size_mb": 0.0,
+            "duplicate": false,
+            "message": "OK",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = clipboard::upload(&server.uri(), "test-api-key", 2, 1, pixels(), ClipboardFormat::Png)
+        .await
+        .expect("Upload");
+    assert_eq!(result.job_id, 31);
+
+    // Binärer Body: Teile-Header direkt in den Bytes suchen
+    let requests = server.received_requests().await.unwrap_or_default();
+    let upload = requests.iter().find(|r| r.url.path() == "/api/scanner/bridge/folder-upload").expect("Upload");
+    let contains = |needle: &str| upload.body.windows(needle.len()).any(|w| w == needle.as_bytes());
+    assert!(contains("filename=\"zwischenablage-"));
+    assert!(contains("Content-Type: image/png"));
+    assert!(contains("Zwischenablage"));
+}
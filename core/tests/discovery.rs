@@ -0,0 +1,80 @@
+// Integrationstests Endpoint-Auswahl - festgelegte Endpoints und automatische Auswahl nach Bewertung
+// Wechsel der Erreichbarkeit lösen die Meldung der Scanner-Liste an DocFlow aus
+
+use docflow_bridge_core::discovery::{self, DiscoveryConfig, ScannerEndpoint};
+use docflow_bridge_core::scanner_sync;
+use docflow_bridge_core::virtual_scanner::{self, VirtualScannerConfig};
+
+fn endpoint(ip: &str, port: u16, use_tls: bool) -> ScannerEndpoint {
+    ScannerEndpoint {
+        ip: ip.to_string(),
+        port,
+        use_tls,
+        score: 0,
+    }
+}
+
+#[test]
+fn unpinning_restores_the_best_endpoint() {
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.id = "mfp".to_string();
+    scanner.ip = "192.168.1.20".to_string();
+    scanner.port = 443;
+    scanner.use_tls = true;
+    scanner.endpoints = vec![endpoint("192.168.1.20", 443, true), endpoint("fe80::1", 80, false)];
+    let mut scanners = vec![scanner];
+
+    let mut config = DiscoveryConfig::default();
+    config.pinned_endpoints.insert("mfp".to_string(), endpoint("fe80::1", 80, false));
+    discovery::apply_scanner_config(&mut scanners, &config);
+    assert_eq!((scanners[0].ip.as_str(), scanners[0].port, scanners[0].use_tls), ("fe80::1", 80, false));
+
+    // Festlegung aufheben: wieder der am besten bewertete Endpoint
+    config.pinned_endpoints.clear();
+    discovery::apply_scanner_config(&mut scanners, &config);
+    assert_eq!((scanners[0].ip.as_str(), scanners[0].port, scanners[0].use_tls), ("192.168.1.20", 443, true));
+    assert!(scanners[0].endpoints.iter().all(|e| e.score != 0));
+}
+
+#[test]
+fn equally_scored_endpoint_is_kept() {
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.ip = "192.168.1.21".to_string();
+    scanner.port = 80;
+    scanner.use_tls = false;
+    scanner.endpoints = vec![endpoint("192.168.1.20", 80, false), endpoint("192.168.1.21", 80, false)];
+    let mut scanners = vec![scanner];
+
+    discovery::apply_scanner_config(&mut scanners, &DiscoveryConfig::default());
+    assert_eq!(scanners[0].ip, "192.168.1.21");
+}
+
+#[tokio::test]
+async fn reachability_changes_trigger_a_scanner_sync() {
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.id = "health-check".to_string();
+    scanner.discovery_method = "escl".to_string();
+    let wait = scanner_sync::DEBOUNCE * 3;
+
+    discovery::mark_reachable(&scanner.id, true);
+    tokio::time::timeout(wait, scanner_sync::changed()).await.expect("Wechsel auf online nicht gemeldet");
+    assert_eq!(scanner_sync::payload(std::slice::from_ref(&scanner), &[])["scanners"][0]["online"], true);
+
+    // Gleicher Zustand: keine erneute Meldung
+    discovery::mark_reachable(&scanner.id, true);
+    assert!(tokio::time::timeout(wait, scanner_sync::changed()).await.is_err());
+
+    discovery::mark_reachable(&scanner.id, false);
+    tokio::time::timeout(wait, scanner_sync::changed()).await.expect("Wechsel auf offline nicht gemeldet");
+    assert_eq!(scanner_sync::payload(&[scanner], &[])["scanners"][0]["online"], false);
+}
+
+#[test]
+fn duplicate_hosts_are_removed_anywhere_in_the_list() {
+    let hosts = discovery::parse_host_list("192.168.1.20\n192.168.1.21:8080\n192.168.1.20\n192.168.1.21\n").expect("Hosts");
+    let hosts: Vec<(&str, Option<u16>)> = hosts.iter().map(|h| (h.host.as_str(), h.port)).collect();
+    assert_eq!(
+        hosts,
+        vec![("192.168.1.20", None), ("192.168.1.21", Some(8080)), ("192.168.1.21", None)]
+    );
+}
@@ -0,0 +1,337 @@
+// Integrationstests DocFlow-Protokoll - Pairing, Job-Polling und Scan-Upload gegen einen Mock-Server
+// Erfolgreiches Pairing wird nicht getestet, da es API-Key und URL in den echten Keyring schreibt
+
+use std::sync::Arc;
+
+use docflow_bridge_core::dedup::{self, Channel};
+use docflow_bridge_core::pairing;
+use docflow_bridge_core::scan_poller::{ScanDocument, ScanPoller};
+use docflow_bridge_core::scanner_sync;
+use docflow_bridge_core::settings::BridgeSettings;
+use docflow_bridge_core::spool;
+use docflow_bridge_core::upload_progress;
+use tokio::sync::RwLock;
+use wiremock::matchers::{body_partial_json, body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "test-api-key";
+
+fn poller(server: &MockServer) -> ScanPoller {
+    ScanPoller::new(
+        API_KEY.to_string(),
+        server.uri(),
+        Arc::new(RwLock::new(Vec::new())),
+        Arc::new(RwLock::new(BridgeSettings::default())),
+    )
+}
+
+#[tokio::test]
+async fn manual_code_is_resolved_before_registration() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/resolve-code"))
+        .and(body_partial_json(serde_json::json!({ "code": "ABCD-EFGH-IJKL" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "docflow_url": "http://docflow.invalid",
+            "tenant_id": 7,
+            "pairing_token": "token-123",
+            "bridge_name": "Empfang",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Registrierung muss an die vom Benutzer angegebene URL gehen, nicht an die aus der Antwort
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/register"))
+        .and(body_partial_json(serde_json::json!({
+            "pairing_token": "token-123",
+            "bridge_name": "Empfang",
+        })))
+        .respond_with(ResponseTemplate::new(403).set_body_string("Token abgelaufen"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = pairing::pair("ABCD-EFGH-IJKL", Some(&server.uri()))
+        .await
+        .expect_err("Registrierung wurde abgelehnt");
+    assert!(error.to_string().contains("Token abgelaufen"), "{}", error);
+}
+
+#[tokio::test]
+async fn manual_code_requires_docflow_url() {
+    let error = pairing::pair("ABCD-EFGH-IJKL", None).await.expect_err("URL fehlt");
+    assert!(error.to_string().contains("DocFlow-URL"), "{}", error);
+}
+
+#[tokio::test]
+async fn validate_connection_sends_bearer_token() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/status"))
+        .and(header("Authorization", format!("Bearer {}", API_KEY).as_str()))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/status"))
+        .respond_with(ResponseTemplate::new(401))
+        .with_priority(10)
+        .mount(&server)
+        .await;
+
+    assert!(pairing::validate_connection(API_KEY, &server.uri()).await);
+    assert!(!pairing::validate_connection("revoked-key", &server.uri()).await);
+}
+
+#[tokio::test]
+async fn requests_carry_request_id_and_user_agent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/status"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    assert!(pairing::validate_connection(API_KEY, &server.uri()).await);
+    assert!(pairing::validate_connection(API_KEY, &server.uri()).await);
+
+    let requests = server.received_requests().await.expect("Aufzeichnung aktiv");
+    let request_ids: Vec<_> = requests
+        .iter()
+        .map(|r| r.headers.get("x-request-id").expect("X-Request-Id fehlt").to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(request_ids.len(), 2);
+    assert_ne!(request_ids[0], request_ids[1], "IDs müssen je Anfrage eindeutig sein");
+
+    let user_agent = requests[0].headers.get("user-agent").expect("User-Agent fehlt").to_str().unwrap();
+    assert!(user_agent.starts_with("DocFlow-Scanner-Bridge/"), "{}", user_agent);
+    assert!(user_agent.contains(env!("CARGO_PKG_VERSION")), "{}", user_agent);
+}
+
+#[tokio::test]
+async fn poll_returns_pending_jobs() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/pending-scans"))
+        .and(header("Authorization", format!("Bearer {}", API_KEY).as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jobs": [
+                {
+                    "job_id": "job-1",
+                    "scanner_id": "192.168.1.20:80",
+                    "resolution": 300,
+                    "source": "adf",
+                    "priority": 2,
+                    "created_at": "2026-01-01T10:00:00Z",
+                    "expires_at": "2026-01-01T10:10:00Z",
+                },
+                {
+                    "job_id": "job-2",
+                    "scanner_id": "192.168.1.21:80",
+                    "created_at": "2026-01-01T10:01:00Z",
+                    "expires_at": "2026-01-01T10:11:00Z",
+                }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let poller = poller(&server);
+    let jobs = poller.poll_pending_jobs().await.expect("Polling erfolgreich");
+
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].job_id, "job-1");
+    assert_eq!(jobs[0].source, "adf");
+    assert_eq!(jobs[0].priority, 2);
+
+    // Fehlende Werte werden beim Auflösen mit Defaults gefüllt
+    let resolved = poller.resolve_job(&jobs[1]).await.expect("ohne Profil auflösbar");
+    assert_eq!(resolved.resolution, 300);
+    assert_eq!(resolved.source, "flatbed");
+    assert_eq!(resolved.format, "pdf");
+}
+
+#[tokio::test]
+async fn poll_reuses_jobs_on_not_modified() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/pending-scans"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/pending-scans"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(serde_json::json!({
+                    "jobs": [{
+                        "job_id": "job-1",
+                        "scanner_id": "192.168.1.20:80",
+                        "created_at": "2026-01-01T10:00:00Z",
+                        "expires_at": "2026-01-01T10:10:00Z",
+                    }]
+                })),
+        )
+        .with_priority(10)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let poller = poller(&server);
+    let first = poller.poll_pending_jobs().await.expect("erste Abfrage");
+    let second = poller.poll_pending_jobs().await.expect("304 verwendet die letzte Liste");
+
+    assert_eq!(first.len(), 1);
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].job_id, "job-1");
+}
+
+#[tokio::test]
+async fn poll_reports_server_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/pending-scans"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("Datenbank nicht erreichbar"))
+        .mount(&server)
+        .await;
+
+    let error = poller(&server).poll_pending_jobs().await.expect_err("HTTP 500");
+    assert!(error.to_string().contains("Datenbank nicht erreichbar"), "{}", error);
+}
+
+#[tokio::test]
+async fn scan_result_is_uploaded_as_multipart() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/scan-upload/job-1"))
+        .and(header("Authorization", format!("Bearer {}", API_KEY).as_str()))
+        .and(body_string_contains("filename=\"scan_2.pdf\""))
+        .and(body_string_contains("%PDF-1.4 test"))
+        .and(body_string_contains("name=\"split_count\""))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let document = ScanDocument {
+        data: spool::Buffer::Memory(b"%PDF-1.4 test".to_vec()),
+        barcodes: Vec::new(),
+        incomplete: false,
+        renditions: Vec::new(),
+    };
+
+    let mut progress = upload_progress::subscribe();
+
+    poller(&server)
+        .upload_scan_result("job-1", document, Some((1, 2)))
+        .await
+        .expect("Upload erfolgreich");
+
+    // Fortschritt wird für die Datei gemeldet, die letzte Meldung deckt alle Bytes ab
+    let update = progress.try_recv().expect("Fortschritt gemeldet");
+    assert_eq!(update.id, "job-1");
+    assert_eq!(update.file_name, "scan_2.pdf");
+    assert_eq!(update.bytes_sent, update.total_bytes);
+    assert_eq!(update.total_bytes, b"%PDF-1.4 test".len() as u64);
+}
+
+#[tokio::test]
+async fn scan_already_uploaded_from_folder_is_flagged() {
+    let server = MockServer::start().await;
+
+    // Eindeutiger Inhalt, der Hash-Speicher ist global und übersteht Testläufe
+    let content = format!("%PDF-1.4 Ordner-Scan {}", uuid::Uuid::new_v4());
+    let file_hash = dedup::hash(content.as_bytes());
+    dedup::record(&file_hash, Channel::Folder, "/scans/rechnung.pdf");
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/scan-upload/job-2"))
+        .and(body_string_contains(file_hash.as_str()))
+        .and(body_string_contains("name=\"duplicate_of\""))
+        .and(body_string_contains("/scans/rechnung.pdf"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Job-Scans werden trotz Duplikat hochgeladen, DocFlow wartet auf das Ergebnis
+    let document = ScanDocument {
+        data: spool::Buffer::Memory(content.into_bytes()),
+        barcodes: Vec::new(),
+        incomplete: false,
+        renditions: Vec::new(),
+    };
+    poller(&server)
+        .upload_scan_result("job-2", document, None)
+        .await
+        .expect("Upload erfolgreich");
+}
+
+#[tokio::test]
+async fn scanner_changes_are_bundled_and_sent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/scanners"))
+        .and(header("Authorization", format!("Bearer {}", API_KEY).as_str()))
+        .and(body_partial_json(serde_json::json!({ "scanners": [] })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Mehrere Änderungen kurz hintereinander ergeben genau einen Durchlauf
+    for _ in 0..3 {
+        scanner_sync::mark_changed();
+    }
+    tokio::time::timeout(scanner_sync::DEBOUNCE * 3, scanner_sync::changed())
+        .await
+        .expect("Änderung nicht gemeldet");
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_millis(200), scanner_sync::changed()).await.is_err(),
+        "gebündelte Änderungen wurden mehrfach gemeldet"
+    );
+
+    scanner_sync::send(&server.uri(), API_KEY, &scanner_sync::payload(&[], &[]))
+        .await
+        .expect("Scanner-Liste senden");
+}
+
+#[tokio::test]
+async fn repeated_job_errors_are_reported_once() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/scan-upload/job-err"))
+        .and(body_string_contains("Scanner nicht erreichbar"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/scan-upload/job-err"))
+        .and(body_string_contains("Papierstau"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Gleicher Fehler bei jedem Wiederholungsversuch: nur die erste Meldung geht an DocFlow
+    let poller = poller(&server);
+    for _ in 0..3 {
+        poller.report_error("job-err", "Scanner nicht erreichbar").await.expect("Meldung");
+    }
+    poller.report_error("job-err", "Papierstau").await.expect("Meldung");
+}
@@ -0,0 +1,365 @@
+// Integrationstests eSCL - Scan-Ablauf (ScannerStatus, ScanJobs, NextDocument) und Probescan gegen einen Mock-Scanner
+
+use docflow_bridge_core::discovery::{self, DiscoveredScanner};
+use docflow_bridge_core::quirks::QuirkProfile;
+use docflow_bridge_core::scanner::{self, ScanJob};
+use docflow_bridge_core::self_test::CheckStatus;
+use docflow_bridge_core::test_scan;
+use docflow_bridge_core::virtual_scanner::{self, VirtualScannerConfig};
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IDLE_STATUS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<scan:ScannerStatus xmlns:scan="http://schemas.hp.com/imaging/escl/2011/05/03" xmlns:pwg="http://www.pwg.org/schemas/2010/12/sm">
+    <pwg:Version>2.0</pwg:Version>
+    <pwg:State>Idle</pwg:State>
+</scan:ScannerStatus>"#;
+
+fn job(source: &str) -> ScanJob {
+    ScanJob {
+        scanner_id: "mock".to_string(),
+        resolution: 150,
+        color_mode: "grayscale".to_string(),
+        format: "application/pdf".to_string(),
+        source: source.to_string(),
+        duplex: false,
+        page_retries: 0,
+        allow_partial: false,
+        height: None,
+        regions: Vec::new(),
+        timeout_secs: 30,
+        spool_after_kb: 0,
+    }
+}
+
+fn port(server: &MockServer) -> u16 {
+    server.address().port()
+}
+
+async fn mount_status(server: &MockServer, body: &str) {
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScannerStatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(server)
+        .await;
+}
+
+async fn mount_job_creation(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .and(body_string_contains("<pwg:InputSource>Feeder</pwg:InputSource>"))
+        .and(body_string_contains("<scan:ColorMode>Grayscale8</scan:ColorMode>"))
+        .respond_with(
+            ResponseTemplate::new(201)
+                .insert_header("Location", format!("{}/eSCL/ScanJobs/42", server.uri()).as_str()),
+        )
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn adf_scan_collects_pages_until_404() {
+    let server = MockServer::start().await;
+    mount_status(&server, IDLE_STATUS).await;
+    mount_job_creation(&server).await;
+
+    // Zwei Seiten, danach meldet der Scanner "keine weiteren Dokumente"
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/42/NextDocument"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4 page".to_vec()))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/42/NextDocument"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let result = scanner::scan_escl("127.0.0.1", port(&server), &job("adf"))
+        .await
+        .expect("Scan erfolgreich");
+
+    assert_eq!(result.total_pages, 2);
+    assert!(!result.incomplete);
+    assert_eq!(result.pages[0].page_number, 1);
+    assert_eq!(result.pages[1].size_bytes, b"%PDF-1.4 page".len());
+}
+
+#[tokio::test]
+async fn finished_jobs_are_cleaned_up_but_running_jobs_are_kept() {
+    let server = MockServer::start().await;
+    mount_status(
+        &server,
+        r#"<scan:ScannerStatus>
+    <pwg:State>Idle</pwg:State>
+    <scan:Jobs>
+        <scan:JobInfo>
+            <pwg:JobUri>/eSCL/ScanJobs/7</pwg:JobUri>
+            <pwg:JobState>Completed</pwg:JobState>
+        </scan:JobInfo>
+        <scan:JobInfo>
+            <pwg:JobUri>/eSCL/ScanJobs/8</pwg:JobUri>
+            <pwg:JobState>Processing</pwg:JobState>
+        </scan:JobInfo>
+    </scan:Jobs>
+</scan:ScannerStatus>"#,
+    )
+    .await;
+    mount_job_creation(&server).await;
+
+    Mock::given(method("DELETE"))
+        .and(path("/eSCL/ScanJobs/7"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/eSCL/ScanJobs/8"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/42/NextDocument"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let result = scanner::scan_escl("127.0.0.1", port(&server), &job("adf"))
+        .await
+        .expect("Scan erfolgreich");
+    assert_eq!(result.total_pages, 0);
+}
+
+#[tokio::test]
+async fn aborted_scan_deletes_the_job_on_the_scanner() {
+    let server = MockServer::start().await;
+    mount_status(&server, IDLE_STATUS).await;
+    mount_job_creation(&server).await;
+    // Seite wird nie fertig
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/42/NextDocument"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/eSCL/ScanJobs/42"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut job = job("adf");
+    job.scanner_id = "abort".to_string();
+    let scan_port = port(&server);
+    let task = tokio::spawn(async move { scanner::scan_escl("127.0.0.1", scan_port, &job).await });
+    let job_url = loop {
+        if let Some(url) = scanner::running_job_url("abort") {
+            break url;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    };
+    assert_eq!(job_url, format!("{}/eSCL/ScanJobs/42", server.uri()));
+
+    // Wie beim Abbruch in der Bridge: Task beenden, danach den Job am Gerät löschen
+    task.abort();
+    let _ = task.await;
+    assert_eq!(scanner::running_job_url("abort"), None);
+    scanner::cancel_running_job(&job_url).await.expect("Job abgebrochen");
+}
+
+#[tokio::test]
+async fn rejected_scan_job_is_an_error() {
+    let server = MockServer::start().await;
+    mount_status(&server, IDLE_STATUS).await;
+
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .respond_with(ResponseTemplate::new(400))
+        .mount(&server)
+        .await;
+
+    let error = scanner::scan_escl("127.0.0.1", port(&server), &job("flatbed"))
+        .await
+        .expect_err("Job abgelehnt");
+    assert!(error.to_string().contains("400"), "{}", error);
+}
+
+#[tokio::test]
+async fn scanner_state_reads_pwg_state() {
+    let server = MockServer::start().await;
+    mount_status(&server, IDLE_STATUS).await;
+
+    let state = scanner::scanner_state("127.0.0.1", port(&server), false, "eSCL")
+        .await
+        .expect("Status lesbar");
+    assert_eq!(state, "Idle");
+}
+
+#[tokio::test]
+async fn status_probes_update_reachability() {
+    // Nicht aus dem Pool, damit der Port nach drop wirklich geschlossen ist
+    let server = MockServer::builder().start().await;
+    let mut scanner = mock_scanner(&server);
+    scanner.id = "reachability".to_string();
+    let online = |scanner: &DiscoveredScanner| discovery::with_reachability(std::slice::from_ref(scanner))[0].online;
+    let probe = |port: u16| async move { scanner::scanner_state("127.0.0.1", port, false, "eSCL").await };
+
+    // Antwort ohne Zustand: Fehler, aber das Gerät ist erreichbar
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScannerStatus"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    discovery::mark_reachable(&scanner.id, true);
+    let result = probe(scanner.port).await;
+    assert!(result.is_err());
+    discovery::mark_result(&scanner.id, &result);
+    assert!(online(&scanner));
+
+    // Keine Verbindung: offline
+    drop(server);
+    let result = probe(scanner.port).await;
+    discovery::mark_result(&scanner.id, &result);
+    assert!(!online(&scanner));
+
+    // Erfolgreiche Abfrage: wieder online
+    let server = MockServer::start().await;
+    mount_status(&server, IDLE_STATUS).await;
+    let result = probe(port(&server)).await;
+    discovery::mark_result(&scanner.id, &result);
+    assert!(online(&scanner));
+}
+
+fn mock_scanner(server: &MockServer) -> DiscoveredScanner {
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.id = "mock".to_string();
+    scanner.port = port(server);
+    scanner.rs_path = "eSCL".to_string();
+    scanner.discovery_method = "manual".to_string();
+    scanner
+}
+
+#[tokio::test]
+async fn test_scan_measures_phases_and_deletes_job() {
+    let server = MockServer::start().await;
+    mount_status(&server, IDLE_STATUS).await;
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .and(body_string_contains("<scan:XResolution>75</scan:XResolution>"))
+        .and(body_string_contains("<pwg:InputSource>Platen</pwg:InputSource>"))
+        .respond_with(
+            ResponseTemplate::new(201)
+                .insert_header("Location", format!("{}/eSCL/ScanJobs/7", server.uri()).as_str()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/7/NextDocument"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0xFF; 2048]))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/eSCL/ScanJobs/7"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = test_scan::run(&mock_scanner(&server)).await;
+
+    assert!(report.passed, "{:?}", report.phases);
+    assert_eq!(report.page_bytes, 2048);
+    let names: Vec<&str> = report.phases.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["status", "job", "transfer"]);
+    assert!(report.phases.iter().all(|p| p.raw_response.is_none()));
+}
+
+#[tokio::test]
+async fn test_scan_reports_raw_response_of_rejected_job() {
+    let server = MockServer::start().await;
+    mount_status(&server, IDLE_STATUS).await;
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("<Error>Unsupported resolution</Error>"))
+        .mount(&server)
+        .await;
+
+    let report = test_scan::run(&mock_scanner(&server)).await;
+
+    assert!(!report.passed);
+    let job = &report.phases[1];
+    assert_eq!(job.status, CheckStatus::Failed);
+    assert_eq!(job.http_status, Some(400));
+    assert_eq!(job.raw_response.as_deref(), Some("<Error>Unsupported resolution</Error>"));
+    assert_eq!(report.phases[2].status, CheckStatus::Skipped);
+}
+
+#[tokio::test]
+async fn scanner_jobs_are_listed_and_cleared_individually() {
+    let server = MockServer::start().await;
+    mount_status(
+        &server,
+        &format!(
+            r#"<scan:ScannerStatus xmlns:scan="http://schemas.hp.com/imaging/escl/2011/05/03" xmlns:pwg="http://www.pwg.org/schemas/2010/12/sm">
+    <pwg:State>Processing</pwg:State>
+    <scan:Jobs>
+        <scan:JobInfo>
+            <pwg:JobUri>{}/eSCL/ScanJobs/7</pwg:JobUri>
+            <scan:Age>125</scan:Age>
+            <pwg:ImagesCompleted>2</pwg:ImagesCompleted>
+            <pwg:JobState>Completed</pwg:JobState>
+            <pwg:JobStateReasons><pwg:JobStateReason>JobCompletedSuccessfully</pwg:JobStateReason></pwg:JobStateReasons>
+        </scan:JobInfo>
+        <scan:JobInfo>
+            <pwg:JobUri>/eSCL/ScanJobs/8</pwg:JobUri>
+            <pwg:JobState>Processing</pwg:JobState>
+        </scan:JobInfo>
+    </scan:Jobs>
+</scan:ScannerStatus>"#,
+            server.uri()
+        ),
+    )
+    .await;
+    Mock::given(method("DELETE"))
+        .and(path("/eSCL/ScanJobs/7"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/eSCL/ScanJobs/8"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let jobs = scanner::scanner_jobs("127.0.0.1", port(&server), false, "eSCL", &QuirkProfile::default())
+        .await
+        .expect("Job-Liste lesbar");
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].job_uri, "/eSCL/ScanJobs/7");
+    assert_eq!(jobs[0].state, "Completed");
+    assert_eq!(jobs[0].age_secs, Some(125));
+    assert_eq!(jobs[0].images_completed, Some(2));
+    assert_eq!(jobs[0].reason.as_deref(), Some("JobCompletedSuccessfully"));
+    assert!(jobs[1].is_running());
+
+    let quirks = QuirkProfile::default();
+    scanner::clear_job("127.0.0.1", port(&server), false, "eSCL", &quirks, &jobs[0].job_uri)
+        .await
+        .expect("Job gelöscht");
+    // Laufender Job wird nicht gelöscht
+    assert!(scanner::clear_job("127.0.0.1", port(&server), false, "eSCL", &quirks, &jobs[1].job_uri)
+        .await
+        .is_err());
+    // Nur Job-Pfade des Scanners, keine beliebigen URLs
+    assert!(scanner::clear_job("127.0.0.1", port(&server), false, "eSCL", &quirks, "http://example.com/eSCL/ScanJobs/1")
+        .await
+        .is_err());
+}
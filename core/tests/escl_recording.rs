@@ -0,0 +1,113 @@
+// Integrationstests eSCL-Aufzeichnung - Scan gegen Mock-Scanner aufzeichnen, bereinigen und wiedergeben
+
+use docflow_bridge_core::escl_recording::{self, RecordingConfig};
+use docflow_bridge_core::scanner::{self, ScanJob};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn page_jpeg() -> Vec<u8> {
+    let page = image::RgbImage::from_fn(120, 160, |x, y| image::Rgb([(x * 2) as u8, (y % 255) as u8, 40]));
+    let mut data = Vec::new();
+    image::DynamicImage::ImageRgb8(page)
+        .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Jpeg)
+        .expect("JPEG");
+    data
+}
+
+async fn mock_scanner() -> MockServer {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScannerStatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<scan:ScannerStatus><pwg:State>Idle</pwg:State>\
+             <pwg:SerialNumber>CN12345678</pwg:SerialNumber></scan:ScannerStatus>",
+            "text/xml",
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .respond_with(
+            ResponseTemplate::new(201).insert_header("Location", format!("{}/eSCL/ScanJobs/5", server.uri()).as_str()),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/5/NextDocument"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(page_jpeg(), "image/jpeg"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/5/NextDocument"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    server
+}
+
+#[tokio::test]
+async fn recorded_scan_is_sanitized_and_replayable() {
+    let server = mock_scanner().await;
+    let directory = tempfile::tempdir().expect("Temp-Ordner");
+    escl_recording::apply_config(&RecordingConfig {
+        enabled: true,
+        include_documents: false,
+        directory: Some(directory.path().to_string_lossy().to_string()),
+    });
+
+    let job = ScanJob {
+        scanner_id: "192.168.1.20:80".to_string(),
+        resolution: 150,
+        color_mode: "color".to_string(),
+        format: "image/jpeg".to_string(),
+        source: "flatbed".to_string(),
+        duplex: false,
+        page_retries: 0,
+        allow_partial: false,
+        height: None,
+        regions: Vec::new(),
+        timeout_secs: 30,
+        spool_after_kb: 0,
+    };
+    let original = scanner::scan_escl("127.0.0.1", server.address().port(), &job)
+        .await
+        .expect("Scan erfolgreich");
+    escl_recording::apply_config(&RecordingConfig::default());
+
+    let file = std::fs::read_dir(directory.path())
+        .expect("Ordner lesbar")
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .expect("Aufzeichnung geschrieben");
+    let raw = std::fs::read_to_string(&file).expect("Datei lesbar");
+
+    // Bereinigung: keine Seriennummer, keine Adresse, keine Scanner-ID
+    assert!(!raw.contains("CN12345678"));
+    assert!(!raw.contains("127.0.0.1"));
+    assert!(!raw.contains("192.168.1.20"));
+
+    let recording = escl_recording::load(&file).expect("Aufzeichnung lesbar");
+    let create = recording
+        .exchanges
+        .iter()
+        .find(|e| e.method == "POST")
+        .expect("ScanJobs aufgezeichnet");
+    assert!(create
+        .headers
+        .iter()
+        .any(|(name, value)| name == "location" && value == "/eSCL/ScanJobs/5"));
+
+    // Wiedergabe: gleiche Seitenzahl, Seite durch leere Seite gleicher Größe ersetzt
+    let replayed = escl_recording::replay(&recording).await.expect("Wiedergabe erfolgreich");
+    assert_eq!(replayed.total_pages, original.total_pages);
+
+    let data = replayed.pages[0].data.read().expect("Seitendaten");
+    let page = image::load_from_memory(&data).expect("JPEG");
+    assert_eq!((page.width(), page.height()), (120, 160));
+    assert_ne!(data, page_jpeg());
+}
@@ -0,0 +1,65 @@
+// Integrationstests Datei-Gruppen - Musterabgleich, Zeitfenster und Zusammenführen der Teil-PDFs
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use docflow_bridge_core::file_grouping::{self, GroupRule, GroupingConfig};
+use docflow_bridge_core::{imaging, pdf};
+
+fn rule(pattern: &str, window_secs: u64) -> GroupRule {
+    GroupRule { pattern: pattern.to_string(), window_secs }
+}
+
+fn page_pdf(shade: u8) -> Vec<u8> {
+    let image = image::RgbImage::from_pixel(120, 160, image::Rgb([shade, shade, shade]));
+    let jpeg = imaging::encode_jpeg(&image::DynamicImage::ImageRgb8(image)).expect("JPEG");
+    pdf::assemble_jpeg_pdf(&[jpeg], 150).expect("PDF")
+}
+
+#[test]
+fn pattern_yields_group_name_and_part() {
+    let rule = rule("*_p{n}.pdf", 60);
+    assert_eq!(rule.matches("vertrag_p2.pdf"), Some(("vertrag.pdf".to_string(), 2)));
+    assert_eq!(rule.matches("Vertrag_P12.PDF"), Some(("Vertrag.PDF".to_string(), 12)));
+    assert_eq!(rule.matches("vertrag.pdf"), None);
+    assert_eq!(rule.matches("vertrag_p2.png"), None);
+}
+
+#[test]
+fn groups_wait_for_their_window() {
+    let config = GroupingConfig { rules: vec![rule("*_p{n}.pdf", 60)] };
+    let now = SystemTime::now();
+    let old = Some(now - Duration::from_secs(120));
+    let fresh = Some(now - Duration::from_secs(5));
+    let dir = PathBuf::from("/scans");
+
+    // Ein Teil ist noch frisch: Gruppe wartet, andere Dateien gehen sofort durch
+    let candidates = vec![
+        (dir.join("vertrag_p2.pdf"), fresh),
+        (dir.join("vertrag_p1.pdf"), old),
+        (dir.join("foto.jpg"), old),
+    ];
+    let batches = file_grouping::batch(candidates.clone(), &config, now);
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].path(), dir.join("foto.jpg"));
+
+    // Nach Ablauf des Fensters: eine Gruppe, nach Teilnummer sortiert
+    let later = now + Duration::from_secs(60);
+    let batches = file_grouping::batch(candidates, &config, later);
+    assert_eq!(batches.len(), 2);
+    let group = batches.iter().find(|batch| batch.group_name.is_some()).expect("Gruppe");
+    assert_eq!(group.group_name.as_deref(), Some("vertrag.pdf"));
+    assert_eq!(group.paths().collect::<Vec<_>>(), vec![dir.join("vertrag_p1.pdf"), dir.join("vertrag_p2.pdf")]);
+
+    // Einzelner Teil wird wie eine normale Datei behandelt
+    let batches = file_grouping::batch(vec![(dir.join("angebot_p1.pdf"), old)], &config, now);
+    assert_eq!(batches.len(), 1);
+    assert!(batches[0].group_name.is_none());
+}
+
+#[test]
+fn parts_are_merged_into_one_pdf() {
+    let merged = pdf::merge(&[page_pdf(40), page_pdf(200)]).expect("Zusammenführen");
+    pdf::validate(&merged).expect("gültiges PDF");
+    assert_eq!(imaging::extract_pdf_jpegs(&merged).len(), 2);
+}
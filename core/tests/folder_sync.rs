@@ -0,0 +1,430 @@
+// Integrationstests Folder-Sync - Upload über den Watcher gegen einen Mock-DocFlow-Server
+
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use docflow_bridge_core::barcode::BarcodeConfig;
+use docflow_bridge_core::dedup::{self, Channel};
+use docflow_bridge_core::folder_watcher::{
+    cancel_upload, upload_files, validate_folder, FolderSyncConfig, FolderWatcher, IgnoreConfig, PostUploadAction,
+    UploadCancelled,
+};
+use docflow_bridge_core::settings::BridgeSettings;
+use docflow_bridge_core::upload_progress;
+use tokio::sync::RwLock;
+use wiremock::matchers::{body_string_contains, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "test-api-key";
+
+/// Startet einen Watcher auf dem Ordner (Polling jede Sekunde)
+fn start_watcher(server: &MockServer, dir: &Path) -> Arc<FolderWatcher> {
+    let mut settings = BridgeSettings::default();
+    settings.intervals.folder_poll_secs = 1;
+
+    let watcher = Arc::new(FolderWatcher::new(
+        FolderSyncConfig {
+            enabled: true,
+            watch_path: dir.to_string_lossy().to_string(),
+            post_upload_action: PostUploadAction::MoveToSubfolder,
+            auto_orient: false,
+        },
+        API_KEY.to_string(),
+        server.uri(),
+        Arc::new(RwLock::new(settings)),
+    ));
+    tokio::spawn(watcher.clone().start_watching());
+    watcher
+}
+
+/// Wartet bis die Bedingung erfüllt ist (Dateien müssen erst stabil sein, ca. 4,5 s)
+async fn wait_for(condition: impl Fn() -> bool) -> bool {
+    for _ in 0..60 {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    false
+}
+
+async fn mount_status_endpoint(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-sync-status"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn new_file_is_uploaded_and_moved() {
+    let server = MockServer::start().await;
+    mount_status_endpoint(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .and(header("Authorization", format!("Bearer {}", API_KEY).as_str()))
+        .and(body_string_contains("filename=\"beleg.png\""))
+        .and(body_string_contains("name=\"file_hash\""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "success": true,
+            "job_id": 17,
+            "filename": "beleg.png",
+            "file_size_mb": 0.0,
+            "duplicate": false,
+            "message": "OK",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    std::fs::write(dir.path().join("beleg.png"), b"not really a png").expect("Datei schreiben");
+    // Temporäre Dateien werden ignoriert
+    std::fs::write(dir.path().join("~$beleg.png"), b"lock").expect("Datei schreiben");
+
+    let watcher = start_watcher(&server, dir.path());
+    let moved = dir.path().join("uploaded").join("beleg.png");
+    let uploaded = wait_for(|| moved.exists()).await;
+    watcher.stop().await;
+
+    assert!(uploaded, "Datei wurde nicht nach uploaded/ verschoben");
+    let status = watcher.get_status().await;
+    assert_eq!(status.files_uploaded, 1);
+    assert_eq!(status.errors, 0);
+}
+
+#[tokio::test]
+async fn broken_pdf_is_quarantined_instead_of_uploaded() {
+    let server = MockServer::start().await;
+    mount_status_endpoint(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    std::fs::write(dir.path().join("abgebrochen.pdf"), b"%PDF-1.4\n1 0 obj\n<<").expect("Datei schreiben");
+
+    let watcher = start_watcher(&server, dir.path());
+    let quarantined = dir.path().join("quarantine").join("abgebrochen.pdf");
+    let moved = wait_for(|| quarantined.exists()).await;
+    watcher.stop().await;
+
+    assert!(moved, "PDF wurde nicht in Quarantäne verschoben");
+    assert!(dir.path().join("quarantine").join("abgebrochen.pdf.reason.txt").exists());
+    assert_eq!(watcher.get_status().await.files_quarantined, 1);
+}
+
+#[tokio::test]
+async fn dropped_files_are_uploaded_and_left_in_place() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .and(body_string_contains("filename=\"rechnung.png\""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "success": true,
+            "job_id": 21,
+            "filename": "rechnung.png",
+            "file_size_mb": 0.0,
+            "duplicate": false,
+            "message": "OK",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let image = dir.path().join("rechnung.png");
+    let text = dir.path().join("notiz.txt");
+    std::fs::write(&image, b"not really a png").expect("Datei schreiben");
+    std::fs::write(&text, b"hallo").expect("Datei schreiben");
+
+    let results = upload_files(
+        &server.uri(),
+        API_KEY,
+        &[image.clone(), text, image.clone()],
+        &BarcodeConfig::default(),
+    )
+    .await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].response.as_ref().map(|r| r.job_id), Some(21));
+    assert!(results[1].error.as_deref().is_some_and(|e| e.contains("nicht unterstützt")));
+    assert!(results[2].error.is_some(), "doppelt abgelegte Datei wurde erneut hochgeladen");
+    assert!(image.exists(), "Datei muss am Originalort bleiben");
+}
+
+#[tokio::test]
+async fn dropped_file_already_scanned_is_not_uploaded() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    // Eindeutiger Inhalt, der Hash-Speicher ist global und übersteht Testläufe
+    let content = format!("%PDF-1.4 abgelegter Scan {}", uuid::Uuid::new_v4());
+    dedup::record(&dedup::hash(content.as_bytes()), Channel::Scan, "Job 43");
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let file = dir.path().join("scan.png");
+    std::fs::write(&file, &content).expect("Datei schreiben");
+
+    let results = upload_files(&server.uri(), API_KEY, &[file], &BarcodeConfig::default()).await;
+    let error = results[0].error.as_deref().unwrap_or_default();
+    assert!(error.contains("als Scan (Job 43)"), "{:?}", results[0]);
+}
+
+#[tokio::test]
+async fn large_file_is_streamed_with_progress() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .and(body_string_contains("ENDE-DER-DATEI"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "success": true,
+            "job_id": 22,
+            "filename": "plan.tiff",
+            "file_size_mb": 12.0,
+            "duplicate": false,
+            "message": "OK",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let file = dir.path().join("plan.tiff");
+    let mut data = vec![b'x'; 12 * 1024 * 1024];
+    data.extend_from_slice(b"ENDE-DER-DATEI");
+    std::fs::write(&file, &data).expect("Datei schreiben");
+
+    let mut progress = upload_progress::subscribe();
+    let results = upload_files(&server.uri(), API_KEY, &[file], &BarcodeConfig::default()).await;
+    assert_eq!(results[0].response.as_ref().map(|r| r.job_id), Some(22), "{:?}", results[0].error);
+
+    let mut last = None;
+    while let Ok(update) = progress.try_recv() {
+        if update.file_name == "plan.tiff" {
+            last = Some(update);
+        }
+    }
+    let last = last.expect("Fortschritt gemeldet");
+    assert_eq!(last.bytes_sent, data.len() as u64);
+    assert_eq!(last.total_bytes, data.len() as u64);
+}
+
+#[tokio::test]
+async fn running_upload_can_be_cancelled() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let file = dir.path().join("gross.png");
+    std::fs::write(&file, b"not really a png").expect("Datei schreiben");
+
+    let uri = server.uri();
+    let paths = vec![file.clone()];
+    let upload = tokio::spawn(async move { upload_files(&uri, API_KEY, &paths, &BarcodeConfig::default()).await });
+
+    let id = file.to_string_lossy().to_string();
+    assert!(wait_for(|| cancel_upload(&id)).await, "Upload lief nicht");
+
+    let results = tokio::time::timeout(Duration::from_secs(5), upload)
+        .await
+        .expect("Abbruch hat den Upload nicht beendet")
+        .expect("Upload-Task");
+    assert_eq!(results[0].error, Some(UploadCancelled.to_string()));
+    assert!(file.exists(), "Datei muss unverändert liegen bleiben");
+}
+
+#[tokio::test]
+async fn uploads_with_identical_content_are_cancelled_separately() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({
+                    "success": true,
+                    "job_id": 5,
+                    "filename": "kopie.png",
+                    "file_size_mb": 0.0,
+                    "duplicate": false,
+                    "message": "ok"
+                }))
+                .set_delay(Duration::from_secs(1)),
+        )
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let mut progress = upload_progress::subscribe();
+    let mut uploads = Vec::new();
+    for name in ["original.png", "kopie.png"] {
+        let file = dir.path().join(name);
+        std::fs::write(&file, b"gleicher Inhalt").expect("Datei schreiben");
+        let uri = server.uri();
+        uploads.push(tokio::spawn(async move { upload_files(&uri, API_KEY, &[file], &BarcodeConfig::default()).await }));
+    }
+
+    // Fortschritts-IDs je Datei - gleicher Inhalt, trotzdem verschiedene IDs
+    let mut ids = std::collections::HashMap::new();
+    while ids.len() < 2 {
+        let update = tokio::time::timeout(Duration::from_secs(5), progress.recv())
+            .await
+            .expect("Fortschritt gemeldet")
+            .expect("Kanal");
+        if update.file_name == "original.png" || update.file_name == "kopie.png" {
+            ids.insert(update.file_name.clone(), update.id.clone());
+        }
+    }
+    assert_ne!(ids["original.png"], ids["kopie.png"]);
+    assert!(cancel_upload(&ids["original.png"]));
+
+    let original = uploads.remove(0).await.expect("Upload-Task");
+    let copy = uploads.remove(0).await.expect("Upload-Task");
+    assert_eq!(original[0].error, Some(UploadCancelled.to_string()));
+    assert_eq!(copy[0].response.as_ref().map(|r| r.job_id), Some(5), "{:?}", copy[0].error);
+}
+
+#[test]
+fn validate_folder_counts_existing_files_and_leaves_no_traces() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.pdf"), vec![0u8; 1000]).unwrap();
+    std::fs::write(dir.path().join("b.jpg"), vec![0u8; 500]).unwrap();
+    std::fs::write(dir.path().join("notes.txt"), b"x").unwrap();
+    std::fs::write(dir.path().join("scan.pdf.part"), b"x").unwrap();
+
+    let validation = validate_folder(dir.path(), &IgnoreConfig::default(), 0);
+
+    assert!(validation.readable && validation.writable && validation.can_create_uploaded);
+    assert!(validation.errors.is_empty(), "{:?}", validation.errors);
+    assert_eq!(validation.eligible_files, 2);
+    assert_eq!(validation.eligible_bytes, 1500);
+    assert!(validation.free_bytes.is_some());
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 4, "Prüfung darf nichts zurücklassen");
+
+    let missing = validate_folder(&dir.path().join("fehlt"), &IgnoreConfig::default(), 0);
+    assert!(!missing.readable);
+    assert_eq!(missing.errors.len(), 1);
+}
+
+#[tokio::test]
+async fn nfd_name_in_long_path_is_uploaded_as_nfc() {
+    let server = MockServer::start().await;
+
+    // "ä" in NFC (C3 A4) statt NFD wie von macOS geliefert (61 CC 88)
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .and(body_string_contains("filename=\"Rechnung M\u{e4}rz 🧾.png\""))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "success": true,
+            "job_id": 22,
+            "filename": "Rechnung März.png",
+            "file_size_mb": 0.0,
+            "duplicate": false,
+            "message": "OK",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Verschachtelter Ordner deutlich über 260 Zeichen
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let mut deep = dir.path().to_path_buf();
+    for i in 0..6 {
+        deep.push(format!("Unterordner mit langem Namen für Ablage Nummer {} äöü", i));
+    }
+    std::fs::create_dir_all(&deep).expect("Ordner anlegen");
+    let image = deep.join("Rechnung Ma\u{0308}rz 🧾.png");
+    std::fs::write(&image, b"not really a png").expect("Datei schreiben");
+    assert!(image.to_string_lossy().len() > 260);
+
+    let results = upload_files(&server.uri(), API_KEY, std::slice::from_ref(&image), &BarcodeConfig::default()).await;
+
+    assert_eq!(results[0].response.as_ref().map(|r| r.job_id), Some(22), "{:?}", results[0].error);
+    assert_eq!(results[0].path, image.to_string_lossy());
+
+    // Auch original_path in NFC, der Name auf der Platte bleibt zerlegt
+    let requests = server.received_requests().await.expect("Anfragen");
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(!body.contains("a\u{0308}"), "NFD-Name im Upload");
+    assert!(image.exists());
+}
+
+#[tokio::test]
+async fn file_already_uploaded_as_scan_is_skipped() {
+    let server = MockServer::start().await;
+    mount_status_endpoint(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    // Eindeutiger Inhalt, der Hash-Speicher ist global und übersteht Testläufe
+    let content = format!("%PDF-1.4 Job-Scan {}", uuid::Uuid::new_v4());
+    dedup::record(&dedup::hash(content.as_bytes()), Channel::Scan, "Job 42");
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    std::fs::write(dir.path().join("kopie.png"), &content).expect("Datei schreiben");
+
+    let watcher = start_watcher(&server, dir.path());
+    let moved = dir.path().join("uploaded").join("kopie.png");
+    let handled = wait_for(|| moved.exists()).await;
+    watcher.stop().await;
+
+    assert!(handled, "Duplikat wurde nicht nach uploaded/ verschoben");
+    assert_eq!(watcher.get_status().await.files_uploaded, 0);
+}
+
+#[tokio::test]
+async fn file_known_to_server_is_not_transferred() {
+    // Eigener Server statt aus dem Pool: Server ohne Hash-Prüfung merkt sich die Bridge je URL
+    let server = MockServer::builder().start().await;
+    mount_status_endpoint(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/folder-upload/exists"))
+        .and(query_param("file_hash", dedup::hash(b"schon in DocFlow").as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "exists": true, "job_id": 23 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    std::fs::write(dir.path().join("bekannt.png"), b"schon in DocFlow").expect("Datei schreiben");
+
+    let watcher = start_watcher(&server, dir.path());
+    let moved = dir.path().join("uploaded").join("bekannt.png");
+    let handled = wait_for(|| moved.exists()).await;
+    watcher.stop().await;
+
+    assert!(handled, "Bekannte Datei wurde nicht nach uploaded/ verschoben");
+    let status = watcher.get_status().await;
+    assert_eq!(status.files_duplicate, 1);
+    assert_eq!(status.files_uploaded, 0);
+}
@@ -0,0 +1,113 @@
+// Integrationstests gRPC-Transport - Endpunkt-Ableitung, Job-Umwandlung und Fallback auf HTTP
+// Eigene Test-Binary, da der gRPC-Status global ist; läuft mit und ohne Feature "grpc"
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use docflow_bridge_core::grpc::{self, GrpcConfig, GrpcService};
+use docflow_bridge_core::scan_poller::{ScanDocument, ScanPoller};
+use docflow_bridge_core::settings::BridgeSettings;
+use docflow_bridge_core::spool;
+use tokio::sync::RwLock;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "test-api-key";
+
+#[test]
+fn endpoint_is_derived_from_docflow_url() {
+    let config = GrpcConfig::default();
+    assert_eq!(config.endpoint("https://docflow.example/app").unwrap(), "https://docflow.example:50051");
+    assert_eq!(config.endpoint("http://10.0.0.5:8000").unwrap(), "http://10.0.0.5:50051");
+    assert!(config.endpoint("kein-url").is_err());
+
+    let custom = GrpcConfig { endpoint: "https://grpc.example:443/".to_string(), ..GrpcConfig::default() };
+    assert_eq!(custom.endpoint("https://docflow.example").unwrap(), "https://grpc.example:443");
+}
+
+#[cfg(feature = "grpc")]
+#[test]
+fn streamed_jobs_become_pending_jobs() {
+    use docflow_bridge_core::enhance::EnhanceStage;
+    use docflow_bridge_core::grpc::proto;
+    use docflow_bridge_core::scan_poller::PendingScanJob;
+
+    let job = proto::ScanJob {
+        job_id: "job-7".to_string(),
+        scanner_id: "scanner-1".to_string(),
+        resolution: 300,
+        color_mode: "color".to_string(),
+        enhancements: Some(proto::Enhancements {
+            stages: vec!["Despeckle".to_string(), "Unbekannt".to_string(), "ContrastStretch".to_string()],
+        }),
+        regions: vec![proto::Region { x_offset: 0, y_offset: 10, width: 1000, height: 600 }],
+        priority: 3,
+        ..proto::ScanJob::default()
+    };
+    let job: PendingScanJob = job.into();
+    assert_eq!(job.job_id, "job-7");
+    assert_eq!(job.enhancements, Some(vec![EnhanceStage::Despeckle, EnhanceStage::ContrastStretch]));
+    assert_eq!(job.regions.len(), 1);
+    assert_eq!(job.regions[0].height, 600);
+    assert_eq!(job.profile_id, None);
+    assert_eq!(job.priority, 3);
+}
+
+#[tokio::test]
+async fn upload_falls_back_to_http_when_grpc_is_unreachable() {
+    let server = MockServer::builder().start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/scan-upload/job-grpc"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Freier Port ohne Dienst
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let settings = BridgeSettings {
+        grpc: GrpcConfig {
+            enabled: true,
+            endpoint: format!("http://127.0.0.1:{}", port),
+            ..GrpcConfig::default()
+        },
+        ..BridgeSettings::default()
+    };
+    let settings = Arc::new(RwLock::new(settings));
+    let poller = Arc::new(ScanPoller::new(
+        API_KEY.to_string(),
+        server.uri(),
+        Arc::new(RwLock::new(Vec::new())),
+        settings.clone(),
+    ));
+
+    let service = GrpcService::start(poller.clone(), server.uri(), API_KEY.to_string(), settings).await;
+    assert_eq!(service.is_some(), grpc::is_supported());
+    if grpc::is_supported() {
+        // Verbindungsfehler abwarten - das Polling bleibt aktiv
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while grpc::status().is_none_or(|s| s.last_error.is_none()) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("Verbindungsfehler nicht gemeldet");
+        assert!(!grpc::is_streaming());
+    }
+
+    let document = ScanDocument {
+        data: spool::Buffer::Memory(b"%PDF-1.4 grpc fallback".to_vec()),
+        barcodes: Vec::new(),
+        incomplete: false,
+        renditions: Vec::new(),
+    };
+    poller
+        .upload_scan_result("job-grpc", document, None)
+        .await
+        .expect("Upload über HTTP");
+
+    if let Some(service) = service {
+        service.stop().await;
+    }
+    assert!(grpc::status().is_none());
+}
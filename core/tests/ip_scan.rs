@@ -0,0 +1,67 @@
+// Integrationstests IP-Range-Scan - CIDR-Bereiche und Ports der Discovery-Konfiguration
+
+use std::net::Ipv4Addr;
+
+use docflow_bridge_core::discovery::{self, IpScanConfig};
+
+#[test]
+fn cidr_ranges_expand_to_host_addresses() {
+    let hosts = discovery::parse_cidr("10.20.30.77/24").expect("gültiger Bereich");
+    assert_eq!(hosts.len(), 254);
+    assert_eq!(hosts.first(), Some(&Ipv4Addr::new(10, 20, 30, 1)));
+    assert_eq!(hosts.last(), Some(&Ipv4Addr::new(10, 20, 30, 254)));
+
+    assert_eq!(discovery::parse_cidr("192.168.5.9").unwrap(), vec![Ipv4Addr::new(192, 168, 5, 9)]);
+    assert_eq!(discovery::parse_cidr("192.168.5.8/31").unwrap().len(), 2);
+    assert_eq!(discovery::parse_cidr("172.16.0.0/20").unwrap().len(), 4094);
+
+    assert!(discovery::parse_cidr("172.16.0.0/19").is_err());
+    assert!(discovery::parse_cidr("10.0.0.0/33").is_err());
+    assert!(discovery::parse_cidr("scanner.local/24").is_err());
+}
+
+#[test]
+fn config_merges_ranges_and_validates_limits() {
+    let config = IpScanConfig {
+        ranges: vec!["10.0.1.0/30".to_string(), "10.0.1.2".to_string(), "10.0.2.0/30".to_string()],
+        ports: vec![8080, 80, 8080],
+        ..IpScanConfig::default()
+    };
+    assert!(config.validate().is_ok());
+    assert_eq!(
+        config.hosts().unwrap(),
+        vec![
+            Ipv4Addr::new(10, 0, 1, 1),
+            Ipv4Addr::new(10, 0, 1, 2),
+            Ipv4Addr::new(10, 0, 2, 1),
+            Ipv4Addr::new(10, 0, 2, 2),
+        ]
+    );
+    assert_eq!(config.ports(), vec![80, 8080]);
+    assert_eq!(IpScanConfig::default().ports(), vec![80, 443, 8080, 9100]);
+
+    let too_many = IpScanConfig {
+        ranges: vec!["10.1.0.0/20".to_string(), "10.2.0.0/24".to_string()],
+        ..IpScanConfig::default()
+    };
+    assert!(too_many.validate().unwrap_err().contains("höchstens"));
+    let port_zero = IpScanConfig {
+        ports: vec![0],
+        ..IpScanConfig::default()
+    };
+    assert!(port_zero.validate().is_err());
+}
+
+#[test]
+fn snmp_sweep_is_on_with_public_community_by_default() {
+    let config: IpScanConfig = serde_json::from_str(r#"{ "ranges": [] }"#).unwrap();
+    assert!(config.snmp);
+    assert_eq!(config.snmp_community(), "public");
+
+    let config = IpScanConfig {
+        snmp: false,
+        snmp_community: Some("scanner-ro".to_string()),
+        ..IpScanConfig::default()
+    };
+    assert_eq!(config.snmp_community(), "scanner-ro");
+}
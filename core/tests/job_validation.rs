@@ -0,0 +1,91 @@
+// Integrationstests Job-Prüfung - Herabstufung auf unterstützte Werte bzw. Ablehnung des Jobs
+
+use docflow_bridge_core::discovery::DiscoveredScanner;
+use docflow_bridge_core::job_validation::{self, ValidationConfig};
+use docflow_bridge_core::scan_poller::PendingScanJob;
+use docflow_bridge_core::virtual_scanner::{self, VirtualScannerConfig};
+
+fn job(resolution: u32, color_mode: &str, source: &str, duplex: bool) -> PendingScanJob {
+    serde_json::from_value(serde_json::json!({
+        "job_id": "job-1",
+        "scanner_id": "mfp",
+        "resolution": resolution,
+        "color_mode": color_mode,
+        "source": source,
+        "duplex": duplex,
+        "format": "pdf",
+        "created_at": "2026-01-01T00:00:00Z",
+        "expires_at": "2026-01-01T01:00:00Z",
+    }))
+    .expect("Job")
+}
+
+/// Flachbett-Scanner ohne Einzug, Duplex und Farbe (max. 300 dpi)
+fn scanner() -> DiscoveredScanner {
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.name = "Flachbett".to_string();
+    scanner.capabilities.adf = false;
+    scanner.capabilities.flatbed = true;
+    scanner.capabilities.duplex = false;
+    scanner.capabilities.max_resolution = 300;
+    scanner.capabilities.color_modes = vec!["Grayscale8".to_string()];
+    scanner
+}
+
+#[test]
+fn unsupported_parameters_are_downgraded_by_default() {
+    let validated =
+        job_validation::validate_job(&job(600, "color", "adf", true), &scanner(), &ValidationConfig::default())
+            .expect("Herabstufung");
+
+    assert_eq!(validated.job.resolution, 300);
+    assert_eq!(validated.job.source, "flatbed");
+    assert!(!validated.job.duplex);
+    assert_eq!(validated.job.color_mode, "grayscale");
+    assert_eq!(validated.warnings.len(), 4, "{:?}", validated.warnings);
+}
+
+#[test]
+fn supported_jobs_pass_unchanged() {
+    let validated =
+        job_validation::validate_job(&job(300, "grayscale", "flatbed", false), &scanner(), &ValidationConfig::default())
+            .expect("Job");
+
+    assert_eq!(validated.job.resolution, 300);
+    assert_eq!(validated.job.source, "flatbed");
+    assert!(validated.warnings.is_empty());
+}
+
+#[test]
+fn unsupported_parameters_are_rejected_without_downgrade() {
+    let config = ValidationConfig { auto_downgrade: false };
+    let error = job_validation::validate_job(&job(600, "color", "adf", false), &scanner(), &config)
+        .err()
+        .expect("Ablehnung");
+
+    assert!(error.contains("Flachbett"), "{}", error);
+    assert!(error.contains("600 dpi"), "{}", error);
+    assert!(error.contains("Einzug"), "{}", error);
+    assert!(error.contains("Farbscan"), "{}", error);
+}
+
+#[test]
+fn modes_without_fallback_are_rejected_even_with_downgrade() {
+    let mut scanner = scanner();
+    scanner.capabilities.color_modes = vec!["BlackAndWhite1".to_string()];
+
+    let result = job_validation::validate_job(&job(300, "color", "flatbed", false), &scanner, &ValidationConfig::default());
+    assert!(result.is_err_and(|e| e.contains("Farbmodus 'color'")));
+}
+
+#[test]
+fn unknown_capabilities_are_not_checked() {
+    let mut scanner = scanner();
+    scanner.capabilities.unknown = true;
+    let config = ValidationConfig { auto_downgrade: false };
+    let validated = job_validation::validate_job(&job(600, "color", "adf", true), &scanner, &config).expect("Job");
+
+    assert_eq!(validated.job.resolution, 600);
+    assert!(validated.job.duplex);
+    assert!(validated.warnings.is_empty());
+}
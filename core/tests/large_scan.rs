@@ -0,0 +1,116 @@
+// Integrationstests großer Einzug-Stapel - Seiten gehen ab der Schwelle beim Eintreffen in den Spool
+// Eigene Test-Binary mit eigenem Datenverzeichnis (XDG_CONFIG_HOME), da der Spool darin liegt
+
+use docflow_bridge_core::autocrop::AutoCropConfig;
+use docflow_bridge_core::barcode::BarcodeConfig;
+use docflow_bridge_core::pipeline::{self, PipelineOptions, RenditionConfig, ScanPage};
+use docflow_bridge_core::scanner::{self, ScanJob};
+use docflow_bridge_core::splitter::SplitConfig;
+use docflow_bridge_core::{imaging, spool};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PAGES: usize = 6;
+
+fn page_jpeg(number: u8) -> Vec<u8> {
+    let image = image::RgbImage::from_fn(240, 320, |x, y| {
+        image::Rgb([(x as u8).wrapping_mul(number), (y as u8) ^ number, ((x + y) as u8).wrapping_add(number)])
+    });
+    imaging::encode_jpeg(&image::DynamicImage::ImageRgb8(image)).expect("JPEG")
+}
+
+fn spool_files() -> usize {
+    std::fs::read_dir(spool::spool_dir()).map(|entries| entries.count()).unwrap_or(0)
+}
+
+#[tokio::test]
+async fn large_batches_are_spooled_and_assembled_from_disk() {
+    let data_dir = tempfile::tempdir().expect("Temp-Ordner");
+    std::env::set_var("XDG_CONFIG_HOME", data_dir.path());
+
+    let server = MockServer::builder().start().await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScannerStatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<pwg:State>Idle</pwg:State>"))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .respond_with(
+            ResponseTemplate::new(201)
+                .insert_header("Location", format!("{}/eSCL/ScanJobs/1", server.uri()).as_str()),
+        )
+        .mount(&server)
+        .await;
+    let page = page_jpeg(3);
+    assert!(page.len() > 1024, "Testseite zu klein für die Schwelle");
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/1/NextDocument"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(page.clone()))
+        .up_to_n_times(PAGES as u64)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/1/NextDocument"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let job = ScanJob {
+        scanner_id: "mock".to_string(),
+        resolution: 150,
+        color_mode: "color".to_string(),
+        format: "image/jpeg".to_string(),
+        source: "adf".to_string(),
+        duplex: false,
+        page_retries: 0,
+        allow_partial: false,
+        height: None,
+        regions: Vec::new(),
+        timeout_secs: 30,
+        spool_after_kb: 1,
+    };
+    let result = scanner::scan_escl("127.0.0.1", server.address().port(), &job)
+        .await
+        .expect("Scan erfolgreich");
+
+    // Erste Seite bis zur Schwelle im Speicher, der Rest verschlüsselt im Spool
+    assert_eq!(result.total_pages, PAGES);
+    assert!(!result.pages[0].data.is_spooled());
+    assert!(result.pages[1..].iter().all(|page| page.data.is_spooled()));
+    assert_eq!(spool_files(), PAGES - 1);
+    assert_eq!(result.pages[PAGES - 1].data.read().unwrap(), page);
+
+    let options = PipelineOptions {
+        resolution: 150,
+        duplex: false,
+        split: SplitConfig { enabled: true, ..SplitConfig::default() },
+        barcode: BarcodeConfig::default(),
+        enhance: Vec::new(),
+        renditions: RenditionConfig::default(),
+        auto_color: None,
+        crop: AutoCropConfig::default(),
+        orientation: None,
+        regions: Vec::new(),
+        max_document_bytes: None,
+    };
+    let pages: Vec<ScanPage> = result.pages.into_iter().map(|page| (page.data, page.format)).collect();
+    let documents = pipeline::process(pages, &options).expect("Verarbeitung");
+
+    // Ein Dokument mit allen Seiten, direkt im Spool erzeugt - die Seiten sind danach gelöscht
+    assert_eq!(documents.len(), 1);
+    let (document, _) = documents.into_iter().next().unwrap();
+    assert!(document.is_spooled());
+    assert_eq!(spool_files(), 1);
+    let pdf = document.read().unwrap();
+    assert_eq!(imaging::extract_pdf_jpegs(&pdf).len(), PAGES);
+    assert_eq!(document.len(), pdf.len());
+
+    // Blockweises Lesen liefert dieselben Daten wie das Laden am Stück
+    let streamed: Vec<u8> = document.chunks(4096).unwrap().flat_map(Result::unwrap).collect();
+    assert_eq!(streamed, pdf);
+
+    drop(document);
+    assert_eq!(spool_files(), 0);
+}
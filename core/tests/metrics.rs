@@ -0,0 +1,42 @@
+// Integrationstests Metrik-Endpunkt - Anfragen in mehreren TCP-Segmenten werden vollständig gelesen
+
+use std::time::Duration;
+
+use docflow_bridge_core::metrics::{self, MetricsConfig};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[tokio::test]
+async fn request_head_split_across_segments_is_answered() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    metrics::apply_config(&MetricsConfig {
+        enabled: true,
+        bind_address: "127.0.0.1".to_string(),
+        port,
+    });
+
+    let mut socket = None;
+    for _ in 0..50 {
+        if let Ok(connected) = TcpStream::connect(("127.0.0.1", port)).await {
+            socket = Some(connected);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let mut socket = socket.expect("Metrik-Endpunkt nicht erreichbar");
+
+    // Kopf in zwei Teilen, der zweite erst nach einer Pause
+    socket.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    socket.write_all(format!("X-Padding: {}\r\n\r\n", "a".repeat(2000)).as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    tokio::time::timeout(Duration::from_secs(5), socket.read_to_string(&mut response))
+        .await
+        .expect("keine Antwort")
+        .unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("docflow_bridge_info"));
+
+    metrics::apply_config(&MetricsConfig::default());
+}
@@ -0,0 +1,59 @@
+// Integrationstests Konfigurations-Migrationen - Alte Einstellungen und Folder-Sync-Stände bleiben beim Update erhalten
+
+use docflow_bridge_core::config_bundle::ConfigBundle;
+use docflow_bridge_core::folder_watcher::{FolderSyncConfig, PostUploadAction};
+use docflow_bridge_core::migrations::{self, VERSION_FIELD};
+use docflow_bridge_core::settings::BridgeSettings;
+
+#[test]
+fn old_settings_keep_readable_sections() {
+    // Stand vor der Versionierung: ein Bereich als null, einer mit falschem Typ
+    let json = r#"{
+        "intervals": { "job_poll_secs": 7 },
+        "wol": null,
+        "resources": "kaputt"
+    }"#;
+
+    let loaded = migrations::load::<BridgeSettings>(json, &migrations::SETTINGS).expect("migrierbar");
+
+    assert_eq!(loaded.from_version, 0);
+    assert!(loaded.changed(&migrations::SETTINGS));
+    assert_eq!(loaded.value.intervals.job_poll_secs, 7);
+    assert_eq!(loaded.dropped, ["resources"]);
+}
+
+#[test]
+fn stored_settings_carry_current_version() {
+    let stored = migrations::store(&BridgeSettings::default(), &migrations::SETTINGS).unwrap();
+    assert_eq!(stored[VERSION_FIELD], migrations::SETTINGS.version());
+
+    let loaded = migrations::load::<BridgeSettings>(&stored.to_string(), &migrations::SETTINGS).unwrap();
+    assert!(!loaded.changed(&migrations::SETTINGS));
+}
+
+#[test]
+fn legacy_folder_sync_action_is_migrated() {
+    let json = r#"{ "watch_path": "/scans", "post_upload_action": "delete" }"#;
+
+    let loaded = migrations::load::<FolderSyncConfig>(json, &migrations::FOLDER_SYNC).expect("migrierbar");
+
+    assert!(loaded.value.enabled);
+    assert_eq!(loaded.value.post_upload_action, PostUploadAction::Delete);
+    assert!(migrations::load::<FolderSyncConfig>("{}", &migrations::FOLDER_SYNC).is_err());
+}
+
+#[test]
+fn config_bundle_round_trips_with_schema_version() {
+    let folder_sync = FolderSyncConfig {
+        enabled: true,
+        watch_path: "/scans".to_string(),
+        post_upload_action: PostUploadAction::Keep,
+        auto_orient: false,
+    };
+    let json = serde_json::to_string(&ConfigBundle::new(BridgeSettings::default(), Some(folder_sync))).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["settings"][VERSION_FIELD], migrations::SETTINGS.version());
+
+    let bundle = ConfigBundle::parse(&json).expect("Import");
+    assert_eq!(bundle.folder_sync.unwrap().post_upload_action, PostUploadAction::Keep);
+}
@@ -0,0 +1,66 @@
+// Integrationstests Netzwerk-Diagnose - Latenz- und Upload-Messung gegen einen Mock-Server
+
+use docflow_bridge_core::network_diagnostics::{self, UPLOAD_TEST_BYTES};
+use docflow_bridge_core::self_test::CheckStatus;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "test-api-key";
+
+#[tokio::test]
+async fn latency_and_upload_are_measured() {
+    let server = MockServer::builder().start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/status"))
+        .and(header("Authorization", format!("Bearer {}", API_KEY).as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "status": "ok" })))
+        .expect(5)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/diagnostics/upload"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = network_diagnostics::run(API_KEY, &server.uri()).await;
+    assert!(report.passed, "{:?}", report.checks);
+    assert_eq!(report.addresses, vec!["127.0.0.1"]);
+    assert!(report.dns_ms.is_none());
+    assert!(report.connect_ms.is_some());
+    assert_eq!(report.latency.as_ref().map(|l| l.samples_ms.len()), Some(4));
+    assert_eq!(report.upload_bytes, UPLOAD_TEST_BYTES);
+    assert!(report.upload_kbit_per_sec.is_some_and(|k| k > 0));
+
+    let received = server.received_requests().await.unwrap();
+    let upload = received.iter().find(|r| r.method.as_str() == "POST").unwrap();
+    assert_eq!(upload.body.len(), UPLOAD_TEST_BYTES);
+}
+
+#[tokio::test]
+async fn missing_upload_endpoint_skips_throughput() {
+    let server = MockServer::builder().start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/scanner/bridge/status"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let report = network_diagnostics::run(API_KEY, &server.uri()).await;
+    assert!(report.passed);
+    assert!(report.upload_kbit_per_sec.is_none());
+    let upload = report.checks.iter().find(|c| c.name == "Upload").unwrap();
+    assert_eq!(upload.status, CheckStatus::Skipped);
+}
+
+#[tokio::test]
+async fn unreachable_docflow_fails() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let report = network_diagnostics::run(API_KEY, &format!("http://127.0.0.1:{}", port)).await;
+    assert!(!report.passed);
+    let latency = report.checks.iter().find(|c| c.name == "Latenz").unwrap();
+    assert_eq!(latency.status, CheckStatus::Failed);
+    let upload = report.checks.iter().find(|c| c.name == "Upload").unwrap();
+    assert_eq!(upload.status, CheckStatus::Skipped);
+}
@@ -0,0 +1,60 @@
+// Integrationstests Seitenausrichtung - synthetische Textseite in allen vier Lagen, Foto/Leerseite unverändert
+
+use docflow_bridge_core::orientation::{self, OrientationConfig, Rotation};
+use image::{DynamicImage, Rgb, RgbImage};
+
+/// A4 mit 150 dpi: Textzeilen aus "Buchstaben" mit typischen Ober- und Unterlängen
+fn text_page() -> DynamicImage {
+    let mut page = RgbImage::from_pixel(1240, 1754, Rgb([255, 255, 255]));
+    let ink = Rgb([20, 20, 20]);
+    let mut fill = |x0: u32, y0: u32, w: u32, h: u32| {
+        for y in y0..y0 + h {
+            for x in x0..x0 + w {
+                page.put_pixel(x, y, ink);
+            }
+        }
+    };
+
+    for line in 0..50u32 {
+        let baseline = 160 + line * 30;
+        let mut x = 120 + line * 5 % 13;
+        let mut glyph = line * 7;
+        while x < 1100 {
+            // Mittelband (x-Höhe)
+            fill(x, baseline - 10, 8, 10);
+            match glyph % 10 {
+                0 | 3 | 5 | 8 => fill(x, baseline - 16, 2, 6), // Oberlänge
+                6 => fill(x + 6, baseline, 2, 5),              // Unterlänge
+                _ => {}
+            }
+            glyph += 1;
+            x += if glyph % 6 == 0 { 20 } else { 11 };
+        }
+    }
+    DynamicImage::ImageRgb8(page)
+}
+
+#[test]
+fn rotated_text_pages_are_turned_upright() {
+    let config = OrientationConfig { enabled: true, ..OrientationConfig::default() };
+    let page = text_page();
+
+    assert_eq!(orientation::detect(&page, &config), Rotation::None);
+    assert_eq!(orientation::detect(&page.rotate180(), &config), Rotation::Rotate180);
+    assert_eq!(orientation::detect(&page.rotate90(), &config), Rotation::Clockwise270);
+    assert_eq!(orientation::detect(&page.rotate270(), &config), Rotation::Clockwise90);
+
+    let (corrected, rotation) = orientation::correct(page.rotate90(), &config);
+    assert_eq!(rotation.degrees(), 270);
+    assert_eq!(corrected.to_rgb8(), page.to_rgb8());
+}
+
+#[test]
+fn blank_pages_and_photos_stay_unchanged() {
+    let config = OrientationConfig::default();
+    let blank = DynamicImage::ImageRgb8(RgbImage::from_pixel(800, 1100, Rgb([250, 250, 250])));
+    assert_eq!(orientation::detect(&blank, &config), Rotation::None);
+
+    let photo = DynamicImage::ImageRgb8(RgbImage::from_fn(800, 600, |x, y| Rgb([(x / 4) as u8, (y / 3) as u8, 90])));
+    assert_eq!(orientation::detect(&photo, &config), Rotation::None);
+}
@@ -0,0 +1,25 @@
+// Integrationstests Pfade - Extended-Length-Pfade unter Windows und Dateinamen für den Upload
+
+use docflow_bridge_core::paths::{upload_name, windows_extended};
+
+#[test]
+fn windows_paths_get_extended_prefix() {
+    assert_eq!(windows_extended(r"C:\Scans\Eingang"), r"\\?\C:\Scans\Eingang");
+    assert_eq!(windows_extended("C:/Scans/./alt/../Eingang"), r"\\?\C:\Scans\Eingang");
+    assert_eq!(windows_extended(r"\\nas\scans\Büro"), r"\\?\UNC\nas\scans\Büro");
+    assert_eq!(windows_extended(r"\\?\C:\bereits\erweitert"), r"\\?\C:\bereits\erweitert");
+    assert_eq!(windows_extended(r"relativ\pfad"), r"relativ\pfad");
+}
+
+#[test]
+fn upload_names_are_normalized() {
+    assert_eq!(upload_name("Ma\u{0308}rz.pdf"), "M\u{e4}rz.pdf");
+    assert_eq!(upload_name("Rechnung 🧾.pdf"), "Rechnung 🧾.pdf");
+    assert_eq!(upload_name("a:b*c?.pdf"), "a_b_c_.pdf");
+    assert_eq!(upload_name("  . "), "scan");
+
+    let long = format!("{}.pdf", "ä".repeat(200));
+    let name = upload_name(&long);
+    assert!(name.len() <= 255);
+    assert!(name.ends_with(".pdf"));
+}
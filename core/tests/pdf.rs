@@ -0,0 +1,92 @@
+// Integrationstests PDF-Prüfung - Verschlüsselung wird am Trailer bzw. xref-Stream erkannt, nicht an beliebigen Bytes im Inhalt
+// Farbraum der eingebetteten JPEGs (Grau, RGB, CMYK mit invertierten Adobe-Werten)
+
+use docflow_bridge_core::{imaging, pdf};
+use image::{DynamicImage, GrayImage, RgbImage};
+
+/// Einseitiges PDF, dessen Bilddaten zufällig die Bytes "/Encrypt" enthalten
+fn pdf_with_encrypt_bytes_in_content() -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(40, 30, image::Rgb([200, 30, 30])));
+    let mut jpeg = imaging::encode_jpeg(&image).expect("JPEG");
+    jpeg.extend_from_slice(b"/Encrypt");
+    pdf::assemble_jpeg_pdf(&[jpeg], 100).expect("PDF")
+}
+
+#[test]
+fn encrypt_bytes_in_content_are_not_treated_as_encryption() {
+    let data = pdf_with_encrypt_bytes_in_content();
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let file = dir.path().join("scan.pdf");
+    std::fs::write(&file, &data).expect("Datei schreiben");
+
+    pdf::validate_file(&file).expect("nicht verschlüsselt");
+    pdf::validate(&data).expect("nicht verschlüsselt");
+}
+
+#[test]
+fn encrypt_entry_in_trailer_is_rejected() {
+    let data = pdf_with_encrypt_bytes_in_content();
+    // Trailer steht hinter der xref-Tabelle - der startxref-Offset bleibt gültig
+    let needle = b"/Root 1 0 R >>";
+    let at = data.windows(needle.len()).rposition(|w| w == needle).expect("Trailer");
+    let encrypted = [&data[..at], b"/Root 1 0 R /Encrypt 9 0 R >>", &data[at + needle.len()..]].concat();
+    let dir = tempfile::tempdir().expect("Temp-Ordner");
+    let file = dir.path().join("geschuetzt.pdf");
+    std::fs::write(&file, &encrypted).expect("Datei schreiben");
+
+    let error = pdf::validate_file(&file).expect_err("verschlüsselt");
+    assert!(error.contains("verschlüsselt"), "{}", error);
+    assert!(pdf::validate(&encrypted).is_err());
+}
+
+#[test]
+fn encrypt_entry_in_xref_stream_dictionary_is_rejected() {
+    let body = b"%PDF-1.5\n1 0 obj\n<< /Type /Catalog >>\nendobj\n";
+    let xref_offset = body.len();
+    let xref = format!(
+        "2 0 obj\n<< /Type /XRef /Size 3 /Root 1 0 R /Encrypt 5 0 R /Length 0 >>\nstream\n\nendstream\nendobj\nstartxref\n{}\n%%EOF\n",
+        xref_offset
+    );
+    let data = [body.as_slice(), xref.as_bytes()].concat();
+    assert!(pdf::validate(&data).expect_err("verschlüsselt").contains("verschlüsselt"));
+
+    let plain = String::from_utf8(data).unwrap().replace(" /Encrypt 5 0 R", "");
+    pdf::validate(plain.as_bytes()).expect("nicht verschlüsselt");
+}
+
+/// JPEG-Header mit vier Komponenten (CMYK), optional mit APP14-Marker "Adobe"
+fn cmyk_jpeg(adobe: bool) -> Vec<u8> {
+    let mut jpeg = vec![0xff, 0xd8];
+    if adobe {
+        jpeg.extend_from_slice(&[0xff, 0xee, 0x00, 0x0e]);
+        jpeg.extend_from_slice(b"Adobe");
+        jpeg.extend_from_slice(&[0x00, 0x64, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    }
+    // SOF0: 8 Bit, 16 x 24 Pixel, vier Komponenten
+    jpeg.extend_from_slice(&[0xff, 0xc0, 0x00, 0x14, 0x08, 0x00, 0x10, 0x00, 0x18, 0x04]);
+    for id in 1..=4 {
+        jpeg.extend_from_slice(&[id, 0x11, 0x00]);
+    }
+    jpeg.extend_from_slice(&[0xff, 0xd9]);
+    jpeg
+}
+
+fn contains(data: &[u8], needle: &str) -> bool {
+    data.windows(needle.len()).any(|w| w == needle.as_bytes())
+}
+
+#[test]
+fn jpeg_color_space_is_taken_from_the_header() {
+    let adobe = pdf::assemble_jpeg_pdf(&[cmyk_jpeg(true)], 100).expect("PDF");
+    assert!(contains(&adobe, "/ColorSpace /DeviceCMYK /Decode [1 0 1 0 1 0 1 0]"));
+    assert!(contains(&adobe, "/Width 24 /Height 16"));
+
+    let plain = pdf::assemble_jpeg_pdf(&[cmyk_jpeg(false)], 100).expect("PDF");
+    assert!(contains(&plain, "/ColorSpace /DeviceCMYK /BitsPerComponent"));
+
+    let rgb = imaging::encode_jpeg(&DynamicImage::ImageRgb8(RgbImage::new(8, 8))).expect("JPEG");
+    let gray = imaging::encode_jpeg(&DynamicImage::ImageLuma8(GrayImage::new(8, 8))).expect("JPEG");
+    let data = pdf::assemble_jpeg_pdf(&[rgb, gray], 100).expect("PDF");
+    assert!(contains(&data, "/ColorSpace /DeviceRGB"));
+    assert!(contains(&data, "/ColorSpace /DeviceGray"));
+}
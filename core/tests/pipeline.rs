@@ -0,0 +1,244 @@
+// Integrationstests Scan-Pipeline - alle Seiten landen im Dokument, PDF-Seiten ohne JPEG werden konvertiert oder abgelehnt,
+// Stapel werden an Trennblättern (leer bzw. Barcode, bei Duplex nur ganze Blätter) geteilt
+
+use docflow_bridge_core::autocrop::AutoCropConfig;
+use docflow_bridge_core::barcode::BarcodeConfig;
+use docflow_bridge_core::pipeline::{self, PipelineOptions, RenditionConfig, RenditionKind, ScanPage};
+use docflow_bridge_core::splitter::{self, SplitConfig, SplitMode};
+use docflow_bridge_core::{imaging, pdf, spool};
+use image::{DynamicImage, GrayImage, Luma, RgbImage};
+use lopdf::{dictionary, Document, Object, Stream};
+use rxing::{qrcode::QRCodeWriter, BarcodeFormat, Writer};
+
+fn options() -> PipelineOptions {
+    PipelineOptions {
+        resolution: 100,
+        duplex: false,
+        split: SplitConfig::default(),
+        barcode: BarcodeConfig::default(),
+        enhance: Vec::new(),
+        renditions: RenditionConfig::default(),
+        auto_color: None,
+        crop: AutoCropConfig::default(),
+        orientation: None,
+        regions: Vec::new(),
+        max_document_bytes: None,
+    }
+}
+
+fn page_jpeg(shade: u8) -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(40, 30, image::Rgb([shade, 30, 30])));
+    imaging::encode_jpeg(&image).expect("JPEG")
+}
+
+/// PDF mit einem Bild-XObject (Filter und Farbraum frei wählbar)
+fn pdf_with_image(dict: lopdf::Dictionary, content: Vec<u8>, compress: bool) -> Vec<u8> {
+    let mut doc = Document::with_version("1.5");
+    let mut stream = Stream::new(dict, content);
+    if compress {
+        stream.compress().expect("Flate");
+    }
+    doc.add_object(Object::Stream(stream));
+    let mut data = Vec::new();
+    doc.save_to(&mut data).expect("PDF");
+    data
+}
+
+#[test]
+fn all_pages_are_kept_without_processing() {
+    let pages: Vec<ScanPage> = (0..3)
+        .map(|i| (spool::Buffer::Memory(page_jpeg(60 * i)), "image/jpeg".to_string()))
+        .collect();
+    let documents = pipeline::process(pages, &options()).expect("Verarbeitung");
+
+    assert_eq!(documents.len(), 1);
+    let (document, mime_type) = &documents[0];
+    assert_eq!(mime_type, "application/pdf");
+    assert_eq!(imaging::extract_pdf_jpegs(&document.read().unwrap()).len(), 3);
+}
+
+#[test]
+fn single_pdf_is_passed_through_unchanged() {
+    let data = pdf::assemble_jpeg_pdf(&[page_jpeg(10), page_jpeg(20)], 100).expect("PDF");
+    let pages = vec![(spool::Buffer::Memory(data.clone()), "application/pdf".to_string())];
+    let documents = pipeline::process(pages, &options()).expect("Verarbeitung");
+
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].0.read().unwrap(), data);
+}
+
+#[test]
+fn flate_images_in_pdfs_are_converted() {
+    let rgb: Vec<u8> = (0..20 * 10).flat_map(|i| [(i % 256) as u8, 128, 0]).collect();
+    let data = pdf_with_image(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => 20,
+            "Height" => 10,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8,
+        },
+        rgb,
+        true,
+    );
+
+    let pages = imaging::pages_as_jpeg(&data, "application/pdf").expect("Konvertierung");
+    assert_eq!(pages.len(), 1);
+    let page = image::load_from_memory(&pages[0]).expect("JPEG");
+    assert_eq!((page.width(), page.height()), (20, 10));
+
+    // Schwarzweiß mit 1 Bit je Pixel (Zeilen auf ganze Bytes aufgefüllt)
+    let data = pdf_with_image(
+        dictionary! {
+            "Subtype" => "Image",
+            "Width" => 12,
+            "Height" => 2,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 1,
+        },
+        vec![0xff, 0xf0, 0x00, 0x00],
+        true,
+    );
+    let pages = imaging::pages_as_jpeg(&data, "application/pdf").expect("Konvertierung");
+    let page = image::load_from_memory(&pages[0]).expect("JPEG").to_luma8();
+    assert!(page.get_pixel(0, 0)[0] > 200);
+    assert!(page.get_pixel(0, 1)[0] < 50);
+}
+
+#[test]
+fn unsupported_pdf_images_are_an_error() {
+    let data = pdf_with_image(
+        dictionary! {
+            "Subtype" => "Image",
+            "Width" => 8,
+            "Height" => 8,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 1,
+            "Filter" => "CCITTFaxDecode",
+        },
+        vec![0; 16],
+        false,
+    );
+    let error = imaging::pages_as_jpeg(&data, "application/pdf").unwrap_err();
+    assert!(error.to_string().contains("CCITTFaxDecode"), "{}", error);
+
+    // Fehler statt eines Dokuments mit fehlenden Seiten
+    let pages = vec![(spool::Buffer::Memory(data), "application/pdf".to_string())];
+    let mut options = options();
+    options.crop.enabled = true;
+    assert!(pipeline::process(pages, &options).is_err());
+}
+
+#[test]
+fn renditions_are_unique_in_any_order() {
+    let document = pdf::assemble_jpeg_pdf(&[page_jpeg(10), page_jpeg(20)], 100).expect("PDF");
+    let config = RenditionConfig {
+        kinds: vec![RenditionKind::PageJpegs, RenditionKind::Thumbnail, RenditionKind::FirstPageJpeg],
+        ..RenditionConfig::default()
+    };
+    let renditions = pipeline::renditions(&(document, "application/pdf".to_string()), &config);
+
+    let names: Vec<&str> = renditions.iter().map(|r| r.file_name.as_str()).collect();
+    assert_eq!(names.iter().filter(|n| **n == "page_1.jpg").count(), 1, "{:?}", names);
+    assert_eq!(names.iter().filter(|n| n.starts_with("page_")).count(), 2, "{:?}", names);
+}
+
+/// Weiße Seite
+fn blank_jpeg() -> Vec<u8> {
+    imaging::encode_jpeg(&DynamicImage::ImageLuma8(GrayImage::from_pixel(400, 400, Luma([255])))).expect("JPEG")
+}
+
+/// Weiße Seite mit QR-Code (Trennblatt)
+fn qr_jpeg(value: &str) -> Vec<u8> {
+    let matrix = QRCodeWriter.encode(value, &BarcodeFormat::QR_CODE, 300, 300).expect("QR-Code");
+    let mut image = GrayImage::from_pixel(400, 400, Luma([255]));
+    for y in 0..matrix.height() {
+        for x in 0..matrix.width() {
+            if matrix.get(x, y) {
+                image.put_pixel(x + 50, y + 50, Luma([0]));
+            }
+        }
+    }
+    imaging::encode_jpeg(&DynamicImage::ImageLuma8(image)).expect("JPEG")
+}
+
+/// Seitenzahl je erzeugtem Dokument
+fn split(pages: Vec<Vec<u8>>, duplex: bool, split: SplitConfig) -> Vec<usize> {
+    let pages: Vec<ScanPage> = pages
+        .into_iter()
+        .map(|page| (spool::Buffer::Memory(page), "image/jpeg".to_string()))
+        .collect();
+    let options = PipelineOptions { duplex, split: SplitConfig { enabled: true, ..split }, ..options() };
+    pipeline::process(pages, &options)
+        .expect("Verarbeitung")
+        .iter()
+        .map(|(document, _)| imaging::extract_pdf_jpegs(&document.read().unwrap()).len())
+        .collect()
+}
+
+#[test]
+fn blank_separator_page_splits_the_batch() {
+    let pages = vec![page_jpeg(10), page_jpeg(20), blank_jpeg(), page_jpeg(30)];
+    let config = SplitConfig { mode: SplitMode::BlankPage, ..SplitConfig::default() };
+
+    assert_eq!(split(pages, false, config), vec![2, 1]);
+}
+
+#[test]
+fn separators_are_kept_unless_dropped() {
+    let pages = || vec![page_jpeg(10), blank_jpeg(), page_jpeg(30)];
+    let keep = SplitConfig { mode: SplitMode::BlankPage, drop_separators: false, ..SplitConfig::default() };
+    let drop = SplitConfig { mode: SplitMode::BlankPage, drop_separators: true, ..SplitConfig::default() };
+
+    // Behaltenes Trennblatt wird erste Seite des Folgedokuments
+    assert_eq!(split(pages(), false, keep), vec![1, 2]);
+    assert_eq!(split(pages(), false, drop), vec![1, 1]);
+}
+
+#[test]
+fn only_configured_barcodes_separate() {
+    let pages = || vec![page_jpeg(10), qr_jpeg("OTHER"), page_jpeg(20), qr_jpeg("SPLIT"), page_jpeg(30)];
+    let any = SplitConfig { mode: SplitMode::Barcode, ..SplitConfig::default() };
+    let filtered = SplitConfig {
+        mode: SplitMode::Barcode,
+        separator_values: vec!["SPLIT".to_string()],
+        ..SplitConfig::default()
+    };
+
+    assert_eq!(split(pages(), false, any), vec![1, 1, 1]);
+    // "OTHER" ist eine normale Seite
+    assert_eq!(split(pages(), false, filtered), vec![3, 1]);
+}
+
+#[test]
+fn duplex_batches_split_only_at_fully_blank_sheets() {
+    // Einseitige Vorlagen im Duplex-Scan: leere Rückseiten trennen nicht
+    let pages = vec![
+        page_jpeg(10), blank_jpeg(), // Blatt 1
+        page_jpeg(20), blank_jpeg(), // Blatt 2
+        blank_jpeg(), blank_jpeg(),  // Trennblatt
+        page_jpeg(30), blank_jpeg(), // Blatt 3
+    ];
+    let config = SplitConfig { mode: SplitMode::BlankPage, ..SplitConfig::default() };
+
+    assert_eq!(split(pages.clone(), true, config.clone()), vec![4, 2]);
+    // Ohne Duplex wäre jede leere Rückseite ein Trennblatt
+    assert_eq!(split(pages, false, config), vec![1, 1, 1]);
+}
+
+#[test]
+fn blank_page_detection_respects_the_threshold() {
+    // Dunkles Feld mit gut 1% der ausgewerteten Fläche (ohne Rand)
+    let mut page = GrayImage::from_pixel(400, 400, Luma([255]));
+    for y in 180..220 {
+        for x in 180..220 {
+            page.put_pixel(x, y, Luma([0]));
+        }
+    }
+    let page = DynamicImage::ImageLuma8(page);
+
+    assert!(!splitter::is_blank_page(&page, 0.5));
+    assert!(splitter::is_blank_page(&page, 2.0));
+    assert!(splitter::is_blank_page(&DynamicImage::ImageLuma8(GrayImage::from_pixel(400, 400, Luma([255]))), 0.0));
+}
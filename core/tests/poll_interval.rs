@@ -0,0 +1,43 @@
+// Tests adaptives Abfrage-Intervall - schnell nach Aktivität und in Bürozeiten, sonst Leerlauf-Intervall
+
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use docflow_bridge_core::settings::IntervalConfig;
+
+fn at(day: u32, hour: u32) -> chrono::NaiveDateTime {
+    // 5. Oktober 2026 ist ein Montag
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+}
+
+#[test]
+fn idle_interval_only_applies_outside_office_hours() {
+    let config = IntervalConfig::default();
+    let idle = Duration::from_secs(config.idle_after_secs);
+    let fast = Duration::from_secs(config.job_poll_secs);
+    let slow = Duration::from_secs(config.job_poll_idle_secs);
+
+    // Kürzlich aktiv: immer schnell
+    assert_eq!(config.job_poll_delay(Duration::from_secs(5), at(5, 23)), fast);
+    // Leerlauf während der Bürozeiten: schnell
+    assert_eq!(config.job_poll_delay(idle, at(5, 10)), fast);
+    // Leerlauf nachts und am Wochenende: langsam
+    assert_eq!(config.job_poll_delay(idle, at(5, 22)), slow);
+    assert_eq!(config.job_poll_delay(idle, at(10, 10)), slow);
+}
+
+#[test]
+fn invalid_office_hours_are_rejected() {
+    let mut config = IntervalConfig {
+        office_hours: "19:00-07:00".to_string(),
+        ..Default::default()
+    };
+    assert!(config.validate().is_err());
+
+    config.office_hours = String::new();
+    assert!(config.validate().is_ok());
+    assert_eq!(
+        config.job_poll_delay(Duration::from_secs(config.idle_after_secs), at(5, 10)),
+        Duration::from_secs(config.job_poll_idle_secs)
+    );
+}
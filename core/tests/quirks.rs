@@ -0,0 +1,111 @@
+// Integrationstests eSCL-Herstellerprofile - Profilauswahl, Location-Auslegung und Scan mit 503-als-belegt
+// Eigene Test-Binary mit eigenem Datenverzeichnis (XDG_CONFIG_HOME), da lokale Profile darin liegen
+
+use docflow_bridge_core::quirks::{self, LocationFormat, QuirkProfile};
+use docflow_bridge_core::scanner::{self, ScanJob};
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ORIGIN: &str = "http://192.168.1.20:80";
+const BASE_URL: &str = "http://192.168.1.20:80/eSCL";
+
+#[test]
+fn location_header_variants_resolve_to_the_scanner() {
+    let spec = QuirkProfile::default();
+    assert_eq!(spec.job_url("http://10.0.0.5/eSCL/ScanJobs/7", ORIGIN, BASE_URL), "http://10.0.0.5/eSCL/ScanJobs/7");
+    assert_eq!(spec.job_url("/eSCL/ScanJobs/7", ORIGIN, BASE_URL), format!("{}/ScanJobs/7", BASE_URL));
+    assert_eq!(spec.job_url("ScanJobs/7", ORIGIN, BASE_URL), format!("{}/ScanJobs/7", BASE_URL));
+    assert_eq!(spec.job_url("7", ORIGIN, BASE_URL), format!("{}/ScanJobs/7", BASE_URL));
+
+    let host = QuirkProfile { location: LocationFormat::ScannerHost, ..QuirkProfile::default() };
+    assert_eq!(
+        host.job_url("http://hpscanner.local:8080/eSCL/ScanJobs/7", ORIGIN, BASE_URL),
+        format!("{}/ScanJobs/7", BASE_URL)
+    );
+}
+
+#[test]
+fn profiles_are_chosen_by_manufacturer_and_model() {
+    let data_dir = tempfile::tempdir().expect("Temp-Ordner");
+    std::env::set_var("XDG_CONFIG_HOME", data_dir.path());
+
+    // Mitgelieferte Profile
+    assert!(!quirks::bundled().is_empty());
+    let brother = quirks::for_scanner("Brother", "Brother MFC-L2750DW");
+    assert!(brother.is_busy(503));
+    assert_eq!(brother.job_state("Running"), "Processing");
+    let unknown = quirks::for_scanner("Unknown", "Generic eSCL");
+    assert!(!unknown.is_busy(503));
+    assert_eq!(unknown.job_state("Running"), "Running");
+
+    // Lokales Modellprofil hat Vorrang vor dem mitgelieferten Herstellerprofil
+    std::fs::create_dir_all(quirks::local_path().parent().unwrap()).unwrap();
+    std::fs::write(
+        quirks::local_path(),
+        r#"[{"manufacturer": "brother", "model": "*MFC-L27??DW", "busy_status": [409, 423], "escl_version": "2.6"}]"#,
+    )
+    .unwrap();
+    let patched = quirks::for_scanner("Brother", "Brother MFC-L2750DW");
+    assert!(patched.is_busy(423));
+    assert_eq!(patched.escl_version.as_deref(), Some("2.6"));
+    assert!(quirks::for_scanner("Brother", "Brother DCP-J1100DW").is_busy(503));
+}
+
+#[tokio::test]
+async fn busy_503_is_retried_and_relative_location_followed() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScannerStatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<pwg:State>Idle</pwg:State>"))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/eSCL/ScanJobs"))
+        .and(body_string_contains("<pwg:Version>2.5</pwg:Version>"))
+        .respond_with(ResponseTemplate::new(201).insert_header("Location", "ScanJobs/9"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/9/NextDocument"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4 page".to_vec()))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/eSCL/ScanJobs/9/NextDocument"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let job = ScanJob {
+        scanner_id: "mock".to_string(),
+        resolution: 150,
+        color_mode: "grayscale".to_string(),
+        format: "application/pdf".to_string(),
+        source: "adf".to_string(),
+        duplex: false,
+        page_retries: 0,
+        allow_partial: false,
+        height: None,
+        regions: Vec::new(),
+        timeout_secs: 30,
+        spool_after_kb: 0,
+    };
+    let canon = quirks::bundled()
+        .into_iter()
+        .find(|profile| profile.manufacturer == "Canon")
+        .expect("Canon-Profil");
+    let result = scanner::scan_escl_with_quirks("127.0.0.1", server.address().port(), false, "eSCL", &job, &canon)
+        .await
+        .expect("Scan erfolgreich");
+    assert_eq!(result.total_pages, 1);
+}
@@ -0,0 +1,159 @@
+// Integrationstests Anfragesignatur - HMAC-Header an authentifizierten DocFlow-Anfragen
+// Eigene Test-Binary, da Netzwerk-Einstellungen und Signaturschlüssel global sind
+
+use docflow_bridge_core::http_client::{self, HttpConfig};
+use docflow_bridge_core::multipart::{Form, Part};
+use docflow_bridge_core::rate_limit::RateLimited;
+use docflow_bridge_core::request_signing::{self, SIGNATURE_HEADER};
+use sha2::{Digest, Sha256};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Beide Tests ändern den globalen Signaturschlüssel
+static SERIAL: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn hmac_matches_rfc4231() {
+    // RFC 4231, Testfall 2
+    assert_eq!(
+        hex(&request_signing::hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    // Testfall 6: Schlüssel länger als ein Block
+    assert_eq!(
+        hex(&request_signing::hmac_sha256(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First"
+        )),
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    );
+}
+
+#[tokio::test]
+async fn signs_authenticated_requests() {
+    let _serial = SERIAL.lock().await;
+    http_client::apply_config(&HttpConfig {
+        request_signing: true,
+        ..HttpConfig::default()
+    })
+    .unwrap();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/heartbeat"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let url = format!("{}/api/scanner/bridge/heartbeat?verbose=1", server.uri());
+    let body = r#"{"status":"online"}"#;
+    let send = || {
+        http_client::docflow()
+            .post(&url)
+            .header("Authorization", "Bearer test-key")
+            .body(body)
+            .send_limited()
+    };
+
+    // Ohne Schlüssel wird nicht unsigniert gesendet
+    request_signing::set_secret(None);
+    assert!(send().await.is_err());
+    assert!(server.received_requests().await.unwrap().is_empty());
+
+    request_signing::set_secret(Some("geheim"));
+    send().await.unwrap();
+    send().await.unwrap();
+    // Pairing-Anfragen ohne Authorization bleiben unsigniert
+    http_client::docflow().post(&url).send_limited().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 3);
+    let header = |i: usize, name: &str| requests[i].headers.get(name).map(|v| v.to_str().unwrap().to_string());
+
+    let timestamp = header(0, request_signing::TIMESTAMP_HEADER).unwrap();
+    let nonce = header(0, request_signing::NONCE_HEADER).unwrap();
+    let content_hash = header(0, request_signing::CONTENT_HASH_HEADER).unwrap();
+    assert_eq!(content_hash, hex(&Sha256::digest(body.as_bytes())));
+    let expected = request_signing::signature(
+        "geheim",
+        "POST",
+        "/api/scanner/bridge/heartbeat?verbose=1",
+        &timestamp,
+        &nonce,
+        &content_hash,
+    );
+    assert_eq!(header(0, SIGNATURE_HEADER).unwrap(), format!("v1={}", expected));
+
+    // Jede Anfrage mit eigener Nonce (Schutz vor Wiederholung)
+    assert_ne!(header(1, request_signing::NONCE_HEADER).unwrap(), nonce);
+    assert_eq!(header(2, SIGNATURE_HEADER), None);
+}
+
+#[tokio::test]
+async fn signs_streamed_multipart_bodies() {
+    let _serial = SERIAL.lock().await;
+    http_client::apply_config(&HttpConfig {
+        request_signing: true,
+        ..HttpConfig::default()
+    })
+    .unwrap();
+    request_signing::set_secret(Some("geheim"));
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/scanner/bridge/folder-upload"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    // Datei größer als ein Block, damit mehrere Blöcke gestreamt werden
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("rechnung.pdf");
+    let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&file, &content).unwrap();
+
+    let form = Form::new()
+        .part(
+            "file",
+            Part::file(&file)
+                .await
+                .unwrap()
+                .file_name("rechnung \"neu\".pdf")
+                .mime_str("application/pdf")
+                .unwrap(),
+        )
+        .text("file_hash", "abc")
+        .part("renditions", Part::bytes(b"thumb".to_vec()).file_name("thumb.jpg"));
+    let expected_hash = form.content_hash().await.unwrap();
+    let expected_len = form.len();
+    let request = http_client::docflow()
+        .post(format!("{}/api/scanner/bridge/folder-upload", server.uri()))
+        .header("Authorization", "Bearer test-key");
+    form.attach(request).await.unwrap().send_limited().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let request = &requests[0];
+    let header = |name: &str| request.headers.get(name).unwrap().to_str().unwrap().to_string();
+
+    // Signiert wird der tatsächlich gesendete Body statt UNSIGNED-PAYLOAD
+    let content_hash = header(request_signing::CONTENT_HASH_HEADER);
+    assert_eq!(content_hash, hex(&Sha256::digest(&request.body)));
+    assert_eq!(content_hash, expected_hash);
+    assert_eq!(request.body.len() as u64, expected_len);
+    let expected = request_signing::signature(
+        "geheim",
+        "POST",
+        "/api/scanner/bridge/folder-upload",
+        &header(request_signing::TIMESTAMP_HEADER),
+        &header(request_signing::NONCE_HEADER),
+        &content_hash,
+    );
+    assert_eq!(header(SIGNATURE_HEADER), format!("v1={}", expected));
+
+    let body = String::from_utf8_lossy(&request.body);
+    assert!(header("content-type").starts_with("multipart/form-data; boundary="));
+    assert!(body.contains("name=\"file\"; filename=\"rechnung \\\"neu\\\".pdf\"\r\nContent-Type: application/pdf"));
+    assert!(body.contains("name=\"file_hash\"\r\n\r\nabc\r\n"));
+    assert!(request.body.windows(content.len()).any(|w| w == content.as_slice()));
+}
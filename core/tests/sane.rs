@@ -0,0 +1,56 @@
+// Integrationstests SANE - Bilddaten aus sane_read in Seiten wandeln und Optionswerte des Geräts wählen
+#![cfg(target_os = "linux")]
+
+use docflow_bridge_core::sane::{self, FrameParameters};
+use image::GenericImageView;
+
+fn parameters(format: i32, depth: i32, pixels_per_line: i32, bytes_per_line: i32, lines: i32) -> FrameParameters {
+    FrameParameters {
+        format,
+        last_frame: 1,
+        bytes_per_line,
+        pixels_per_line,
+        lines,
+        depth,
+    }
+}
+
+#[test]
+fn lineart_bits_become_black_and_white_pixels() {
+    // 10 Pixel je Zeile → 2 Bytes, gesetztes Bit = schwarz
+    let data = vec![0b1000_0000, 0b0100_0000, 0b0000_0000, 0b0000_0000];
+    let image = sane::frame_image(&parameters(0, 1, 10, 2, 2), data).expect("Bild");
+    assert_eq!(image.dimensions(), (10, 2));
+    let gray = image.to_luma8();
+    assert_eq!(gray.get_pixel(0, 0).0, [0]);
+    assert_eq!(gray.get_pixel(1, 0).0, [255]);
+    assert_eq!(gray.get_pixel(9, 0).0, [0]);
+    assert_eq!(gray.get_pixel(0, 1).0, [255]);
+}
+
+#[test]
+fn rgb_frame_with_unknown_height_uses_read_data() {
+    // Zeilen mit Füllbyte am Ende, Höhe -1 (Einzug mit Längenerkennung)
+    let row = [255, 0, 0, 0, 0, 255, 9];
+    let data = [row, row, row].concat();
+    let image = sane::frame_image(&parameters(1, 8, 2, 7, -1), data).expect("Bild");
+    assert_eq!(image.dimensions(), (2, 3));
+    let rgb = image.to_rgb8();
+    assert_eq!(rgb.get_pixel(0, 2).0, [255, 0, 0]);
+    assert_eq!(rgb.get_pixel(1, 2).0, [0, 0, 255]);
+}
+
+#[test]
+fn truncated_or_three_pass_frames_are_rejected() {
+    assert!(sane::frame_image(&parameters(0, 8, 4, 4, 3), vec![0; 8]).is_err());
+    // SANE_FRAME_RED (Drei-Durchgang-Scanner)
+    assert!(sane::frame_image(&parameters(2, 8, 4, 4, 1), vec![0; 4]).is_err());
+}
+
+#[test]
+fn pick_prefers_earlier_terms_and_ignores_case() {
+    let sources: Vec<String> = ["Flatbed", "ADF Front", "ADF Duplex"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(sane::pick(&sources, &["duplex"]).as_deref(), Some("ADF Duplex"));
+    assert_eq!(sane::pick(&sources, &["adf", "feeder"]).as_deref(), Some("ADF Front"));
+    assert_eq!(sane::pick(&sources, &["platen"]), None);
+}
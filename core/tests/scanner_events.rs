@@ -0,0 +1,110 @@
+// Integrationstests WSD-Zustands-Events - Zustand, Störungen und leerer Einzug aus WS-Eventing-Nachrichten
+
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use docflow_bridge_core::push_scan::{self, PushScanConfig};
+use docflow_bridge_core::scanner_events;
+use docflow_bridge_core::virtual_scanner::{self, VirtualScannerConfig};
+
+fn event(scanner_id: &str, action: &str, body: &str) -> String {
+    format!(
+        r#"<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"
+               xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing"
+               xmlns:sca="http://schemas.microsoft.com/windows/2006/08/wdp/scan"
+               xmlns:docflow="urn:docflow:scanner-bridge">
+    <soap:Header>
+        <wsa:Action>http://schemas.microsoft.com/windows/2006/08/wdp/scan/{action}</wsa:Action>
+        <docflow:ScannerId>{scanner_id}</docflow:ScannerId>
+        <docflow:Token>secret</docflow:Token>
+    </soap:Header>
+    <soap:Body><sca:{action}>{body}</sca:{action}></soap:Body>
+</soap:Envelope>"#
+    )
+}
+
+#[test]
+fn events_update_state_and_conditions() {
+    let scanner_id = "192.168.1.50:80";
+    assert!(scanner_events::current(scanner_id).is_none());
+    scanner_events::subscribed(scanner_id);
+
+    assert!(scanner_events::handle_event(&event(
+        scanner_id,
+        "ScannerStatusSummaryEvent",
+        "<sca:StatusSummary><sca:ScannerState>Processing</sca:ScannerState></sca:StatusSummary>",
+    )));
+    let state = scanner_events::current(scanner_id).expect("Zustand gemeldet");
+    assert_eq!(state.state.as_deref(), Some("Processing"));
+
+    let condition = |id: &str, name: &str| {
+        format!(
+            "<sca:DeviceCondition><sca:ConditionId>{}</sca:ConditionId><sca:Name>{}</sca:Name></sca:DeviceCondition>",
+            id, name
+        )
+    };
+    assert!(scanner_events::handle_event(&event(scanner_id, "ScannerStatusConditionEvent", &condition("1", "InputTrayEmpty"))));
+    assert!(scanner_events::handle_event(&event(scanner_id, "ScannerStatusConditionEvent", &condition("2", "MediaJam"))));
+
+    let state = scanner_events::current(scanner_id).unwrap();
+    assert!(state.adf_empty());
+    assert_eq!(state.faults(), vec!["MediaJam"]);
+
+    assert!(scanner_events::handle_event(&event(
+        scanner_id,
+        "ScannerStatusConditionClearedEvent",
+        "<sca:DeviceConditionCleared><sca:ConditionId>2</sca:ConditionId></sca:DeviceConditionCleared>",
+    )));
+    assert!(scanner_events::current(scanner_id).unwrap().faults().is_empty());
+}
+
+#[test]
+fn scan_available_event_is_not_a_status_event() {
+    let xml = event("192.168.1.51:80", "ScanAvailableEvent", "<sca:ClientContext>docflow:x</sca:ClientContext>");
+    assert!(!scanner_events::handle_event(&xml));
+}
+
+#[test]
+fn pushed_state_expires_without_new_events() {
+    let scanner_id = "192.168.1.52:80";
+    scanner_events::subscribed(scanner_id);
+    assert!(scanner_events::handle_event(&event(
+        scanner_id,
+        "ScannerStatusSummaryEvent",
+        "<sca:StatusSummary><sca:ScannerState>Processing</sca:ScannerState></sca:StatusSummary>",
+    )));
+    assert!(scanner_events::current(scanner_id).is_some());
+
+    // Idle-Event verloren: nach Ablauf wieder ScannerStatus abfragen statt weiter "Processing"
+    let later = Instant::now() + scanner_events::PUSHED_STATE_TTL + Duration::from_secs(1);
+    assert!(scanner_events::current_as_of(scanner_id, later).is_none());
+}
+
+#[test]
+fn events_are_only_accepted_with_token_from_the_subscribed_scanner() {
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.id = "192.168.1.53:80".to_string();
+    scanner.ip = "192.168.1.53".to_string();
+    scanner.wsd_url = Some("http://192.168.1.54:5357/WDP/SCAN".to_string());
+    let scanners = vec![scanner];
+    let config = PushScanConfig::default();
+    let xml = event("192.168.1.53:80", "ScannerStatusSummaryEvent", "");
+    let ip = |address: &str| address.parse::<IpAddr>().unwrap();
+
+    assert_eq!(
+        push_scan::verify_event(&xml, ip("192.168.1.53"), "secret", &config, &scanners).as_deref(),
+        Some("192.168.1.53:80")
+    );
+    // WSD-Endpunkt des Scanners liegt auf einer anderen Adresse
+    assert!(push_scan::verify_event(&xml, ip("192.168.1.54"), "secret", &config, &scanners).is_some());
+
+    // Falsches Token, fremder Absender oder unbekannter Scanner
+    assert!(push_scan::verify_event(&xml, ip("192.168.1.53"), "other", &config, &scanners).is_none());
+    assert!(push_scan::verify_event(&xml, ip("192.168.1.99"), "secret", &config, &scanners).is_none());
+    let unknown = event("192.168.1.60:80", "ScannerStatusSummaryEvent", "");
+    assert!(push_scan::verify_event(&unknown, ip("192.168.1.53"), "secret", &config, &scanners).is_none());
+
+    // Freigegebene Zusatzadresse
+    let config = PushScanConfig { allowed_sources: vec!["192.168.1.99".to_string()], ..PushScanConfig::default() };
+    assert!(push_scan::verify_event(&xml, ip("192.168.1.99"), "secret", &config, &scanners).is_some());
+}
@@ -0,0 +1,142 @@
+// Integrationstests SNMP - GetResponse lesen, Sweep gegen einen lokalen Agenten (v2c, Rückfall auf v1) und Übernahme in die Discovery
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use docflow_bridge_core::discovery;
+use docflow_bridge_core::snmp::{self, SnmpDevice};
+use docflow_bridge_core::virtual_scanner::{self, VirtualScannerConfig};
+use tokio::net::UdpSocket;
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let header = match content.len() {
+        len @ 0..=0x7f => vec![tag, len as u8],
+        len => vec![tag, 0x81, len as u8],
+    };
+    [header, content.to_vec()].concat()
+}
+
+/// INTEGER ohne führende Nullbytes (positiv)
+fn integer(value: u32) -> Vec<u8> {
+    let mut bytes: Vec<u8> = value.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+    if bytes.first().is_none_or(|b| b & 0x80 != 0) {
+        bytes.insert(0, 0);
+    }
+    tlv(0x02, &bytes)
+}
+
+/// GetResponse (SNMPv2c) mit je einem Varbind pro Wert: Some = OCTET STRING, None = noSuchObject
+fn response(request_id: u32, values: &[Option<&str>]) -> Vec<u8> {
+    let varbinds: Vec<u8> = values
+        .iter()
+        .flat_map(|value| {
+            let oid = tlv(0x06, &[0x2b, 6, 1, 2, 1, 1, 1, 0]);
+            let value = match value {
+                Some(text) => tlv(0x04, text.as_bytes()),
+                None => vec![0x80, 0x00],
+            };
+            tlv(0x30, &[oid, value].concat())
+        })
+        .collect();
+    let pdu = tlv(
+        0xa2,
+        &[integer(request_id), tlv(0x02, &[0]), tlv(0x02, &[0]), tlv(0x30, &varbinds)].concat(),
+    );
+    tlv(0x30, &[tlv(0x02, &[1]), tlv(0x04, b"public"), pdu].concat())
+}
+
+#[test]
+fn get_response_yields_one_value_per_oid() {
+    let answer = response(
+        0x1234_5678,
+        &[Some("Brother NC-8300h, Firmware Ver.1.04"), None, Some("E12345F6N789\0")],
+    );
+    let values = snmp::decode_get_response(&answer, 0x1234_5678).expect("Antwort lesbar");
+    assert_eq!(
+        values,
+        vec![Some("Brother NC-8300h, Firmware Ver.1.04".to_string()), None, Some("E12345F6N789".to_string())]
+    );
+
+    let device = SnmpDevice::from_values("192.168.1.40", &values);
+    assert_eq!(device.model_name().as_deref(), Some("Brother NC-8300h"));
+    assert_eq!(device.serial_number.as_deref(), Some("E12345F6N789"));
+
+    // Antwort auf eine andere Anfrage bzw. Anfrage statt Antwort wird nicht als Ergebnis gelesen
+    assert!(snmp::decode_get_response(&answer, 7).is_none());
+    let request = snmp::device_request(snmp::VERSION_2C, "public", 7);
+    assert_eq!(snmp::request_id(&request), Some(7));
+    assert!(snmp::decode_get_response(&request, 7).is_none());
+}
+
+#[tokio::test]
+async fn sweep_collects_agents_and_fills_generic_scanner() {
+    let agent = UdpSocket::bind("127.0.0.1:0").await.expect("Agent-Socket");
+    let agent_addr = agent.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 1500];
+        let (len, from) = agent.recv_from(&mut buffer).await.unwrap();
+        let request_id = snmp::request_id(&buffer[..len]).expect("Request-ID");
+        // Verspätete Antwort auf eine fremde Anfrage wird übergangen
+        let stale = response(request_id.wrapping_add(1), &[Some("Fremdes Gerät")]);
+        agent.send_to(&stale, from).await.unwrap();
+        let answer = response(
+            request_id,
+            &[
+                Some("HP ETHERNET MULTI-ENVIRONMENT,ROM none,JETDIRECT"),
+                Some("HP LaserJet MFP M428fdw"),
+                Some("PHBQM12345"),
+            ],
+        );
+        agent.send_to(&answer, from).await.unwrap();
+    });
+
+    // Zweites Ziel antwortet nicht
+    let silent: SocketAddr = "127.0.0.1:9".parse().unwrap();
+    let devices = snmp::sweep(&[agent_addr, silent], "public", Duration::from_millis(500))
+        .await
+        .expect("Sweep");
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].ip, "127.0.0.1");
+
+    // Generischer Eintrag aus der eSCL-Prüfung bekommt Hersteller, Modell und Seriennummer
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.name = "Scanner at 127.0.0.1".to_string();
+    scanner.manufacturer = "Unknown".to_string();
+    discovery::apply_snmp(&mut scanner, &devices[0]);
+    assert_eq!(scanner.manufacturer, "HP");
+    assert_eq!(scanner.model, "HP LaserJet MFP M428fdw");
+    assert_eq!(scanner.name, "HP LaserJet MFP M428fdw");
+    assert_eq!(scanner.serial_number.as_deref(), Some("PHBQM12345"));
+
+    // Bekannter Hersteller (z.B. per mDNS) bleibt, nur die Seriennummer wird ergänzt
+    let mut known = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    let model = known.model.clone();
+    discovery::apply_snmp(&mut known, &devices[0]);
+    assert_eq!(known.model, model);
+    assert_eq!(known.serial_number.as_deref(), Some("PHBQM12345"));
+}
+
+#[tokio::test]
+async fn agents_without_v2c_are_asked_again_with_v1() {
+    let agent = UdpSocket::bind("127.0.0.1:0").await.expect("Agent-Socket");
+    let agent_addr = agent.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 1500];
+        loop {
+            let (len, from) = agent.recv_from(&mut buffer).await.unwrap();
+            // Nachricht: SEQUENCE, Länge, INTEGER-Tag, Länge 1, Version
+            if buffer[4] as u32 != snmp::VERSION_1 {
+                continue;
+            }
+            let request_id = snmp::request_id(&buffer[..len]).expect("Request-ID");
+            let answer = response(request_id, &[Some("Canon iR-ADV C3530 /P")]);
+            agent.send_to(&answer, from).await.unwrap();
+        }
+    });
+
+    let devices = snmp::sweep(&[agent_addr], "public", Duration::from_millis(300))
+        .await
+        .expect("Sweep");
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].model_name().as_deref(), Some("Canon iR-ADV C3530 /P"));
+}
@@ -0,0 +1,50 @@
+// Integrationstests Spool - Verschlüsselung auf Disk, Auslagerung großer Dokumente und sicheres Löschen
+// Eigene Test-Binary mit eigenem Datenverzeichnis (XDG_CONFIG_HOME), da der Spool darin liegt
+
+use docflow_bridge_core::spool::{self, Buffer, SpoolConfig, SpoolFile};
+
+#[test]
+fn spool_encrypts_at_rest_and_deletes_after_use() {
+    let data_dir = tempfile::tempdir().expect("Temp-Ordner");
+    std::env::set_var("XDG_CONFIG_HOME", data_dir.path());
+
+    // Reste eines früheren Starts werden beim Aufräumen entfernt
+    std::fs::create_dir_all(spool::spool_dir()).unwrap();
+    std::fs::write(spool::spool_dir().join("alt.spool"), b"unlesbar").unwrap();
+    assert_eq!(spool::cleanup(), 1);
+
+    let document = b"%PDF-1.4 Gehaltsabrechnung Max Mustermann".repeat(100);
+    let file = SpoolFile::write(&document).unwrap();
+    let path = file.path().to_path_buf();
+    assert_eq!(file.len(), document.len());
+
+    // Auf Disk kein Klartext
+    let on_disk = std::fs::read(&path).unwrap();
+    assert!(!on_disk.windows(16).any(|w| w == b"Gehaltsabrechnun"));
+    assert_eq!(file.read().unwrap(), document);
+
+    // Manipulierte Datei wird erkannt
+    let mut tampered = on_disk.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 0xff;
+    std::fs::write(&path, &tampered).unwrap();
+    assert!(file.read().is_err());
+
+    drop(file);
+    assert!(!path.exists());
+
+    // Nur große Dokumente werden ausgelagert
+    let config = SpoolConfig { spill_over_kb: 1 };
+    assert!(matches!(Buffer::new(vec![1; 512], &config), Buffer::Memory(_)));
+    let spooled = Buffer::new(vec![7; 4096], &config);
+    let Buffer::Spooled(file) = &spooled else {
+        panic!("Dokument nicht ausgelagert");
+    };
+    let path = file.path().to_path_buf();
+    assert!(path.exists());
+    assert_eq!(spooled.into_data().unwrap(), vec![7; 4096]);
+    assert!(!path.exists());
+
+    let never = SpoolConfig { spill_over_kb: 0 };
+    assert!(matches!(Buffer::new(vec![7; 4096], &never), Buffer::Memory(_)));
+}
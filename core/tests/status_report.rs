@@ -0,0 +1,47 @@
+// Integrationstests Statusmeldungen - vollständige Meldung, Deltas, Heartbeat und Neusynchronisation nach Fehlern
+
+use std::time::Duration;
+
+use docflow_bridge_core::status_report::DeltaReport;
+use serde_json::{json, Map, Value};
+
+fn status(files_uploaded: u32, watched_folder: &str) -> Map<String, Value> {
+    let Value::Object(map) = json!({ "files_uploaded": files_uploaded, "watched_folder": watched_folder }) else {
+        unreachable!()
+    };
+    map
+}
+
+#[test]
+fn only_changes_are_reported_after_first_full_report() {
+    let mut report = DeltaReport::new();
+    let heartbeat = Duration::from_secs(300);
+
+    let first = report.next(&status(1, "C:\\Scans"), heartbeat).expect("erste Meldung");
+    assert_eq!(first["report_type"], "full");
+    assert_eq!(first["watched_folder"], "C:\\Scans");
+    report.confirm(status(1, "C:\\Scans"));
+
+    // Unverändert und Heartbeat noch nicht fällig
+    assert!(report.next(&status(1, "C:\\Scans"), heartbeat).is_none());
+
+    let delta = report.next(&status(2, "C:\\Scans"), heartbeat).expect("Änderung");
+    assert_eq!(delta["report_type"], "delta");
+    assert_eq!(delta["files_uploaded"], 2);
+    assert!(delta.get("watched_folder").is_none(), "{}", delta);
+
+    // Unverändert, aber Heartbeat fällig
+    let heartbeat_only = report.next(&status(1, "C:\\Scans"), Duration::ZERO).expect("Heartbeat");
+    assert_eq!(heartbeat_only, json!({ "report_type": "heartbeat" }));
+}
+
+#[test]
+fn failed_report_resends_full_status() {
+    let mut report = DeltaReport::new();
+    report.confirm(status(1, "C:\\Scans"));
+    report.reset();
+
+    let next = report.next(&status(1, "C:\\Scans"), Duration::from_secs(300)).expect("Neusynchronisation");
+    assert_eq!(next["report_type"], "full");
+    assert_eq!(next["files_uploaded"], 1);
+}
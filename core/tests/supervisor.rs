@@ -0,0 +1,28 @@
+// Tests Supervisor - beendete Tasks werden mit Backoff zum Neustart gemeldet
+
+use std::time::Duration;
+
+use docflow_bridge_core::supervisor;
+
+#[tokio::test]
+async fn finished_task_is_restarted_with_backoff() {
+    // Task, der sofort abstürzt
+    let handle = tokio::spawn(async { panic!("Absturz im Test") });
+    supervisor::watch(supervisor::POLLER, handle);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    assert_eq!(supervisor::due_for_restart(), vec![supervisor::POLLER]);
+    // Neu gestartet, stürzt wieder ab: Backoff verhindert sofortigen zweiten Neustart
+    supervisor::watch(supervisor::POLLER, tokio::spawn(async {}));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(supervisor::due_for_restart().is_empty());
+
+    let health = supervisor::status();
+    assert_eq!(health.len(), 1);
+    assert_eq!(health[0].restarts, 1);
+    assert!(!health[0].healthy);
+    assert!(supervisor::failing().is_none(), "ein Neustart ist noch kein Dauerfehler");
+
+    supervisor::unwatch(supervisor::POLLER);
+    assert!(supervisor::status().is_empty());
+}
@@ -0,0 +1,94 @@
+// Integrationstests System-Proxy - Auswertung von PAC-Skripten und Windows-Proxy-Einträgen
+
+use docflow_bridge_core::http_client::{HttpConfig, ProxyMode};
+use docflow_bridge_core::system_proxy::{self, PacDecision, ProxySource};
+
+#[test]
+fn pac_script_without_conditions_yields_first_proxy() {
+    let script = r#"
+        // Alles über den Proxy, if-Abfragen folgen später
+        function FindProxyForURL(url, host) {
+            return "PROXY proxy.firma.local:3128; PROXY backup.firma.local:8080; DIRECT";
+        }
+    "#;
+    assert_eq!(
+        system_proxy::parse_pac(script),
+        PacDecision::Proxy("http://proxy.firma.local:3128".to_string())
+    );
+    assert_eq!(
+        system_proxy::parse_pac("function FindProxyForURL(url, host) { return 'DIRECT'; }"),
+        PacDecision::Direct
+    );
+}
+
+#[test]
+fn pac_script_with_conditions_is_not_guessed() {
+    let script = r#"
+        function FindProxyForURL(url, host) {
+            if (isPlainHostName(host) || dnsDomainIs(host, ".firma.local")) {
+                return "DIRECT";
+            }
+            return "PROXY proxy.firma.local:3128; DIRECT";
+        }
+    "#;
+    assert_eq!(system_proxy::parse_pac(script), PacDecision::Conditional);
+    let ternary = "function FindProxyForURL(url, host) { return isPlainHostName(host) ? 'DIRECT' : 'PROXY p:8080'; }";
+    assert_eq!(system_proxy::parse_pac(ternary), PacDecision::Conditional);
+}
+
+#[test]
+fn local_exception_matches_plain_host_names_only() {
+    assert!(system_proxy::is_plain_host_name("docflow"));
+    assert!(!system_proxy::is_plain_host_name("docflow.firma.local"));
+    assert!(!system_proxy::is_plain_host_name("10.0.0.5"));
+    assert!(!system_proxy::is_plain_host_name("::1"));
+}
+
+#[test]
+fn windows_proxy_server_prefers_https_entry() {
+    assert_eq!(
+        system_proxy::parse_proxy_server("proxy.firma.local:3128").as_deref(),
+        Some("http://proxy.firma.local:3128")
+    );
+    assert_eq!(
+        system_proxy::parse_proxy_server("ftp=ftp.firma.local:21;http=web:80;https=secure:8443").as_deref(),
+        Some("http://secure:8443")
+    );
+    assert_eq!(system_proxy::parse_proxy_server("socks=socks.firma.local:1080"), None);
+}
+
+#[test]
+fn manual_proxy_and_direct_mode_override_detection() {
+    let manual = HttpConfig {
+        proxy_url: Some("http://proxy.manuell:3128".to_string()),
+        ..Default::default()
+    };
+    let detected = system_proxy::effective(&manual).expect("eingetragener Proxy");
+    assert_eq!(detected.source, ProxySource::Manual);
+    assert_eq!(detected.proxy_url.as_deref(), Some("http://proxy.manuell:3128"));
+
+    let direct = HttpConfig {
+        proxy_mode: ProxyMode::Direct,
+        ..manual
+    };
+    let detected = system_proxy::effective(&direct).expect("direkt");
+    assert_eq!(detected.source, ProxySource::Direct);
+    assert_eq!(detected.proxy_url, None);
+}
+
+#[test]
+fn proxy_exceptions_apply_to_tunnel_hosts() {
+    let proxy = system_proxy::DetectedProxy {
+        source: ProxySource::System,
+        proxy_url: Some("http://proxy.firma.local:3128".to_string()),
+        no_proxy: Some(".firma.local,docflow.intern".to_string()),
+        bypass_local: true,
+        pac_url: None,
+    };
+    assert!(system_proxy::bypasses(&proxy, "docflow.firma.local"));
+    assert!(system_proxy::bypasses(&proxy, "firma.local"));
+    assert!(system_proxy::bypasses(&proxy, "docflow.intern"));
+    assert!(system_proxy::bypasses(&proxy, "docflow"));
+    assert!(!system_proxy::bypasses(&proxy, "docflow.example.com"));
+    assert!(!system_proxy::bypasses(&proxy, "notfirma.local"));
+}
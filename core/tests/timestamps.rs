@@ -0,0 +1,31 @@
+// Integrationstests Zeitangaben - "vor X" und Dauer-Texte für die Statusanzeige
+
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use docflow_bridge_core::timestamps::{display_at, duration};
+
+#[test]
+fn timestamps_are_described_relative_to_now() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+
+    let recent = display_at("2026-10-16T11:59:55Z", now).expect("gültiger Zeitstempel");
+    assert_eq!(recent.seconds_ago, 5);
+    assert_eq!(recent.ago, "gerade eben");
+
+    // Andere Zeitzone im gespeicherten Wert
+    let earlier = display_at("2026-10-16T13:30:00+02:00", now).unwrap();
+    assert_eq!(earlier.seconds_ago, 1800);
+    assert_eq!(earlier.ago, "vor 30 Min.");
+
+    assert_eq!(display_at("2026-10-16T12:05:00Z", now).unwrap().seconds_ago, 0);
+    assert!(display_at("gestern", now).is_none());
+}
+
+#[test]
+fn durations_are_readable() {
+    assert_eq!(duration(Duration::from_secs(42)), "42 s");
+    assert_eq!(duration(Duration::from_secs(7200)), "2 Std.");
+    assert_eq!(duration(Duration::from_secs(7500)), "2 Std. 5 Min.");
+    assert_eq!(duration(Duration::from_secs(3 * 86_400 + 4 * 3600)), "3 Tg. 4 Std.");
+}
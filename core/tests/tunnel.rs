@@ -0,0 +1,110 @@
+// Integrationstests Reverse-Tunnel - URL-Ableitung, Anfrage/Antwort-Protokoll und Verbindung zu einem WebSocket-Server
+// Eigene Test-Binary, da der Tunnel-Status global ist
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use docflow_bridge_core::scan_poller::ScanPoller;
+use docflow_bridge_core::settings::BridgeSettings;
+use docflow_bridge_core::tunnel::{self, TunnelConfig, TunnelResponse, TunnelService};
+use futures::{SinkExt, StreamExt};
+use tokio::sync::RwLock;
+use tokio_tungstenite::tungstenite::Message;
+
+const API_KEY: &str = "test-api-key";
+
+fn poller(docflow_url: &str, settings: BridgeSettings) -> Arc<ScanPoller> {
+    Arc::new(ScanPoller::new(
+        API_KEY.to_string(),
+        docflow_url.to_string(),
+        Arc::new(RwLock::new(Vec::new())),
+        Arc::new(RwLock::new(settings)),
+    ))
+}
+
+#[test]
+fn endpoint_is_derived_from_docflow_url() {
+    let config = TunnelConfig::default();
+    assert_eq!(
+        config.endpoint("https://docflow.example/"),
+        "wss://docflow.example/api/scanner/bridge/tunnel"
+    );
+    assert_eq!(config.endpoint("http://10.0.0.5:8000"), "ws://10.0.0.5:8000/api/scanner/bridge/tunnel");
+
+    let custom = TunnelConfig { url: "wss://tunnel.example/bridge".to_string(), ..TunnelConfig::default() };
+    assert_eq!(custom.endpoint("https://docflow.example"), "wss://tunnel.example/bridge");
+}
+
+#[tokio::test]
+async fn requests_are_answered_with_their_id() {
+    let poller = poller("http://docflow.invalid", BridgeSettings::default());
+
+    let pong = tunnel::respond(&poller, r#"{"id":"r1","type":"ping"}"#).await;
+    assert_eq!(pong.id, "r1");
+    assert!(pong.ok);
+    assert_eq!(pong.result, Some(serde_json::json!("pong")));
+
+    let jobs = tunnel::respond(&poller, r#"{"id":"r2","type":"active_jobs"}"#).await;
+    assert_eq!(jobs.result, Some(serde_json::json!([])));
+
+    let unknown = tunnel::respond(&poller, r#"{"id":"r3","type":"reboot"}"#).await;
+    assert_eq!(unknown.id, "r3");
+    assert!(!unknown.ok);
+    assert!(unknown.error.unwrap().contains("Ungültige Anfrage"));
+
+    let priority = tunnel::respond(&poller, r#"{"id":"r4","type":"set_priority","job_id":"job-x","priority":5}"#).await;
+    assert!(!priority.ok);
+    assert!(priority.error.unwrap().contains("job-x"));
+}
+
+#[tokio::test]
+async fn tunnel_connects_with_api_key_and_serves_requests() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        // Handshake-Header vor der Übernahme durch tungstenite mitlesen
+        let mut handshake = vec![0u8; 4096];
+        let read = stream.peek(&mut handshake).await.unwrap();
+        let handshake = String::from_utf8_lossy(&handshake[..read]).to_string();
+        let authorization = handshake
+            .lines()
+            .find_map(|line| line.strip_prefix("authorization: "))
+            .map(str::to_string);
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        socket.send(Message::Text(r#"{"id":"a","type":"ping"}"#.to_string())).await.unwrap();
+        let response = loop {
+            match socket.next().await.unwrap().unwrap() {
+                Message::Text(text) => break serde_json::from_str::<TunnelResponse>(&text).unwrap(),
+                _ => continue,
+            }
+        };
+        (authorization, response)
+    });
+
+    let settings = BridgeSettings {
+        tunnel: TunnelConfig {
+            enabled: true,
+            url: format!("ws://127.0.0.1:{}/api/scanner/bridge/tunnel", port),
+            ..TunnelConfig::default()
+        },
+        ..BridgeSettings::default()
+    };
+    let settings = Arc::new(RwLock::new(settings));
+    let poller = poller("http://docflow.invalid", settings.read().await.clone());
+    let service = TunnelService::start(poller, "http://docflow.invalid".to_string(), API_KEY.to_string(), settings)
+        .await
+        .expect("Tunnel aktiviert");
+
+    let (authorization, response) = tokio::time::timeout(Duration::from_secs(10), server).await.unwrap().unwrap();
+    assert_eq!(authorization.as_deref(), Some("Bearer test-api-key"));
+    assert_eq!(response.id, "a");
+    assert!(response.ok);
+    assert!(tunnel::status().is_some_and(|s| s.connected));
+    assert!(tunnel::is_connected());
+
+    service.stop().await;
+    assert!(tunnel::status().is_none());
+}
@@ -0,0 +1,171 @@
+// Integrationstests Reverse-Tunnel - zugestellte Jobs gehen bei belegtem Scanner bzw. Fehlern nicht verloren
+// Eigene Test-Binary, da der Tunnel-Status global ist (Tests laufen nacheinander)
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use docflow_bridge_core::job_queue::JobState;
+use docflow_bridge_core::scan_poller::{PendingScanJob, ScanPoller};
+use docflow_bridge_core::settings::BridgeSettings;
+use docflow_bridge_core::tunnel::{self, TunnelConfig, TunnelService};
+use docflow_bridge_core::virtual_scanner::{self, VirtualScannerConfig};
+use futures::StreamExt;
+use tokio::sync::{Mutex, RwLock};
+use wiremock::matchers::path;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "test-api-key";
+const NOT_EXPIRING: &str = "2099-01-01T00:00:00Z";
+
+const PROCESSING_STATUS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<scan:ScannerStatus xmlns:scan="http://schemas.hp.com/imaging/escl/2011/05/03" xmlns:pwg="http://www.pwg.org/schemas/2010/12/sm">
+    <pwg:Version>2.0</pwg:Version>
+    <pwg:State>Processing</pwg:State>
+</scan:ScannerStatus>"#;
+
+/// Der Tunnel-Status ist global - nur ein Test gleichzeitig
+static TUNNEL: Mutex<()> = Mutex::const_new(());
+
+fn job(scanner_id: &str, expires_at: &str) -> PendingScanJob {
+    serde_json::from_value(serde_json::json!({
+        "job_id": "job-1",
+        "scanner_id": scanner_id,
+        "resolution": 300,
+        "color_mode": "color",
+        "source": "flatbed",
+        "duplex": false,
+        "format": "pdf",
+        "created_at": "2026-01-01T00:00:00Z",
+        "expires_at": expires_at,
+    }))
+    .expect("Job")
+}
+
+/// Belegter eSCL-Scanner "mfp", DocFlow-Mock und verbundener Tunnel
+async fn connected_poller() -> (Arc<ScanPoller>, Arc<TunnelService>, MockServer, MockServer) {
+    // Tunnel-Gegenstelle: nimmt die Verbindung an und hält sie offen
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tunnel_port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        while socket.next().await.is_some() {}
+    });
+
+    // Belegter eSCL-Scanner und DocFlow (nimmt Status-Meldungen an)
+    let device = MockServer::start().await;
+    Mock::given(path("/eSCL/ScannerStatus"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(PROCESSING_STATUS))
+        .mount(&device)
+        .await;
+    let docflow = MockServer::start().await;
+    Mock::given(path("/api/scanner/bridge/scan-status/job-1"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&docflow)
+        .await;
+
+    let mut scanner = virtual_scanner::scanner(&VirtualScannerConfig::default());
+    scanner.id = "mfp".to_string();
+    scanner.discovery_method = "mdns".to_string();
+    scanner.protocols = vec!["escl".to_string()];
+    scanner.port = device.address().port();
+    scanner.rs_path = "eSCL".to_string();
+
+    let settings = BridgeSettings {
+        tunnel: TunnelConfig {
+            enabled: true,
+            url: format!("ws://127.0.0.1:{}/api/scanner/bridge/tunnel", tunnel_port),
+            ..TunnelConfig::default()
+        },
+        ..BridgeSettings::default()
+    };
+    let settings = Arc::new(RwLock::new(settings));
+    let poller = Arc::new(ScanPoller::new(
+        API_KEY.to_string(),
+        docflow.uri(),
+        Arc::new(RwLock::new(vec![scanner])),
+        settings.clone(),
+    ));
+    let service = TunnelService::start(poller.clone(), docflow.uri(), API_KEY.to_string(), settings)
+        .await
+        .expect("Tunnel aktiviert");
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !tunnel::is_connected() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("Tunnel verbunden");
+
+    (poller, service, device, docflow)
+}
+
+/// Wartet, bis job-1 im angegebenen Zustand in der Warteschlange steht
+async fn wait_for_state(poller: &ScanPoller, state: JobState) -> bool {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let jobs = poller.active_jobs().await;
+            if jobs.iter().any(|job| job.id == "job-1" && job.state == state) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+#[tokio::test]
+async fn busy_scanner_keeps_tunnel_job_queued() {
+    let _tunnel = TUNNEL.lock().await;
+    let (poller, service, _device, _docflow) = connected_poller().await;
+
+    poller.enqueue_job(job("mfp", NOT_EXPIRING)).await.expect("eingereiht");
+
+    // Nach dem ersten "belegt" wartet der Job wieder in der Warteschlange (statt zu verschwinden)
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !poller.scanner_activity().await.iter().any(|a| a.scanner_id == "mfp" && a.state == "busy") {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("Scanner belegt");
+    let requeued = wait_for_state(&poller, JobState::Queued).await;
+
+    poller.abort_workers().await;
+    service.stop().await;
+    assert!(requeued, "Job nach belegtem Scanner verloren");
+}
+
+#[tokio::test]
+async fn failed_tunnel_job_waits_for_the_retry_delay() {
+    let _tunnel = TUNNEL.lock().await;
+    let (poller, service, _device, _docflow) = connected_poller().await;
+
+    // Unbekannter Scanner: Job schlägt fehl und wird erst nach der Wartezeit erneut versucht
+    poller.enqueue_job(job("ghost", NOT_EXPIRING)).await.expect("eingereiht");
+    let retrying = wait_for_state(&poller, JobState::Retrying).await;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let jobs = poller.active_jobs().await;
+
+    poller.abort_workers().await;
+    service.stop().await;
+    assert!(retrying, "Fehlgeschlagener Job nicht zur Wiederholung vorgemerkt");
+    assert!(
+        jobs.iter().all(|job| job.state == JobState::Retrying),
+        "Job vor Ablauf der Wartezeit erneut eingereiht"
+    );
+}
+
+#[tokio::test]
+async fn expired_tunnel_job_is_rejected() {
+    let _tunnel = TUNNEL.lock().await;
+    let (poller, service, _device, _docflow) = connected_poller().await;
+
+    let error = poller.enqueue_job(job("mfp", "2020-01-01T00:00:00Z")).await.err();
+    let jobs = poller.active_jobs().await;
+
+    service.stop().await;
+    assert!(error.is_some_and(|e| e.to_string().contains("abgelaufen")));
+    assert!(jobs.is_empty());
+}
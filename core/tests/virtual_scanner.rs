@@ -0,0 +1,72 @@
+// Integrationstests virtueller Scanner - generierte Seiten müssen die Scan-Pipeline wie echte Scans durchlaufen
+
+use docflow_bridge_core::scanner::ScanJob;
+use docflow_bridge_core::virtual_scanner::{self, VirtualScannerConfig};
+use docflow_bridge_core::{imaging, pdf};
+
+fn job(source: &str, format: &str, duplex: bool) -> ScanJob {
+    ScanJob {
+        scanner_id: virtual_scanner::VIRTUAL_SCANNER_ID.to_string(),
+        resolution: 100,
+        color_mode: "color".to_string(),
+        format: format.to_string(),
+        source: source.to_string(),
+        duplex,
+        page_retries: 0,
+        allow_partial: false,
+        height: None,
+        regions: Vec::new(),
+        timeout_secs: 0,
+        spool_after_kb: 0,
+    }
+}
+
+fn decode(page: &docflow_bridge_core::scanner::ScannedPage) -> Vec<u8> {
+    page.data.read().expect("Seitendaten")
+}
+
+#[test]
+fn adf_pdf_scan_is_one_valid_multi_page_pdf() {
+    let config = VirtualScannerConfig {
+        enabled: true,
+        adf_pages: 2,
+        ..Default::default()
+    };
+
+    let result = virtual_scanner::scan(&config, &job("adf", "application/pdf", true)).expect("Scan");
+    assert_eq!(result.total_pages, 1);
+
+    let data = decode(&result.pages[0]);
+    pdf::validate(&data).expect("gültiges PDF");
+    // Duplex: zwei Blätter → vier Seiten
+    assert_eq!(imaging::extract_pdf_jpegs(&data).len(), 4);
+}
+
+#[test]
+fn flatbed_jpeg_scan_returns_one_a4_page() {
+    let result = virtual_scanner::scan(&VirtualScannerConfig::default(), &job("flatbed", "image/jpeg", false))
+        .expect("Scan");
+    assert_eq!(result.total_pages, 1);
+
+    let page = image::load_from_memory(&decode(&result.pages[0])).expect("JPEG");
+    // A4 bei 100 dpi
+    assert_eq!((page.width(), page.height()), (826, 1169));
+}
+
+#[test]
+fn sync_adds_and_removes_the_virtual_scanner() {
+    let mut scanners = Vec::new();
+    let mut config = VirtualScannerConfig {
+        enabled: true,
+        ..Default::default()
+    };
+
+    virtual_scanner::sync(&mut scanners, &config);
+    virtual_scanner::sync(&mut scanners, &config);
+    assert_eq!(scanners.len(), 1);
+    assert!(virtual_scanner::is_virtual(&scanners[0]));
+
+    config.enabled = false;
+    virtual_scanner::sync(&mut scanners, &config);
+    assert!(scanners.is_empty());
+}
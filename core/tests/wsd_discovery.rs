@@ -0,0 +1,101 @@
+// Integrationstests WSD-Discovery - ProbeMatch/ResolveMatch lesen und Gerätemetadaten per WS-Transfer Get
+
+use docflow_bridge_core::wsd_discovery::{self, ProbeMatch};
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ENDPOINT: &str = "urn:uuid:e3248000-80ce-11db-8000-30055c773bcf";
+
+#[test]
+fn probe_and_resolve_matches_are_parsed() {
+    let probe = wsd_discovery::probe_message("1234");
+    assert!(probe.contains("discovery/Probe"));
+    assert!(probe.contains("wscn:ScanDeviceType"));
+
+    let probe_matches = format!(
+        r#"<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope">
+    <soap:Body>
+        <wsd:ProbeMatches>
+            <wsd:ProbeMatch>
+                <wsa:EndpointReference><wsa:Address>{}</wsa:Address></wsa:EndpointReference>
+                <wsd:Types>wsdp:Device wscn:ScanDeviceType wprt:PrintDeviceType</wsd:Types>
+                <wsd:XAddrs>http://[fe80::1]:53048/ http://192.168.1.40:53048/</wsd:XAddrs>
+                <wsd:MetadataVersion>3</wsd:MetadataVersion>
+            </wsd:ProbeMatch>
+        </wsd:ProbeMatches>
+    </soap:Body>
+</soap:Envelope>"#,
+        ENDPOINT
+    );
+    assert_eq!(
+        wsd_discovery::parse_matches(&probe_matches),
+        vec![ProbeMatch {
+            endpoint: ENDPOINT.to_string(),
+            types: "wsdp:Device wscn:ScanDeviceType wprt:PrintDeviceType".to_string(),
+            xaddrs: vec!["http://[fe80::1]:53048/".to_string(), "http://192.168.1.40:53048/".to_string()],
+        }]
+    );
+
+    // ProbeMatch ohne Adressen: Resolve liefert sie nach
+    let resolve_matches = format!(
+        r#"<wsd:ResolveMatches><wsd:ResolveMatch>
+            <wsa:EndpointReference><wsa:Address>{}</wsa:Address></wsa:EndpointReference>
+            <wsd:XAddrs>http://192.168.1.40:53048/</wsd:XAddrs>
+        </wsd:ResolveMatch></wsd:ResolveMatches>"#,
+        ENDPOINT
+    );
+    assert_eq!(wsd_discovery::parse_matches(&resolve_matches)[0].xaddrs, vec!["http://192.168.1.40:53048/"]);
+    assert!(wsd_discovery::parse_matches("<soap:Envelope>Hello</soap:Envelope>").is_empty());
+}
+
+#[tokio::test]
+async fn device_metadata_yields_scanner_with_wsd_endpoint() {
+    let server = MockServer::start().await;
+    let scan_service = format!("{}/WDP/SCAN", server.uri());
+    Mock::given(method("POST"))
+        .and(path("/device"))
+        .and(body_string_contains("transfer/Get"))
+        .and(body_string_contains(ENDPOINT))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<soap:Envelope><soap:Body><wsx:Metadata>
+    <wsdp:ThisModel>
+        <wsdp:Manufacturer>Brother Industries, Ltd.</wsdp:Manufacturer>
+        <wsdp:ModelName>MFC-8510DN</wsdp:ModelName>
+        <wsdp:PresentationUrl>http://192.168.1.40/</wsdp:PresentationUrl>
+    </wsdp:ThisModel>
+    <wsdp:ThisDevice><wsdp:FriendlyName>Brother MFC-8510DN Büro</wsdp:FriendlyName></wsdp:ThisDevice>
+    <wsdp:Relationship>
+        <wsdp:Hosted>
+            <wsa:EndpointReference><wsa:Address>http://192.168.1.40:80/WDP/PRINT</wsa:Address></wsa:EndpointReference>
+            <wsdp:Types>wprt:PrinterServiceType</wsdp:Types>
+        </wsdp:Hosted>
+        <wsdp:Hosted>
+            <wsa:EndpointReference><wsa:Address>{}</wsa:Address></wsa:EndpointReference>
+            <wsdp:Types>wscn:ScannerServiceType</wsdp:Types>
+        </wsdp:Hosted>
+    </wsdp:Relationship>
+</wsx:Metadata></soap:Body></soap:Envelope>"#,
+            scan_service
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let found = ProbeMatch {
+        endpoint: ENDPOINT.to_string(),
+        types: "wscn:ScanDeviceType".to_string(),
+        xaddrs: vec![format!("{}/device", server.uri())],
+    };
+    let scanner = wsd_discovery::device(&found).await.expect("Scanner");
+
+    assert_eq!(scanner.id, "e3248000-80ce-11db-8000-30055c773bcf");
+    assert_eq!(scanner.manufacturer, "Brother");
+    assert_eq!(scanner.model, "MFC-8510DN");
+    assert_eq!(scanner.name, "Brother MFC-8510DN Büro");
+    assert_eq!(scanner.ip, "127.0.0.1");
+    assert_eq!(scanner.port, server.address().port());
+    assert_eq!(scanner.discovery_method, "wsd");
+    assert_eq!(scanner.protocols, vec!["wsd"]);
+    assert!(scanner.rs_path.is_empty());
+    assert_eq!(scanner.wsd_url.as_deref(), Some(scan_service.as_str()));
+}
@@ -3,12 +3,15 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod discovery;
-mod folder_watcher;
-mod pairing;
-mod scanner;
-mod scan_poller;
+use docflow_bridge_core::{
+    announce, audit, cert_expiry, circuit_breaker, clipboard, config_bundle, counters, dedup, device_info, digest,
+    discovery, escl_recording, events, folder_watcher, grpc, http_client, job_queue, kiosk, metrics,
+    network_diagnostics, onboarding, pairing, profiles, push_scan, quirks, rate_limit, request_signing, resources,
+    scan_poller, scanner, scanner_stats, scanner_sync, secrets, self_test, server_discovery, settings, spool,
+    supervisor, system_proxy, telemetry, test_scan, timestamps, tray, tunnel, upload_progress, virtual_scanner, wol,
+};
 
+use std::path::Path;
 use std::sync::Arc;
 use tauri::{
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
@@ -18,10 +21,13 @@ use tauri_plugin_autostart::MacosLauncher;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use serde_json;
-use reqwest;
 
 use folder_watcher::{FolderSyncConfig, FolderSyncStatus, FolderWatcher, PostUploadAction};
+use push_scan::PushScanService;
 use scan_poller::ScanPoller;
+use grpc::GrpcService;
+use tunnel::TunnelService;
+use settings::{BridgeSettings, CloseAction};
 
 /// Bridge-Status für das Frontend
 #[derive(Clone, Serialize, Deserialize)]
@@ -32,9 +38,31 @@ pub struct BridgeStatus {
     last_discovery: Option<String>,
     version: String,
     poller_active: bool,
+    /// Scan-Jobs dieser Sitzung (Gesamtzahl in totals)
     jobs_processed: u32,
     folder_sync_active: bool,
     folder_sync_path: Option<String>,
+    /// Wenig Speicherplatz/Arbeitsspeicher - Scans werden zurückgestellt
+    resource_warning: Option<String>,
+    /// Poller oder Folder-Sync lassen sich nicht stabil neu starten
+    task_error: Option<String>,
+    /// DocFlow-Server, zu denen der Verkehr wegen eines Ausfalls pausiert ist
+    docflow_paused: Vec<circuit_breaker::PausedServer>,
+    /// TLS-Zertifikat des DocFlow-Servers läuft bald ab bzw. ist abgelaufen
+    certificate_warning: Option<String>,
+    /// Audit-Log-Kette beim Prüfen gebrochen (Einträge verändert oder entfernt)
+    audit_warning: Option<String>,
+    /// Reverse-Tunnel zu DocFlow (None = nicht aktiviert)
+    tunnel: Option<tunnel::TunnelStatus>,
+    /// gRPC-Transport zu DocFlow (None = nicht aktiviert)
+    grpc: Option<grpc::GrpcStatus>,
+    /// Gesamtzahlen über Neustarts hinweg
+    totals: counters::Counters,
+    /// last_discovery in lokaler Zeit und als "vor X"
+    last_discovery_display: Option<timestamps::DisplayTime>,
+    /// Laufzeit der Bridge seit dem Start (monoton gemessen)
+    uptime_secs: u64,
+    uptime: String,
 }
 
 /// Globaler App-State
@@ -43,7 +71,12 @@ pub struct AppState {
     api_key: RwLock<Option<String>>,
     scanners: Arc<RwLock<Vec<discovery::DiscoveredScanner>>>,
     poller: RwLock<Option<Arc<ScanPoller>>>,
+    push_scan: RwLock<Option<Arc<PushScanService>>>,
+    tunnel: RwLock<Option<Arc<TunnelService>>>,
+    grpc: RwLock<Option<Arc<GrpcService>>>,
     folder_watcher: RwLock<Option<Arc<FolderWatcher>>>,
+    settings: Arc<RwLock<BridgeSettings>>,
+    kiosk: kiosk::KioskLock,
 }
 
 impl Default for AppState {
@@ -59,11 +92,27 @@ impl Default for AppState {
                 jobs_processed: 0,
                 folder_sync_active: false,
                 folder_sync_path: None,
+                resource_warning: None,
+                task_error: None,
+                docflow_paused: Vec::new(),
+                certificate_warning: None,
+                audit_warning: None,
+                tunnel: None,
+                grpc: None,
+                totals: counters::Counters::default(),
+                last_discovery_display: None,
+                uptime_secs: 0,
+                uptime: String::new(),
             }),
             api_key: RwLock::new(None),
             scanners: Arc::new(RwLock::new(Vec::new())),
             poller: RwLock::new(None),
+            push_scan: RwLock::new(None),
+            tunnel: RwLock::new(None),
+            grpc: RwLock::new(None),
             folder_watcher: RwLock::new(None),
+            settings: Arc::new(RwLock::new(BridgeSettings::load())),
+            kiosk: kiosk::KioskLock::new(),
         }
     }
 }
@@ -71,14 +120,37 @@ impl Default for AppState {
 /// Tauri-Befehl: Status abrufen
 #[tauri::command]
 async fn get_status(state: tauri::State<'_, Arc<AppState>>) -> Result<BridgeStatus, String> {
-    let status = state.bridge_status.read().await;
-    Ok(status.clone())
+    let mut status = state.bridge_status.read().await.clone();
+    status.resource_warning = resources::current_warning();
+    status.task_error = supervisor::failing();
+    status.docflow_paused = circuit_breaker::paused();
+    status.certificate_warning = cert_expiry::current_warning();
+    status.audit_warning = audit::current_warning();
+    status.tunnel = tunnel::status();
+    status.grpc = grpc::status();
+    status.totals = counters::snapshot();
+    status.last_discovery_display = status.last_discovery.as_deref().and_then(timestamps::display);
+    let uptime = timestamps::uptime();
+    status.uptime_secs = uptime.as_secs();
+    status.uptime = timestamps::duration(uptime);
+    Ok(status)
 }
 
 /// Tauri-Befehl: Scanner suchen und an DocFlow senden
 #[tauri::command]
 async fn discover_scanners(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<discovery::DiscoveredScanner>, String> {
-    let scanners = discovery::discover_all().await.map_err(|e| e.to_string())?;
+    let (discovery_config, mdns_browse_secs) = {
+        let settings = state.settings.read().await;
+        (settings.discovery.clone(), settings.intervals.mdns_browse_secs)
+    };
+    let scanners = discovery::discover_all(&discovery_config, std::time::Duration::from_secs(mdns_browse_secs))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    metrics::inc(&metrics::METRICS.discovery_runs);
+    metrics::METRICS
+        .discovered_scanners
+        .store(scanners.len() as u64, std::sync::atomic::Ordering::Relaxed);
 
     // Scanner im State speichern (für Poller)
     {
@@ -94,65 +166,72 @@ async fn discover_scanners(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec
     }
 
     // Scanner an DocFlow senden (falls verbunden)
-    let api_key = state.api_key.read().await.clone();
-    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    scanner_sync::mark_changed();
 
-    if let (Some(key), Some(url)) = (api_key, docflow_url) {
-        if let Err(e) = send_scanners_to_docflow(&url, &key, &scanners).await {
-            eprintln!("Warnung: Konnte Scanner nicht an DocFlow senden: {}", e);
-        }
+    events::record(events::EventKind::Discovery, format!("{} Scanner gefunden", scanners.len()));
+    if !scanners.is_empty() {
+        onboarding::advance(onboarding::OnboardingStep::ScannerFound);
     }
 
     Ok(scanners)
 }
 
-/// Sendet die gefundenen Scanner an DocFlow
-async fn send_scanners_to_docflow(
-    docflow_url: &str,
-    api_key: &str,
-    scanners: &[discovery::DiscoveredScanner]
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/api/scanner/bridge/scanners", docflow_url.trim_end_matches('/'));
-
-    // Scanner-Daten für API aufbereiten
-    let scanner_data: Vec<serde_json::Value> = scanners.iter().map(|s| {
-        serde_json::json!({
-            "id": s.id,
-            "name": s.name,
-            "manufacturer": s.manufacturer,
-            "model": s.model,
-            "ip": s.ip,
-            "port": s.port,
-            "protocols": s.protocols,
-            "discovery_method": s.discovery_method,
-            "capabilities": {
-                "duplex": s.capabilities.duplex,
-                "adf": s.capabilities.adf,
-                "flatbed": s.capabilities.flatbed,
-                "max_resolution": s.capabilities.max_resolution,
-                "color_modes": s.capabilities.color_modes,
-                "formats": s.capabilities.formats
-            }
-        })
-    }).collect();
+/// Tauri-Befehl: Zuletzt gefundene Scanner sofort liefern (ohne neue Suche), inkl. Erreichbarkeit
+#[tauri::command]
+async fn get_scanners(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<discovery::KnownScanner>, String> {
+    Ok(discovery::with_reachability(&state.scanners.read().await))
+}
 
-    let response = client
-        .post(&url)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&serde_json::json!({ "scanners": scanner_data }))
-        .send()
-        .await?;
+/// Tauri-Befehl: Scanner aus der Liste entfernen (hide: auch bei künftigen Suchen ausblenden)
+/// Die Entfernung wird mit der nächsten Scanner-Liste an DocFlow gemeldet
+#[tauri::command]
+async fn remove_scanner(state: tauri::State<'_, Arc<AppState>>, scanner_id: String, hide: bool) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    let (removed, remaining) = {
+        let mut scanners = state.scanners.write().await;
+        let removed = scanners.iter().find(|s| s.id == scanner_id).cloned();
+        scanners.retain(|s| s.id != scanner_id);
+        (removed, scanners.len())
+    };
+    if removed.is_none() && !hide {
+        return Err(format!("Scanner '{}' nicht gefunden", scanner_id));
+    }
 
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("DocFlow-Fehler: {}", error_text).into());
+    {
+        let mut settings = state.settings.write().await;
+        if hide {
+            settings.discovery.hidden.insert(scanner_id.clone());
+        }
+        // Importierten Host nicht bei der nächsten Suche erneut prüfen
+        if let Some(scanner) = &removed {
+            settings.discovery.static_hosts.retain(|h| h.host != scanner.ip);
+        }
+        settings.discovery.pinned_endpoints.remove(&scanner_id);
+        settings.save().map_err(|e| e.to_string())?;
     }
 
-    println!("✓ {} Scanner an DocFlow gesendet", scanners.len());
+    state.bridge_status.write().await.scanner_count = remaining;
+    scanner_sync::mark_removed(&scanner_id);
+    let name = removed.map(|s| s.name).unwrap_or_else(|| scanner_id.clone());
+    println!("🗑 Scanner {} {}", name, if hide { "ausgeblendet" } else { "entfernt" });
+    events::record(
+        events::EventKind::Discovery,
+        format!("Scanner {} {}", name, if hide { "ausgeblendet" } else { "entfernt" }),
+    );
     Ok(())
 }
 
+/// Tauri-Befehl: Ausgeblendeten Scanner wieder zulassen (erscheint bei der nächsten Suche)
+#[tauri::command]
+async fn unhide_scanner(state: tauri::State<'_, Arc<AppState>>, scanner_id: String) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    let mut settings = state.settings.write().await;
+    if !settings.discovery.hidden.remove(&scanner_id) {
+        return Err(format!("Scanner '{}' ist nicht ausgeblendet", scanner_id));
+    }
+    settings.save().map_err(|e| e.to_string())
+}
+
 /// Tauri-Befehl: Mit DocFlow verbinden (Pairing)
 /// docflow_url: Optional - nur für manuelle Codes benötigt (z.B. "http://localhost:4000")
 #[tauri::command]
@@ -161,6 +240,7 @@ async fn pair_with_docflow(
     pairing_code: String,
     docflow_url: Option<String>
 ) -> Result<bool, String> {
+    state.kiosk.ensure_unlocked().await?;
     // Pairing-Code parsen und mit DocFlow verbinden
     let result = pairing::pair(&pairing_code, docflow_url.as_deref()).await.map_err(|e| e.to_string())?;
 
@@ -168,6 +248,18 @@ async fn pair_with_docflow(
     let api_key_value = result.api_key.clone();
     let docflow_url_value = result.docflow_url.clone();
 
+    // Bridge-ID in den User-Agent übernehmen (Abgleich mit DocFlow-Server-Logs)
+    http_client::set_bridge_id(Some(&result.bridge_id));
+    println!("🔗 Gekoppelt als Bridge {}", result.bridge_id);
+    audit::record(
+        audit::AuditAction::Paired,
+        serde_json::json!({
+            "bridge_id": result.bridge_id,
+            "docflow_url": result.docflow_url,
+            "tenant_name": result.tenant_name,
+        }),
+    );
+
     // Status aktualisieren
     {
         let mut status = state.bridge_status.write().await;
@@ -182,10 +274,37 @@ async fn pair_with_docflow(
     }
 
     // Scan-Poller starten
+    start_poller(state.inner(), api_key_value, docflow_url_value).await;
+
+    println!("✓ Scan-Poller gestartet");
+    onboarding::advance(onboarding::OnboardingStep::Paired);
+    announce::set_paired(true);
+
+    Ok(true)
+}
+
+/// Tauri-Befehl: DocFlow-Server im LAN suchen (Vorschläge für den Pairing-Dialog)
+#[tauri::command]
+async fn discover_docflow_servers(
+    state: tauri::State<'_, Arc<AppState>>,
+) -> Result<Vec<server_discovery::DetectedServer>, String> {
+    let browse = std::time::Duration::from_secs(state.settings.read().await.intervals.mdns_browse_secs);
+    Ok(server_discovery::discover(browse).await)
+}
+
+/// Startet Scan-Poller, Push-Scan-Dienst, Reverse-Tunnel und gRPC-Transport
+async fn start_poller(state: &Arc<AppState>, api_key: String, docflow_url: String) {
+    // Laufende Dienste zuerst beenden (Push-Scan-Port wird sonst nicht frei)
+    stop_services(state).await;
+
+    // Neue Verbindung: aktuelle Scanner-Liste melden
+    scanner_sync::mark_changed();
+
     let poller = Arc::new(ScanPoller::new(
-        api_key_value,
-        docflow_url_value,
+        api_key.clone(),
+        docflow_url.clone(),
         state.scanners.clone(),
+        state.settings.clone(),
     ));
 
     {
@@ -193,11 +312,12 @@ async fn pair_with_docflow(
         *poller_lock = Some(poller.clone());
     }
 
-    // Poller in separatem Task starten
+    // Poller in separatem Task starten (vom Supervisor überwacht)
     let poller_clone = poller.clone();
-    tokio::spawn(async move {
+    let handle = tokio::spawn(async move {
         poller_clone.start_polling().await;
     });
+    supervisor::watch(supervisor::POLLER, handle);
 
     // Poller-Status im Bridge-Status aktualisieren
     {
@@ -205,26 +325,45 @@ async fn pair_with_docflow(
         status.poller_active = true;
     }
 
-    println!("✓ Scan-Poller gestartet");
+    // Reverse-Tunnel für Cloud-DocFlow, falls aktiviert (ersetzt dann das Polling)
+    let tunnel = TunnelService::start(poller.clone(), docflow_url.clone(), api_key.clone(), state.settings.clone()).await;
+    *state.tunnel.write().await = tunnel;
 
-    Ok(true)
+    // gRPC statt REST+Multipart, falls aktiviert und im Build enthalten (sonst bzw. bei Ausfall HTTP)
+    let grpc = GrpcService::start(poller.clone(), docflow_url, api_key, state.settings.clone()).await;
+    *state.grpc.write().await = grpc;
+
+    // Push-Scans (Scan-Taste am Gerät), falls aktiviert
+    let push_scan = PushScanService::start(poller, state.scanners.clone(), state.settings.clone()).await;
+    *state.push_scan.write().await = push_scan;
+}
+
+/// Stoppt Poller (samt Scanner-Workern), Push-Scan-Dienst, Tunnel und gRPC und wartet auf deren Ende
+async fn stop_services(state: &AppState) {
+    if let Some(poller) = state.poller.write().await.take() {
+        poller.stop().await;
+    }
+    if let Some(push_scan) = state.push_scan.write().await.take() {
+        push_scan.stop().await;
+    }
+    if let Some(tunnel) = state.tunnel.write().await.take() {
+        tunnel.stop().await;
+    }
+    if let Some(grpc) = state.grpc.write().await.take() {
+        grpc.stop().await;
+    }
 }
 
 /// Tauri-Befehl: Verbindung trennen
 #[tauri::command]
 async fn disconnect(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String> {
-    // Poller stoppen
-    {
-        let poller_lock = state.poller.read().await;
-        if let Some(poller) = poller_lock.as_ref() {
-            poller.stop().await;
-        }
-    }
-
-    {
-        let mut poller_lock = state.poller.write().await;
-        *poller_lock = None;
-    }
+    state.kiosk.ensure_unlocked().await?;
+    let previous_url = state.bridge_status.read().await.docflow_url.clone();
+    audit::record(audit::AuditAction::Unpaired, serde_json::json!({ "docflow_url": previous_url }));
+    supervisor::unwatch(supervisor::POLLER);
+    supervisor::unwatch(supervisor::FOLDER_WATCHER);
+    // Poller, Push-Scan-Dienst, Tunnel und gRPC stoppen
+    stop_services(&state).await;
 
     // Folder-Watcher stoppen
     {
@@ -239,6 +378,9 @@ async fn disconnect(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String
         *watcher_lock = None;
     }
 
+    http_client::set_bridge_id(None);
+    announce::set_paired(false);
+
     let mut status = state.bridge_status.write().await;
     status.connected = false;
     status.docflow_url = None;
@@ -248,36 +390,36 @@ async fn disconnect(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String
 
     drop(status);
 
+    // Restliche Audit-Einträge (inkl. Trennung) noch mit dem alten API-Key übertragen
     let mut api_key = state.api_key.write().await;
+    if let (Some(key), Some(url)) = (api_key.as_deref(), previous_url.as_deref()) {
+        if let Err(e) = audit::upload_pending(url, key).await {
+            eprintln!("⚠ Audit-Log vor dem Trennen nicht übertragen: {}", e);
+        }
+    }
     *api_key = None;
 
     // API-Key aus Keyring löschen
-    if let Ok(entry) = keyring::Entry::new("docflow-scanner-bridge", "api_key") {
-        if let Err(e) = entry.delete_password() {
-            eprintln!("Warnung: Konnte API-Key nicht löschen: {}", e);
-        }
+    if let Err(e) = secrets::delete("api_key") {
+        eprintln!("Warnung: Konnte API-Key nicht löschen: {}", e);
     }
+    let _ = secrets::delete("bridge_id");
+    let _ = secrets::delete(request_signing::SECRET_ENTRY);
+    request_signing::set_secret(None);
 
     println!("✓ Verbindung getrennt, Poller & Folder-Sync gestoppt");
 
     Ok(())
 }
 
-/// Tauri-Befehl: Ordner-Sync konfigurieren und starten
-#[tauri::command]
-async fn configure_folder_sync(
-    state: tauri::State<'_, Arc<AppState>>,
-    watch_path: String,
-    post_action: String,
-) -> Result<bool, String> {
-    // Prüfe ob verbunden
-    let api_key = state.api_key.read().await.clone();
-    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
-
-    let (key, url) = match (api_key, docflow_url) {
-        (Some(k), Some(u)) => (k, u),
-        _ => return Err("Nicht mit DocFlow verbunden".to_string()),
-    };
+/// Speichert die Folder-Sync-Config und startet den Watcher (ersetzt einen laufenden)
+async fn start_folder_sync(
+    state: &AppState,
+    config: FolderSyncConfig,
+    key: String,
+    url: String,
+) -> Result<(), String> {
+    let watch_path = config.watch_path.clone();
 
     // Prüfe ob Ordner existiert
     if !std::path::Path::new(&watch_path).exists() {
@@ -292,37 +434,22 @@ async fn configure_folder_sync(
         }
     }
 
-    let action = match post_action.as_str() {
-        "delete" => PostUploadAction::Delete,
-        "keep" => PostUploadAction::Keep,
-        _ => PostUploadAction::MoveToSubfolder,
-    };
-
-    let config = FolderSyncConfig {
-        enabled: true,
-        watch_path: watch_path.clone(),
-        post_upload_action: action,
-    };
-
     // Config im Keyring speichern
-    if let Ok(entry) = keyring::Entry::new("docflow-scanner-bridge", "folder_sync_config") {
-        if let Ok(json) = serde_json::to_string(&config) {
-            let _ = entry.set_password(&json);
-        }
-    }
+    config.save();
 
-    let watcher = Arc::new(FolderWatcher::new(config, key, url));
+    let watcher = Arc::new(FolderWatcher::new(config, key, url, state.settings.clone()));
 
     {
         let mut watcher_lock = state.folder_watcher.write().await;
         *watcher_lock = Some(watcher.clone());
     }
 
-    // Watcher in separatem Task starten
+    // Watcher in separatem Task starten (vom Supervisor überwacht)
     let watcher_clone = watcher.clone();
-    tokio::spawn(async move {
+    let handle = tokio::spawn(async move {
         watcher_clone.start_watching().await;
     });
+    supervisor::watch(supervisor::FOLDER_WATCHER, handle);
 
     // Bridge-Status aktualisieren
     {
@@ -331,6 +458,42 @@ async fn configure_folder_sync(
         status.folder_sync_path = Some(watch_path);
     }
 
+    Ok(())
+}
+
+/// Tauri-Befehl: Ordner-Sync konfigurieren und starten
+#[tauri::command]
+async fn configure_folder_sync(
+    state: tauri::State<'_, Arc<AppState>>,
+    watch_path: String,
+    post_action: String,
+    auto_orient: Option<bool>,
+) -> Result<bool, String> {
+    state.kiosk.ensure_unlocked().await?;
+    // Prüfe ob verbunden
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+
+    let (key, url) = match (api_key, docflow_url) {
+        (Some(k), Some(u)) => (k, u),
+        _ => return Err("Nicht mit DocFlow verbunden".to_string()),
+    };
+
+    let action = match post_action.as_str() {
+        "delete" => PostUploadAction::Delete,
+        "keep" => PostUploadAction::Keep,
+        _ => PostUploadAction::MoveToSubfolder,
+    };
+
+    let config = FolderSyncConfig {
+        enabled: true,
+        watch_path,
+        post_upload_action: action,
+        auto_orient: auto_orient.unwrap_or(false),
+    };
+
+    start_folder_sync(&state, config, key, url).await?;
+    onboarding::advance(onboarding::OnboardingStep::FolderConfigured);
     println!("✓ Folder-Sync gestartet");
     Ok(true)
 }
@@ -338,6 +501,8 @@ async fn configure_folder_sync(
 /// Tauri-Befehl: Ordner-Sync stoppen
 #[tauri::command]
 async fn stop_folder_sync(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    supervisor::unwatch(supervisor::FOLDER_WATCHER);
     {
         let watcher_lock = state.folder_watcher.read().await;
         if let Some(watcher) = watcher_lock.as_ref() {
@@ -351,15 +516,9 @@ async fn stop_folder_sync(state: tauri::State<'_, Arc<AppState>>) -> Result<(),
     }
 
     // Config im Keyring deaktivieren
-    if let Ok(entry) = keyring::Entry::new("docflow-scanner-bridge", "folder_sync_config") {
-        if let Ok(json_str) = entry.get_password() {
-            if let Ok(mut config) = serde_json::from_str::<FolderSyncConfig>(&json_str) {
-                config.enabled = false;
-                if let Ok(json) = serde_json::to_string(&config) {
-                    let _ = entry.set_password(&json);
-                }
-            }
-        }
+    if let Some(mut config) = FolderSyncConfig::load() {
+        config.enabled = false;
+        config.save();
     }
 
     {
@@ -372,6 +531,53 @@ async fn stop_folder_sync(state: tauri::State<'_, Arc<AppState>>) -> Result<(),
     Ok(())
 }
 
+/// Tauri-Befehl: Dateien hochladen (Drag & Drop ins Fenster), Ergebnis pro Datei
+#[tauri::command]
+async fn upload_files(
+    state: tauri::State<'_, Arc<AppState>>,
+    paths: Vec<String>,
+) -> Result<Vec<folder_watcher::FileUploadResult>, String> {
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    let (key, url) = match (api_key, docflow_url) {
+        (Some(k), Some(u)) => (k, u),
+        _ => return Err("Nicht mit DocFlow verbunden".to_string()),
+    };
+
+    let barcode_config = state.settings.read().await.barcode.clone();
+    let paths: Vec<std::path::PathBuf> = paths.into_iter().map(Into::into).collect();
+    Ok(folder_watcher::upload_files(&url, &key, &paths, &barcode_config).await)
+}
+
+/// Tauri-Befehl: Bild aus der Zwischenablage hochladen (Standard: als PDF)
+#[tauri::command]
+async fn upload_clipboard(
+    state: tauri::State<'_, Arc<AppState>>,
+    format: Option<clipboard::ClipboardFormat>,
+) -> Result<folder_watcher::FolderUploadResponse, String> {
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    let (key, url) = match (api_key, docflow_url) {
+        (Some(k), Some(u)) => (k, u),
+        _ => return Err("Nicht mit DocFlow verbunden".to_string()),
+    };
+
+    let image = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_image())
+        .map_err(|e| format!("Kein Bild in der Zwischenablage: {}", e))?;
+
+    clipboard::upload(
+        &url,
+        &key,
+        image.width as u32,
+        image.height as u32,
+        image.bytes.into_owned(),
+        format.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
 /// Tauri-Befehl: Folder-Sync-Status abfragen
 #[tauri::command]
 async fn get_folder_sync_status(state: tauri::State<'_, Arc<AppState>>) -> Result<FolderSyncStatus, String> {
@@ -379,16 +585,622 @@ async fn get_folder_sync_status(state: tauri::State<'_, Arc<AppState>>) -> Resul
     if let Some(watcher) = watcher_lock.as_ref() {
         Ok(watcher.get_status().await)
     } else {
-        Ok(FolderSyncStatus {
-            running: false,
-            watch_path: None,
-            files_uploaded: 0,
-            files_pending: 0,
-            errors: 0,
-            last_upload: None,
-            last_error: None,
-        })
+        Ok(FolderSyncStatus::default())
+    }
+}
+
+/// Tauri-Befehl: Poller-Status abrufen (None = nicht verbunden)
+#[tauri::command]
+async fn get_poller_status(state: tauri::State<'_, Arc<AppState>>) -> Result<Option<scan_poller::PollerStatus>, String> {
+    let poller = state.poller.read().await.clone();
+    match poller {
+        Some(poller) => Ok(Some(poller.get_status().await)),
+        None => Ok(None),
+    }
+}
+/// Tauri-Befehl: Endpoint eines Scanners festlegen (None = automatische Auswahl)
+/// Wirkt sofort auf die bekannten Scanner und bleibt für spätere Discoveries gespeichert
+#[tauri::command]
+async fn pin_scanner_endpoint(
+    state: tauri::State<'_, Arc<AppState>>,
+    scanner_id: String,
+    endpoint: Option<discovery::ScannerEndpoint>,
+) -> Result<Vec<discovery::DiscoveredScanner>, String> {
+    state.kiosk.ensure_unlocked().await?;
+    let discovery_config = {
+        let mut settings = state.settings.write().await;
+        match endpoint {
+            Some(endpoint) => {
+                settings.discovery.pinned_endpoints.insert(scanner_id, endpoint);
+            }
+            None => {
+                settings.discovery.pinned_endpoints.remove(&scanner_id);
+            }
+        }
+        settings.save().map_err(|e| e.to_string())?;
+        settings.discovery.clone()
+    };
+
+    let mut scanners = state.scanners.write().await;
+    discovery::apply_scanner_config(&mut scanners, &discovery_config);
+    scanner_sync::mark_changed();
+    Ok(scanners.clone())
+}
+
+/// Tauri-Befehl: Standort/Gruppe eines Scanners setzen (None = Wert aus mDNS bzw. keiner)
+#[tauri::command]
+async fn set_scanner_label(
+    state: tauri::State<'_, Arc<AppState>>,
+    scanner_id: String,
+    location: Option<String>,
+    group: Option<String>,
+) -> Result<Vec<discovery::DiscoveredScanner>, String> {
+    state.kiosk.ensure_unlocked().await?;
+    let location = location.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    let group = group.map(|g| g.trim().to_string()).filter(|g| !g.is_empty());
+
+    let discovery_config = {
+        let mut settings = state.settings.write().await;
+        if location.is_none() && group.is_none() {
+            settings.discovery.labels.remove(&scanner_id);
+        } else {
+            settings
+                .discovery
+                .labels
+                .insert(scanner_id.clone(), discovery::ScannerLabel { location: location.clone(), group: group.clone() });
+        }
+        settings.save().map_err(|e| e.to_string())?;
+        settings.discovery.clone()
+    };
+
+    let scanners = {
+        let mut scanners = state.scanners.write().await;
+        // Entfernte Bezeichnung sofort zurücksetzen (mDNS-Standort kommt mit der nächsten Discovery)
+        if let Some(scanner) = scanners.iter_mut().find(|s| s.id == scanner_id) {
+            scanner.location = location;
+            scanner.group = group;
+        }
+        discovery::apply_scanner_config(&mut scanners, &discovery_config);
+        scanners.clone()
+    };
+
+    // Geänderte Bezeichnung an DocFlow weitergeben
+    scanner_sync::mark_changed();
+
+    Ok(scanners)
+}
+
+/// Tauri-Befehl: Zuordnung Scanner → Verbindungsprofil abrufen
+#[tauri::command]
+async fn get_scanner_assignments(
+    state: tauri::State<'_, Arc<AppState>>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    Ok(state.settings.read().await.discovery.assignments.clone())
+}
+
+/// Tauri-Befehl: Scanner einem Verbindungsprofil (DocFlow-URL) zuordnen (None = für alle sichtbar)
+#[tauri::command]
+async fn set_scanner_assignment(
+    state: tauri::State<'_, Arc<AppState>>,
+    scanner_id: String,
+    profile: Option<String>,
+) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    let profile = profile.map(|p| discovery::connection_profile(&p)).filter(|p| !p.is_empty());
+
+    {
+        let mut settings = state.settings.write().await;
+        match profile {
+            Some(profile) => {
+                settings.discovery.assignments.insert(scanner_id, profile);
+            }
+            None => {
+                settings.discovery.assignments.remove(&scanner_id);
+            }
+        }
+        settings.save().map_err(|e| e.to_string())?;
+    }
+
+    // DocFlow bekommt die gefilterte Liste
+    scanner_sync::mark_changed();
+    Ok(())
+}
+
+/// Tauri-Befehl: IP-Bereiche (CIDR) und Ports für SNMP-Sweep und IP-Range-Scan setzen
+/// Leere Listen = /24 der lokalen IP bzw. Standard-Ports, wirkt ab der nächsten Discovery
+/// SNMP-Sweep an/aus und Community (leer = "public"), ohne Angabe bleibt die bisherige Einstellung
+#[tauri::command]
+async fn set_discovery_config(
+    state: tauri::State<'_, Arc<AppState>>,
+    ranges: Vec<String>,
+    ports: Vec<u16>,
+    snmp: Option<bool>,
+    snmp_community: Option<String>,
+) -> Result<discovery::IpScanConfig, String> {
+    state.kiosk.ensure_unlocked().await?;
+    let current = state.settings.read().await.discovery.ip_scan.clone();
+    let ip_scan = discovery::IpScanConfig {
+        ranges: ranges.into_iter().map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect(),
+        ports,
+        snmp: snmp.unwrap_or(current.snmp),
+        snmp_community: match snmp_community {
+            Some(community) => Some(community.trim().to_string()).filter(|c| !c.is_empty()),
+            None => current.snmp_community,
+        },
+    };
+    ip_scan.validate()?;
+
+    let mut settings = state.settings.write().await;
+    settings.discovery.ip_scan = ip_scan.clone();
+    settings.save().map_err(|e| e.to_string())?;
+    Ok(ip_scan)
+}
+
+/// Tauri-Befehl: Scanner-Liste importieren (CSV/JSON mit host[:port], z.B. vom Druckserver)
+/// Prüft jeden Host auf eSCL und übernimmt bestätigte Scanner (bleiben für spätere Discoveries gespeichert)
+#[tauri::command]
+async fn import_scanners(
+    state: tauri::State<'_, Arc<AppState>>,
+    content: String,
+) -> Result<Vec<discovery::DiscoveredScanner>, String> {
+    state.kiosk.ensure_unlocked().await?;
+    let hosts = discovery::parse_host_list(&content)?;
+    println!("📥 Prüfe {} importierte Hosts...", hosts.len());
+
+    let mut confirmed = discovery::probe_hosts(&hosts).await;
+    println!("✓ {} von {} Hosts sind eSCL-Scanner", confirmed.len(), hosts.len());
+    device_info::collect(&mut confirmed).await;
+    wol::fill_mac_addresses(&mut confirmed).await;
+
+    let discovery_config = {
+        let mut settings = state.settings.write().await;
+        for host in hosts {
+            if !settings.discovery.static_hosts.contains(&host) {
+                settings.discovery.static_hosts.push(host);
+            }
+        }
+        // Ausdrücklich importierte Scanner wieder einblenden
+        for scanner in &confirmed {
+            settings.discovery.hidden.remove(&scanner.id);
+        }
+        settings.save().map_err(|e| e.to_string())?;
+        settings.discovery.clone()
+    };
+    discovery::apply_scanner_config(&mut confirmed, &discovery_config);
+    for scanner in &confirmed {
+        discovery::mark_reachable(&scanner.id, true);
+    }
+
+    let scanners = {
+        let mut stored_scanners = state.scanners.write().await;
+        for scanner in &confirmed {
+            stored_scanners.retain(|s| s.id != scanner.id && s.ip != scanner.ip);
+            stored_scanners.push(scanner.clone());
+        }
+        stored_scanners.clone()
+    };
+    state.bridge_status.write().await.scanner_count = scanners.len();
+    scanner_sync::mark_changed();
+
+    Ok(confirmed)
+}
+
+/// Tauri-Befehl: Gecachte Scan-Profile aus DocFlow abrufen
+#[tauri::command]
+async fn get_scan_profiles(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<profiles::ScanProfile>, String> {
+    let poller_lock = state.poller.read().await;
+    match poller_lock.as_ref() {
+        Some(poller) => Ok(poller.get_profiles().await),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Tauri-Befehl: Endgültig fehlgeschlagene Jobs (Dead-Letter) abrufen
+#[tauri::command]
+async fn get_dead_letters(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<scan_poller::DeadLetter>, String> {
+    let poller_lock = state.poller.read().await;
+    match poller_lock.as_ref() {
+        Some(poller) => Ok(poller.dead_letters().await),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Tauri-Befehl: Laufende und wartende Scan-Jobs und Ordner-Uploads (Warteschlangen-Ansicht)
+#[tauri::command]
+async fn get_active_jobs(state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<job_queue::ActiveJob>, String> {
+    let mut jobs = Vec::new();
+    if let Some(poller) = state.poller.read().await.as_ref() {
+        jobs.extend(poller.active_jobs().await);
+    }
+    if let Some(watcher) = state.folder_watcher.read().await.as_ref() {
+        jobs.extend(watcher.active_jobs().await);
+    }
+    job_queue::sort_for_display(&mut jobs);
+    Ok(jobs)
+}
+
+/// Tauri-Befehl: Eintrag der Warteschlange abbrechen
+#[tauri::command]
+async fn cancel_job(
+    state: tauri::State<'_, Arc<AppState>>,
+    kind: upload_progress::UploadKind,
+    id: String,
+) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    let result = match kind {
+        upload_progress::UploadKind::Scan => match state.poller.read().await.as_ref() {
+            Some(poller) => poller.cancel_job(&id).await,
+            None => return Err("Scan-Poller läuft nicht".to_string()),
+        },
+        upload_progress::UploadKind::Folder => {
+            let watcher = state.folder_watcher.read().await.clone();
+            match watcher {
+                Some(watcher) => watcher.cancel_file(Path::new(&id)).await,
+                None => Err("Folder-Sync ist nicht aktiv".into()),
+            }
+            // Per Drag & Drop gestartete Uploads laufen außerhalb des Watchers
+            .or_else(|e| if folder_watcher::cancel_upload(&id) { Ok(()) } else { Err(e) })
+        }
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Tauri-Befehl: Laufenden Datei-Upload abbrechen (ID aus "upload-progress", Datei bleibt unverändert)
+#[tauri::command]
+async fn cancel_upload(state: tauri::State<'_, Arc<AppState>>, id: String) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    if folder_watcher::cancel_upload(&id) {
+        Ok(())
+    } else {
+        Err("Kein laufender Upload mit dieser ID".to_string())
+    }
+}
+
+/// Tauri-Befehl: Priorität eines wartenden Eintrags ändern (höher = früher)
+#[tauri::command]
+async fn set_job_priority(
+    state: tauri::State<'_, Arc<AppState>>,
+    kind: upload_progress::UploadKind,
+    id: String,
+    priority: i32,
+) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    let result = match kind {
+        upload_progress::UploadKind::Scan => match state.poller.read().await.as_ref() {
+            Some(poller) => poller.set_job_priority(&id, priority).await,
+            None => return Err("Scan-Poller läuft nicht".to_string()),
+        },
+        upload_progress::UploadKind::Folder => match state.folder_watcher.read().await.as_ref() {
+            Some(watcher) => watcher.set_file_priority(Path::new(&id), priority).await,
+            None => return Err("Folder-Sync ist nicht aktiv".to_string()),
+        },
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Tauri-Befehl: Geräte-Statistik (Jobs, Seiten, Fehler, Scan-Dauer je Scanner)
+#[tauri::command]
+async fn get_scanner_stats() -> Result<Vec<scanner_stats::ScannerStats>, String> {
+    Ok(scanner_stats::snapshot())
+}
+
+/// Tauri-Befehl: Geräte-Statistik zurücksetzen
+#[tauri::command]
+async fn reset_scanner_stats(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    scanner_stats::reset();
+    Ok(())
+}
+
+/// Tauri-Befehl: Gesamtzahlen der Statusseite zurücksetzen
+#[tauri::command]
+async fn reset_counters(state: tauri::State<'_, Arc<AppState>>) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    counters::reset();
+    Ok(())
+}
+
+/// Tauri-Befehl: Einstellungen abrufen
+#[tauri::command]
+async fn get_settings(state: tauri::State<'_, Arc<AppState>>) -> Result<BridgeSettings, String> {
+    Ok(state.settings.read().await.clone())
+}
+
+/// Tauri-Befehl: Aktiver Proxy für DocFlow-Anfragen (eingetragen, System, PAC oder direkt)
+#[tauri::command]
+async fn get_proxy_status(state: tauri::State<'_, Arc<AppState>>) -> Result<Option<system_proxy::DetectedProxy>, String> {
+    Ok(system_proxy::effective(&state.settings.read().await.http))
+}
+
+/// Tauri-Befehl: Einstellungen speichern (wirken sofort auf Poller & Folder-Sync)
+#[tauri::command]
+async fn save_settings(
+    state: tauri::State<'_, Arc<AppState>>,
+    settings: BridgeSettings,
+) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    apply_settings(&state, settings).await
+}
+
+/// Prüft, speichert und übernimmt neue Einstellungen
+async fn apply_settings(state: &AppState, settings: BridgeSettings) -> Result<(), String> {
+    settings.intervals.validate()?;
+    settings.discovery.ip_scan.validate()?;
+    if state.settings.read().await.http != settings.http {
+        http_client::apply_config(&settings.http)?;
+    }
+    settings.save().map_err(|e| e.to_string())?;
+
+    // Metrik-Endpunkt nur bei Änderung neu starten
+    if state.settings.read().await.metrics != settings.metrics {
+        metrics::apply_config(&settings.metrics);
     }
+    events::apply_config(&settings.events);
+    if state.settings.read().await.rate_limit != settings.rate_limit {
+        rate_limit::apply_config(&settings.rate_limit);
+    }
+    if state.settings.read().await.circuit_breaker != settings.circuit_breaker {
+        circuit_breaker::apply_config(&settings.circuit_breaker);
+    }
+    escl_recording::apply_config(&settings.recording);
+    dedup::apply_config(&settings.dedup);
+    let transport_changed = {
+        let current = state.settings.read().await;
+        current.tunnel != settings.tunnel || current.grpc != settings.grpc
+    };
+    announce::apply_config(&settings.announce, &settings.metrics, &settings.push_scan);
+
+    // Festgelegte Endpoints und virtuellen Scanner sofort übernehmen (Gewichte wirken ab der nächsten Discovery)
+    {
+        let mut scanners = state.scanners.write().await;
+        virtual_scanner::sync(&mut scanners, &settings.discovery.virtual_scanner);
+        discovery::apply_scanner_config(&mut scanners, &settings.discovery);
+    }
+    scanner_sync::mark_changed();
+
+    *state.settings.write().await = settings;
+    if transport_changed {
+        restart_transports(state).await;
+    }
+    audit::record(audit::AuditAction::SettingsChanged, serde_json::json!({}));
+    println!("✓ Einstellungen gespeichert");
+    Ok(())
+}
+
+/// Startet Reverse-Tunnel und gRPC-Transport mit den aktuellen Einstellungen neu (nur bei bestehender Kopplung)
+async fn restart_transports(state: &AppState) {
+    if let Some(tunnel) = state.tunnel.write().await.take() {
+        tunnel.stop().await;
+    }
+    if let Some(grpc) = state.grpc.write().await.take() {
+        grpc.stop().await;
+    }
+    let poller = state.poller.read().await.clone();
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    if let (Some(poller), Some(key), Some(url)) = (poller, api_key, docflow_url) {
+        let tunnel = TunnelService::start(poller.clone(), url.clone(), key.clone(), state.settings.clone()).await;
+        *state.tunnel.write().await = tunnel;
+        *state.grpc.write().await = GrpcService::start(poller, url, key, state.settings.clone()).await;
+    }
+}
+
+/// Tauri-Befehl: Konfiguration (ohne Secrets) in eine JSON-Datei exportieren
+/// Liefert den gewählten Pfad (None = Dialog abgebrochen)
+#[tauri::command]
+async fn export_config(state: tauri::State<'_, Arc<AppState>>) -> Result<Option<String>, String> {
+    let folder_sync = FolderSyncConfig::load();
+    let bundle = config_bundle::ConfigBundle::new(state.settings.read().await.clone(), folder_sync);
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_title("Konfiguration exportieren")
+        .set_file_name("docflow-bridge-config.json")
+        .add_filter("JSON", &["json"])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    std::fs::write(file.path(), json).map_err(|e| e.to_string())?;
+    println!("✓ Konfiguration exportiert: {}", file.path().display());
+    Ok(Some(file.path().to_string_lossy().to_string()))
+}
+
+/// Tauri-Befehl: Exportierte Konfiguration übernehmen (Einstellungen + Folder-Sync)
+#[tauri::command]
+async fn import_config(state: tauri::State<'_, Arc<AppState>>, path: String) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Datei nicht lesbar: {}", e))?;
+    let bundle = config_bundle::ConfigBundle::parse(&json)?;
+
+    apply_settings(&state, bundle.settings).await?;
+
+    if let Some(folder_sync) = bundle.folder_sync {
+        let api_key = state.api_key.read().await.clone();
+        let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+
+        match (api_key, docflow_url) {
+            // Verbunden: Folder-Sync direkt starten (Ordner muss auf diesem Rechner existieren)
+            (Some(key), Some(url)) if folder_sync.enabled => {
+                start_folder_sync(&state, folder_sync, key, url).await?;
+            }
+            // Sonst nur speichern - wird nach dem Pairing beim nächsten Start übernommen
+            _ => {
+                folder_sync.save();
+            }
+        }
+    }
+
+    println!("✓ Konfiguration importiert: {}", path);
+    Ok(())
+}
+
+/// Tauri-Befehl: Sperrstatus des Kiosk-Modus abrufen
+#[tauri::command]
+async fn get_lock_status(state: tauri::State<'_, Arc<AppState>>) -> Result<kiosk::LockStatus, String> {
+    Ok(state.kiosk.status().await)
+}
+
+/// Tauri-Befehl: Einstellungen mit Admin-PIN entsperren (für einige Minuten)
+#[tauri::command]
+async fn unlock_settings(state: tauri::State<'_, Arc<AppState>>, pin: String) -> Result<kiosk::LockStatus, String> {
+    state.kiosk.unlock(&pin).await?;
+    Ok(state.kiosk.status().await)
+}
+
+/// Tauri-Befehl: Einstellungen sofort wieder sperren
+#[tauri::command]
+async fn lock_settings(state: tauri::State<'_, Arc<AppState>>) -> Result<kiosk::LockStatus, String> {
+    state.kiosk.lock().await;
+    Ok(state.kiosk.status().await)
+}
+
+/// Tauri-Befehl: Admin-PIN setzen/ändern (None = Kiosk-Modus deaktivieren)
+#[tauri::command]
+async fn set_admin_pin(state: tauri::State<'_, Arc<AppState>>, pin: Option<String>) -> Result<kiosk::LockStatus, String> {
+    state.kiosk.set_pin(pin).await?;
+    Ok(state.kiosk.status().await)
+}
+
+/// Tauri-Befehl: Ereignis-Verlauf abrufen (since = zuletzt gesehene Ereignis-ID)
+#[tauri::command]
+async fn get_events(since: Option<u64>) -> Result<Vec<events::BridgeEvent>, String> {
+    Ok(events::since(since))
+}
+
+/// Tauri-Befehl: Selbsttest (Keyring, DocFlow, Scanner, Scan-Ordner, Speicherplatz)
+#[tauri::command]
+async fn run_self_test(state: tauri::State<'_, Arc<AppState>>) -> Result<self_test::SelfTestReport, String> {
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    let scanners = state.scanners.read().await.clone();
+    let watch_path = FolderSyncConfig::load().filter(|c| c.enabled).map(|c| c.watch_path);
+
+    let report = self_test::run(self_test::SelfTestInput {
+        api_key: api_key.as_deref(),
+        docflow_url: docflow_url.as_deref(),
+        scanners: &scanners,
+        watch_path: watch_path.as_deref(),
+    })
+    .await;
+
+    println!("🩺 Selbsttest: {}", if report.passed { "bestanden" } else { "Probleme gefunden" });
+    Ok(report)
+}
+
+/// Tauri-Befehl: Netzwerk-Diagnose zu DocFlow (DNS, Latenz, Upload-Durchsatz)
+#[tauri::command]
+async fn run_network_diagnostics(
+    state: tauri::State<'_, Arc<AppState>>,
+) -> Result<network_diagnostics::NetworkDiagnosticsReport, String> {
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    let (key, url) = match (api_key, docflow_url) {
+        (Some(k), Some(u)) => (k, u),
+        _ => return Err("Nicht mit DocFlow verbunden".to_string()),
+    };
+
+    println!("📶 Netzwerk-Diagnose zu {} ...", url);
+    let report = network_diagnostics::run(&key, &url).await;
+    println!("📶 Netzwerk-Diagnose: {}", if report.passed { "bestanden" } else { "Probleme gefunden" });
+    Ok(report)
+}
+
+/// Tauri-Befehl: Probescan (eine Seite, niedrige Auflösung) mit Zeitmessung je Phase - ohne Upload
+#[tauri::command]
+async fn test_scan(
+    state: tauri::State<'_, Arc<AppState>>,
+    scanner_id: String,
+) -> Result<test_scan::TestScanReport, String> {
+    let scanner = state
+        .scanners
+        .read()
+        .await
+        .iter()
+        .find(|s| s.id == scanner_id)
+        .cloned()
+        .ok_or_else(|| format!("Scanner '{}' nicht gefunden", scanner_id))?;
+
+    println!("🧪 Probescan an {} ...", scanner.name);
+    let report = test_scan::run(&scanner).await;
+    println!(
+        "🧪 Probescan {}: {} ({} ms)",
+        scanner.name,
+        if report.passed { "erfolgreich" } else { "fehlgeschlagen" },
+        report.total_ms
+    );
+    Ok(report)
+}
+
+/// Tauri-Befehl: Job-Liste des Scanners (eSCL JobInfo) zur Fehlersuche bei hängenden Geräten
+#[tauri::command]
+async fn get_scanner_jobs(
+    state: tauri::State<'_, Arc<AppState>>,
+    scanner_id: String,
+) -> Result<Vec<scanner::ScannerJobInfo>, String> {
+    let device = state
+        .scanners
+        .read()
+        .await
+        .iter()
+        .find(|s| s.id == scanner_id)
+        .cloned()
+        .ok_or_else(|| format!("Scanner '{}' nicht gefunden", scanner_id))?;
+
+    let quirks = quirks::for_scanner(&device.manufacturer, &device.model);
+    let result = scanner::scanner_jobs(&device.ip, device.port, device.use_tls, &device.rs_path, &quirks).await;
+    discovery::mark_result(&device.id, &result);
+    result.map_err(|e| format!("Job-Liste von {} nicht abrufbar: {}", device.name, e))
+}
+
+/// Tauri-Befehl: Einzelnen Job am Scanner löschen (vom Benutzer in der Job-Liste gewählt)
+#[tauri::command]
+async fn clear_scanner_job(
+    state: tauri::State<'_, Arc<AppState>>,
+    scanner_id: String,
+    job_uri: String,
+) -> Result<(), String> {
+    state.kiosk.ensure_unlocked().await?;
+    let device = state
+        .scanners
+        .read()
+        .await
+        .iter()
+        .find(|s| s.id == scanner_id)
+        .cloned()
+        .ok_or_else(|| format!("Scanner '{}' nicht gefunden", scanner_id))?;
+
+    let quirks = quirks::for_scanner(&device.manufacturer, &device.model);
+    scanner::clear_job(&device.ip, device.port, device.use_tls, &device.rs_path, &quirks, &job_uri)
+        .await
+        .map_err(|e| format!("Job auf {} nicht gelöscht: {}", device.name, e))
+}
+
+/// Tauri-Befehl: Vorschau der anonymen Telemetrie (genau diese Daten würden gesendet)
+#[tauri::command]
+async fn get_telemetry_preview(state: tauri::State<'_, Arc<AppState>>) -> Result<telemetry::TelemetryReport, String> {
+    Ok(telemetry::build(&state.scanners.read().await))
+}
+
+/// Tauri-Befehl: Stand der Ersteinrichtung abrufen
+#[tauri::command]
+async fn get_onboarding_state() -> Result<onboarding::OnboardingState, String> {
+    Ok(onboarding::OnboardingState::load())
+}
+
+/// Tauri-Befehl: Einrichtung fortsetzen (z.B. Ordner überspringen oder abschließen)
+#[tauri::command]
+async fn advance_onboarding(step: onboarding::OnboardingStep) -> Result<onboarding::OnboardingState, String> {
+    Ok(onboarding::advance(step))
+}
+
+/// Tauri-Befehl: Einrichtung neu starten
+#[tauri::command]
+async fn reset_onboarding(state: tauri::State<'_, Arc<AppState>>) -> Result<onboarding::OnboardingState, String> {
+    state.kiosk.ensure_unlocked().await?;
+    Ok(onboarding::reset())
 }
 
 /// Tauri-Befehl: Nativen Ordner-Dialog öffnen
@@ -402,6 +1214,29 @@ async fn pick_folder() -> Result<Option<String>, String> {
     Ok(folder.map(|f| f.path().to_string_lossy().to_string()))
 }
 
+/// Tauri-Befehl: Bekannte Scan-Ordner vorschlagen (Setup-Assistent)
+#[tauri::command]
+async fn suggest_watch_folders() -> Result<Vec<folder_watcher::FolderSuggestion>, String> {
+    tokio::task::spawn_blocking(folder_watcher::suggest_watch_folders)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri-Befehl: Scan-Ordner vor dem Aktivieren prüfen (Rechte, uploaded/, Netzlaufwerk, Speicherplatz, vorhandene Dateien)
+#[tauri::command]
+async fn validate_folder(
+    state: tauri::State<'_, Arc<AppState>>,
+    path: String,
+) -> Result<folder_watcher::FolderValidation, String> {
+    let (ignore, min_free_disk_mb) = {
+        let settings = state.settings.read().await;
+        (settings.folder_ignore.clone(), settings.resources.min_free_disk_mb)
+    };
+    tokio::task::spawn_blocking(move || folder_watcher::validate_folder(Path::new(&path), &ignore, min_free_disk_mb))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Prüft auf Updates und zeigt ggf. einen Dialog
 async fn check_for_updates(app: tauri::AppHandle) {
     use tauri_plugin_updater::UpdaterExt;
@@ -430,7 +1265,274 @@ async fn check_for_updates(app: tauri::AppHandle) {
     }
 }
 
+/// Hält das Tray-Icon aktuell (Zustand + Badge), zeichnet nur bei Änderungen neu
+async fn run_tray_updates(app: tauri::AppHandle, state: Arc<AppState>) {
+    let Some(base) = app.default_window_icon().cloned() else {
+        return;
+    };
+    let (base_rgba, width, height) = (base.rgba().to_vec(), base.width(), base.height());
+    let mut shown: Option<(tray::TrayState, usize, String)> = None;
+
+    loop {
+        let connected = state.bridge_status.read().await.connected;
+        let (tray_state, queued, mut tooltip) = tray::current(connected);
+
+        // Fehlende Einrichtungsschritte im Tooltip anzeigen
+        if let Some(hint) = onboarding::OnboardingState::load().step.hint() {
+            tooltip.push_str(&format!("\n{}", hint));
+        }
+
+        let current = (tray_state, queued, tooltip);
+        if shown.as_ref() != Some(&current) {
+            if let Some(tray_icon) = app.tray_by_id("main") {
+                let rgba = tray::render(&base_rgba, width, height, tray_state, queued);
+                let _ = tray_icon.set_icon(Some(tauri::image::Image::new_owned(rgba, width, height)));
+                let _ = tray_icon.set_tooltip(Some(current.2.clone()));
+                shown = Some(current);
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+}
+
+/// Verschickt einmal täglich die Folder-Sync-Zusammenfassung (Desktop + DocFlow)
+async fn run_digest_loop(app: tauri::AppHandle, state: Arc<AppState>) {
+    use tauri_plugin_notification::NotificationExt;
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+
+        let config = state.settings.read().await.digest.clone();
+        if !digest::is_due(&config) {
+            continue;
+        }
+
+        let summary = digest::take_summary();
+        println!("📋 Tageszusammenfassung: {}", summary.message());
+
+        if config.notify_desktop {
+            let _ = app
+                .notification()
+                .builder()
+                .title("DocFlow Folder-Sync")
+                .body(summary.message())
+                .show();
+        }
+
+        if config.report_to_docflow {
+            let api_key = state.api_key.read().await.clone();
+            let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+            if let (Some(key), Some(url)) = (api_key, docflow_url) {
+                if let Err(e) = digest::report(&url, &key, &summary).await {
+                    eprintln!("⚠ Tageszusammenfassung konnte nicht gemeldet werden: {}", e);
+                }
+            }
+        }
+
+        digest::mark_sent(&summary);
+    }
+}
+
+/// Stündlicher Verbindungs-Check: liest dabei das Serverzertifikat und warnt ab 14 Tagen vor Ablauf (einmal täglich)
+async fn run_certificate_check_loop(app: tauri::AppHandle, state: Arc<AppState>) {
+    use tauri_plugin_notification::NotificationExt;
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+
+        let api_key = state.api_key.read().await.clone();
+        let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+        if let (Some(key), Some(url)) = (api_key, docflow_url) {
+            pairing::validate_connection(&key, &url).await;
+        }
+
+        for expiry in cert_expiry::due_notifications() {
+            let message = expiry.message();
+            eprintln!("⚠ {}", message);
+            events::record(events::EventKind::Error, message.clone());
+            let _ = app
+                .notification()
+                .builder()
+                .title("DocFlow-Zertifikat läuft ab")
+                .body(message)
+                .show();
+        }
+    }
+}
+
+/// Leitet Upload-Fortschritt (Poller, Folder-Sync) als "upload-progress" ans Frontend weiter
+async fn run_upload_progress_forwarder(app: tauri::AppHandle) {
+    let mut progress = upload_progress::subscribe();
+    loop {
+        match progress.recv().await {
+            Ok(update) => {
+                let _ = app.emit("upload-progress", update);
+            }
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Startet beendete oder hängende Tasks neu und meldet dauerhaft fehlschlagende Neustarts
+async fn run_supervisor_loop(state: Arc<AppState>) {
+    let mut reported = false;
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(15)).await;
+
+        for task in supervisor::due_for_restart() {
+            match task {
+                supervisor::POLLER => restart_poller(&state).await,
+                supervisor::FOLDER_WATCHER => restart_folder_sync(&state).await,
+                _ => {}
+            }
+        }
+
+        match supervisor::failing() {
+            Some(message) if !reported => {
+                tray::report_error(&message);
+                events::record(events::EventKind::Error, message);
+                reported = true;
+            }
+            Some(_) => {}
+            None => reported = false,
+        }
+    }
+}
+
+/// Ersetzt den Poller (und Push-Scan-Dienst, Tunnel und gRPC) durch eine neue Instanz
+async fn restart_poller(state: &Arc<AppState>) {
+    stop_services(state).await;
+
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    match (api_key, docflow_url) {
+        (Some(key), Some(url)) => start_poller(state, key, url).await,
+        _ => supervisor::unwatch(supervisor::POLLER),
+    }
+}
+
+/// Startet den Folder-Watcher mit seiner bisherigen Konfiguration neu
+async fn restart_folder_sync(state: &AppState) {
+    let Some(watcher) = state.folder_watcher.read().await.clone() else {
+        supervisor::unwatch(supervisor::FOLDER_WATCHER);
+        return;
+    };
+    let config = watcher.config.read().await.clone();
+
+    let api_key = state.api_key.read().await.clone();
+    let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+    let (Some(key), Some(url)) = (api_key, docflow_url) else {
+        supervisor::unwatch(supervisor::FOLDER_WATCHER);
+        return;
+    };
+
+    if let Err(e) = start_folder_sync(state, config, key, url).await {
+        eprintln!("⚠ Folder-Sync-Neustart fehlgeschlagen: {}", e);
+    }
+}
+
+/// Meldet Änderungen der Scanner-Liste gebündelt an DocFlow (nur wenn sich die Daten unterscheiden)
+async fn run_scanner_sync_loop(state: Arc<AppState>) {
+    let mut last_sent: Option<(String, serde_json::Value)> = None;
+
+    loop {
+        scanner_sync::changed().await;
+
+        let api_key = state.api_key.read().await.clone();
+        let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+        let (Some(key), Some(url)) = (api_key, docflow_url) else {
+            continue;
+        };
+
+        // Nur Scanner, die dieser DocFlow-Instanz zugeordnet sind
+        let discovery_config = state.settings.read().await.discovery.clone();
+        let scanners = discovery_config.visible_scanners(&state.scanners.read().await, &url);
+        let removed = scanner_sync::removed_ids(&scanners, &discovery_config.hidden);
+        let payload = scanner_sync::payload(&scanners, &removed);
+        if last_sent.as_ref().is_some_and(|(u, p)| u == &url && p == &payload) {
+            continue;
+        }
+
+        match scanner_sync::send(&url, &key, &payload).await {
+            Ok(()) => last_sent = Some((url, payload)),
+            Err(e) => {
+                eprintln!("Warnung: Konnte Scanner nicht an DocFlow senden: {}", e);
+                // Später erneut versuchen
+                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                scanner_sync::mark_changed();
+            }
+        }
+    }
+}
+
+/// System-Proxy beim Start und nach Netz- oder Einstellungswechsel neu erkennen
+async fn run_proxy_detection_loop(state: Arc<AppState>) {
+    let mut first = true;
+    loop {
+        let http = state.settings.read().await.http.clone();
+        // Eingetragener Proxy oder "direkt" überschreiben die Erkennung
+        if http.proxy_mode == http_client::ProxyMode::Auto && http.proxy_url.as_deref().is_none_or(|p| p.trim().is_empty()) {
+            let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+            system_proxy::refresh(docflow_url.as_deref(), first).await;
+            first = false;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    }
+}
+
+/// Überträgt das Audit-Log alle 5 Minuten gebündelt an DocFlow (Rückstand nach Offline-Phasen inklusive)
+async fn run_audit_upload_loop(state: Arc<AppState>) {
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(300)).await;
+
+        let api_key = state.api_key.read().await.clone();
+        let docflow_url = state.bridge_status.read().await.docflow_url.clone();
+        let (Some(key), Some(url)) = (api_key, docflow_url) else {
+            continue;
+        };
+        match audit::upload_pending(&url, &key).await {
+            Ok(0) => {}
+            Ok(count) => println!("📜 {} Audit-Einträge an DocFlow übertragen", count),
+            Err(e) => eprintln!("⚠ Audit-Log nicht übertragen: {}", e),
+        }
+    }
+}
+
+/// Anonyme Telemetrie (nur bei Opt-in, höchstens einmal täglich)
+async fn run_telemetry_loop(state: Arc<AppState>) {
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+
+        let config = state.settings.read().await.telemetry.clone();
+        if !telemetry::is_due(&config) {
+            continue;
+        }
+
+        // Ohne eigenen Endpunkt nur mit bestehender DocFlow-Verbindung
+        let api_key = state.api_key.read().await.clone().unwrap_or_default();
+        let docflow_url = state.bridge_status.read().await.docflow_url.clone().unwrap_or_default();
+        if config.endpoint.is_none() && (api_key.is_empty() || docflow_url.is_empty()) {
+            continue;
+        }
+
+        let report = telemetry::build(&state.scanners.read().await);
+        match telemetry::send(&config, &report, &docflow_url, &api_key).await {
+            Ok(()) => {
+                println!("📈 Anonyme Telemetrie gesendet");
+                telemetry::mark_sent();
+            }
+            Err(e) => eprintln!("⚠ Telemetrie konnte nicht gesendet werden: {}", e),
+        }
+    }
+}
+
 fn main() {
+    // Keyring-Einträge je Benutzer/Profil trennen, bevor Kiosk-PIN und Verbindung gelesen werden
+    secrets::migrate_legacy();
+    timestamps::mark_start();
     let state = Arc::new(AppState::default());
 
     tauri::Builder::default()
@@ -454,14 +1556,17 @@ fn main() {
                 .text("quit", "Beenden")
                 .build()?;
 
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id("main")
                 .icon(app.default_window_icon().unwrap().clone())
                 .tooltip("DocFlow Scanner Bridge")
                 .menu(&tray_menu)
                 .show_menu_on_left_click(false)
                 .on_menu_event(|app, event| {
+                    // Benutzer ist da: Poller aus dem Leerlauf holen
+                    scan_poller::wake();
                     match event.id.as_ref() {
                         "quit" => {
+                            counters::flush();
                             std::process::exit(0);
                         }
                         "settings" => {
@@ -494,6 +1599,7 @@ fn main() {
                         button_state: MouseButtonState::Up,
                         ..
                     } = event {
+                        scan_poller::wake();
                         let app = tray.app_handle();
                         if let Some(window) = app.get_webview_window("main") {
                             let _ = window.show();
@@ -503,15 +1609,45 @@ fn main() {
                 })
                 .build(app)?;
 
-            // Fenster beim Schließen minimieren statt beenden
+            // Fenster-Verhalten aus den Einstellungen (Schließen → Tray oder Beenden)
+            let window_state = app.state::<Arc<AppState>>().inner().clone();
             let main_window = app.get_webview_window("main").unwrap();
-            main_window.on_window_event(|event| {
+            let window_for_event = main_window.clone();
+            let app_for_event = app.handle().clone();
+            main_window.on_window_event(move |event| {
                 if let WindowEvent::CloseRequested { api, .. } = event {
-                    // Fenster verstecken statt schließen
-                    api.prevent_close();
+                    let close_action = window_state
+                        .settings
+                        .try_read()
+                        .map(|s| s.window.close_action)
+                        .unwrap_or_default();
+
+                    match close_action {
+                        CloseAction::MinimizeToTray => {
+                            // Fenster verstecken statt schließen
+                            api.prevent_close();
+                            let _ = window_for_event.hide();
+                        }
+                        CloseAction::Exit => {
+                            counters::flush();
+                            app_for_event.exit(0)
+                        }
+                    }
                 }
             });
 
+            // Fenster ist in tauri.conf.json unsichtbar - nur anzeigen, wenn nicht minimiert gestartet
+            let start_minimized = std::env::args().any(|a| a == "--minimized")
+                || app
+                    .state::<Arc<AppState>>()
+                    .settings
+                    .try_read()
+                    .map(|s| s.window.start_minimized)
+                    .unwrap_or(false);
+            if !start_minimized {
+                let _ = main_window.show();
+            }
+
             // Auto-Update beim Start (nur in Release-Builds)
             #[cfg(not(debug_assertions))]
             {
@@ -526,13 +1662,58 @@ fn main() {
             // Beim Start: Gespeicherten API-Key und DocFlow-URL laden
             let state = app.state::<Arc<AppState>>();
             let state_clone = state.inner().clone();
+
+            // Tray-Icon mit Status und Warteschlangen-Badge
+            tauri::async_runtime::spawn(run_tray_updates(app.handle().clone(), state_clone.clone()));
+
+            // Tageszusammenfassung (prüft jede Minute, ob sie fällig ist)
+            tauri::async_runtime::spawn(run_digest_loop(app.handle().clone(), state_clone.clone()));
+
+            // Audit-Log prüfen und gebündelt an DocFlow übertragen
+            let verification = audit::verify();
+            if !verification.valid {
+                eprintln!("⚠ Audit-Log: {}", verification.message);
+                events::record(events::EventKind::Error, format!("Audit-Log beschädigt: {}", verification.message));
+            }
+            tauri::async_runtime::spawn(run_audit_upload_loop(state_clone.clone()));
+
+            // Anonyme Telemetrie (standardmäßig aus)
+            tauri::async_runtime::spawn(run_telemetry_loop(state_clone.clone()));
+
+            // Poller und Folder-Sync überwachen und bei Bedarf neu starten
+            tauri::async_runtime::spawn(run_supervisor_loop(state_clone.clone()));
+
+            // System-Proxy (inkl. PAC) erkennen und bei Netzwechsel nachziehen
+            tauri::async_runtime::spawn(run_proxy_detection_loop(state_clone.clone()));
+
+            // Scanner-Liste bei Änderungen an DocFlow melden
+            tauri::async_runtime::spawn(run_scanner_sync_loop(state_clone.clone()));
+
+            // Ablauf des DocFlow-Serverzertifikats überwachen
+            tauri::async_runtime::spawn(run_certificate_check_loop(app.handle().clone(), state_clone.clone()));
+
+            // Upload-Fortschritt ans Frontend
+            tauri::async_runtime::spawn(run_upload_progress_forwarder(app.handle().clone()));
             tauri::async_runtime::spawn(async move {
-                let api_key_result = keyring::Entry::new("docflow-scanner-bridge", "api_key")
-                    .ok()
-                    .and_then(|e| e.get_password().ok());
-                let docflow_url_result = keyring::Entry::new("docflow-scanner-bridge", "docflow_url")
-                    .ok()
-                    .and_then(|e| e.get_password().ok());
+                // Metrik-Endpunkt starten (falls aktiviert)
+                metrics::apply_config(&state_clone.settings.read().await.metrics);
+                events::apply_config(&state_clone.settings.read().await.events);
+                rate_limit::apply_config(&state_clone.settings.read().await.rate_limit);
+                circuit_breaker::apply_config(&state_clone.settings.read().await.circuit_breaker);
+                escl_recording::apply_config(&state_clone.settings.read().await.recording);
+                dedup::apply_config(&state_clone.settings.read().await.dedup);
+                // Ausgelagerte Scans eines früheren Starts sind ohne dessen Schlüssel nicht mehr lesbar
+                spool::cleanup();
+                {
+                    let settings = state_clone.settings.read().await;
+                    announce::apply_config(&settings.announce, &settings.metrics, &settings.push_scan);
+                }
+                if let Err(e) = http_client::apply_config(&state_clone.settings.read().await.http) {
+                    eprintln!("⚠️ Netzwerk-Einstellungen ungültig, nutze Standard: {}", e);
+                }
+
+                let api_key_result = secrets::get("api_key");
+                let docflow_url_result = secrets::get("docflow_url");
 
                 if let (Some(key), Some(url)) = (api_key_result, docflow_url_result) {
                     // API-Key und URL speichern
@@ -551,36 +1732,26 @@ fn main() {
                     let key_for_watcher = key.clone();
                     let url_for_watcher = url.clone();
 
-                    // Scan-Poller starten
-                    let poller = Arc::new(ScanPoller::new(
-                        key,
-                        url,
-                        state_clone.scanners.clone(),
-                    ));
-
-                    {
-                        let mut poller_lock = state_clone.poller.write().await;
-                        *poller_lock = Some(poller.clone());
+                    // Bridge-ID für den User-Agent
+                    if let Some(bridge_id) = secrets::get("bridge_id") {
+                        http_client::set_bridge_id(Some(&bridge_id));
                     }
 
-                    // Poller in separatem Task starten
-                    let poller_clone = poller.clone();
-                    tokio::spawn(async move {
-                        poller_clone.start_polling().await;
-                    });
-
-                    {
-                        let mut status = state_clone.bridge_status.write().await;
-                        status.poller_active = true;
+                    // Signaturschlüssel aus dem Pairing
+                    if !request_signing::load_secret() && state_clone.settings.read().await.http.request_signing {
+                        eprintln!("⚠ Anfragesignatur aktiviert, aber kein Signaturschlüssel gespeichert - Bridge neu koppeln");
                     }
 
+                    // Scan-Poller starten
+                    start_poller(&state_clone, key, url).await;
+
                     println!("✓ Verbindung wiederhergestellt, Poller gestartet");
+                    announce::set_paired(true);
+                    // Bestehende Installationen: Pairing gilt als erledigt
+                    onboarding::advance(onboarding::OnboardingStep::Paired);
 
                     // Folder-Sync Config laden und ggf. starten
-                    let folder_config_result = keyring::Entry::new("docflow-scanner-bridge", "folder_sync_config")
-                        .ok()
-                        .and_then(|e| e.get_password().ok())
-                        .and_then(|json| serde_json::from_str::<FolderSyncConfig>(&json).ok());
+                    let folder_config_result = FolderSyncConfig::load();
 
                     if let Some(config) = folder_config_result {
                         if config.enabled && std::path::Path::new(&config.watch_path).exists() {
@@ -588,6 +1759,7 @@ fn main() {
                                 config.clone(),
                                 key_for_watcher.clone(),
                                 url_for_watcher.clone(),
+                                state_clone.settings.clone(),
                             ));
 
                             {
@@ -596,9 +1768,10 @@ fn main() {
                             }
 
                             let watcher_clone = watcher.clone();
-                            tokio::spawn(async move {
+                            let handle = tokio::spawn(async move {
                                 watcher_clone.start_watching().await;
                             });
+                            supervisor::watch(supervisor::FOLDER_WATCHER, handle);
 
                             {
                                 let mut status = state_clone.bridge_status.write().await;
@@ -617,12 +1790,55 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             get_status,
             discover_scanners,
+            get_scanners,
+            remove_scanner,
+            unhide_scanner,
             pair_with_docflow,
+            discover_docflow_servers,
             disconnect,
             configure_folder_sync,
             stop_folder_sync,
             get_folder_sync_status,
+            get_poller_status,
+            upload_files,
+            upload_clipboard,
+            get_settings,
+            get_proxy_status,
+            pin_scanner_endpoint,
+            set_scanner_label,
+            get_scanner_assignments,
+            set_scanner_assignment,
+            import_scanners,
+            set_discovery_config,
+            save_settings,
+            get_scan_profiles,
+            get_dead_letters,
+            get_active_jobs,
+            get_scanner_stats,
+            reset_scanner_stats,
+            reset_counters,
+            cancel_job,
+            cancel_upload,
+            set_job_priority,
+            export_config,
+            import_config,
+            get_lock_status,
+            unlock_settings,
+            lock_settings,
+            set_admin_pin,
+            get_events,
+            run_self_test,
+            run_network_diagnostics,
+            test_scan,
+            get_scanner_jobs,
+            clear_scanner_job,
+            get_telemetry_preview,
+            get_onboarding_state,
+            advance_onboarding,
+            reset_onboarding,
             pick_folder,
+            suggest_watch_folders,
+            validate_folder,
         ])
         .run(tauri::generate_context!())
         .expect("Fehler beim Starten der Anwendung");
@@ -1,16 +1,56 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Scan, Link2, Settings, RefreshCw, CheckCircle, XCircle, Loader2, FolderSync, FolderOpen, Play, Square } from 'lucide-react';
+import { getCurrentWebview } from '@tauri-apps/api/webview';
+import { Scan, Link2, Settings, RefreshCw, CheckCircle, XCircle, Loader2, FolderSync, FolderOpen, Play, Square, ArrowUp, X } from 'lucide-react';
 import './App.css';
 
+/** Im Backend berechnete Anzeige eines Zeitstempels */
+interface DisplayTime {
+  local: string;
+  seconds_ago: number;
+  ago: string;
+}
+
 interface BridgeStatus {
   connected: boolean;
   docflow_url: string | null;
   scanner_count: number;
   last_discovery: string | null;
   version: string;
+  jobs_processed: number;
   folder_sync_active: boolean;
   folder_sync_path: string | null;
+  resource_warning: string | null;
+  task_error: string | null;
+  docflow_paused: { server: string; retry_in_secs: number }[];
+  certificate_warning: string | null;
+  audit_warning: string | null;
+  tunnel: {
+    connected: boolean;
+    url: string;
+    connected_since: string | null;
+    requests: number;
+    last_error: string | null;
+  } | null;
+  grpc: {
+    endpoint: string;
+    streaming: boolean;
+    uploads: number;
+    fallbacks: number;
+    last_error: string | null;
+  } | null;
+  totals: {
+    jobs_processed: number;
+    files_uploaded: number;
+    folder_errors: number;
+    files_quarantined: number;
+    files_skipped: number;
+    files_duplicate: number;
+    since: string | null;
+  };
+  last_discovery_display: DisplayTime | null;
+  uptime_secs: number;
+  uptime: string;
 }
 
 interface FolderSyncStatusInfo {
@@ -19,8 +59,13 @@ interface FolderSyncStatusInfo {
   files_uploaded: number;
   files_pending: number;
   errors: number;
+  files_quarantined: number;
+  files_skipped: number;
+  files_duplicate: number;
   last_upload: string | null;
   last_error: string | null;
+  last_upload_display: DisplayTime | null;
+  running_secs: number | null;
 }
 
 interface Scanner {
@@ -32,6 +77,74 @@ interface Scanner {
   port: number;
   protocols: string[];
   discovery_method: string;
+  /** Nur aus get_scanners (zuletzt bekannte Erreichbarkeit) */
+  online?: boolean;
+  last_seen?: string | null;
+}
+
+interface TestScanReport {
+  scanner_id: string;
+  passed: boolean;
+  total_ms: number;
+  page_bytes: number;
+  phases: {
+    name: string;
+    status: 'ok' | 'warning' | 'failed' | 'skipped';
+    duration_ms: number;
+    message: string;
+    http_status?: number;
+    raw_response?: string;
+  }[];
+}
+
+interface ScannerJobInfo {
+  job_uri: string;
+  state: string;
+  age_secs: number | null;
+  images_completed: number | null;
+  images_to_transfer: number | null;
+  reason: string | null;
+}
+
+interface NetworkDiagnosticsReport {
+  passed: boolean;
+  checks: {
+    name: string;
+    status: 'ok' | 'warning' | 'failed' | 'skipped';
+    message: string;
+  }[];
+}
+
+interface FolderValidation {
+  readable: boolean;
+  writable: boolean;
+  can_create_uploaded: boolean;
+  network_share: boolean;
+  eligible_files: number;
+  warnings: string[];
+  errors: string[];
+}
+
+interface FileUploadResult {
+  path: string;
+  response?: { job_id: number; filename: string; duplicate: boolean };
+  error?: string;
+}
+
+interface ActiveJob {
+  kind: 'scan' | 'folder';
+  id: string;
+  name: string;
+  state: 'running' | 'queued' | 'retrying';
+  priority: number;
+  message?: string;
+}
+
+interface DetectedServer {
+  url: string;
+  name: string;
+  source: string;
+  version?: string;
 }
 
 type View = 'status' | 'pairing' | 'scanners' | 'folder_sync' | 'settings';
@@ -47,17 +160,38 @@ function App() {
   });
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState('');
+  const [detectedServers, setDetectedServers] = useState<DetectedServer[]>([]);
+  const [searchingServers, setSearchingServers] = useState(false);
+  const [uploadNotice, setUploadNotice] = useState('');
+  const [activeJobs, setActiveJobs] = useState<ActiveJob[]>([]);
+  const [testScanning, setTestScanning] = useState<string | null>(null);
+  const [testScanReport, setTestScanReport] = useState<TestScanReport | null>(null);
+  const [scannerJobs, setScannerJobs] = useState<{ scannerId: string; jobs: ScannerJobInfo[] } | null>(null);
+  const [loadingJobs, setLoadingJobs] = useState<string | null>(null);
+  const [networkCheck, setNetworkCheck] = useState(false);
+  const [networkReport, setNetworkReport] = useState<NetworkDiagnosticsReport | null>(null);
 
   // Folder Sync State
   const [folderSyncStatus, setFolderSyncStatus] = useState<FolderSyncStatusInfo | null>(null);
   const [watchPath, setWatchPath] = useState(() => localStorage.getItem('docflow-watch-path') || '');
   const [postAction, setPostAction] = useState<string>(() => localStorage.getItem('docflow-post-action') || 'move');
+  const [autoOrient, setAutoOrient] = useState<boolean>(() => localStorage.getItem('docflow-auto-orient') === 'true');
 
   // Status beim Start laden
   useEffect(() => {
     loadStatus();
+    loadScanners();
   }, []);
 
+  // Zuletzt gefundene Scanner sofort anzeigen, ohne neue Suche
+  const loadScanners = async () => {
+    try {
+      setScanners(await invoke<Scanner[]>('get_scanners'));
+    } catch (e) {
+      console.error('Scanner laden fehlgeschlagen:', e);
+    }
+  };
+
   const loadStatus = async () => {
     try {
       const s = await invoke<BridgeStatus>('get_status');
@@ -70,6 +204,75 @@ function App() {
     }
   };
 
+  const resetCounters = async () => {
+    if (!confirm('Alle Zähler auf null setzen?')) return;
+    try {
+      await invoke('reset_counters');
+      await loadStatus();
+    } catch (e) {
+      setError(`Zurücksetzen fehlgeschlagen: ${e}`);
+    }
+  };
+
+  const removeScanner = async (scanner: Scanner, hide: boolean) => {
+    try {
+      await invoke('remove_scanner', { scannerId: scanner.id, hide });
+      setScanners((current) => current.filter((s) => s.id !== scanner.id));
+      await loadStatus();
+    } catch (e) {
+      setError(`Entfernen fehlgeschlagen: ${e}`);
+    }
+  };
+
+  const runTestScan = async (scanner: Scanner) => {
+    setTestScanning(scanner.id);
+    setTestScanReport(null);
+    try {
+      setTestScanReport(await invoke<TestScanReport>('test_scan', { scannerId: scanner.id }));
+    } catch (e) {
+      setError(`Probescan fehlgeschlagen: ${e}`);
+    } finally {
+      setTestScanning(null);
+    }
+  };
+
+  const loadScannerJobs = async (scanner: Scanner) => {
+    setLoadingJobs(scanner.id);
+    try {
+      const jobs = await invoke<ScannerJobInfo[]>('get_scanner_jobs', { scannerId: scanner.id });
+      setScannerJobs({ scannerId: scanner.id, jobs });
+    } catch (e) {
+      setError(`${e}`);
+    } finally {
+      setLoadingJobs(null);
+    }
+  };
+
+  const clearScannerJob = async (scanner: Scanner, job: ScannerJobInfo) => {
+    const running = job.state === 'Processing' || job.state === 'Pending';
+    if (running && !confirm(`Job ${job.job_uri} läuft noch (${job.state}). Trotzdem am Scanner löschen?`)) {
+      return;
+    }
+    try {
+      await invoke('clear_scanner_job', { scannerId: scanner.id, jobUri: job.job_uri });
+      await loadScannerJobs(scanner);
+    } catch (e) {
+      setError(`${e}`);
+    }
+  };
+
+  const runNetworkDiagnostics = async () => {
+    setNetworkCheck(true);
+    setNetworkReport(null);
+    try {
+      setNetworkReport(await invoke<NetworkDiagnosticsReport>('run_network_diagnostics'));
+    } catch (e) {
+      setError(`Netzwerk-Diagnose fehlgeschlagen: ${e}`);
+    } finally {
+      setNetworkCheck(false);
+    }
+  };
+
   const discoverScanners = async () => {
     setLoading(true);
     setError('');
@@ -85,6 +288,26 @@ function App() {
     }
   };
 
+  const handleSearchServers = async () => {
+    setSearchingServers(true);
+    setError('');
+    try {
+      const found = await invoke<DetectedServer[]>('discover_docflow_servers');
+      setDetectedServers(found);
+      // Genau ein Treffer: direkt übernehmen
+      if (found.length === 1) {
+        setDocflowUrl(found[0].url);
+      }
+      if (found.length === 0) {
+        setError('Kein DocFlow-Server im Netzwerk gefunden - bitte URL manuell eingeben');
+      }
+    } catch (e) {
+      setError(`Server-Suche fehlgeschlagen: ${e}`);
+    } finally {
+      setSearchingServers(false);
+    }
+  };
+
   const handlePairing = async () => {
     if (!pairingCode.trim()) {
       setError('Bitte Pairing-Code eingeben');
@@ -159,12 +382,26 @@ function App() {
     setLoading(true);
     setError('');
     try {
+      // Vor dem Aktivieren prüfen: "uploaded/" wird nur beim Verschieben benötigt
+      const validation = await invoke<FolderValidation>('validate_folder', { path: watchPath.trim() });
+      const usable = validation.readable && validation.writable;
+      if (!usable || (postAction === 'move' && !validation.can_create_uploaded)) {
+        setError(validation.errors.join(' • '));
+        return;
+      }
+      const warnings = validation.warnings.join('\n');
+      if (warnings && !confirm(`${warnings}\n\nFolder-Sync trotzdem starten?`)) {
+        return;
+      }
+
       await invoke('configure_folder_sync', {
         watchPath: watchPath.trim(),
         postAction: postAction,
+        autoOrient: autoOrient,
       });
       localStorage.setItem('docflow-watch-path', watchPath.trim());
       localStorage.setItem('docflow-post-action', postAction);
+      localStorage.setItem('docflow-auto-orient', String(autoOrient));
       await loadStatus();
       await loadFolderSyncStatus();
     } catch (e) {
@@ -184,6 +421,65 @@ function App() {
     }
   };
 
+  // Dateien ins Fenster ziehen → direkt zu DocFlow hochladen
+  useEffect(() => {
+    const unlisten = getCurrentWebview().onDragDropEvent(async (event) => {
+      if (event.payload.type !== 'drop' || event.payload.paths.length === 0) return;
+      try {
+        const results = await invoke<FileUploadResult[]>('upload_files', { paths: event.payload.paths });
+        const failed = results.filter((r) => r.error);
+        const uploaded = results.length - failed.length;
+        if (uploaded > 0) {
+          setUploadNotice(`${uploaded} Datei(en) an DocFlow übergeben`);
+        }
+        if (failed.length > 0) {
+          const names = failed.map((r) => `${r.path.split(/[\\/]/).pop()}: ${r.error}`);
+          setError(`Upload fehlgeschlagen – ${names.join('; ')}`);
+        }
+      } catch (e) {
+        setError(`Upload fehlgeschlagen: ${e}`);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Warteschlange in den Einstellungen (alle 2 Sekunden aktualisieren)
+  const loadActiveJobs = async () => {
+    try {
+      setActiveJobs(await invoke<ActiveJob[]>('get_active_jobs'));
+    } catch (e) {
+      console.error('Warteschlange laden fehlgeschlagen:', e);
+    }
+  };
+
+  const handleCancelJob = async (job: ActiveJob) => {
+    try {
+      await invoke('cancel_job', { kind: job.kind, id: job.id });
+      await loadActiveJobs();
+    } catch (e) {
+      setError(`Abbrechen fehlgeschlagen: ${e}`);
+    }
+  };
+
+  const handleRaisePriority = async (job: ActiveJob) => {
+    try {
+      await invoke('set_job_priority', { kind: job.kind, id: job.id, priority: job.priority + 1 });
+      await loadActiveJobs();
+    } catch (e) {
+      setError(`Priorität ändern fehlgeschlagen: ${e}`);
+    }
+  };
+
+  useEffect(() => {
+    if (view === 'settings') {
+      loadActiveJobs();
+      const interval = setInterval(loadActiveJobs, 2000);
+      return () => clearInterval(interval);
+    }
+  }, [view]);
+
   // Status-Polling für Folder-Sync (alle 2 Sekunden wenn aktiv)
   useEffect(() => {
     if (view === 'folder_sync' || status?.folder_sync_active) {
@@ -262,6 +558,12 @@ function App() {
           <button onClick={() => setError('')}>×</button>
         </div>
       )}
+      {uploadNotice && (
+        <div className="notice-banner">
+          {uploadNotice}
+          <button onClick={() => setUploadNotice('')}>×</button>
+        </div>
+      )}
 
       {/* Content */}
       <main className="content">
@@ -269,24 +571,120 @@ function App() {
           <div className="view-status">
             <div className="status-card">
               <h2>Bridge-Status</h2>
+              {status?.task_error && (
+                <p className="text-error">{status.task_error}</p>
+              )}
+              {status?.docflow_paused.map((p) => (
+                <p key={p.server} className="text-error">
+                  DocFlow ({p.server}) nicht erreichbar - Anfragen pausiert,{" "}
+                  {p.retry_in_secs > 0 ? `nächster Versuch in ${p.retry_in_secs}s` : "Verbindung wird geprüft"}
+                </p>
+              ))}
+              {status?.resource_warning && (
+                <p className="text-error">{status.resource_warning}</p>
+              )}
+              {status?.certificate_warning && (
+                <p className="text-error">{status.certificate_warning}</p>
+              )}
+              {status?.audit_warning && (
+                <p className="text-error">{status.audit_warning}</p>
+              )}
+              {status?.tunnel && !status.tunnel.connected && status.tunnel.last_error && (
+                <p className="text-error">
+                  Reverse-Tunnel getrennt ({status.tunnel.last_error}) - Jobs werden bis dahin abgefragt
+                </p>
+              )}
               {status?.connected ? (
                 <div className="status-info">
                   <div className="info-row">
                     <span>DocFlow Server:</span>
                     <span>{status.docflow_url}</span>
                   </div>
+                  {status.grpc && (
+                    <div className="info-row">
+                      <span>gRPC:</span>
+                      <span title={status.grpc.last_error ?? status.grpc.endpoint}>
+                        {status.grpc.streaming
+                          ? `verbunden (${status.grpc.uploads} Uploads, ${status.grpc.fallbacks} über HTTP)`
+                          : 'nicht erreichbar - HTTP'}
+                      </span>
+                    </div>
+                  )}
+                  {status.tunnel && (
+                    <div className="info-row">
+                      <span>Reverse-Tunnel:</span>
+                      <span title={status.tunnel.url}>
+                        {status.tunnel.connected
+                          ? `verbunden (${status.tunnel.requests} Anfragen)`
+                          : 'wird verbunden...'}
+                      </span>
+                    </div>
+                  )}
                   <div className="info-row">
                     <span>Erkannte Scanner:</span>
                     <span>{status.scanner_count}</span>
                   </div>
                   <div className="info-row">
                     <span>Letzte Suche:</span>
+                    <span title={status.last_discovery_display?.local}>
+                      {status.last_discovery_display ? status.last_discovery_display.ago : 'Noch nicht gesucht'}
+                    </span>
+                  </div>
+                  <div className="info-row">
+                    <span>Laufzeit:</span>
+                    <span>{status.uptime}</span>
+                  </div>
+                  <div className="info-row">
+                    <span>Scan-Jobs:</span>
                     <span>
-                      {status.last_discovery
-                        ? new Date(status.last_discovery).toLocaleString('de-DE')
-                        : 'Noch nicht gesucht'}
+                      {status.totals.jobs_processed}
+                      {status.jobs_processed > 0 && <span> ({status.jobs_processed} in dieser Sitzung)</span>}
                     </span>
                   </div>
+                  <div className="info-row">
+                    <span>Ordner-Uploads:</span>
+                    <span>
+                      {status.totals.files_uploaded}
+                      {status.totals.files_duplicate > 0 && (
+                        <span> ({status.totals.files_duplicate} Duplikate)</span>
+                      )}
+                      {status.totals.folder_errors > 0 && (
+                        <span className="text-error"> ({status.totals.folder_errors} Fehler)</span>
+                      )}
+                    </span>
+                  </div>
+                  {status.totals.since && (
+                    <div className="info-row">
+                      <span>Gezählt seit:</span>
+                      <span>
+                        {new Date(status.totals.since).toLocaleString('de-DE')}{' '}
+                        <button className="btn-link" onClick={resetCounters}>
+                          Zurücksetzen
+                        </button>
+                      </span>
+                    </div>
+                  )}
+                  <div className="info-row">
+                    <span>Netzwerk:</span>
+                    <span>
+                      <button className="btn-link" onClick={runNetworkDiagnostics} disabled={networkCheck}>
+                        {networkCheck ? 'Messung läuft…' : 'Verbindung zu DocFlow messen'}
+                      </button>
+                    </span>
+                  </div>
+                  {networkReport && (
+                    <div className="test-scan-report">
+                      <strong className={networkReport.passed ? 'text-success' : 'text-error'}>
+                        {networkReport.passed ? 'Netzwerk-Diagnose abgeschlossen' : 'Netzwerk-Probleme gefunden'}
+                      </strong>
+                      {networkReport.checks.map((check) => (
+                        <div key={check.name} className="info-row">
+                          <span className={check.status === 'failed' ? 'text-error' : undefined}>{check.name}:</span>
+                          <span className={check.status === 'warning' ? 'text-error' : undefined}>{check.message}</span>
+                        </div>
+                      ))}
+                    </div>
+                  )}
                 </div>
               ) : (
                 <p className="not-connected">
@@ -365,8 +763,93 @@ function App() {
                           </span>
                         ))}
                         <span className="tag tag-method">{scanner.discovery_method}</span>
+                        {scanner.online === false && (
+                          <span
+                            className="tag text-error"
+                            title={
+                              scanner.last_seen
+                                ? `Zuletzt erreichbar: ${new Date(scanner.last_seen).toLocaleString('de-DE')}`
+                                : undefined
+                            }
+                          >
+                            offline
+                          </span>
+                        )}
                       </div>
                     </div>
+                    <div className="scanner-actions">
+                      <button
+                        className="btn-link"
+                        title="Eine Seite in niedriger Auflösung scannen, ohne Upload"
+                        disabled={testScanning !== null}
+                        onClick={() => runTestScan(scanner)}
+                      >
+                        {testScanning === scanner.id ? 'Probescan läuft…' : 'Probescan'}
+                      </button>
+                      <button
+                        className="btn-link"
+                        title="Job-Liste des Scanners anzeigen, hängende Jobs gezielt löschen"
+                        disabled={loadingJobs !== null}
+                        onClick={() => loadScannerJobs(scanner)}
+                      >
+                        {loadingJobs === scanner.id ? 'Lade Jobs…' : 'Jobs am Scanner'}
+                      </button>
+                      <button className="btn-link" onClick={() => removeScanner(scanner, false)}>
+                        Entfernen
+                      </button>
+                      <button
+                        className="btn-link"
+                        title="Auch bei künftigen Suchen nicht mehr anzeigen"
+                        onClick={() => removeScanner(scanner, true)}
+                      >
+                        Ausblenden
+                      </button>
+                    </div>
+                    {testScanReport?.scanner_id === scanner.id && (
+                      <div className="test-scan-report">
+                        <strong className={testScanReport.passed ? 'text-success' : 'text-error'}>
+                          {testScanReport.passed
+                            ? `Probescan erfolgreich (${testScanReport.total_ms} ms, ${Math.ceil(testScanReport.page_bytes / 1024)} KB)`
+                            : 'Probescan fehlgeschlagen'}
+                        </strong>
+                        {testScanReport.phases.map((phase) => (
+                          <div key={phase.name} className="info-row">
+                            <span className={phase.status === 'failed' ? 'text-error' : undefined}>
+                              {phase.name}: {phase.message}
+                            </span>
+                            <span>{phase.status === 'skipped' ? '–' : `${phase.duration_ms} ms`}</span>
+                            {phase.raw_response && <pre className="raw-response">{phase.raw_response}</pre>}
+                          </div>
+                        ))}
+                      </div>
+                    )}
+                    {scannerJobs?.scannerId === scanner.id && (
+                      <div className="test-scan-report">
+                        <strong>
+                          {scannerJobs.jobs.length === 0
+                            ? 'Keine Jobs am Scanner'
+                            : `${scannerJobs.jobs.length} Job(s) am Scanner`}
+                        </strong>
+                        {scannerJobs.jobs.map((job) => (
+                          <div key={job.job_uri} className="info-row">
+                            <span
+                              className={job.state === 'Processing' || job.state === 'Pending' ? 'text-warning' : undefined}
+                              title={job.reason ?? undefined}
+                            >
+                              {job.job_uri}: {job.state || 'unbekannt'}
+                              {job.images_completed !== null && ` (${job.images_completed} Seite(n))`}
+                            </span>
+                            <span>{job.age_secs !== null ? `vor ${job.age_secs} s` : '–'}</span>
+                            <button className="btn-link" onClick={() => clearScannerJob(scanner, job)}>
+                              Löschen
+                            </button>
+                          </div>
+                        ))}
+                        <button className="btn-link" onClick={() => setScannerJobs(null)}>
+                          Schließen
+                        </button>
+                      </div>
+                    )}
                   </div>
                 ))}
               </div>
@@ -410,6 +893,27 @@ function App() {
                     <span className="input-hint">
                       Lokaler Docker: http://localhost:4000 | Cloud: https://docflow.example.de
                     </span>
+                    <button
+                      className="btn-link"
+                      onClick={handleSearchServers}
+                      disabled={searchingServers}
+                    >
+                      {searchingServers ? 'Suche im Netzwerk...' : 'Server im Netzwerk suchen'}
+                    </button>
+                    {detectedServers.length > 0 && (
+                      <div className="server-list">
+                        {detectedServers.map((server) => (
+                          <button
+                            key={server.url}
+                            className={`server-option${server.url === docflowUrl ? ' selected' : ''}`}
+                            onClick={() => setDocflowUrl(server.url)}
+                          >
+                            <strong>{server.name}</strong>
+                            <span>{server.url}{server.version ? ` · v${server.version}` : ''}</span>
+                          </button>
+                        ))}
+                      </div>
+                    )}
                   </div>
 
                   <div className="input-group">
@@ -520,6 +1024,20 @@ function App() {
                   </div>
                 </div>
 
+                {/* Ausrichtung */}
+                <div className="settings-section">
+                  <h3>Ausrichtung</h3>
+                  <label className="toggle">
+                    <input
+                      type="checkbox"
+                      checked={autoOrient}
+                      onChange={(e) => setAutoOrient(e.target.checked)}
+                      disabled={folderSyncStatus?.running}
+                    />
+                    <span>Quer oder kopfüber liegende Bilder vor dem Upload aufrecht drehen</span>
+                  </label>
+                </div>
+
                 {/* Start/Stop Button */}
                 <div className="sync-controls">
                   {folderSyncStatus?.running ? (
@@ -572,10 +1090,30 @@ function App() {
                           <span className="text-error">{folderSyncStatus.errors}</span>
                         </div>
                       )}
-                      {folderSyncStatus.last_upload && (
+                      {folderSyncStatus.files_quarantined > 0 && (
+                        <div className="info-row">
+                          <span>Quarantäne:</span>
+                          <span className="text-error">{folderSyncStatus.files_quarantined} Dateien</span>
+                        </div>
+                      )}
+                      {folderSyncStatus.files_duplicate > 0 && (
+                        <div className="info-row">
+                          <span>Duplikate:</span>
+                          <span>{folderSyncStatus.files_duplicate} Dateien</span>
+                        </div>
+                      )}
+                      {folderSyncStatus.files_skipped > 0 && (
+                        <div className="info-row">
+                          <span>Abgebrochen:</span>
+                          <span>{folderSyncStatus.files_skipped} Dateien</span>
+                        </div>
+                      )}
+                      {folderSyncStatus.last_upload_display && (
                         <div className="info-row">
                           <span>Letzter Upload:</span>
-                          <span>{new Date(folderSyncStatus.last_upload).toLocaleString('de-DE')}</span>
+                          <span title={folderSyncStatus.last_upload_display.ago}>
+                            {folderSyncStatus.last_upload_display.local}
+                          </span>
                         </div>
                       )}
                       {folderSyncStatus.last_error && (
@@ -596,6 +1134,37 @@ function App() {
           <div className="view-settings">
             <h2>Einstellungen</h2>
 
+            <div className="settings-section">
+              <h3>Warteschlange</h3>
+              {activeJobs.length === 0 ? (
+                <p className="hint">Keine laufenden oder wartenden Aufträge</p>
+              ) : (
+                <div className="job-list">
+                  {activeJobs.map((job) => (
+                    <div key={`${job.kind}-${job.id}`} className={`job-item job-${job.state}`}>
+                      <div className="job-info">
+                        <strong>{job.kind === 'scan' ? `Scan ${job.id}` : job.name}</strong>
+                        <span>
+                          {job.kind === 'scan' ? job.name : 'Ordner-Upload'} ·{' '}
+                          {job.state === 'running' ? 'läuft' : job.state === 'queued' ? 'wartet' : 'erneuter Versuch'}
+                          {job.priority !== 0 && ` · Priorität ${job.priority}`}
+                        </span>
+                        {job.message && <span>{job.message}</span>}
+                      </div>
+                      {job.state !== 'running' && (
+                        <button className="btn-icon" title="Vorziehen" onClick={() => handleRaisePriority(job)}>
+                          <ArrowUp size={16} />
+                        </button>
+                      )}
+                      <button className="btn-icon" title="Abbrechen" onClick={() => handleCancelJob(job)}>
+                        <X size={16} />
+                      </button>
+                    </div>
+                  ))}
+                </div>
+              )}
+            </div>
+
             <div className="settings-section">
               <h3>Autostart</h3>
               <label className="toggle">